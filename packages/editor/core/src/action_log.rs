@@ -0,0 +1,119 @@
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex};
+
+use chrono::{DateTime, Utc};
+
+use crate::clock::Clock;
+use crate::middleware::{Middleware, Transition};
+use crate::{actions::Action, reducer::reduce, state::EditorState};
+
+/// Enough to cover the run-up to most reported bugs
+pub const DEFAULT_CAPACITY: usize = 500;
+
+/// A dispatched action and when it happened
+#[derive(Debug, Clone, PartialEq)]
+pub struct LoggedAction {
+    pub at: DateTime<Utc>,
+    pub action: Action,
+}
+
+/// The newest dispatched actions, with enough to rebuild the state after any of them
+///
+/// Only the state before the oldest kept action is stored, everything after
+/// it is replayed through `reduce`. Once full, the oldest action is folded
+/// into that state and dropped.
+#[derive(Debug, Clone)]
+pub struct ActionLog {
+    capacity: usize,
+    base: Option<EditorState>,
+    entries: VecDeque<LoggedAction>,
+    /// Actions applied to the state being inspected, `None` while following along live
+    cursor: Option<usize>,
+}
+
+impl Default for ActionLog {
+    fn default() -> Self {
+        Self::new(DEFAULT_CAPACITY)
+    }
+}
+
+impl ActionLog {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity: capacity.max(1),
+            base: None,
+            entries: VecDeque::new(),
+            cursor: None,
+        }
+    }
+
+    /// Middleware that records into a log shared with whatever shows it, stamped by `clock`
+    pub fn recorder(log: Arc<Mutex<ActionLog>>, clock: Arc<dyn Clock>) -> impl Middleware {
+        move |transition: &Transition<'_>| {
+            if let Ok(mut log) = log.lock() {
+                log.record(transition.before, transition.action, clock.utc());
+            }
+        }
+    }
+
+    /// Note an action dispatched against `before`
+    pub fn record(&mut self, before: &EditorState, action: &Action, at: DateTime<Utc>) {
+        if self.base.is_none() {
+            self.base = Some(before.clone());
+        }
+        self.entries.push_back(LoggedAction { at, action: action.clone() });
+
+        if self.entries.len() > self.capacity {
+            if let (Some(base), Some(oldest)) = (self.base.as_mut(), self.entries.pop_front()) {
+                *base = reduce(base, &oldest.action).0;
+            }
+            // Whatever was being inspected moved one step closer to the start
+            self.cursor = self.cursor.map(|c| c.saturating_sub(1));
+        }
+    }
+
+    pub fn entries(&self) -> &VecDeque<LoggedAction> {
+        &self.entries
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    pub fn cursor(&self) -> Option<usize> {
+        self.cursor
+    }
+
+    /// State after the first `applied` kept actions, `None` before anything was recorded
+    pub fn state_at(&self, applied: usize) -> Option<EditorState> {
+        let mut state = self.base.clone()?;
+        for entry in self.entries.iter().take(applied) {
+            state = reduce(&state, &entry.action).0;
+        }
+        Some(state)
+    }
+
+    /// Undo one more action, stopping at the oldest kept
+    pub fn step_back(&mut self) -> Option<EditorState> {
+        let applied = self.cursor.unwrap_or(self.entries.len()).saturating_sub(1);
+        let state = self.state_at(applied)?;
+        self.cursor = Some(applied);
+        Some(state)
+    }
+
+    /// Redo one action, going back to live after the newest
+    pub fn step_forward(&mut self) -> Option<EditorState> {
+        let applied = self.cursor? + 1;
+        self.cursor = (applied < self.entries.len()).then_some(applied);
+        self.state_at(applied)
+    }
+
+    /// Stop inspecting and follow new actions again
+    pub fn resume(&mut self) {
+        self.cursor = None;
+    }
+}
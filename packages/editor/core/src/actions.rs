@@ -1,4 +1,6 @@
+use crate::filter::{InteractionFilter, SortOrder};
 use crate::types::*;
+use std::collections::{HashMap, HashSet};
 
 /// All possible user actions that can modify the editor state
 #[derive(Debug, Clone, PartialEq)]
@@ -11,39 +13,121 @@ pub enum Action {
     ReorderReviewStack { from_index: usize, to_index: usize },
     SetDraftMetadata { key: String, value: serde_json::Value },
     ClearDraft,
+    /// Move to another interaction's draft, `None` for a new interaction
+    FocusInteraction { interaction_id: Option<String> },
+    /// Forget the draft of an interaction that's gone, focused or put away
+    DiscardDraft { interaction_id: String },
+    /// The server gave the interaction known as `temp_id` its own id, its draft and focus follow
+    InteractionIdAssigned { temp_id: String, interaction_id: String },
     
     // Interaction Submission
-    SubmitInteraction,
-    InteractionSubmitted(Result<Interaction, String>),
+    /// Submit the draft, `at` stamps the provisional interaction shown until the server answers
+    SubmitInteraction { at: chrono::DateTime<chrono::Utc> },
+    /// The server's answer to the submission shown under `temp_id`
+    InteractionSubmitted { temp_id: String, result: Result<Interaction, String> },
     
     // Review Actions
+    /// `reviewed_at` is set by the caller so replaying the log gives the same history
     SubmitReview { 
         interaction_id: String, 
         approved: bool, 
-        feedback: Option<String> 
+        feedback: Option<String>,
+        reviewed_at: chrono::DateTime<chrono::Utc>,
     },
     ReviewSubmitted(Result<(), String>),
+    /// Approve or deny the tool call an interaction is waiting on
+    RespondToPermission { interaction_id: String, approved: bool },
+    PermissionResponded(Result<(), String>),
     
     // Queue Status
     UpdateQueueStatus(InteractionQueueStatus),
+    /// One session's queue, kept while the server lists that session
+    SessionQueueLoaded { session_id: String, status: InteractionQueueStatus },
     
     // Real-time Events from Server
     InteractionPosted { interaction: Interaction },
+    /// `at` stamps the history event, set by the caller so replays match
     InteractionProcessing { 
         interaction_id: String, 
-        agent_id: String 
+        agent_id: String,
+        at: chrono::DateTime<chrono::Utc>,
     },
     InteractionCompleted { 
         interaction_id: String, 
-        result: serde_json::Value 
+        result: serde_json::Value,
+        at: chrono::DateTime<chrono::Utc>,
     },
     
+    // Tags
+    /// Tag an interaction, blank or repeated tags are ignored
+    AddTag { interaction_id: String, tag: String },
+    RemoveTag { interaction_id: String, tag: String },
+    
+    // Filtered Views
+    SetFilter { filter: InteractionFilter },
+    ClearFilter,
+    SetSortOrder { sort: SortOrder },
+    
     // Error Handling
     SetError { message: String },
     ClearError,
     
+    // Session Management
+    FetchSessions,
+    SessionsLoaded(Result<Vec<Session>, String>),
+    CreateSession { name: Option<String> },
+    SessionCreated(Result<Session, String>),
+    SwitchSession { session_id: String },
+    
+    // Snapshots
+    SaveSnapshot,
+    /// Restore the working set from a snapshot, e.g. on startup after a crash
+    LoadSnapshot { snapshot: String },
+    
+    // Cold storage
+    /// Move interactions quiet since before `cutoff` to disk, keeping stubs
+    ArchiveOlderThan { cutoff: chrono::DateTime<chrono::Utc> },
+    /// Ids of the interactions written to disk
+    InteractionsArchived(Result<Vec<String>, String>),
+    /// An archived interaction read back from disk
+    ArchivedInteractionLoaded { interaction_id: String, result: Result<Interaction, String> },
+    
+    // Reports
+    /// Write every review decision in a session out as Markdown
+    ExportReviewReport { session_id: String },
+    /// Where the report was written
+    ReviewReportExported(Result<String, String>),
+    
     // Connection Management
     Connect { server_url: String },
     Connected,
     Disconnected { reason: Option<String> },
+    
+    // Worktrees
+    WorktreesLoaded { worktrees: Vec<Worktree> },
+    /// A worktree made here or heard about, listed once
+    WorktreeAdded { worktree: Worktree },
+    WorktreeDeleted { worktree_id: String },
+    /// Work moved to another worktree, `None` for the main checkout
+    FocusWorktree { worktree_id: Option<String> },
+    WorktreeStatusesLoaded { statuses: HashMap<String, WorktreeGitStatus> },
+    WorktreeFilesLoaded { files: Vec<String> },
+    
+    // Notifications
+    ShowNotification { notification: Notification, at: chrono::DateTime<chrono::Utc> },
+    ClearNotification,
+    
+    // Permission Answers
+    /// A waiting request answered without asking the user, by its request key
+    PermissionAutoAnswered { key: String },
+    /// The request keys still waiting, and the interactions waiting on one as (server, interaction id)
+    SyncPendingPermissions {
+        waiting: HashSet<String>,
+        interactions: HashSet<(String, String)>,
+        at: chrono::DateTime<chrono::Utc>,
+    },
+    PermissionResponseQueued { interaction_id: String, server: String, approved: bool },
+    PermissionResponseRetrying { interaction_id: String },
+    PermissionResponseDelivered { interaction_id: String },
+    PermissionResponseFailed { interaction_id: String, error: MessageError },
 }
\ No newline at end of file
@@ -0,0 +1,56 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::types::{Interaction, InteractionType};
+use crate::EditorState;
+
+/// What stays in memory of an interaction moved to cold storage, enough to list it
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct InteractionStub {
+    pub id: String,
+    /// Session it's reloaded into
+    pub session_id: String,
+    pub title: String,
+    pub interaction_type: InteractionType,
+    pub last_activity: DateTime<Utc>,
+}
+
+impl InteractionStub {
+    pub fn of(interaction: &Interaction, session_id: &str) -> Self {
+        Self {
+            id: interaction.id.clone(),
+            session_id: session_id.to_string(),
+            title: interaction.title(),
+            interaction_type: interaction.interaction_type.clone(),
+            last_activity: last_activity(interaction),
+        }
+    }
+}
+
+/// When anything last happened in the interaction
+pub fn last_activity(interaction: &Interaction) -> DateTime<Utc> {
+    interaction
+        .content
+        .iter()
+        .map(|item| item.timestamp)
+        .chain(interaction.history.iter().map(|event| event.timestamp))
+        .fold(interaction.timestamp, DateTime::max)
+}
+
+/// Interactions of the current session quiet since before `cutoff`, oldest first
+///
+/// Ones still being worked on, waiting on the user, focused or not yet
+/// confirmed by the server stay in memory whatever their age.
+pub fn archivable(state: &EditorState, cutoff: DateTime<Utc>) -> Vec<&Interaction> {
+    let mut quiet: Vec<&Interaction> = state
+        .interactions
+        .values()
+        .filter(|interaction| last_activity(interaction) < cutoff)
+        .filter(|interaction| !interaction.needs_work && interaction.pending_permission().is_none())
+        .filter(|interaction| !state.pending_reviews.contains(&interaction.id))
+        .filter(|interaction| state.focused_interaction.as_ref() != Some(&interaction.id))
+        .filter(|interaction| !state.provisional.contains(&interaction.id))
+        .collect();
+    quiet.sort_by(|a, b| last_activity(a).cmp(&last_activity(b)).then_with(|| a.id.cmp(&b.id)));
+    quiet
+}
@@ -0,0 +1,124 @@
+use chrono::{DateTime, Duration, Utc};
+use std::collections::VecDeque;
+use std::time::Instant;
+
+// Enough round trips to ride out a slow response or two
+const MAX_SAMPLES: usize = 16;
+
+/// Where time comes from, shared by the reducer's callers and side effects so tests can control it
+pub trait Clock: std::fmt::Debug + Send + Sync {
+    fn instant(&self) -> Instant;
+    fn utc(&self) -> DateTime<Utc>;
+
+    /// How long ago a moment on this clock was
+    fn since(&self, earlier: Instant) -> std::time::Duration {
+        self.instant().saturating_duration_since(earlier)
+    }
+}
+
+/// The real time
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn instant(&self) -> Instant {
+        Instant::now()
+    }
+
+    fn utc(&self) -> DateTime<Utc> {
+        Utc::now()
+    }
+}
+
+/// Running estimate of how far the server's clock is ahead of ours
+///
+/// Each response's `Date` header is one sample, assumed to be stamped halfway
+/// through the round trip. The median of recent samples is used so a single
+/// slow request doesn't drag the estimate around.
+#[derive(Debug, Clone, Default)]
+pub struct ClockSkew {
+    samples: VecDeque<i64>,
+}
+
+impl ClockSkew {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Record a round trip that was sent at `sent`, answered at `received`
+    pub fn observe(&mut self, server_time: DateTime<Utc>, sent: DateTime<Utc>, received: DateTime<Utc>) {
+        if received < sent {
+            // Our own clock jumped mid-request, the sample says nothing
+            return;
+        }
+
+        let midpoint = sent + (received - sent) / 2;
+        self.samples.push_back((server_time - midpoint).num_milliseconds());
+        if self.samples.len() > MAX_SAMPLES {
+            self.samples.pop_front();
+        }
+    }
+
+    /// Server time minus local time, zero until something has been observed
+    pub fn offset(&self) -> Duration {
+        if self.samples.is_empty() {
+            return Duration::zero();
+        }
+
+        let mut sorted: Vec<i64> = self.samples.iter().copied().collect();
+        sorted.sort_unstable();
+        Duration::milliseconds(sorted[sorted.len() / 2])
+    }
+
+    /// A server timestamp on our clock
+    pub fn to_local(&self, server_time: DateTime<Utc>) -> DateTime<Utc> {
+        server_time - self.offset()
+    }
+}
+
+/// Parse an HTTP `Date` header, e.g. `Sun, 06 Nov 1994 08:49:37 GMT`
+pub fn parse_http_date(value: &str) -> Option<DateTime<Utc>> {
+    DateTime::parse_from_rfc2822(value.trim()).ok().map(|time| time.with_timezone(&Utc))
+}
+
+/// Parse a server timestamp, either RFC 3339 or milliseconds since the epoch
+pub fn parse_timestamp(value: &str) -> Option<DateTime<Utc>> {
+    let value = value.trim();
+    if let Ok(time) = DateTime::parse_from_rfc3339(value) {
+        return Some(time.with_timezone(&Utc));
+    }
+    value.parse::<i64>().ok().and_then(DateTime::from_timestamp_millis)
+}
+
+/// Times safe to display for items already in order
+///
+/// Nothing ends up later than `now` or earlier than the item before it, and
+/// missing times borrow their nearest neighbour's.
+pub fn normalize(times: &[Option<DateTime<Utc>>], now: DateTime<Utc>) -> Vec<DateTime<Utc>> {
+    let first_known = times.iter().flatten().next().copied().unwrap_or(now);
+
+    let mut previous: Option<DateTime<Utc>> = None;
+    times
+        .iter()
+        .map(|time| {
+            let time = time.or(previous).unwrap_or(first_known).min(now);
+            let time = previous.map_or(time, |previous| time.max(previous));
+            previous = Some(time);
+            time
+        })
+        .collect()
+}
+
+/// `just now`, `5m ago` or `3h ago`, None once it's a day old and needs a date
+pub fn format_relative(time: DateTime<Utc>, now: DateTime<Utc>) -> Option<String> {
+    let age = now - time;
+    if age < Duration::minutes(1) {
+        Some("just now".to_string())
+    } else if age < Duration::hours(1) {
+        Some(format!("{}m ago", age.num_minutes()))
+    } else if age < Duration::days(1) {
+        Some(format!("{}h ago", age.num_hours()))
+    } else {
+        None
+    }
+}
@@ -0,0 +1,125 @@
+/// What a diff does to a file
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum FileStatus {
+    Added,
+    Deleted,
+    Modified,
+    Renamed,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum LineKind {
+    Added,
+    Removed,
+    Context,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct DiffLine {
+    pub kind: LineKind,
+    pub text: String,
+}
+
+/// One `@@` section of a file's diff
+#[derive(Debug, Clone, PartialEq)]
+pub struct Hunk {
+    pub header: String,
+    pub lines: Vec<DiffLine>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct FileDiff {
+    pub path: String,
+    /// Where the file used to live, for renames
+    pub old_path: Option<String>,
+    pub status: FileStatus,
+    pub binary: bool,
+    pub hunks: Vec<Hunk>,
+}
+
+impl FileDiff {
+    fn new(path: String) -> Self {
+        Self {
+            path,
+            old_path: None,
+            status: FileStatus::Modified,
+            binary: false,
+            hunks: Vec::new(),
+        }
+    }
+
+    pub fn added(&self) -> usize {
+        self.count(LineKind::Added)
+    }
+
+    pub fn removed(&self) -> usize {
+        self.count(LineKind::Removed)
+    }
+
+    fn count(&self, kind: LineKind) -> usize {
+        self.hunks
+            .iter()
+            .flat_map(|hunk| &hunk.lines)
+            .filter(|line| line.kind == kind)
+            .count()
+    }
+}
+
+/// Split `git diff` output into files and hunks
+///
+/// Headers are only read before a file's first hunk, so content lines that
+/// happen to start with `---` or `+++` aren't mistaken for them.
+pub fn parse(diff: &str) -> Vec<FileDiff> {
+    let mut files: Vec<FileDiff> = Vec::new();
+
+    for line in diff.lines() {
+        if let Some(paths) = line.strip_prefix("diff --git ") {
+            // `a/old b/new`, paths may contain spaces so split on the last ` b/`
+            let path = paths
+                .rfind(" b/")
+                .map(|idx| &paths[idx + 3..])
+                .unwrap_or(paths);
+            files.push(FileDiff::new(path.to_string()));
+            continue;
+        }
+
+        let Some(file) = files.last_mut() else {
+            continue;
+        };
+
+        if line.starts_with("@@") {
+            file.hunks.push(Hunk {
+                header: line.to_string(),
+                lines: Vec::new(),
+            });
+            continue;
+        }
+
+        if let Some(hunk) = file.hunks.last_mut() {
+            let (kind, text) = match line.chars().next() {
+                Some('+') => (LineKind::Added, &line[1..]),
+                Some('-') => (LineKind::Removed, &line[1..]),
+                Some(' ') => (LineKind::Context, &line[1..]),
+                // `\ No newline at end of file` and anything unexpected
+                _ => continue,
+            };
+            hunk.lines.push(DiffLine {
+                kind,
+                text: text.to_string(),
+            });
+        } else if line.starts_with("new file mode") {
+            file.status = FileStatus::Added;
+        } else if line.starts_with("deleted file mode") {
+            file.status = FileStatus::Deleted;
+        } else if let Some(old_path) = line.strip_prefix("rename from ") {
+            file.status = FileStatus::Renamed;
+            file.old_path = Some(old_path.to_string());
+        } else if let Some(path) = line.strip_prefix("+++ b/") {
+            file.path = path.to_string();
+        } else if line.starts_with("Binary files ") {
+            file.binary = true;
+        }
+    }
+
+    files
+}
@@ -3,12 +3,16 @@ use crate::types::*;
 /// Side effects that need to be performed (API calls, etc)
 #[derive(Debug, Clone, PartialEq)]
 pub enum Effect {
-    /// Submit an interaction to the server
+    /// Submit an interaction to the server, answered with `InteractionSubmitted`
     SubmitInteraction {
+        /// Provisional interaction standing in for it until the server answers
+        temp_id: String,
         session_id: String,
         content: String,
         interaction_type: InteractionType,
         metadata: std::collections::HashMap<String, serde_json::Value>,
+        /// Interaction it follows up on, `None` starts a new one
+        interaction_id: Option<String>,
     },
     
     /// Submit review feedback for an interaction
@@ -18,6 +22,12 @@ pub enum Effect {
         feedback: Option<String>,
     },
     
+    /// Answer the tool call an interaction is waiting on
+    RespondToPermission {
+        interaction_id: String,
+        approved: bool,
+    },
+    
     /// Connect to SSE stream for real-time updates
     ConnectToStream {
         server_url: String,
@@ -26,4 +36,39 @@ pub enum Effect {
     
     /// Fetch current queue status
     FetchQueueStatus,
+    
+    /// Fetch every session on the server
+    FetchSessions,
+    
+    /// Create a new session on the server
+    CreateSession {
+        name: Option<String>,
+    },
+    
+    /// Replace an interaction's tags on the server
+    UpdateInteractionTags {
+        interaction_id: String,
+        tags: Vec<String>,
+    },
+    
+    /// Write a snapshot of the working set somewhere that survives a crash
+    PersistState {
+        snapshot: String,
+    },
+    
+    /// Write interactions to cold storage, answered with `InteractionsArchived`
+    ArchiveInteractions {
+        interactions: Vec<Interaction>,
+    },
+    
+    /// Read an archived interaction back, answered with `ArchivedInteractionLoaded`
+    LoadArchivedInteraction {
+        interaction_id: String,
+    },
+    
+    /// Save a Markdown export, `name` is what its file is named after
+    WriteExport {
+        name: String,
+        contents: String,
+    },
 }
\ No newline at end of file
@@ -0,0 +1,79 @@
+use std::cmp::Reverse;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::types::{Interaction, InteractionType};
+
+/// Which interactions a view shows, every field left empty matches everything
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct InteractionFilter {
+    /// Any of these types
+    pub types: Vec<InteractionType>,
+    /// All of these tags, as the `tags` metadata lists them
+    pub tags: Vec<String>,
+    /// Posted at or after
+    pub since: Option<DateTime<Utc>>,
+    /// Posted before
+    pub until: Option<DateTime<Utc>>,
+    pub needs_work: Option<bool>,
+}
+
+impl InteractionFilter {
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+
+    /// Whether the type filter lets `interaction_type` through, for lists that only know types
+    pub fn matches_type(&self, interaction_type: &InteractionType) -> bool {
+        self.types.is_empty() || self.types.contains(interaction_type)
+    }
+
+    pub fn matches(&self, interaction: &Interaction) -> bool {
+        let interaction_tags = tags(interaction);
+        self.matches_type(&interaction.interaction_type)
+            && self.tags.iter().all(|tag| interaction_tags.contains(&tag.as_str()))
+            && self.since.is_none_or(|since| interaction.timestamp >= since)
+            && self.until.is_none_or(|until| interaction.timestamp < until)
+            && self.needs_work.is_none_or(|needs_work| interaction.needs_work == needs_work)
+    }
+}
+
+/// Order of a filtered view
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum SortOrder {
+    #[default]
+    NewestFirst,
+    OldestFirst,
+    /// Latest message or agent event first, so busy interactions rise to the top
+    RecentActivity,
+}
+
+impl SortOrder {
+    /// Sort in place, ties broken by id so views don't shuffle between renders
+    pub fn sort(self, interactions: &mut [&Interaction]) {
+        match self {
+            SortOrder::NewestFirst => interactions.sort_by_key(|i| (Reverse(i.timestamp), &i.id)),
+            SortOrder::OldestFirst => interactions.sort_by_key(|i| (i.timestamp, &i.id)),
+            SortOrder::RecentActivity => interactions.sort_by_key(|i| (Reverse(last_activity(i)), &i.id)),
+        }
+    }
+}
+
+/// Tags from the interaction's `tags` metadata, ignoring anything that isn't a string
+pub fn tags(interaction: &Interaction) -> Vec<&str> {
+    interaction
+        .metadata
+        .get("tags")
+        .and_then(|tags| tags.as_array())
+        .map(|tags| tags.iter().filter_map(|tag| tag.as_str()).collect())
+        .unwrap_or_default()
+}
+
+/// When anything last happened to the interaction
+pub fn last_activity(interaction: &Interaction) -> DateTime<Utc> {
+    let messages = interaction.content.iter().map(|item| item.timestamp);
+    let events = interaction.history.iter().map(|event| event.timestamp);
+    messages.chain(events).fold(interaction.timestamp, DateTime::max)
+}
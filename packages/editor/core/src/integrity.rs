@@ -0,0 +1,165 @@
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::reducer::NEW_DRAFT_KEY;
+use crate::state::EditorState;
+use crate::types::{Interaction, InteractionDraft};
+
+/// A snapshot's state with a checksum of it, so torn writes and hand edits are caught
+#[derive(Serialize, Deserialize)]
+struct Sealed {
+    checksum: String,
+    state: String,
+}
+
+/// Wrap a state's JSON with its checksum
+pub fn seal(state: &str) -> Result<String, String> {
+    let sealed = Sealed {
+        checksum: checksum(state),
+        state: state.to_string(),
+    };
+    serde_json::to_string(&sealed).map_err(|e| e.to_string())
+}
+
+/// The state's JSON out of a sealed snapshot, checked against its checksum
+///
+/// Snapshots from before sealing are passed through as they are.
+pub fn unseal(snapshot: &str) -> Result<String, String> {
+    let Ok(sealed) = serde_json::from_str::<Sealed>(snapshot) else {
+        return Ok(snapshot.to_string());
+    };
+    let actual = checksum(&sealed.state);
+    if actual != sealed.checksum {
+        return Err(format!("checksum mismatch, expected {} but the state hashes to {}", sealed.checksum, actual));
+    }
+    Ok(sealed.state)
+}
+
+// FNV-1a, stable across Rust versions unlike the std hasher
+fn checksum(data: &str) -> String {
+    let hash = data.bytes().fold(0xcbf2_9ce4_8422_2325_u64, |hash, byte| {
+        (hash ^ u64::from(byte)).wrapping_mul(0x0000_0100_0000_01b3)
+    });
+    format!("fnv1a:{:016x}", hash)
+}
+
+/// What a restored state couldn't keep in place, saved with the state until cleared
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Quarantine {
+    /// Drafts for interactions that are gone, by the interaction's id
+    pub drafts: HashMap<String, InteractionDraft>,
+    /// Interactions stored under another's id, where their own was taken
+    pub interactions: Vec<Interaction>,
+}
+
+impl Quarantine {
+    pub fn is_empty(&self) -> bool {
+        self.drafts.is_empty() && self.interactions.is_empty()
+    }
+}
+
+/// The outcome of checking a restored state
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct IntegrityReport {
+    /// Problems fixed in place, one line each
+    pub repaired: Vec<String>,
+    /// Taken out of this check, also added to the state's quarantine
+    pub quarantined: Quarantine,
+}
+
+impl IntegrityReport {
+    pub fn is_clean(&self) -> bool {
+        self.repaired.is_empty() && self.quarantined.is_empty()
+    }
+}
+
+/// Repair what can be repaired safely, and set aside what can't
+///
+/// Nothing the user wrote is dropped, a draft or interaction that can't stay
+/// moves to `state.quarantine`. Ids that point at nothing are removed. An
+/// interaction parked with another session still counts as there.
+pub fn check(state: &mut EditorState) -> IntegrityReport {
+    let mut report = IntegrityReport::default();
+
+    rekey(&mut state.interactions, "", &mut report);
+    for (session_id, interactions) in &mut state.session_interactions {
+        rekey(interactions, session_id, &mut report);
+    }
+
+    let orphaned: Vec<String> = state
+        .drafts
+        .keys()
+        .filter(|key| key.as_str() != NEW_DRAFT_KEY && !known(state, key))
+        .cloned()
+        .collect();
+    for interaction_id in orphaned {
+        if let Some(draft) = state.drafts.remove(&interaction_id) {
+            report.quarantined.drafts.insert(interaction_id, draft);
+        }
+    }
+
+    if let Some(interaction_id) = state.focused_interaction.clone() {
+        if !known(state, &interaction_id) {
+            // The draft stays, as the start of a new interaction
+            state.focused_interaction = None;
+            report.repaired.push(format!("Draft followed up on missing interaction {}, now starts a new one", interaction_id));
+        }
+    }
+
+    let provisional = std::mem::take(&mut state.provisional);
+    state.provisional = drop_missing(state, provisional, "provisional id", &mut report);
+    let session_provisional = std::mem::take(&mut state.session_provisional);
+    state.session_provisional = session_provisional
+        .into_iter()
+        .map(|(session_id, ids)| (session_id, drop_missing(state, ids, "provisional id", &mut report)))
+        .filter(|(_, ids)| !ids.is_empty())
+        .collect();
+    let pending_reviews = std::mem::take(&mut state.pending_reviews);
+    state.pending_reviews = drop_missing(state, pending_reviews, "pending review", &mut report);
+
+    state.quarantine.drafts.extend(report.quarantined.drafts.clone());
+    state.quarantine.interactions.extend(report.quarantined.interactions.iter().cloned());
+    report
+}
+
+fn known(state: &EditorState, interaction_id: &str) -> bool {
+    state.interactions.contains_key(interaction_id)
+        || state.session_interactions.values().any(|parked| parked.contains_key(interaction_id))
+        || state.archived.contains_key(interaction_id)
+}
+
+fn drop_missing(state: &EditorState, ids: Vec<String>, what: &str, report: &mut IntegrityReport) -> Vec<String> {
+    ids.into_iter()
+        .filter(|id| {
+            let found = known(state, id);
+            if !found {
+                report.repaired.push(format!("Removed {} {}, its interaction is missing", what, id));
+            }
+            found
+        })
+        .collect()
+}
+
+// Interactions are keyed by their own id, move any that aren't
+fn rekey(interactions: &mut HashMap<String, Interaction>, session_id: &str, report: &mut IntegrityReport) {
+    let misplaced: Vec<String> = interactions
+        .iter()
+        .filter(|(key, interaction)| **key != interaction.id)
+        .map(|(key, _)| key.clone())
+        .collect();
+    let session = if session_id.is_empty() { String::new() } else { format!(" in session {}", session_id) };
+
+    for key in misplaced {
+        let Some(interaction) = interactions.remove(&key) else {
+            continue;
+        };
+        if interactions.contains_key(&interaction.id) {
+            report.quarantined.interactions.push(interaction);
+        } else {
+            report.repaired.push(format!("Moved interaction {} stored as {}{}", interaction.id, key, session));
+            interactions.insert(interaction.id.clone(), interaction);
+        }
+    }
+}
@@ -0,0 +1,47 @@
+use chrono::{DateTime, Utc};
+
+use crate::{actions::Action, state::EditorState};
+
+/// Commands that can be bound to a key, whichever frontend reads the key
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum KeyCommand {
+    NewConversation,
+    CommandPalette,
+    SwitchConversation(usize),
+    Send,
+    Interrupt,
+    FocusMode,
+}
+
+impl KeyCommand {
+    /// Parse the command names used in Keymap.toml
+    pub fn parse(name: &str) -> Option<Self> {
+        match name {
+            "new_conversation" => Some(Self::NewConversation),
+            "command_palette" => Some(Self::CommandPalette),
+            "send" => Some(Self::Send),
+            "interrupt" => Some(Self::Interrupt),
+            "focus_mode" => Some(Self::FocusMode),
+            _ => name
+                .strip_prefix("switch_conversation_")
+                .and_then(|n| n.parse::<usize>().ok())
+                .filter(|n| *n > 0)
+                .map(Self::SwitchConversation),
+        }
+    }
+
+    /// The action the command dispatches, `None` when there's nothing to do or it only moves the view
+    ///
+    /// Conversations are numbered from 1 in the order `visible_interactions` lists them.
+    pub fn action(&self, state: &EditorState, at: DateTime<Utc>) -> Option<Action> {
+        match self {
+            Self::NewConversation => Some(Action::FocusInteraction { interaction_id: None }),
+            Self::SwitchConversation(n) => {
+                let interaction = state.visible_interactions().into_iter().nth(n.checked_sub(1)?)?;
+                Some(Action::FocusInteraction { interaction_id: Some(interaction.id.clone()) })
+            }
+            Self::Send => (!state.draft.content.trim().is_empty()).then_some(Action::SubmitInteraction { at }),
+            Self::CommandPalette | Self::Interrupt | Self::FocusMode => None,
+        }
+    }
+}
@@ -3,6 +3,20 @@ pub mod actions;
 pub mod effects;
 pub mod reducer;
 pub mod types;
+pub mod tokenizer;
+pub mod clock;
+pub mod diff;
+pub mod theme;
+pub mod policy;
+pub mod stats;
+pub mod middleware;
+pub mod action_log;
+pub mod filter;
+pub mod integrity;
+pub mod report;
+pub mod plugin;
+pub mod archive;
+pub mod keymap;
 
 #[cfg(test)]
 mod tests;
@@ -10,4 +24,6 @@ mod tests;
 pub use state::EditorState;
 pub use actions::Action;
 pub use effects::Effect;
-pub use reducer::reduce;
\ No newline at end of file
+pub use reducer::reduce;
+pub use middleware::{Middleware, Pipeline};
+pub use filter::{InteractionFilter, SortOrder};
\ No newline at end of file
@@ -0,0 +1,82 @@
+use std::fmt;
+
+use crate::{actions::Action, effects::Effect, reducer::reduce, state::EditorState};
+
+/// One trip through the reducer, as middleware sees it
+#[derive(Debug, Clone, Copy)]
+pub struct Transition<'a> {
+    pub before: &'a EditorState,
+    pub action: &'a Action,
+    pub after: &'a EditorState,
+    pub effects: &'a [Effect],
+}
+
+impl Transition<'_> {
+    /// Whether the action left the state as it was
+    pub fn unchanged(&self) -> bool {
+        self.before == self.after
+    }
+}
+
+/// Watches every transition without being able to change it
+///
+/// Logging, analytics, persistence and undo recording hang off this so
+/// `reduce` stays pure. Any `FnMut(&Transition)` closure is middleware too.
+pub trait Middleware: Send {
+    fn observe(&mut self, transition: &Transition<'_>);
+}
+
+impl<F> Middleware for F
+where
+    F: FnMut(&Transition<'_>) + Send,
+{
+    fn observe(&mut self, transition: &Transition<'_>) {
+        self(transition)
+    }
+}
+
+/// `reduce` followed by each registered middleware, in the order registered
+#[derive(Default)]
+pub struct Pipeline {
+    middleware: Vec<Box<dyn Middleware>>,
+}
+
+impl Pipeline {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Frontends register theirs once at startup
+    pub fn register(&mut self, middleware: impl Middleware + 'static) {
+        self.middleware.push(Box::new(middleware));
+    }
+
+    pub fn len(&self) -> usize {
+        self.middleware.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.middleware.is_empty()
+    }
+
+    /// Same result as `reduce`, middleware only gets to look
+    pub fn dispatch(&mut self, state: &EditorState, action: &Action) -> (EditorState, Vec<Effect>) {
+        let (after, effects) = reduce(state, action);
+        let transition = Transition {
+            before: state,
+            action,
+            after: &after,
+            effects: &effects,
+        };
+        for middleware in &mut self.middleware {
+            middleware.observe(&transition);
+        }
+        (after, effects)
+    }
+}
+
+impl fmt::Debug for Pipeline {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Pipeline").field("middleware", &self.middleware.len()).finish()
+    }
+}
@@ -0,0 +1,145 @@
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+/// What a plugin shows, laid out by the editor in its own style
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum Block {
+    Heading { text: String },
+    Text { text: String },
+    Code {
+        text: String,
+        #[serde(default)]
+        language: Option<String>,
+    },
+    /// A label and its value on one line
+    Field { label: String, value: String },
+}
+
+/// A tool call Wake made while answering
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ToolCall {
+    pub name: String,
+    #[serde(default)]
+    pub arguments: Value,
+}
+
+/// An answered message, what the render hook is given
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MessageContext {
+    pub prompt: String,
+    pub response: String,
+    #[serde(default)]
+    pub model: Option<String>,
+    #[serde(default)]
+    pub tool_calls: Vec<ToolCall>,
+}
+
+/// Where the user is, what panels and commands are given
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Context {
+    pub server: String,
+    #[serde(default)]
+    pub conversation_id: Option<String>,
+    /// Branch of the worktree being worked in
+    #[serde(default)]
+    pub branch: Option<String>,
+}
+
+/// A sidebar section
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Panel {
+    pub title: String,
+    pub blocks: Vec<Block>,
+}
+
+/// A command offered in the command palette
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CommandInfo {
+    /// Passed back when it's run
+    pub id: String,
+    pub label: String,
+}
+
+/// What running a command left for the user
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum Outcome {
+    Done,
+    /// Show a message
+    Notify { message: String },
+    /// Put text in the composer to edit and send
+    Draft { text: String },
+}
+
+/// What a plugin does, asked for once when it's loaded
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Manifest {
+    pub name: String,
+    /// Messages with calls to these tools are offered to `render_message`
+    #[serde(default)]
+    pub tools: Vec<String>,
+    /// Whether it has a sidebar panel
+    #[serde(default)]
+    pub panel: bool,
+    #[serde(default)]
+    pub commands: Vec<CommandInfo>,
+}
+
+impl Manifest {
+    /// Whether the message has a tool call this plugin renders
+    pub fn renders(&self, message: &MessageContext) -> bool {
+        message.tool_calls.iter().any(|call| self.tools.contains(&call.name))
+    }
+}
+
+/// A program that extends the editors, run once per `Request`
+///
+/// The editor writes the request as JSON to its stdin and reads the answer from
+/// its stdout, `handle` does both halves of that for an implementation.
+/// Hooks left unimplemented leave the editor as it is.
+pub trait Plugin {
+    fn manifest(&self) -> Manifest;
+
+    /// Blocks to show in place of the response text, `None` keeps the text
+    fn render_message(&self, _message: &MessageContext) -> Option<Vec<Block>> {
+        None
+    }
+
+    /// The sidebar panel for where the user is, `None` hides it
+    fn panel(&self, _context: &Context) -> Option<Panel> {
+        None
+    }
+
+    fn run_command(&self, _id: &str, _context: &Context) -> Result<Outcome, String> {
+        Ok(Outcome::Done)
+    }
+}
+
+/// What the editor asks a plugin
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum Request {
+    /// Answered with a `Manifest`
+    Describe,
+    /// Answered with blocks or `null`
+    RenderMessage { message: MessageContext },
+    /// Answered with a `Panel` or `null`
+    Panel { context: Context },
+    /// Answered with an `Outcome`
+    RunCommand { id: String, context: Context },
+}
+
+/// Answer the request read from stdin, print the `Ok` and exit non-zero with the `Err`
+pub fn handle(plugin: &dyn Plugin, request: &str) -> Result<String, String> {
+    let request: Request = serde_json::from_str(request).map_err(|e| format!("Bad request: {}", e))?;
+    let answer = match request {
+        Request::Describe => serde_json::to_value(plugin.manifest()),
+        Request::RenderMessage { message } => serde_json::to_value(plugin.render_message(&message)),
+        Request::Panel { context } => serde_json::to_value(plugin.panel(&context)),
+        Request::RunCommand { id, context } => serde_json::to_value(plugin.run_command(&id, &context)?),
+    };
+    answer.map(|answer| answer.to_string()).map_err(|e| e.to_string())
+}
@@ -0,0 +1,233 @@
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+// Input fields tools use for the file they touch
+const PATH_FIELDS: [&str; 3] = ["file_path", "path", "notebook_path"];
+
+/// What a policy says about a tool request
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Decision {
+    Allow,
+    Deny,
+}
+
+/// A regex matched against a tool's `command` input
+///
+/// Kept as a string in config files, invalid patterns fail to deserialize.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct CommandPattern(Regex);
+
+impl CommandPattern {
+    pub fn new(pattern: &str) -> Result<Self, String> {
+        Regex::new(pattern).map(Self).map_err(|e| e.to_string())
+    }
+
+    pub fn as_str(&self) -> &str {
+        self.0.as_str()
+    }
+
+    pub fn is_match(&self, command: &str) -> bool {
+        self.0.is_match(command)
+    }
+}
+
+impl TryFrom<String> for CommandPattern {
+    type Error = String;
+
+    fn try_from(pattern: String) -> Result<Self, Self::Error> {
+        Self::new(&pattern)
+    }
+}
+
+impl From<CommandPattern> for String {
+    fn from(pattern: CommandPattern) -> Self {
+        pattern.0.as_str().to_string()
+    }
+}
+
+impl PartialEq for CommandPattern {
+    fn eq(&self, other: &Self) -> bool {
+        self.as_str() == other.as_str()
+    }
+}
+
+/// One allow or deny rule, every condition that's set has to match
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Rule {
+    /// Tool name, `*` and `?` wildcards allowed
+    pub tool: String,
+    /// Only in the worktree at this path, anywhere when unset
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub worktree: Option<String>,
+    /// Globs for the file the tool touches, relative to the worktree where possible
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub paths: Vec<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub command: Option<CommandPattern>,
+}
+
+impl Rule {
+    /// Matches every use of one tool
+    pub fn tool(tool: impl Into<String>) -> Self {
+        Self {
+            tool: tool.into(),
+            worktree: None,
+            paths: Vec::new(),
+            command: None,
+        }
+    }
+
+    pub fn matches(&self, request: &Request) -> bool {
+        if !glob_match(&self.tool, request.tool) {
+            return false;
+        }
+        if self.worktree.is_some() && self.worktree.as_deref() != request.worktree {
+            return false;
+        }
+        if !self.paths.is_empty() {
+            // A path rule can't vouch for a request that doesn't name a path
+            let path = match request.path.as_deref() {
+                Some(path) => relative_to(path, request.worktree),
+                None => return false,
+            };
+            if !self.paths.iter().any(|glob| glob_match(glob, path)) {
+                return false;
+            }
+        }
+        match (&self.command, request.command.as_deref()) {
+            (Some(pattern), Some(command)) => pattern.is_match(command),
+            (Some(_), None) => false,
+            (None, _) => true,
+        }
+    }
+
+    /// One line description, e.g. `Bash $ ^cargo in ~/repo`
+    pub fn summary(&self) -> String {
+        let mut summary = self.tool.clone();
+        if !self.paths.is_empty() {
+            summary.push_str(&format!(" on {}", self.paths.join(", ")));
+        }
+        if let Some(command) = &self.command {
+            summary.push_str(&format!(" $ {}", command.as_str()));
+        }
+        if let Some(worktree) = &self.worktree {
+            summary.push_str(&format!(" in {}", worktree));
+        }
+        summary
+    }
+}
+
+/// The parts of a tool request rules look at
+#[derive(Debug, Clone, PartialEq)]
+pub struct Request<'a> {
+    pub tool: &'a str,
+    pub worktree: Option<&'a str>,
+    pub path: Option<String>,
+    pub command: Option<String>,
+}
+
+impl<'a> Request<'a> {
+    /// Pull the path and command out of a tool's arguments
+    pub fn new(tool: &'a str, input: Option<&Value>, worktree: Option<&'a str>) -> Self {
+        let field = |name: &str| input.and_then(|input| input.get(name)).and_then(Value::as_str).map(str::to_string);
+        Self {
+            tool,
+            worktree,
+            path: PATH_FIELDS.iter().find_map(|name| field(name)),
+            command: field("command"),
+        }
+    }
+}
+
+/// Allow and deny rules, deny wins when both match
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Policy {
+    #[serde(default)]
+    pub allow: Vec<Rule>,
+    #[serde(default)]
+    pub deny: Vec<Rule>,
+}
+
+impl Policy {
+    /// `None` when no rule matches and the user should be asked
+    pub fn decide(&self, request: &Request) -> Option<Decision> {
+        self.matching_rule(request).map(|(decision, _)| decision)
+    }
+
+    /// The rule that decides a request, first deny rule before first allow rule
+    pub fn matching_rule(&self, request: &Request) -> Option<(Decision, &Rule)> {
+        [Decision::Deny, Decision::Allow].into_iter().find_map(|decision| {
+            self.rules(decision)
+                .iter()
+                .find(|rule| rule.matches(request))
+                .map(|rule| (decision, rule))
+        })
+    }
+
+    pub fn rules(&self, decision: Decision) -> &[Rule] {
+        match decision {
+            Decision::Allow => &self.allow,
+            Decision::Deny => &self.deny,
+        }
+    }
+
+    pub fn rules_mut(&mut self, decision: Decision) -> &mut Vec<Rule> {
+        match decision {
+            Decision::Allow => &mut self.allow,
+            Decision::Deny => &mut self.deny,
+        }
+    }
+
+    /// Add a rule unless the same one is already there
+    pub fn add(&mut self, decision: Decision, rule: Rule) {
+        let rules = self.rules_mut(decision);
+        if !rules.contains(&rule) {
+            rules.push(rule);
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.allow.len() + self.deny.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+// Paths inside the worktree are matched relative to it, so rules carry between checkouts
+fn relative_to<'p>(path: &'p str, worktree: Option<&str>) -> &'p str {
+    worktree
+        .and_then(|root| path.strip_prefix(root.trim_end_matches('/')))
+        .and_then(|rest| rest.strip_prefix('/'))
+        .unwrap_or(path)
+}
+
+/// Shell style glob: `?` is one character, `*` stays within a path segment and `**` crosses them
+pub fn glob_match(pattern: &str, text: &str) -> bool {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let text: Vec<char> = text.chars().collect();
+    glob_match_from(&pattern, &text)
+}
+
+fn glob_match_from(pattern: &[char], text: &[char]) -> bool {
+    match pattern.first() {
+        None => text.is_empty(),
+        Some('*') if pattern.get(1) == Some(&'*') => {
+            let rest = &pattern[2..];
+            // `**/` also matches no directories at all
+            if rest.first() == Some(&'/') && glob_match_from(&rest[1..], text) {
+                return true;
+            }
+            (0..=text.len()).any(|skip| glob_match_from(rest, &text[skip..]))
+        }
+        Some('*') => {
+            let segment = text.iter().position(|c| *c == '/').unwrap_or(text.len());
+            (0..=segment).any(|skip| glob_match_from(&pattern[1..], &text[skip..]))
+        }
+        Some('?') => text.first().is_some_and(|c| *c != '/') && glob_match_from(&pattern[1..], &text[1..]),
+        Some(c) => text.first() == Some(c) && glob_match_from(&pattern[1..], &text[1..]),
+    }
+}
@@ -1,4 +1,12 @@
-use crate::{state::EditorState, actions::Action, effects::Effect, types::*};
+use crate::{state::{EditorState, PermissionDelivery, DEFAULT_SESSION_ID}, actions::Action, effects::Effect, report::{self, ReviewDecision}, types::*};
+use crate::archive::{self, InteractionStub};
+use std::collections::HashMap;
+
+/// Metadata key carrying a submission's temp id to the server
+pub const TEMP_ID_KEY: &str = "tempId";
+
+/// Where the draft of a new interaction is kept while another is focused, no interaction has an empty id
+pub const NEW_DRAFT_KEY: &str = "";
 
 /// Pure state transition function
 /// Returns new state and any effects that need to be performed
@@ -39,41 +47,105 @@ pub fn reduce(state: &EditorState, action: &Action) -> (EditorState, Vec<Effect>
         }
         
         Action::ClearDraft => {
-            new_state.draft = InteractionDraft {
-                content: String::new(),
-                interaction_type: InteractionType::Query,
-                review_stack: vec!["user".to_string()],
-                metadata: std::collections::HashMap::new(),
-            };
+            new_state.draft = InteractionDraft::default();
+        }
+        
+        Action::FocusInteraction { interaction_id } => {
+            if new_state.focused_interaction != *interaction_id {
+                let left = draft_key(new_state.focused_interaction.as_deref());
+                let draft = std::mem::take(&mut new_state.draft);
+                // Untouched drafts aren't worth keeping
+                if draft == InteractionDraft::default() {
+                    new_state.drafts.remove(&left);
+                } else {
+                    new_state.drafts.insert(left, draft);
+                }
+                
+                new_state.draft = new_state.drafts
+                    .remove(&draft_key(interaction_id.as_deref()))
+                    .unwrap_or_default();
+                new_state.focused_interaction = interaction_id.clone();
+            }
+            // Opening a stub brings the interaction back from disk
+            if let Some(id) = interaction_id {
+                if new_state.archived.contains_key(id) && !new_state.interactions.contains_key(id) {
+                    effects.push(Effect::LoadArchivedInteraction { interaction_id: id.clone() });
+                }
+            }
+        }
+        
+        Action::DiscardDraft { interaction_id } => {
+            new_state.drafts.remove(interaction_id);
+            if new_state.focused_interaction.as_deref() == Some(interaction_id.as_str()) {
+                new_state.draft = InteractionDraft::default();
+            }
+        }
+        
+        Action::InteractionIdAssigned { temp_id, interaction_id } => {
+            if new_state.focused_interaction.as_deref() == Some(temp_id.as_str()) {
+                new_state.focused_interaction = Some(interaction_id.clone());
+            } else if let Some(draft) = new_state.drafts.remove(temp_id) {
+                new_state.drafts.insert(interaction_id.clone(), draft);
+            }
         }
         
         // Interaction Submission
-        Action::SubmitInteraction => {
+        Action::SubmitInteraction { at } => {
             if !new_state.draft.content.is_empty() && new_state.connected {
+                // Show it straight away, the server's copy replaces it once submitted
+                let provisional = provisional_interaction(&mut new_state, *at);
+                let mut metadata = new_state.draft.metadata.clone();
+                metadata.insert(TEMP_ID_KEY.to_string(), serde_json::Value::String(provisional.id.clone()));
                 effects.push(Effect::SubmitInteraction {
+                    temp_id: provisional.id.clone(),
                     session_id: new_state.session_id.clone(),
                     content: new_state.draft.content.clone(),
                     interaction_type: new_state.draft.interaction_type.clone(),
-                    metadata: new_state.draft.metadata.clone(),
+                    metadata,
+                    interaction_id: new_state.focused_interaction.clone(),
                 });
+                new_state.provisional.push(provisional.id.clone());
+                new_state.submitted_drafts.insert(provisional.id.clone(), draft_key(new_state.focused_interaction.as_deref()));
+                new_state.interactions.insert(provisional.id.clone(), provisional);
             } else if !new_state.connected {
                 new_state.error = Some("Not connected to server".to_string());
             }
         }
         
-        Action::InteractionSubmitted(Ok(interaction)) => {
-            new_state.interactions.insert(interaction.id.clone(), interaction.clone());
-            new_state.draft.content.clear(); // Clear draft on success
+        Action::InteractionSubmitted { temp_id, result: Ok(interaction) } => {
+            // Lands in the session it was submitted from, which may have been switched away from
+            let (interactions, provisional) = submitted_in(&mut new_state, temp_id);
+            provisional.retain(|id| id != temp_id);
+            interactions.remove(temp_id);
+            interactions.insert(interaction.id.clone(), interaction.clone());
+            if let Some(key) = new_state.submitted_drafts.remove(temp_id) {
+                clear_submitted_draft(&mut new_state, &key);
+            }
             new_state.error = None;
         }
         
-        Action::InteractionSubmitted(Err(error)) => {
+        Action::InteractionSubmitted { temp_id, result: Err(error) } => {
+            new_state.submitted_drafts.remove(temp_id);
+            // Kept, marked as failed, next to whatever else is in flight, the draft is still there to resend
+            let (interactions, provisional) = submitted_in(&mut new_state, temp_id);
+            provisional.retain(|id| id != temp_id);
+            if let Some(interaction) = interactions.get_mut(temp_id) {
+                interaction.needs_work = false;
+                interaction.metadata.insert(SUBMIT_ERROR_KEY.to_string(), serde_json::Value::String(error.clone()));
+            }
             new_state.error = Some(error.clone());
         }
         
         // Review Actions
-        Action::SubmitReview { interaction_id, approved, feedback } => {
-            if new_state.interactions.contains_key(interaction_id) {
+        Action::SubmitReview { interaction_id, approved, feedback, reviewed_at } => {
+            if let Some(interaction) = new_state.interactions.get_mut(interaction_id) {
+                // Kept for the review report, as submitted
+                let decision = ReviewDecision {
+                    approved: *approved,
+                    feedback: feedback.clone(),
+                    reviewed_at: *reviewed_at,
+                };
+                interaction.history.push(decision.to_event());
                 effects.push(Effect::SubmitReview {
                     interaction_id: interaction_id.clone(),
                     approved: *approved,
@@ -90,17 +162,49 @@ pub fn reduce(state: &EditorState, action: &Action) -> (EditorState, Vec<Effect>
             new_state.error = Some(error.clone());
         }
         
+        Action::RespondToPermission { interaction_id, approved } => {
+            if let Some(interaction) = new_state.interactions.get_mut(interaction_id) {
+                // Answered once, the prompt goes now rather than when the server catches up
+                if interaction.pending_permission().is_some() {
+                    interaction.metadata.remove(PENDING_PERMISSION_KEY);
+                    effects.push(Effect::RespondToPermission {
+                        interaction_id: interaction_id.clone(),
+                        approved: *approved,
+                    });
+                }
+            }
+        }
+        
+        Action::PermissionResponded(Ok(())) => {
+            new_state.error = None;
+        }
+        
+        Action::PermissionResponded(Err(error)) => {
+            new_state.error = Some(error.clone());
+        }
+        
         // Queue Status
         Action::UpdateQueueStatus(status) => {
             new_state.queue_status = Some(status.clone());
         }
         
+        Action::SessionQueueLoaded { session_id, status } => {
+            if new_state.sessions.iter().any(|s| s.id == *session_id) {
+                new_state.session_queues.insert(session_id.clone(), status.clone());
+            }
+            if new_state.session_id == *session_id {
+                new_state.queue_status = Some(status.clone());
+            }
+        }
+        
         // Real-time Events
         Action::InteractionPosted { interaction } => {
+            // Fresher than the copy on disk
+            new_state.archived.remove(&interaction.id);
             new_state.interactions.insert(interaction.id.clone(), interaction.clone());
         }
         
-        Action::InteractionProcessing { interaction_id, agent_id } => {
+        Action::InteractionProcessing { interaction_id, agent_id, at } => {
             if let Some(interaction) = new_state.interactions.get_mut(interaction_id) {
                 // Add processing event to history
                 interaction.history.push(Event {
@@ -108,12 +212,12 @@ pub fn reduce(state: &EditorState, action: &Action) -> (EditorState, Vec<Effect>
                     action: "processing".to_string(),
                     content: serde_json::Value::Null,
                     metadata: None,
-                    timestamp: chrono::Utc::now(),
+                    timestamp: *at,
                 });
             }
         }
         
-        Action::InteractionCompleted { interaction_id, result } => {
+        Action::InteractionCompleted { interaction_id, result, at } => {
             if let Some(interaction) = new_state.interactions.get_mut(interaction_id) {
                 interaction.needs_work = false;
                 // Add completion to history
@@ -122,11 +226,47 @@ pub fn reduce(state: &EditorState, action: &Action) -> (EditorState, Vec<Effect>
                     action: "completed".to_string(),
                     content: result.clone(),
                     metadata: None,
-                    timestamp: chrono::Utc::now(),
+                    timestamp: *at,
                 });
             }
         }
         
+        // Tags
+        Action::AddTag { interaction_id, tag } => {
+            let tag = tag.trim();
+            if let Some(interaction) = new_state.interactions.get_mut(interaction_id) {
+                let mut tags = owned_tags(interaction);
+                if !tag.is_empty() && !tags.iter().any(|t| t == tag) {
+                    tags.push(tag.to_string());
+                    effects.push(set_tags(interaction, tags));
+                }
+            }
+        }
+        
+        Action::RemoveTag { interaction_id, tag } => {
+            if let Some(interaction) = new_state.interactions.get_mut(interaction_id) {
+                let mut tags = owned_tags(interaction);
+                let before = tags.len();
+                tags.retain(|t| t != tag.trim());
+                if tags.len() != before {
+                    effects.push(set_tags(interaction, tags));
+                }
+            }
+        }
+        
+        // Filtered Views
+        Action::SetFilter { filter } => {
+            new_state.filter = filter.clone();
+        }
+        
+        Action::ClearFilter => {
+            new_state.filter = Default::default();
+        }
+        
+        Action::SetSortOrder { sort } => {
+            new_state.sort = *sort;
+        }
+        
         // Error Handling
         Action::SetError { message } => {
             new_state.error = Some(message.clone());
@@ -136,8 +276,134 @@ pub fn reduce(state: &EditorState, action: &Action) -> (EditorState, Vec<Effect>
             new_state.error = None;
         }
         
+        // Session Management
+        Action::FetchSessions => {
+            effects.push(Effect::FetchSessions);
+        }
+        
+        Action::SessionsLoaded(Ok(sessions)) => {
+            new_state.sessions = sessions.clone();
+            new_state.session_queues.retain(|id, _| sessions.iter().any(|s| s.id == *id));
+            new_state.error = None;
+            // The session we were in is gone, work goes back to the default one
+            if new_state.session_id != DEFAULT_SESSION_ID && new_state.current_session().is_none() {
+                switch_session(&mut new_state, DEFAULT_SESSION_ID, &mut effects);
+            }
+        }
+        
+        Action::SessionsLoaded(Err(error)) => {
+            new_state.error = Some(error.clone());
+        }
+        
+        Action::CreateSession { name } => {
+            if new_state.connected {
+                effects.push(Effect::CreateSession { name: name.clone() });
+            } else {
+                new_state.error = Some("Not connected to server".to_string());
+            }
+        }
+        
+        Action::SessionCreated(Ok(session)) => {
+            if !new_state.sessions.iter().any(|s| s.id == session.id) {
+                new_state.sessions.push(session.clone());
+            }
+            new_state.error = None;
+            switch_session(&mut new_state, &session.id, &mut effects);
+        }
+        
+        Action::SessionCreated(Err(error)) => {
+            new_state.error = Some(error.clone());
+        }
+        
+        Action::SwitchSession { session_id } => {
+            switch_session(&mut new_state, session_id, &mut effects);
+        }
+        
+        // Snapshots
+        Action::SaveSnapshot => {
+            match new_state.to_snapshot() {
+                Ok(snapshot) => effects.push(Effect::PersistState { snapshot }),
+                Err(e) => new_state.error = Some(format!("Couldn't save snapshot: {}", e)),
+            }
+        }
+        
+        Action::LoadSnapshot { snapshot } => {
+            match EditorState::from_snapshot(snapshot) {
+                Ok(restored) => restore_snapshot(&mut new_state, restored, &mut effects),
+                Err(e) => new_state.error = Some(format!("Couldn't restore snapshot: {}", e)),
+            }
+        }
+        
+        // Cold storage
+        Action::ArchiveOlderThan { cutoff } => {
+            let interactions: Vec<Interaction> = archive::archivable(&new_state, *cutoff).into_iter().cloned().collect();
+            if !interactions.is_empty() {
+                new_state.archive_cutoff = Some(*cutoff);
+                effects.push(Effect::ArchiveInteractions { interactions });
+            }
+        }
+        
+        Action::InteractionsArchived(Ok(ids)) => {
+            // Anything that changed while being written stays in memory
+            if let Some(cutoff) = new_state.archive_cutoff.take() {
+                let written: Vec<InteractionStub> = archive::archivable(&new_state, cutoff)
+                    .into_iter()
+                    .filter(|interaction| ids.contains(&interaction.id))
+                    .map(|interaction| InteractionStub::of(interaction, &new_state.session_id))
+                    .collect();
+                for stub in written {
+                    new_state.interactions.remove(&stub.id);
+                    new_state.archived.insert(stub.id.clone(), stub);
+                }
+            }
+        }
+        
+        Action::InteractionsArchived(Err(error)) => {
+            new_state.archive_cutoff = None;
+            new_state.error = Some(format!("Couldn't archive interactions: {}", error));
+        }
+        
+        Action::ArchivedInteractionLoaded { interaction_id, result: Ok(interaction) } => {
+            // Gone already when the server sent a newer copy meanwhile
+            if let Some(stub) = new_state.archived.remove(interaction_id) {
+                if stub.session_id == new_state.session_id {
+                    new_state.interactions.insert(interaction_id.clone(), interaction.clone());
+                } else {
+                    new_state.session_interactions
+                        .entry(stub.session_id)
+                        .or_default()
+                        .insert(interaction_id.clone(), interaction.clone());
+                }
+            }
+        }
+        
+        Action::ArchivedInteractionLoaded { interaction_id, result: Err(error) } => {
+            new_state.error = Some(format!("Couldn't reload {}: {}", interaction_id, error));
+        }
+        
+        // Reports
+        Action::ExportReviewReport { session_id } => {
+            match report::review_report(&new_state, session_id) {
+                Some(contents) => effects.push(Effect::WriteExport {
+                    name: format!("review report {}", session_id),
+                    contents,
+                }),
+                None => new_state.error = Some(format!("No session {} to report on", session_id)),
+            }
+        }
+        
+        Action::ReviewReportExported(Ok(path)) => {
+            new_state.last_export = Some(path.clone());
+            new_state.error = None;
+        }
+        
+        Action::ReviewReportExported(Err(error)) => {
+            new_state.error = Some(format!("Couldn't export review report: {}", error));
+        }
+        
         // Connection Management
         Action::Connect { server_url } => {
+            new_state.server_url = Some(server_url.clone());
             effects.push(Effect::ConnectToStream {
                 server_url: server_url.clone(),
                 session_id: new_state.session_id.clone(),
@@ -156,6 +422,93 @@ pub fn reduce(state: &EditorState, action: &Action) -> (EditorState, Vec<Effect>
                 new_state.error = Some(format!("Disconnected: {}", reason));
             }
         }
+        
+        // Worktrees
+        Action::WorktreesLoaded { worktrees } => {
+            new_state.available_worktrees = worktrees.clone();
+        }
+        
+        Action::WorktreeAdded { worktree } => {
+            if !new_state.available_worktrees.iter().any(|w| w.id == worktree.id) {
+                new_state.available_worktrees.push(worktree.clone());
+            }
+        }
+        
+        Action::WorktreeDeleted { worktree_id } => {
+            new_state.available_worktrees.retain(|w| &w.id != worktree_id);
+            new_state.worktree_git_status.remove(worktree_id);
+            if new_state.current_worktree.as_ref().is_some_and(|w| &w.id == worktree_id) {
+                new_state.current_worktree = None;
+                new_state.worktree_files.clear();
+            }
+        }
+        
+        Action::FocusWorktree { worktree_id } => {
+            let worktree = worktree_id
+                .as_ref()
+                .and_then(|id| new_state.available_worktrees.iter().find(|w| &w.id == id))
+                .cloned();
+            // The files listed belong to the worktree we're leaving
+            if new_state.current_worktree.as_ref().map(|w| &w.id) != worktree.as_ref().map(|w| &w.id) {
+                new_state.current_worktree = worktree;
+                new_state.worktree_files.clear();
+            }
+        }
+        
+        Action::WorktreeStatusesLoaded { statuses } => {
+            new_state.worktree_git_status = statuses.clone();
+        }
+        
+        Action::WorktreeFilesLoaded { files } => {
+            new_state.worktree_files = files.clone();
+        }
+        
+        // Notifications
+        Action::ShowNotification { notification, at } => {
+            new_state.notification = Some((notification.clone(), *at));
+        }
+        
+        Action::ClearNotification => {
+            new_state.notification = None;
+        }
+        
+        // Permission Answers
+        Action::PermissionAutoAnswered { key } => {
+            new_state.auto_answered.insert(key.clone());
+        }
+        
+        Action::SyncPendingPermissions { waiting, interactions, at } => {
+            new_state.auto_answered.retain(|key| waiting.contains(key));
+            new_state.permission_requested.retain(|key, _| waiting.contains(key));
+            for key in waiting {
+                new_state.permission_requested.entry(key.clone()).or_insert(*at);
+            }
+            // A failed answer stops mattering once the server has moved on without it
+            new_state.permission_deliveries.retain(|id, delivery| {
+                delivery.failed.is_none() || interactions.contains(&(delivery.server.clone(), id.clone()))
+            });
+        }
+        
+        Action::PermissionResponseQueued { interaction_id, server, approved } => {
+            let delivery = PermissionDelivery { server: server.clone(), approved: *approved, attempts: 1, failed: None };
+            new_state.permission_deliveries.insert(interaction_id.clone(), delivery);
+        }
+        
+        Action::PermissionResponseRetrying { interaction_id } => {
+            if let Some(delivery) = new_state.permission_deliveries.get_mut(interaction_id) {
+                delivery.attempts += 1;
+            }
+        }
+        
+        Action::PermissionResponseDelivered { interaction_id } => {
+            new_state.permission_deliveries.remove(interaction_id);
+        }
+        
+        Action::PermissionResponseFailed { interaction_id, error } => {
+            if let Some(delivery) = new_state.permission_deliveries.get_mut(interaction_id) {
+                delivery.failed = Some(error.clone());
+            }
+        }
     }
     
     // Update pending reviews whenever interactions change
@@ -165,4 +518,121 @@ pub fn reduce(state: &EditorState, action: &Action) -> (EditorState, Vec<Effect>
         .collect();
     
     (new_state, effects)
-}
\ No newline at end of file
+}
+
+fn draft_key(interaction_id: Option<&str>) -> String {
+    interaction_id.unwrap_or(NEW_DRAFT_KEY).to_string()
+}
+
+/// Clear the draft a submission was made from, wherever focus has moved since
+fn clear_submitted_draft(state: &mut EditorState, key: &str) {
+    if draft_key(state.focused_interaction.as_deref()) == key {
+        state.draft.content.clear();
+    } else if let Some(draft) = state.drafts.get_mut(key) {
+        draft.content.clear();
+        if *draft == InteractionDraft::default() {
+            state.drafts.remove(key);
+        }
+    }
+}
+
+/// The draft as an interaction under a temp id, until the server confirms it
+fn provisional_interaction(state: &mut EditorState, now: chrono::DateTime<chrono::Utc>) -> Interaction {
+    let id = format!("temp-{}", state.next_temp_id);
+    state.next_temp_id += 1;
+    
+    Interaction {
+        id,
+        source: "user".to_string(),
+        interaction_type: state.draft.interaction_type.clone(),
+        content: vec![ConversationItem {
+            role: "user".to_string(),
+            content: state.draft.content.clone(),
+            timestamp: now,
+            metadata: None,
+        }],
+        needs_work: true,
+        review_stack: state.draft.review_stack.clone(),
+        history: vec![],
+        metadata: state.draft.metadata.clone(),
+        timestamp: now,
+    }
+}
+
+/// The interactions and temp ids of the session `temp_id` was submitted in, the current one if it's unknown
+fn submitted_in<'a>(state: &'a mut EditorState, temp_id: &str) -> (&'a mut HashMap<String, Interaction>, &'a mut Vec<String>) {
+    let parked = state.session_provisional
+        .iter()
+        .find(|(_, provisional)| provisional.iter().any(|id| id == temp_id))
+        .map(|(session_id, _)| session_id.clone());
+    match parked {
+        Some(session_id) => (
+            state.session_interactions.entry(session_id.clone()).or_default(),
+            state.session_provisional.entry(session_id).or_default(),
+        ),
+        None => (&mut state.interactions, &mut state.provisional),
+    }
+}
+
+/// Take the snapshot's working set, keeping the connection we have now
+fn restore_snapshot(state: &mut EditorState, restored: EditorState, effects: &mut Vec<Effect>) {
+    let session_changed = restored.session_id != state.session_id;
+    *state = EditorState {
+        server_url: state.server_url.clone().or(restored.server_url.clone()),
+        connected: state.connected,
+        ..restored
+    };
+    // A crash mid-write or a hand edit can leave ids pointing at nothing
+    let report = crate::integrity::check(state);
+    state.integrity_report = (!report.is_clean()).then_some(report);
+    
+    // Already streaming, but for the session we had before
+    if state.connected && session_changed {
+        if let Some(server_url) = &state.server_url {
+            effects.push(Effect::ConnectToStream {
+                server_url: server_url.clone(),
+                session_id: state.session_id.clone(),
+            });
+        }
+    }
+}
+
+/// Park the current session's interactions and bring back `session_id`'s
+fn switch_session(state: &mut EditorState, session_id: &str, effects: &mut Vec<Effect>) {
+    if state.session_id == session_id {
+        return;
+    }
+    
+    let parked = std::mem::take(&mut state.interactions);
+    state.session_interactions.insert(state.session_id.clone(), parked);
+    state.interactions = state.session_interactions.remove(session_id).unwrap_or_default();
+    let parked = std::mem::take(&mut state.provisional);
+    if !parked.is_empty() {
+        state.session_provisional.insert(state.session_id.clone(), parked);
+    }
+    state.provisional = state.session_provisional.remove(session_id).unwrap_or_default();
+    state.session_id = session_id.to_string();
+    // The draft stays, the queue belongs to the old session
+    state.queue_status = None;
+    
+    // The stream is per session
+    if let Some(server_url) = &state.server_url {
+        effects.push(Effect::ConnectToStream {
+            server_url: server_url.clone(),
+            session_id: session_id.to_string(),
+        });
+    }
+}
+
+fn owned_tags(interaction: &Interaction) -> Vec<String> {
+    crate::filter::tags(interaction).into_iter().map(str::to_string).collect()
+}
+
+/// Show the new tags straight away, the server hears about them through the effect
+fn set_tags(interaction: &mut Interaction, tags: Vec<String>) -> Effect {
+    interaction.metadata.insert("tags".to_string(), serde_json::json!(tags));
+    Effect::UpdateInteractionTags {
+        interaction_id: interaction.id.clone(),
+        tags,
+    }
+}
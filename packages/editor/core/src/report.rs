@@ -0,0 +1,174 @@
+use chrono::{DateTime, Utc};
+use serde_json::{json, Value};
+
+use crate::stats::ConversationStats;
+use crate::types::{Event, Interaction};
+use crate::EditorState;
+
+/// History action a review decision is recorded under
+pub const REVIEWED_ACTION: &str = "reviewed";
+
+/// Who reviews, as review stacks name the user
+pub const REVIEWER: &str = "user";
+
+/// One approval or request for changes, as it was submitted
+#[derive(Debug, Clone, PartialEq)]
+pub struct ReviewDecision {
+    pub approved: bool,
+    pub feedback: Option<String>,
+    pub reviewed_at: DateTime<Utc>,
+}
+
+impl ReviewDecision {
+    /// The history entry the decision is kept as
+    pub fn to_event(&self) -> Event {
+        Event {
+            agent_id: REVIEWER.to_string(),
+            action: REVIEWED_ACTION.to_string(),
+            content: json!({ "approved": self.approved, "feedback": self.feedback }),
+            metadata: None,
+            timestamp: self.reviewed_at,
+        }
+    }
+
+    /// Read a decision back, `None` for any other history entry
+    pub fn from_event(event: &Event) -> Option<Self> {
+        if event.action != REVIEWED_ACTION {
+            return None;
+        }
+        let feedback = event
+            .content
+            .get("feedback")
+            .and_then(Value::as_str)
+            .map(str::trim)
+            .filter(|feedback| !feedback.is_empty())
+            .map(str::to_string);
+        Some(Self {
+            approved: event.content.get("approved")?.as_bool()?,
+            feedback,
+            reviewed_at: event.timestamp,
+        })
+    }
+}
+
+/// A reviewed interaction with its decisions, oldest first
+#[derive(Debug, Clone)]
+pub struct ReviewedInteraction<'a> {
+    pub interaction: &'a Interaction,
+    pub decisions: Vec<ReviewDecision>,
+    /// What Wake's tool calls changed in the interaction
+    pub changes: ConversationStats,
+}
+
+/// The interactions that have been reviewed, by when they were first reviewed
+pub fn reviewed<'a>(interactions: impl IntoIterator<Item = &'a Interaction>) -> Vec<ReviewedInteraction<'a>> {
+    let mut reviewed: Vec<ReviewedInteraction> = interactions
+        .into_iter()
+        .filter_map(|interaction| {
+            let mut decisions: Vec<ReviewDecision> = interaction.history.iter().filter_map(ReviewDecision::from_event).collect();
+            if decisions.is_empty() {
+                return None;
+            }
+            decisions.sort_by_key(|decision| decision.reviewed_at);
+            Some(ReviewedInteraction { interaction, decisions, changes: changes(interaction) })
+        })
+        .collect();
+    reviewed.sort_by(|a, b| {
+        a.decisions[0]
+            .reviewed_at
+            .cmp(&b.decisions[0].reviewed_at)
+            .then_with(|| a.interaction.id.cmp(&b.interaction.id))
+    });
+    reviewed
+}
+
+// The server keeps tool calls on the messages as `toolCalls: [{ name, arguments }]`
+fn changes(interaction: &Interaction) -> ConversationStats {
+    let mut stats = ConversationStats::default();
+    let calls = interaction
+        .content
+        .iter()
+        .filter_map(|item| item.metadata.as_ref()?.get("toolCalls")?.as_array())
+        .flatten();
+    for call in calls {
+        if let Some(name) = call.get("name").and_then(Value::as_str) {
+            stats.add_tool_call(name, call.get("arguments").unwrap_or(&Value::Null));
+        }
+    }
+    stats
+}
+
+/// File name for an export of `name`, made at `timestamp` seconds since the epoch
+pub fn export_file_name(name: &str, timestamp: u64) -> String {
+    format!("{}-{}.md", slug(name), timestamp)
+}
+
+fn slug(name: &str) -> String {
+    name.to_lowercase()
+        .chars()
+        .map(|c| if c.is_alphanumeric() { c } else { '-' })
+        .collect::<String>()
+        .split('-')
+        .filter(|part| !part.is_empty())
+        .collect::<Vec<_>>()
+        .join("-")
+}
+
+/// Every review decision in a session as Markdown, `None` for a session we don't have
+pub fn review_report(state: &EditorState, session_id: &str) -> Option<String> {
+    let interactions = state.interactions_in(session_id)?;
+    let name = state
+        .sessions
+        .iter()
+        .find(|session| session.id == session_id)
+        .and_then(|session| session.name.as_deref())
+        .unwrap_or(session_id);
+    let reviewed = reviewed(interactions.values());
+
+    let mut markdown = format!("# Review report: {}\n\n", name);
+    if reviewed.is_empty() {
+        markdown.push_str("_Nothing in this session has been reviewed._\n");
+        return Some(markdown);
+    }
+    let decisions: Vec<&ReviewDecision> = reviewed.iter().flat_map(|r| &r.decisions).collect();
+    let approved = decisions.iter().filter(|decision| decision.approved).count();
+    markdown.push_str(&format!(
+        "{} interaction(s) reviewed: {} approved, {} changes requested.\n\n",
+        reviewed.len(),
+        approved,
+        decisions.len() - approved
+    ));
+
+    for entry in &reviewed {
+        markdown.push_str(&format!("## {}\n\n", entry.interaction.title()));
+        markdown.push_str(&format!("Interaction `{}`\n\n", entry.interaction.id));
+
+        for decision in &entry.decisions {
+            let verdict = if decision.approved { "Approved" } else { "Changes requested" };
+            markdown.push_str(&format!("- **{}** {}\n", verdict, decision.reviewed_at.format("%Y-%m-%d %H:%M UTC")));
+            for line in decision.feedback.iter().flat_map(|feedback| feedback.lines()) {
+                markdown.push_str(format!("  > {}", line).trim_end());
+                markdown.push('\n');
+            }
+        }
+        markdown.push('\n');
+
+        let changes = &entry.changes;
+        if changes.files.is_empty() {
+            markdown.push_str("No file changes.\n\n");
+        } else {
+            markdown.push_str(&format!(
+                "Changes: {} file(s), +{} -{}\n\n",
+                changes.files.len(),
+                changes.lines_added,
+                changes.lines_removed
+            ));
+            for file in &changes.files {
+                markdown.push_str(&format!("- `{}`\n", file));
+            }
+            markdown.push('\n');
+        }
+    }
+
+    Some(markdown)
+}
@@ -1,46 +1,175 @@
+use crate::archive::InteractionStub;
+use crate::filter::{InteractionFilter, SortOrder};
+use crate::integrity::{IntegrityReport, Quarantine};
 use crate::types::*;
-use std::collections::HashMap;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
 
-/// Core editor state focused on interaction management
+/// Session the server puts work in when the client names none
+pub const DEFAULT_SESSION_ID: &str = "default-session";
+
+/// A permission answer on its way to the server
 #[derive(Debug, Clone, PartialEq)]
+pub struct PermissionDelivery {
+    pub server: String,
+    pub approved: bool,
+    /// Sends so far, counting the first
+    pub attempts: u32,
+    /// Why the last send failed, set once we've stopped retrying
+    pub failed: Option<MessageError>,
+}
+
+/// Core editor state focused on interaction management
+///
+/// Serializes to a snapshot of the working set. The connection isn't part of
+/// it, a restored state starts disconnected with no error.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
 pub struct EditorState {
-    /// Current session ID (hardcoded for now)
+    /// Session we're working in
     pub session_id: String,
     
-    /// All interactions we know about
+    /// Sessions the server knows about
+    pub sessions: Vec<Session>,
+    
+    /// All interactions we know about in the current session
     pub interactions: HashMap<String, Interaction>,
     
+    /// Interactions of the other sessions, kept for when we switch back
+    pub session_interactions: HashMap<String, HashMap<String, Interaction>>,
+    
+    /// Interactions moved to cold storage, by id, reloaded when focused
+    pub archived: HashMap<String, InteractionStub>,
+    
+    /// Cutoff of the archiving waiting to be written, `None` when there isn't one
+    #[serde(skip)]
+    pub archive_cutoff: Option<chrono::DateTime<chrono::Utc>>,
+    
+    /// Server we last connected to, used to reconnect on session switch
+    pub server_url: Option<String>,
+    
     /// The interaction we're currently drafting
     pub draft: InteractionDraft,
     
+    /// Interaction the draft follows up on, `None` while drafting a new one
+    pub focused_interaction: Option<String>,
+    
+    /// Drafts put away when focus moved, by interaction id, a new interaction's under `NEW_DRAFT_KEY`
+    pub drafts: HashMap<String, InteractionDraft>,
+    
     /// Current queue status
     pub queue_status: Option<InteractionQueueStatus>,
     
+    /// Queue status of each listed session, by session id
+    #[serde(skip)]
+    pub session_queues: HashMap<String, InteractionQueueStatus>,
+    
     /// Interactions awaiting our review (where we're top of review stack)
     pub pending_reviews: Vec<String>,
     
+    /// Temp ids of the current session's interactions shown before the server confirmed them, oldest first
+    pub provisional: Vec<String>,
+    
+    /// Temp ids of the other sessions, parked with their interactions
+    pub session_provisional: HashMap<String, Vec<String>>,
+    
+    /// Draft each submission still in flight was made from, by temp id
+    pub submitted_drafts: HashMap<String, String>,
+    
+    /// Next number for a provisional interaction's temp id
+    pub next_temp_id: u64,
+    
+    /// What `visible_interactions` shows, and in which order
+    pub filter: InteractionFilter,
+    pub sort: SortOrder,
+    
+    /// What restoring a snapshot had to set aside, kept until the user deals with it
+    pub quarantine: Quarantine,
+    
+    /// What the last restore repaired and quarantined, `None` when it found nothing wrong
+    #[serde(skip)]
+    pub integrity_report: Option<IntegrityReport>,
+    
+    /// Where the last export was written
+    #[serde(skip)]
+    pub last_export: Option<String>,
+    
     /// Connection state
+    #[serde(skip)]
     pub connected: bool,
     
     /// Current error message (if any)
+    #[serde(skip)]
     pub error: Option<String>,
+    
+    /// Worktrees the server has
+    #[serde(skip)]
+    pub available_worktrees: Vec<Worktree>,
+    
+    /// Worktree the focused work happens in, `None` for the main checkout
+    #[serde(skip)]
+    pub current_worktree: Option<Worktree>,
+    
+    /// Latest git status of each worktree, by worktree id
+    #[serde(skip)]
+    pub worktree_git_status: HashMap<String, WorktreeGitStatus>,
+    
+    /// Files in the current worktree
+    #[serde(skip)]
+    pub worktree_files: Vec<String>,
+    
+    /// Toast on screen, with when it was shown
+    #[serde(skip)]
+    pub notification: Option<(Notification, chrono::DateTime<chrono::Utc>)>,
+    
+    /// Permission requests already answered without asking, by request key
+    #[serde(skip)]
+    pub auto_answered: HashSet<String>,
+    
+    /// When each waiting permission request was first seen, by request key
+    #[serde(skip)]
+    pub permission_requested: HashMap<String, chrono::DateTime<chrono::Utc>>,
+    
+    /// Permission answers not yet accepted by the server, by interaction id
+    #[serde(skip)]
+    pub permission_deliveries: HashMap<String, PermissionDelivery>,
 }
 
 impl Default for EditorState {
     fn default() -> Self {
         Self {
-            session_id: "default-session".to_string(), // Hardcoded for MVP
+            session_id: DEFAULT_SESSION_ID.to_string(),
+            sessions: Vec::new(),
             interactions: HashMap::new(),
-            draft: InteractionDraft {
-                content: String::new(),
-                interaction_type: InteractionType::Query,
-                review_stack: vec!["user".to_string()], // User reviews by default
-                metadata: HashMap::new(),
-            },
+            session_interactions: HashMap::new(),
+            archived: HashMap::new(),
+            archive_cutoff: None,
+            server_url: None,
+            draft: InteractionDraft::default(),
+            focused_interaction: None,
+            drafts: HashMap::new(),
             queue_status: None,
+            session_queues: HashMap::new(),
             pending_reviews: Vec::new(),
+            provisional: Vec::new(),
+            session_provisional: HashMap::new(),
+            submitted_drafts: HashMap::new(),
+            next_temp_id: 0,
+            filter: InteractionFilter::default(),
+            sort: SortOrder::default(),
+            quarantine: Quarantine::default(),
+            integrity_report: None,
+            last_export: None,
             connected: false,
             error: None,
+            available_worktrees: Vec::new(),
+            current_worktree: None,
+            worktree_git_status: HashMap::new(),
+            worktree_files: Vec::new(),
+            notification: None,
+            auto_answered: HashSet::new(),
+            permission_requested: HashMap::new(),
+            permission_deliveries: HashMap::new(),
         }
     }
 }
@@ -57,6 +186,14 @@ impl EditorState {
             .collect()
     }
     
+    /// Interactions waiting on the user to approve or deny a tool call
+    pub fn get_pending_permissions(&self) -> Vec<&Interaction> {
+        self.interactions
+            .values()
+            .filter(|i| i.pending_permission().is_some())
+            .collect()
+    }
+    
     /// Get interactions currently being processed
     pub fn get_processing(&self) -> Vec<&Interaction> {
         self.interactions
@@ -65,6 +202,59 @@ impl EditorState {
             .collect()
     }
     
+    /// Interactions matching `filter`, in `sort` order
+    pub fn visible_interactions(&self) -> Vec<&Interaction> {
+        self.query(&self.filter, self.sort)
+    }
+    
+    /// Interactions matching any filter and order, for views that keep their own
+    pub fn query(&self, filter: &InteractionFilter, sort: SortOrder) -> Vec<&Interaction> {
+        let mut interactions: Vec<&Interaction> = self.interactions
+            .values()
+            .filter(|i| filter.matches(i))
+            .collect();
+        sort.sort(&mut interactions);
+        interactions
+    }
+    
+    /// The current session, if the server has told us about it
+    pub fn current_session(&self) -> Option<&Session> {
+        self.sessions.iter().find(|s| s.id == self.session_id)
+    }
+    
+    /// The current session's archived interactions, most recently active first
+    pub fn archived_interactions(&self) -> Vec<&InteractionStub> {
+        let mut stubs: Vec<&InteractionStub> = self.archived
+            .values()
+            .filter(|stub| stub.session_id == self.session_id)
+            .collect();
+        stubs.sort_by(|a, b| b.last_activity.cmp(&a.last_activity).then_with(|| a.id.cmp(&b.id)));
+        stubs
+    }
+    
+    /// A session's interactions, whether it's the current one or put away
+    pub fn interactions_in(&self, session_id: &str) -> Option<&HashMap<String, Interaction>> {
+        if session_id == self.session_id {
+            Some(&self.interactions)
+        } else {
+            self.session_interactions.get(session_id)
+        }
+    }
+    
+    /// The working set as checksummed JSON, as `Effect::PersistState` carries it
+    pub fn to_snapshot(&self) -> Result<String, String> {
+        let state = serde_json::to_string(self).map_err(|e| e.to_string())?;
+        crate::integrity::seal(&state)
+    }
+    
+    /// Read back a snapshot, fields it doesn't have keep their defaults
+    ///
+    /// Fails when the checksum doesn't match, snapshots from before checksums are read as they are.
+    pub fn from_snapshot(snapshot: &str) -> Result<Self, String> {
+        let state = crate::integrity::unseal(snapshot)?;
+        serde_json::from_str(&state).map_err(|e| e.to_string())
+    }
+    
     /// Get completed interactions
     pub fn get_completed(&self) -> Vec<&Interaction> {
         self.interactions
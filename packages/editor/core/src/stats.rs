@@ -0,0 +1,287 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+use serde_json::Value;
+
+use crate::diff;
+
+/// Average silent reading speed for technical prose
+pub const WORDS_PER_MINUTE: usize = 200;
+
+/// Totals for a conversation, built up one message and tool call at a time
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ConversationStats {
+    pub words: usize,
+    /// Files the agent edited or wrote, by the path the tool was given
+    pub files: BTreeSet<String>,
+    pub lines_added: usize,
+    pub lines_removed: usize,
+    /// Activity in each programming language, from code blocks and file edits
+    pub languages: BTreeMap<&'static str, LanguageActivity>,
+}
+
+/// How much of a conversation was in one language
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct LanguageActivity {
+    /// Code blocks written in it
+    pub blocks: usize,
+    /// Lines in those blocks, plus lines added and removed in its files
+    pub lines: usize,
+}
+
+// Names a language goes by in code fences, file extensions included
+const LANGUAGES: &[(&str, &[&str])] = &[
+    ("Rust", &["rust", "rs"]),
+    ("Python", &["python", "py", "python3"]),
+    ("TypeScript", &["typescript", "ts", "tsx", "mts"]),
+    ("JavaScript", &["javascript", "js", "jsx", "mjs", "cjs", "node"]),
+    ("Go", &["go", "golang"]),
+    ("Java", &["java"]),
+    ("Kotlin", &["kotlin", "kt", "kts"]),
+    ("Swift", &["swift"]),
+    ("C", &["c", "h"]),
+    ("C++", &["cpp", "c++", "cc", "cxx", "hpp", "hh"]),
+    ("C#", &["csharp", "cs", "c#"]),
+    ("Ruby", &["ruby", "rb"]),
+    ("PHP", &["php"]),
+    ("Elixir", &["elixir", "ex", "exs"]),
+    ("Haskell", &["haskell", "hs"]),
+    ("Lua", &["lua"]),
+    ("Zig", &["zig"]),
+    ("Nix", &["nix"]),
+    ("Shell", &["shell", "sh", "bash", "zsh", "fish", "console"]),
+    ("SQL", &["sql"]),
+    ("HTML", &["html", "htm"]),
+    ("CSS", &["css", "scss", "sass"]),
+    ("JSON", &["json", "jsonc"]),
+    ("YAML", &["yaml", "yml"]),
+    ("TOML", &["toml"]),
+    ("Markdown", &["markdown", "md"]),
+];
+
+/// The language a code fence's info string names, e.g. `rs` or `python title="x"`
+pub fn language_of_fence(info: &str) -> Option<&'static str> {
+    let tag = info.split_whitespace().next()?.trim_start_matches('{').trim_start_matches('.').to_lowercase();
+    LANGUAGES
+        .iter()
+        .find(|(_, names)| names.contains(&tag.as_str()))
+        .map(|(language, _)| *language)
+}
+
+/// The language a file is written in, going by its extension
+pub fn language_of_path(path: &str) -> Option<&'static str> {
+    let name = path.rsplit('/').next()?;
+    let (_, extension) = name.rsplit_once('.')?;
+    language_of_fence(extension)
+}
+
+/// A guess at an untagged code block's language from telltale syntax
+pub fn guess_language(code: &str) -> Option<&'static str> {
+    let has = |needle: &str| code.contains(needle);
+    let first = code.lines().next().unwrap_or_default();
+    if first.starts_with("#!") {
+        return if first.contains("python") { Some("Python") } else { Some("Shell") };
+    }
+    if has("fn ") && (has("let ") || has("->") || has("pub ")) {
+        Some("Rust")
+    } else if has("package ") && has("func ") {
+        Some("Go")
+    } else if (has("def ") && (has("):") || has("self"))) || (has("import ") && has("__name__")) {
+        Some("Python")
+    } else if has("interface ") && has(": ") && (has("const ") || has("export ")) {
+        Some("TypeScript")
+    } else if has("function ") || (has("const ") && has("=>")) {
+        Some("JavaScript")
+    } else {
+        None
+    }
+}
+
+impl ConversationStats {
+    pub fn add_text(&mut self, text: &str) {
+        self.words += word_count(text);
+        for (info, code) in code_blocks(text) {
+            self.add_code_block(info, &code);
+        }
+    }
+
+    /// Count a fenced code block towards its language, diffs towards their files' languages
+    pub fn add_code_block(&mut self, info: &str, code: &str) {
+        let is_diff = matches!(info.split_whitespace().next(), Some("diff" | "patch"))
+            || (info.trim().is_empty() && (code.starts_with("diff --git") || code.starts_with("--- ")));
+        if is_diff {
+            let mut seen = BTreeSet::new();
+            for (path, lines) in diff_files(code) {
+                if let Some(language) = language_of_path(&path) {
+                    let activity = self.languages.entry(language).or_default();
+                    activity.lines += lines;
+                    if seen.insert(language) {
+                        activity.blocks += 1;
+                    }
+                }
+            }
+            return;
+        }
+
+        let language = match info.trim() {
+            "" => guess_language(code),
+            info => language_of_fence(info),
+        };
+        if let Some(language) = language {
+            let activity = self.languages.entry(language).or_default();
+            activity.blocks += 1;
+            activity.lines += code.lines().count();
+        }
+    }
+
+    /// Languages by how much went on in them, busiest first
+    pub fn top_languages(&self) -> Vec<(&'static str, LanguageActivity)> {
+        let mut languages: Vec<_> = self.languages.iter().map(|(language, activity)| (*language, *activity)).collect();
+        languages.sort_by_key(|(_, activity)| std::cmp::Reverse(activity.lines));
+        languages
+    }
+
+    /// Count what a tool call changed, tools that don't edit files are ignored
+    ///
+    /// Understands Edit and MultiEdit (`old_string`/`new_string`), Write (`content`)
+    /// and anything that passes a unified `diff` or `patch`.
+    pub fn add_tool_call(&mut self, tool: &str, input: &Value) {
+        let path = ["file_path", "path", "notebook_path"]
+            .iter()
+            .find_map(|key| input.get(*key).and_then(Value::as_str));
+
+        if let Some(patch) = input.get("diff").or_else(|| input.get("patch")).and_then(Value::as_str) {
+            for file in diff::parse(patch) {
+                self.lines_added += file.added();
+                self.lines_removed += file.removed();
+                self.add_file_lines(&file.path, file.added() + file.removed());
+                self.files.insert(file.path);
+            }
+            return;
+        }
+        let before = self.lines_added + self.lines_removed;
+
+        let edits: Vec<(&str, &str)> = match input.get("edits").and_then(Value::as_array) {
+            Some(edits) => edits.iter().filter_map(string_pair).collect(),
+            None => string_pair(input).into_iter().collect(),
+        };
+        let changed = if !edits.is_empty() {
+            for (old, new) in edits {
+                let (added, removed) = line_changes(old, new);
+                self.lines_added += added;
+                self.lines_removed += removed;
+            }
+            true
+        } else if let Some(content) = input.get("content").and_then(Value::as_str).filter(|_| tool == "Write") {
+            self.lines_added += content.lines().count();
+            true
+        } else {
+            false
+        };
+
+        if let Some(path) = path.filter(|_| changed) {
+            self.add_file_lines(path, self.lines_added + self.lines_removed - before);
+            self.files.insert(path.to_string());
+        }
+    }
+
+    fn add_file_lines(&mut self, path: &str, lines: usize) {
+        if let Some(language) = language_of_path(path) {
+            self.languages.entry(language).or_default().lines += lines;
+        }
+    }
+
+    /// Whole minutes to read everything, rounded up
+    pub fn reading_minutes(&self) -> usize {
+        self.words.div_ceil(WORDS_PER_MINUTE)
+    }
+}
+
+/// Fenced code blocks in markdown, with each one's info string
+fn code_blocks(text: &str) -> Vec<(&str, String)> {
+    let mut blocks = Vec::new();
+    let mut open: Option<(&str, &str, String)> = None;
+    for line in text.lines() {
+        let trimmed = line.trim_start();
+        match &mut open {
+            Some((fence, _, _)) if trimmed.starts_with(*fence) && trimmed.trim_start_matches(*fence).trim().is_empty() => {
+                let (_, info, code) = open.take().unwrap_or_default();
+                blocks.push((info, code));
+            }
+            Some((_, _, code)) => {
+                code.push_str(line);
+                code.push('\n');
+            }
+            None => {
+                for fence in ["```", "~~~"] {
+                    if let Some(info) = trimmed.strip_prefix(fence) {
+                        open = Some((fence, info.trim(), String::new()));
+                        break;
+                    }
+                }
+            }
+        }
+    }
+    blocks
+}
+
+/// Each file a diff touches with the lines it changed there
+///
+/// Diffs pasted into messages often lack the `diff --git` header, those are
+/// read as a single file named by their `+++` line.
+fn diff_files(code: &str) -> Vec<(String, usize)> {
+    let files = diff::parse(code);
+    if !files.is_empty() {
+        return files
+            .into_iter()
+            .map(|file| {
+                let lines = file.added() + file.removed();
+                (file.path, lines)
+            })
+            .collect();
+    }
+
+    let Some(path) = code.lines().find_map(|line| line.strip_prefix("+++ ")) else {
+        return Vec::new();
+    };
+    let path = path.trim().trim_start_matches("b/").to_string();
+    let lines = code
+        .lines()
+        .filter(|line| {
+            (line.starts_with('+') && !line.starts_with("+++")) || (line.starts_with('-') && !line.starts_with("---"))
+        })
+        .count();
+    vec![(path, lines)]
+}
+
+fn string_pair(edit: &Value) -> Option<(&str, &str)> {
+    Some((
+        edit.get("old_string")?.as_str()?,
+        edit.get("new_string")?.as_str()?,
+    ))
+}
+
+/// Words as a reader would count them, punctuation on its own isn't one
+pub fn word_count(text: &str) -> usize {
+    text.split_whitespace()
+        .filter(|word| word.chars().any(char::is_alphanumeric))
+        .count()
+}
+
+/// Lines added and removed replacing `old` with `new`
+///
+/// Lines the two share at the start and end aren't counted, which matches a
+/// diff for the usual single-block edit.
+pub fn line_changes(old: &str, new: &str) -> (usize, usize) {
+    let old: Vec<&str> = old.lines().collect();
+    let new: Vec<&str> = new.lines().collect();
+
+    let prefix = old.iter().zip(&new).take_while(|(a, b)| a == b).count();
+    let suffix = old[prefix..]
+        .iter()
+        .rev()
+        .zip(new[prefix..].iter().rev())
+        .take_while(|(a, b)| a == b)
+        .count();
+
+    (new.len() - prefix - suffix, old.len() - prefix - suffix)
+}
@@ -0,0 +1,108 @@
+use crate::{
+    state::EditorState,
+    actions::Action,
+    action_log::ActionLog,
+    clock::Clock,
+    middleware::Pipeline,
+};
+use pretty_assertions::assert_eq;
+use chrono::{DateTime, TimeZone, Utc};
+use std::sync::{Arc, Mutex};
+use std::time::Instant;
+
+// Every action lands at the same moment
+#[derive(Debug)]
+struct FixedClock;
+
+impl Clock for FixedClock {
+    fn instant(&self) -> Instant {
+        Instant::now()
+    }
+
+    fn utc(&self) -> DateTime<Utc> {
+        recorded_at()
+    }
+}
+
+fn recorded_at() -> DateTime<Utc> {
+    Utc.with_ymd_and_hms(2024, 3, 1, 10, 0, 0).unwrap()
+}
+
+fn type_draft(content: &str) -> Action {
+    Action::UpdateDraftContent {
+        content: content.to_string()
+    }
+}
+
+// Dispatch each draft in turn, recording into a log of the given size
+fn typed(capacity: usize, drafts: &[&str]) -> (ActionLog, EditorState) {
+    let log = Arc::new(Mutex::new(ActionLog::new(capacity)));
+    let mut pipeline = Pipeline::new();
+    pipeline.register(ActionLog::recorder(log.clone(), Arc::new(FixedClock)));
+
+    let mut state = EditorState::default();
+    for draft in drafts {
+        state = pipeline.dispatch(&state, &type_draft(draft)).0;
+    }
+    let log = log.lock().unwrap().clone();
+    (log, state)
+}
+
+#[test]
+fn test_records_every_dispatched_action() {
+    let (log, _) = typed(10, &["a", "ab", "abc"]);
+
+    assert_eq!(log.len(), 3);
+    assert_eq!(log.entries()[2].action, type_draft("abc"));
+    assert!(log.entries().iter().all(|entry| entry.at == recorded_at()));
+}
+
+#[test]
+fn test_replay_rebuilds_each_state() {
+    let (log, live) = typed(10, &["a", "ab", "abc"]);
+
+    assert_eq!(log.state_at(0).unwrap().draft.content, "");
+    assert_eq!(log.state_at(2).unwrap().draft.content, "ab");
+    assert_eq!(log.state_at(3).unwrap(), live);
+}
+
+#[test]
+fn test_step_back_and_forward_to_live() {
+    let (mut log, live) = typed(10, &["a", "ab", "abc"]);
+
+    assert_eq!(log.step_back().unwrap().draft.content, "ab");
+    assert_eq!(log.step_back().unwrap().draft.content, "a");
+    assert_eq!(log.cursor(), Some(1));
+
+    assert_eq!(log.step_forward().unwrap().draft.content, "ab");
+    assert_eq!(log.step_forward().unwrap(), live);
+    assert_eq!(log.cursor(), None);
+    assert_eq!(log.step_forward(), None);
+}
+
+#[test]
+fn test_step_back_stops_at_oldest() {
+    let (mut log, _) = typed(10, &["a"]);
+
+    assert_eq!(log.step_back().unwrap().draft.content, "");
+    assert_eq!(log.step_back().unwrap().draft.content, "");
+    assert_eq!(log.cursor(), Some(0));
+}
+
+#[test]
+fn test_full_log_folds_oldest_into_base() {
+    let (log, live) = typed(2, &["a", "ab", "abc", "abcd"]);
+
+    assert_eq!(log.len(), 2);
+    assert_eq!(log.state_at(0).unwrap().draft.content, "ab");
+    assert_eq!(log.state_at(2).unwrap(), live);
+}
+
+#[test]
+fn test_empty_log_has_nothing_to_replay() {
+    let mut log = ActionLog::default();
+
+    assert!(log.is_empty());
+    assert_eq!(log.state_at(0), None);
+    assert_eq!(log.step_back(), None);
+}
@@ -0,0 +1,174 @@
+use crate::{
+    state::EditorState,
+    actions::Action,
+    effects::Effect,
+    reducer::reduce,
+    archive::{self, InteractionStub},
+    types::{ConversationItem, Interaction, InteractionType}
+};
+use pretty_assertions::assert_eq;
+use chrono::{DateTime, TimeZone, Utc};
+use std::collections::HashMap;
+
+fn at(day: u32) -> DateTime<Utc> {
+    Utc.with_ymd_and_hms(2024, 3, day, 9, 0, 0).unwrap()
+}
+
+// Started on the first, last message on `last_day`
+fn create_interaction(id: &str, last_day: u32) -> Interaction {
+    Interaction {
+        id: id.to_string(),
+        source: "user".to_string(),
+        interaction_type: InteractionType::Query,
+        content: vec![
+            ConversationItem { role: "user".to_string(), content: format!("Question {}", id), timestamp: at(1), metadata: None },
+            ConversationItem { role: "assistant".to_string(), content: "Answer".to_string(), timestamp: at(last_day), metadata: None },
+        ],
+        needs_work: false,
+        review_stack: vec![],
+        history: vec![],
+        metadata: HashMap::new(),
+        timestamp: at(1),
+    }
+}
+
+fn state_with(interactions: Vec<Interaction>) -> EditorState {
+    let mut state = EditorState::default();
+    for interaction in interactions {
+        state.interactions.insert(interaction.id.clone(), interaction);
+    }
+    state
+}
+
+// Archive everything quiet before the tenth and confirm the write
+fn archived(state: &EditorState) -> EditorState {
+    let (state, effects) = reduce(state, &Action::ArchiveOlderThan { cutoff: at(10) });
+    let ids = match &effects[..] {
+        [Effect::ArchiveInteractions { interactions }] => interactions.iter().map(|i| i.id.clone()).collect(),
+        other => panic!("Expected ArchiveInteractions, got {:?}", other),
+    };
+    reduce(&state, &Action::InteractionsArchived(Ok(ids))).0
+}
+
+#[test]
+fn test_last_activity_is_the_newest_timestamp() {
+    assert_eq!(archive::last_activity(&create_interaction("i1", 5)), at(5));
+}
+
+#[test]
+fn test_only_settled_interactions_are_archivable() {
+    let mut working = create_interaction("working", 2);
+    working.needs_work = true;
+    let mut reviewing = create_interaction("reviewing", 2);
+    reviewing.review_stack = vec!["user".to_string()];
+    let mut state = state_with(vec![
+        create_interaction("old", 2),
+        create_interaction("older", 1),
+        create_interaction("recent", 12),
+        create_interaction("focused", 2),
+        working,
+        reviewing,
+    ]);
+    state.focused_interaction = Some("focused".to_string());
+    let (state, _) = reduce(&state, &Action::ClearError);
+
+    let ids: Vec<&str> = archive::archivable(&state, at(10)).iter().map(|i| i.id.as_str()).collect();
+    assert_eq!(ids, vec!["older", "old"]);
+}
+
+#[test]
+fn test_archived_interactions_leave_stubs() {
+    let state = archived(&state_with(vec![create_interaction("old", 2), create_interaction("recent", 12)]));
+
+    assert_eq!(state.interactions.keys().collect::<Vec<_>>(), vec!["recent"]);
+    assert_eq!(state.archived.get("old"), Some(&InteractionStub {
+        id: "old".to_string(),
+        session_id: "default-session".to_string(),
+        title: "Question old".to_string(),
+        interaction_type: InteractionType::Query,
+        last_activity: at(2),
+    }));
+    assert_eq!(state.archive_cutoff, None);
+}
+
+#[test]
+fn test_nothing_to_archive_writes_nothing() {
+    let (state, effects) = reduce(&state_with(vec![create_interaction("recent", 12)]), &Action::ArchiveOlderThan { cutoff: at(10) });
+    assert!(effects.is_empty());
+    assert_eq!(state.archive_cutoff, None);
+}
+
+#[test]
+fn test_interaction_changed_while_writing_stays_in_memory() {
+    let state = state_with(vec![create_interaction("old", 2)]);
+    let (state, _) = reduce(&state, &Action::ArchiveOlderThan { cutoff: at(10) });
+    let (state, _) = reduce(&state, &Action::InteractionPosted { interaction: create_interaction("old", 11) });
+    let (state, _) = reduce(&state, &Action::InteractionsArchived(Ok(vec!["old".to_string()])));
+
+    assert!(state.interactions.contains_key("old"));
+    assert!(state.archived.is_empty());
+}
+
+#[test]
+fn test_failed_write_keeps_everything() {
+    let state = state_with(vec![create_interaction("old", 2)]);
+    let (state, _) = reduce(&state, &Action::ArchiveOlderThan { cutoff: at(10) });
+    let (state, _) = reduce(&state, &Action::InteractionsArchived(Err("disk full".to_string())));
+
+    assert!(state.interactions.contains_key("old"));
+    assert_eq!(state.error, Some("Couldn't archive interactions: disk full".to_string()));
+}
+
+#[test]
+fn test_focusing_a_stub_reloads_it() {
+    let state = archived(&state_with(vec![create_interaction("old", 2)]));
+    let (state, effects) = reduce(&state, &Action::FocusInteraction { interaction_id: Some("old".to_string()) });
+    assert_eq!(effects, vec![Effect::LoadArchivedInteraction { interaction_id: "old".to_string() }]);
+
+    let (state, _) = reduce(&state, &Action::ArchivedInteractionLoaded {
+        interaction_id: "old".to_string(),
+        result: Ok(create_interaction("old", 2)),
+    });
+    assert_eq!(state.interactions.get("old"), Some(&create_interaction("old", 2)));
+    assert!(state.archived.is_empty());
+}
+
+#[test]
+fn test_reloads_into_the_session_it_was_archived_from() {
+    let state = archived(&state_with(vec![create_interaction("old", 2)]));
+    let (state, _) = reduce(&state, &Action::SwitchSession { session_id: "other".to_string() });
+    assert!(state.archived_interactions().is_empty());
+
+    let (state, _) = reduce(&state, &Action::ArchivedInteractionLoaded {
+        interaction_id: "old".to_string(),
+        result: Ok(create_interaction("old", 2)),
+    });
+    assert!(state.interactions.is_empty());
+    assert!(state.session_interactions["default-session"].contains_key("old"));
+}
+
+#[test]
+fn test_server_update_replaces_the_stub() {
+    let state = archived(&state_with(vec![create_interaction("old", 2)]));
+    let (state, _) = reduce(&state, &Action::InteractionPosted { interaction: create_interaction("old", 11) });
+    assert!(state.archived.is_empty());
+
+    // A reload finishing afterwards doesn't put the older copy back
+    let (state, _) = reduce(&state, &Action::ArchivedInteractionLoaded {
+        interaction_id: "old".to_string(),
+        result: Ok(create_interaction("old", 2)),
+    });
+    assert_eq!(state.interactions["old"], create_interaction("old", 11));
+}
+
+#[test]
+fn test_stubs_survive_a_snapshot() {
+    let mut state = archived(&state_with(vec![create_interaction("old", 2)]));
+    state.focused_interaction = Some("old".to_string());
+
+    let (restored, _) = reduce(&EditorState::default(), &Action::LoadSnapshot { snapshot: state.to_snapshot().unwrap() });
+    assert_eq!(restored.archived, state.archived);
+    // Focus on a stub isn't a dangling id
+    assert_eq!(restored.focused_interaction, Some("old".to_string()));
+    assert_eq!(restored.integrity_report, None);
+}
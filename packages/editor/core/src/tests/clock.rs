@@ -0,0 +1,108 @@
+use crate::clock::{format_relative, normalize, parse_http_date, parse_timestamp, ClockSkew};
+use chrono::{DateTime, Duration, Utc};
+use pretty_assertions::assert_eq;
+
+fn at(value: &str) -> DateTime<Utc> {
+    parse_timestamp(value).unwrap()
+}
+
+#[test]
+fn test_skew_starts_at_zero() {
+    let skew = ClockSkew::new();
+    assert_eq!(skew.offset(), Duration::zero());
+    assert_eq!(skew.to_local(at("2024-01-01T00:00:00Z")), at("2024-01-01T00:00:00Z"));
+}
+
+#[test]
+fn test_skew_measured_from_round_trip_midpoint() {
+    let mut skew = ClockSkew::new();
+    // Server is 30s ahead, the request took 2s
+    skew.observe(
+        at("2024-01-01T00:00:31Z"),
+        at("2024-01-01T00:00:00Z"),
+        at("2024-01-01T00:00:02Z"),
+    );
+
+    assert_eq!(skew.offset(), Duration::seconds(30));
+    assert_eq!(skew.to_local(at("2024-01-01T00:10:00Z")), at("2024-01-01T00:09:30Z"));
+}
+
+#[test]
+fn test_skew_ignores_outliers() {
+    let mut skew = ClockSkew::new();
+    let sent = at("2024-01-01T00:00:00Z");
+    for server in ["2024-01-01T00:00:05Z", "2024-01-01T00:00:05Z", "2024-01-01T00:02:00Z"] {
+        skew.observe(at(server), sent, sent);
+    }
+
+    assert_eq!(skew.offset(), Duration::seconds(5));
+}
+
+#[test]
+fn test_skew_skips_samples_when_local_clock_jumps_back() {
+    let mut skew = ClockSkew::new();
+    skew.observe(
+        at("2024-01-01T00:00:00Z"),
+        at("2024-01-01T00:01:00Z"),
+        at("2024-01-01T00:00:00Z"),
+    );
+
+    assert_eq!(skew.offset(), Duration::zero());
+}
+
+#[test]
+fn test_parse_http_date() {
+    assert_eq!(
+        parse_http_date("Mon, 01 Jan 2024 12:30:00 GMT"),
+        Some(at("2024-01-01T12:30:00Z"))
+    );
+    assert_eq!(parse_http_date("yesterday"), None);
+}
+
+#[test]
+fn test_parse_timestamp_formats() {
+    assert_eq!(parse_timestamp("2024-01-01T01:00:00+01:00"), Some(at("2024-01-01T00:00:00Z")));
+    assert_eq!(parse_timestamp("1704067200000"), Some(at("2024-01-01T00:00:00Z")));
+    assert_eq!(parse_timestamp(""), None);
+}
+
+#[test]
+fn test_normalize_keeps_order_monotonic() {
+    let now = at("2024-01-01T01:00:00Z");
+    let times = [
+        Some(at("2024-01-01T00:00:10Z")),
+        Some(at("2024-01-01T00:00:05Z")),
+        Some(at("2024-01-01T00:00:20Z")),
+    ];
+
+    assert_eq!(
+        normalize(&times, now),
+        vec![at("2024-01-01T00:00:10Z"), at("2024-01-01T00:00:10Z"), at("2024-01-01T00:00:20Z")]
+    );
+}
+
+#[test]
+fn test_normalize_clamps_future_times() {
+    let now = at("2024-01-01T00:00:00Z");
+    let times = [Some(at("2024-01-01T00:00:00Z")), Some(at("2024-01-01T00:05:00Z"))];
+
+    assert_eq!(normalize(&times, now), vec![now, now]);
+}
+
+#[test]
+fn test_normalize_fills_missing_times() {
+    let now = at("2024-01-01T01:00:00Z");
+    let times = [None, Some(at("2024-01-01T00:00:10Z")), None];
+
+    assert_eq!(normalize(&times, now), vec![at("2024-01-01T00:00:10Z"); 3]);
+    assert_eq!(normalize(&[None], now), vec![now]);
+}
+
+#[test]
+fn test_format_relative() {
+    let now = at("2024-01-02T12:00:00Z");
+    assert_eq!(format_relative(at("2024-01-02T12:00:30Z"), now).as_deref(), Some("just now"));
+    assert_eq!(format_relative(at("2024-01-02T11:55:00Z"), now).as_deref(), Some("5m ago"));
+    assert_eq!(format_relative(at("2024-01-02T09:00:00Z"), now).as_deref(), Some("3h ago"));
+    assert_eq!(format_relative(at("2023-12-25T12:00:00Z"), now), None);
+}
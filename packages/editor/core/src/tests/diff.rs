@@ -0,0 +1,93 @@
+use crate::diff::{parse, FileStatus, LineKind};
+use pretty_assertions::assert_eq;
+
+const DIFF: &str = "\
+diff --git a/src/lib.rs b/src/lib.rs
+index 1111111..2222222 100644
+--- a/src/lib.rs
++++ b/src/lib.rs
+@@ -1,3 +1,3 @@
+ pub mod state;
+-pub mod old;
++pub mod new;
+ pub mod types;
+@@ -10,2 +10,3 @@ fn main() {
+ let x = 1;
++--- not a header
+\\ No newline at end of file
+diff --git a/notes.md b/notes.md
+new file mode 100644
+index 0000000..3333333
+--- /dev/null
++++ b/notes.md
+@@ -0,0 +1 @@
++hello
+diff --git a/gone.txt b/gone.txt
+deleted file mode 100644
+index 4444444..0000000
+--- a/gone.txt
++++ /dev/null
+@@ -1 +0,0 @@
+-bye
+";
+
+#[test]
+fn test_parse_files_and_hunks() {
+    let files = parse(DIFF);
+
+    assert_eq!(files.len(), 3);
+    assert_eq!(files[0].path, "src/lib.rs");
+    assert_eq!(files[0].status, FileStatus::Modified);
+    assert_eq!(files[0].hunks.len(), 2);
+    assert_eq!(files[0].hunks[1].header, "@@ -10,2 +10,3 @@ fn main() {");
+    assert_eq!(files[0].added(), 2);
+    assert_eq!(files[0].removed(), 1);
+}
+
+#[test]
+fn test_content_lines_that_look_like_headers() {
+    let files = parse(DIFF);
+    let last = files[0].hunks[1].lines.last().unwrap();
+
+    assert_eq!(last.kind, LineKind::Added);
+    assert_eq!(last.text, "--- not a header");
+}
+
+#[test]
+fn test_added_and_deleted_files() {
+    let files = parse(DIFF);
+
+    assert_eq!(files[1].path, "notes.md");
+    assert_eq!(files[1].status, FileStatus::Added);
+    assert_eq!(files[1].added(), 1);
+
+    assert_eq!(files[2].path, "gone.txt");
+    assert_eq!(files[2].status, FileStatus::Deleted);
+    assert_eq!(files[2].removed(), 1);
+}
+
+#[test]
+fn test_renames_and_binaries() {
+    let files = parse(
+        "\
+diff --git a/old name.txt b/new name.txt
+similarity index 100%
+rename from old name.txt
+rename to new name.txt
+diff --git a/logo.png b/logo.png
+index 5555555..6666666 100644
+Binary files a/logo.png and b/logo.png differ
+",
+    );
+
+    assert_eq!(files[0].path, "new name.txt");
+    assert_eq!(files[0].old_path.as_deref(), Some("old name.txt"));
+    assert_eq!(files[0].status, FileStatus::Renamed);
+    assert!(files[1].binary);
+    assert!(files[1].hunks.is_empty());
+}
+
+#[test]
+fn test_empty_diff() {
+    assert!(parse("").is_empty());
+}
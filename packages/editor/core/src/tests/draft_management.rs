@@ -133,4 +133,97 @@ fn test_clear_draft() {
     assert_eq!(new_state.draft.review_stack, vec!["user"]);
     assert!(new_state.draft.metadata.is_empty());
     assert!(effects.is_empty());
-}
\ No newline at end of file
+}
+
+fn focus(state: &EditorState, interaction_id: Option<&str>) -> EditorState {
+    reduce(state, &Action::FocusInteraction { interaction_id: interaction_id.map(str::to_string) }).0
+}
+
+fn typed(state: &EditorState, content: &str) -> EditorState {
+    reduce(state, &Action::UpdateDraftContent { content: content.to_string() }).0
+}
+
+#[test]
+fn test_focus_keeps_a_draft_per_interaction() {
+    let state = typed(&EditorState::default(), "A new question");
+    let state = focus(&state, Some("i1"));
+    assert_eq!(state.draft.content, "");
+
+    let state = typed(&state, "Follow up on i1");
+    let state = focus(&state, Some("i2"));
+    assert_eq!(state.draft.content, "");
+
+    let state = focus(&state, Some("i1"));
+    assert_eq!(state.draft.content, "Follow up on i1");
+    assert_eq!(state.focused_interaction.as_deref(), Some("i1"));
+
+    let state = focus(&state, None);
+    assert_eq!(state.draft.content, "A new question");
+    assert_eq!(state.drafts.len(), 1);
+}
+
+#[test]
+fn test_focus_keeps_draft_settings() {
+    let state = typed(&EditorState::default(), "Ship it");
+    let (state, _) = reduce(&state, &Action::SetDraftType { interaction_type: InteractionType::Action });
+    let state = focus(&state, Some("i1"));
+    let state = focus(&state, None);
+
+    assert_eq!(state.draft.interaction_type, InteractionType::Action);
+}
+
+#[test]
+fn test_refocusing_the_same_interaction_keeps_the_draft() {
+    let state = focus(&EditorState::default(), Some("i1"));
+    let state = typed(&state, "Still typing");
+    let state = focus(&state, Some("i1"));
+
+    assert_eq!(state.draft.content, "Still typing");
+    assert!(state.drafts.is_empty());
+}
+
+#[test]
+fn test_untouched_drafts_are_not_kept() {
+    let state = focus(&EditorState::default(), Some("i1"));
+    let state = focus(&state, Some("i2"));
+
+    assert!(state.drafts.is_empty());
+}
+
+#[test]
+fn test_discarded_drafts_are_gone() {
+    let state = focus(&EditorState::default(), Some("i1"));
+    let state = typed(&state, "About i1");
+    let state = focus(&state, Some("i2"));
+    let state = typed(&state, "About i2");
+
+    let (state, _) = reduce(&state, &Action::DiscardDraft { interaction_id: "i1".to_string() });
+    assert!(state.drafts.is_empty());
+    assert_eq!(state.draft.content, "About i2");
+
+    let (state, _) = reduce(&state, &Action::DiscardDraft { interaction_id: "i2".to_string() });
+    assert_eq!(state.draft.content, "");
+}
+
+#[test]
+fn test_drafts_follow_assigned_ids() {
+    let state = focus(&EditorState::default(), Some("local-1"));
+    let state = typed(&state, "Still on it");
+    let (state, _) = reduce(&state, &Action::InteractionIdAssigned {
+        temp_id: "local-1".to_string(),
+        interaction_id: "i1".to_string(),
+    });
+    assert_eq!(state.focused_interaction.as_deref(), Some("i1"));
+    assert_eq!(state.draft.content, "Still on it");
+
+    let state = focus(&state, Some("local-2"));
+    let state = typed(&state, "Second thoughts");
+    let state = focus(&state, Some("i1"));
+    let (state, _) = reduce(&state, &Action::InteractionIdAssigned {
+        temp_id: "local-2".to_string(),
+        interaction_id: "i2".to_string(),
+    });
+    let state = focus(&state, Some("i2"));
+
+    assert_eq!(state.draft.content, "Second thoughts");
+}
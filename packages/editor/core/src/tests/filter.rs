@@ -0,0 +1,156 @@
+use crate::{
+    state::EditorState,
+    actions::Action,
+    reducer::reduce,
+    filter::{InteractionFilter, SortOrder},
+    types::{Event, Interaction, InteractionType}
+};
+use pretty_assertions::assert_eq;
+use chrono::{DateTime, Duration, TimeZone, Utc};
+use serde_json::json;
+use std::collections::HashMap;
+
+fn at(hour: u32) -> DateTime<Utc> {
+    Utc.with_ymd_and_hms(2024, 1, 1, hour, 0, 0).unwrap()
+}
+
+fn create_interaction(id: &str, interaction_type: InteractionType, hour: u32) -> Interaction {
+    Interaction {
+        id: id.to_string(),
+        source: "user".to_string(),
+        interaction_type,
+        content: vec![],
+        needs_work: false,
+        review_stack: vec![],
+        history: vec![],
+        metadata: HashMap::new(),
+        timestamp: at(hour),
+    }
+}
+
+// A query at 9, a tagged action at 10 that needs work, an observation at 11
+fn populated_state() -> EditorState {
+    let mut action = create_interaction("action", InteractionType::Action, 10);
+    action.needs_work = true;
+    action.metadata.insert("tags".to_string(), json!(["rust", "async"]));
+
+    let mut state = EditorState::default();
+    for interaction in [
+        create_interaction("query", InteractionType::Query, 9),
+        action,
+        create_interaction("observation", InteractionType::Observation, 11),
+    ] {
+        state = reduce(&state, &Action::InteractionPosted { interaction }).0;
+    }
+    state
+}
+
+fn ids(interactions: Vec<&Interaction>) -> Vec<&str> {
+    interactions.into_iter().map(|i| i.id.as_str()).collect()
+}
+
+#[test]
+fn test_no_filter_shows_everything_newest_first() {
+    let state = populated_state();
+    assert!(state.filter.is_empty());
+    assert_eq!(ids(state.visible_interactions()), vec!["observation", "action", "query"]);
+}
+
+#[test]
+fn test_filter_by_type() {
+    let filter = InteractionFilter {
+        types: vec![InteractionType::Query, InteractionType::Observation],
+        ..Default::default()
+    };
+    let (state, effects) = reduce(&populated_state(), &Action::SetFilter { filter });
+
+    assert_eq!(ids(state.visible_interactions()), vec!["observation", "query"]);
+    assert!(effects.is_empty());
+}
+
+#[test]
+fn test_filter_needs_every_tag() {
+    let state = populated_state();
+    let tagged = |tags: &[&str]| InteractionFilter {
+        tags: tags.iter().map(|t| t.to_string()).collect(),
+        ..Default::default()
+    };
+
+    assert_eq!(ids(state.query(&tagged(&["rust"]), SortOrder::NewestFirst)), vec!["action"]);
+    assert_eq!(ids(state.query(&tagged(&["rust", "async"]), SortOrder::NewestFirst)), vec!["action"]);
+    assert!(state.query(&tagged(&["rust", "wasm"]), SortOrder::NewestFirst).is_empty());
+}
+
+#[test]
+fn test_filter_by_date_range() {
+    let filter = InteractionFilter {
+        since: Some(at(10)),
+        until: Some(at(11)),
+        ..Default::default()
+    };
+    let state = populated_state();
+
+    assert_eq!(ids(state.query(&filter, SortOrder::NewestFirst)), vec!["action"]);
+}
+
+#[test]
+fn test_filter_by_needs_work() {
+    let state = populated_state();
+    let needs_work = |needs_work| InteractionFilter { needs_work: Some(needs_work), ..Default::default() };
+
+    assert_eq!(ids(state.query(&needs_work(true), SortOrder::NewestFirst)), vec!["action"]);
+    assert_eq!(ids(state.query(&needs_work(false), SortOrder::OldestFirst)), vec!["query", "observation"]);
+}
+
+#[test]
+fn test_sort_by_recent_activity() {
+    let mut state = populated_state();
+    // The oldest interaction had an agent reply after everything else was posted
+    state.interactions.get_mut("query").unwrap().history.push(Event {
+        agent_id: "wake".to_string(),
+        action: "completed".to_string(),
+        content: json!({}),
+        metadata: None,
+        timestamp: at(11) + Duration::minutes(5),
+    });
+
+    let (state, _) = reduce(&state, &Action::SetSortOrder { sort: SortOrder::RecentActivity });
+    assert_eq!(ids(state.visible_interactions()), vec!["query", "observation", "action"]);
+}
+
+#[test]
+fn test_ties_are_ordered_by_id() {
+    let mut state = EditorState::default();
+    for id in ["b", "a", "c"] {
+        let interaction = create_interaction(id, InteractionType::Query, 9);
+        state = reduce(&state, &Action::InteractionPosted { interaction }).0;
+    }
+
+    assert_eq!(ids(state.visible_interactions()), vec!["a", "b", "c"]);
+}
+
+#[test]
+fn test_clear_filter() {
+    let filter = InteractionFilter { needs_work: Some(true), ..Default::default() };
+    let (state, _) = reduce(&populated_state(), &Action::SetFilter { filter });
+    let (state, _) = reduce(&state, &Action::ClearFilter);
+
+    assert!(state.filter.is_empty());
+    assert_eq!(state.visible_interactions().len(), 3);
+}
+
+#[test]
+fn test_matches_type_without_an_interaction() {
+    let filter = InteractionFilter { types: vec![InteractionType::Action], ..Default::default() };
+
+    assert!(filter.matches_type(&InteractionType::Action));
+    assert!(!filter.matches_type(&InteractionType::Query));
+    assert!(InteractionFilter::default().matches_type(&InteractionType::Reflection));
+}
+
+#[test]
+fn test_interaction_type_from_server_name() {
+    assert_eq!(InteractionType::from_name("observation"), Some(InteractionType::Observation));
+    assert_eq!(InteractionType::from_name("Query"), Some(InteractionType::Query));
+    assert_eq!(InteractionType::from_name("message"), None);
+}
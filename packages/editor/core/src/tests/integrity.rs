@@ -0,0 +1,188 @@
+use crate::{
+    state::EditorState,
+    actions::Action,
+    reducer::reduce,
+    integrity,
+    types::{Interaction, InteractionDraft, InteractionType}
+};
+use pretty_assertions::assert_eq;
+use chrono::Utc;
+use std::collections::HashMap;
+
+fn create_interaction(id: &str) -> Interaction {
+    Interaction {
+        id: id.to_string(),
+        source: "user".to_string(),
+        interaction_type: InteractionType::Query,
+        content: vec![],
+        needs_work: false,
+        review_stack: vec![],
+        history: vec![],
+        metadata: HashMap::new(),
+        timestamp: Utc::now(),
+    }
+}
+
+fn draft(content: &str) -> InteractionDraft {
+    InteractionDraft {
+        content: content.to_string(),
+        ..Default::default()
+    }
+}
+
+fn with_interactions(ids: &[&str]) -> EditorState {
+    let mut state = EditorState::default();
+    for id in ids {
+        state.interactions.insert(id.to_string(), create_interaction(id));
+    }
+    state
+}
+
+fn load(state: &EditorState) -> EditorState {
+    let (restored, _) = reduce(&EditorState::default(), &Action::LoadSnapshot {
+        snapshot: state.to_snapshot().unwrap()
+    });
+    restored
+}
+
+#[test]
+fn test_clean_state_has_no_report() {
+    let mut state = with_interactions(&["i1"]);
+    state.drafts.insert("i1".to_string(), draft("Follow up"));
+    state.focused_interaction = Some("i1".to_string());
+
+    let restored = load(&state);
+
+    assert_eq!(restored.integrity_report, None);
+    assert_eq!(restored, state);
+}
+
+#[test]
+fn test_tampered_snapshot_is_rejected() {
+    let state = with_interactions(&["i1"]);
+    let snapshot = state.to_snapshot().unwrap().replace("i1", "i2");
+
+    let (new_state, effects) = reduce(&state, &Action::LoadSnapshot { snapshot });
+
+    assert!(effects.is_empty());
+    assert_eq!(new_state.interactions, state.interactions);
+    assert!(new_state.error.unwrap().contains("checksum mismatch"));
+}
+
+#[test]
+fn test_snapshot_without_checksum_still_loads() {
+    let state = with_interactions(&["i1"]);
+    let legacy = serde_json::to_string(&state).unwrap();
+
+    let restored = EditorState::from_snapshot(&legacy).unwrap();
+
+    assert_eq!(restored, state);
+}
+
+#[test]
+fn test_orphaned_draft_is_quarantined() {
+    let mut state = with_interactions(&["i1"]);
+    state.drafts.insert("gone".to_string(), draft("Half a thought"));
+    state.drafts.insert("i1".to_string(), draft("Follow up"));
+
+    let restored = load(&state);
+
+    assert!(!restored.drafts.contains_key("gone"));
+    assert!(restored.drafts.contains_key("i1"));
+    assert_eq!(restored.quarantine.drafts["gone"].content, "Half a thought");
+    let report = restored.integrity_report.unwrap();
+    assert_eq!(report.quarantined.drafts.len(), 1);
+}
+
+#[test]
+fn test_new_draft_is_never_orphaned() {
+    let mut state = EditorState::default();
+    state.drafts.insert(crate::reducer::NEW_DRAFT_KEY.to_string(), draft("Start"));
+
+    let report = integrity::check(&mut state);
+
+    assert!(report.is_clean());
+    assert_eq!(state.drafts.len(), 1);
+}
+
+#[test]
+fn test_parked_sessions_count_as_known() {
+    let mut state = EditorState::default();
+    let parked = with_interactions(&["p1"]).interactions;
+    state.session_interactions.insert("s2".to_string(), parked);
+    state.drafts.insert("p1".to_string(), draft("Later"));
+    state.focused_interaction = Some("p1".to_string());
+
+    let report = integrity::check(&mut state);
+
+    assert!(report.is_clean());
+    assert_eq!(state.focused_interaction, Some("p1".to_string()));
+}
+
+#[test]
+fn test_dangling_focus_keeps_draft_as_new() {
+    let mut state = EditorState {
+        draft: draft("Keep me"),
+        focused_interaction: Some("gone".to_string()),
+        ..Default::default()
+    };
+
+    let report = integrity::check(&mut state);
+
+    assert_eq!(state.focused_interaction, None);
+    assert_eq!(state.draft.content, "Keep me");
+    assert_eq!(report.repaired.len(), 1);
+}
+
+#[test]
+fn test_dangling_ids_are_dropped() {
+    let mut state = with_interactions(&["i1", "temp-1"]);
+    state.provisional = vec!["temp-1".to_string(), "temp-2".to_string()];
+    state.pending_reviews = vec!["gone".to_string(), "i1".to_string()];
+
+    let report = integrity::check(&mut state);
+
+    assert_eq!(state.provisional, vec!["temp-1".to_string()]);
+    assert_eq!(state.pending_reviews, vec!["i1".to_string()]);
+    assert_eq!(report.repaired.len(), 2);
+}
+
+#[test]
+fn test_misplaced_interaction_is_rekeyed() {
+    let mut state = EditorState::default();
+    state.interactions.insert("wrong".to_string(), create_interaction("i1"));
+
+    let report = integrity::check(&mut state);
+
+    assert!(state.interactions.contains_key("i1"));
+    assert!(!state.interactions.contains_key("wrong"));
+    assert_eq!(report.repaired.len(), 1);
+    assert!(report.quarantined.is_empty());
+}
+
+#[test]
+fn test_conflicting_interaction_is_quarantined() {
+    let mut state = with_interactions(&["i1"]);
+    let mut stray = create_interaction("i1");
+    stray.source = "assistant".to_string();
+    state.interactions.insert("wrong".to_string(), stray);
+
+    let report = integrity::check(&mut state);
+
+    assert_eq!(state.interactions.len(), 1);
+    assert_eq!(state.interactions["i1"].source, "user");
+    assert_eq!(state.quarantine.interactions.len(), 1);
+    assert_eq!(report.quarantined.interactions[0].source, "assistant");
+}
+
+#[test]
+fn test_quarantine_survives_the_next_save() {
+    let mut state = with_interactions(&["i1"]);
+    state.drafts.insert("gone".to_string(), draft("Half a thought"));
+    let restored = load(&state);
+
+    let reloaded = load(&restored);
+
+    assert_eq!(reloaded.quarantine.drafts["gone"].content, "Half a thought");
+    assert_eq!(reloaded.integrity_report, None);
+}
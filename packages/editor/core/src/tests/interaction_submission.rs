@@ -2,13 +2,22 @@ use crate::{
     state::EditorState, 
     actions::Action, 
     effects::Effect,
-    reducer::reduce, 
+    reducer::{reduce, NEW_DRAFT_KEY, TEMP_ID_KEY}, 
     types::{Interaction, InteractionType}
 };
 use pretty_assertions::assert_eq;
-use chrono::Utc;
+use chrono::{DateTime, TimeZone, Utc};
 use std::collections::HashMap;
 
+// When the tests submit, fixed so replays can be compared
+fn submitted_at() -> DateTime<Utc> {
+    Utc.with_ymd_and_hms(2024, 3, 1, 9, 0, 0).unwrap()
+}
+
+fn submit() -> Action {
+    Action::SubmitInteraction { at: submitted_at() }
+}
+
 fn create_test_interaction() -> Interaction {
     Interaction {
         id: "test-123".to_string(),
@@ -30,11 +39,11 @@ fn test_submit_interaction_when_connected() {
     state.draft.content = "Test interaction".to_string();
     state.draft.interaction_type = InteractionType::Query;
     
-    let action = Action::SubmitInteraction;
+    let action = submit();
     
     let (new_state, effects) = reduce(&state, &action);
     
-    // State should not change yet (waiting for response)
+    // Draft stays until the server confirms
     assert_eq!(new_state.draft.content, "Test interaction");
     
     // Should create submit effect
@@ -55,7 +64,7 @@ fn test_submit_interaction_when_disconnected() {
     state.connected = false;
     state.draft.content = "Test interaction".to_string();
     
-    let action = Action::SubmitInteraction;
+    let action = submit();
     
     let (new_state, effects) = reduce(&state, &action);
     
@@ -72,7 +81,7 @@ fn test_submit_empty_interaction() {
     state.connected = true;
     state.draft.content = "".to_string();
     
-    let action = Action::SubmitInteraction;
+    let action = submit();
     
     let (new_state, effects) = reduce(&state, &action);
     
@@ -83,11 +92,10 @@ fn test_submit_empty_interaction() {
 
 #[test]
 fn test_interaction_submitted_success() {
-    let mut state = EditorState::default();
-    state.draft.content = "Test content".to_string();
+    let state = submitted("Test content");
     
     let interaction = create_test_interaction();
-    let action = Action::InteractionSubmitted(Ok(interaction.clone()));
+    let action = Action::InteractionSubmitted { temp_id: "temp-0".to_string(), result: Ok(interaction.clone()) };
     
     let (new_state, effects) = reduce(&state, &action);
     
@@ -107,7 +115,7 @@ fn test_interaction_submitted_success() {
 #[test]
 fn test_interaction_submitted_error() {
     let state = EditorState::default();
-    let action = Action::InteractionSubmitted(Err("Network error".to_string()));
+    let action = Action::InteractionSubmitted { temp_id: "temp-0".to_string(), result: Err("Network error".to_string()) };
     
     let (new_state, effects) = reduce(&state, &action);
     
@@ -128,11 +136,154 @@ fn test_pending_reviews_updated_after_interaction_added() {
     interaction.needs_work = false;
     interaction.review_stack = vec!["user".to_string()];
     
-    let action = Action::InteractionSubmitted(Ok(interaction));
+    let action = Action::InteractionSubmitted { temp_id: "temp-0".to_string(), result: Ok(interaction) };
     
     let (new_state, effects) = reduce(&state, &action);
     
     // Should update pending reviews
     assert_eq!(new_state.pending_reviews, vec!["test-123"]);
     assert!(effects.is_empty());
-}
\ No newline at end of file
+}
+
+fn submitted(content: &str) -> EditorState {
+    let mut state = EditorState { connected: true, ..Default::default() };
+    state.draft.content = content.to_string();
+    reduce(&state, &submit()).0
+}
+
+#[test]
+fn test_submit_shows_provisional_interaction() {
+    let mut state = EditorState { connected: true, ..Default::default() };
+    state.draft.content = "Test interaction".to_string();
+
+    let (new_state, effects) = reduce(&state, &submit());
+
+    assert_eq!(new_state.provisional, vec!["temp-0"]);
+    let provisional = &new_state.interactions["temp-0"];
+    assert_eq!(provisional.content[0].content, "Test interaction");
+    assert_eq!(provisional.timestamp, submitted_at());
+    assert!(provisional.needs_work);
+    // Replaying the submission shows the same provisional interaction
+    assert_eq!(reduce(&state, &submit()).0, new_state);
+    match &effects[0] {
+        Effect::SubmitInteraction { temp_id, metadata, .. } => {
+            assert_eq!(temp_id, "temp-0");
+            assert_eq!(metadata.get(TEMP_ID_KEY), Some(&serde_json::json!("temp-0")));
+        }
+        _ => panic!("Expected SubmitInteraction effect"),
+    }
+}
+
+#[test]
+fn test_confirmed_interaction_replaces_provisional() {
+    let state = submitted("Test interaction");
+
+    let (new_state, _) = reduce(&state, &Action::InteractionSubmitted { temp_id: "temp-0".to_string(), result: Ok(create_test_interaction()) });
+
+    assert!(new_state.provisional.is_empty());
+    assert!(!new_state.interactions.contains_key("temp-0"));
+    assert!(new_state.interactions.contains_key("test-123"));
+}
+
+#[test]
+fn test_answers_land_on_their_own_provisional() {
+    let mut state = submitted("First");
+    state.draft.content = "Second".to_string();
+    let (state, _) = reduce(&state, &submit());
+
+    // The second answers first
+    let (new_state, _) = reduce(&state, &Action::InteractionSubmitted {
+        temp_id: "temp-1".to_string(),
+        result: Ok(create_test_interaction()),
+    });
+
+    assert_eq!(new_state.provisional, vec!["temp-0"]);
+    assert!(new_state.interactions.contains_key("temp-0"));
+    assert!(!new_state.interactions.contains_key("temp-1"));
+}
+
+#[test]
+fn test_failure_marks_only_its_own_submission() {
+    let mut state = submitted("First");
+    state.draft.content = "Second".to_string();
+    let (state, _) = reduce(&state, &submit());
+
+    let (new_state, _) = reduce(&state, &Action::InteractionSubmitted {
+        temp_id: "temp-1".to_string(),
+        result: Err("Network error".to_string()),
+    });
+
+    assert_eq!(new_state.provisional, vec!["temp-0"]);
+    assert_eq!(new_state.interactions["temp-0"].submit_error(), None);
+    assert!(new_state.interactions["temp-0"].needs_work);
+    assert_eq!(new_state.interactions["temp-1"].submit_error(), Some("Network error"));
+    assert!(!new_state.interactions["temp-1"].needs_work);
+}
+
+#[test]
+fn test_answer_after_switching_session_lands_in_the_submitting_one() {
+    let state = submitted("Test interaction");
+    let (state, _) = reduce(&state, &Action::SwitchSession { session_id: "other".to_string() });
+    assert!(state.provisional.is_empty());
+
+    let (new_state, _) = reduce(&state, &Action::InteractionSubmitted {
+        temp_id: "temp-0".to_string(),
+        result: Ok(create_test_interaction()),
+    });
+
+    assert!(new_state.interactions.is_empty());
+    let parked = &new_state.session_interactions["default-session"];
+    assert!(parked.contains_key("test-123"));
+    assert!(!parked.contains_key("temp-0"));
+    assert!(new_state.session_provisional["default-session"].is_empty());
+}
+
+#[test]
+fn test_failed_submission_is_marked_not_removed() {
+    let state = submitted("Test interaction");
+
+    let (new_state, effects) = reduce(&state, &Action::InteractionSubmitted { temp_id: "temp-0".to_string(), result: Err("Network error".to_string()) });
+
+    assert!(new_state.provisional.is_empty());
+    assert_eq!(new_state.interactions["temp-0"].submit_error(), Some("Network error"));
+    assert_eq!(new_state.error, Some("Network error".to_string()));
+    assert_eq!(new_state.draft.content, "Test interaction");
+    assert!(effects.is_empty());
+}
+
+#[test]
+fn test_submission_follows_up_on_focused_interaction() {
+    let mut state = EditorState { connected: true, ..Default::default() };
+    state.interactions.insert("test-123".to_string(), create_test_interaction());
+    let (mut state, _) = reduce(&state, &Action::FocusInteraction {
+        interaction_id: Some("test-123".to_string())
+    });
+    state.draft.content = "And another thing".to_string();
+
+    let (_, effects) = reduce(&state, &submit());
+
+    match &effects[0] {
+        Effect::SubmitInteraction { interaction_id, .. } => {
+            assert_eq!(interaction_id.as_deref(), Some("test-123"));
+        }
+        _ => panic!("Expected SubmitInteraction effect"),
+    }
+}
+
+#[test]
+fn test_success_clears_the_submitted_draft_not_the_focused_one() {
+    let mut state = submitted("New question");
+    state.interactions.insert("test-123".to_string(), create_test_interaction());
+    let (mut state, _) = reduce(&state, &Action::FocusInteraction {
+        interaction_id: Some("test-123".to_string())
+    });
+    state.draft.content = "Half a follow-up".to_string();
+
+    let (new_state, _) = reduce(&state, &Action::InteractionSubmitted {
+        temp_id: "temp-0".to_string(),
+        result: Ok(create_test_interaction()),
+    });
+
+    assert_eq!(new_state.draft.content, "Half a follow-up");
+    assert!(!new_state.drafts.contains_key(NEW_DRAFT_KEY));
+}
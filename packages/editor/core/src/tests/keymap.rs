@@ -0,0 +1,64 @@
+use crate::{
+    state::EditorState,
+    actions::Action,
+    keymap::KeyCommand,
+    reducer::reduce,
+    types::{Interaction, InteractionType}
+};
+use pretty_assertions::assert_eq;
+use chrono::{TimeZone, Utc};
+use std::collections::HashMap;
+
+fn interaction(id: &str, minute: u32) -> Interaction {
+    Interaction {
+        id: id.to_string(),
+        source: "user".to_string(),
+        interaction_type: InteractionType::Query,
+        content: vec![],
+        needs_work: true,
+        review_stack: vec![],
+        history: vec![],
+        metadata: HashMap::new(),
+        timestamp: Utc.with_ymd_and_hms(2024, 3, 1, 10, minute, 0).unwrap(),
+    }
+}
+
+#[test]
+fn test_parse_command_names() {
+    assert_eq!(KeyCommand::parse("new_conversation"), Some(KeyCommand::NewConversation));
+    assert_eq!(KeyCommand::parse("switch_conversation_3"), Some(KeyCommand::SwitchConversation(3)));
+    assert_eq!(KeyCommand::parse("switch_conversation_0"), None);
+    assert_eq!(KeyCommand::parse("launch_rockets"), None);
+}
+
+#[test]
+fn test_switching_focuses_by_list_position() {
+    let state = EditorState::default();
+    let (state, _) = reduce(&state, &Action::InteractionPosted { interaction: interaction("older", 0) });
+    let (state, _) = reduce(&state, &Action::InteractionPosted { interaction: interaction("newer", 5) });
+    let ids: Vec<&str> = state.visible_interactions().iter().map(|i| i.id.as_str()).collect();
+
+    let action = KeyCommand::SwitchConversation(2).action(&state, Utc::now());
+
+    assert_eq!(action, Some(Action::FocusInteraction { interaction_id: Some(ids[1].to_string()) }));
+    assert_eq!(KeyCommand::SwitchConversation(3).action(&state, Utc::now()), None);
+}
+
+#[test]
+fn test_send_needs_a_draft() {
+    let at = Utc.with_ymd_and_hms(2024, 3, 1, 11, 0, 0).unwrap();
+    let state = EditorState::default();
+    assert_eq!(KeyCommand::Send.action(&state, at), None);
+
+    let (state, _) = reduce(&state, &Action::UpdateDraftContent { content: "Ship it".to_string() });
+    assert_eq!(KeyCommand::Send.action(&state, at), Some(Action::SubmitInteraction { at }));
+}
+
+#[test]
+fn test_view_commands_dispatch_nothing() {
+    let state = EditorState::default();
+
+    for command in [KeyCommand::CommandPalette, KeyCommand::Interrupt, KeyCommand::FocusMode] {
+        assert_eq!(command.action(&state, Utc::now()), None);
+    }
+}
@@ -0,0 +1,70 @@
+use crate::{
+    state::EditorState,
+    actions::Action,
+    reducer::reduce,
+    middleware::{Pipeline, Transition},
+};
+use pretty_assertions::assert_eq;
+use std::sync::{Arc, Mutex};
+
+fn connect() -> Action {
+    Action::Connect {
+        server_url: "http://localhost:3456".to_string()
+    }
+}
+
+#[test]
+fn test_dispatch_matches_reduce() {
+    let mut pipeline = Pipeline::new();
+    pipeline.register(|_: &Transition<'_>| {});
+    let state = EditorState::default();
+
+    assert_eq!(pipeline.dispatch(&state, &connect()), reduce(&state, &connect()));
+}
+
+#[test]
+fn test_middleware_sees_every_transition_in_order() {
+    let seen = Arc::new(Mutex::new(Vec::new()));
+    let mut pipeline = Pipeline::new();
+    for name in ["first", "second"] {
+        let seen = seen.clone();
+        pipeline.register(move |transition: &Transition<'_>| {
+            seen.lock().unwrap().push((name, transition.action.clone(), transition.effects.to_vec()));
+        });
+    }
+
+    let state = EditorState::default();
+    let (state, effects) = pipeline.dispatch(&state, &connect());
+    pipeline.dispatch(&state, &Action::Connected);
+
+    let seen = seen.lock().unwrap();
+    assert_eq!(seen.len(), 4);
+    assert_eq!(seen[0], ("first", connect(), effects.clone()));
+    assert_eq!(seen[1], ("second", connect(), effects));
+    assert_eq!(seen[3].1, Action::Connected);
+}
+
+#[test]
+fn test_transition_carries_both_states() {
+    let changes = Arc::new(Mutex::new(Vec::new()));
+    let mut pipeline = Pipeline::new();
+    let recorded = changes.clone();
+    pipeline.register(move |transition: &Transition<'_>| {
+        recorded.lock().unwrap().push((transition.before.clone(), transition.unchanged()));
+    });
+
+    let before = EditorState::default();
+    let (after, _) = pipeline.dispatch(&before, &connect());
+    pipeline.dispatch(&after, &Action::UpdateDraftContent { content: after.draft.content.clone() });
+
+    let changes = changes.lock().unwrap();
+    assert_eq!(changes[0], (before, false));
+    assert_eq!(changes[1], (after, true));
+}
+
+#[test]
+fn test_empty_pipeline() {
+    let pipeline = Pipeline::new();
+    assert!(pipeline.is_empty());
+    assert_eq!(format!("{:?}", pipeline), "Pipeline { middleware: 0 }");
+}
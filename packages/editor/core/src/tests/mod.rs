@@ -11,4 +11,48 @@ mod state_tests;
 #[cfg(test)]
 mod reducer_tests;
 #[cfg(test)]
-mod integration_tests;
\ No newline at end of file
+mod integration_tests;
+#[cfg(test)]
+mod tokenizer;
+#[cfg(test)]
+mod clock;
+#[cfg(test)]
+mod diff;
+#[cfg(test)]
+mod theme;
+#[cfg(test)]
+mod policy;
+#[cfg(test)]
+mod sessions;
+#[cfg(test)]
+mod stats;
+#[cfg(test)]
+mod middleware;
+#[cfg(test)]
+mod action_log;
+#[cfg(test)]
+mod snapshot;
+#[cfg(test)]
+mod filter;
+#[cfg(test)]
+mod tags;
+#[cfg(test)]
+mod wire_format;
+#[cfg(test)]
+mod integrity;
+#[cfg(test)]
+mod tool_permissions;
+#[cfg(test)]
+mod report;
+#[cfg(test)]
+mod plugin;
+#[cfg(test)]
+mod archive;
+#[cfg(test)]
+mod worktrees;
+#[cfg(test)]
+mod notifications;
+#[cfg(test)]
+mod permission_deliveries;
+#[cfg(test)]
+mod keymap;
\ No newline at end of file
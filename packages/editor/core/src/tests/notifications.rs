@@ -0,0 +1,48 @@
+use crate::{
+    state::EditorState,
+    actions::Action,
+    reducer::reduce,
+    types::Notification
+};
+use pretty_assertions::assert_eq;
+use chrono::{Duration, Utc};
+
+#[test]
+fn test_show_notification_keeps_when_it_was_shown() {
+    let at = Utc::now();
+    let action = Action::ShowNotification { notification: Notification::Info("hi".to_string()), at };
+
+    let (new_state, effects) = reduce(&EditorState::default(), &action);
+
+    assert!(effects.is_empty());
+    assert_eq!(new_state.notification, Some((Notification::Info("hi".to_string()), at)));
+}
+
+#[test]
+fn test_new_notification_replaces_the_last() {
+    let at = Utc::now();
+    let (state, _) = reduce(&EditorState::default(), &Action::ShowNotification {
+        notification: Notification::Info("first".to_string()),
+        at,
+    });
+    let later = at + Duration::seconds(3);
+
+    let (new_state, _) = reduce(&state, &Action::ShowNotification {
+        notification: Notification::Error("second".to_string()),
+        at: later,
+    });
+
+    assert_eq!(new_state.notification, Some((Notification::Error("second".to_string()), later)));
+}
+
+#[test]
+fn test_clear_notification() {
+    let (state, _) = reduce(&EditorState::default(), &Action::ShowNotification {
+        notification: Notification::Success("done".to_string()),
+        at: Utc::now(),
+    });
+
+    let (new_state, _) = reduce(&state, &Action::ClearNotification);
+
+    assert_eq!(new_state.notification, None);
+}
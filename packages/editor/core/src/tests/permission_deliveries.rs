@@ -0,0 +1,113 @@
+use crate::{
+    state::{EditorState, PermissionDelivery},
+    actions::Action,
+    reducer::reduce,
+    types::MessageError
+};
+use pretty_assertions::assert_eq;
+use chrono::{Duration, Utc};
+use std::collections::HashSet;
+
+fn keys(keys: &[&str]) -> HashSet<String> {
+    keys.iter().map(|key| key.to_string()).collect()
+}
+
+fn waiting_on(interactions: &[(&str, &str)]) -> HashSet<(String, String)> {
+    interactions.iter().map(|(server, id)| (server.to_string(), id.to_string())).collect()
+}
+
+fn queued(interaction_id: &str) -> EditorState {
+    let action = Action::PermissionResponseQueued {
+        interaction_id: interaction_id.to_string(),
+        server: "local".to_string(),
+        approved: true,
+    };
+    reduce(&EditorState::default(), &action).0
+}
+
+fn failed(state: &EditorState, interaction_id: &str) -> EditorState {
+    let action = Action::PermissionResponseFailed {
+        interaction_id: interaction_id.to_string(),
+        error: MessageError::server("connection refused".to_string()),
+    };
+    reduce(state, &action).0
+}
+
+#[test]
+fn test_delivery_counts_attempts_until_delivered() {
+    let state = queued("i1");
+    let (state, _) = reduce(&state, &Action::PermissionResponseRetrying { interaction_id: "i1".to_string() });
+
+    assert_eq!(state.permission_deliveries.get("i1"), Some(&PermissionDelivery {
+        server: "local".to_string(),
+        approved: true,
+        attempts: 2,
+        failed: None,
+    }));
+
+    let (state, effects) = reduce(&state, &Action::PermissionResponseDelivered { interaction_id: "i1".to_string() });
+    assert!(effects.is_empty());
+    assert!(state.permission_deliveries.is_empty());
+}
+
+#[test]
+fn test_failure_is_kept_on_the_delivery() {
+    let state = failed(&queued("i1"), "i1");
+
+    let error = state.permission_deliveries["i1"].failed.as_ref().map(|e| e.detail.as_str());
+    assert_eq!(error, Some("connection refused"));
+
+    // Nothing to fail for an answer that was never queued
+    let state = failed(&state, "i2");
+    assert!(!state.permission_deliveries.contains_key("i2"));
+}
+
+#[test]
+fn test_sync_keeps_when_requests_were_first_seen() {
+    let first = Utc::now();
+    let state = EditorState::default();
+    let (state, _) = reduce(&state, &Action::PermissionAutoAnswered { key: "req-1".to_string() });
+    let (state, _) = reduce(&state, &Action::SyncPendingPermissions {
+        waiting: keys(&["req-1", "req-2"]),
+        interactions: HashSet::new(),
+        at: first,
+    });
+
+    let (state, _) = reduce(&state, &Action::SyncPendingPermissions {
+        waiting: keys(&["req-2", "req-3"]),
+        interactions: HashSet::new(),
+        at: first + Duration::seconds(10),
+    });
+
+    assert!(state.auto_answered.is_empty());
+    assert_eq!(state.permission_requested.len(), 2);
+    assert_eq!(state.permission_requested["req-2"], first);
+    assert_eq!(state.permission_requested["req-3"], first + Duration::seconds(10));
+}
+
+#[test]
+fn test_sync_drops_failures_the_server_moved_on_from() {
+    let state = failed(&queued("i1"), "i1");
+    let (state, _) = reduce(&state, &Action::PermissionResponseQueued {
+        interaction_id: "i2".to_string(),
+        server: "local".to_string(),
+        approved: false,
+    });
+    let state = failed(&state, "i2");
+    let (state, _) = reduce(&state, &Action::PermissionResponseQueued {
+        interaction_id: "i3".to_string(),
+        server: "local".to_string(),
+        approved: true,
+    });
+
+    // i2 is still waiting but on another server's conversation of the same id
+    let (state, _) = reduce(&state, &Action::SyncPendingPermissions {
+        waiting: HashSet::new(),
+        interactions: waiting_on(&[("local", "i1"), ("remote", "i2")]),
+        at: Utc::now(),
+    });
+
+    let mut left: Vec<&str> = state.permission_deliveries.keys().map(String::as_str).collect();
+    left.sort();
+    assert_eq!(left, vec!["i1", "i3"]);
+}
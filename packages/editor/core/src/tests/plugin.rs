@@ -0,0 +1,130 @@
+use crate::plugin::{handle, Block, CommandInfo, Context, Manifest, MessageContext, Outcome, Panel, Plugin, Request, ToolCall};
+use pretty_assertions::assert_eq;
+use serde_json::{json, Value};
+
+struct TestSummary;
+
+impl Plugin for TestSummary {
+    fn manifest(&self) -> Manifest {
+        Manifest {
+            name: "test-summary".to_string(),
+            tools: vec!["run_tests".to_string()],
+            panel: true,
+            commands: vec![CommandInfo { id: "rerun".to_string(), label: "Rerun tests".to_string() }],
+        }
+    }
+
+    fn render_message(&self, message: &MessageContext) -> Option<Vec<Block>> {
+        let passed = message.response.contains("passed");
+        passed.then(|| vec![Block::Field { label: "Tests".to_string(), value: "passed".to_string() }])
+    }
+
+    fn panel(&self, context: &Context) -> Option<Panel> {
+        let branch = context.branch.clone()?;
+        Some(Panel { title: "Tests".to_string(), blocks: vec![Block::Text { text: branch }] })
+    }
+
+    fn run_command(&self, id: &str, _context: &Context) -> Result<Outcome, String> {
+        match id {
+            "rerun" => Ok(Outcome::Draft { text: "Run the tests again".to_string() }),
+            _ => Err(format!("No command {}", id)),
+        }
+    }
+}
+
+// Nothing but a manifest, every hook left as it is
+struct Bare;
+
+impl Plugin for Bare {
+    fn manifest(&self) -> Manifest {
+        Manifest { name: "bare".to_string(), ..Default::default() }
+    }
+}
+
+fn ask(plugin: &dyn Plugin, request: &Request) -> Result<Value, String> {
+    let answer = handle(plugin, &serde_json::to_string(request).unwrap())?;
+    Ok(serde_json::from_str(&answer).unwrap())
+}
+
+fn message(response: &str, tools: &[&str]) -> MessageContext {
+    MessageContext {
+        prompt: "Run the tests".to_string(),
+        response: response.to_string(),
+        model: None,
+        tool_calls: tools.iter().map(|name| ToolCall { name: name.to_string(), arguments: Value::Null }).collect(),
+    }
+}
+
+#[test]
+fn test_requests_are_tagged_by_type() {
+    let request = Request::RunCommand { id: "rerun".to_string(), context: Context::default() };
+    assert_eq!(
+        serde_json::to_value(&request).unwrap(),
+        json!({ "type": "runCommand", "id": "rerun", "context": { "server": "", "conversationId": null, "branch": null } })
+    );
+    assert_eq!(serde_json::to_value(Request::Describe).unwrap(), json!({ "type": "describe" }));
+}
+
+#[test]
+fn test_describe_answers_with_the_manifest() {
+    let answer = ask(&TestSummary, &Request::Describe).unwrap();
+    let manifest: Manifest = serde_json::from_value(answer).unwrap();
+    assert_eq!(manifest, TestSummary.manifest());
+}
+
+#[test]
+fn test_render_message_answers_with_blocks_or_null() {
+    let answer = ask(&TestSummary, &Request::RenderMessage { message: message("3 passed", &["run_tests"]) }).unwrap();
+    assert_eq!(answer, json!([{ "type": "field", "label": "Tests", "value": "passed" }]));
+
+    let answer = ask(&TestSummary, &Request::RenderMessage { message: message("1 failed", &["run_tests"]) }).unwrap();
+    assert_eq!(answer, Value::Null);
+}
+
+#[test]
+fn test_panel_follows_the_context() {
+    let context = Context { branch: Some("feature/plugins".to_string()), ..Default::default() };
+    let answer = ask(&TestSummary, &Request::Panel { context }).unwrap();
+    assert_eq!(answer, json!({ "title": "Tests", "blocks": [{ "type": "text", "text": "feature/plugins" }] }));
+
+    let answer = ask(&TestSummary, &Request::Panel { context: Context::default() }).unwrap();
+    assert_eq!(answer, Value::Null);
+}
+
+#[test]
+fn test_run_command_answers_with_the_outcome() {
+    let answer = ask(&TestSummary, &Request::RunCommand { id: "rerun".to_string(), context: Context::default() }).unwrap();
+    assert_eq!(answer, json!({ "type": "draft", "text": "Run the tests again" }));
+
+    let failed = ask(&TestSummary, &Request::RunCommand { id: "deploy".to_string(), context: Context::default() });
+    assert_eq!(failed, Err("No command deploy".to_string()));
+}
+
+#[test]
+fn test_unimplemented_hooks_leave_the_editor_alone() {
+    assert_eq!(ask(&Bare, &Request::RenderMessage { message: message("3 passed", &["run_tests"]) }), Ok(Value::Null));
+    assert_eq!(ask(&Bare, &Request::Panel { context: Context::default() }), Ok(Value::Null));
+    assert_eq!(
+        ask(&Bare, &Request::RunCommand { id: "anything".to_string(), context: Context::default() }),
+        Ok(json!({ "type": "done" }))
+    );
+}
+
+#[test]
+fn test_bad_request_is_an_error() {
+    assert!(handle(&Bare, "{\"type\": \"dance\"}").unwrap_err().starts_with("Bad request"));
+}
+
+#[test]
+fn test_manifest_renders_messages_with_its_tools() {
+    let manifest = TestSummary.manifest();
+    assert!(manifest.renders(&message("", &["read_file", "run_tests"])));
+    assert!(!manifest.renders(&message("", &["read_file"])));
+    assert!(!Bare.manifest().renders(&message("", &["run_tests"])));
+}
+
+#[test]
+fn test_manifest_fields_are_optional() {
+    let manifest: Manifest = serde_json::from_value(json!({ "name": "minimal" })).unwrap();
+    assert_eq!(manifest, Manifest { name: "minimal".to_string(), ..Default::default() });
+}
@@ -0,0 +1,71 @@
+use crate::policy::{glob_match, CommandPattern, Decision, Policy, Request, Rule};
+use pretty_assertions::assert_eq;
+use serde_json::json;
+
+#[test]
+fn test_glob_match_segments() {
+    assert!(glob_match("mcp__*", "mcp__github"));
+    assert!(glob_match("src/*.rs", "src/main.rs"));
+    assert!(!glob_match("src/*.rs", "src/bin/main.rs"));
+    assert!(glob_match("src/**/*.rs", "src/bin/main.rs"));
+    assert!(glob_match("src/**/*.rs", "src/main.rs"));
+    assert!(glob_match("**/.env", ".env"));
+    assert!(glob_match("Rea?", "Read"));
+    assert!(!glob_match("Read", "ReadFile"));
+}
+
+#[test]
+fn test_request_picks_up_path_and_command() {
+    let input = json!({ "file_path": "/repo/src/lib.rs", "command": "cargo test" });
+    let request = Request::new("Edit", Some(&input), Some("/repo"));
+
+    assert_eq!(request.path.as_deref(), Some("/repo/src/lib.rs"));
+    assert_eq!(request.command.as_deref(), Some("cargo test"));
+}
+
+#[test]
+fn test_rule_conditions_all_have_to_match() {
+    let rule = Rule {
+        paths: vec!["src/**".to_string()],
+        worktree: Some("/repo".to_string()),
+        ..Rule::tool("Edit")
+    };
+
+    let inside = json!({ "file_path": "/repo/src/lib.rs" });
+    let outside = json!({ "file_path": "/repo/Cargo.toml" });
+    assert!(rule.matches(&Request::new("Edit", Some(&inside), Some("/repo"))));
+    assert!(!rule.matches(&Request::new("Edit", Some(&outside), Some("/repo"))));
+    assert!(!rule.matches(&Request::new("Edit", Some(&inside), Some("/other"))));
+    assert!(!rule.matches(&Request::new("Edit", None, Some("/repo"))));
+}
+
+#[test]
+fn test_deny_wins_over_allow() {
+    let policy = Policy {
+        allow: vec![Rule::tool("Bash")],
+        deny: vec![Rule {
+            command: Some(CommandPattern::new(r"\brm\s+-rf\b").unwrap()),
+            ..Rule::tool("Bash")
+        }],
+    };
+
+    let safe = json!({ "command": "cargo build" });
+    let risky = json!({ "command": "rm -rf /" });
+    assert_eq!(policy.decide(&Request::new("Bash", Some(&safe), None)), Some(Decision::Allow));
+    assert_eq!(policy.decide(&Request::new("Bash", Some(&risky), None)), Some(Decision::Deny));
+    assert_eq!(policy.decide(&Request::new("Write", None, None)), None);
+
+    let (decision, rule) = policy.matching_rule(&Request::new("Bash", Some(&risky), None)).unwrap();
+    assert_eq!(decision, Decision::Deny);
+    assert_eq!(rule.summary(), r"Bash $ \brm\s+-rf\b");
+}
+
+#[test]
+fn test_command_patterns_roundtrip_and_reject_bad_regex() {
+    let pattern = CommandPattern::new("^git (status|diff)").unwrap();
+    let json = serde_json::to_string(&pattern).unwrap();
+    assert_eq!(json, r#""^git (status|diff)""#);
+    assert_eq!(serde_json::from_str::<CommandPattern>(&json).unwrap(), pattern);
+
+    assert!(serde_json::from_str::<CommandPattern>(r#""(unclosed""#).is_err());
+}
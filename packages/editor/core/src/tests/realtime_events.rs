@@ -6,7 +6,7 @@ use crate::{
     types::{Interaction, InteractionType}
 };
 use pretty_assertions::assert_eq;
-use chrono::Utc;
+use chrono::{DateTime, TimeZone, Utc};
 use std::collections::HashMap;
 
 fn create_test_interaction() -> Interaction {
@@ -23,6 +23,10 @@ fn create_test_interaction() -> Interaction {
     }
 }
 
+fn processed_at() -> DateTime<Utc> {
+    Utc.with_ymd_and_hms(2024, 3, 1, 10, 0, 0).unwrap()
+}
+
 #[test]
 fn test_interaction_posted_event() {
     let state = EditorState::default();
@@ -49,6 +53,7 @@ fn test_interaction_processing_event() {
     let action = Action::InteractionProcessing {
         interaction_id: "realtime-123".to_string(),
         agent_id: "wake".to_string(),
+        at: processed_at(),
     };
     
     let (new_state, effects) = reduce(&state, &action);
@@ -58,6 +63,7 @@ fn test_interaction_processing_event() {
     assert_eq!(interaction.history.len(), 1);
     assert_eq!(interaction.history[0].agent_id, "wake");
     assert_eq!(interaction.history[0].action, "processing");
+    assert_eq!(interaction.history[0].timestamp, processed_at());
     assert!(effects.is_empty());
 }
 
@@ -68,6 +74,7 @@ fn test_interaction_processing_nonexistent() {
     let action = Action::InteractionProcessing {
         interaction_id: "nonexistent".to_string(),
         agent_id: "wake".to_string(),
+        at: processed_at(),
     };
     
     let (new_state, effects) = reduce(&state, &action);
@@ -90,6 +97,7 @@ fn test_interaction_completed_event() {
     let action = Action::InteractionCompleted {
         interaction_id: "realtime-123".to_string(),
         result: result.clone(),
+        at: processed_at(),
     };
     
     let (new_state, effects) = reduce(&state, &action);
@@ -103,6 +111,7 @@ fn test_interaction_completed_event() {
     assert_eq!(interaction.history[0].agent_id, "system");
     assert_eq!(interaction.history[0].action, "completed");
     assert_eq!(interaction.history[0].content, result);
+    assert_eq!(interaction.history[0].timestamp, processed_at());
     
     assert!(effects.is_empty());
 }
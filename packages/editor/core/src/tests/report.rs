@@ -0,0 +1,202 @@
+use crate::{
+    state::EditorState,
+    actions::Action,
+    effects::Effect,
+    reducer::reduce,
+    report::{self, ReviewDecision},
+    types::{ConversationItem, Interaction, InteractionType, Session, SessionStatus}
+};
+use pretty_assertions::assert_eq;
+use chrono::{TimeZone, Utc};
+use serde_json::json;
+use std::collections::HashMap;
+
+fn create_interaction(id: &str, prompt: &str) -> Interaction {
+    let timestamp = Utc.with_ymd_and_hms(2024, 3, 1, 9, 0, 0).unwrap();
+    Interaction {
+        id: id.to_string(),
+        source: "user".to_string(),
+        interaction_type: InteractionType::Query,
+        content: vec![ConversationItem {
+            role: "user".to_string(),
+            content: prompt.to_string(),
+            timestamp,
+            metadata: None,
+        }],
+        needs_work: false,
+        review_stack: vec!["user".to_string()],
+        history: vec![],
+        metadata: HashMap::new(),
+        timestamp,
+    }
+}
+
+fn decision(approved: bool, feedback: Option<&str>, minute: u32) -> ReviewDecision {
+    ReviewDecision {
+        approved,
+        feedback: feedback.map(str::to_string),
+        reviewed_at: Utc.with_ymd_and_hms(2024, 3, 1, 10, minute, 0).unwrap(),
+    }
+}
+
+fn reply_with_edit(path: &str) -> ConversationItem {
+    let mut metadata = HashMap::new();
+    metadata.insert("toolCalls".to_string(), json!([{
+        "name": "Edit",
+        "arguments": { "file_path": path, "old_string": "a\nb", "new_string": "a\nc\nd" }
+    }]));
+    ConversationItem {
+        role: "assistant".to_string(),
+        content: "Done".to_string(),
+        timestamp: Utc.with_ymd_and_hms(2024, 3, 1, 9, 30, 0).unwrap(),
+        metadata: Some(metadata),
+    }
+}
+
+#[test]
+fn test_submitting_a_review_records_the_decision() {
+    let mut state = EditorState::default();
+    state.interactions.insert("i1".to_string(), create_interaction("i1", "Fix it"));
+
+    let reviewed_at = Utc.with_ymd_and_hms(2024, 3, 1, 11, 0, 0).unwrap();
+    let action = Action::SubmitReview {
+        interaction_id: "i1".to_string(),
+        approved: false,
+        feedback: Some("Needs tests".to_string()),
+        reviewed_at,
+    };
+
+    let (new_state, _) = reduce(&state, &action);
+
+    let recorded: Vec<ReviewDecision> = new_state.interactions["i1"].history.iter()
+        .filter_map(ReviewDecision::from_event)
+        .collect();
+    assert_eq!(recorded.len(), 1);
+    assert!(!recorded[0].approved);
+    assert_eq!(recorded[0].feedback.as_deref(), Some("Needs tests"));
+    assert_eq!(recorded[0].reviewed_at, reviewed_at);
+    // Replaying the action records the same decision
+    assert_eq!(reduce(&state, &action).0, new_state);
+}
+
+#[test]
+fn test_report_lists_decisions_feedback_and_changes() {
+    let mut first = create_interaction("i1", "Fix the parser");
+    first.content.push(reply_with_edit("src/parser.rs"));
+    first.history.push(decision(false, Some("Missing a test\n\nPlease add one"), 5).to_event());
+    first.history.push(decision(true, None, 20).to_event());
+    let mut second = create_interaction("i2", "Explain the lexer");
+    second.history.push(decision(true, Some("Thanks"), 10).to_event());
+    let unreviewed = create_interaction("i3", "Not looked at");
+
+    let mut state = EditorState::default();
+    state.sessions.push(Session {
+        id: state.session_id.clone(),
+        name: Some("Parser work".to_string()),
+        created_at: Utc::now(),
+        status: SessionStatus::Active,
+        metadata: None,
+    });
+    for interaction in [first, second, unreviewed] {
+        state.interactions.insert(interaction.id.clone(), interaction);
+    }
+
+    let markdown = report::review_report(&state, "default-session").unwrap();
+
+    assert_eq!(markdown, "\
+# Review report: Parser work
+
+2 interaction(s) reviewed: 2 approved, 1 changes requested.
+
+## Fix the parser
+
+Interaction `i1`
+
+- **Changes requested** 2024-03-01 10:05 UTC
+  > Missing a test
+  >
+  > Please add one
+- **Approved** 2024-03-01 10:20 UTC
+
+Changes: 1 file(s), +2 -1
+
+- `src/parser.rs`
+
+## Explain the lexer
+
+Interaction `i2`
+
+- **Approved** 2024-03-01 10:10 UTC
+  > Thanks
+
+No file changes.
+
+");
+}
+
+#[test]
+fn test_report_covers_sessions_put_away() {
+    let mut reviewed = create_interaction("i1", "Old work");
+    reviewed.history.push(decision(true, None, 0).to_event());
+    let mut state = EditorState::default();
+    state.session_interactions.insert("s2".to_string(), HashMap::from([("i1".to_string(), reviewed)]));
+
+    let markdown = report::review_report(&state, "s2").unwrap();
+
+    assert!(markdown.starts_with("# Review report: s2\n"));
+    assert!(markdown.contains("## Old work"));
+    assert!(report::review_report(&state, "default-session").unwrap().contains("Nothing in this session has been reviewed"));
+    assert_eq!(report::review_report(&state, "missing"), None);
+}
+
+#[test]
+fn test_export_writes_the_report_as_an_effect() {
+    let state = EditorState::default();
+
+    let (_, effects) = reduce(&state, &Action::ExportReviewReport { session_id: "default-session".to_string() });
+
+    assert_eq!(effects, vec![Effect::WriteExport {
+        name: "review report default-session".to_string(),
+        contents: report::review_report(&state, "default-session").unwrap(),
+    }]);
+}
+
+#[test]
+fn test_export_of_an_unknown_session_is_an_error() {
+    let state = EditorState::default();
+
+    let (new_state, effects) = reduce(&state, &Action::ExportReviewReport { session_id: "missing".to_string() });
+
+    assert!(effects.is_empty());
+    assert_eq!(new_state.error, Some("No session missing to report on".to_string()));
+}
+
+#[test]
+fn test_exported_report_remembers_where_it_went() {
+    let state = EditorState { error: Some("Earlier".to_string()), ..Default::default() };
+
+    let (new_state, _) = reduce(&state, &Action::ReviewReportExported(Ok("exports/report.md".to_string())));
+    assert_eq!(new_state.last_export.as_deref(), Some("exports/report.md"));
+    assert_eq!(new_state.error, None);
+
+    let (new_state, _) = reduce(&state, &Action::ReviewReportExported(Err("disk full".to_string())));
+    assert_eq!(new_state.error, Some("Couldn't export review report: disk full".to_string()));
+}
+
+#[test]
+fn test_export_file_names_are_slugged() {
+    assert_eq!(report::export_file_name("Review report: Parser/Work!", 42), "review-report-parser-work-42.md");
+}
+
+#[test]
+fn test_title_prefers_metadata_then_first_prompt() {
+    let mut named = create_interaction("abcdef123", "\nFix the parser\nplease");
+    assert_eq!(named.title(), "Fix the parser");
+
+    named.metadata.insert("title".to_string(), json!("Parser work"));
+    assert_eq!(named.title(), "Parser work");
+
+    named.metadata.clear();
+    named.content.clear();
+    assert_eq!(named.title(), "abcdef12");
+}
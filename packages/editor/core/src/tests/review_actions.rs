@@ -33,11 +33,12 @@ fn test_submit_review_for_existing_interaction() {
         interaction_id: "review-123".to_string(),
         approved: true,
         feedback: Some("Looks good!".to_string()),
+        reviewed_at: Utc::now(),
     };
     
     let (new_state, effects) = reduce(&state, &action);
     
-    // State unchanged until response
+    // Decision kept in the interaction's history
     assert_eq!(new_state.interactions.len(), 1);
     
     // Should create review effect
@@ -60,6 +61,7 @@ fn test_submit_review_for_nonexistent_interaction() {
         interaction_id: "nonexistent".to_string(),
         approved: true,
         feedback: None,
+        reviewed_at: Utc::now(),
     };
     
     let (new_state, effects) = reduce(&state, &action);
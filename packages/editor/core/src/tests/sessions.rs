@@ -0,0 +1,163 @@
+use crate::{
+    state::EditorState,
+    actions::Action,
+    effects::Effect,
+    reducer::reduce,
+    types::{Interaction, InteractionQueueStatus, InteractionType, Session, SessionStatus}
+};
+use pretty_assertions::assert_eq;
+use chrono::Utc;
+use std::collections::HashMap;
+
+fn create_session(id: &str) -> Session {
+    Session {
+        id: id.to_string(),
+        name: Some(format!("Session {}", id)),
+        created_at: Utc::now(),
+        status: SessionStatus::Active,
+        metadata: None,
+    }
+}
+
+fn create_interaction(id: &str) -> Interaction {
+    Interaction {
+        id: id.to_string(),
+        source: "user".to_string(),
+        interaction_type: InteractionType::Query,
+        content: vec![],
+        needs_work: true,
+        review_stack: vec![],
+        history: vec![],
+        metadata: HashMap::new(),
+        timestamp: Utc::now(),
+    }
+}
+
+fn connected_state() -> EditorState {
+    let (state, _) = reduce(&EditorState::default(), &Action::Connect {
+        server_url: "http://localhost:3456".to_string()
+    });
+    let (state, _) = reduce(&state, &Action::Connected);
+    state
+}
+
+#[test]
+fn test_fetch_sessions() {
+    let state = EditorState::default();
+
+    let (_, effects) = reduce(&state, &Action::FetchSessions);
+
+    assert_eq!(effects, vec![Effect::FetchSessions]);
+}
+
+#[test]
+fn test_sessions_loaded() {
+    let state = EditorState::default();
+    let sessions = vec![create_session("default-session"), create_session("s2")];
+
+    let (new_state, effects) = reduce(&state, &Action::SessionsLoaded(Ok(sessions.clone())));
+
+    assert_eq!(new_state.sessions, sessions);
+    assert_eq!(new_state.current_session().map(|s| s.id.as_str()), Some("default-session"));
+    assert!(effects.is_empty());
+}
+
+#[test]
+fn test_create_session_requires_connection() {
+    let state = EditorState::default();
+
+    let (new_state, effects) = reduce(&state, &Action::CreateSession { name: None });
+
+    assert!(effects.is_empty());
+    assert_eq!(new_state.error, Some("Not connected to server".to_string()));
+}
+
+#[test]
+fn test_created_session_becomes_current() {
+    let state = connected_state();
+
+    let (state, effects) = reduce(&state, &Action::CreateSession { name: Some("Refactor".to_string()) });
+    assert_eq!(effects, vec![Effect::CreateSession { name: Some("Refactor".to_string()) }]);
+
+    let (state, effects) = reduce(&state, &Action::SessionCreated(Ok(create_session("s2"))));
+    assert_eq!(state.session_id, "s2");
+    assert_eq!(state.sessions.len(), 1);
+    assert_eq!(effects, vec![Effect::ConnectToStream {
+        server_url: "http://localhost:3456".to_string(),
+        session_id: "s2".to_string(),
+    }]);
+}
+
+#[test]
+fn test_switch_session_keeps_interactions_per_session() {
+    let state = connected_state();
+    let (state, _) = reduce(&state, &Action::InteractionPosted { interaction: create_interaction("a") });
+
+    let (state, _) = reduce(&state, &Action::SwitchSession { session_id: "s2".to_string() });
+    assert!(state.interactions.is_empty());
+
+    let (state, _) = reduce(&state, &Action::InteractionPosted { interaction: create_interaction("b") });
+    let (state, _) = reduce(&state, &Action::SwitchSession { session_id: "default-session".to_string() });
+
+    assert_eq!(state.interactions.keys().collect::<Vec<_>>(), vec!["a"]);
+    assert!(state.session_interactions["s2"].contains_key("b"));
+}
+
+#[test]
+fn test_switch_to_current_session_does_nothing() {
+    let state = connected_state();
+
+    let (new_state, effects) = reduce(&state, &Action::SwitchSession { session_id: "default-session".to_string() });
+
+    assert_eq!(new_state, state);
+    assert!(effects.is_empty());
+}
+
+fn queue(needs_work: u32) -> InteractionQueueStatus {
+    InteractionQueueStatus {
+        queue_size: needs_work,
+        needs_work,
+        needs_review: 0,
+        processing: 0,
+        completed: 0,
+        analyzing: 0,
+    }
+}
+
+#[test]
+fn test_session_gone_from_list_switches_to_default() {
+    let state = connected_state();
+    let (state, _) = reduce(&state, &Action::SessionsLoaded(Ok(vec![create_session("s2")])));
+    let (state, _) = reduce(&state, &Action::SwitchSession { session_id: "s2".to_string() });
+
+    let (state, effects) = reduce(&state, &Action::SessionsLoaded(Ok(vec![])));
+
+    assert_eq!(state.session_id, "default-session");
+    assert_eq!(effects, vec![Effect::ConnectToStream {
+        server_url: "http://localhost:3456".to_string(),
+        session_id: "default-session".to_string(),
+    }]);
+}
+
+#[test]
+fn test_session_queues_kept_for_listed_sessions() {
+    let (state, _) = reduce(&EditorState::default(), &Action::SessionsLoaded(Ok(vec![create_session("s2")])));
+
+    let (state, _) = reduce(&state, &Action::SessionQueueLoaded { session_id: "s2".to_string(), status: queue(2) });
+    let (state, _) = reduce(&state, &Action::SessionQueueLoaded { session_id: "s3".to_string(), status: queue(5) });
+
+    assert_eq!(state.session_queues.keys().collect::<Vec<_>>(), vec!["s2"]);
+    assert_eq!(state.queue_status, None);
+
+    let (state, _) = reduce(&state, &Action::SessionsLoaded(Ok(vec![])));
+    assert!(state.session_queues.is_empty());
+}
+
+#[test]
+fn test_current_session_queue_is_the_queue_status() {
+    let state = EditorState::default();
+
+    let (state, _) = reduce(&state, &Action::SessionQueueLoaded { session_id: "default-session".to_string(), status: queue(3) });
+
+    assert_eq!(state.queue_status, Some(queue(3)));
+}
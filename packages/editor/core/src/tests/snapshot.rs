@@ -0,0 +1,109 @@
+use crate::{
+    state::EditorState,
+    actions::Action,
+    effects::Effect,
+    reducer::reduce,
+    types::{Interaction, InteractionType}
+};
+use pretty_assertions::assert_eq;
+use chrono::Utc;
+use std::collections::HashMap;
+
+fn create_interaction(id: &str) -> Interaction {
+    Interaction {
+        id: id.to_string(),
+        source: "user".to_string(),
+        interaction_type: InteractionType::Query,
+        content: vec![],
+        needs_work: true,
+        review_stack: vec![],
+        history: vec![],
+        metadata: HashMap::new(),
+        timestamp: Utc::now(),
+    }
+}
+
+// A state with a draft and an interaction in flight, as a crash would find it
+fn working_state() -> EditorState {
+    let (state, _) = reduce(&EditorState::default(), &Action::UpdateDraftContent {
+        content: "Refactor the parser".to_string()
+    });
+    let (state, _) = reduce(&state, &Action::InteractionPosted {
+        interaction: create_interaction("i1")
+    });
+    state
+}
+
+fn saved(state: &EditorState) -> String {
+    let (_, effects) = reduce(state, &Action::SaveSnapshot);
+    match effects.as_slice() {
+        [Effect::PersistState { snapshot }] => snapshot.clone(),
+        other => panic!("expected one PersistState, got {:?}", other),
+    }
+}
+
+#[test]
+fn test_snapshot_round_trips_working_set() {
+    let state = working_state();
+
+    let (restored, effects) = reduce(&EditorState::default(), &Action::LoadSnapshot {
+        snapshot: saved(&state)
+    });
+
+    assert_eq!(restored, state);
+    assert!(effects.is_empty());
+}
+
+#[test]
+fn test_connection_is_not_saved() {
+    let (state, _) = reduce(&working_state(), &Action::Connected);
+    let (state, _) = reduce(&state, &Action::SetError { message: "boom".to_string() });
+
+    let restored = EditorState::from_snapshot(&saved(&state)).unwrap();
+
+    assert!(!restored.connected);
+    assert_eq!(restored.error, None);
+    assert_eq!(restored.draft.content, "Refactor the parser");
+}
+
+#[test]
+fn test_restore_keeps_current_connection() {
+    let (state, _) = reduce(&EditorState::default(), &Action::Connect {
+        server_url: "http://localhost:3456".to_string()
+    });
+    let (state, _) = reduce(&state, &Action::Connected);
+    let mut other_session = working_state();
+    other_session.session_id = "s2".to_string();
+
+    let (restored, effects) = reduce(&state, &Action::LoadSnapshot {
+        snapshot: saved(&other_session)
+    });
+
+    assert!(restored.connected);
+    assert_eq!(restored.session_id, "s2");
+    assert_eq!(effects, vec![Effect::ConnectToStream {
+        server_url: "http://localhost:3456".to_string(),
+        session_id: "s2".to_string(),
+    }]);
+}
+
+#[test]
+fn test_missing_fields_take_defaults() {
+    let restored = EditorState::from_snapshot(r#"{"session_id": "s1"}"#).unwrap();
+
+    assert_eq!(restored.session_id, "s1");
+    assert_eq!(restored.draft, EditorState::default().draft);
+}
+
+#[test]
+fn test_bad_snapshot_leaves_state_alone() {
+    let state = working_state();
+
+    let (new_state, effects) = reduce(&state, &Action::LoadSnapshot {
+        snapshot: "not json".to_string()
+    });
+
+    assert!(effects.is_empty());
+    assert_eq!(new_state.interactions, state.interactions);
+    assert!(new_state.error.unwrap().starts_with("Couldn't restore snapshot"));
+}
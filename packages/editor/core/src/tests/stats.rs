@@ -0,0 +1,124 @@
+use crate::stats::{guess_language, language_of_fence, language_of_path, line_changes, word_count, ConversationStats, LanguageActivity};
+use pretty_assertions::assert_eq;
+use serde_json::json;
+
+#[test]
+fn test_word_count_skips_bare_punctuation() {
+    assert_eq!(word_count("Fix the build - it's broken, again."), 6);
+    assert_eq!(word_count("  "), 0);
+}
+
+#[test]
+fn test_reading_time_rounds_up() {
+    let mut stats = ConversationStats::default();
+    assert_eq!(stats.reading_minutes(), 0);
+
+    stats.add_text(&"word ".repeat(201));
+    assert_eq!(stats.reading_minutes(), 2);
+}
+
+#[test]
+fn test_line_changes_ignore_shared_lines() {
+    assert_eq!(line_changes("a\nb\nc", "a\nB\nB2\nc"), (2, 1));
+    assert_eq!(line_changes("same", "same"), (0, 0));
+    assert_eq!(line_changes("", "new\nfile"), (2, 0));
+}
+
+#[test]
+fn test_edit_and_multi_edit() {
+    let mut stats = ConversationStats::default();
+    stats.add_tool_call("Edit", &json!({
+        "file_path": "src/lib.rs",
+        "old_string": "pub mod old;",
+        "new_string": "pub mod new;\npub mod extra;"
+    }));
+    stats.add_tool_call("MultiEdit", &json!({
+        "file_path": "src/main.rs",
+        "edits": [
+            { "old_string": "a", "new_string": "b" },
+            { "old_string": "c\nd", "new_string": "" }
+        ]
+    }));
+
+    assert_eq!(stats.lines_added, 3);
+    assert_eq!(stats.lines_removed, 4);
+    assert_eq!(stats.files.iter().collect::<Vec<_>>(), vec!["src/lib.rs", "src/main.rs"]);
+}
+
+#[test]
+fn test_write_counts_whole_file() {
+    let mut stats = ConversationStats::default();
+    stats.add_tool_call("Write", &json!({ "file_path": "notes.md", "content": "one\ntwo\nthree\n" }));
+
+    assert_eq!(stats.lines_added, 3);
+    assert!(stats.files.contains("notes.md"));
+}
+
+#[test]
+fn test_patch_counts_every_file() {
+    let patch = "\
+diff --git a/src/lib.rs b/src/lib.rs
+--- a/src/lib.rs
++++ b/src/lib.rs
+@@ -1,2 +1,2 @@
+ pub mod state;
+-pub mod old;
++pub mod new;
+";
+    let mut stats = ConversationStats::default();
+    stats.add_tool_call("Bash", &json!({ "patch": patch }));
+
+    assert_eq!((stats.lines_added, stats.lines_removed), (1, 1));
+    assert!(stats.files.contains("src/lib.rs"));
+}
+
+#[test]
+fn test_reads_are_not_touches() {
+    let mut stats = ConversationStats::default();
+    stats.add_tool_call("Read", &json!({ "file_path": "src/lib.rs" }));
+    stats.add_tool_call("Bash", &json!({ "command": "cargo test", "content": "x" }));
+
+    assert_eq!(stats, ConversationStats::default());
+}
+
+#[test]
+fn test_languages_by_fence_and_extension() {
+    assert_eq!(language_of_fence("rs"), Some("Rust"));
+    assert_eq!(language_of_fence("Python title=\"setup.py\""), Some("Python"));
+    assert_eq!(language_of_fence("text"), None);
+    assert_eq!(language_of_path("web/src/App.tsx"), Some("TypeScript"));
+    assert_eq!(language_of_path("Makefile"), None);
+}
+
+#[test]
+fn test_untagged_blocks_are_guessed() {
+    assert_eq!(guess_language("fn main() {\n    let x = 1;\n}"), Some("Rust"));
+    assert_eq!(guess_language("#!/usr/bin/env bash\nset -e"), Some("Shell"));
+    assert_eq!(guess_language("def run(self):\n    pass"), Some("Python"));
+    assert_eq!(guess_language("Just some words"), None);
+}
+
+#[test]
+fn test_code_blocks_count_towards_their_language() {
+    let mut stats = ConversationStats::default();
+    stats.add_text("Try this:\n\n```rust\nfn a() {}\nfn b() {}\n```\n\nand\n\n~~~py\nprint(1)\n~~~\n\n```\nplain output\n```\n");
+
+    assert_eq!(stats.languages["Rust"], LanguageActivity { blocks: 1, lines: 2 });
+    assert_eq!(stats.languages["Python"], LanguageActivity { blocks: 1, lines: 1 });
+    assert_eq!(stats.languages.len(), 2);
+}
+
+#[test]
+fn test_diffs_and_edits_count_towards_their_files_languages() {
+    let mut stats = ConversationStats::default();
+    stats.add_text("```diff\n--- a/app.py\n+++ b/app.py\n@@ -1 +1,2 @@\n-x = 1\n+x = 2\n+y = 3\n```");
+    stats.add_tool_call("Edit", &json!({
+        "file_path": "src/lib.rs",
+        "old_string": "pub mod old;",
+        "new_string": "pub mod new;\npub mod extra;"
+    }));
+
+    assert_eq!(stats.languages["Python"], LanguageActivity { blocks: 1, lines: 3 });
+    assert_eq!(stats.languages["Rust"], LanguageActivity { blocks: 0, lines: 3 });
+    assert_eq!(stats.top_languages().iter().map(|(language, _)| *language).collect::<Vec<_>>(), ["Python", "Rust"]);
+}
@@ -0,0 +1,107 @@
+use crate::{
+    state::EditorState,
+    actions::Action,
+    effects::Effect,
+    reducer::reduce,
+    types::{Interaction, InteractionType}
+};
+use pretty_assertions::assert_eq;
+use chrono::Utc;
+use serde_json::json;
+use std::collections::HashMap;
+
+fn state_with_tags(tags: serde_json::Value) -> EditorState {
+    let mut metadata = HashMap::new();
+    metadata.insert("tags".to_string(), tags);
+    let interaction = Interaction {
+        id: "tagged".to_string(),
+        source: "user".to_string(),
+        interaction_type: InteractionType::Query,
+        content: vec![],
+        needs_work: false,
+        review_stack: vec![],
+        history: vec![],
+        metadata,
+        timestamp: Utc::now(),
+    };
+
+    let mut state = EditorState::default();
+    state.interactions.insert(interaction.id.clone(), interaction);
+    state
+}
+
+fn add(tag: &str) -> Action {
+    Action::AddTag { interaction_id: "tagged".to_string(), tag: tag.to_string() }
+}
+
+fn remove(tag: &str) -> Action {
+    Action::RemoveTag { interaction_id: "tagged".to_string(), tag: tag.to_string() }
+}
+
+#[test]
+fn test_add_tag_updates_metadata_and_server() {
+    let state = state_with_tags(json!(["rust"]));
+
+    let (new_state, effects) = reduce(&state, &add(" async "));
+
+    assert_eq!(new_state.interactions["tagged"].metadata["tags"], json!(["rust", "async"]));
+    assert_eq!(effects, vec![Effect::UpdateInteractionTags {
+        interaction_id: "tagged".to_string(),
+        tags: vec!["rust".to_string(), "async".to_string()],
+    }]);
+}
+
+#[test]
+fn test_add_tag_to_untagged_interaction() {
+    let mut state = state_with_tags(json!(null));
+    state.interactions.get_mut("tagged").unwrap().metadata.clear();
+
+    let (new_state, effects) = reduce(&state, &add("rust"));
+
+    assert_eq!(new_state.interactions["tagged"].metadata["tags"], json!(["rust"]));
+    assert_eq!(effects.len(), 1);
+}
+
+#[test]
+fn test_repeated_or_blank_tag_is_ignored() {
+    let state = state_with_tags(json!(["rust"]));
+
+    for tag in ["rust", "  "] {
+        let (new_state, effects) = reduce(&state, &add(tag));
+        assert_eq!(new_state, state);
+        assert!(effects.is_empty());
+    }
+}
+
+#[test]
+fn test_remove_tag() {
+    let state = state_with_tags(json!(["rust", "async"]));
+
+    let (new_state, effects) = reduce(&state, &remove("rust"));
+
+    assert_eq!(new_state.interactions["tagged"].metadata["tags"], json!(["async"]));
+    assert_eq!(effects, vec![Effect::UpdateInteractionTags {
+        interaction_id: "tagged".to_string(),
+        tags: vec!["async".to_string()],
+    }]);
+}
+
+#[test]
+fn test_remove_missing_tag_does_nothing() {
+    let state = state_with_tags(json!(["rust"]));
+
+    let (new_state, effects) = reduce(&state, &remove("async"));
+
+    assert_eq!(new_state, state);
+    assert!(effects.is_empty());
+}
+
+#[test]
+fn test_tagging_unknown_interaction_does_nothing() {
+    let state = EditorState::default();
+
+    let (new_state, effects) = reduce(&state, &add("rust"));
+
+    assert_eq!(new_state, state);
+    assert!(effects.is_empty());
+}
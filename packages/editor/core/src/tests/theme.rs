@@ -0,0 +1,46 @@
+use crate::theme::{parse_hex, parse_tokens};
+use pretty_assertions::assert_eq;
+
+#[test]
+fn test_parse_hex_forms() {
+    assert_eq!(parse_hex("#61a6ff"), Some([0x61, 0xa6, 0xff, 255]));
+    assert_eq!(parse_hex("#fff"), Some([255, 255, 255, 255]));
+    assert_eq!(parse_hex(" #00000080 "), Some([0, 0, 0, 0x80]));
+}
+
+#[test]
+fn test_parse_hex_rejects_garbage() {
+    assert_eq!(parse_hex("61a6ff"), None);
+    assert_eq!(parse_hex("#12345"), None);
+    assert_eq!(parse_hex("#gggggg"), None);
+    assert_eq!(parse_hex("#ééé"), None);
+}
+
+#[test]
+fn test_parse_tokens_css_style() {
+    let source = "\
+/* My theme */
+:root {
+  --background: #101014;
+  primary: #ff8800; text: #eee;
+}
+";
+    let (tokens, errors) = parse_tokens(source);
+
+    assert!(errors.is_empty());
+    let names: Vec<_> = tokens.iter().map(|t| (t.name.as_str(), t.line)).collect();
+    assert_eq!(names, vec![("background", 3), ("primary", 4), ("text", 4)]);
+    assert_eq!(tokens[1].color, [0xff, 0x88, 0x00, 255]);
+}
+
+#[test]
+fn test_parse_tokens_reports_bad_lines() {
+    let source = "background: #101014;\n/* a\nmultiline comment */\nprimary: blue;\nnonsense\n";
+    let (tokens, errors) = parse_tokens(source);
+
+    assert_eq!(tokens.len(), 1);
+    assert_eq!(errors.len(), 2);
+    assert_eq!(errors[0].line, 4);
+    assert!(errors[0].message.contains("blue"));
+    assert_eq!(errors[1].line, 5);
+}
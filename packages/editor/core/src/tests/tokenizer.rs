@@ -0,0 +1,44 @@
+use crate::tokenizer::{estimate_tokens, exceeds_limit};
+use pretty_assertions::assert_eq;
+
+#[test]
+fn test_empty_text_has_no_tokens() {
+    assert_eq!(estimate_tokens(""), 0);
+    assert_eq!(estimate_tokens("   "), 0);
+}
+
+#[test]
+fn test_short_words_are_single_tokens() {
+    assert_eq!(estimate_tokens("Hello agents"), 2);
+    assert_eq!(estimate_tokens("fix the bug"), 3);
+}
+
+#[test]
+fn test_long_words_split() {
+    // 14 letters: one token for the first six, two more for the rest
+    assert_eq!(estimate_tokens("internationals"), 3);
+}
+
+#[test]
+fn test_punctuation_and_digits() {
+    assert_eq!(estimate_tokens("Hello, world!"), 4);
+    assert_eq!(estimate_tokens("1234567"), 3);
+}
+
+#[test]
+fn test_newline_runs_merge() {
+    assert_eq!(estimate_tokens("a\n\n\nb"), 3);
+}
+
+#[test]
+fn test_code_is_denser_than_prose() {
+    let code = "fn main() { println!(\"{}\", x); }";
+    let prose = "the main function prints out a value";
+    assert!(estimate_tokens(code) > estimate_tokens(prose));
+}
+
+#[test]
+fn test_exceeds_limit() {
+    assert!(!exceeds_limit("one two three", 3));
+    assert!(exceeds_limit("one two three four", 3));
+}
@@ -0,0 +1,111 @@
+use crate::{
+    state::EditorState,
+    actions::Action,
+    effects::Effect,
+    reducer::reduce,
+    types::{Interaction, InteractionType, ToolPermissionRequest, PENDING_PERMISSION_KEY}
+};
+use pretty_assertions::assert_eq;
+use chrono::Utc;
+use serde_json::json;
+use std::collections::HashMap;
+
+fn create_interaction(id: &str, permission: Option<serde_json::Value>) -> Interaction {
+    let mut metadata = HashMap::new();
+    if let Some(permission) = permission {
+        metadata.insert(PENDING_PERMISSION_KEY.to_string(), permission);
+    }
+    Interaction {
+        id: id.to_string(),
+        source: "user".to_string(),
+        interaction_type: InteractionType::Query,
+        content: vec![],
+        needs_work: true,
+        review_stack: vec![],
+        history: vec![],
+        metadata,
+        timestamp: Utc::now(),
+    }
+}
+
+fn waiting() -> serde_json::Value {
+    json!({
+        "toolName": "Bash",
+        "description": "Run cargo test",
+        "requestId": "req-1",
+        "input": { "command": "cargo test" }
+    })
+}
+
+fn state_with(interactions: Vec<Interaction>) -> EditorState {
+    let mut state = EditorState::default();
+    for interaction in interactions {
+        state.interactions.insert(interaction.id.clone(), interaction);
+    }
+    state
+}
+
+#[test]
+fn test_reads_pending_permission_from_metadata() {
+    let interaction = create_interaction("i1", Some(waiting()));
+
+    assert_eq!(interaction.pending_permission(), Some(ToolPermissionRequest {
+        tool_name: "Bash".to_string(),
+        description: "Run cargo test".to_string(),
+        request_id: Some("req-1".to_string()),
+        input: Some(json!({ "command": "cargo test" })),
+    }));
+}
+
+#[test]
+fn test_answered_permission_is_cleared_to_null() {
+    let interaction = create_interaction("i1", Some(serde_json::Value::Null));
+
+    assert_eq!(interaction.pending_permission(), None);
+}
+
+#[test]
+fn test_lists_interactions_waiting_on_permission() {
+    let state = state_with(vec![
+        create_interaction("i1", Some(waiting())),
+        create_interaction("i2", None),
+    ]);
+
+    let waiting: Vec<&str> = state.get_pending_permissions().iter().map(|i| i.id.as_str()).collect();
+
+    assert_eq!(waiting, vec!["i1"]);
+}
+
+#[test]
+fn test_responding_sends_answer_and_clears_prompt() {
+    let state = state_with(vec![create_interaction("i1", Some(waiting()))]);
+
+    let (new_state, effects) = reduce(&state, &Action::RespondToPermission {
+        interaction_id: "i1".to_string(),
+        approved: true,
+    });
+
+    assert_eq!(effects, vec![Effect::RespondToPermission {
+        interaction_id: "i1".to_string(),
+        approved: true,
+    }]);
+    assert!(new_state.get_pending_permissions().is_empty());
+}
+
+#[test]
+fn test_responding_twice_sends_one_answer() {
+    let state = state_with(vec![create_interaction("i1", Some(waiting()))]);
+    let deny = Action::RespondToPermission { interaction_id: "i1".to_string(), approved: false };
+
+    let (state, _) = reduce(&state, &deny);
+    let (_, effects) = reduce(&state, &deny);
+
+    assert!(effects.is_empty());
+}
+
+#[test]
+fn test_failed_response_is_reported() {
+    let (state, _) = reduce(&EditorState::default(), &Action::PermissionResponded(Err("offline".to_string())));
+
+    assert_eq!(state.error.as_deref(), Some("offline"));
+}
@@ -0,0 +1,102 @@
+use crate::types::{Interaction, InteractionType, ServerConversation};
+use pretty_assertions::assert_eq;
+use chrono::{TimeZone, Utc};
+use serde_json::json;
+use std::collections::HashMap;
+
+// As `GET /interactions/:id` sends it
+fn server_conversation() -> serde_json::Value {
+    json!({
+        "interaction": {
+            "id": "i1",
+            "source": "user",
+            "type": "action",
+            "createdAt": "2024-01-01T12:00:00.000Z",
+            "metadata": { "tags": ["rust"], "worktreeContext": { "worktreeId": "wt1" } }
+        },
+        "messages": [
+            {
+                "id": "m1",
+                "interactionId": "i1",
+                "role": "user",
+                "content": "Fix the build",
+                "timestamp": "2024-01-01T12:00:01.000Z",
+                "status": "completed",
+                "metadata": null
+            },
+            {
+                "id": "m2",
+                "interactionId": "i1",
+                "role": "assistant",
+                "content": "Done",
+                "timestamp": "2024-01-01T12:00:05.000Z",
+                "status": "completed",
+                "metadata": { "model": "claude" }
+            }
+        ],
+        "hasMore": true
+    })
+}
+
+#[test]
+fn test_reads_server_conversation() {
+    let conversation: ServerConversation = serde_json::from_value(server_conversation()).unwrap();
+
+    assert!(conversation.has_more);
+    assert_eq!(conversation.messages.len(), 2);
+    assert_eq!(conversation.messages[1].role, "assistant");
+    assert_eq!(conversation.messages[0].id, "m1");
+    assert_eq!(conversation.messages[0].status, "completed");
+    assert_eq!(conversation.messages[1].metadata.as_ref().unwrap()["model"], json!("claude"));
+
+    let interaction = conversation.into_interaction();
+    assert_eq!(interaction.interaction_type, InteractionType::Action);
+    assert_eq!(interaction.timestamp, Utc.with_ymd_and_hms(2024, 1, 1, 12, 0, 0).unwrap());
+    assert_eq!(interaction.metadata["tags"], json!(["rust"]));
+    assert_eq!(interaction.content[0].content, "Fix the build");
+    assert!(!interaction.needs_work);
+    assert!(interaction.history.is_empty());
+}
+
+#[test]
+fn test_writes_server_field_names() {
+    let interaction = Interaction {
+        id: "i1".to_string(),
+        source: "user".to_string(),
+        interaction_type: InteractionType::Observation,
+        content: vec![],
+        needs_work: true,
+        review_stack: vec![],
+        history: vec![],
+        metadata: HashMap::new(),
+        timestamp: Utc.with_ymd_and_hms(2024, 1, 1, 12, 0, 0).unwrap(),
+    };
+    let value = serde_json::to_value(&interaction).unwrap();
+
+    assert_eq!(value["type"], "observation");
+    assert_eq!(value["createdAt"], "2024-01-01T12:00:00Z");
+    assert_eq!(value["needsWork"], true);
+
+    let back: Interaction = serde_json::from_value(value).unwrap();
+    assert_eq!(back, interaction);
+}
+
+#[test]
+fn test_reads_interactions_saved_before_the_rename() {
+    let old = json!({
+        "id": "i1",
+        "source": "user",
+        "interaction_type": "Query",
+        "content": [],
+        "needs_work": true,
+        "review_stack": ["user"],
+        "history": [],
+        "metadata": {},
+        "timestamp": "2024-01-01T12:00:00Z"
+    });
+    let interaction: Interaction = serde_json::from_value(old).unwrap();
+
+    assert_eq!(interaction.interaction_type, InteractionType::Query);
+    assert!(interaction.needs_work);
+    assert_eq!(interaction.review_stack, vec!["user".to_string()]);
+}
@@ -0,0 +1,97 @@
+use crate::{
+    state::EditorState,
+    actions::Action,
+    reducer::reduce,
+    types::{Worktree, WorktreeGitStatus, WorktreeStatus}
+};
+use pretty_assertions::assert_eq;
+use std::collections::HashMap;
+
+fn worktree(id: &str) -> Worktree {
+    Worktree {
+        id: id.to_string(),
+        path: format!("/tmp/worktrees/{}", id),
+        branch: Some(format!("feature-{}", id)),
+        base_branch: Some("main".to_string()),
+        base_commit: None,
+        status: WorktreeStatus::Active,
+        created_at: "2024-01-01T00:00:00Z".to_string(),
+    }
+}
+
+fn state_with(ids: &[&str]) -> EditorState {
+    let action = Action::WorktreesLoaded { worktrees: ids.iter().map(|id| worktree(id)).collect() };
+    reduce(&EditorState::default(), &action).0
+}
+
+fn focused(state: &EditorState, id: &str) -> EditorState {
+    let state = reduce(state, &Action::FocusWorktree { worktree_id: Some(id.to_string()) }).0;
+    reduce(&state, &Action::WorktreeFilesLoaded { files: vec!["src/main.rs".to_string()] }).0
+}
+
+#[test]
+fn test_worktree_added_once() {
+    let state = state_with(&["w1"]);
+
+    let (new_state, effects) = reduce(&state, &Action::WorktreeAdded { worktree: worktree("w2") });
+    let (new_state, _) = reduce(&new_state, &Action::WorktreeAdded { worktree: worktree("w2") });
+
+    assert!(effects.is_empty());
+    let ids: Vec<&str> = new_state.available_worktrees.iter().map(|w| w.id.as_str()).collect();
+    assert_eq!(ids, vec!["w1", "w2"]);
+}
+
+#[test]
+fn test_focusing_a_worktree_clears_the_last_ones_files() {
+    let state = focused(&state_with(&["w1", "w2"]), "w1");
+    assert_eq!(state.current_worktree.as_ref().map(|w| w.id.as_str()), Some("w1"));
+    assert_eq!(state.worktree_files.len(), 1);
+
+    // Staying put keeps them
+    let (same, _) = reduce(&state, &Action::FocusWorktree { worktree_id: Some("w1".to_string()) });
+    assert_eq!(same.worktree_files.len(), 1);
+
+    let (moved, _) = reduce(&state, &Action::FocusWorktree { worktree_id: Some("w2".to_string()) });
+    assert_eq!(moved.current_worktree.map(|w| w.id), Some("w2".to_string()));
+    assert!(moved.worktree_files.is_empty());
+
+    let (main, _) = reduce(&state, &Action::FocusWorktree { worktree_id: None });
+    assert_eq!(main.current_worktree, None);
+}
+
+#[test]
+fn test_focusing_an_unknown_worktree_falls_back_to_main() {
+    let state = focused(&state_with(&["w1"]), "w1");
+
+    let (new_state, _) = reduce(&state, &Action::FocusWorktree { worktree_id: Some("gone".to_string()) });
+
+    assert_eq!(new_state.current_worktree, None);
+    assert!(new_state.worktree_files.is_empty());
+}
+
+#[test]
+fn test_deleting_a_worktree_forgets_it() {
+    let state = focused(&state_with(&["w1", "w2"]), "w1");
+    let statuses = HashMap::from([
+        ("w1".to_string(), WorktreeGitStatus { dirty: 2, ahead: 1, behind: 0 }),
+        ("w2".to_string(), WorktreeGitStatus::default()),
+    ]);
+    let (state, _) = reduce(&state, &Action::WorktreeStatusesLoaded { statuses });
+
+    let (new_state, _) = reduce(&state, &Action::WorktreeDeleted { worktree_id: "w1".to_string() });
+
+    assert_eq!(new_state.available_worktrees, vec![worktree("w2")]);
+    assert_eq!(new_state.worktree_git_status.keys().collect::<Vec<_>>(), vec!["w2"]);
+    assert_eq!(new_state.current_worktree, None);
+    assert!(new_state.worktree_files.is_empty());
+}
+
+#[test]
+fn test_worktrees_are_left_out_of_snapshots() {
+    let state = focused(&state_with(&["w1"]), "w1");
+
+    let restored = EditorState::from_snapshot(&state.to_snapshot().unwrap()).unwrap();
+
+    assert!(restored.available_worktrees.is_empty());
+    assert_eq!(restored.current_worktree, None);
+}
@@ -0,0 +1,93 @@
+/// A color override read from a theme file
+#[derive(Debug, Clone, PartialEq)]
+pub struct Token {
+    pub name: String,
+    /// RGBA, alpha is 255 unless given
+    pub color: [u8; 4],
+    pub line: usize,
+}
+
+/// Something in a theme file that couldn't be used
+#[derive(Debug, Clone, PartialEq)]
+pub struct TokenError {
+    pub line: usize,
+    pub message: String,
+}
+
+impl std::fmt::Display for TokenError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "line {}: {}", self.line, self.message)
+    }
+}
+
+/// `#rgb`, `#rrggbb` or `#rrggbbaa`
+pub fn parse_hex(value: &str) -> Option<[u8; 4]> {
+    let hex = value.trim().strip_prefix('#').filter(|hex| hex.is_ascii())?;
+    let channel = |idx: usize| u8::from_str_radix(&hex[idx..idx + 2], 16).ok();
+
+    match hex.len() {
+        3 => {
+            let mut color = [255; 4];
+            for (idx, digit) in hex.chars().enumerate() {
+                color[idx] = digit.to_digit(16)? as u8 * 17;
+            }
+            Some(color)
+        }
+        6 => Some([channel(0)?, channel(2)?, channel(4)?, 255]),
+        8 => Some([channel(0)?, channel(2)?, channel(4)?, channel(6)?]),
+        _ => None,
+    }
+}
+
+// Blank out /* */ comments, keeping newlines so line numbers still match
+fn strip_comments(source: &str) -> String {
+    let mut out = String::with_capacity(source.len());
+    let mut rest = source;
+    while let Some(start) = rest.find("/*") {
+        out.push_str(&rest[..start]);
+        let comment = &rest[start..];
+        let end = comment.find("*/").map(|end| end + 2).unwrap_or(comment.len());
+        out.extend(comment[..end].chars().filter(|c| *c == '\n'));
+        rest = &comment[end..];
+    }
+    out.push_str(rest);
+    out
+}
+
+/// Parse `name: #hex;` declarations, CSS style
+///
+/// Names may carry a `--` prefix and declarations may sit inside a `:root { }`
+/// block. Tokens are returned for every valid declaration and errors for the
+/// rest, so one typo doesn't throw away the whole file. Which names exist is
+/// up to the caller.
+pub fn parse_tokens(source: &str) -> (Vec<Token>, Vec<TokenError>) {
+    let mut tokens = Vec::new();
+    let mut errors = Vec::new();
+
+    for (idx, line) in strip_comments(source).lines().enumerate() {
+        let line_no = idx + 1;
+        let line = line.replace(['{', '}'], " ");
+        let line = line.trim().trim_start_matches(":root").trim();
+
+        for declaration in line.split(';').map(str::trim).filter(|d| !d.is_empty()) {
+            let Some((name, value)) = declaration.split_once(':') else {
+                errors.push(TokenError {
+                    line: line_no,
+                    message: format!("expected `name: #color`, found `{}`", declaration),
+                });
+                continue;
+            };
+
+            let name = name.trim().trim_start_matches("--").to_lowercase();
+            match parse_hex(value) {
+                Some(color) => tokens.push(Token { name, color, line: line_no }),
+                None => errors.push(TokenError {
+                    line: line_no,
+                    message: format!("`{}` is not a hex color for {}", value.trim(), name),
+                }),
+            }
+        }
+    }
+
+    (tokens, errors)
+}
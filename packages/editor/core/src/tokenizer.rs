@@ -0,0 +1,47 @@
+/// Cheap token estimate that tracks cl100k-style BPE counts closely enough for UI hints.
+///
+/// Words up to six letters are usually a single token, longer words split roughly every
+/// four characters, digits group in threes, and punctuation is mostly one token per symbol.
+pub fn estimate_tokens(text: &str) -> usize {
+    let mut tokens = 0;
+    let mut chars = text.chars().peekable();
+
+    while let Some(ch) = chars.next() {
+        if ch.is_ascii_alphabetic() {
+            let mut len: usize = 1;
+            while chars.peek().is_some_and(|c| c.is_ascii_alphabetic()) {
+                chars.next();
+                len += 1;
+            }
+            tokens += 1 + len.saturating_sub(6).div_ceil(4);
+        } else if ch.is_ascii_digit() {
+            let mut len: usize = 1;
+            while chars.peek().is_some_and(|c| c.is_ascii_digit()) {
+                chars.next();
+                len += 1;
+            }
+            tokens += len.div_ceil(3);
+        } else if ch == '\n' {
+            // Runs of newlines merge into a single token
+            while chars.peek() == Some(&'\n') {
+                chars.next();
+            }
+            tokens += 1;
+        } else if ch.is_whitespace() {
+            // Spaces attach to the following word
+            continue;
+        } else if ch.is_ascii() {
+            tokens += 1;
+        } else {
+            // Non-latin scripts and emoji rarely merge
+            tokens += ch.len_utf8().div_ceil(2);
+        }
+    }
+
+    tokens
+}
+
+/// Whether text is estimated to go over a token limit
+pub fn exceeds_limit(text: &str, limit: usize) -> bool {
+    estimate_tokens(text) > limit
+}
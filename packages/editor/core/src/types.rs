@@ -18,29 +18,120 @@ pub enum SessionStatus {
     Archived,
 }
 
+/// An interaction as the server sends it, plus what the editor tracks about it
+///
+/// Serializes in the server's format. `content`, `needs_work`, `review_stack`
+/// and `history` aren't part of it and start empty when read from the server.
+/// The aliases keep snapshots from before the rename readable.
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
 pub struct Interaction {
     pub id: String,
     pub source: String,
+    #[serde(rename = "type", alias = "interaction_type")]
     pub interaction_type: InteractionType,
+    #[serde(default)]
     pub content: Vec<ConversationItem>,
+    #[serde(default, alias = "needs_work")]
     pub needs_work: bool,
+    #[serde(default, alias = "review_stack")]
     pub review_stack: Vec<String>,
+    #[serde(default)]
     pub history: Vec<Event>,
+    #[serde(default)]
     pub metadata: HashMap<String, serde_json::Value>,
+    #[serde(rename = "createdAt", alias = "timestamp")]
     pub timestamp: DateTime<Utc>,
 }
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
 pub enum InteractionType {
+    #[serde(alias = "Query")]
     Query,
+    #[serde(alias = "Action")]
     Action,
+    #[serde(alias = "Observation")]
     Observation,
+    #[serde(alias = "Feedback")]
     Feedback,
+    #[serde(alias = "System")]
     System,
+    #[serde(alias = "Reflection")]
     Reflection,
 }
 
+impl InteractionType {
+    pub const ALL: [InteractionType; 6] = [
+        InteractionType::Query,
+        InteractionType::Action,
+        InteractionType::Observation,
+        InteractionType::Feedback,
+        InteractionType::System,
+        InteractionType::Reflection,
+    ];
+
+    /// Parse the server's lowercase name, e.g. `"observation"`
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|t| format!("{:?}", t).eq_ignore_ascii_case(name))
+    }
+}
+
+/// Metadata key of the tool call an interaction is waiting on the user for
+pub const PENDING_PERMISSION_KEY: &str = "pendingToolPermission";
+
+/// Metadata key of why the server turned down an interaction we submitted
+pub const SUBMIT_ERROR_KEY: &str = "submitError";
+
+/// A tool call Wake wants to make, held until the user approves or denies it
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ToolPermissionRequest {
+    pub tool_name: String,
+    pub description: String,
+    #[serde(default)]
+    pub request_id: Option<String>,
+    /// The tool's arguments, e.g. the shell command or the file and edit
+    #[serde(default)]
+    pub input: Option<serde_json::Value>,
+}
+
+impl Interaction {
+    /// The tool call waiting on the user, the server clears it to null once answered
+    pub fn pending_permission(&self) -> Option<ToolPermissionRequest> {
+        serde_json::from_value(self.metadata.get(PENDING_PERMISSION_KEY)?.clone()).ok()
+    }
+
+    /// Why submitting it failed, for interactions the server never accepted
+    pub fn submit_error(&self) -> Option<&str> {
+        self.metadata.get(SUBMIT_ERROR_KEY)?.as_str()
+    }
+
+    /// What lists call it, its title or the start of its first message, its id failing both
+    pub fn title(&self) -> String {
+        let named = self
+            .metadata
+            .get("title")
+            .and_then(|title| title.as_str())
+            .map(str::trim)
+            .filter(|title| !title.is_empty());
+        let first = self
+            .content
+            .iter()
+            .find(|item| item.role == "user")
+            .and_then(|item| item.content.lines().find(|line| !line.trim().is_empty()))
+            .map(str::trim);
+
+        match named.or(first) {
+            Some(title) => title.to_string(),
+            None => self.id.chars().take(8).collect(),
+        }
+    }
+}
+
+/// One message of an interaction, reads the server's messages and ignores the fields it doesn't keep
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct ConversationItem {
     pub role: String,
@@ -49,6 +140,54 @@ pub struct ConversationItem {
     pub metadata: Option<HashMap<String, serde_json::Value>>,
 }
 
+/// `GET /interactions/:id`, an interaction with its newest messages
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ServerConversation {
+    pub interaction: Interaction,
+    #[serde(default)]
+    pub messages: Vec<ServerMessage>,
+    /// Older messages are left on the server, paged in with `before`
+    #[serde(default)]
+    pub has_more: bool,
+}
+
+impl ServerConversation {
+    /// The interaction with its messages as content
+    pub fn into_interaction(self) -> Interaction {
+        Interaction {
+            content: self.messages.into_iter().map(ServerMessage::into_item).collect(),
+            ..self.interaction
+        }
+    }
+}
+
+/// One message as the server keeps it, with the id and status `ConversationItem` leaves out
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ServerMessage {
+    pub id: String,
+    pub role: String,
+    pub content: String,
+    pub timestamp: DateTime<Utc>,
+    /// `pending`, `processing`, `completed`, `error` or `failed`
+    #[serde(default)]
+    pub status: String,
+    #[serde(default)]
+    pub metadata: Option<HashMap<String, serde_json::Value>>,
+}
+
+impl ServerMessage {
+    pub fn into_item(self) -> ConversationItem {
+        ConversationItem {
+            role: self.role,
+            content: self.content,
+            timestamp: self.timestamp,
+            metadata: self.metadata,
+        }
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Event {
     pub agent_id: String,
@@ -68,10 +207,77 @@ pub struct InteractionQueueStatus {
     pub analyzing: u32,
 }
 
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct InteractionDraft {
     pub content: String,
     pub interaction_type: InteractionType,
     pub review_stack: Vec<String>,
     pub metadata: HashMap<String, serde_json::Value>,
-}
\ No newline at end of file
+}
+
+impl Default for InteractionDraft {
+    fn default() -> Self {
+        Self {
+            content: String::new(),
+            interaction_type: InteractionType::Query,
+            review_stack: vec!["user".to_string()], // User reviews by default
+            metadata: HashMap::new(),
+        }
+    }
+}
+
+/// A git worktree the server made for a branch of work
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Worktree {
+    pub id: String,
+    pub path: String,
+    pub branch: Option<String>,
+    #[serde(rename = "baseBranch")]
+    pub base_branch: Option<String>,
+    #[serde(rename = "baseCommit")]
+    pub base_commit: Option<String>,
+    pub status: WorktreeStatus,
+    #[serde(rename = "createdAt")]
+    pub created_at: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum WorktreeStatus {
+    Active,
+    Inactive,
+}
+
+/// Uncommitted files, and commits ahead of and behind the base branch
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct WorktreeGitStatus {
+    pub dirty: u32,
+    pub ahead: u32,
+    pub behind: u32,
+}
+
+/// Why a message failed, as shown in its bubble
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MessageError {
+    pub detail: String,
+    /// Whether sending it again might work
+    pub retryable: bool,
+    /// What the user can do about it
+    #[serde(default)]
+    pub guidance: Option<String>,
+}
+
+impl MessageError {
+    /// Wake failed while working on the message, another go may well succeed
+    pub fn server(detail: String) -> Self {
+        Self { detail, retryable: true, guidance: None }
+    }
+}
+
+/// A toast for the user
+#[derive(Debug, Clone, PartialEq)]
+pub enum Notification {
+    Success(String),
+    Error(String),
+    Info(String),
+}
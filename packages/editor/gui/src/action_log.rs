@@ -0,0 +1,118 @@
+use chrono::Local;
+use iced::widget::{button, column, container, row, scrollable, text, Column, Space};
+use iced::{Element, Length};
+
+use bicky_editor_core::{Action as EditorAction, EditorState};
+
+use crate::{fonts, format, scrolling, theme, BickyApp, Message};
+
+pub(crate) const PANEL_WIDTH: f32 = 320.0;
+
+// Variant names are enough to follow along, the fields can be pages long
+fn action_name(action: &EditorAction) -> String {
+    let debug = format!("{:?}", action);
+    debug.split(['(', '{', ' ']).next().unwrap_or_default().to_string()
+}
+
+// The parts of the core state worth comparing between steps
+fn summary(editor: &EditorState) -> Vec<String> {
+    vec![
+        format!("session {}", editor.session_id),
+        format!("focused {}", editor.focused_interaction.as_deref().unwrap_or("new")),
+        format!("draft {} chars, {} kept", editor.draft.content.chars().count(), editor.drafts.len()),
+        format!("{} interactions, {} archived", editor.interactions.len(), editor.archived.len()),
+        if editor.connected { "connected".to_string() } else { "disconnected".to_string() },
+        match &editor.error {
+            Some(error) => format!("error {}", error),
+            None => "no error".to_string(),
+        },
+    ]
+}
+
+impl BickyApp {
+    /// Core actions dispatched so far, with the state as of the one stepped back to
+    pub(crate) fn build_action_log_panel(&self) -> Element<'_, Message> {
+        let (rows, applied, title) = match self.state.action_log.lock() {
+            Ok(log) => {
+                let applied = log.cursor().unwrap_or(log.len());
+                let rows: Vec<(String, String)> = log
+                    .entries()
+                    .iter()
+                    .map(|entry| {
+                        (format::locale().time(entry.at.with_timezone(&Local)), action_name(&entry.action))
+                    })
+                    .collect();
+                let title = match log.cursor() {
+                    Some(cursor) => format!("{} of {}", cursor, log.len()),
+                    None => format!("live, {}", log.len()),
+                };
+                (rows, applied, title)
+            }
+            Err(_) => (Vec::new(), 0, "unavailable".to_string()),
+        };
+
+        let mut list = column![
+            row![
+                text("Action log").size(14).font(fonts::bold()),
+                Space::with_width(Length::Fill),
+                button(text("×").size(14).font(fonts::mono()))
+                    .on_press(Message::ToggleActionLog)
+                    .padding(0)
+                    .style(theme::icon_button),
+            ]
+            .align_y(iced::Alignment::Center),
+            row![
+                button(text("◀").size(12).font(fonts::mono()))
+                    .on_press_maybe((applied > 0).then_some(Message::StepActionLogBack))
+                    .padding([2, 8])
+                    .style(theme::icon_button),
+                button(text("▶").size(12).font(fonts::mono()))
+                    .on_press_maybe(self.state.inspected.is_some().then_some(Message::StepActionLogForward))
+                    .padding([2, 8])
+                    .style(theme::icon_button),
+                text(title).size(12).font(fonts::mono()).color(theme::colors().text_dim),
+            ]
+            .spacing(8)
+            .align_y(iced::Alignment::Center),
+        ]
+        .spacing(10);
+
+        // What the core state looked like after the step, while stepping through
+        if let Some(editor) = &self.state.inspected {
+            list = list.push(Column::with_children(summary(editor).into_iter().map(|line| {
+                text(line).size(11).font(fonts::mono()).color(theme::colors().text).into()
+            })).spacing(2));
+        }
+
+        if rows.is_empty() {
+            list = list.push(
+                text("Nothing dispatched yet")
+                    .size(12)
+                    .font(fonts::mono())
+                    .color(theme::colors().text_dim),
+            );
+        }
+
+        list = list.push(Column::with_children(rows.into_iter().enumerate().map(|(idx, (at, name))| {
+            // Not applied to the state being inspected
+            let color = if idx < applied { theme::colors().text } else { theme::colors().text_dim };
+            row![
+                text(at).size(11).font(fonts::mono()).color(theme::colors().text_dim),
+                text(name).size(12).font(fonts::mono()).color(color),
+            ]
+            .spacing(8)
+            .into()
+        })).spacing(4));
+
+        container(
+            scrollable(list.padding(16))
+                .direction(scrolling::scrollbar())
+                .style(theme::scrollbar)
+                .height(Length::Fill),
+        )
+            .width(Length::Fixed(PANEL_WIDTH))
+            .height(Length::Fill)
+            .style(theme::sidebar_container)
+            .into()
+    }
+}
@@ -0,0 +1,241 @@
+use chrono::{DateTime, Utc};
+use iced::widget::{button, column, container, row, scrollable, text, Space};
+use iced::{Border, Color, Element, Length, Theme};
+use iced::Task as Command;
+use std::time::Duration;
+
+use crate::agents;
+use crate::api::ApiError;
+use crate::jobs::format_elapsed;
+use crate::state::{update, Action, AppState, Conversation};
+use crate::types::{AgentHealth, AgentStatus};
+use crate::{fonts, scrolling, state, theme, BickyApp, Message};
+
+pub(crate) const PANEL_WIDTH: f32 = 280.0;
+// Often enough to explain a stalled job soon after it stalls
+const STATUS_POLL_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Whether the agent can't be making progress on anything right now
+pub fn is_down(status: &AgentStatus) -> bool {
+    matches!(status.status, AgentHealth::Crashed | AgentHealth::Restarting)
+}
+
+/// Agents on the conversation that are down, which would explain it getting stuck
+pub fn down_agents<'a>(state: &'a AppState, conv: &Conversation) -> Vec<&'a AgentStatus> {
+    conv.agents
+        .iter()
+        .filter_map(|agent_id| state.agent_statuses.get(agent_id))
+        .filter(|status| is_down(status))
+        .collect()
+}
+
+/// How long the agent has been running, `None` while it's down or the server doesn't say
+pub fn uptime(status: &AgentStatus, now: DateTime<Utc>) -> Option<Duration> {
+    if is_down(status) {
+        return None;
+    }
+    (now - status.started_at?).to_std().ok()
+}
+
+/// `agent crashed: out of memory`, for the jobs panel
+pub fn down_label(state: &AppState, status: &AgentStatus) -> String {
+    let name = agents::name(state, &status.agent_id);
+    match (status.status, &status.error) {
+        (AgentHealth::Restarting, _) => format!("{} is restarting", name),
+        (_, Some(error)) => format!("{} crashed: {}", name, error),
+        _ => format!("{} crashed", name),
+    }
+}
+
+fn health_label(health: AgentHealth) -> (&'static str, Color) {
+    let colors = theme::colors();
+    match health {
+        AgentHealth::Idle => ("Idle", colors.text_dim),
+        AgentHealth::Busy => ("Busy", colors.success),
+        AgentHealth::Crashed => ("Crashed", colors.error),
+        AgentHealth::Restarting => ("Restarting", colors.spinner),
+        AgentHealth::Unknown => ("Unknown", colors.text_dim),
+    }
+}
+
+impl BickyApp {
+    /// Ask for agent statuses every few seconds while the panel is open or a job has stalled
+    pub(crate) fn poll_agent_status(&mut self) -> Command<Message> {
+        let wanted = self.state.show_agent_health || !self.state.stalled.is_empty();
+        let due = self
+            .state
+            .agent_status_checked
+            .map_or(true, |checked| self.state.clock.since(checked) >= STATUS_POLL_INTERVAL);
+        if !wanted || !due || self.state.agents.is_empty() {
+            return Command::none();
+        }
+        self.load_agent_statuses()
+    }
+
+    fn load_agent_statuses(&mut self) -> Command<Message> {
+        update(&mut self.state, Action::AgentStatusRequested);
+        let server = self.state.active_server.clone();
+        let api = self.api_client.clone();
+        self.tasks.run(
+            "get agent statuses",
+            async move { api.get_agent_statuses().await },
+            move |result| Message::AgentStatusesLoaded(server.clone(), result)
+        )
+    }
+
+    pub(crate) fn agent_statuses_loaded(&mut self, server: String, result: Result<Vec<AgentStatus>, ApiError>) {
+        // The user moved to another server while this was in flight
+        if server != self.state.active_server {
+            return;
+        }
+        match result {
+            Ok(statuses) => update(&mut self.state, Action::AgentStatusesLoaded(statuses)),
+            Err(e) => eprintln!("[GUI] Failed to load agent statuses from {}: {}", server, e),
+        }
+    }
+
+    pub(crate) fn restart_agent(&mut self, agent_id: String) -> Command<Message> {
+        println!("[GUI] Restarting agent {}", agent_id);
+        update(&mut self.state, Action::AgentRestartRequested(agent_id.clone()));
+
+        let server = self.state.active_server.clone();
+        let api = self.api_client.clone();
+        let id = agent_id.clone();
+        self.tasks.run(
+            "restart agent",
+            async move { api.restart_agent(&id).await },
+            move |result| Message::AgentRestarted(server.clone(), agent_id.clone(), result)
+        )
+    }
+
+    pub(crate) fn agent_restarted(&mut self, server: String, agent_id: String, result: Result<AgentStatus, ApiError>) -> Command<Message> {
+        if server != self.state.active_server {
+            return Command::none();
+        }
+        match result {
+            Ok(status) => {
+                update(&mut self.state, Action::AgentStatusChanged(status));
+                Command::none()
+            }
+            Err(e) => {
+                eprintln!("[GUI] Restarting agent {} failed: {}", agent_id, e);
+                let message = format!("Couldn't restart {}: {}", agents::name(&self.state, &agent_id), e);
+                update(&mut self.state, Action::ShowNotification(state::Notification::Error(message)));
+                // Ask again rather than guess what state the restart left it in
+                self.load_agent_statuses()
+            }
+        }
+    }
+
+    pub(crate) fn build_agent_health_panel(&self) -> Element<'_, Message> {
+        let mut list = column![
+            row![
+                text("Agents").size(14).font(fonts::bold()),
+                Space::with_width(Length::Fill),
+                button(text("×").size(14).font(fonts::mono()))
+                    .on_press(Message::ToggleAgentHealth)
+                    .padding(0)
+                    .style(theme::icon_button),
+            ]
+            .align_y(iced::Alignment::Center)
+        ]
+        .spacing(10);
+
+        if self.state.agents.is_empty() {
+            list = list.push(
+                text("This server has no agents").size(12).font(fonts::mono()).color(theme::colors().text_dim)
+            );
+        }
+
+        let now = self.state.clock.utc();
+        for agent in &self.state.agents {
+            let status = self.state.agent_statuses.get(&agent.id);
+            let (label, color) = health_label(status.map_or(AgentHealth::Unknown, |status| status.status));
+            let uptime = status
+                .and_then(|status| uptime(status, now))
+                .map(|up| format!("up {}", format_elapsed(up)))
+                .unwrap_or_default();
+
+            let mut entry = column![
+                row![
+                    text(&agent.name).size(13).font(fonts::mono()),
+                    Space::with_width(Length::Fill),
+                    text(label).size(12).font(fonts::mono()).color(color),
+                ]
+                .align_y(iced::Alignment::Center),
+            ]
+            .spacing(6);
+            if !uptime.is_empty() {
+                entry = entry.push(text(uptime).size(12).font(fonts::mono()).color(theme::colors().text_dim));
+            }
+
+            // What it's working on, with a way to get there
+            let working_on = status.and_then(|status| {
+                let idx = self.state.conversations.iter().position(|conv| {
+                    state::on_active_server(&self.state, conv) && Some(&conv.id) == status.current_interaction.as_ref()
+                });
+                let task = status.current_task.as_deref();
+                match (idx, task) {
+                    (None, None) => None,
+                    (Some(idx), task) => Some((
+                        format!("#{}{}", self.state.conversations[idx].title, task.map(|t| format!(": {}", t)).unwrap_or_default()),
+                        Some(idx),
+                    )),
+                    (None, Some(task)) => Some((task.to_string(), None)),
+                }
+            });
+            if let Some((task, idx)) = working_on {
+                entry = entry.push(
+                    row![
+                        text(task).size(12).font(fonts::mono()).color(theme::colors().text_dim).width(Length::Fill),
+                    ]
+                    .push_maybe(idx.map(|idx| {
+                        button(text("Open").size(12).font(fonts::mono()))
+                            .on_press(Message::SelectConversation(idx))
+                            .padding(6)
+                            .style(theme::secondary_button)
+                    }))
+                    .spacing(6)
+                    .align_y(iced::Alignment::Center),
+                );
+            }
+            if let Some(error) = status.and_then(|status| status.error.as_ref()) {
+                entry = entry.push(text(error).size(12).font(fonts::mono()).color(theme::colors().error));
+            }
+
+            let restarting = status.is_some_and(|status| status.status == AgentHealth::Restarting);
+            entry = entry.push(
+                button(text(if restarting { "Restarting..." } else { "Restart" }).size(12).font(fonts::mono()))
+                    .on_press_maybe((!restarting).then(|| Message::RestartAgent(agent.id.clone())))
+                    .padding(6)
+                    .style(theme::secondary_button),
+            );
+
+            list = list.push(
+                container(entry)
+                    .padding(10)
+                    .width(Length::Fill)
+                    .style(|_theme: &Theme| container::Style {
+                        background: Some(iced::Background::Color(theme::colors().surface_light)),
+                        border: Border {
+                            color: theme::colors().border,
+                            width: 1.0,
+                            radius: 6.0.into(),
+                        },
+                        ..Default::default()
+                    }),
+            );
+        }
+
+        container(
+            scrollable(list.padding(16))
+                .direction(scrolling::scrollbar())
+                .style(theme::scrollbar)
+                .height(Length::Fill),
+        )
+            .width(Length::Fixed(PANEL_WIDTH))
+            .height(Length::Fill)
+            .style(theme::sidebar_container)
+            .into()
+    }
+}
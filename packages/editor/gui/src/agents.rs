@@ -0,0 +1,518 @@
+use bicky_editor_core::types::Interaction;
+use iced::widget::{button, column, container, row, text, text_input, tooltip, Space, Stack};
+use iced::{Border, Color, Element, Length, Theme};
+use iced::Task as Command;
+use serde_json::{json, Value};
+
+use crate::api::ApiError;
+use crate::state::{update, Action, AgentSelectorState, AppState, Conversation};
+use crate::types::{Agent, AgentConfig};
+use crate::{fonts, theme, BickyApp, Message};
+
+/// Agents the server says are working on the interaction, `None` when it doesn't say
+pub fn attached(interaction: &Interaction) -> Option<Vec<String>> {
+    let agents = interaction.metadata.get("attachedAgents")?.as_array()?;
+    Some(agents.iter().filter_map(|agent| agent.as_str()).map(str::to_string).collect())
+}
+
+/// The agent's name, its id when the server no longer offers it
+pub fn name<'a>(state: &'a AppState, agent_id: &'a str) -> &'a str {
+    state
+        .agents
+        .iter()
+        .find(|agent| agent.id == agent_id)
+        .map_or(agent_id, |agent| agent.name.as_str())
+}
+
+/// The request's metadata with the conversation's route
+///
+/// A chosen agent goes as `agentId`. With several agents attached and none
+/// chosen the server is asked to route, a single agent needs no routing.
+pub fn with_route(conv: &Conversation, metadata: Option<Value>) -> Option<Value> {
+    let (key, value) = match &conv.route {
+        Some(agent_id) => ("agentId", json!(agent_id)),
+        None if conv.agents.len() > 1 => ("routing", json!("auto")),
+        None => return metadata,
+    };
+    let mut metadata = metadata.unwrap_or_else(|| json!({}));
+    if let Some(object) = metadata.as_object_mut() {
+        object.insert(key.to_string(), value);
+    }
+    Some(metadata)
+}
+
+/// Who handles the interaction in turn, `None` while only the user reviews
+pub fn pipeline(state: &AppState, conv: &Conversation) -> Option<String> {
+    if conv.review_stack.len() < 2 {
+        return None;
+    }
+    let steps: Vec<&str> = conv
+        .review_stack
+        .iter()
+        .map(|step| if step == "user" { "you" } else { name(state, step) })
+        .collect();
+    Some(steps.join(" → "))
+}
+
+/// Fields of the agent configuration form
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConfigField {
+    SystemPrompt,
+    Temperature,
+    MaxTokens,
+    AllowedTools,
+}
+
+/// An agent's configuration being edited, as typed
+#[derive(Debug, Clone, PartialEq)]
+pub struct ConfigDraft {
+    pub agent_id: String,
+    pub system_prompt: String,
+    pub temperature: String,
+    pub max_tokens: String,
+    /// Comma separated, empty for every tool
+    pub allowed_tools: String,
+    pub saving: bool,
+    pub error: Option<String>,
+}
+
+impl ConfigDraft {
+    /// Starts from what the server last said about the agent
+    pub fn for_agent(agent: &Agent) -> Self {
+        let config = agent.config.clone().unwrap_or_default();
+        Self {
+            agent_id: agent.id.clone(),
+            system_prompt: config.system_prompt.unwrap_or_default(),
+            temperature: config.temperature.map(|t| t.to_string()).unwrap_or_default(),
+            max_tokens: config.max_tokens.map(|n| n.to_string()).unwrap_or_default(),
+            allowed_tools: config.allowed_tools.map(|tools| tools.join(", ")).unwrap_or_default(),
+            saving: false,
+            error: None,
+        }
+    }
+
+    pub fn set(&mut self, field: ConfigField, value: String) {
+        match field {
+            ConfigField::SystemPrompt => self.system_prompt = value,
+            ConfigField::Temperature => self.temperature = value,
+            ConfigField::MaxTokens => self.max_tokens = value,
+            ConfigField::AllowedTools => self.allowed_tools = value,
+        }
+        self.error = None;
+    }
+
+    /// Empty fields leave the setting to the server
+    pub fn build(&self) -> Result<AgentConfig, String> {
+        let system_prompt = self.system_prompt.trim();
+        let temperature = match self.temperature.trim() {
+            "" => None,
+            t => match t.parse::<f32>() {
+                Ok(t) if (0.0..=2.0).contains(&t) => Some(t),
+                _ => return Err("Temperature must be a number from 0 to 2".to_string()),
+            },
+        };
+        let max_tokens = match self.max_tokens.trim() {
+            "" => None,
+            n => match n.parse::<u32>() {
+                Ok(n) if n > 0 => Some(n),
+                _ => return Err("Max tokens must be a whole number above 0".to_string()),
+            },
+        };
+        let tools: Vec<String> = self
+            .allowed_tools
+            .split(',')
+            .map(str::trim)
+            .filter(|tool| !tool.is_empty())
+            .map(str::to_string)
+            .collect();
+
+        Ok(AgentConfig {
+            system_prompt: (!system_prompt.is_empty()).then(|| system_prompt.to_string()),
+            temperature,
+            max_tokens,
+            allowed_tools: (!tools.is_empty()).then_some(tools),
+        })
+    }
+}
+
+/// The agent's settings one per line, for its badge and the selector
+pub fn describe(config: &AgentConfig) -> Vec<String> {
+    let mut lines = Vec::new();
+    if let Some(prompt) = &config.system_prompt {
+        let first = prompt.lines().next().unwrap_or_default();
+        let ellipsis = if first.chars().count() > 60 || prompt.lines().nth(1).is_some() { "…" } else { "" };
+        lines.push(format!("Prompt: {}{}", first.chars().take(60).collect::<String>(), ellipsis));
+    }
+    if let Some(temperature) = config.temperature {
+        lines.push(format!("Temperature: {}", temperature));
+    }
+    if let Some(max_tokens) = config.max_tokens {
+        lines.push(format!("Max tokens: {}", max_tokens));
+    }
+    match &config.allowed_tools {
+        Some(tools) => lines.push(format!("Tools: {}", tools.join(", "))),
+        None => lines.push("Tools: all".to_string()),
+    }
+    lines
+}
+
+impl BickyApp {
+    /// Fetch the agents the active server offers, servers without them return none
+    pub(crate) fn load_agents(&self) -> Command<Message> {
+        let server = self.state.active_server.clone();
+        let api = self.api_client.clone();
+        self.tasks.run(
+            "get agents",
+            async move { api.get_agents().await },
+            move |result| Message::AgentsLoaded(server.clone(), result)
+        )
+    }
+
+    pub(crate) fn agents_loaded(&mut self, server: String, result: Result<Vec<Agent>, ApiError>) {
+        // The user moved to another server while this was in flight
+        if server != self.state.active_server {
+            return;
+        }
+        match result {
+            Ok(agents) => update(&mut self.state, Action::AgentsLoaded(agents)),
+            Err(e) => eprintln!("[GUI] Failed to load agents from {}: {}", server, e),
+        }
+    }
+
+    /// Attach the agent to the selector's conversation, or detach it if it's attached
+    pub(crate) fn toggle_agent(&mut self, agent_id: String) -> Command<Message> {
+        let Some(selector) = &self.state.agent_selector else {
+            return Command::none();
+        };
+        if selector.pending.is_some() {
+            return Command::none();
+        }
+        let Some(conv) = self.state.conversations.get(selector.conversation_idx) else {
+            return Command::none();
+        };
+
+        let attach = !conv.agents.contains(&agent_id);
+        let server = conv.server.clone();
+        let conversation_id = conv.id.clone();
+        let api = self.api_for(&server);
+        println!(
+            "[GUI] {} agent {} {} {}",
+            if attach { "Attaching" } else { "Detaching" },
+            agent_id,
+            if attach { "to" } else { "from" },
+            conversation_id
+        );
+        update(&mut self.state, Action::AgentChangeRequested(agent_id.clone()));
+
+        let id = conversation_id.clone();
+        self.tasks.run(
+            if attach { "attach agent" } else { "detach agent" },
+            async move {
+                if attach {
+                    api.attach_agent(&id, &agent_id).await
+                } else {
+                    api.detach_agent(&id, &agent_id).await
+                }
+            },
+            move |result| Message::AgentsChanged(server.clone(), conversation_id.clone(), result)
+        )
+    }
+
+    pub(crate) fn agents_changed(&mut self, server: String, conversation_id: String, result: Result<Vec<String>, ApiError>) {
+        match result {
+            Ok(agents) => update(&mut self.state, Action::AgentsAttached { server, conversation_id, agents }),
+            Err(e) => {
+                eprintln!("[GUI] Changing agents on {} failed: {}", conversation_id, e);
+                update(&mut self.state, Action::AgentChangeFailed(e.to_string()));
+            }
+        }
+    }
+
+    /// Send the edited configuration to the server
+    pub(crate) fn save_agent_config(&mut self) -> Command<Message> {
+        let Some(draft) = &self.state.agent_config else {
+            return Command::none();
+        };
+        if draft.saving {
+            return Command::none();
+        }
+        let config = match draft.build() {
+            Ok(config) => config,
+            Err(e) => {
+                update(&mut self.state, Action::AgentConfigFailed(e));
+                return Command::none();
+            }
+        };
+
+        let server = self.state.active_server.clone();
+        let agent_id = draft.agent_id.clone();
+        let api = self.api_client.clone();
+        println!("[GUI] Updating configuration of agent {}", agent_id);
+        update(&mut self.state, Action::AgentConfigRequested);
+
+        let id = agent_id.clone();
+        self.tasks.run(
+            "update agent config",
+            async move { api.update_agent_config(&id, &config).await },
+            move |result| Message::AgentConfigSaved(server.clone(), agent_id.clone(), result)
+        )
+    }
+
+    pub(crate) fn agent_config_saved(&mut self, server: String, agent_id: String, result: Result<AgentConfig, ApiError>) {
+        match result {
+            Ok(config) => update(&mut self.state, Action::AgentConfigSaved { server, agent_id, config }),
+            Err(e) => {
+                eprintln!("[GUI] Updating configuration of agent {} failed: {}", agent_id, e);
+                update(&mut self.state, Action::AgentConfigFailed(e.to_string()));
+            }
+        }
+    }
+
+    /// The conversation's agents as badges, with the button that opens the selector
+    pub(crate) fn build_agent_badges(&self, conv: &Conversation) -> Option<Element<'_, Message>> {
+        if self.state.agents.is_empty() && conv.agents.is_empty() {
+            return None;
+        }
+        let idx = self.state.conversations.iter().position(|c| c.server == conv.server && c.id == conv.id)?;
+
+        let mut badges = row![].spacing(6).align_y(iced::Alignment::Center);
+        if let Some(pipeline) = pipeline(&self.state, conv) {
+            badges = badges.push(text(pipeline).size(11).font(fonts::mono()).color(theme::colors().text_dim));
+        }
+        for agent_id in &conv.agents {
+            let badge = container(text(name(&self.state, agent_id).to_string()).size(11).font(fonts::mono()))
+                .padding([2, 8])
+                .style(|_theme: &Theme| container::Style {
+                    background: Some(iced::Background::Color(theme::colors().surface_light)),
+                    border: Border {
+                        color: theme::colors().border,
+                        width: 1.0,
+                        radius: 10.0.into(),
+                    },
+                    ..Default::default()
+                });
+            let config = self
+                .state
+                .agents
+                .iter()
+                .find(|agent| &agent.id == agent_id)
+                .and_then(|agent| agent.config.as_ref());
+            match config {
+                Some(config) => {
+                    let details = container(text(describe(config).join("\n")).size(11).font(fonts::mono()))
+                        .padding(8)
+                        .style(|_theme: &Theme| container::Style {
+                            background: Some(iced::Background::Color(theme::colors().surface)),
+                            border: Border {
+                                color: theme::colors().border,
+                                width: 1.0,
+                                radius: 4.0.into(),
+                            },
+                            ..Default::default()
+                        });
+                    badges = badges.push(tooltip(badge, details, tooltip::Position::Bottom));
+                }
+                None => badges = badges.push(badge),
+            }
+        }
+        if !self.state.agents.is_empty() {
+            badges = badges.push(
+                button(text("Agents").size(12).font(fonts::mono()))
+                    .on_press(Message::OpenAgentSelector(idx))
+                    .padding(6)
+                    .style(theme::secondary_button),
+            );
+        }
+        Some(badges.into())
+    }
+
+    /// Which agent handles the next message, above the composer when several are attached
+    pub(crate) fn build_route_selector(&self) -> Element<'_, Message> {
+        let Some((idx, conv)) = self
+            .state
+            .active_conversation
+            .and_then(|idx| Some((idx, self.state.conversations.get(idx)?)))
+            .filter(|(_, conv)| conv.agents.len() > 1)
+        else {
+            return Space::with_height(0).into();
+        };
+
+        let choice = |label: String, agent_id: Option<String>| {
+            let style = if conv.route == agent_id { theme::primary_button } else { theme::secondary_button };
+            button(text(label).size(12).font(fonts::mono()))
+                .on_press(Message::RouteTo(idx, agent_id))
+                .padding([4, 10])
+                .style(style)
+                .into()
+        };
+        let mut choices = vec![text("Send to").size(12).font(fonts::mono()).color(theme::colors().text_dim).into()];
+        choices.push(choice("Auto".to_string(), None));
+        for agent_id in &conv.agents {
+            choices.push(choice(name(&self.state, agent_id).to_string(), Some(agent_id.clone())));
+        }
+        row(choices).spacing(6).align_y(iced::Alignment::Center).into()
+    }
+
+    pub(crate) fn build_agent_selector<'a>(&'a self, selector: &'a AgentSelectorState, main_view: Element<'a, Message>) -> Element<'a, Message> {
+        let attached = self
+            .state
+            .conversations
+            .get(selector.conversation_idx)
+            .map(|conv| conv.agents.as_slice())
+            .unwrap_or_default();
+        let title = self
+            .state
+            .conversations
+            .get(selector.conversation_idx)
+            .map_or("Agents".to_string(), |conv| format!("Agents on {}", conv.title));
+
+        let mut list = column![].spacing(4);
+        for agent in &self.state.agents {
+            let is_attached = attached.contains(&agent.id);
+            let pending = selector.pending.as_ref() == Some(&agent.id);
+            let action = match (pending, is_attached) {
+                (true, _) => "Working...",
+                (false, true) => "Detach",
+                (false, false) => "Attach",
+            };
+            let mut details = column![text(&agent.name).size(14).font(fonts::mono())].spacing(2);
+            if let Some(description) = &agent.description {
+                details = details.push(text(description).size(12).font(fonts::mono()).color(theme::colors().text_dim));
+            }
+            if let Some(config) = &agent.config {
+                details = details.push(text(describe(config).join(" · ")).size(11).font(fonts::mono()).color(theme::colors().text_dim));
+            }
+            list = list.push(
+                row![
+                    text(if is_attached { "●" } else { "○" }).size(12).font(fonts::mono()),
+                    details,
+                    Space::with_width(Length::Fill),
+                    button(text("Configure").size(12).font(fonts::mono()))
+                        .on_press(Message::OpenAgentConfig(agent.id.clone()))
+                        .padding(6)
+                        .style(theme::secondary_button),
+                    button(text(action).size(12).font(fonts::mono()))
+                        .on_press_maybe(selector.pending.is_none().then(|| Message::ToggleAgent(agent.id.clone())))
+                        .padding(6)
+                        .style(if is_attached { theme::secondary_button } else { theme::primary_button }),
+                ]
+                .spacing(10)
+                .align_y(iced::Alignment::Center),
+            );
+        }
+
+        let mut content = column![text(title).size(18).font(fonts::bold()), Space::with_height(12), list].spacing(8);
+        if let Some(error) = &selector.error {
+            content = content.push(text(error).size(13).font(fonts::mono()).color(theme::colors().error));
+        }
+        content = content.push(Space::with_height(12)).push(
+            button(text("Done").size(14).font(fonts::mono()))
+                .on_press(Message::CloseAgentSelector)
+                .padding(10)
+                .style(theme::secondary_button),
+        );
+
+        let dialog_content = container(content.padding(30).width(460))
+            .style(|_theme: &Theme| container::Style {
+                background: Some(iced::Background::Color(theme::colors().background)),
+                border: Border {
+                    color: theme::colors().border,
+                    width: 1.0,
+                    radius: 8.0.into(),
+                },
+                ..Default::default()
+            })
+            .center_x(Length::Fill)
+            .center_y(Length::Fill);
+
+        Stack::new()
+            .push(main_view)
+            .push(
+                container(dialog_content)
+                    .width(Length::Fill)
+                    .height(Length::Fill)
+                    .style(|_: &Theme| container::Style {
+                        background: Some(iced::Background::Color(Color::from_rgba(0.0, 0.0, 0.0, 0.7))),
+                        ..Default::default()
+                    }),
+            )
+            .width(Length::Fill)
+            .height(Length::Fill)
+            .into()
+    }
+
+    pub(crate) fn build_agent_config<'a>(&'a self, draft: &'a ConfigDraft, main_view: Element<'a, Message>) -> Element<'a, Message> {
+        let field = |label: &'a str, placeholder: &'a str, value: &'a str, field: ConfigField| {
+            column![
+                text(label).size(12).font(fonts::mono()).color(theme::colors().text_dim),
+                text_input(placeholder, value)
+                    .on_input(move |value| Message::AgentConfigEdited(field, value))
+                    .on_submit(Message::SaveAgentConfig)
+                    .padding(8)
+                    .size(13)
+                    .font(fonts::mono())
+                    .style(theme::input_style),
+            ]
+            .spacing(4)
+        };
+
+        let mut content = column![
+            text(format!("Configure {}", name(&self.state, &draft.agent_id))).size(18).font(fonts::bold()),
+            text("Empty fields keep the server's defaults").size(12).font(fonts::mono()).color(theme::colors().text_dim),
+            Space::with_height(8),
+            field("System prompt", "You are a careful reviewer...", &draft.system_prompt, ConfigField::SystemPrompt),
+            row![
+                field("Temperature", "0 to 2", &draft.temperature, ConfigField::Temperature),
+                field("Max tokens", "4096", &draft.max_tokens, ConfigField::MaxTokens),
+            ]
+            .spacing(12),
+            field("Allowed tools", "Comma separated, empty for all", &draft.allowed_tools, ConfigField::AllowedTools),
+        ]
+        .spacing(10);
+        if let Some(error) = &draft.error {
+            content = content.push(text(error).size(13).font(fonts::mono()).color(theme::colors().error));
+        }
+        content = content.push(Space::with_height(8)).push(
+            row![
+                Space::with_width(Length::Fill),
+                button(text("Cancel").size(14).font(fonts::mono()))
+                    .on_press(Message::CloseAgentConfig)
+                    .padding(10)
+                    .style(theme::secondary_button),
+                button(text(if draft.saving { "Saving..." } else { "Save" }).size(14).font(fonts::mono()))
+                    .on_press_maybe((!draft.saving).then_some(Message::SaveAgentConfig))
+                    .padding(10)
+                    .style(theme::primary_button),
+            ]
+            .spacing(10),
+        );
+
+        let dialog_content = container(content.padding(30).width(520))
+            .style(|_theme: &Theme| container::Style {
+                background: Some(iced::Background::Color(theme::colors().background)),
+                border: Border {
+                    color: theme::colors().border,
+                    width: 1.0,
+                    radius: 8.0.into(),
+                },
+                ..Default::default()
+            })
+            .center_x(Length::Fill)
+            .center_y(Length::Fill);
+
+        Stack::new()
+            .push(main_view)
+            .push(
+                container(dialog_content)
+                    .width(Length::Fill)
+                    .height(Length::Fill)
+                    .style(|_: &Theme| container::Style {
+                        background: Some(iced::Background::Color(Color::from_rgba(0.0, 0.0, 0.0, 0.7))),
+                        ..Default::default()
+                    }),
+            )
+            .width(Length::Fill)
+            .height(Length::Fill)
+            .into()
+    }
+}
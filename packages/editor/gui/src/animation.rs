@@ -0,0 +1,218 @@
+use iced::widget::container;
+use iced::{Element, Length, Padding};
+use std::collections::HashMap;
+use std::hash::Hash;
+use std::time::{Duration, Instant};
+
+use crate::{action_log, agent_health, diff_view, jobs, permission_audit, permission_policy, worktrees, BickyApp, Message};
+
+const ENTRANCE_DURATION: Duration = Duration::from_millis(220);
+const PANEL_DURATION: Duration = Duration::from_millis(180);
+// How far new messages slide in from
+const ENTRANCE_OFFSET: f32 = 16.0;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Easing {
+    EaseOut,
+    EaseInOut,
+}
+
+impl Easing {
+    fn apply(self, t: f32) -> f32 {
+        match self {
+            Easing::EaseOut => 1.0 - (1.0 - t).powi(3),
+            Easing::EaseInOut if t < 0.5 => 4.0 * t * t * t,
+            Easing::EaseInOut => 1.0 - (-2.0 * t + 2.0).powi(3) / 2.0,
+        }
+    }
+}
+
+/// A value moving from one number to another over time
+#[derive(Debug, Clone)]
+pub struct Tween {
+    from: f32,
+    to: f32,
+    start: Instant,
+    duration: Duration,
+    easing: Easing,
+}
+
+impl Tween {
+    pub fn value_at(&self, now: Instant) -> f32 {
+        let elapsed = now.saturating_duration_since(self.start).as_secs_f32();
+        let t = (elapsed / self.duration.as_secs_f32().max(f32::EPSILON)).min(1.0);
+        self.from + (self.to - self.from) * self.easing.apply(t)
+    }
+
+    pub fn is_done(&self, now: Instant) -> bool {
+        now.saturating_duration_since(self.start) >= self.duration
+    }
+}
+
+/// Running tweens by key, finished ones are dropped on `prune`
+#[derive(Debug)]
+pub struct Timeline<K> {
+    tweens: HashMap<K, Tween>,
+}
+
+impl<K> Default for Timeline<K> {
+    fn default() -> Self {
+        Self { tweens: HashMap::new() }
+    }
+}
+
+impl<K: Eq + Hash> Timeline<K> {
+    /// Start moving towards `to` at `now`, from wherever the key is or `from` if it's idle
+    pub fn animate(&mut self, key: K, from: f32, to: f32, duration: Duration, easing: Easing, now: Instant) {
+        let from = self.tweens.get(&key).map_or(from, |tween| tween.value_at(now));
+        self.tweens.insert(key, Tween { from, to, start: now, duration, easing });
+    }
+
+    /// Where the key is at `now`, `None` when it isn't animating
+    pub fn value(&self, key: &K, now: Instant) -> Option<f32> {
+        self.tweens.get(key).map(|tween| tween.value_at(now))
+    }
+
+    pub fn prune(&mut self, now: Instant) {
+        self.tweens.retain(|_, tween| !tween.is_done(now));
+    }
+}
+
+/// Side panels that slide open and closed
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Panel {
+    Jobs,
+    AgentHealth,
+    Worktrees,
+    Diff,
+    PermissionRules,
+    PermissionAudit,
+    ActionLog,
+}
+
+impl Panel {
+    const ALL: [Panel; 7] = [
+        Panel::Jobs,
+        Panel::AgentHealth,
+        Panel::Worktrees,
+        Panel::Diff,
+        Panel::PermissionRules,
+        Panel::PermissionAudit,
+        Panel::ActionLog,
+    ];
+
+    fn width(self) -> f32 {
+        match self {
+            Panel::Jobs => jobs::PANEL_WIDTH,
+            Panel::AgentHealth => agent_health::PANEL_WIDTH,
+            Panel::Worktrees => worktrees::PANEL_WIDTH,
+            Panel::Diff => diff_view::PANEL_WIDTH,
+            Panel::PermissionRules => permission_policy::PANEL_WIDTH,
+            Panel::PermissionAudit => permission_audit::PANEL_WIDTH,
+            Panel::ActionLog => action_log::PANEL_WIDTH,
+        }
+    }
+}
+
+/// Everything in the UI that can be animated
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Track {
+    Panel(Panel),
+    /// A message, or its response, arriving in a conversation
+    Entrance { conversation: String, index: usize, response: bool },
+}
+
+/// What was on screen last tick, to tell what's new
+#[derive(Debug, Default)]
+pub struct Motion {
+    pub timeline: Timeline<Track>,
+    panels_open: HashMap<Panel, bool>,
+    // Whether each message had a response, by conversation
+    shown: HashMap<String, Vec<bool>>,
+}
+
+impl BickyApp {
+    fn panel_open(&self, panel: Panel) -> bool {
+        match panel {
+            Panel::Jobs => self.state.show_jobs,
+            Panel::AgentHealth => self.state.show_agent_health,
+            Panel::Worktrees => self.state.show_worktrees,
+            Panel::Diff => self.state.diff_view.is_some(),
+            Panel::PermissionRules => self.state.show_permission_rules,
+            Panel::PermissionAudit => self.state.show_permission_audit,
+            Panel::ActionLog => self.state.show_action_log,
+        }
+    }
+
+    /// Start animations for panels that toggled and messages that arrived since the last tick
+    pub(crate) fn update_motion(&mut self) {
+        let now = self.state.clock.instant();
+        self.motion.timeline.prune(now);
+        let animate = !self.state.settings.appearance.reduced_motion;
+
+        for panel in Panel::ALL {
+            let open = self.panel_open(panel);
+            let was_open = self.motion.panels_open.insert(panel, open).unwrap_or(open);
+            if animate && open != was_open {
+                let (from, to) = if open { (0.0, 1.0) } else { (1.0, 0.0) };
+                self.motion.timeline.animate(Track::Panel(panel), from, to, PANEL_DURATION, Easing::EaseInOut, now);
+            }
+        }
+
+        let Some(conv) = self.state.active_conversation.and_then(|idx| self.state.conversations.get(idx)) else {
+            return;
+        };
+        let responded: Vec<bool> = conv.messages.iter().map(|msg| msg.response.is_some()).collect();
+
+        // Conversations seen for the first time, e.g. on switching, show up as they are
+        if let Some(previous) = self.motion.shown.get(&conv.id).filter(|_| animate) {
+            for (index, has_response) in responded.iter().enumerate() {
+                let had_message = index < previous.len();
+                let had_response = previous.get(index).copied().unwrap_or(false);
+                for (response, arrived) in [(false, !had_message), (true, *has_response && !had_response)] {
+                    if arrived {
+                        let track = Track::Entrance { conversation: conv.id.clone(), index, response };
+                        self.motion.timeline.animate(track, 0.0, 1.0, ENTRANCE_DURATION, Easing::EaseOut, now);
+                    }
+                }
+            }
+        }
+
+        self.motion.shown.insert(conv.id.clone(), responded);
+    }
+
+    /// How far a message part has come in, 1.0 once settled
+    pub(crate) fn entrance(&self, conversation: &str, index: usize, response: bool) -> f32 {
+        let track = Track::Entrance { conversation: conversation.to_string(), index, response };
+        self.motion.timeline.value(&track, self.state.clock.instant()).unwrap_or(1.0)
+    }
+
+    /// Slide a message part in from the side while it arrives
+    pub(crate) fn slide_in<'a>(&self, content: impl Into<Element<'a, Message>>, progress: f32) -> Element<'a, Message> {
+        if progress >= 1.0 {
+            return content.into();
+        }
+        container(content)
+            .padding(Padding { left: ENTRANCE_OFFSET * (1.0 - progress), ..Padding::ZERO })
+            .into()
+    }
+
+    /// A side panel at its animated width, `None` once fully closed
+    pub(crate) fn animated_panel<'a>(&self, panel: Panel, build: impl FnOnce() -> Element<'a, Message>) -> Option<Element<'a, Message>> {
+        let open = if self.panel_open(panel) { 1.0 } else { 0.0 };
+        let progress = self.motion.timeline.value(&Track::Panel(panel), self.state.clock.instant()).unwrap_or(open);
+        if progress <= 0.0 {
+            return None;
+        }
+        if progress >= 1.0 {
+            return Some(build());
+        }
+        Some(
+            container(build())
+                .width(Length::Fixed(panel.width() * progress))
+                .height(Length::Fill)
+                .clip(true)
+                .into(),
+        )
+    }
+}
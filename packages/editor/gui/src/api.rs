@@ -1,141 +1,839 @@
 use crate::types::*;
-use reqwest::Client;
+use bicky_editor_core::clock::{self, Clock, ClockSkew, SystemClock};
+use bicky_editor_core::types::{InteractionQueueStatus, ServerConversation, Session};
+use chrono::{DateTime, Utc};
+use reqwest::{Client, RequestBuilder};
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::{Arc, Mutex, OnceLock};
+use std::time::Duration;
+use tokio::sync::{OwnedSemaphorePermit, Semaphore};
 
+use crate::config::RequestLimits;
+
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(5);
+// Long enough for merges and pushes, short enough to notice a hung server
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(120);
+// Idle connections kept for the next request, the local server is hit every few seconds
+const POOL_IDLE_TIMEOUT: Duration = Duration::from_secs(90);
+const POOL_MAX_IDLE_PER_HOST: usize = 16;
+// Probes that notice a server gone without closing the socket, event streams sit idle for long
+const KEEP_ALIVE_INTERVAL: Duration = Duration::from_secs(30);
+// Error pages can be whole HTML documents, keep what fits in a message
+const MAX_ERROR_BODY: usize = 300;
+
+/// Why a request to the server failed
+#[derive(Debug, Clone, PartialEq)]
+pub enum ApiError {
+    /// The server couldn't be reached
+    Network(String),
+    /// The server didn't answer in time
+    Timeout,
+    /// An error status the server didn't explain
+    Http { status: u16, body: String },
+    /// The response wasn't what we expected
+    Decode(String),
+    /// The server explained why it refused
+    Server { code: u16, message: String },
+    /// The server wants an API key we don't have or didn't accept
+    Unauthorized,
+}
+
+impl ApiError {
+    /// Whether the same request might go through if sent again
+    pub fn is_retryable(&self) -> bool {
+        match self {
+            ApiError::Network(_) | ApiError::Timeout => true,
+            ApiError::Http { status: code, .. } | ApiError::Server { code, .. } => {
+                *code >= 500 || *code == 408 || *code == 429
+            }
+            ApiError::Decode(_) | ApiError::Unauthorized => false,
+        }
+    }
+
+    /// What the user can do about it
+    pub fn guidance(&self) -> &'static str {
+        match self {
+            ApiError::Network(_) => "Check that the Bicamrl server is running",
+            ApiError::Timeout => "The server is busy or stuck, try again in a moment",
+            ApiError::Decode(_) => "The server and GUI may be out of date with each other",
+            ApiError::Unauthorized => "Enter the server's API key to continue",
+            _ if self.is_retryable() => "The server had a problem, try again",
+            ApiError::Http { status: 404, .. } | ApiError::Server { code: 404, .. } => "It may have been deleted elsewhere",
+            _ => "The server refused the request",
+        }
+    }
+}
+
+impl fmt::Display for ApiError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ApiError::Network(e) => write!(f, "Couldn't reach the server: {}", e),
+            ApiError::Timeout => write!(f, "The server took too long to respond"),
+            ApiError::Http { status, body } if body.trim().is_empty() => write!(f, "Server returned {}", status),
+            ApiError::Http { status, body } => write!(f, "Server returned {}: {}", status, body.trim()),
+            ApiError::Decode(e) => write!(f, "Unexpected response from the server: {}", e),
+            ApiError::Server { message, .. } => write!(f, "{}", message),
+            ApiError::Unauthorized => write!(f, "The server didn't accept the API key"),
+        }
+    }
+}
+
+impl From<ApiError> for MessageError {
+    fn from(e: ApiError) -> Self {
+        Self {
+            detail: e.to_string(),
+            retryable: e.is_retryable(),
+            guidance: Some(e.guidance().to_string()),
+        }
+    }
+}
+
+impl From<reqwest::Error> for ApiError {
+    fn from(e: reqwest::Error) -> Self {
+        if e.is_timeout() {
+            ApiError::Timeout
+        } else if e.is_decode() {
+            ApiError::Decode(e.to_string())
+        } else {
+            ApiError::Network(e.to_string())
+        }
+    }
+}
+
+/// API key sent with every request
+///
+/// Clones share the key, so one entered after a 401 reaches every task holding a client.
+#[derive(Debug, Clone, Default)]
+pub struct Credentials(Arc<Mutex<Option<String>>>);
+
+impl Credentials {
+    pub fn set(&self, api_key: Option<String>) {
+        if let Ok(mut key) = self.0.lock() {
+            *key = api_key.filter(|k| !k.trim().is_empty());
+        }
+    }
+
+    pub fn is_set(&self) -> bool {
+        self.0.lock().map(|key| key.is_some()).unwrap_or(false)
+    }
+
+    /// Attach the key as a bearer token, servers without auth ignore it
+    pub fn authorize(&self, request: RequestBuilder) -> RequestBuilder {
+        match self.0.lock().ok().and_then(|key| key.clone()) {
+            Some(key) => request.bearer_auth(key.trim()),
+            None => request,
+        }
+    }
+}
+
+/// Which queue a request waits in
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Lane {
+    /// The user is waiting on it
+    Interactive,
+    /// Refreshes and refetches, they can't hold up interactive ones
+    Background,
+}
+
+/// How busy a lane is, shown in the debug overlay
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct LaneStats {
+    pub limit: usize,
+    pub in_flight: usize,
+    pub queued: usize,
+    /// Most requests ever waiting at once
+    pub peak_queued: usize,
+    /// Requests that had to wait for a slot
+    pub waited: u64,
+    pub longest_wait: Duration,
+}
+
+struct LaneSlots {
+    semaphore: Arc<Semaphore>,
+    stats: Mutex<LaneStats>,
+}
+
+impl LaneSlots {
+    fn new(limit: usize) -> Arc<Self> {
+        // A limit of 0 would never let anything through
+        let limit = limit.max(1);
+        Arc::new(Self {
+            semaphore: Arc::new(Semaphore::new(limit)),
+            stats: Mutex::new(LaneStats { limit, ..Default::default() }),
+        })
+    }
+
+    fn stats(&self, change: impl FnOnce(&mut LaneStats)) {
+        if let Ok(mut stats) = self.stats.lock() {
+            change(&mut stats);
+        }
+    }
+}
+
+/// Caps on requests in flight, one semaphore per lane
+///
+/// Clones share the slots, so every task holding a client waits in the same queue.
+#[derive(Clone)]
+pub struct Limiter {
+    interactive: Arc<LaneSlots>,
+    background: Arc<LaneSlots>,
+    // Times how long requests wait for a slot
+    clock: Arc<dyn Clock>,
+}
+
+/// A slot in a lane, given back when dropped
+pub struct Permit {
+    _permit: OwnedSemaphorePermit,
+    lane: Arc<LaneSlots>,
+}
+
+impl Drop for Permit {
+    fn drop(&mut self) {
+        self.lane.stats(|stats| stats.in_flight -= 1);
+    }
+}
+
+// Counts a request as queued until it's dropped, a cancelled task stops waiting too
+struct Waiting(Arc<LaneSlots>);
+
+impl Drop for Waiting {
+    fn drop(&mut self) {
+        self.0.stats(|stats| stats.queued -= 1);
+    }
+}
+
+impl Limiter {
+    pub fn new(limits: &RequestLimits) -> Self {
+        Self {
+            interactive: LaneSlots::new(limits.interactive),
+            background: LaneSlots::new(limits.background),
+            clock: Arc::new(SystemClock),
+        }
+    }
+
+    /// The same slots, waits timed on `clock`
+    pub fn with_clock(mut self, clock: Arc<dyn Clock>) -> Self {
+        self.clock = clock;
+        self
+    }
+
+    fn slots(&self, lane: Lane) -> &Arc<LaneSlots> {
+        match lane {
+            Lane::Interactive => &self.interactive,
+            Lane::Background => &self.background,
+        }
+    }
+
+    /// Wait for a slot in `lane`, hold it until the response is read
+    pub async fn acquire(&self, lane: Lane) -> Permit {
+        let slots = self.slots(lane).clone();
+        let permit = match slots.semaphore.clone().try_acquire_owned() {
+            Ok(permit) => permit,
+            Err(_) => {
+                slots.stats(|stats| {
+                    stats.queued += 1;
+                    stats.peak_queued = stats.peak_queued.max(stats.queued);
+                });
+                let waiting = Waiting(slots.clone());
+                let started = self.clock.instant();
+                let permit = slots.semaphore.clone().acquire_owned().await.expect("request slots are never closed");
+                drop(waiting);
+                slots.stats(|stats| {
+                    stats.waited += 1;
+                    stats.longest_wait = stats.longest_wait.max(self.clock.since(started));
+                });
+                permit
+            }
+        };
+        slots.stats(|stats| stats.in_flight += 1);
+        Permit { _permit: permit, lane: slots }
+    }
+
+    pub fn stats(&self, lane: Lane) -> LaneStats {
+        self.slots(lane).stats.lock().map(|stats| *stats).unwrap_or_default()
+    }
+}
+
+/// The HTTP client every server and event stream shares, built once
+///
+/// HTTP/2 is used where the server offers it over TLS, the local server
+/// speaks HTTP/1.1 and gets pooled keep-alive connections instead.
+pub fn http() -> Client {
+    static HTTP: OnceLock<Client> = OnceLock::new();
+    HTTP.get_or_init(|| {
+        // No overall timeout, event streams stay open, requests set their own
+        Client::builder()
+            .connect_timeout(CONNECT_TIMEOUT)
+            .pool_idle_timeout(POOL_IDLE_TIMEOUT)
+            .pool_max_idle_per_host(POOL_MAX_IDLE_PER_HOST)
+            .tcp_keepalive(KEEP_ALIVE_INTERVAL)
+            .tcp_nodelay(true)
+            .http2_keep_alive_interval(KEEP_ALIVE_INTERVAL)
+            .http2_keep_alive_while_idle(true)
+            .http2_adaptive_window(true)
+            .build()
+            .unwrap_or_default()
+    })
+    .clone()
+}
+
+// Clones share the connection pool, skew estimate, credentials and request slots
+#[derive(Clone)]
 pub struct ApiClient {
     client: Client,
     pub base_url: String,
+    pub credentials: Credentials,
+    skew: Arc<Mutex<ClockSkew>>,
+    pub limiter: Limiter,
+    // Stamps requests for the skew estimate
+    clock: Arc<dyn Clock>,
 }
 
 impl ApiClient {
     pub fn new(base_url: String) -> Self {
         Self {
-            client: Client::new(),
+            client: http(),
             base_url,
+            credentials: Credentials::default(),
+            skew: Arc::new(Mutex::new(ClockSkew::new())),
+            limiter: Limiter::new(&RequestLimits::default()),
+            clock: Arc::new(SystemClock),
+        }
+    }
+
+    /// The same client with its own slots, `limits` many
+    pub fn with_limits(mut self, limits: &RequestLimits) -> Self {
+        self.limiter = Limiter::new(limits).with_clock(self.clock.clone());
+        self
+    }
+
+    /// The same client reading time from `clock`, e.g. the one the app state uses
+    pub fn with_clock(mut self, clock: Arc<dyn Clock>) -> Self {
+        self.limiter = self.limiter.with_clock(clock.clone());
+        self.clock = clock;
+        self
+    }
+
+    fn request(&self, method: reqwest::Method, url: String) -> RequestBuilder {
+        self.credentials.authorize(self.client.request(method, url)).timeout(REQUEST_TIMEOUT)
+    }
+
+    /// A long-lived GET, without the request timeout
+    pub fn stream(&self, path: &str) -> RequestBuilder {
+        self.credentials.authorize(self.client.get(format!("{}{}", self.base_url, path)))
+    }
+
+    /// Current estimate of the server's clock skew
+    pub fn clock_skew(&self) -> ClockSkew {
+        self.skew.lock().map(|skew| skew.clone()).unwrap_or_default()
+    }
+
+    // Feed the response's Date header into the skew estimate
+    fn observe_clock(&self, sent: DateTime<Utc>, resp: &reqwest::Response) {
+        let date = resp.headers().get(reqwest::header::DATE).and_then(|value| value.to_str().ok());
+        self.observe_date(sent, date);
+    }
+
+    /// Take a response's `Date` header as answered now, for a request sent at `sent`
+    pub fn observe_date(&self, sent: DateTime<Utc>, date: Option<&str>) {
+        let server_time = date.and_then(clock::parse_http_date);
+        if let (Some(server_time), Ok(mut skew)) = (server_time, self.skew.lock()) {
+            skew.observe(server_time, sent, self.clock.utc());
         }
     }
 
-    pub async fn send_message(&self, request: SendMessageRequest) -> Result<SendMessageResponse, String> {
+    pub async fn send_message(&self, request: SendMessageRequest) -> Result<SendMessageResponse, ApiError> {
+        let _slot = self.limiter.acquire(Lane::Interactive).await;
+        let sent = self.clock.utc();
         let resp = self
-            .client
-            .post(format!("{}/message", self.base_url))
+            .request(reqwest::Method::POST, format!("{}/message", self.base_url))
             .json(&request)
             .send()
-            .await
-            .map_err(|e| e.to_string())?;
+            .await?;
+        self.observe_clock(sent, &resp);
 
         if !resp.status().is_success() {
-            return Err(format!("Failed to send message: {}", resp.status()));
+            return Err(error_reason(resp).await);
         }
 
         resp.json::<SendMessageResponse>()
             .await
-            .map_err(|e| e.to_string())
+            .map_err(ApiError::from)
     }
 
-
-    pub async fn get_interactions(&self) -> Result<Vec<serde_json::Value>, String> {
+    pub async fn import_conversation(&self, request: &ImportConversationRequest) -> Result<ImportConversationResponse, ApiError> {
+        let _slot = self.limiter.acquire(Lane::Interactive).await;
+        let sent = self.clock.utc();
         let resp = self
-            .client
-            .get(format!("{}/interactions", self.base_url))
+            .request(reqwest::Method::POST, format!("{}/interactions/import", self.base_url))
+            .json(request)
             .send()
+            .await?;
+        self.observe_clock(sent, &resp);
+
+        if !resp.status().is_success() {
+            return Err(error_reason(resp).await);
+        }
+
+        resp.json::<ImportConversationResponse>()
             .await
-            .map_err(|e| e.to_string())?;
+            .map_err(ApiError::from)
+    }
+
+    pub async fn get_interactions(&self) -> Result<Vec<serde_json::Value>, ApiError> {
+        let _slot = self.limiter.acquire(Lane::Background).await;
+        let sent = self.clock.utc();
+        let resp = self
+            .request(reqwest::Method::GET, format!("{}/interactions", self.base_url))
+            .send()
+            .await?;
+        self.observe_clock(sent, &resp);
 
         if !resp.status().is_success() {
-            return Err(format!("Failed to get interactions: {}", resp.status()));
+            return Err(error_reason(resp).await);
         }
 
         resp.json::<Vec<serde_json::Value>>()
             .await
-            .map_err(|e| e.to_string())
+            .map_err(ApiError::from)
+    }
+
+    /// An interaction with its newest `limit` messages, or the ones before `before`
+    ///
+    /// `hasMore` in the response says whether older messages are left.
+    pub async fn get_interaction(&self, id: &str, limit: usize, before: Option<&str>) -> Result<ServerConversation, ApiError> {
+        let page = self.get_interaction_json(id, limit, before).await?;
+        serde_json::from_value(page).map_err(|e| ApiError::Decode(e.to_string()))
     }
 
-    pub async fn get_interaction(&self, id: &str) -> Result<serde_json::Value, String> {
+    /// The same page as the server sent it, for exports that keep every field
+    pub async fn get_interaction_json(&self, id: &str, limit: usize, before: Option<&str>) -> Result<serde_json::Value, ApiError> {
+        // Older pages are asked for by scrolling, the newest is refetched on server events
+        let lane = if before.is_some() { Lane::Interactive } else { Lane::Background };
+        let _slot = self.limiter.acquire(lane).await;
+        let sent = self.clock.utc();
+        let mut query = vec![("limit", limit.to_string())];
+        query.extend(before.map(|before| ("before", before.to_string())));
         let resp = self
-            .client
-            .get(format!("{}/interactions/{}", self.base_url, id))
+            .request(reqwest::Method::GET, format!("{}/interactions/{}", self.base_url, id))
+            .query(&query)
             .send()
-            .await
-            .map_err(|e| e.to_string())?;
+            .await?;
+        self.observe_clock(sent, &resp);
 
         if !resp.status().is_success() {
-            return Err(format!("Failed to get interaction: {}", resp.status()));
+            return Err(error_reason(resp).await);
         }
 
         resp.json::<serde_json::Value>()
             .await
-            .map_err(|e| e.to_string())
+            .map_err(ApiError::from)
     }
 
-    pub async fn respond_to_permission(&self, interaction_id: &str, approved: bool) -> Result<(), String> {
+    pub async fn respond_to_permission(&self, interaction_id: &str, approved: bool) -> Result<(), ApiError> {
         // The server expects a full result submission for permission responses
-        let result = if approved {
-            serde_json::json!({
-                "response": "Permission granted",
-                "metadata": {
-                    "permissionResponse": {
-                        "approved": true
-                    }
+        let request = SubmitResultRequest {
+            response: if approved { "Permission granted" } else { "Permission denied" }.to_string(),
+            metadata: Some(serde_json::json!({
+                "permissionResponse": {
+                    "approved": approved
                 }
-            })
-        } else {
-            serde_json::json!({
-                "response": "Permission denied",
-                "metadata": {
-                    "permissionResponse": {
-                        "approved": false
-                    }
-                }
-            })
+            })),
         };
+        self.submit_result(interaction_id, request).await
+    }
 
+    /// Post a response to an interaction as the agent working on it would
+    pub async fn submit_result(&self, interaction_id: &str, request: SubmitResultRequest) -> Result<(), ApiError> {
+        let _slot = self.limiter.acquire(Lane::Interactive).await;
         let resp = self
-            .client
-            .post(format!("{}/interactions/{}/result", self.base_url, interaction_id))
-            .json(&result)
+            .request(reqwest::Method::POST, format!("{}/interactions/{}/result", self.base_url, interaction_id))
+            .json(&request)
             .send()
-            .await
-            .map_err(|e| e.to_string())?;
+            .await?;
 
         if !resp.status().is_success() {
-            return Err(format!("Failed to respond to permission: {}", resp.status()));
+            return Err(error_reason(resp).await);
+        }
+
+        Ok(())
+    }
+
+    pub async fn interrupt_interaction(&self, interaction_id: &str) -> Result<(), ApiError> {
+        let _slot = self.limiter.acquire(Lane::Interactive).await;
+        let resp = self
+            .request(reqwest::Method::POST, format!("{}/interactions/{}/interrupt", self.base_url, interaction_id))
+            .send()
+            .await?;
+
+        if !resp.status().is_success() {
+            return Err(error_reason(resp).await);
+        }
+
+        Ok(())
+    }
+
+    pub async fn delete_interaction(&self, interaction_id: &str) -> Result<(), ApiError> {
+        let _slot = self.limiter.acquire(Lane::Interactive).await;
+        let resp = self
+            .request(reqwest::Method::DELETE, format!("{}/interactions/{}", self.base_url, interaction_id))
+            .send()
+            .await?;
+
+        // Conversations that were never sent don't exist on the server
+        if !resp.status().is_success() && resp.status() != reqwest::StatusCode::NOT_FOUND {
+            return Err(error_reason(resp).await);
         }
 
         Ok(())
     }
 
-    pub async fn get_worktrees(&self) -> Result<Vec<Worktree>, String> {
+    /// Delete a message, returns false when the server doesn't support it
+    pub async fn delete_message(&self, message_id: &str) -> Result<bool, ApiError> {
+        let _slot = self.limiter.acquire(Lane::Interactive).await;
+        let resp = self
+            .request(reqwest::Method::DELETE, format!("{}/messages/{}", self.base_url, message_id))
+            .send()
+            .await?;
+
+        match resp.status() {
+            status if status.is_success() => Ok(true),
+            reqwest::StatusCode::NOT_FOUND | reqwest::StatusCode::METHOD_NOT_ALLOWED => Ok(false),
+            _ => Err(error_reason(resp).await),
+        }
+    }
+
+    /// Sessions on the server, empty when the server doesn't have sessions
+    pub async fn get_sessions(&self) -> Result<Vec<Session>, ApiError> {
+        let _slot = self.limiter.acquire(Lane::Background).await;
+        let resp = self
+            .request(reqwest::Method::GET, format!("{}/sessions", self.base_url))
+            .send()
+            .await?;
+
+        match resp.status() {
+            status if status.is_success() => resp.json::<Vec<Session>>().await.map_err(ApiError::from),
+            reqwest::StatusCode::NOT_FOUND | reqwest::StatusCode::METHOD_NOT_ALLOWED => Ok(Vec::new()),
+            _ => Err(error_reason(resp).await),
+        }
+    }
+
+    /// Start a session, servers that only have their default session refuse
+    pub async fn create_session(&self, name: Option<&str>) -> Result<Session, ApiError> {
+        let _slot = self.limiter.acquire(Lane::Interactive).await;
         let resp = self
-            .client
-            .get(format!("{}/worktrees", self.base_url))
+            .request(reqwest::Method::POST, format!("{}/sessions", self.base_url))
+            .json(&serde_json::json!({ "name": name }))
             .send()
+            .await?;
+
+        match resp.status() {
+            status if status.is_success() => resp.json::<Session>().await.map_err(ApiError::from),
+            status @ (reqwest::StatusCode::NOT_FOUND | reqwest::StatusCode::METHOD_NOT_ALLOWED) => Err(ApiError::Server {
+                code: status.as_u16(),
+                message: "This server only has its default session".to_string(),
+            }),
+            _ => Err(error_reason(resp).await),
+        }
+    }
+
+    /// Agents the server offers, servers without agents have none
+    pub async fn get_agents(&self) -> Result<Vec<Agent>, ApiError> {
+        let _slot = self.limiter.acquire(Lane::Background).await;
+        let resp = self
+            .request(reqwest::Method::GET, format!("{}/agents", self.base_url))
+            .send()
+            .await?;
+
+        match resp.status() {
+            status if status.is_success() => resp.json::<Vec<Agent>>().await.map_err(ApiError::from),
+            reqwest::StatusCode::NOT_FOUND | reqwest::StatusCode::METHOD_NOT_ALLOWED => Ok(Vec::new()),
+            _ => Err(error_reason(resp).await),
+        }
+    }
+
+    /// Replace an interaction's tags, gives the tags the server kept
+    pub async fn update_interaction_tags(&self, interaction_id: &str, tags: &[String]) -> Result<Vec<String>, ApiError> {
+        let _slot = self.limiter.acquire(Lane::Interactive).await;
+        let resp = self
+            .request(reqwest::Method::PUT, format!("{}/interactions/{}/tags", self.base_url, interaction_id))
+            .json(&serde_json::json!({ "tags": tags }))
+            .send()
+            .await?;
+
+        if !resp.status().is_success() {
+            return Err(error_reason(resp).await);
+        }
+
+        resp.json::<InteractionTags>()
+            .await
+            .map(|kept| kept.tags)
+            .map_err(ApiError::from)
+    }
+
+    /// Put an agent to work on an interaction, gives every agent now attached
+    pub async fn attach_agent(&self, interaction_id: &str, agent_id: &str) -> Result<Vec<String>, ApiError> {
+        let _slot = self.limiter.acquire(Lane::Interactive).await;
+        let resp = self
+            .request(reqwest::Method::POST, format!("{}/interactions/{}/agents", self.base_url, interaction_id))
+            .json(&serde_json::json!({ "agentId": agent_id }))
+            .send()
+            .await?;
+
+        if !resp.status().is_success() {
+            return Err(error_reason(resp).await);
+        }
+
+        resp.json::<AttachedAgents>()
+            .await
+            .map(|attached| attached.attached_agents)
+            .map_err(ApiError::from)
+    }
+
+    /// Take an agent off an interaction, gives the agents still attached
+    pub async fn detach_agent(&self, interaction_id: &str, agent_id: &str) -> Result<Vec<String>, ApiError> {
+        let _slot = self.limiter.acquire(Lane::Interactive).await;
+        let resp = self
+            .request(reqwest::Method::DELETE, format!("{}/interactions/{}/agents/{}", self.base_url, interaction_id, agent_id))
+            .send()
+            .await?;
+
+        if !resp.status().is_success() {
+            return Err(error_reason(resp).await);
+        }
+
+        resp.json::<AttachedAgents>()
+            .await
+            .map(|attached| attached.attached_agents)
+            .map_err(ApiError::from)
+    }
+
+    /// Upload a file for a message to carry, gives the id to send it by
+    pub async fn upload_attachment(&self, file_name: &str, content_type: &str, bytes: Vec<u8>) -> Result<Attachment, ApiError> {
+        let _slot = self.limiter.acquire(Lane::Interactive).await;
+        let part = reqwest::multipart::Part::bytes(bytes)
+            .file_name(file_name.to_string())
+            .mime_str(content_type)?;
+        let resp = self
+            .request(reqwest::Method::POST, format!("{}/attachments", self.base_url))
+            .multipart(reqwest::multipart::Form::new().part("file", part))
+            .send()
+            .await?;
+
+        if !resp.status().is_success() {
+            return Err(error_reason(resp).await);
+        }
+
+        resp.json::<Attachment>().await.map_err(ApiError::from)
+    }
+
+    /// The contents of a file a tool produced
+    pub async fn download_artifact(&self, artifact_id: &str) -> Result<Vec<u8>, ApiError> {
+        let _slot = self.limiter.acquire(Lane::Interactive).await;
+        let resp = self
+            .request(reqwest::Method::GET, format!("{}/artifacts/{}", self.base_url, artifact_id))
+            .send()
+            .await?;
+
+        if !resp.status().is_success() {
+            return Err(error_reason(resp).await);
+        }
+
+        Ok(resp.bytes().await?.to_vec())
+    }
+
+    /// Change how an agent works, gives the configuration the server kept
+    pub async fn update_agent_config(&self, agent_id: &str, config: &AgentConfig) -> Result<AgentConfig, ApiError> {
+        let _slot = self.limiter.acquire(Lane::Interactive).await;
+        let resp = self
+            .request(reqwest::Method::PUT, format!("{}/agents/{}/config", self.base_url, agent_id))
+            .json(config)
+            .send()
+            .await?;
+
+        if !resp.status().is_success() {
+            return Err(error_reason(resp).await);
+        }
+
+        resp.json::<AgentConfig>().await.map_err(ApiError::from)
+    }
+
+    /// How each agent is doing, servers without agent status report none
+    pub async fn get_agent_statuses(&self) -> Result<Vec<AgentStatus>, ApiError> {
+        let _slot = self.limiter.acquire(Lane::Background).await;
+        let resp = self
+            .request(reqwest::Method::GET, format!("{}/agents/status", self.base_url))
+            .send()
+            .await?;
+
+        match resp.status() {
+            status if status.is_success() => resp.json::<Vec<AgentStatus>>().await.map_err(ApiError::from),
+            reqwest::StatusCode::NOT_FOUND | reqwest::StatusCode::METHOD_NOT_ALLOWED => Ok(Vec::new()),
+            _ => Err(error_reason(resp).await),
+        }
+    }
+
+    /// Start the agent again, gives its status once the server has begun
+    pub async fn restart_agent(&self, agent_id: &str) -> Result<AgentStatus, ApiError> {
+        let _slot = self.limiter.acquire(Lane::Interactive).await;
+        let resp = self
+            .request(reqwest::Method::POST, format!("{}/agents/{}/restart", self.base_url, agent_id))
+            .send()
+            .await?;
+
+        if !resp.status().is_success() {
+            return Err(error_reason(resp).await);
+        }
+
+        resp.json::<AgentStatus>().await.map_err(ApiError::from)
+    }
+
+    pub async fn get_session_status(&self, session_id: &str) -> Result<InteractionQueueStatus, ApiError> {
+        let _slot = self.limiter.acquire(Lane::Background).await;
+        let resp = self
+            .request(reqwest::Method::GET, format!("{}/sessions/{}/status", self.base_url, session_id))
+            .send()
+            .await?;
+
+        if !resp.status().is_success() {
+            return Err(error_reason(resp).await);
+        }
+
+        resp.json::<InteractionQueueStatus>()
             .await
-            .map_err(|e| e.to_string())?;
+            .map_err(ApiError::from)
+    }
+
+    pub async fn get_worktrees(&self) -> Result<Vec<Worktree>, ApiError> {
+        let _slot = self.limiter.acquire(Lane::Background).await;
+        let resp = self
+            .request(reqwest::Method::GET, format!("{}/worktrees", self.base_url))
+            .send()
+            .await?;
 
         if !resp.status().is_success() {
-            return Err(format!("Failed to get worktrees: {}", resp.status()));
+            return Err(error_reason(resp).await);
         }
 
         resp.json::<Vec<Worktree>>()
             .await
-            .map_err(|e| e.to_string())
+            .map_err(ApiError::from)
     }
 
-    pub async fn create_worktree(&self, request: CreateWorktreeRequest) -> Result<Worktree, String> {
+    pub async fn create_worktree(&self, request: CreateWorktreeRequest) -> Result<Worktree, ApiError> {
+        let _slot = self.limiter.acquire(Lane::Interactive).await;
         let resp = self
-            .client
-            .post(format!("{}/worktrees", self.base_url))
+            .request(reqwest::Method::POST, format!("{}/worktrees", self.base_url))
             .json(&request)
             .send()
-            .await
-            .map_err(|e| e.to_string())?;
+            .await?;
 
         if !resp.status().is_success() {
-            return Err(format!("Failed to create worktree: {}", resp.status()));
+            return Err(error_reason(resp).await);
         }
 
         resp.json::<Worktree>()
             .await
-            .map_err(|e| e.to_string())
+            .map_err(ApiError::from)
+    }
+
+    pub async fn delete_worktree(&self, worktree_id: &str) -> Result<(), ApiError> {
+        let _slot = self.limiter.acquire(Lane::Interactive).await;
+        let resp = self
+            .request(reqwest::Method::DELETE, format!("{}/worktrees/{}", self.base_url, worktree_id))
+            .send()
+            .await?;
+
+        // Already gone is as good as deleted
+        if resp.status().is_success() || resp.status() == reqwest::StatusCode::NOT_FOUND {
+            return Ok(());
+        }
+
+        // The server explains refusals, e.g. a running interaction or a dirty checkout
+        Err(error_reason(resp).await)
+    }
+
+    pub async fn get_worktree_diff(&self, worktree_id: &str) -> Result<WorktreeDiff, ApiError> {
+        let _slot = self.limiter.acquire(Lane::Interactive).await;
+        let resp = self
+            .request(reqwest::Method::GET, format!("{}/worktrees/{}/diff", self.base_url, worktree_id))
+            .send()
+            .await?;
+
+        if !resp.status().is_success() {
+            return Err(error_reason(resp).await);
+        }
+
+        resp.json::<WorktreeDiff>()
+            .await
+            .map_err(ApiError::from)
     }
-}
\ No newline at end of file
+
+    /// Git status of every active worktree, keyed by id
+    pub async fn get_worktree_statuses(&self) -> Result<HashMap<String, WorktreeGitStatus>, ApiError> {
+        let _slot = self.limiter.acquire(Lane::Background).await;
+        let resp = self
+            .request(reqwest::Method::GET, format!("{}/worktrees/status", self.base_url))
+            .send()
+            .await?;
+
+        if !resp.status().is_success() {
+            return Err(error_reason(resp).await);
+        }
+
+        resp.json::<HashMap<String, WorktreeGitStatus>>()
+            .await
+            .map_err(ApiError::from)
+    }
+
+    pub async fn merge_worktree(&self, worktree_id: &str, request: MergeWorktreeRequest) -> Result<MergeResult, ApiError> {
+        let _slot = self.limiter.acquire(Lane::Interactive).await;
+        let resp = self
+            .request(reqwest::Method::POST, format!("{}/worktrees/{}/merge", self.base_url, worktree_id))
+            .json(&request)
+            .send()
+            .await?;
+
+        if !resp.status().is_success() {
+            return Err(error_reason(resp).await);
+        }
+
+        resp.json::<MergeResult>()
+            .await
+            .map_err(ApiError::from)
+    }
+
+    pub async fn create_pull_request(&self, worktree_id: &str, request: CreatePullRequestRequest) -> Result<PullRequestResult, ApiError> {
+        let _slot = self.limiter.acquire(Lane::Interactive).await;
+        let resp = self
+            .request(reqwest::Method::POST, format!("{}/worktrees/{}/pr", self.base_url, worktree_id))
+            .json(&request)
+            .send()
+            .await?;
+
+        if !resp.status().is_success() {
+            return Err(error_reason(resp).await);
+        }
+
+        resp.json::<PullRequestResult>()
+            .await
+            .map_err(ApiError::from)
+    }
+}
+
+// The server's explanation for a failed request, git output included
+async fn error_reason(resp: reqwest::Response) -> ApiError {
+    let status = resp.status().as_u16();
+    if resp.status() == reqwest::StatusCode::UNAUTHORIZED {
+        return ApiError::Unauthorized;
+    }
+    let body = resp.text().await.unwrap_or_default();
+    match serde_json::from_str::<serde_json::Value>(&body)
+        .ok()
+        .and_then(|json| json.get("error").and_then(|e| e.as_str()).map(str::to_string))
+    {
+        Some(message) => ApiError::Server { code: status, message },
+        None => ApiError::Http { status, body: body.chars().take(MAX_ERROR_BODY).collect() },
+    }
+}
@@ -0,0 +1,68 @@
+use iced::Task as Command;
+use std::time::Duration;
+
+use crate::config::AppearanceMode;
+use crate::state::{self, Action};
+use crate::theme::{self, Appearance};
+use crate::{BickyApp, Message};
+
+// Most platforms have no change notification we can hook into, so ask now and then
+const APPEARANCE_POLL_INTERVAL: Duration = Duration::from_secs(3);
+
+impl BickyApp {
+    /// Ask the OS for its appearance every few seconds while following it
+    pub(crate) fn poll_system_appearance(&mut self) -> Command<Message> {
+        let due = self
+            .state
+            .appearance_checked
+            .map_or(true, |checked| self.state.clock.since(checked) >= APPEARANCE_POLL_INTERVAL);
+        if !due || state::appearance_mode(&self.state) != AppearanceMode::System {
+            return Command::none();
+        }
+
+        state::update(&mut self.state, Action::AppearanceCheckRequested);
+        // Detection can go over D-Bus, keep it off the UI thread
+        self.tasks.run(
+            "detect appearance",
+            async {
+                tokio::task::spawn_blocking(Appearance::detect)
+                    .await
+                    .unwrap_or(Appearance::Dark)
+            },
+            Message::SystemAppearance,
+        )
+    }
+
+    /// Apply appearance switches and theme file changes, validation problems always show as a notification
+    pub(crate) fn reload_theme(&mut self, announce: bool) {
+        let appearance = state::effective_appearance(&self.state);
+        let variant = state::palette_variant(&self.state);
+        let settings = &self.state.settings.appearance;
+        let file = match appearance {
+            Appearance::Dark => settings.dark_theme.clone(),
+            Appearance::Light => settings.light_theme.clone(),
+        };
+
+        // Only edits to the file are worth a notification, not switching palettes
+        let announce = announce && self.theme_watcher.applied() == Some((appearance, variant));
+
+        let notification = match self.theme_watcher.poll(appearance, variant, &file, self.state.clock.instant()) {
+            None => return,
+            Some(theme::ThemeReload::Applied(path)) => {
+                println!("[GUI] Applied theme from {}", path.display());
+                announce.then(|| state::Notification::Info("Theme reloaded".to_string()))
+            }
+            Some(theme::ThemeReload::Invalid(path, problems)) => {
+                eprintln!("[GUI] Problems in {}: {}", path.display(), problems.join("; "));
+                Some(state::Notification::Error(format!("{}: {}", file, problems.join("; "))))
+            }
+            Some(theme::ThemeReload::Builtin) => {
+                announce.then(|| state::Notification::Info("Theme file removed, using the default colors".to_string()))
+            }
+        };
+
+        if let Some(notification) = notification {
+            state::update(&mut self.state, Action::ShowNotification(notification));
+        }
+    }
+}
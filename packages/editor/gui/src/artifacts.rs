@@ -0,0 +1,280 @@
+use std::path::{Path, PathBuf};
+
+use iced::widget::{button, column, container, row, scrollable, text, text_input, Column, Space, Stack};
+use iced::{Border, Color, Element, Length, Theme};
+use iced::Task as Command;
+
+use crate::attachments::size_label;
+use crate::state::{update, Action, Conversation, Notification};
+use crate::types::Artifact;
+use crate::{fonts, theme, BickyApp, Message};
+
+/// Where an artifact is being saved to, while the user picks the path
+#[derive(Debug, Clone, PartialEq)]
+pub struct SaveDialog {
+    pub artifact: Artifact,
+    pub path: String,
+    pub saving: bool,
+    /// Why the last try failed
+    pub error: Option<String>,
+}
+
+/// The artifacts the conversation's tools produced, in the order they were made
+pub fn list(conv: &Conversation) -> Vec<&Artifact> {
+    let mut artifacts: Vec<&Artifact> = Vec::new();
+    for artifact in conv
+        .messages
+        .iter()
+        .filter_map(|msg| msg.metadata.as_ref()?.artifacts.as_ref())
+        .flatten()
+    {
+        // A file rewritten by a later tool call is listed once
+        if !artifacts.iter().any(|a| a.id == artifact.id) {
+            artifacts.push(artifact);
+        }
+    }
+    artifacts
+}
+
+/// The artifact's name as a file name, the server's name can't point elsewhere
+pub fn file_name(artifact: &Artifact) -> String {
+    Path::new(&artifact.name)
+        .file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .filter(|name| !name.is_empty())
+        .unwrap_or_else(|| format!("artifact-{}", artifact.id))
+}
+
+/// Where a save without asking puts it, next to earlier saves but never over them
+pub fn save_path(dir: &Path, artifact: &Artifact, exists: impl Fn(&Path) -> bool) -> PathBuf {
+    let name = file_name(artifact);
+    let path = dir.join(&name);
+    if !exists(&path) {
+        return path;
+    }
+    let (stem, extension) = match name.rsplit_once('.') {
+        Some((stem, extension)) if !stem.is_empty() => (stem.to_string(), format!(".{}", extension)),
+        _ => (name.clone(), String::new()),
+    };
+    (2..)
+        .map(|n| dir.join(format!("{} ({}){}", stem, n, extension)))
+        .find(|path| !exists(path))
+        .unwrap_or(path)
+}
+
+// Saves land with the exports unless the user picks somewhere else
+fn downloads_dir() -> PathBuf {
+    std::env::current_dir()
+        .unwrap_or_else(|_| PathBuf::from("."))
+        .join("exports")
+        .join("artifacts")
+}
+
+impl BickyApp {
+    /// Download an artifact from the server and write it to `path`
+    pub(crate) fn save_artifact(&mut self, artifact: Artifact, path: PathBuf) -> Command<Message> {
+        update(&mut self.state, Action::ArtifactSaveStarted(artifact.id.clone()));
+        let api = self.api_client.clone();
+        let id = artifact.id.clone();
+        self.tasks.run(
+            "save artifact",
+            async move {
+                let bytes = api.download_artifact(&artifact.id).await.map_err(|e| e.to_string())?;
+                if let Some(dir) = path.parent() {
+                    tokio::fs::create_dir_all(dir).await.map_err(|e| e.to_string())?;
+                }
+                tokio::fs::write(&path, bytes).await.map_err(|e| e.to_string())?;
+                Ok(path)
+            },
+            move |result| Message::ArtifactSaved(id.clone(), result),
+        )
+    }
+
+    /// Save into the downloads folder straight away
+    pub(crate) fn save_artifact_here(&mut self, artifact: Artifact) -> Command<Message> {
+        let path = save_path(&downloads_dir(), &artifact, Path::exists);
+        self.save_artifact(artifact, path)
+    }
+
+    pub(crate) fn save_artifact_as(&mut self, artifact: Artifact) -> Command<Message> {
+        let path = downloads_dir().join(file_name(&artifact)).display().to_string();
+        update(&mut self.state, Action::OpenSaveArtifact(SaveDialog { artifact, path, saving: false, error: None }));
+        Command::none()
+    }
+
+    pub(crate) fn confirm_save_artifact(&mut self) -> Command<Message> {
+        let Some(dialog) = self.state.artifact_save.as_ref().filter(|dialog| !dialog.saving && !dialog.path.trim().is_empty()) else {
+            return Command::none();
+        };
+        let path = PathBuf::from(dialog.path.trim());
+        let artifact = dialog.artifact.clone();
+        self.save_artifact(artifact, path)
+    }
+
+    pub(crate) fn artifact_saved(&mut self, id: String, result: Result<PathBuf, String>) {
+        let in_dialog = self.state.artifact_save.as_ref().is_some_and(|dialog| dialog.artifact.id == id);
+        match &result {
+            Ok(path) => {
+                println!("[GUI] Saved artifact {} to {}", id, path.display());
+                update(&mut self.state, Action::ShowNotification(Notification::Success(format!("Saved to {}", path.display()))));
+            }
+            Err(e) => {
+                eprintln!("[GUI] Failed to save artifact {}: {}", id, e);
+                // The dialog shows its own failures so the path can be fixed
+                if !in_dialog {
+                    update(&mut self.state, Action::ShowNotification(Notification::Error(format!("Couldn't save the artifact: {}", e))));
+                }
+            }
+        }
+        update(&mut self.state, Action::ArtifactSaved { id, result });
+    }
+
+    /// Switches the conversation between its transcript and its artifacts, once it has any
+    pub(crate) fn build_conversation_tabs(&self, conv: &Conversation) -> Option<Element<'_, Message>> {
+        let count = list(conv).len();
+        if count == 0 {
+            return None;
+        }
+
+        let tab = |label: String, selected: bool, show_artifacts: bool| {
+            button(text(label).size(11).font(fonts::mono()))
+                .on_press(Message::ShowArtifacts(show_artifacts))
+                .padding([4, 8])
+                .style(move |theme, _| {
+                    if selected {
+                        theme::channel_button_active(theme)
+                    } else {
+                        theme::channel_button_inactive(theme)
+                    }
+                })
+        };
+        let artifacts_tab = self.state.artifacts_tab;
+        Some(
+            container(
+                row![
+                    tab("Transcript".to_string(), !artifacts_tab, false),
+                    tab(format!("Artifacts {}", count), artifacts_tab, true),
+                ]
+                .spacing(4),
+            )
+            .padding([4, 0])
+            .into(),
+        )
+    }
+
+    /// The conversation's artifacts with what made them and ways to get them
+    pub(crate) fn build_artifact_list<'a>(&'a self, conv: &'a Conversation) -> Element<'a, Message> {
+        let mut rows = Column::new().spacing(8);
+        for artifact in list(conv) {
+            let saving = self.state.saving_artifacts.contains(&artifact.id);
+            let mut details = Vec::new();
+            if let Some(tool) = &artifact.tool {
+                details.push(format!("from {}", tool));
+            }
+            if let Some(size) = artifact.size {
+                details.push(size_label(size));
+            }
+            if let Some(content_type) = &artifact.content_type {
+                details.push(content_type.clone());
+            }
+
+            let mut about = column![text(&artifact.name).size(14).font(fonts::bold())].spacing(4);
+            if !details.is_empty() {
+                about = about.push(text(details.join(" · ")).size(11).font(fonts::mono()).color(theme::colors().text_dim));
+            }
+            if let Some(path) = &artifact.path {
+                about = about.push(text(path).size(11).font(fonts::mono()).color(theme::colors().text_dim));
+            }
+
+            let actions = if saving {
+                row![text("saving…").size(12).font(fonts::mono()).color(theme::colors().spinner)]
+            } else {
+                row![
+                    button(text("Download").size(12).font(fonts::mono()))
+                        .on_press(Message::SaveArtifact(artifact.clone()))
+                        .padding([4, 10])
+                        .style(theme::primary_button),
+                    button(text("Save as…").size(12).font(fonts::mono()))
+                        .on_press(Message::SaveArtifactAs(artifact.clone()))
+                        .padding([4, 10])
+                        .style(theme::secondary_button),
+                ]
+                .spacing(6)
+            };
+
+            rows = rows.push(
+                container(row![about, Space::with_width(Length::Fill), actions].spacing(12).align_y(iced::Alignment::Center))
+                    .padding(12)
+                    .width(Length::Fill)
+                    .style(theme::assistant_message_container),
+            );
+        }
+
+        scrollable(rows.padding([8, 0]))
+            .direction(crate::scrolling::scrollbar())
+            .style(theme::scrollbar)
+            .height(Length::Fill)
+            .into()
+    }
+
+    pub(crate) fn build_save_artifact<'a>(&'a self, dialog: &'a SaveDialog, main_view: Element<'a, Message>) -> Element<'a, Message> {
+        let mut content = column![
+            text(format!("Save {}", dialog.artifact.name)).size(18).font(fonts::bold()),
+            Space::with_height(8),
+            text("Save to").size(12).font(fonts::mono()).color(theme::colors().text_dim),
+            text_input("Path", &dialog.path)
+                .on_input(Message::SaveArtifactPathChanged)
+                .on_submit(Message::ConfirmSaveArtifact)
+                .padding(10)
+                .size(13)
+                .font(fonts::mono()),
+        ]
+        .spacing(8);
+        if let Some(error) = &dialog.error {
+            content = content.push(text(error).size(13).font(fonts::mono()).color(theme::colors().error));
+        }
+
+        let save = button(text(if dialog.saving { "Saving…" } else { "Save" }).size(14).font(fonts::mono()))
+            .on_press_maybe((!dialog.saving).then_some(Message::ConfirmSaveArtifact))
+            .padding(10)
+            .style(theme::primary_button);
+        content = content.push(Space::with_height(8)).push(
+            row![
+                save,
+                button(text("Cancel").size(14).font(fonts::mono()))
+                    .on_press(Message::CloseSaveArtifact)
+                    .padding(10)
+                    .style(theme::secondary_button),
+            ]
+            .spacing(10),
+        );
+
+        let dialog_content = container(content.padding(30).width(520))
+            .style(|_theme: &Theme| container::Style {
+                background: Some(iced::Background::Color(theme::colors().background)),
+                border: Border {
+                    color: theme::colors().border,
+                    width: 1.0,
+                    radius: 8.0.into(),
+                },
+                ..Default::default()
+            })
+            .center_x(Length::Fill)
+            .center_y(Length::Fill);
+
+        Stack::new()
+            .push(main_view)
+            .push(
+                container(dialog_content)
+                    .width(Length::Fill)
+                    .height(Length::Fill)
+                    .style(|_: &Theme| container::Style {
+                        background: Some(iced::Background::Color(Color::from_rgba(0.0, 0.0, 0.0, 0.7))),
+                        ..Default::default()
+                    }),
+            )
+            .width(Length::Fill)
+            .height(Length::Fill)
+            .into()
+    }
+}
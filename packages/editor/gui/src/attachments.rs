@@ -0,0 +1,316 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use iced::widget::{button, column, container, row, scrollable, text, Row, Space, Stack};
+use iced::{Border, Color, Element, Length, Theme};
+use iced::Task as Command;
+
+use crate::api::ApiError;
+use crate::state::{update, Action, Notification};
+use crate::types::Attachment;
+use crate::{fonts, theme, BickyApp, Message};
+
+// Bigger files are refused before anything is read
+pub const MAX_BYTES: u64 = 10 * 1024 * 1024;
+
+/// Something in the directory the file picker shows
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Entry {
+    pub name: String,
+    pub is_dir: bool,
+}
+
+/// The file picker's directory and what is in it
+#[derive(Debug, Clone, PartialEq)]
+pub struct FilePicker {
+    pub dir: PathBuf,
+    pub entries: Vec<Entry>,
+    /// Why the directory couldn't be listed
+    pub error: Option<String>,
+}
+
+impl FilePicker {
+    /// Lists `dir` now, an unreadable one shows why instead
+    pub fn open(dir: PathBuf) -> Self {
+        match list(&dir) {
+            Ok(entries) => Self { dir, entries, error: None },
+            Err(error) => Self { dir, entries: Vec::new(), error: Some(error) },
+        }
+    }
+}
+
+/// Folders then files, each by name, hidden ones left out
+pub fn list(dir: &Path) -> Result<Vec<Entry>, String> {
+    let mut entries: Vec<Entry> = fs::read_dir(dir)
+        .map_err(|e| e.to_string())?
+        .filter_map(Result::ok)
+        .filter_map(|entry| {
+            let name = entry.file_name().to_string_lossy().into_owned();
+            let is_dir = entry.file_type().ok()?.is_dir();
+            (!name.starts_with('.')).then_some(Entry { name, is_dir })
+        })
+        .collect();
+    entries.sort_by(|a, b| b.is_dir.cmp(&a.is_dir).then_with(|| a.name.to_lowercase().cmp(&b.name.to_lowercase())));
+    Ok(entries)
+}
+
+/// Where a picked file's upload is at
+#[derive(Debug, Clone, PartialEq)]
+pub enum Upload {
+    Uploading,
+    Done(Attachment),
+    Failed(String),
+}
+
+/// A file picked for the next message
+#[derive(Debug, Clone, PartialEq)]
+pub struct Pending {
+    pub path: PathBuf,
+    pub name: String,
+    pub size: u64,
+    pub upload: Upload,
+}
+
+/// What the server is told the file holds, by extension
+pub fn content_type(path: &Path) -> &'static str {
+    let extension = path.extension().and_then(|e| e.to_str()).unwrap_or_default().to_lowercase();
+    match extension.as_str() {
+        "png" => "image/png",
+        "jpg" | "jpeg" => "image/jpeg",
+        "gif" => "image/gif",
+        "svg" => "image/svg+xml",
+        "pdf" => "application/pdf",
+        "json" => "application/json",
+        "md" => "text/markdown",
+        "csv" => "text/csv",
+        "html" => "text/html",
+        "txt" | "log" | "rs" | "py" | "js" | "ts" | "toml" | "yaml" | "yml" | "sh" | "c" | "h" | "go" => "text/plain",
+        _ => "application/octet-stream",
+    }
+}
+
+/// "512 B", "1.5 KB", "3.2 MB"
+pub fn size_label(bytes: u64) -> String {
+    match bytes {
+        b if b < 1024 => format!("{} B", b),
+        b if b < 1024 * 1024 => format!("{:.1} KB", b as f64 / 1024.0),
+        b => format!("{:.1} MB", b as f64 / (1024.0 * 1024.0)),
+    }
+}
+
+/// The attachments that made it to the server, in the order they were picked
+pub fn uploaded(pending: &[Pending]) -> Vec<Attachment> {
+    pending
+        .iter()
+        .filter_map(|p| match &p.upload {
+            Upload::Done(attachment) => Some(attachment.clone()),
+            _ => None,
+        })
+        .collect()
+}
+
+/// Whether any picked file is still on its way to the server
+pub fn uploading(pending: &[Pending]) -> bool {
+    pending.iter().any(|p| p.upload == Upload::Uploading)
+}
+
+fn chip<'a>(content: Element<'a, Message>) -> Element<'a, Message> {
+    container(content)
+        .padding([2, 8])
+        .style(|_theme: &Theme| container::Style {
+            background: Some(iced::Background::Color(theme::colors().surface_light)),
+            border: Border {
+                color: theme::colors().border,
+                width: 1.0,
+                radius: 10.0.into(),
+            },
+            ..Default::default()
+        })
+        .into()
+}
+
+impl BickyApp {
+    /// Start in the current worktree, where the files worth sending usually are
+    pub(crate) fn open_file_picker(&mut self) -> Command<Message> {
+        let dir = self
+            .state
+            .editor
+            .current_worktree
+            .as_ref()
+            .map(|worktree| PathBuf::from(&worktree.path))
+            .or_else(|| std::env::current_dir().ok())
+            .unwrap_or_else(|| PathBuf::from("."));
+        update(&mut self.state, Action::OpenFilePicker(FilePicker::open(dir)));
+        Command::none()
+    }
+
+    /// Read the file and send it to the server, the chip shows how it went
+    pub(crate) fn attach_file(&mut self, path: PathBuf) -> Command<Message> {
+        let name = path.file_name().map_or_else(|| path.display().to_string(), |n| n.to_string_lossy().into_owned());
+        let size = fs::metadata(&path).map(|m| m.len()).unwrap_or_default();
+        if size > MAX_BYTES {
+            update(&mut self.state, Action::ShowNotification(Notification::Error(format!(
+                "{} is {}, attachments can be up to {}",
+                name,
+                size_label(size),
+                size_label(MAX_BYTES)
+            ))));
+            return Command::none();
+        }
+        let bytes = match fs::read(&path) {
+            Ok(bytes) => bytes,
+            Err(e) => {
+                eprintln!("[GUI] Couldn't read {}: {}", path.display(), e);
+                update(&mut self.state, Action::ShowNotification(Notification::Error(format!("Couldn't read {}: {}", name, e))));
+                return Command::none();
+            }
+        };
+
+        update(&mut self.state, Action::AttachmentPicked(Pending {
+            path: path.clone(),
+            name: name.clone(),
+            size,
+            upload: Upload::Uploading,
+        }));
+        let api = self.api_client.clone();
+        let content_type = content_type(&path);
+        self.tasks.run(
+            "upload attachment",
+            async move { api.upload_attachment(&name, content_type, bytes).await },
+            move |result| Message::AttachmentUploaded(path.clone(), result),
+        )
+    }
+
+    pub(crate) fn attachment_uploaded(&mut self, path: PathBuf, result: Result<Attachment, ApiError>) {
+        let result = result.map_err(|e| {
+            eprintln!("[GUI] Failed to upload {}: {}", path.display(), e);
+            e.to_string()
+        });
+        update(&mut self.state, Action::AttachmentUploaded { path, result });
+    }
+
+    /// The attach button and the files picked for the next message
+    pub(crate) fn build_attachment_bar(&self) -> Element<'_, Message> {
+        if self.state.active_conversation.is_none() {
+            return Space::with_height(0).into();
+        }
+
+        let mut chips = vec![button(text("📎 Attach").size(12).font(fonts::mono()))
+            .on_press(Message::OpenFilePicker)
+            .padding([4, 10])
+            .style(theme::secondary_button)
+            .into()];
+        for (idx, pending) in self.state.composer_attachments.iter().enumerate() {
+            let (status, color) = match &pending.upload {
+                Upload::Uploading => ("uploading…".to_string(), theme::colors().text_dim),
+                Upload::Done(_) => (size_label(pending.size), theme::colors().text_dim),
+                Upload::Failed(error) => (format!("failed: {}", error), theme::colors().error),
+            };
+            chips.push(chip(
+                row![
+                    text(&pending.name).size(11).font(fonts::mono()),
+                    text(status).size(11).font(fonts::mono()).color(color),
+                    button(text("×").size(11).font(fonts::mono()))
+                        .on_press(Message::RemoveAttachment(idx))
+                        .padding(0)
+                        .style(theme::icon_button),
+                ]
+                .spacing(6)
+                .align_y(iced::Alignment::Center)
+                .into(),
+            ));
+        }
+        Row::with_children(chips).spacing(6).align_y(iced::Alignment::Center).wrap().into()
+    }
+
+    /// A sent message's files, under what was asked
+    pub(crate) fn build_message_attachments<'a>(&self, attachments: &'a [Attachment]) -> Option<Element<'a, Message>> {
+        if attachments.is_empty() {
+            return None;
+        }
+        let chips = attachments.iter().map(|attachment| {
+            chip(
+                text(format!("📎 {} · {}", attachment.name, size_label(attachment.size)))
+                    .size(11)
+                    .font(fonts::mono())
+                    .into(),
+            )
+        });
+        Some(Row::with_children(chips).spacing(6).wrap().into())
+    }
+
+    pub(crate) fn build_file_picker<'a>(&'a self, picker: &'a FilePicker, main_view: Element<'a, Message>) -> Element<'a, Message> {
+        let mut list = column![].spacing(2);
+        if let Some(parent) = picker.dir.parent() {
+            list = list.push(
+                button(text("📁 ..").size(13).font(fonts::mono()))
+                    .on_press(Message::BrowseFiles(parent.to_path_buf()))
+                    .padding([4, 8])
+                    .width(Length::Fill)
+                    .style(theme::icon_button),
+            );
+        }
+        for entry in &picker.entries {
+            let path = picker.dir.join(&entry.name);
+            let (icon, message) = if entry.is_dir {
+                ("📁", Message::BrowseFiles(path))
+            } else {
+                ("📄", Message::AttachFile(path))
+            };
+            list = list.push(
+                button(text(format!("{} {}", icon, entry.name)).size(13).font(fonts::mono()))
+                    .on_press(message)
+                    .padding([4, 8])
+                    .width(Length::Fill)
+                    .style(theme::icon_button),
+            );
+        }
+
+        let mut content = column![
+            text("Attach a file").size(18).font(fonts::bold()),
+            text(picker.dir.display().to_string()).size(12).font(fonts::mono()).color(theme::colors().text_dim),
+            Space::with_height(8),
+        ]
+        .spacing(8);
+        if let Some(error) = &picker.error {
+            content = content.push(text(error).size(13).font(fonts::mono()).color(theme::colors().error));
+        }
+        content = content
+            .push(scrollable(list).height(Length::Fixed(360.0)))
+            .push(Space::with_height(8))
+            .push(
+                button(text("Cancel").size(14).font(fonts::mono()))
+                    .on_press(Message::CloseFilePicker)
+                    .padding(10)
+                    .style(theme::secondary_button),
+            );
+
+        let dialog_content = container(content.padding(30).width(520))
+            .style(|_theme: &Theme| container::Style {
+                background: Some(iced::Background::Color(theme::colors().background)),
+                border: Border {
+                    color: theme::colors().border,
+                    width: 1.0,
+                    radius: 8.0.into(),
+                },
+                ..Default::default()
+            })
+            .center_x(Length::Fill)
+            .center_y(Length::Fill);
+
+        Stack::new()
+            .push(main_view)
+            .push(
+                container(dialog_content)
+                    .width(Length::Fill)
+                    .height(Length::Fill)
+                    .style(|_: &Theme| container::Style {
+                        background: Some(iced::Background::Color(Color::from_rgba(0.0, 0.0, 0.0, 0.7))),
+                        ..Default::default()
+                    }),
+            )
+            .width(Length::Fill)
+            .height(Length::Fill)
+            .into()
+    }
+}
@@ -0,0 +1,141 @@
+use iced::widget::{button, column, container, row, text, text_input, Space, Stack};
+use iced::{Border, Color, Element, Length, Theme};
+use iced::Task as Command;
+
+use crate::api::ApiError;
+use crate::state::{update, Action, AuthPromptState, Notification};
+use crate::{fonts, theme, BickyApp, Message};
+
+/// Whether the server on screen turned us away for missing or wrong credentials
+pub fn unauthorized(message: &Message, active_server: &str) -> bool {
+    let error = match message {
+        // Background servers keep their configured key, the prompt can't change it
+        Message::InteractionFetched(server, _, _)
+        | Message::HistoryLoaded(server, _, _)
+        | Message::SessionsLoaded(server, _)
+        | Message::ConversationsListed(server, _)
+        | Message::TagsUpdated(server, _, _)
+        | Message::MessageSent(server, _, _) if server != active_server => return false,
+        Message::MessageSent(_, _, Err(e))
+        | Message::ConversationDeleted(Err(e))
+        | Message::WorktreeDeleted(Err(e))
+        | Message::DiffLoaded(_, Err(e))
+        | Message::MergeDone(Err(e))
+        | Message::InterruptSent(_, Err(e))
+        | Message::SentDeleted(Err(e))
+        | Message::PermissionResponseSent(_, Err(e))
+        | Message::ReviewReplySubmitted(_, _, Err(e))
+        | Message::TagsUpdated(_, _, Err(e))
+        | Message::InteractionFetched(_, _, Err(e))
+        | Message::HistoryLoaded(_, _, Err(e))
+        | Message::SessionsLoaded(_, Err(e))
+        | Message::ConversationsListed(_, Err(e))
+        | Message::WorktreesLoaded(Err(e))
+        | Message::WorktreeStatusesLoaded(Err(e))
+        | Message::WorktreeCreated(Err(e))
+        | Message::TranscriptImported(_, _, Err(e)) => e,
+        _ => return false,
+    };
+    *error == ApiError::Unauthorized
+}
+
+impl BickyApp {
+    pub(crate) fn build_auth_prompt<'a>(&self, prompt: &'a AuthPromptState, main_view: Element<'a, Message>) -> Element<'a, Message> {
+        let hint = if self.api_client.credentials.is_set() {
+            "The server didn't accept the API key."
+        } else {
+            "The server needs an API key."
+        };
+
+        let content = column![
+            text("Sign in to Bicamrl").size(18).font(fonts::bold()),
+            Space::with_height(12),
+            text(hint).size(14).font(fonts::mono()),
+            text(format!("Used for {} until the GUI restarts. Set the server's api_key in Mind.toml, or BICAMRL_API_KEY for the main one, to keep it.", self.api_client.base_url))
+                .size(12)
+                .font(fonts::mono())
+                .color(theme::colors().text_dim),
+            Space::with_height(12),
+            column![
+                text("API Key").size(14).font(fonts::mono()),
+                text_input("Paste the server's key", &prompt.api_key)
+                    .on_input(Message::ApiKeyChanged)
+                    .on_submit(Message::SubmitApiKey)
+                    .secure(true)
+                    .padding(10)
+                    .font(fonts::mono())
+                    .size(14)
+                    .style(theme::input_style),
+            ]
+            .spacing(8),
+            Space::with_height(12),
+            row![
+                button(text("Cancel").size(14).font(fonts::mono()))
+                    .on_press(Message::CloseAuthPrompt)
+                    .padding(10)
+                    .style(theme::secondary_button),
+                Space::with_width(10),
+                button(text("Connect").size(14).font(fonts::mono()))
+                    .on_press_maybe((!prompt.api_key.trim().is_empty()).then_some(Message::SubmitApiKey))
+                    .padding(10)
+                    .style(theme::primary_button),
+            ]
+            .align_y(iced::Alignment::Center),
+        ]
+        .spacing(8);
+
+        let dialog_content = container(content.padding(30).width(460))
+            .style(|_theme: &Theme| container::Style {
+                background: Some(iced::Background::Color(theme::colors().background)),
+                border: Border {
+                    color: theme::colors().border,
+                    width: 1.0,
+                    radius: 8.0.into(),
+                },
+                ..Default::default()
+            })
+            .center_x(Length::Fill)
+            .center_y(Length::Fill);
+
+        Stack::new()
+            .push(main_view)
+            .push(
+                container(dialog_content)
+                    .width(Length::Fill)
+                    .height(Length::Fill)
+                    .style(|_: &Theme| container::Style {
+                        background: Some(iced::Background::Color(Color::from_rgba(0.0, 0.0, 0.0, 0.7))),
+                        ..Default::default()
+                    }),
+            )
+            .width(Length::Fill)
+            .height(Length::Fill)
+            .into()
+    }
+
+    /// Use the entered key from now on and reload what the 401 kept from us
+    pub(crate) fn submit_api_key(&mut self) -> Command<Message> {
+        let Some(prompt) = &self.state.auth_prompt else {
+            return Command::none();
+        };
+        if prompt.api_key.trim().is_empty() {
+            return Command::none();
+        }
+
+        // The SSE task shares these credentials and picks the key up on its next reconnect
+        self.api_client.credentials.set(Some(prompt.api_key.clone()));
+        update(&mut self.state, Action::CloseAuthPrompt);
+        update(&mut self.state, Action::ShowNotification(Notification::Info("API key updated".to_string())));
+
+        let api = self.api_client.clone();
+        Command::batch([
+            self.tasks.run(
+                "get worktrees",
+                async move { api.get_worktrees().await },
+                Message::WorktreesLoaded
+            ),
+            self.load_sessions(),
+            self.hydrate_conversations(self.state.active_server.clone()),
+        ])
+    }
+}
@@ -0,0 +1,162 @@
+use chrono::{Local, NaiveDate};
+use std::cmp::Reverse;
+use std::path::{Path, PathBuf};
+
+use crate::state::{self, Action};
+use crate::{config, labels, mutes, permission_audit, permission_policy, BickyApp};
+
+// One folder a day inside this, next to Mind.toml
+const BACKUP_DIR: &str = "backups";
+const DATE_FORMAT: &str = "%Y-%m-%d";
+
+/// Files the GUI keeps itself, conversations live on the server
+pub const FILES: [&str; 4] = [
+    labels::LABELS_FILE,
+    mutes::MUTES_FILE,
+    permission_policy::POLICY_FILE,
+    permission_audit::AUDIT_FILE,
+];
+
+/// One day's copies
+#[derive(Debug, Clone, PartialEq)]
+pub struct Backup {
+    pub date: NaiveDate,
+    pub path: PathBuf,
+}
+
+/// Where backups go, next to Mind.toml or here when there isn't one
+pub fn root() -> PathBuf {
+    config::find_config_file("Mind.toml")
+        .map(|mind| mind.with_file_name(BACKUP_DIR))
+        .unwrap_or_else(|| PathBuf::from(BACKUP_DIR))
+}
+
+/// Where `name` is read from, or would be written when it doesn't exist yet
+pub fn local_path(name: &str) -> PathBuf {
+    config::find_config_file(name)
+        .or_else(|| config::find_config_file("Mind.toml").map(|mind| mind.with_file_name(name)))
+        .unwrap_or_else(|| PathBuf::from(name))
+}
+
+/// Backups under `root`, newest first, skipping anything that isn't a dated folder
+pub fn list(root: &Path) -> Vec<Backup> {
+    let Ok(entries) = std::fs::read_dir(root) else {
+        return Vec::new();
+    };
+
+    let mut backups: Vec<Backup> = entries
+        .filter_map(Result::ok)
+        .filter(|entry| entry.path().is_dir())
+        .filter_map(|entry| {
+            let date = NaiveDate::parse_from_str(entry.file_name().to_str()?, DATE_FORMAT).ok()?;
+            Some(Backup { date, path: entry.path() })
+        })
+        .collect();
+    backups.sort_by_key(|backup| Reverse(backup.date));
+    backups
+}
+
+/// Copy whichever of `files` exist into a folder for `today`, unless there's one already
+///
+/// Returns `None` when today is already covered or there was nothing to copy.
+pub fn back_up(root: &Path, today: NaiveDate, files: &[(&str, PathBuf)]) -> Result<Option<Backup>, String> {
+    let path = root.join(today.format(DATE_FORMAT).to_string());
+    if path.exists() {
+        return Ok(None);
+    }
+
+    let existing: Vec<&(&str, PathBuf)> = files.iter().filter(|(_, source)| source.is_file()).collect();
+    if existing.is_empty() {
+        return Ok(None);
+    }
+
+    std::fs::create_dir_all(&path).map_err(|e| e.to_string())?;
+    for (name, source) in existing {
+        std::fs::copy(source, path.join(name)).map_err(|e| format!("{}: {}", name, e))?;
+    }
+    Ok(Some(Backup { date: today, path }))
+}
+
+/// Delete all but the newest `keep` backups, returns how many went
+pub fn prune(root: &Path, keep: usize) -> Result<usize, String> {
+    let stale = list(root).into_iter().skip(keep.max(1)).collect::<Vec<_>>();
+    for backup in &stale {
+        std::fs::remove_dir_all(&backup.path).map_err(|e| e.to_string())?;
+    }
+    Ok(stale.len())
+}
+
+/// Copy a backup's files back over `files`, returns how many were restored
+///
+/// Files the backup doesn't have are left as they are.
+pub fn restore(backup: &Backup, files: &[(&str, PathBuf)]) -> Result<usize, String> {
+    let mut restored = 0;
+    for (name, destination) in files {
+        let source = backup.path.join(name);
+        if source.is_file() {
+            std::fs::copy(&source, destination).map_err(|e| format!("{}: {}", name, e))?;
+            restored += 1;
+        }
+    }
+    Ok(restored)
+}
+
+fn local_files() -> Vec<(&'static str, PathBuf)> {
+    FILES.iter().map(|name| (*name, local_path(name))).collect()
+}
+
+impl BickyApp {
+    /// Take today's backup if it's due, checked at startup and whenever the date changes
+    pub(crate) fn run_daily_backup(&mut self) {
+        let today = self.state.clock.utc().with_timezone(&Local).date_naive();
+        if self.state.backup_checked == Some(today) {
+            return;
+        }
+
+        let root = root();
+        let settings = self.state.settings.backup.clone();
+        if settings.enabled {
+            let result = back_up(&root, today, &local_files()).and_then(|backup| {
+                if let Some(backup) = backup {
+                    println!("[GUI] Backed up local files to {}", backup.path.display());
+                }
+                prune(&root, settings.keep)
+            });
+            // Only costs a day's copy, tomorrow tries again
+            if let Err(e) = result {
+                eprintln!("[GUI] Backup failed: {}", e);
+                state::update(
+                    &mut self.state,
+                    Action::ShowNotification(state::Notification::Error(format!("Couldn't back up local files: {}", e))),
+                );
+            }
+        }
+
+        state::update(&mut self.state, Action::BackupsChecked { date: today, backups: list(&root) });
+    }
+
+    pub(crate) fn restore_backup(&mut self, date: NaiveDate) {
+        let root = root();
+        let Some(backup) = list(&root).into_iter().find(|b| b.date == date) else {
+            return;
+        };
+
+        let notification = match restore(&backup, &local_files()) {
+            Ok(restored) => {
+                println!("[GUI] Restored {} files from {}", restored, backup.path.display());
+                state::update(
+                    &mut self.state,
+                    Action::LocalFilesRestored {
+                        labels: labels::load(),
+                        muted: mutes::load(),
+                        permission_policy: permission_policy::load(),
+                        permission_audit: permission_audit::load(),
+                    },
+                );
+                state::Notification::Success(format!("Restored the backup from {}", date))
+            }
+            Err(e) => state::Notification::Error(format!("Couldn't restore the backup from {}: {}", date, e)),
+        };
+        state::update(&mut self.state, Action::ShowNotification(notification));
+    }
+}
@@ -0,0 +1,110 @@
+use iced::widget::{progress_bar, row, text};
+use iced::{Background, Border, Element, Length};
+
+use crate::state::{self, update, Action, Conversation};
+use crate::{format, fonts, theme, BickyApp, Message};
+
+/// Budgets offered in the command palette
+pub const PRESETS: [u32; 4] = [100_000, 250_000, 500_000, 1_000_000];
+
+// Sends warn once this much of the budget is used
+const NEAR_PERCENT: u64 = 80;
+
+/// Tokens a conversation may use
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TokenBudget {
+    pub limit: u32,
+    /// Refuse sends once it's used up, otherwise only warn
+    pub block: bool,
+}
+
+/// Whether a send to a conversation should go ahead
+#[derive(Debug, Clone, PartialEq)]
+pub enum Verdict {
+    Send,
+    /// Send, telling the user why they might not want to
+    Warn(String),
+    Refuse(String),
+}
+
+/// Tokens the conversation's messages have used, as far as we've loaded them
+pub fn tokens_used(conv: &Conversation) -> u32 {
+    conv.messages
+        .iter()
+        .filter_map(|msg| msg.metadata.as_ref()?.tokens.as_ref())
+        .fold(0u32, |used, tokens| used.saturating_add(tokens.total))
+}
+
+/// What to do with a send to `conv` given its budget
+pub fn check(conv: &Conversation) -> Verdict {
+    let Some(budget) = conv.budget else {
+        return Verdict::Send;
+    };
+    let used = tokens_used(conv);
+    let locale = format::locale();
+    let spent = format!("{} of {}", locale.token_count(used), locale.token_count(budget.limit));
+
+    if used >= budget.limit && budget.block {
+        Verdict::Refuse(format!("Over this conversation's token budget ({}), raise or remove it from the command palette", spent))
+    } else if used >= budget.limit {
+        Verdict::Warn(format!("Over this conversation's token budget ({})", spent))
+    } else if used as u64 * 100 >= budget.limit as u64 * NEAR_PERCENT {
+        Verdict::Warn(format!("Close to this conversation's token budget ({})", spent))
+    } else {
+        Verdict::Send
+    }
+}
+
+impl BickyApp {
+    /// Whether a send to the conversation may go ahead, telling the user when it's over budget
+    pub(crate) fn within_budget(&mut self, conversation_idx: usize) -> bool {
+        let Some(conv) = self.state.conversations.get(conversation_idx) else {
+            return true;
+        };
+        match check(conv) {
+            Verdict::Send => true,
+            Verdict::Warn(warning) => {
+                update(&mut self.state, Action::ShowNotification(state::Notification::Info(warning)));
+                true
+            }
+            Verdict::Refuse(reason) => {
+                println!("[GUI] Not sending to {}: {}", conv.id, reason);
+                update(&mut self.state, Action::ShowNotification(state::Notification::Error(reason)));
+                false
+            }
+        }
+    }
+
+    /// How much of the conversation's budget is used, for the stats bar
+    pub(crate) fn build_budget_meter(&self, conv: &Conversation) -> Option<Element<'_, Message>> {
+        let budget = conv.budget?;
+        let used = tokens_used(conv);
+        let colors = theme::colors();
+        let color = match check(conv) {
+            Verdict::Send => colors.success,
+            Verdict::Warn(_) if used < budget.limit => colors.spinner,
+            _ => colors.error,
+        };
+        let locale = format::locale();
+
+        Some(
+            row![
+                progress_bar(0.0..=budget.limit as f32, used.min(budget.limit) as f32)
+                    .width(Length::Fixed(80.0))
+                    .height(6)
+                    .style(move |_theme| progress_bar::Style {
+                        background: Background::Color(colors.border),
+                        bar: Background::Color(color),
+                        border: Border { radius: 3.0.into(), ..Default::default() },
+                    }),
+                text(format!("{} / {}", locale.token_count(used), locale.token_count(budget.limit)))
+                    .size(12)
+                    .font(fonts::mono())
+                    .color(color),
+            ]
+            .spacing(6)
+            .align_y(iced::Alignment::Center)
+            .into(),
+        )
+    }
+}
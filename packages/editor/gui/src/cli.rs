@@ -0,0 +1,205 @@
+use serde::Serialize;
+use serde_json::{json, Value};
+
+use crate::api::{ApiClient, ApiError};
+use crate::config::Settings;
+use crate::servers;
+use crate::state::SystemClock;
+use crate::types::{CreateWorktreeRequest, SendMessageRequest};
+
+// Messages fetched per request when exporting a whole conversation
+const EXPORT_PAGE_SIZE: usize = 200;
+
+pub const USAGE: &str = "\
+Usage: bicky-gui [--server NAME] <command>
+
+Commands:
+  send <message> [--interaction ID] [--worktree ID]
+                  Send a message, a new conversation unless --interaction is given
+  list            List the server's conversations
+  export <id>     Print a conversation with all of its messages
+  worktree create <branch> [--base BRANCH] [--path PATH]
+                  Create a worktree
+
+Results are printed as JSON. Without a command the GUI starts.
+--server picks one of the servers configured in Mind.toml by name.";
+
+/// A headless command, run instead of starting the GUI
+#[derive(Debug, Clone, PartialEq)]
+pub enum Command {
+    Send {
+        content: String,
+        interaction_id: Option<String>,
+        worktree_id: Option<String>,
+    },
+    List,
+    Export {
+        interaction_id: String,
+    },
+    CreateWorktree {
+        branch: String,
+        base_branch: Option<String>,
+        path: Option<String>,
+    },
+    Help,
+}
+
+/// A command and the configured server it goes to, the primary one when `None`
+#[derive(Debug, Clone, PartialEq)]
+pub struct Invocation {
+    pub server: Option<String>,
+    pub command: Command,
+}
+
+/// Read the arguments after the program name, `Ok(None)` means start the GUI
+pub fn parse(args: &[String]) -> Result<Option<Invocation>, String> {
+    let mut server = None;
+    let mut options: Vec<(String, String)> = Vec::new();
+    let mut words: Vec<&str> = Vec::new();
+
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "-h" | "--help" => words.insert(0, "help"),
+            "--server" | "--interaction" | "--worktree" | "--base" | "--path" => {
+                let value = args.next().ok_or_else(|| format!("{} needs a value", arg))?;
+                if arg == "--server" {
+                    server = Some(value.clone());
+                } else {
+                    options.push((arg.clone(), value.clone()));
+                }
+            }
+            flag if flag.starts_with("--") => return Err(format!("Unknown option {}", flag)),
+            word => words.push(word),
+        }
+    }
+    let option = |name: &str| options.iter().find(|(flag, _)| flag == name).map(|(_, value)| value.clone());
+
+    let command = match words.as_slice() {
+        [] if server.is_none() => return Ok(None),
+        ["help", ..] => Command::Help,
+        ["send", content] if !content.trim().is_empty() => Command::Send {
+            content: content.to_string(),
+            interaction_id: option("--interaction"),
+            worktree_id: option("--worktree"),
+        },
+        ["send", ..] => return Err("send takes the message as one argument, quote it".to_string()),
+        ["list"] => Command::List,
+        ["export", id] => Command::Export {
+            interaction_id: id.to_string(),
+        },
+        ["worktree", "create", branch] => Command::CreateWorktree {
+            branch: branch.to_string(),
+            base_branch: option("--base"),
+            path: option("--path"),
+        },
+        [] => return Err("Missing command".to_string()),
+        _ => return Err(format!("Unknown command: {}", words.join(" "))),
+    };
+
+    let allowed: &[&str] = match command {
+        Command::Send { .. } => &["--interaction", "--worktree"],
+        Command::CreateWorktree { .. } => &["--base", "--path"],
+        _ => &[],
+    };
+    if let Some((flag, _)) = options.iter().find(|(flag, _)| !allowed.contains(&flag.as_str())) {
+        return Err(format!("{} doesn't apply to this command", flag));
+    }
+
+    Ok(Some(Invocation { server, command }))
+}
+
+/// Run a command against its server, prints the result and gives the exit code
+pub fn run(invocation: Invocation) -> i32 {
+    if invocation.command == Command::Help {
+        println!("{}", USAGE);
+        return 0;
+    }
+
+    let servers = servers::configured(&Settings::load(), std::sync::Arc::new(SystemClock));
+    let server = match &invocation.server {
+        Some(name) => servers.iter().find(|server| &server.name == name),
+        None => servers.first(),
+    };
+    let Some(server) = server else {
+        eprintln!("No server named {} in Mind.toml", invocation.server.unwrap_or_default());
+        return 2;
+    };
+    let runtime = match tokio::runtime::Runtime::new() {
+        Ok(runtime) => runtime,
+        Err(e) => {
+            eprintln!("Couldn't start: {}", e);
+            return 1;
+        }
+    };
+
+    match runtime.block_on(execute(&server.api, invocation.command)) {
+        Ok(output) => {
+            println!("{}", serde_json::to_string_pretty(&output).unwrap_or_default());
+            0
+        }
+        Err(e) => {
+            eprintln!("{} ({})", e, e.guidance());
+            1
+        }
+    }
+}
+
+async fn execute(api: &ApiClient, command: Command) -> Result<Value, ApiError> {
+    match command {
+        Command::Send { content, interaction_id, worktree_id } => {
+            let request = SendMessageRequest {
+                content,
+                metadata: None,
+                worktree_id,
+                interaction_id,
+                attachment_ids: Vec::new(),
+            };
+            to_json(api.send_message(request).await?)
+        }
+        Command::List => Ok(Value::Array(api.get_interactions().await?)),
+        Command::Export { interaction_id } => export(api, &interaction_id).await,
+        Command::CreateWorktree { branch, base_branch, path } => {
+            let request = CreateWorktreeRequest { branch, base_branch, path };
+            to_json(api.create_worktree(request).await?)
+        }
+        Command::Help => Ok(Value::Null),
+    }
+}
+
+// Page back through the conversation until the server has nothing older
+async fn export(api: &ApiClient, interaction_id: &str) -> Result<Value, ApiError> {
+    let mut page = api.get_interaction_json(interaction_id, EXPORT_PAGE_SIZE, None).await?;
+    let interaction = page.get("interaction").cloned().unwrap_or(Value::Null);
+    let mut messages: Vec<Value> = Vec::new();
+
+    loop {
+        let mut older = take_messages(&mut page);
+        older.append(&mut messages);
+        messages = older;
+
+        let has_more = page.get("hasMore").and_then(Value::as_bool).unwrap_or(false);
+        let oldest = messages.first().and_then(|msg| msg.get("id")).and_then(Value::as_str);
+        match oldest.filter(|_| has_more) {
+            Some(before) => page = api.get_interaction_json(interaction_id, EXPORT_PAGE_SIZE, Some(before)).await?,
+            None => break,
+        }
+    }
+
+    Ok(json!({ "interaction": interaction, "messages": messages }))
+}
+
+/// A page's messages, oldest first
+pub fn take_messages(page: &mut Value) -> Vec<Value> {
+    let mut messages = match page.get_mut("messages").map(Value::take) {
+        Some(Value::Array(messages)) => messages,
+        _ => Vec::new(),
+    };
+    // ISO timestamps sort as text
+    messages.sort_by_key(|msg| msg.get("timestamp").and_then(Value::as_str).unwrap_or_default().to_string());
+    messages
+}
+
+fn to_json(value: impl Serialize) -> Result<Value, ApiError> {
+    serde_json::to_value(value).map_err(|e| ApiError::Decode(e.to_string()))
+}
@@ -0,0 +1,54 @@
+use iced::Task as Command;
+
+use crate::platform::{self, Os};
+
+/// Which clipboard protocol we end up talking to
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Backend {
+    /// macOS and Windows, one system clipboard
+    Native,
+    Wayland,
+    X11,
+    /// Neither display variable is set, e.g. over SSH without forwarding
+    Unavailable,
+}
+
+impl Backend {
+    /// Same order winit picks its backend in: Wayland first, X11 as the fallback
+    pub fn detect(os: Os, env: impl Fn(&str) -> Option<String>) -> Self {
+        if os != Os::Unix {
+            return Backend::Native;
+        }
+        if env("WAYLAND_DISPLAY").is_some() || env("WAYLAND_SOCKET").is_some() {
+            Backend::Wayland
+        } else if env("DISPLAY").is_some() {
+            Backend::X11
+        } else {
+            Backend::Unavailable
+        }
+    }
+
+    pub fn current() -> Self {
+        Self::detect(Os::current(), platform::env)
+    }
+
+    /// Wayland and X11 users paste with middle click from the primary selection
+    pub fn has_primary_selection(self) -> bool {
+        matches!(self, Backend::Wayland | Backend::X11)
+    }
+}
+
+/// Copy `text` so it pastes the way the desktop expects
+pub fn copy<T: Send + 'static>(text: String) -> Command<T> {
+    let backend = Backend::current();
+    if backend == Backend::Unavailable {
+        eprintln!("[GUI] No clipboard, neither WAYLAND_DISPLAY nor DISPLAY is set");
+        return Command::none();
+    }
+
+    if backend.has_primary_selection() {
+        Command::batch([iced::clipboard::write_primary(text.clone()), iced::clipboard::write(text)])
+    } else {
+        iced::clipboard::write(text)
+    }
+}
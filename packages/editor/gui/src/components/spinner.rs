@@ -50,9 +50,9 @@ impl Spinner {
         let frame = self.current_frame().to_string();
         container(
             text(frame)
-                .font(fonts::BERKELEY_MONO)
+                .font(fonts::mono())
                 .size(14)
-                .color(theme::Colors::PRIMARY)
+                .color(theme::colors().primary)
         )
         .width(Length::Shrink)
         .into()
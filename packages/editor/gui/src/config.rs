@@ -0,0 +1,451 @@
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+/// GUI settings, read from the `[gui]` table of Mind.toml
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct Settings {
+    pub notifications: NotificationSettings,
+    pub composer: ComposerSettings,
+    pub templates: Templates,
+    /// Buttons above the composer, `[[gui.quick_actions]]` entries
+    pub quick_actions: QuickActions,
+    pub safety: SafetyLimits,
+    pub heartbeat: HeartbeatSettings,
+    pub backup: BackupSettings,
+    pub requests: RequestLimits,
+    pub messages: MessageSettings,
+    pub models: ModelStyles,
+    pub appearance: AppearanceSettings,
+    pub server: ServerSettings,
+    /// More servers to switch between, `[[gui.servers]]` entries
+    pub servers: Vec<ServerSettings>,
+    /// `[[gui.plugins]]` entries
+    pub plugins: Vec<PluginSettings>,
+    /// `[[gui.hooks]]` entries
+    pub hooks: Vec<HookSettings>,
+}
+
+/// A plugin program and how to start it
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(default)]
+pub struct PluginSettings {
+    pub command: String,
+    pub args: Vec<String>,
+    pub enabled: bool,
+}
+
+impl Default for PluginSettings {
+    fn default() -> Self {
+        Self {
+            command: String::new(),
+            args: Vec::new(),
+            enabled: true,
+        }
+    }
+}
+
+/// Something that happened that hooks can run on
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum HookEvent {
+    MessageCompleted,
+    PermissionRequested,
+    WorktreeCreated,
+}
+
+impl HookEvent {
+    pub fn name(&self) -> &'static str {
+        match self {
+            HookEvent::MessageCompleted => "message_completed",
+            HookEvent::PermissionRequested => "permission_requested",
+            HookEvent::WorktreeCreated => "worktree_created",
+        }
+    }
+}
+
+/// A Rhai script run on an event, what it's given and can ask for is in `hooks`
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct HookSettings {
+    pub on: HookEvent,
+    /// Path to the script, relative to the hooks folder next to Mind.toml
+    pub script: String,
+}
+
+/// Where the Bicamrl server runs and how to authenticate with it
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct ServerSettings {
+    /// Shown in the server switcher, defaults to the url
+    pub name: Option<String>,
+    /// e.g. `https://bicamrl.example.com`, defaults to the local port file
+    pub url: Option<String>,
+    /// Sent as a bearer token, `BICAMRL_API_KEY` takes precedence
+    pub api_key: Option<String>,
+}
+
+impl ServerSettings {
+    pub fn api_key(&self) -> Option<String> {
+        std::env::var("BICAMRL_API_KEY")
+            .ok()
+            .filter(|key| !key.trim().is_empty())
+            .or_else(|| self.api_key.clone())
+    }
+}
+
+/// When to fire native OS notifications
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct NotificationSettings {
+    pub enabled: bool,
+    pub on_completion: bool,
+    pub on_permission_request: bool,
+}
+
+impl Default for NotificationSettings {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            on_completion: true,
+            on_permission_request: true,
+        }
+    }
+}
+
+/// Message composer behaviour
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct ComposerSettings {
+    /// Warn when a single message is estimated to go over this many tokens
+    pub max_message_tokens: usize,
+}
+
+impl Default for ComposerSettings {
+    fn default() -> Self {
+        Self {
+            max_message_tokens: 16_000,
+        }
+    }
+}
+
+/// A stat that can show under Wake's messages
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum MetadataChip {
+    Tokens,
+    Time,
+    Model,
+    Tools,
+    Branch,
+    Tags,
+}
+
+/// How messages are shown
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct MessageSettings {
+    /// Which stats show under responses, in order
+    pub metadata: Vec<MetadataChip>,
+    /// Overview strip beside the transcript
+    pub minimap: bool,
+}
+
+impl Default for MessageSettings {
+    fn default() -> Self {
+        Self {
+            metadata: vec![
+                MetadataChip::Tokens,
+                MetadataChip::Time,
+                MetadataChip::Tools,
+                MetadataChip::Branch,
+                MetadataChip::Tags,
+            ],
+            minimap: true,
+        }
+    }
+}
+
+/// Which palette to use
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum AppearanceMode {
+    /// Follow the OS dark/light setting
+    System,
+    Dark,
+    Light,
+}
+
+/// Built-in color sets, for accessibility
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum PaletteVariant {
+    Standard,
+    HighContrast,
+    /// Status and diff colors that stay apart with deuteranopia and protanopia
+    Colorblind,
+}
+
+/// Dark and light themes, each with its own overrides file next to Mind.toml
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct AppearanceSettings {
+    pub mode: AppearanceMode,
+    pub palette: PaletteVariant,
+    /// Skip entrance and panel animations
+    pub reduced_motion: bool,
+    pub dark_theme: String,
+    pub light_theme: String,
+}
+
+impl Default for AppearanceSettings {
+    fn default() -> Self {
+        Self {
+            mode: AppearanceMode::System,
+            palette: PaletteVariant::Standard,
+            reduced_motion: false,
+            dark_theme: "theme.css".to_string(),
+            light_theme: "theme-light.css".to_string(),
+        }
+    }
+}
+
+/// Interrupt interactions that run away, 0 turns a limit off
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct SafetyLimits {
+    pub max_wall_time_secs: u64,
+    pub max_tool_calls: usize,
+    pub max_tokens: u32,
+}
+
+impl Default for SafetyLimits {
+    fn default() -> Self {
+        Self {
+            max_wall_time_secs: 30 * 60,
+            max_tool_calls: 100,
+            max_tokens: 200_000,
+        }
+    }
+}
+
+/// When a running interaction counts as stalled
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct HeartbeatSettings {
+    /// Seconds without any progress from the server, 0 turns detection off
+    pub stall_after_secs: u64,
+}
+
+impl Default for HeartbeatSettings {
+    fn default() -> Self {
+        Self {
+            stall_after_secs: 120,
+        }
+    }
+}
+
+/// Daily copies of the GUI's own files, labels and permission rules and audit log
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct BackupSettings {
+    pub enabled: bool,
+    /// Daily copies kept, the oldest are deleted past this
+    pub keep: usize,
+}
+
+impl Default for BackupSettings {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            keep: 7,
+        }
+    }
+}
+
+/// Requests to each server at once, more wait their turn
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(default)]
+pub struct RequestLimits {
+    /// Ones the user is waiting on, sending, answering, merging
+    pub interactive: usize,
+    /// Refreshes and refetches the user didn't ask for
+    pub background: usize,
+}
+
+impl Default for RequestLimits {
+    fn default() -> Self {
+        Self {
+            interactive: 6,
+            background: 4,
+        }
+    }
+}
+
+/// Draft templates keyed by the prefix that triggers them, `{cursor}` marks where typing continues
+#[derive(Debug, Clone, Deserialize)]
+#[serde(transparent)]
+pub struct Templates(pub HashMap<String, String>);
+
+impl Default for Templates {
+    fn default() -> Self {
+        let templates = [
+            (
+                "fix:",
+                "Fix: {cursor}\n\nExpected behaviour:\n\nActual behaviour:\n\nSteps to reproduce:\n1. ",
+            ),
+            (
+                "review:",
+                "Review: {cursor}\n\nFocus on:\n- correctness\n- error handling\n- tests\n\nIgnore:\n- formatting",
+            ),
+        ];
+
+        Self(
+            templates
+                .into_iter()
+                .map(|(trigger, template)| (trigger.to_string(), template.to_string()))
+                .collect(),
+        )
+    }
+}
+
+/// A button above the composer that fills it in or sends straight away
+///
+/// `{selection}` is replaced with the text selected in the composer, or all of it,
+/// `{worktree}` with the current worktree's path and `{cursor}` marks where typing continues.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct QuickAction {
+    pub label: String,
+    pub prompt: String,
+    /// Send the prompt instead of putting it in the composer
+    #[serde(default)]
+    pub send: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(transparent)]
+pub struct QuickActions(pub Vec<QuickAction>);
+
+impl Default for QuickActions {
+    fn default() -> Self {
+        let actions = [
+            ("Explain", "Explain what this does and why:\n\n{selection}", true),
+            ("Write tests", "Write tests for {cursor}{selection}", false),
+            ("Refactor selection", "Refactor this without changing what it does:\n\n{selection}", true),
+        ];
+
+        Self(
+            actions
+                .into_iter()
+                .map(|(label, prompt, send)| QuickAction {
+                    label: label.to_string(),
+                    prompt: prompt.to_string(),
+                    send,
+                })
+                .collect(),
+        )
+    }
+}
+
+/// How one model is marked in headers, the sidebar and stats
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct ModelStyle {
+    /// Hex color like `#c084fc`
+    pub color: Option<String>,
+    pub icon: Option<String>,
+}
+
+/// Model styles keyed by part of the model name, e.g. `opus` or `gpt-4`
+#[derive(Debug, Clone, Deserialize)]
+#[serde(transparent)]
+pub struct ModelStyles(pub HashMap<String, ModelStyle>);
+
+impl Default for ModelStyles {
+    fn default() -> Self {
+        let styles = [
+            ("opus", "#c084fc", "◆"),
+            ("sonnet", "#61a6ff", "●"),
+            ("haiku", "#5eead4", "▲"),
+        ];
+
+        Self(
+            styles
+                .into_iter()
+                .map(|(key, color, icon)| {
+                    let style = ModelStyle {
+                        color: Some(color.to_string()),
+                        icon: Some(icon.to_string()),
+                    };
+                    (key.to_string(), style)
+                })
+                .collect(),
+        )
+    }
+}
+
+// Mind.toml is shared with the server, we only care about the gui table
+#[derive(Debug, Default, Deserialize)]
+struct MindFile {
+    #[serde(default)]
+    gui: Settings,
+}
+
+// Same lookup order as the server's loadMindConfig
+const CONFIG_DIRS: [&str; 3] = [".", "../..", "../../.."];
+
+/// Find a config file next to Mind.toml, then in the user's config dir
+pub fn find_config_file(name: &str) -> Option<PathBuf> {
+    CONFIG_DIRS
+        .iter()
+        .map(|dir| Path::new(dir).join(name))
+        .chain(crate::platform::config_dir().map(|dir| dir.join(name)))
+        .find(|path| path.is_file())
+}
+
+impl Settings {
+    pub fn load() -> Self {
+        let Some(path) = find_config_file("Mind.toml") else {
+            return Self::default();
+        };
+
+        match std::fs::read_to_string(&path).map(|content| toml::from_str::<MindFile>(&content)) {
+            Ok(Ok(mind)) => {
+                // On stderr, the command line prints its results on stdout
+                eprintln!("[GUI] Loaded settings from {}", path.display());
+                for (model, style) in &mind.gui.models.0 {
+                    if let Some(color) = style.color.as_deref().filter(|c| crate::theme::parse_hex_color(c).is_none()) {
+                        eprintln!("[GUI] Ignoring invalid color {:?} for model {}", color, model);
+                    }
+                }
+                mind.gui
+            }
+            Ok(Err(e)) => {
+                eprintln!("[GUI] Failed to parse {}: {}", path.display(), e);
+                Self::default()
+            }
+            Err(e) => {
+                eprintln!("[GUI] Failed to read {}: {}", path.display(), e);
+                Self::default()
+            }
+        }
+    }
+}
+
+/// Open Mind.toml in the user's editor
+pub fn open_settings() -> Result<(), String> {
+    let path = find_config_file("Mind.toml").ok_or("Mind.toml not found")?;
+
+    #[cfg(target_os = "macos")]
+    let mut command = std::process::Command::new("open");
+    #[cfg(target_os = "windows")]
+    let mut command = {
+        let mut command = std::process::Command::new("cmd");
+        command.args(["/C", "start", ""]);
+        command
+    };
+    #[cfg(not(any(target_os = "macos", target_os = "windows")))]
+    let mut command = std::process::Command::new("xdg-open");
+
+    command.arg(&path).spawn().map(|_| ()).map_err(|e| e.to_string())
+}
@@ -0,0 +1,130 @@
+use iced::widget::{column, container, row, text, Space, Stack};
+use iced::{Border, Color, Element, Length, Theme};
+
+use crate::api::{Lane, LaneStats};
+use crate::jobs::format_elapsed;
+use crate::tasks::{TaskInfo, TaskStatus};
+use crate::{fonts, theme, BickyApp, Message};
+
+// Only the newest finished tasks fit next to the running ones
+const MAX_FINISHED_SHOWN: usize = 12;
+
+fn status_label(task: &TaskInfo) -> (String, Color) {
+    let colors = theme::colors();
+    match &task.status {
+        TaskStatus::Running => ("running".to_string(), colors.spinner),
+        TaskStatus::Finished => ("done".to_string(), colors.text_dim),
+        TaskStatus::Cancelled => ("cancelled".to_string(), colors.text_dim),
+        TaskStatus::Panicked(reason) => (format!("panicked: {}", reason), colors.error),
+        TaskStatus::Restarting(reason) => (format!("restarting: {}", reason), colors.error),
+    }
+}
+
+/// A lane's slots and queue on one line
+pub fn lane_summary(stats: &LaneStats) -> String {
+    let mut summary = format!("{}/{} in flight", stats.in_flight, stats.limit);
+    if stats.queued > 0 {
+        summary.push_str(&format!(" · {} queued", stats.queued));
+    }
+    if stats.waited > 0 {
+        summary.push_str(&format!(
+            " · {} waited, peak {}, longest {}ms",
+            stats.waited,
+            stats.peak_queued,
+            stats.longest_wait.as_millis()
+        ));
+    }
+    summary
+}
+
+impl BickyApp {
+    /// Every supervised task, running ones first, floating over the top right
+    pub(crate) fn build_debug_overlay<'a>(&self, main_view: Element<'a, Message>) -> Element<'a, Message> {
+        let now = self.state.clock.instant();
+        let tasks = self.tasks.supervisor.snapshot();
+        let (running, finished): (Vec<_>, Vec<_>) = tasks.into_iter().partition(|t| t.status == TaskStatus::Running);
+
+        let mut list = column![text(format!("Background tasks ({} running)", running.len()))
+            .size(12)
+            .font(fonts::bold())]
+        .spacing(4);
+
+        for task in running.iter().chain(finished.iter().rev().take(MAX_FINISHED_SHOWN)) {
+            let (status, color) = status_label(task);
+            let age = if task.status == TaskStatus::Running {
+                format_elapsed(now.saturating_duration_since(task.started))
+            } else {
+                String::new()
+            };
+            let restarts = if task.restarts > 0 { format!("↻{}", task.restarts) } else { String::new() };
+
+            list = list.push(
+                row![
+                    text(task.name.clone()).size(11).font(fonts::mono()),
+                    Space::with_width(Length::Fill),
+                    text(restarts).size(11).font(fonts::mono()).color(theme::colors().error),
+                    text(age).size(11).font(fonts::mono()).color(theme::colors().text_dim),
+                    text(status).size(11).font(fonts::mono()).color(color),
+                ]
+                .spacing(8),
+            );
+        }
+
+        // Whether requests are piling up behind the limits
+        list = list.push(Space::with_height(8)).push(text("Requests").size(12).font(fonts::bold()));
+        for server in &self.servers {
+            for (label, lane) in [("interactive", Lane::Interactive), ("background", Lane::Background)] {
+                let stats = server.api.limiter.stats(lane);
+                let color = if stats.queued > 0 { theme::colors().error } else { theme::colors().text_dim };
+                list = list.push(
+                    row![
+                        text(format!("{} {}", server.name, label)).size(11).font(fonts::mono()),
+                        Space::with_width(Length::Fill),
+                        text(lane_summary(&stats)).size(11).font(fonts::mono()).color(color),
+                    ]
+                    .spacing(8),
+                );
+            }
+        }
+
+        // Protocol additions we're dropping, so they don't go unnoticed
+        if !self.state.unhandled_events.is_empty() {
+            list = list.push(Space::with_height(8)).push(text("Unhandled SSE events").size(12).font(fonts::bold()));
+            for (event_type, count) in &self.state.unhandled_events {
+                list = list.push(
+                    row![
+                        text(event_type.clone()).size(11).font(fonts::mono()),
+                        Space::with_width(Length::Fill),
+                        text(count.to_string()).size(11).font(fonts::mono()).color(theme::colors().error),
+                    ]
+                    .spacing(8),
+                );
+            }
+        }
+
+        let overlay = container(list)
+            .padding(12)
+            .width(Length::Fixed(420.0))
+            .style(|_theme: &Theme| container::Style {
+                background: Some(iced::Background::Color(theme::colors().surface_light.scale_alpha(0.95))),
+                border: Border {
+                    color: theme::colors().border,
+                    width: 1.0,
+                    radius: 6.0.into(),
+                },
+                ..Default::default()
+            });
+
+        Stack::new()
+            .push(main_view)
+            .push(
+                container(overlay)
+                    .width(Length::Fill)
+                    .padding(16)
+                    .align_x(iced::alignment::Horizontal::Right),
+            )
+            .width(Length::Fill)
+            .height(Length::Fill)
+            .into()
+    }
+}
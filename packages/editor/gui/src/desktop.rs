@@ -0,0 +1,106 @@
+use crate::config::NotificationSettings;
+use crate::events::EventBus;
+use crate::types::{LegacyMessage, MessageStatus};
+
+/// Events coming back from native OS notifications
+#[derive(Debug, Clone)]
+pub enum DesktopEvent {
+    Clicked { server: String, conversation_id: String },
+}
+
+/// A native notification we want to show
+#[derive(Debug, Clone, PartialEq)]
+pub struct DesktopAlert {
+    pub summary: String,
+    pub body: String,
+}
+
+/// Work out which alerts a conversation refresh should raise
+pub fn alerts_for_update(
+    title: &str,
+    old: &[LegacyMessage],
+    new: &[LegacyMessage],
+    settings: &NotificationSettings,
+) -> Vec<DesktopAlert> {
+    let mut alerts = Vec::new();
+    if !settings.enabled {
+        return alerts;
+    }
+
+    for msg in new {
+        let previous = old.iter().find(|m| m.id == msg.id).map(|m| &m.status);
+        if previous == Some(&msg.status) {
+            continue;
+        }
+
+        match msg.status {
+            MessageStatus::Completed if settings.on_completion => {
+                let body = msg.response.as_deref().unwrap_or("Wake finished").lines().next().unwrap_or("");
+                alerts.push(DesktopAlert {
+                    summary: format!("{} • completed", title),
+                    body: truncate(body, 120),
+                });
+            }
+            MessageStatus::Error | MessageStatus::Failed if settings.on_completion => {
+                alerts.push(DesktopAlert {
+                    summary: format!("{} • failed", title),
+                    body: truncate(&msg.content, 120),
+                });
+            }
+            MessageStatus::WaitingForPermission if settings.on_permission_request => {
+                let tool = msg
+                    .pending_tool_permission
+                    .as_ref()
+                    .map(|p| p.tool_name.as_str())
+                    .unwrap_or("a tool");
+                alerts.push(DesktopAlert {
+                    summary: format!("{} • permission requested", title),
+                    body: format!("Wake wants to use {}", tool),
+                });
+            }
+            _ => {}
+        }
+    }
+
+    alerts
+}
+
+/// Show a native notification; clicking it is published on the bus
+pub fn notify(alert: DesktopAlert, server: String, conversation_id: String, bus: EventBus) {
+    std::thread::spawn(move || {
+        let mut notification = notify_rust::Notification::new();
+        notification.appname("Bicky").summary(&alert.summary).body(&alert.body);
+
+        // Only the freedesktop backend tells us about clicks, macOS and Windows
+        // bring the app forward on their own
+        #[cfg(all(unix, not(target_os = "macos")))]
+        {
+            notification.action("default", "Open");
+            match notification.show() {
+                Ok(handle) => handle.wait_for_action(|action| {
+                    if action == "default" {
+                        bus.publish(DesktopEvent::Clicked { server, conversation_id });
+                    }
+                }),
+                Err(e) => eprintln!("[GUI] Failed to show notification: {}", e),
+            }
+        }
+
+        #[cfg(not(all(unix, not(target_os = "macos"))))]
+        {
+            let _ = (bus, server, conversation_id);
+            if let Err(e) = notification.show() {
+                eprintln!("[GUI] Failed to show notification: {}", e);
+            }
+        }
+    });
+}
+
+fn truncate(text: &str, max_chars: usize) -> String {
+    if text.chars().count() > max_chars {
+        let truncated: String = text.chars().take(max_chars).collect();
+        format!("{}…", truncated)
+    } else {
+        text.to_string()
+    }
+}
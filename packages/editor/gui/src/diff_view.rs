@@ -0,0 +1,315 @@
+use bicky_editor_core::diff::{FileDiff, FileStatus, LineKind};
+use iced::widget::{button, column, container, row, scrollable, text, text_input, Column, Space};
+use iced::{Color, Element, Length, Theme};
+use iced::Task as Command;
+
+use crate::api::ApiError;
+use crate::config::PaletteVariant;
+use crate::state::{self, update, Action, MergeMode};
+use crate::types::SubmitResultRequest;
+use crate::{fonts, message_errors, scrolling, theme, BickyApp, Message};
+
+// Who a review reply is from, as the multi-agent protocol names agents
+const REVIEWER_AGENT_ID: &str = "user";
+
+pub(crate) const PANEL_WIDTH: f32 = 640.0;
+// Past this a file's diff is summarised rather than drawn line by line
+const MAX_RENDERED_LINES: usize = 2_000;
+
+fn status_marker(file: &FileDiff) -> &'static str {
+    match file.status {
+        FileStatus::Added => "A",
+        FileStatus::Deleted => "D",
+        FileStatus::Modified => "M",
+        FileStatus::Renamed => "R",
+    }
+}
+
+pub(crate) fn line_colors(kind: LineKind, tint: f32) -> (&'static str, Color, Option<Color>) {
+    match kind {
+        LineKind::Added => ("+", theme::colors().success, Some(Color { a: tint, ..theme::colors().success })),
+        LineKind::Removed => ("-", theme::colors().error, Some(Color { a: tint, ..theme::colors().error })),
+        LineKind::Context => (" ", theme::colors().text_dim, None),
+    }
+}
+
+impl BickyApp {
+    // Faint backgrounds wash out in high contrast
+    pub(crate) fn diff_tint(&self) -> f32 {
+        if state::palette_variant(&self.state) == PaletteVariant::HighContrast { 0.3 } else { 0.12 }
+    }
+
+    pub(crate) fn build_diff_panel(&self) -> Element<'_, Message> {
+        let Some(view) = &self.state.diff_view else {
+            return Space::with_width(0).into();
+        };
+
+        let header = row![
+            text(format!("Diff: {}", view.title)).size(14).font(fonts::bold()),
+            Space::with_width(Length::Fill),
+            button(text("Merge").size(12).font(fonts::mono()))
+                .on_press(Message::OpenMergeDialog(view.worktree_id.clone(), MergeMode::Merge))
+                .padding(6)
+                .style(theme::secondary_button),
+            button(text("Create PR").size(12).font(fonts::mono()))
+                .on_press(Message::OpenMergeDialog(view.worktree_id.clone(), MergeMode::PullRequest))
+                .padding(6)
+                .style(theme::secondary_button),
+            button(text("Refresh").size(12).font(fonts::mono()))
+                .on_press(Message::OpenDiff(view.worktree_id.clone()))
+                .padding(6)
+                .style(theme::secondary_button),
+            button(text("×").size(14).font(fonts::mono()))
+                .on_press(Message::CloseDiff)
+                .padding(0)
+                .style(theme::icon_button),
+        ]
+        .spacing(10)
+        .align_y(iced::Alignment::Center);
+
+        let status: Option<Element<'_, Message>> = if view.loading {
+            Some(text("Loading diff...").size(12).font(fonts::mono()).color(theme::colors().text_dim).into())
+        } else if let Some(error) = &view.error {
+            Some(text(error).size(12).font(fonts::mono()).color(theme::colors().error).into())
+        } else if view.files.is_empty() {
+            Some(text("No changes since the base").size(12).font(fonts::mono()).color(theme::colors().text_dim).into())
+        } else {
+            None
+        };
+
+        let mut content = column![header].spacing(12);
+        if let Some(status) = status {
+            content = content.push(status).push(Space::with_height(Length::Fill));
+        } else {
+            content = content
+                .push(self.build_diff_file_list())
+                .push(self.build_diff_hunks());
+        }
+        content = content.push(self.build_review_reply());
+
+        container(content.padding(16))
+            .width(Length::Fixed(PANEL_WIDTH))
+            .height(Length::Fill)
+            .style(theme::sidebar_container)
+            .into()
+    }
+
+    /// Answer the interaction behind the changes as its reviewer
+    fn build_review_reply(&self) -> Element<'_, Message> {
+        let Some(view) = &self.state.diff_view else {
+            return Space::with_height(0).into();
+        };
+        let target = state::review_target(&self.state)
+            .and_then(|idx| self.state.conversations.get(idx))
+            .filter(|conv| message_errors::interaction_id(conv).is_some());
+        let Some(conv) = target else {
+            return text("No conversation works in this worktree to reply to")
+                .size(12)
+                .font(fonts::mono())
+                .color(theme::colors().text_dim)
+                .into();
+        };
+
+        let can_send = !view.reply_sending && !view.reply.trim().is_empty();
+        let mut input = text_input("Reply as reviewer", &view.reply)
+            .padding(8)
+            .font(fonts::mono())
+            .size(13)
+            .style(theme::input_style);
+        if !view.reply_sending {
+            input = input.on_input(Message::ReviewReplyChanged).on_submit(Message::SubmitReviewReply);
+        }
+
+        column![
+            text(format!("Reply to {}", conv.title)).size(12).font(fonts::bold()).color(theme::colors().text_dim),
+            row![
+                input,
+                button(text(if view.reply_sending { "Sending..." } else { "Send" }).size(12).font(fonts::mono()))
+                    .on_press_maybe(can_send.then_some(Message::SubmitReviewReply))
+                    .padding(8)
+                    .style(theme::primary_button),
+            ]
+            .spacing(8)
+            .align_y(iced::Alignment::Center),
+        ]
+        .push_maybe(view.reply_error.as_ref().map(|error| {
+            text(error).size(12).font(fonts::mono()).color(theme::colors().error)
+        }))
+        .spacing(6)
+        .into()
+    }
+
+    /// Post the reply as the interaction's result, the way a reviewing agent would
+    pub(crate) fn submit_review_reply(&mut self) -> Command<Message> {
+        let Some(view) = &self.state.diff_view else {
+            return Command::none();
+        };
+        let response = view.reply.trim().to_string();
+        if view.reply_sending || response.is_empty() {
+            return Command::none();
+        }
+        let Some(conv) = state::review_target(&self.state).and_then(|idx| self.state.conversations.get(idx)) else {
+            return Command::none();
+        };
+        let Some(interaction_id) = message_errors::interaction_id(conv) else {
+            return Command::none();
+        };
+
+        let request = SubmitResultRequest {
+            response,
+            metadata: Some(serde_json::json!({
+                "agentId": REVIEWER_AGENT_ID,
+                "review": { "worktreeId": view.worktree_id }
+            })),
+        };
+        let server = conv.server.clone();
+        println!("[GUI] Posting review reply to {}", interaction_id);
+        update(&mut self.state, Action::ReviewReplySending);
+
+        let api = self.api_for(&server);
+        self.tasks.run(
+            "submit result",
+            async move {
+                let result = api.submit_result(&interaction_id, request).await;
+                (server, interaction_id, result)
+            },
+            |(server, id, result)| Message::ReviewReplySubmitted(server, id, result)
+        )
+    }
+
+    pub(crate) fn review_reply_submitted(&mut self, server: String, interaction_id: String, result: Result<(), ApiError>) -> Command<Message> {
+        match result {
+            Ok(()) => {
+                update(&mut self.state, Action::ReviewReplySent);
+                // Show the reply in the transcript without waiting for the stream
+                self.fetch_interaction(server, interaction_id)
+            }
+            Err(e) => {
+                eprintln!("[GUI] Failed to post review reply to {}: {}", interaction_id, e);
+                update(&mut self.state, Action::ReviewReplyFailed(e.to_string()));
+                Command::none()
+            }
+        }
+    }
+
+    fn build_diff_file_list(&self) -> Element<'_, Message> {
+        let Some(view) = &self.state.diff_view else {
+            return Space::with_height(0).into();
+        };
+
+        let files = view.files.iter().enumerate().map(|(idx, file)| {
+            let is_selected = idx == view.selected_file;
+            let path = match &file.old_path {
+                Some(old_path) => format!("{} → {}", old_path, file.path),
+                None => file.path.clone(),
+            };
+
+            button(
+                row![
+                    text(status_marker(file)).size(12).font(fonts::mono()).color(theme::colors().text_dim),
+                    text(path).size(12).font(fonts::mono()),
+                    Space::with_width(Length::Fill),
+                    text(format!("+{}", file.added())).size(12).font(fonts::mono()).color(theme::colors().success),
+                    text(format!("-{}", file.removed())).size(12).font(fonts::mono()).color(theme::colors().error),
+                ]
+                .spacing(8)
+            )
+            .on_press(Message::SelectDiffFile(idx))
+            .width(Length::Fill)
+            .padding(4)
+            .style(move |theme, _| {
+                if is_selected {
+                    theme::channel_button_active(theme)
+                } else {
+                    theme::channel_button_inactive(theme)
+                }
+            })
+            .into()
+        });
+
+        scrollable(Column::with_children(files).spacing(2))
+            .direction(scrolling::scrollbar())
+            .style(theme::scrollbar)
+            .height(Length::Fixed(160.0))
+            .into()
+    }
+
+    fn build_diff_hunks(&self) -> Element<'_, Message> {
+        let Some(file) = self.state.diff_view.as_ref().and_then(|view| view.files.get(view.selected_file)) else {
+            return Space::with_height(0).into();
+        };
+        let collapsed = self.state.diff_view.as_ref().map(|view| &view.collapsed);
+        let tint = self.diff_tint();
+
+        let mut hunks = column![].spacing(8);
+
+        if file.binary {
+            hunks = hunks.push(text("Binary file").size(12).font(fonts::mono()).color(theme::colors().text_dim));
+        }
+
+        let total_lines: usize = file.hunks.iter().map(|hunk| hunk.lines.len()).sum();
+        if total_lines > MAX_RENDERED_LINES {
+            hunks = hunks.push(
+                text(format!("{} changed lines, too many to show here", total_lines))
+                    .size(12)
+                    .font(fonts::mono())
+                    .color(theme::colors().text_dim),
+            );
+            return scrollable(hunks)
+                .direction(scrolling::scrollbar())
+                .style(theme::scrollbar)
+                .height(Length::Fill)
+                .into();
+        }
+
+        for (idx, hunk) in file.hunks.iter().enumerate() {
+            let is_collapsed = collapsed.is_some_and(|collapsed| collapsed.contains(&idx));
+            let arrow = if is_collapsed { "▸" } else { "▾" };
+
+            hunks = hunks.push(
+                button(
+                    text(format!("{} {}", arrow, hunk.header))
+                        .size(12)
+                        .font(fonts::mono())
+                        .color(theme::colors().primary),
+                )
+                .on_press(Message::ToggleHunk(idx))
+                .padding(2)
+                .style(theme::icon_button),
+            );
+
+            if is_collapsed {
+                continue;
+            }
+
+            let lines = hunk.lines.iter().map(|line| {
+                let (sign, color, background) = line_colors(line.kind, tint);
+                container(
+                    text(format!("{}{}", sign, line.text))
+                        .size(12)
+                        .font(fonts::mono())
+                        .color(color),
+                )
+                .width(Length::Fill)
+                .style(move |_theme: &Theme| container::Style {
+                    background: background.map(iced::Background::Color),
+                    ..Default::default()
+                })
+                .into()
+            });
+
+            hunks = hunks.push(
+                container(Column::with_children(lines))
+                    .padding(6)
+                    .width(Length::Fill)
+                    .style(theme::code_container),
+            );
+        }
+
+        scrollable(hunks)
+            .direction(scrolling::scrollbar())
+            .style(theme::scrollbar)
+            .height(Length::Fill)
+            .into()
+    }
+}
@@ -0,0 +1,173 @@
+use chrono::{DateTime, Duration, Utc};
+use iced::Task as Command;
+
+use crate::state::{self, update, Action, AppState, Notification};
+use crate::types::{LegacyMessage, MessageStatus};
+use crate::widgets::multiline_input;
+use crate::{export, BickyApp, Message};
+
+// How far back a digest looks
+const PERIOD_DAYS: i64 = 7;
+// Completions listed as notable, the ones that took the most tokens
+const NOTABLE: usize = 5;
+// Prompts are named by their first line, cut to this
+const PROMPT_CHARS: usize = 80;
+
+/// One conversation's week
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConversationWeek {
+    pub title: String,
+    pub messages: usize,
+    pub tokens: u64,
+}
+
+/// A finished message worth a mention
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Completion {
+    pub conversation: String,
+    pub prompt: String,
+    pub tokens: u64,
+}
+
+/// What happened on the active server over a week
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Digest {
+    pub since: DateTime<Utc>,
+    pub until: DateTime<Utc>,
+    /// Most active first
+    pub conversations: Vec<ConversationWeek>,
+    /// Branches, or paths for worktrees without one
+    pub worktrees: Vec<String>,
+    pub tokens: u64,
+    pub completions: Vec<Completion>,
+}
+
+fn tokens(msg: &LegacyMessage) -> u64 {
+    msg.metadata.as_ref().and_then(|m| m.tokens.as_ref()).map_or(0, |t| u64::from(t.total))
+}
+
+fn first_line(content: &str) -> String {
+    let line = content.lines().find(|l| !l.trim().is_empty()).unwrap_or_default().trim();
+    let ellipsis = if line.chars().count() > PROMPT_CHARS { "…" } else { "" };
+    format!("{}{}", line.chars().take(PROMPT_CHARS).collect::<String>(), ellipsis)
+}
+
+/// The week up to `now`, from messages sent in it
+pub fn weekly(state: &AppState, now: DateTime<Utc>) -> Digest {
+    let since = now - Duration::days(PERIOD_DAYS);
+    let in_week = |msg: &&LegacyMessage| msg.sent_at.is_some_and(|t| t > since && t <= now);
+
+    let mut digest = Digest {
+        since,
+        until: now,
+        conversations: Vec::new(),
+        worktrees: Vec::new(),
+        tokens: 0,
+        completions: Vec::new(),
+    };
+    for conv in state.conversations.iter().filter(|c| state::on_active_server(state, c)) {
+        let messages: Vec<&LegacyMessage> = conv.messages.iter().filter(in_week).collect();
+        if messages.is_empty() {
+            continue;
+        }
+        let spent = messages.iter().map(|m| tokens(m)).sum();
+        digest.tokens += spent;
+        digest.conversations.push(ConversationWeek { title: conv.title.clone(), messages: messages.len(), tokens: spent });
+
+        if let Some(worktree_id) = &conv.worktree_id {
+            let name = state
+                .editor
+                .available_worktrees
+                .iter()
+                .find(|w| &w.id == worktree_id)
+                .map_or(worktree_id.clone(), |w| {
+                    w.branch.as_deref().map(|b| b.strip_prefix("refs/heads/").unwrap_or(b)).unwrap_or(&w.path).to_string()
+                });
+            if !digest.worktrees.contains(&name) {
+                digest.worktrees.push(name);
+            }
+        }
+
+        digest.completions.extend(messages.iter().filter(|m| m.status == MessageStatus::Completed).map(|m| Completion {
+            conversation: conv.title.clone(),
+            prompt: first_line(&m.content),
+            tokens: tokens(m),
+        }));
+    }
+
+    digest.conversations.sort_by(|a, b| b.messages.cmp(&a.messages).then_with(|| b.tokens.cmp(&a.tokens)));
+    digest.worktrees.sort();
+    digest.completions.sort_by_key(|c| std::cmp::Reverse(c.tokens));
+    digest.completions.truncate(NOTABLE);
+    digest
+}
+
+/// The digest as a Markdown document
+pub fn to_markdown(digest: &Digest) -> String {
+    let messages: usize = digest.conversations.iter().map(|c| c.messages).sum();
+    let mut markdown = format!(
+        "# Weekly digest\n\n{} to {}\n\n## Summary\n\n- {} conversations, {} messages\n- {} tokens spent\n- {} worktrees touched\n\n",
+        digest.since.format("%Y-%m-%d"),
+        digest.until.format("%Y-%m-%d"),
+        digest.conversations.len(),
+        messages,
+        digest.tokens,
+        digest.worktrees.len(),
+    );
+    if digest.conversations.is_empty() {
+        markdown.push_str("_Nothing happened this week._\n");
+        return markdown;
+    }
+
+    markdown.push_str("## Conversations\n\n| Conversation | Messages | Tokens |\n| --- | --- | --- |\n");
+    for conv in &digest.conversations {
+        markdown.push_str(&format!("| {} | {} | {} |\n", conv.title.replace('|', "\\|"), conv.messages, conv.tokens));
+    }
+    if !digest.worktrees.is_empty() {
+        markdown.push_str("\n## Worktrees touched\n\n");
+        for worktree in &digest.worktrees {
+            markdown.push_str(&format!("- {}\n", worktree));
+        }
+    }
+    if !digest.completions.is_empty() {
+        markdown.push_str("\n## Notable completions\n\n");
+        for completion in &digest.completions {
+            markdown.push_str(&format!("- **{}**: {} ({} tokens)\n", completion.conversation, completion.prompt, completion.tokens));
+        }
+    }
+    markdown
+}
+
+/// What Wake is asked to narrate the digest with
+pub fn narration_prompt(markdown: &str) -> String {
+    format!(
+        "Here is a digest of my past week of work with you. Write a short narrative summary of it: \
+         what I worked on, what got done and anything that looks unfinished.\n\n{}",
+        markdown.trim_end()
+    )
+}
+
+impl BickyApp {
+    /// Write the past week's digest into the exports, then optionally have Wake narrate it
+    pub(crate) fn generate_digest(&mut self, narrate: bool) -> Command<Message> {
+        let digest = weekly(&self.state, self.state.clock.utc());
+        let markdown = to_markdown(&digest);
+        let name = format!("Weekly digest {}", digest.until.format("%Y-%m-%d"));
+        let notification = match export::write_export(&name, &markdown) {
+            Ok(path) => Notification::Success(format!("Digest written to {}", path.display())),
+            Err(e) => Notification::Error(format!("Couldn't write the digest: {}", e)),
+        };
+        update(&mut self.state, Action::ShowNotification(notification));
+        if !narrate || digest.conversations.is_empty() {
+            return Command::none();
+        }
+
+        // Narrated in a conversation of its own, sent like anything typed
+        self.tasks.switch_conversation();
+        update(&mut self.state, Action::NewConversation);
+        let prompt = narration_prompt(&markdown);
+        multiline_input::set_text(&mut self.composer, &prompt);
+        update(&mut self.state, Action::InputChanged(prompt));
+        self.update(Message::SendMessage)
+    }
+}
@@ -0,0 +1,137 @@
+use bicky_editor_core::types::ServerConversation;
+use bicky_editor_core::{Action as EditorAction, Effect};
+use iced::Task as Command;
+
+use crate::state::{self, Action};
+use crate::types::{SendMessageRequest, SubmitResultRequest};
+use crate::{export, snapshot, BickyApp, Message};
+
+impl BickyApp {
+    /// Carry out what the core reducer asked for while the last message was handled
+    pub(crate) fn run_effects(&mut self) -> Command<Message> {
+        let effects = std::mem::take(&mut self.state.effects);
+        let commands: Vec<Command<Message>> = effects.into_iter().map(|effect| self.run_effect(effect)).collect();
+        Command::batch(commands)
+    }
+
+    fn run_effect(&mut self, effect: Effect) -> Command<Message> {
+        match effect {
+            // Sent into the current worktree, the answer comes back with its messages as content
+            Effect::SubmitInteraction { temp_id, content, metadata, interaction_id, .. } => {
+                let server = match &interaction_id {
+                    Some(id) => self.server_of(id),
+                    None => self.state.active_server.clone(),
+                };
+                let api = self.api_for(&server);
+                let request = SendMessageRequest {
+                    content,
+                    metadata: (!metadata.is_empty()).then(|| serde_json::json!(metadata)),
+                    worktree_id: self.state.editor.current_worktree.as_ref().map(|worktree| worktree.id.clone()),
+                    interaction_id,
+                    attachment_ids: Vec::new(),
+                };
+                self.tasks.run(
+                    "submit interaction",
+                    async move {
+                        let sent = api.send_message(request).await.map_err(|e| e.to_string())?;
+                        api.get_interaction(&sent.id, state::HISTORY_PAGE_SIZE, None)
+                            .await
+                            .map(ServerConversation::into_interaction)
+                            .map_err(|e| e.to_string())
+                    },
+                    move |result| Message::Editor(EditorAction::InteractionSubmitted { temp_id: temp_id.clone(), result }),
+                )
+            }
+
+            // The review goes back as a result, the feedback as its text
+            Effect::SubmitReview { interaction_id, approved, feedback } => {
+                let api = self.api_for(&self.server_of(&interaction_id));
+                let request = SubmitResultRequest {
+                    response: feedback.unwrap_or_else(|| if approved { "Approved" } else { "Changes requested" }.to_string()),
+                    metadata: Some(serde_json::json!({ "review": { "approved": approved } })),
+                };
+                self.tasks.run(
+                    "submit review",
+                    async move { api.submit_result(&interaction_id, request).await },
+                    |result| Message::Editor(EditorAction::ReviewSubmitted(result.map_err(|e| e.to_string()))),
+                )
+            }
+
+            Effect::RespondToPermission { interaction_id, approved } => {
+                let api = self.api_for(&self.server_of(&interaction_id));
+                self.tasks.run(
+                    "respond to permission",
+                    async move { api.respond_to_permission(&interaction_id, approved).await },
+                    |result| Message::Editor(EditorAction::PermissionResponded(result.map_err(|e| e.to_string()))),
+                )
+            }
+
+            // Every server streams already, this moves the active one onto the session it should follow
+            Effect::ConnectToStream { .. } => {
+                self.start_sse();
+                Command::none()
+            }
+
+            Effect::FetchSessions => self.load_sessions(),
+
+            Effect::CreateSession { name } => {
+                let api = self.api_client.clone();
+                self.tasks.run(
+                    "create session",
+                    async move { api.create_session(name.as_deref()).await },
+                    |result| Message::Editor(EditorAction::SessionCreated(result.map_err(|e| e.to_string()))),
+                )
+            }
+
+            // The default stream has no counts of its own to fetch
+            Effect::FetchQueueStatus => match state::active_session(&self.state) {
+                Some(session_id) => self.load_session_counts(session_id.to_string()),
+                None => Command::none(),
+            },
+
+            // Written before returning, it runs on the way out too
+            Effect::PersistState { snapshot } => {
+                if let Err(e) = snapshot::persist(&snapshot) {
+                    eprintln!("[GUI] Failed to save snapshot: {}", e);
+                    state::update(
+                        &mut self.state,
+                        Action::ShowNotification(state::Notification::Error(format!("Couldn't save drafts: {}", e))),
+                    );
+                }
+                Command::none()
+            }
+
+            // Tags were already changed in the core, this saves them on the conversation's server
+            Effect::UpdateInteractionTags { interaction_id, tags } => {
+                let server = self.server_of(&interaction_id);
+                let api = self.api_for(&server);
+                self.tasks.run(
+                    "update tags",
+                    async move {
+                        let result = api.update_interaction_tags(&interaction_id, &tags).await;
+                        (server, interaction_id, result)
+                    },
+                    |(server, interaction_id, result)| Message::TagsUpdated(server, interaction_id, result),
+                )
+            }
+
+            Effect::WriteExport { name, contents } => {
+                let result = export::write_export(&name, &contents).map(|path| path.display().to_string());
+                Command::done(Message::Editor(EditorAction::ReviewReportExported(result)))
+            }
+
+            Effect::ArchiveInteractions { interactions } => {
+                Command::done(Message::Editor(EditorAction::InteractionsArchived(snapshot::archive(&interactions))))
+            }
+
+            // An archive that's gone missing is fetched again from the server
+            Effect::LoadArchivedInteraction { interaction_id } => match snapshot::unarchive(&interaction_id) {
+                Ok(interaction) => Command::done(Message::Editor(EditorAction::ArchivedInteractionLoaded {
+                    interaction_id,
+                    result: Ok(interaction),
+                })),
+                Err(_) => self.fetch_interaction(self.server_of(&interaction_id), interaction_id),
+            },
+        }
+    }
+}
@@ -0,0 +1,154 @@
+use futures::stream::{self, Stream};
+use iced::Subscription;
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex};
+use tokio::sync::Notify;
+
+use crate::desktop::DesktopEvent;
+use crate::sse::SSEMessage;
+
+/// Something background work has to tell the UI
+#[derive(Debug, Clone)]
+pub enum AppEvent {
+    /// From the stream of the named server
+    Sse { server: String, message: SSEMessage },
+    Desktop(DesktopEvent),
+}
+
+impl From<DesktopEvent> for AppEvent {
+    fn from(event: DesktopEvent) -> Self {
+        AppEvent::Desktop(event)
+    }
+}
+
+// Most events the UI handles before it gets to redraw
+pub const BATCH_SIZE: usize = 32;
+
+// Events that can wait at once, past this new ones are dropped
+pub const BACKLOG_CAPACITY: usize = 2048;
+
+/// Events waiting for the UI
+///
+/// Updates to an interaction that already has one waiting replace it, the UI
+/// refetches the interaction either way.
+#[derive(Debug, Default)]
+pub struct Backlog {
+    events: VecDeque<AppEvent>,
+    /// Events dropped since the backlog was last empty
+    pub dropped: usize,
+}
+
+impl Backlog {
+    pub fn push(&mut self, event: AppEvent) {
+        if let Some(waiting) = self.events.iter_mut().find(|waiting| same_interaction(waiting, &event)) {
+            *waiting = event;
+        } else if self.events.len() < BACKLOG_CAPACITY {
+            self.events.push_back(event);
+        } else {
+            self.dropped += 1;
+        }
+    }
+
+    /// Up to `max` of the oldest events
+    pub fn take(&mut self, max: usize) -> Vec<AppEvent> {
+        let taken = self.events.drain(..max.min(self.events.len())).collect();
+        if self.is_empty() {
+            self.dropped = 0;
+        }
+        taken
+    }
+
+    pub fn len(&self) -> usize {
+        self.events.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.events.is_empty()
+    }
+}
+
+fn same_interaction(a: &AppEvent, b: &AppEvent) -> bool {
+    match (a, b) {
+        (
+            AppEvent::Sse { server: a_server, message: SSEMessage::InteractionUpdate { id: a_id, .. } },
+            AppEvent::Sse { server: b_server, message: SSEMessage::InteractionUpdate { id: b_id, .. } },
+        ) => a_server == b_server && a_id == b_id,
+        _ => false,
+    }
+}
+
+/// What the UI gets from the inbox at once
+#[derive(Debug, Clone)]
+pub struct Batch {
+    pub events: Vec<AppEvent>,
+    /// Events still waiting behind these
+    pub remaining: usize,
+}
+
+#[derive(Debug, Default)]
+struct Shared {
+    backlog: Mutex<Backlog>,
+    ready: Notify,
+}
+
+/// Publishing side of the event bus, cheap to clone into tasks and threads
+#[derive(Debug, Clone)]
+pub struct EventBus {
+    shared: Arc<Shared>,
+}
+
+impl EventBus {
+    /// Queue an event for the UI, dropped when too many are already waiting
+    pub fn publish(&self, event: impl Into<AppEvent>) {
+        let Ok(mut backlog) = self.shared.backlog.lock() else {
+            return;
+        };
+        let was_dropping = backlog.dropped > 0;
+        backlog.push(event.into());
+        if backlog.dropped > 0 && !was_dropping {
+            eprintln!("[GUI] {} events waiting, dropping new ones until the UI catches up", BACKLOG_CAPACITY);
+        }
+        drop(backlog);
+        self.shared.ready.notify_one();
+    }
+}
+
+/// Receiving side, handed to the UI as a subscription
+///
+/// Events arrive in the order they were published, across every publisher.
+#[derive(Debug, Clone)]
+pub struct Inbox {
+    shared: Arc<Shared>,
+}
+
+pub fn bus() -> (EventBus, Inbox) {
+    let shared = Arc::new(Shared::default());
+    (EventBus { shared: shared.clone() }, Inbox { shared })
+}
+
+impl Inbox {
+    pub fn subscription(&self) -> Subscription<Batch> {
+        // Keyed by type so iced keeps one stream running across view updates
+        Subscription::run_with_id(std::any::TypeId::of::<Inbox>(), self.stream())
+    }
+
+    /// The next batch of waiting events, `None` when nothing is waiting
+    pub fn take_batch(&self) -> Option<Batch> {
+        let mut backlog = self.shared.backlog.lock().ok()?;
+        let events = backlog.take(BATCH_SIZE);
+        (!events.is_empty()).then(|| Batch { events, remaining: backlog.len() })
+    }
+
+    fn stream(&self) -> impl Stream<Item = Batch> {
+        let inbox = self.clone();
+        // A burst is handed over a batch at a time so the UI redraws in between
+        stream::unfold(inbox, |inbox| async move {
+            loop {
+                if let Some(batch) = inbox.take_batch() {
+                    return Some((batch, inbox));
+                }
+                inbox.shared.ready.notified().await;
+            }
+        })
+    }
+}
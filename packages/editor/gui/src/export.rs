@@ -0,0 +1,241 @@
+use bicky_editor_core::report;
+use iced::widget::{button, column, container, row, scrollable, text, Space, Stack};
+use iced::{Border, Color, Element, Length, Theme};
+
+use crate::state::{Conversation, ExportDialogState};
+use crate::types::MessageStatus;
+use crate::{fonts, scrolling, theme, BickyApp, Message};
+use std::path::PathBuf;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+// Lines of the export shown in the dialog before it's cut off
+const PREVIEW_LINES: usize = 40;
+
+/// Which messages of a conversation go into an export
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ExportSelection {
+    /// First and last message exported, counted from zero and both included
+    pub from: usize,
+    pub to: usize,
+    /// What was asked
+    pub prompts: bool,
+    /// What Wake answered, along with notes on failed or interrupted ones
+    pub responses: bool,
+}
+
+impl ExportSelection {
+    /// The whole conversation
+    pub fn all(conv: &Conversation) -> Self {
+        Self {
+            from: 0,
+            to: conv.messages.len().saturating_sub(1),
+            prompts: true,
+            responses: true,
+        }
+    }
+
+    /// Whether exporting would write any messages at all
+    pub fn is_empty(&self, conv: &Conversation) -> bool {
+        !(self.prompts || self.responses) || self.from >= conv.messages.len()
+    }
+}
+
+/// Render the selected part of a conversation as Markdown
+pub fn selection_to_markdown(conv: &Conversation, selection: &ExportSelection) -> String {
+    let mut markdown = format!("# {}\n\n", conv.title);
+
+    let selected = conv.messages.iter().skip(selection.from).take((selection.to + 1).saturating_sub(selection.from));
+    for msg in selected {
+        if selection.prompts {
+            markdown.push_str("## You\n\n");
+            markdown.push_str(msg.content.trim_end());
+            markdown.push_str("\n\n");
+        }
+
+        if !selection.responses {
+            continue;
+        }
+        if let Some(response) = &msg.response {
+            let model = msg.metadata.as_ref().and_then(|m| m.model.as_deref());
+            match model {
+                Some(model) => markdown.push_str(&format!("## Wake ({})\n\n", model)),
+                None => markdown.push_str("## Wake\n\n"),
+            }
+            markdown.push_str(response.trim_end());
+            markdown.push_str("\n\n");
+        } else if matches!(msg.status, MessageStatus::Error | MessageStatus::Failed) {
+            markdown.push_str("_No response: the interaction failed._\n\n");
+        } else if msg.status == MessageStatus::Cancelled {
+            markdown.push_str("_No response: the interaction was interrupted._\n\n");
+        }
+    }
+
+    markdown
+}
+
+/// The start of an export, with a note of how much more there is
+pub fn preview(markdown: &str) -> String {
+    let lines: Vec<&str> = markdown.trim_end().lines().collect();
+    if lines.len() <= PREVIEW_LINES {
+        return lines.join("\n");
+    }
+    format!(
+        "{}\n\n… {} more lines",
+        lines[..PREVIEW_LINES].join("\n"),
+        lines.len() - PREVIEW_LINES
+    )
+}
+
+/// Write an export into `./exports`, returns the path of the new file
+pub fn write_export(name: &str, contents: &str) -> Result<PathBuf, String> {
+    let dir = std::env::current_dir()
+        .map_err(|e| e.to_string())?
+        .join("exports");
+    std::fs::create_dir_all(&dir).map_err(|e| e.to_string())?;
+
+    let timestamp = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or_default();
+    let path = dir.join(report::export_file_name(name, timestamp));
+
+    std::fs::write(&path, contents).map_err(|e| e.to_string())?;
+    Ok(path)
+}
+
+impl BickyApp {
+    pub(crate) fn build_export_dialog<'a>(&self, dialog: &'a ExportDialogState, main_view: Element<'a, Message>) -> Element<'a, Message> {
+        let Some(conv) = self.state.conversations.get(dialog.conversation_idx) else {
+            return main_view;
+        };
+        let selection = dialog.selection;
+        let count = conv.messages.len();
+        let empty = selection.is_empty(conv);
+
+        let range = row![
+            text("Messages").size(14).font(fonts::mono()),
+            Space::with_width(Length::Fill),
+            stepper(selection.from, count, Message::SetExportFrom),
+            text("to").size(14).font(fonts::mono()).color(theme::colors().text_dim),
+            stepper(selection.to, count, Message::SetExportTo),
+            text(format!("of {}", count)).size(14).font(fonts::mono()).color(theme::colors().text_dim),
+        ]
+        .spacing(8)
+        .align_y(iced::Alignment::Center);
+
+        let roles = row![
+            text("Include").size(14).font(fonts::mono()),
+            Space::with_width(Length::Fill),
+            toggle("Prompts", selection.prompts, Message::ToggleExportPrompts),
+            toggle("Responses", selection.responses, Message::ToggleExportResponses),
+        ]
+        .spacing(8)
+        .align_y(iced::Alignment::Center);
+
+        let preview_text = if empty {
+            "Nothing selected".to_string()
+        } else {
+            preview(&selection_to_markdown(conv, &selection))
+        };
+        let preview_box = container(
+            scrollable(text(preview_text).size(12).font(fonts::mono()).color(theme::colors().text_dim))
+                .direction(scrolling::scrollbar())
+                .style(theme::scrollbar)
+                .height(Length::Fill),
+        )
+        .padding(10)
+        .height(280)
+        .width(Length::Fill)
+        .style(|_theme: &Theme| container::Style {
+            border: Border {
+                color: theme::colors().border,
+                width: 1.0,
+                radius: 4.0.into(),
+            },
+            ..Default::default()
+        });
+
+        let content = column![
+            text(format!("Export {}", conv.title)).size(18).font(fonts::bold()),
+            Space::with_height(12),
+            range,
+            roles,
+            Space::with_height(8),
+            preview_box,
+            Space::with_height(12),
+            row![
+                button(text("Cancel").size(14).font(fonts::mono()))
+                    .on_press(Message::CloseExportDialog)
+                    .padding(10)
+                    .style(theme::secondary_button),
+                Space::with_width(10),
+                button(text("Export").size(14).font(fonts::mono()))
+                    .on_press_maybe((!empty).then_some(Message::ExportConversation))
+                    .padding(10)
+                    .style(theme::primary_button),
+            ]
+            .align_y(iced::Alignment::Center),
+        ]
+        .spacing(8);
+
+        let dialog_content = container(content.padding(30).width(560))
+            .style(|_theme: &Theme| container::Style {
+                background: Some(iced::Background::Color(theme::colors().background)),
+                border: Border {
+                    color: theme::colors().border,
+                    width: 1.0,
+                    radius: 8.0.into(),
+                },
+                ..Default::default()
+            })
+            .center_x(Length::Fill)
+            .center_y(Length::Fill);
+
+        Stack::new()
+            .push(main_view)
+            .push(
+                container(dialog_content)
+                    .width(Length::Fill)
+                    .height(Length::Fill)
+                    .style(|_: &Theme| container::Style {
+                        background: Some(iced::Background::Color(Color::from_rgba(0.0, 0.0, 0.0, 0.7))),
+                        ..Default::default()
+                    }),
+            )
+            .width(Length::Fill)
+            .height(Length::Fill)
+            .into()
+    }
+}
+
+// A message number with buttons either side, shown counting from one
+fn stepper<'a>(value: usize, count: usize, on_change: fn(usize) -> Message) -> Element<'a, Message> {
+    let step = |label: &'a str, target: Option<usize>| {
+        button(text(label).size(14).font(fonts::mono()))
+            .on_press_maybe(target.map(on_change))
+            .padding([2, 6])
+            .style(theme::icon_button)
+    };
+    row![
+        step("◂", value.checked_sub(1)),
+        text(format!("{}", value + 1)).size(14).font(fonts::mono()),
+        step("▸", (value + 1 < count).then_some(value + 1)),
+    ]
+    .spacing(2)
+    .align_y(iced::Alignment::Center)
+    .into()
+}
+
+fn toggle<'a>(label: &'a str, on: bool, message: Message) -> Element<'a, Message> {
+    button(text(label).size(13).font(fonts::mono()))
+        .on_press(message)
+        .padding([4, 10])
+        .style(move |theme, _| {
+            if on {
+                theme::channel_button_active(theme)
+            } else {
+                theme::channel_button_inactive(theme)
+            }
+        })
+        .into()
+}
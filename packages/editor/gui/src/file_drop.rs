@@ -0,0 +1,97 @@
+use std::path::{Path, PathBuf};
+
+use iced::widget::{container, text, Stack};
+use iced::{Border, Element, Length, Theme};
+use iced::Task as Command;
+
+use crate::attachments::MAX_BYTES;
+use crate::state::{update, Action};
+use crate::widgets::multiline_input;
+use crate::{fonts, theme, BickyApp, Message};
+
+/// What a file dropped on the chat turns into
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DropAction {
+    /// Uploaded and sent with the next message
+    Attach,
+    /// Its path goes into the draft, for folders and files too big to upload
+    InsertPath,
+}
+
+pub fn drop_action(is_dir: bool, size: u64) -> DropAction {
+    if is_dir || size > MAX_BYTES {
+        DropAction::InsertPath
+    } else {
+        DropAction::Attach
+    }
+}
+
+/// How a dropped path is written into the draft
+///
+/// Paths inside the worktree become `@` mentions like typed ones, others
+/// are written out in full.
+pub fn reference(path: &Path, worktree: Option<&Path>) -> String {
+    match worktree.and_then(|root| path.strip_prefix(root).ok()) {
+        Some(relative) if !relative.as_os_str().is_empty() => format!("@{}", relative.display()),
+        _ => path.display().to_string(),
+    }
+}
+
+impl BickyApp {
+    pub(crate) fn file_dropped(&mut self, path: PathBuf) -> Command<Message> {
+        update(&mut self.state, Action::SetDropHover(false));
+        if self.state.active_conversation.is_none() {
+            return Command::none();
+        }
+        let Ok(metadata) = std::fs::metadata(&path) else {
+            eprintln!("[GUI] Dropped {} is gone", path.display());
+            return Command::none();
+        };
+
+        match drop_action(metadata.is_dir(), metadata.len()) {
+            DropAction::Attach => self.attach_file(path),
+            DropAction::InsertPath => {
+                let worktree = self.state.editor.current_worktree.as_ref().map(|w| PathBuf::from(&w.path));
+                let mut draft = self.state.editor.draft.content.clone();
+                if !draft.is_empty() && !draft.ends_with(char::is_whitespace) {
+                    draft.push(' ');
+                }
+                draft.push_str(&reference(&path, worktree.as_deref()));
+                draft.push(' ');
+                multiline_input::set_text(&mut self.composer, &draft);
+                update(&mut self.state, Action::InputChanged(draft));
+                Command::none()
+            }
+        }
+    }
+
+    /// Outlines the window while files are dragged over it
+    pub(crate) fn build_drop_target<'a>(&self, main_view: Element<'a, Message>) -> Element<'a, Message> {
+        let hint = container(
+            text("Drop to attach, folders and large files go in as paths")
+                .size(14)
+                .font(fonts::mono())
+                .color(theme::colors().primary),
+        )
+        .center_x(Length::Fill)
+        .center_y(Length::Fill)
+        .width(Length::Fill)
+        .height(Length::Fill)
+        .style(|_theme: &Theme| container::Style {
+            background: Some(iced::Background::Color(iced::Color { a: 0.15, ..theme::colors().primary })),
+            border: Border {
+                color: theme::colors().primary,
+                width: 2.0,
+                radius: 8.0.into(),
+            },
+            ..Default::default()
+        });
+
+        Stack::new()
+            .push(main_view)
+            .push(container(hint).padding(12).width(Length::Fill).height(Length::Fill))
+            .width(Length::Fill)
+            .height(Length::Fill)
+            .into()
+    }
+}
@@ -0,0 +1,30 @@
+use iced::widget::{container, Column};
+use iced::{Color, Element, Length};
+
+use crate::{BickyApp, Message};
+
+// Widest the transcript gets in focus mode, about 90 characters of body text
+const MEASURE: f32 = 760.0;
+// Processing indicators fade back this far so they don't pull the eye
+const DIM_ALPHA: f32 = 0.35;
+
+/// A processing indicator's color, faded in focus mode
+pub fn indicator(focus_mode: bool, color: Color) -> Color {
+    if focus_mode {
+        Color { a: color.a * DIM_ALPHA, ..color }
+    } else {
+        color
+    }
+}
+
+impl BickyApp {
+    /// The transcript and composer alone, centered at a readable width
+    pub(crate) fn build_focus_layout<'a>(&self, main_content: Column<'a, Message>) -> Element<'a, Message> {
+        container(main_content.max_width(MEASURE).height(Length::Fill))
+            .width(Length::Fill)
+            .height(Length::Fill)
+            .padding(20)
+            .center_x(Length::Fill)
+            .into()
+    }
+}
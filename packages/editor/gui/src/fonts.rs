@@ -1,3 +1,5 @@
+use std::sync::OnceLock;
+
 use iced::Font;
 
 // Font bytes embedded at compile time
@@ -25,5 +27,50 @@ pub const UNICODE_FONT: Font = Font {
     style: iced::font::Style::Normal,
 };
 
-// Main font to use everywhere
-pub const DEFAULT_FONT: Font = BERKELEY_MONO;
\ No newline at end of file
+pub const UNICODE_FONT_BOLD: Font = Font {
+    weight: iced::font::Weight::Bold,
+    ..UNICODE_FONT
+};
+
+// Set when the embedded fonts can't be used, with the reason
+static FALLBACK: OnceLock<String> = OnceLock::new();
+
+/// Check that every embedded font parses and has the family name we ask for
+///
+/// A font that doesn't register renders as blank text instead of falling
+/// back, so this has to pass before the embedded fonts are used at all.
+pub fn verify() -> Result<(), String> {
+    let mut db = fontdb::Database::new();
+    db.load_font_data(BERKELEY_MONO_BYTES.to_vec());
+    db.load_font_data(BERKELEY_MONO_SEMIBOLD_BYTES.to_vec());
+
+    for family in ["Berkeley Mono Variable", "Berkeley Mono"] {
+        let found = db
+            .faces()
+            .any(|face| face.families.iter().any(|(name, _)| name == family));
+        if !found {
+            return Err(format!("embedded font {} didn't load", family));
+        }
+    }
+    Ok(())
+}
+
+/// Render everything in the system monospace font from now on
+pub fn use_fallback(reason: String) {
+    eprintln!("[GUI] {}, falling back to the system monospace font", reason);
+    let _ = FALLBACK.set(reason);
+}
+
+/// Why the embedded fonts aren't in use, if they aren't
+pub fn fallback_reason() -> Option<&'static str> {
+    FALLBACK.get().map(String::as_str)
+}
+
+/// Main font to use everywhere
+pub fn mono() -> Font {
+    if FALLBACK.get().is_some() { UNICODE_FONT } else { BERKELEY_MONO }
+}
+
+pub fn bold() -> Font {
+    if FALLBACK.get().is_some() { UNICODE_FONT_BOLD } else { BERKELEY_MONO_BOLD }
+}
\ No newline at end of file
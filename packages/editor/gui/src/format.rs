@@ -0,0 +1,166 @@
+use bicky_editor_core::clock;
+use chrono::{DateTime, Datelike, Local, Utc};
+use std::sync::OnceLock;
+
+// Languages that write 1.234,5 or 1 234,5 rather than 1,234.5
+const DECIMAL_COMMA: [&str; 30] = [
+    "bg", "cs", "da", "de", "el", "es", "et", "fi", "fr", "hr", "hu", "id", "it", "lt", "lv",
+    "nb", "nl", "nn", "no", "pl", "pt", "ro", "ru", "sk", "sl", "sr", "sv", "tr", "uk", "vi",
+];
+// Of those, the ones grouping thousands with a space
+const SPACE_GROUPING: [&str; 16] = [
+    "bg", "cs", "et", "fi", "fr", "hu", "lt", "lv", "nb", "nn", "no", "pl", "ru", "sk", "sv", "uk",
+];
+// Languages writing dates as 25.12.
+const DOTTED_DATES: [&str; 12] = ["cs", "da", "de", "fi", "hr", "nb", "nn", "no", "pl", "ru", "sk", "uk"];
+// Regions on a 12 hour clock
+const TWELVE_HOUR: [&str; 6] = ["US", "CA", "AU", "NZ", "IN", "PH"];
+
+/// Order of day and month in short dates
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum DateOrder {
+    MonthDay,
+    DayMonth,
+    YearMonthDay,
+}
+
+/// How numbers, times and dates are written where the user is
+#[derive(Debug, Clone, PartialEq)]
+pub struct Locale {
+    pub decimal: char,
+    pub grouping: char,
+    pub hour24: bool,
+    pub date_order: DateOrder,
+    pub date_separator: char,
+}
+
+impl Default for Locale {
+    fn default() -> Self {
+        Self::from_tag("en_US")
+    }
+}
+
+impl Locale {
+    /// Read the system locale from the usual environment variables
+    pub fn detect() -> Self {
+        ["LC_ALL", "LC_NUMERIC", "LC_TIME", "LANG"]
+            .iter()
+            .filter_map(|var| std::env::var(var).ok())
+            .find(|tag| !tag.is_empty() && tag != "C" && tag != "POSIX")
+            .map(|tag| Self::from_tag(&tag))
+            .unwrap_or_default()
+    }
+
+    /// Conventions for a tag like `de_DE.UTF-8` or `en-GB`
+    pub fn from_tag(tag: &str) -> Self {
+        let tag = tag.split(['.', '@']).next().unwrap_or(tag);
+        let mut parts = tag.split(['_', '-']);
+        let language = parts.next().unwrap_or("en").to_lowercase();
+        let region = parts.next().unwrap_or("").to_uppercase();
+        let language = language.as_str();
+
+        let (decimal, grouping) = if region == "CH" {
+            ('.', '\'')
+        } else if DECIMAL_COMMA.contains(&language) {
+            (',', if SPACE_GROUPING.contains(&language) { '\u{a0}' } else { '.' })
+        } else {
+            ('.', ',')
+        };
+
+        let date_order = match (language, region.as_str()) {
+            ("ja" | "zh" | "ko" | "hu" | "lt" | "sv", _) => DateOrder::YearMonthDay,
+            (_, "US" | "PH") | ("en", "") => DateOrder::MonthDay,
+            _ => DateOrder::DayMonth,
+        };
+
+        let date_separator = match date_order {
+            DateOrder::YearMonthDay => '-',
+            _ if DOTTED_DATES.contains(&language) => '.',
+            _ => '/',
+        };
+
+        Self {
+            decimal,
+            grouping,
+            hour24: !(TWELVE_HOUR.contains(&region.as_str()) || (language == "en" && region.is_empty())),
+            date_order,
+            date_separator,
+        }
+    }
+
+    /// `12,345` or `12.345`
+    pub fn number(&self, value: u64) -> String {
+        let digits = value.to_string();
+        let mut grouped = String::with_capacity(digits.len() + digits.len() / 3);
+        for (idx, digit) in digits.chars().enumerate() {
+            if idx > 0 && (digits.len() - idx) % 3 == 0 {
+                grouped.push(self.grouping);
+            }
+            grouped.push(digit);
+        }
+        grouped
+    }
+
+    /// `1.5` or `1,5`
+    pub fn decimal(&self, value: f64, places: usize) -> String {
+        let formatted = format!("{:.*}", places, value);
+        if self.decimal == '.' {
+            formatted
+        } else {
+            formatted.replace('.', &self.decimal.to_string())
+        }
+    }
+
+    /// `950 tokens`, `1.2K tokens`, `3.4M tokens`
+    pub fn token_count(&self, tokens: u32) -> String {
+        match tokens {
+            t if t >= 1_000_000 => format!("{}M tokens", self.decimal(t as f64 / 1_000_000.0, 1)),
+            t if t >= 1_000 => format!("{}K tokens", self.decimal(t as f64 / 1_000.0, 1)),
+            t => format!("{} tokens", t),
+        }
+    }
+
+    /// `1.5s`
+    pub fn seconds(&self, millis: u64) -> String {
+        format!("{}s", self.decimal(millis as f64 / 1000.0, 1))
+    }
+
+    /// `14:05` or `2:05 PM`
+    pub fn time(&self, time: DateTime<Local>) -> String {
+        if self.hour24 {
+            time.format("%H:%M").to_string()
+        } else {
+            time.format("%-I:%M %p").to_string()
+        }
+    }
+
+    /// `12/25`, `25.12.` or `2024-12-25`, with the year when it isn't this one
+    pub fn date(&self, time: DateTime<Local>, now: DateTime<Local>) -> String {
+        let sep = self.date_separator;
+        let (day, month, year) = (time.day(), time.month(), time.year());
+        let this_year = year == now.year();
+
+        match self.date_order {
+            DateOrder::YearMonthDay => format!("{}{sep}{:02}{sep}{:02}", year, month, day),
+            DateOrder::MonthDay if this_year => format!("{}{sep}{}", month, day),
+            DateOrder::MonthDay => format!("{}{sep}{}{sep}{}", month, day, year),
+            DateOrder::DayMonth if this_year && sep == '.' => format!("{}.{}.", day, month),
+            DateOrder::DayMonth if this_year => format!("{}{sep}{}", day, month),
+            DateOrder::DayMonth => format!("{}{sep}{}{sep}{}", day, month, year),
+        }
+    }
+
+    /// Relative while recent, then the local date and time
+    pub fn timestamp(&self, time: DateTime<Utc>, now: DateTime<Utc>) -> String {
+        clock::format_relative(time, now).unwrap_or_else(|| {
+            let (time, now) = (time.with_timezone(&Local), now.with_timezone(&Local));
+            format!("{} {}", self.date(time, now), self.time(time))
+        })
+    }
+}
+
+/// The user's locale, detected once
+pub fn locale() -> &'static Locale {
+    static LOCALE: OnceLock<Locale> = OnceLock::new();
+    LOCALE.get_or_init(Locale::detect)
+}
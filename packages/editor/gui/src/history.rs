@@ -0,0 +1,82 @@
+use bicky_editor_core::types::ServerConversation;
+use iced::widget::{button, container, text};
+use iced::{Element, Length};
+use iced::Task as Command;
+
+use crate::api::ApiError;
+use crate::state::{self, update, Action, Conversation};
+use crate::{fonts, theme, BickyApp, Message};
+
+// Close enough to the top of the transcript to fetch the page before it
+const LOAD_THRESHOLD: f32 = 0.05;
+
+impl BickyApp {
+    /// Fetch older messages once the transcript is scrolled up to the oldest we have
+    pub(crate) fn transcript_near_top(&mut self) -> Command<Message> {
+        match self.transcript_scroll.visible_range() {
+            Some((top, _)) if top <= LOAD_THRESHOLD => self.load_older_history(),
+            _ => Command::none(),
+        }
+    }
+
+    pub(crate) fn load_older_history(&mut self) -> Command<Message> {
+        let Some(conversation_idx) = self.state.active_conversation else {
+            return Command::none();
+        };
+        let Some(conv) = self.state.conversations.get(conversation_idx) else {
+            return Command::none();
+        };
+        let Some(before) = conv.history_cursor.clone().filter(|_| !conv.loading_history) else {
+            return Command::none();
+        };
+
+        let server = conv.server.clone();
+        let id = conv.id.clone();
+        println!("[GUI] Loading messages before {} in {}", before, id);
+        update(&mut self.state, Action::HistoryRequested(conversation_idx));
+
+        let api = self.api_for(&server);
+        self.tasks.run(
+            "get history",
+            async move {
+                let result = api.get_interaction(&id, state::HISTORY_PAGE_SIZE, Some(&before)).await;
+                (server, id, result)
+            },
+            |(server, id, result)| Message::HistoryLoaded(server, id, result)
+        )
+    }
+
+    pub(crate) fn history_loaded(&mut self, server: String, conversation_id: String, result: Result<ServerConversation, ApiError>) -> Command<Message> {
+        let page = result
+            .map_err(|e| e.to_string())
+            .map(|conversation| (self.convert_server_conversation(&server, &conversation), conversation.has_more));
+
+        match page {
+            Ok((mut messages, has_more)) => {
+                messages.retain(|m| !self.state.deleted_messages.contains(&m.id));
+                update(&mut self.state, Action::HistoryLoaded { server, conversation_id, messages, has_more });
+            }
+            Err(e) => {
+                eprintln!("[GUI] Failed to load history for {} from {}: {}", conversation_id, server, e);
+                update(&mut self.state, Action::HistoryFailed { server, conversation_id });
+            }
+        }
+        Command::none()
+    }
+
+    /// Sits above the oldest message while the server has older ones
+    pub(crate) fn build_history_loader(&self, conv: &Conversation) -> Option<Element<'_, Message>> {
+        conv.history_cursor.as_ref()?;
+
+        let content: Element<'_, Message> = if conv.loading_history {
+            text("Loading earlier messages…").size(12).font(fonts::mono()).color(theme::colors().text_dim).into()
+        } else {
+            button(text("Load earlier messages").size(12).font(fonts::mono()))
+                .on_press(Message::LoadOlderHistory)
+                .padding(6)
+                .style(theme::secondary_button)
+                .into()
+        };
+        Some(container(content).center_x(Length::Fill).into())
+    }
+}
@@ -0,0 +1,226 @@
+use iced::Task as Command;
+use rhai::module_resolvers::DummyModuleResolver;
+use rhai::{Dynamic, Engine, Scope};
+use serde_json::{json, Value};
+use std::path::{Component, Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use crate::config::{self, HookEvent};
+use crate::state::{self, update, Action, Conversation};
+use crate::types::{LegacyMessage, MessageStatus, Worktree};
+use crate::{BickyApp, Message};
+
+// Scripts and the files hooks write go in here, next to Mind.toml
+const HOOKS_DIR: &str = "hooks";
+// Plenty for a hook that looks at an event and decides, a runaway loop is stopped well before it's noticed
+const MAX_OPERATIONS: u64 = 1_000_000;
+const TIME_LIMIT: Duration = Duration::from_secs(2);
+const MAX_CALL_LEVELS: usize = 32;
+const MAX_STRING_SIZE: usize = 1024 * 1024;
+
+/// What a hook can ask for, by calling `notify`, `write_file` or `respond`
+#[derive(Debug, Clone, PartialEq)]
+pub enum HookAction {
+    Notify { message: String },
+    /// `path` is relative to the hooks folder, anywhere else is refused
+    WriteFile { path: String, contents: String },
+    /// Approve or deny the permission request the hook was run for
+    Respond { approved: bool },
+}
+
+/// One hook script run on one event
+#[derive(Debug, Clone, PartialEq)]
+pub struct HookRun {
+    pub script: String,
+    pub event: HookEvent,
+    /// Server and conversation of the permission request it can respond to
+    pub permission: Option<(String, String)>,
+}
+
+/// Where hooks write, next to Mind.toml or here when there isn't one
+pub fn root() -> PathBuf {
+    config::find_config_file("Mind.toml")
+        .map(|mind| mind.with_file_name(HOOKS_DIR))
+        .unwrap_or_else(|| PathBuf::from(HOOKS_DIR))
+}
+
+/// `path` inside `root`, refusing absolute paths and any way out of it
+pub fn sandboxed(root: &Path, path: &str) -> Result<PathBuf, String> {
+    let relative = Path::new(path);
+    let inside = relative
+        .components()
+        .all(|component| matches!(component, Component::Normal(_) | Component::CurDir));
+    if !inside || relative.file_name().is_none() {
+        return Err(format!("{} isn't a file in the hooks folder", path));
+    }
+    Ok(root.join(relative))
+}
+
+/// Run a hook script with the event's payload as `event`, returns what it asked for
+///
+/// Scripts get no files, processes, network or imports, only the three
+/// functions that ask for a `HookAction`. They're stopped after
+/// `MAX_OPERATIONS` steps or `TIME_LIMIT`, whichever comes first.
+pub fn run_script(script: &str, payload: &Value) -> Result<Vec<HookAction>, String> {
+    let actions: Arc<Mutex<Vec<HookAction>>> = Arc::default();
+    let record = |action: fn(&str, &str, bool) -> HookAction| {
+        let actions = actions.clone();
+        move |text: &str, contents: &str, approved: bool| {
+            if let Ok(mut actions) = actions.lock() {
+                actions.push(action(text, contents, approved));
+            }
+        }
+    };
+    let notify = record(|message, _, _| HookAction::Notify { message: message.to_string() });
+    let write_file = record(|path, contents, _| HookAction::WriteFile { path: path.to_string(), contents: contents.to_string() });
+    let respond = record(|_, _, approved| HookAction::Respond { approved });
+
+    let mut engine = Engine::new();
+    engine
+        .set_module_resolver(DummyModuleResolver::new())
+        .set_max_operations(MAX_OPERATIONS)
+        .set_max_call_levels(MAX_CALL_LEVELS)
+        .set_max_string_size(MAX_STRING_SIZE);
+    let deadline = Instant::now() + TIME_LIMIT;
+    engine.on_progress(move |_| (Instant::now() > deadline).then_some(Dynamic::UNIT));
+    engine.register_fn("notify", move |message: &str| notify(message, "", false));
+    engine.register_fn("write_file", move |path: &str, contents: &str| write_file(path, contents, false));
+    engine.register_fn("respond", move |approved: bool| respond("", "", approved));
+
+    let mut scope = Scope::new();
+    scope.push_constant("event", rhai::serde::to_dynamic(payload).map_err(|e| e.to_string())?);
+    engine.run_with_scope(&mut scope, script).map_err(|e| e.to_string())?;
+
+    let actions = actions.lock().map_err(|e| e.to_string())?;
+    Ok(actions.clone())
+}
+
+/// Events new messages fire, with the message each is about
+pub fn fired<'a>(old: &[LegacyMessage], new: &'a [LegacyMessage]) -> Vec<(HookEvent, &'a LegacyMessage)> {
+    new.iter()
+        .filter(|msg| old.iter().find(|m| m.id == msg.id).map(|m| &m.status) != Some(&msg.status))
+        .filter_map(|msg| match msg.status {
+            MessageStatus::Completed => Some((HookEvent::MessageCompleted, msg)),
+            MessageStatus::WaitingForPermission if msg.pending_tool_permission.is_some() => {
+                Some((HookEvent::PermissionRequested, msg))
+            }
+            _ => None,
+        })
+        .collect()
+}
+
+/// What a hook on a message event is given
+pub fn message_payload(event: HookEvent, conv: &Conversation, msg: &LegacyMessage) -> Value {
+    let permission = msg.pending_tool_permission.as_ref().map(|permission| {
+        json!({
+            "tool": permission.tool_name,
+            "description": permission.description,
+            "input": permission.input,
+        })
+    });
+    json!({
+        "event": event.name(),
+        "server": conv.server,
+        "conversationId": conv.id,
+        "conversationTitle": conv.title,
+        "message": { "id": msg.id, "prompt": msg.content, "response": msg.response },
+        "permission": permission,
+    })
+}
+
+/// What a hook on `worktree_created` is given
+pub fn worktree_payload(server: &str, worktree: &Worktree) -> Value {
+    json!({
+        "event": HookEvent::WorktreeCreated.name(),
+        "server": server,
+        "worktree": worktree,
+    })
+}
+
+impl BickyApp {
+    /// Run every hook configured for `event`
+    pub(crate) fn run_hooks(&self, event: HookEvent, payload: Value, permission: Option<(String, String)>) -> Command<Message> {
+        let hooks = self.state.settings.hooks.iter().filter(|hook| hook.on == event);
+        Command::batch(hooks.map(|hook| {
+            let run = HookRun { script: hook.script.clone(), event, permission: permission.clone() };
+            let payload = payload.clone();
+            // Relative to the hooks folder, `join` keeps absolute paths as they are
+            let path = root().join(&hook.script);
+            self.tasks.run(
+                format!("hook {}", event.name()),
+                async move {
+                    tokio::task::spawn_blocking(move || {
+                        let script = std::fs::read_to_string(&path).map_err(|e| format!("couldn't read {}: {}", path.display(), e))?;
+                        run_script(&script, &payload)
+                    })
+                    .await
+                    .map_err(|e| e.to_string())?
+                },
+                move |result| Message::HookFinished(run.clone(), result),
+            )
+        }))
+    }
+
+    /// Hooks for what changed in a conversation's messages
+    pub(crate) fn conversation_hooks(&self, conv: &Conversation, new_messages: &[LegacyMessage]) -> Command<Message> {
+        Command::batch(fired(&conv.messages, new_messages).into_iter().map(|(event, msg)| {
+            let permission = (event == HookEvent::PermissionRequested).then(|| (conv.server.clone(), conv.id.clone()));
+            self.run_hooks(event, message_payload(event, conv, msg), permission)
+        }))
+    }
+
+    pub(crate) fn hook_finished(&mut self, run: HookRun, result: Result<Vec<HookAction>, String>) -> Command<Message> {
+        let actions = match result {
+            Ok(actions) => actions,
+            Err(e) => {
+                eprintln!("[GUI] Hook {} on {} failed: {}", run.script, run.event.name(), e);
+                update(&mut self.state, Action::ShowNotification(state::Notification::Error(
+                    format!("Hook {} failed: {}", run.script, e)
+                )));
+                return Command::none();
+            }
+        };
+
+        let mut commands = Vec::new();
+        for action in actions {
+            match action {
+                HookAction::Notify { message } => {
+                    update(&mut self.state, Action::ShowNotification(state::Notification::Info(message)));
+                }
+                HookAction::WriteFile { path, contents } => match sandboxed(&root(), &path) {
+                    Ok(target) => {
+                        let script = run.script.clone();
+                        commands.push(self.tasks.run(
+                            "hook write",
+                            async move {
+                                if let Some(dir) = target.parent() {
+                                    tokio::fs::create_dir_all(dir).await.map_err(|e| e.to_string())?;
+                                }
+                                tokio::fs::write(&target, contents).await.map_err(|e| e.to_string()).map(|_| target)
+                            },
+                            move |result| Message::HookWrote(script.clone(), path.clone(), result),
+                        ));
+                    }
+                    Err(e) => self.hook_wrote(run.script.clone(), path, Err(e)),
+                },
+                HookAction::Respond { approved } => match &run.permission {
+                    Some((server, interaction_id)) => {
+                        commands.push(self.answer_permission_from_hook(server, interaction_id, approved, &run.script));
+                    }
+                    None => eprintln!("[GUI] Hook {} responded on {}, there's nothing to answer", run.script, run.event.name()),
+                },
+            }
+        }
+        Command::batch(commands)
+    }
+
+    pub(crate) fn hook_wrote(&mut self, script: String, path: String, result: Result<PathBuf, String>) {
+        match result {
+            Ok(written) => println!("[GUI] Hook {} wrote {}", script, written.display()),
+            Err(e) => update(&mut self.state, Action::ShowNotification(state::Notification::Error(
+                format!("Hook {} couldn't write {}: {}", script, path, e)
+            ))),
+        }
+    }
+}
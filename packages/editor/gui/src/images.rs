@@ -0,0 +1,418 @@
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use base64::Engine;
+use iced::widget::{button, column, container, image, svg, text, Row, Space, Stack};
+use iced::{Border, Color, ContentFit, Element, Length, Theme};
+use iced::Task as Command;
+
+use crate::state::{update, Action, Conversation};
+use crate::types::LegacyMessage;
+use crate::{api, attachments, fonts, theme, BickyApp, Message};
+
+const EXTENSIONS: [&str; 7] = ["png", "jpg", "jpeg", "gif", "webp", "bmp", "svg"];
+// More than this in one message is a listing, not something to look at
+const MAX_PER_MESSAGE: usize = 6;
+// Images are loaded whole, bigger ones are left alone
+const MAX_BYTES: usize = 20 * 1024 * 1024;
+const LOAD_TIMEOUT: Duration = Duration::from_secs(30);
+const THUMBNAIL_HEIGHT: f32 = 120.0;
+const EXPANDED_HEIGHT: f32 = 480.0;
+
+/// Where an image mentioned in a message comes from
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ImageSource {
+    File(PathBuf),
+    Url(String),
+    /// A `data:` URI, the payload still base64 encoded
+    Inline { mime: String, data: String },
+}
+
+impl ImageSource {
+    /// What the image is cached under
+    pub fn key(&self) -> String {
+        match self {
+            ImageSource::File(path) => path.display().to_string(),
+            ImageSource::Url(url) => url.clone(),
+            // Payloads can be megabytes, their length and start tell them apart well enough
+            ImageSource::Inline { mime, data } => {
+                format!("data:{};{}:{}", mime, data.len(), data.chars().take(32).collect::<String>())
+            }
+        }
+    }
+
+    /// Whether it's loaded without asking, fetching a URL tells its server the message was read
+    pub fn loads_automatically(&self) -> bool {
+        !matches!(self, ImageSource::Url(_))
+    }
+
+    /// How the image is named on its card
+    pub fn label(&self) -> String {
+        match self {
+            ImageSource::File(path) => path.file_name().map_or_else(|| path.display().to_string(), |n| n.to_string_lossy().into_owned()),
+            ImageSource::Url(url) => {
+                let path = url.split(['?', '#']).next().unwrap_or(url);
+                path.rsplit('/').find(|segment| !segment.is_empty()).unwrap_or(url).to_string()
+            }
+            ImageSource::Inline { mime, .. } => format!("inline {}", mime),
+        }
+    }
+}
+
+fn has_image_extension(path: &str) -> bool {
+    let path = path.split(['?', '#']).next().unwrap_or(path);
+    path.rsplit_once('.')
+        .is_some_and(|(_, extension)| EXTENSIONS.contains(&extension.to_lowercase().as_str()))
+}
+
+// A reference as written, made absolute against the worktree when it's a relative path
+fn source_of(reference: &str, root: Option<&Path>) -> Option<ImageSource> {
+    if let Some(rest) = reference.strip_prefix("data:image/") {
+        let (mime, data) = rest.split_once(";base64,")?;
+        let data: String = data.chars().take_while(|c| c.is_ascii_alphanumeric() || matches!(c, '+' | '/' | '=')).collect();
+        return (!data.is_empty()).then(|| ImageSource::Inline { mime: format!("image/{}", mime), data });
+    }
+    if !has_image_extension(reference) {
+        return None;
+    }
+    if reference.starts_with("http://") || reference.starts_with("https://") {
+        return Some(ImageSource::Url(reference.to_string()));
+    }
+    let path = Path::new(reference.strip_prefix("file://").unwrap_or(reference));
+    if path.is_absolute() {
+        Some(ImageSource::File(path.to_path_buf()))
+    } else {
+        Some(ImageSource::File(root?.join(path)))
+    }
+}
+
+/// Images a message's text points at, in the order they appear
+///
+/// Markdown images and `data:` URIs count wherever they are. Bare URLs and
+/// paths only count when they end in an image extension and, for paths,
+/// name a directory so a passing mention of `logo.png` isn't loaded.
+pub fn find_images(text: &str, root: Option<&Path>) -> Vec<ImageSource> {
+    let mut references: Vec<&str> = Vec::new();
+
+    let mut rest = text;
+    while let Some(start) = rest.find("![") {
+        rest = &rest[start + 2..];
+        let Some(target) = rest.find("](").map(|idx| &rest[idx + 2..]) else {
+            break;
+        };
+        if let Some(end) = target.find(')') {
+            references.push(target[..end].split_whitespace().next().unwrap_or_default());
+        }
+    }
+
+    let trim = |c: char| matches!(c, '(' | ')' | '[' | ']' | '<' | '>' | '"' | '\'' | '`' | ',' | ';' | '!');
+    for word in text.split_whitespace() {
+        let word = word.trim_start_matches(trim).trim_end_matches(|c| trim(c) || matches!(c, '.' | ':'));
+        let word = word.rsplit_once("](").map_or(word, |(_, target)| target);
+        if word.starts_with("data:image/")
+            || ((word.starts_with("http://") || word.starts_with("https://") || word.contains('/')) && has_image_extension(word))
+        {
+            references.push(word);
+        }
+    }
+
+    let mut sources: Vec<ImageSource> = Vec::new();
+    for source in references.into_iter().filter_map(|reference| source_of(reference, root)) {
+        if !sources.contains(&source) {
+            sources.push(source);
+        }
+    }
+    sources.truncate(MAX_PER_MESSAGE);
+    sources
+}
+
+/// Images in a response and in the arguments of the tools it called
+pub fn message_images(msg: &LegacyMessage, root: Option<&Path>) -> Vec<ImageSource> {
+    let mut text = msg.response.clone().unwrap_or_default();
+    for call in msg.metadata.iter().flat_map(|m| m.tool_calls.iter().flatten()) {
+        if let Some(arguments) = call.arguments.as_object() {
+            for value in arguments.values().filter_map(|v| v.as_str()) {
+                text.push(' ');
+                text.push_str(value);
+            }
+        }
+    }
+    find_images(&text, root)
+}
+
+/// What could be read from an image's header
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ImageInfo {
+    pub format: &'static str,
+    /// Width and height in pixels, when the header says
+    pub dimensions: Option<(u32, u32)>,
+    pub bytes: usize,
+}
+
+/// Sniff the format and size of an image, `None` when it isn't one
+pub fn inspect(bytes: &[u8]) -> Option<ImageInfo> {
+    let be32 = |at: usize| Some(u32::from_be_bytes(bytes.get(at..at + 4)?.try_into().ok()?));
+    let le16 = |at: usize| Some(u32::from(u16::from_le_bytes(bytes.get(at..at + 2)?.try_into().ok()?)));
+    let be16 = |at: usize| Some(u32::from(u16::from_be_bytes(bytes.get(at..at + 2)?.try_into().ok()?)));
+    let info = |format, dimensions| Some(ImageInfo { format, dimensions, bytes: bytes.len() });
+
+    if bytes.starts_with(b"\x89PNG\r\n\x1a\n") {
+        return info("PNG", be32(16).zip(be32(20)));
+    }
+    if bytes.starts_with(b"GIF87a") || bytes.starts_with(b"GIF89a") {
+        return info("GIF", le16(6).zip(le16(8)));
+    }
+    if bytes.starts_with(b"BM") {
+        return info("BMP", None);
+    }
+    if bytes.starts_with(b"RIFF") && bytes.get(8..12) == Some(b"WEBP") {
+        return info("WebP", None);
+    }
+    if bytes.starts_with(&[0xff, 0xd8]) {
+        // Walk the segments to the frame header that holds the size
+        let mut at = 2;
+        while bytes.get(at) == Some(&0xff) {
+            let marker = *bytes.get(at + 1)?;
+            if matches!(marker, 0xc0..=0xc3 | 0xc5..=0xc7 | 0xc9..=0xcb | 0xcd..=0xcf) {
+                return info("JPEG", be16(at + 7).zip(be16(at + 5)));
+            }
+            at += 2 + be16(at + 2)? as usize;
+        }
+        return info("JPEG", None);
+    }
+    let start = String::from_utf8_lossy(&bytes[..bytes.len().min(256)]).to_lowercase();
+    if start.trim_start().starts_with("<svg") || (start.trim_start().starts_with("<?xml") && start.contains("<svg")) {
+        return info("SVG", None);
+    }
+    None
+}
+
+/// The image ready to draw, SVGs are drawn as vectors
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Preview {
+    Raster(image::Handle),
+    Vector(svg::Handle),
+}
+
+/// An image that loaded, its bytes kept in the handle for drawing
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LoadedImage {
+    pub info: ImageInfo,
+    pub preview: Preview,
+}
+
+impl LoadedImage {
+    /// Look at the bytes, `None` when they aren't an image
+    pub fn from_bytes(bytes: Vec<u8>) -> Option<Self> {
+        let info = inspect(&bytes)?;
+        let preview = if info.format == "SVG" {
+            Preview::Vector(svg::Handle::from_memory(bytes))
+        } else {
+            Preview::Raster(image::Handle::from_bytes(bytes))
+        };
+        Some(Self { info, preview })
+    }
+
+    fn view<'a>(&self, height: f32) -> Element<'a, Message> {
+        match &self.preview {
+            Preview::Raster(handle) => image(handle.clone()).height(height).content_fit(ContentFit::Contain).into(),
+            Preview::Vector(handle) => svg(handle.clone()).height(height).content_fit(ContentFit::Contain).into(),
+        }
+    }
+}
+
+/// Where an image is at, by its source's key
+#[derive(Debug, Clone, PartialEq)]
+pub enum ImageLoad {
+    Loading,
+    Loaded(LoadedImage),
+    Failed(String),
+}
+
+/// Read or download the image and look at what came back
+pub async fn load(source: ImageSource) -> Result<LoadedImage, String> {
+    let bytes = match source {
+        ImageSource::File(path) => {
+            let size = std::fs::metadata(&path).map_err(|e| e.to_string())?.len();
+            if size as usize > MAX_BYTES {
+                return Err(format!("too large ({})", attachments::size_label(size)));
+            }
+            std::fs::read(&path).map_err(|e| e.to_string())?
+        }
+        ImageSource::Url(url) => {
+            let resp = api::http().get(&url).timeout(LOAD_TIMEOUT).send().await.map_err(|e| e.to_string())?;
+            if !resp.status().is_success() {
+                return Err(format!("HTTP {}", resp.status().as_u16()));
+            }
+            if resp.content_length().is_some_and(|length| length as usize > MAX_BYTES) {
+                return Err("too large".to_string());
+            }
+            resp.bytes().await.map_err(|e| e.to_string())?.to_vec()
+        }
+        ImageSource::Inline { data, .. } => base64::engine::general_purpose::STANDARD
+            .decode(data.as_bytes())
+            .map_err(|e| e.to_string())?,
+    };
+    LoadedImage::from_bytes(bytes).ok_or_else(|| "not an image".to_string())
+}
+
+fn describe(source: &ImageSource, load: Option<&ImageLoad>) -> (String, Color) {
+    match load {
+        None if !source.loads_automatically() => ("remote · click to load".to_string(), theme::colors().text_dim),
+        None | Some(ImageLoad::Loading) => ("loading…".to_string(), theme::colors().text_dim),
+        Some(ImageLoad::Loaded(LoadedImage { info, .. })) => {
+            let size = attachments::size_label(info.bytes as u64);
+            match info.dimensions {
+                Some((width, height)) => (format!("{} {}×{} · {}", info.format, width, height, size), theme::colors().text_dim),
+                None => (format!("{} · {}", info.format, size), theme::colors().text_dim),
+            }
+        }
+        Some(ImageLoad::Failed(error)) => (format!("couldn't load: {}", error), theme::colors().error),
+    }
+}
+
+fn card_style(_theme: &Theme) -> container::Style {
+    container::Style {
+        background: Some(iced::Background::Color(theme::colors().surface_light)),
+        border: Border {
+            color: theme::colors().border,
+            width: 1.0,
+            radius: 6.0.into(),
+        },
+        ..Default::default()
+    }
+}
+
+impl BickyApp {
+    /// The directory relative image paths in a conversation are read from
+    fn image_root(&self, conv: &Conversation) -> Option<PathBuf> {
+        conv.worktree_id
+            .as_ref()
+            .and_then(|id| self.state.editor.available_worktrees.iter().find(|w| &w.id == id))
+            .map(|worktree| PathBuf::from(&worktree.path))
+            .or_else(|| std::env::current_dir().ok())
+    }
+
+    /// Start loading the active conversation's images that aren't cached yet
+    pub(crate) fn load_images(&mut self) -> Command<Message> {
+        let Some(conv) = self.state.active_conversation.and_then(|idx| self.state.conversations.get(idx)) else {
+            return Command::none();
+        };
+        let root = self.image_root(conv);
+        let mut sources: Vec<ImageSource> = conv.messages.iter().flat_map(|msg| message_images(msg, root.as_deref())).collect();
+        sources.retain(|source| source.loads_automatically() && !self.state.images.contains_key(&source.key()));
+        sources.dedup();
+
+        let loads: Vec<Command<Message>> = sources.into_iter().map(|source| self.load_image(source)).collect();
+        Command::batch(loads)
+    }
+
+    /// Load one image, remote ones only get here from a click
+    pub(crate) fn load_image(&mut self, source: ImageSource) -> Command<Message> {
+        let key = source.key();
+        if matches!(self.state.images.get(&key), Some(ImageLoad::Loading | ImageLoad::Loaded(_))) {
+            return Command::none();
+        }
+        update(&mut self.state, Action::ImageRequested(key.clone()));
+        self.tasks.run("load image", load(source), move |result| Message::ImageLoaded(key.clone(), result))
+    }
+
+    /// A card for each image the response points at, click one to expand it
+    pub(crate) fn build_message_images(&self, conv: &Conversation, msg: &LegacyMessage) -> Option<Element<'_, Message>> {
+        let sources = message_images(msg, self.image_root(conv).as_deref());
+        if sources.is_empty() {
+            return None;
+        }
+
+        let cards = sources.into_iter().map(|source| {
+            let load = self.state.images.get(&source.key());
+            let (details, color) = describe(&source, load);
+            let mut card = column![].spacing(4);
+            if let Some(ImageLoad::Loaded(loaded)) = load {
+                card = card.push(loaded.view(THUMBNAIL_HEIGHT));
+            }
+            card = card
+                .push(text(format!("🖼 {}", source.label())).size(12).font(fonts::mono()))
+                .push(text(details).size(11).font(fonts::mono()).color(color));
+            // Remote images wait for a click before anything is fetched
+            let press = match load {
+                None if !source.loads_automatically() => Message::LoadImage(source),
+                _ => Message::ExpandImage(Some(source)),
+            };
+            button(card).on_press(press).padding(0).style(theme::icon_button).into()
+        });
+        let cards = Row::with_children(cards.map(|card: Element<Message>| container(card).padding([6, 10]).style(card_style).into()))
+            .spacing(8)
+            .wrap();
+        Some(container(cards).padding(4).into())
+    }
+
+    pub(crate) fn build_expanded_image<'a>(&'a self, source: &'a ImageSource, main_view: Element<'a, Message>) -> Element<'a, Message> {
+        let load = self.state.images.get(&source.key());
+        let (details, color) = describe(source, load);
+        let location = match source {
+            ImageSource::File(path) => path.display().to_string(),
+            ImageSource::Url(url) => url.clone(),
+            ImageSource::Inline { data, .. } => format!("Embedded in the message, {} encoded", attachments::size_label(data.len() as u64)),
+        };
+
+        let picture: Element<'a, Message> = match load {
+            Some(ImageLoad::Loaded(loaded)) => loaded.view(EXPANDED_HEIGHT),
+            None if !source.loads_automatically() => button(text("Load image").size(14).font(fonts::mono()))
+                .on_press(Message::LoadImage(source.clone()))
+                .padding(10)
+                .style(theme::primary_button)
+                .into(),
+            _ => text("🖼").size(64).font(fonts::UNICODE_FONT).into(),
+        };
+        let preview = container(
+            column![picture, text(details).size(14).font(fonts::mono()).color(color)]
+                .spacing(12)
+                .align_x(iced::Alignment::Center),
+        )
+        .padding(12)
+        .center_x(Length::Fill)
+        .style(card_style);
+
+        let content = column![
+            text(source.label()).size(18).font(fonts::bold()),
+            text(location).size(12).font(fonts::mono()).color(theme::colors().text_dim),
+            Space::with_height(8),
+            preview,
+            Space::with_height(8),
+            button(text("Close").size(14).font(fonts::mono()))
+                .on_press(Message::ExpandImage(None))
+                .padding(10)
+                .style(theme::secondary_button),
+        ]
+        .spacing(8);
+
+        let dialog_content = container(content.padding(30).width(720))
+            .style(|_theme: &Theme| container::Style {
+                background: Some(iced::Background::Color(theme::colors().background)),
+                border: Border {
+                    color: theme::colors().border,
+                    width: 1.0,
+                    radius: 8.0.into(),
+                },
+                ..Default::default()
+            })
+            .center_x(Length::Fill)
+            .center_y(Length::Fill);
+
+        Stack::new()
+            .push(main_view)
+            .push(
+                container(dialog_content)
+                    .width(Length::Fill)
+                    .height(Length::Fill)
+                    .style(|_: &Theme| container::Style {
+                        background: Some(iced::Background::Color(Color::from_rgba(0.0, 0.0, 0.0, 0.7))),
+                        ..Default::default()
+                    }),
+            )
+            .width(Length::Fill)
+            .height(Length::Fill)
+            .into()
+    }
+}
@@ -0,0 +1,294 @@
+use serde_json::Value;
+use std::path::{Path, PathBuf};
+
+use crate::state::{self, Action};
+use crate::types::{ImportConversationRequest, ImportedMessage, ImportedRole};
+use crate::{BickyApp, Message};
+use iced::Task as Command;
+
+// Transcripts waiting to be imported, next to exports/
+const IMPORT_DIR: &str = "imports";
+// Imported ones move in here so they aren't imported twice
+const DONE_DIR: &str = "done";
+
+/// Transcripts we know how to read
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    /// A Claude Code session, one JSON entry per line or a JSON array of them
+    ClaudeCode,
+    /// Headings or bold names saying who's speaking, our own exports included
+    Markdown,
+}
+
+impl Format {
+    /// Picked by extension, `None` for files we don't read
+    pub fn of(path: &Path) -> Option<Self> {
+        match path.extension()?.to_str()?.to_ascii_lowercase().as_str() {
+            "jsonl" | "json" => Some(Self::ClaudeCode),
+            "md" | "markdown" | "txt" => Some(Self::Markdown),
+            _ => None,
+        }
+    }
+
+    /// How the server records where an import came from
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::ClaudeCode => "claude-code",
+            Self::Markdown => "markdown",
+        }
+    }
+}
+
+/// Where transcripts are picked up from
+pub fn inbox() -> PathBuf {
+    std::env::current_dir().unwrap_or_default().join(IMPORT_DIR)
+}
+
+/// Transcripts in `dir` we can read, by name
+pub fn pending(dir: &Path) -> Vec<PathBuf> {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return Vec::new();
+    };
+
+    let mut paths: Vec<PathBuf> = entries
+        .filter_map(Result::ok)
+        .map(|entry| entry.path())
+        .filter(|path| path.is_file() && Format::of(path).is_some())
+        .collect();
+    paths.sort();
+    paths
+}
+
+/// Move an imported transcript out of the way, returns where it went
+pub fn archive(path: &Path) -> Result<PathBuf, String> {
+    let (Some(dir), Some(name)) = (path.parent(), path.file_name()) else {
+        return Err(format!("{} isn't a file", path.display()));
+    };
+    let done = dir.join(DONE_DIR);
+    std::fs::create_dir_all(&done).map_err(|e| e.to_string())?;
+    let destination = done.join(name);
+    std::fs::rename(path, &destination).map_err(|e| e.to_string())?;
+    Ok(destination)
+}
+
+/// Read a transcript file into what the server imports
+pub fn read(path: &Path) -> Result<ImportConversationRequest, String> {
+    let format = Format::of(path).ok_or_else(|| "not a transcript we can read".to_string())?;
+    let contents = std::fs::read_to_string(path).map_err(|e| e.to_string())?;
+    parse(format, &contents)
+}
+
+/// Parse a transcript, consecutive messages from the same side become one
+pub fn parse(format: Format, contents: &str) -> Result<ImportConversationRequest, String> {
+    let (title, messages) = match format {
+        Format::ClaudeCode => parse_claude_code(contents)?,
+        Format::Markdown => parse_markdown(contents),
+    };
+    if messages.is_empty() {
+        return Err("no messages found".to_string());
+    }
+    Ok(ImportConversationRequest {
+        title,
+        from: format.name().to_string(),
+        messages,
+    })
+}
+
+fn parse_claude_code(contents: &str) -> Result<(Option<String>, Vec<ImportedMessage>), String> {
+    let entries: Vec<Value> = if contents.trim_start().starts_with('[') {
+        serde_json::from_str(contents).map_err(|e| e.to_string())?
+    } else {
+        // A session cut off mid-write still has its earlier lines
+        contents.lines().filter_map(|line| serde_json::from_str(line).ok()).collect()
+    };
+
+    let mut title = None;
+    let mut messages = Vec::new();
+    for entry in &entries {
+        if entry.get("type").and_then(Value::as_str) == Some("summary") {
+            title = title.or_else(|| entry.get("summary").and_then(Value::as_str).map(str::to_string));
+            continue;
+        }
+        // Notes Claude Code adds for itself, and sub-agents' own conversations
+        let flagged = |key: &str| entry.get(key).and_then(Value::as_bool) == Some(true);
+        if flagged("isMeta") || flagged("isSidechain") {
+            continue;
+        }
+
+        let Some(message) = entry.get("message") else {
+            continue;
+        };
+        let role = match message.get("role").and_then(Value::as_str) {
+            Some("user") => ImportedRole::User,
+            Some("assistant") => ImportedRole::Assistant,
+            _ => continue,
+        };
+        let text = text_of(message.get("content").unwrap_or(&Value::Null));
+        // Slash commands and their output, not something the user asked
+        if role == ImportedRole::User && (text.starts_with("<command-") || text.starts_with("<local-command-")) {
+            continue;
+        }
+        let timestamp = entry.get("timestamp").and_then(Value::as_str).map(str::to_string);
+        push(&mut messages, role, &text, timestamp);
+    }
+
+    Ok((title, messages))
+}
+
+// Only the text, tool calls and their results are left out
+fn text_of(content: &Value) -> String {
+    match content {
+        Value::String(text) => text.clone(),
+        Value::Array(blocks) => blocks
+            .iter()
+            .filter(|block| block.get("type").and_then(Value::as_str) == Some("text"))
+            .filter_map(|block| block.get("text").and_then(Value::as_str))
+            .collect::<Vec<_>>()
+            .join("\n\n"),
+        _ => String::new(),
+    }
+}
+
+fn parse_markdown(contents: &str) -> (Option<String>, Vec<ImportedMessage>) {
+    let mut title = None;
+    let mut messages = Vec::new();
+    let mut current: Option<(ImportedRole, String)> = None;
+    let mut in_fence = false;
+
+    let flush = |current: &mut Option<(ImportedRole, String)>, messages: &mut Vec<ImportedMessage>| {
+        if let Some((role, body)) = current.take() {
+            push(messages, role, &body, None);
+        }
+    };
+
+    for line in contents.lines() {
+        let trimmed = line.trim_start();
+        if trimmed.starts_with("```") || trimmed.starts_with("~~~") {
+            in_fence = !in_fence;
+        } else if !in_fence {
+            if let Some(heading) = heading(line) {
+                if let Some(role) = speaker(heading) {
+                    flush(&mut current, &mut messages);
+                    current = Some((role, String::new()));
+                    continue;
+                }
+                if line.starts_with("# ") && title.is_none() && current.is_none() && messages.is_empty() {
+                    title = Some(heading.to_string());
+                    continue;
+                }
+            }
+            if let Some((role, rest)) = bold_speaker(line) {
+                flush(&mut current, &mut messages);
+                current = Some((role, format!("{}\n", rest)));
+                continue;
+            }
+            // Our exports note missing responses, that's not part of the conversation
+            if trimmed.starts_with("_No response:") {
+                continue;
+            }
+        }
+
+        if let Some((_, body)) = &mut current {
+            body.push_str(line);
+            body.push('\n');
+        }
+    }
+    flush(&mut current, &mut messages);
+
+    (title, messages)
+}
+
+// "## You" to "You"
+fn heading(line: &str) -> Option<&str> {
+    let level = line.chars().take_while(|c| *c == '#').count();
+    if !(1..=6).contains(&level) {
+        return None;
+    }
+    line[level..].strip_prefix(' ').map(str::trim)
+}
+
+// "**User:** hi" to the user and "hi"
+fn bold_speaker(line: &str) -> Option<(ImportedRole, &str)> {
+    let inner = line.strip_prefix("**")?;
+    let end = inner.find("**")?;
+    let (name, rest) = (&inner[..end], &inner[end + 2..]);
+    let rest = match (name.strip_suffix(':'), rest.strip_prefix(':')) {
+        (Some(_), _) => rest,
+        (None, Some(rest)) => rest,
+        (None, None) => return None,
+    };
+    Some((speaker(name)?, rest.trim()))
+}
+
+// Names transcripts give each side, "Wake (sonnet)" is Wake
+fn speaker(name: &str) -> Option<ImportedRole> {
+    let name = name.trim().trim_end_matches(':');
+    let name = name.split('(').next().unwrap_or(name).trim().to_lowercase();
+    match name.as_str() {
+        "user" | "you" | "human" | "me" | "prompt" => Some(ImportedRole::User),
+        "assistant" | "wake" | "bicky" | "claude" | "ai" | "agent" | "model" | "response" => Some(ImportedRole::Assistant),
+        _ => None,
+    }
+}
+
+fn push(messages: &mut Vec<ImportedMessage>, role: ImportedRole, text: &str, timestamp: Option<String>) {
+    let text = text.trim();
+    if text.is_empty() {
+        return;
+    }
+    match messages.last_mut() {
+        Some(last) if last.role == role => {
+            last.content.push_str("\n\n");
+            last.content.push_str(text);
+        }
+        _ => messages.push(ImportedMessage {
+            role,
+            content: text.to_string(),
+            timestamp,
+        }),
+    }
+}
+
+impl BickyApp {
+    /// Send every transcript in the inbox to the server on screen
+    pub(crate) fn import_transcripts(&mut self) -> Command<Message> {
+        let dir = inbox();
+        let paths = pending(&dir);
+        if paths.is_empty() {
+            state::update(&mut self.state, Action::ShowNotification(state::Notification::Info(format!(
+                "Nothing to import, put Claude Code sessions (.jsonl) or Markdown logs in {}",
+                dir.display()
+            ))));
+            return Command::none();
+        }
+
+        let server = self.state.active_server.clone();
+        let mut imports = Vec::new();
+        let mut unreadable = Vec::new();
+        for path in paths {
+            match read(&path) {
+                Ok(request) => {
+                    let api = self.api_for(&server);
+                    let server = server.clone();
+                    imports.push(self.tasks.run(
+                        "import transcript",
+                        async move { api.import_conversation(&request).await },
+                        move |result| Message::TranscriptImported(server.clone(), path.clone(), result),
+                    ));
+                }
+                Err(e) => {
+                    eprintln!("[GUI] Couldn't read {}: {}", path.display(), e);
+                    unreadable.push(path);
+                }
+            }
+        }
+
+        let notification = match unreadable.as_slice() {
+            [] => state::Notification::Info(format!("Importing {} transcripts", imports.len())),
+            [path] => state::Notification::Error(format!("Couldn't read {}, left it in place", path.display())),
+            _ => state::Notification::Error(format!("Couldn't read {} transcripts, left them in place", unreadable.len())),
+        };
+        state::update(&mut self.state, Action::ShowNotification(notification));
+        Command::batch(imports)
+    }
+}
@@ -0,0 +1,240 @@
+use iced::widget::{button, column, container, row, scrollable, text, Space};
+use iced::{Border, Element, Length, Theme};
+use std::time::{Duration, Instant};
+
+use crate::config::{HeartbeatSettings, SafetyLimits};
+use crate::state::{update, Action, AppState, Conversation};
+use crate::types::MessageStatus;
+use crate::{agent_health, fonts, format, scrolling, theme, BickyApp, Message};
+
+/// An interaction the agent is still working on
+#[derive(Debug, Clone)]
+pub struct Job<'a> {
+    pub conversation_idx: usize,
+    pub interaction_id: &'a str,
+    pub title: &'a str,
+    pub current_action: Option<&'a str>,
+    pub waiting_for_permission: bool,
+    pub elapsed: Option<Duration>,
+    pub tool_calls: usize,
+    pub tokens: u32,
+    /// How long since the server last reported progress
+    pub quiet_for: Option<Duration>,
+    pub stalled: bool,
+}
+
+/// Whether the agent is busy with this conversation
+///
+/// Pending messages haven't reached the server yet, so they don't count.
+pub fn is_running(conv: &Conversation) -> bool {
+    conv.messages.iter().any(|m| {
+        matches!(m.status, MessageStatus::Processing | MessageStatus::WaitingForPermission)
+    })
+}
+
+/// Every running interaction across conversations, longest running first
+pub fn running_jobs(state: &AppState, now: Instant) -> Vec<Job<'_>> {
+    let mut jobs: Vec<Job> = state
+        .conversations
+        .iter()
+        .enumerate()
+        .filter(|(_, conv)| is_running(conv))
+        .map(|(idx, conv)| {
+            let running = conv.messages.iter().rev().find(|m| {
+                matches!(m.status, MessageStatus::Processing | MessageStatus::WaitingForPermission)
+            });
+
+            let metadata = running.and_then(|m| m.metadata.as_ref());
+
+            Job {
+                conversation_idx: idx,
+                interaction_id: &conv.id,
+                title: &conv.title,
+                current_action: metadata.and_then(|meta| meta.current_action.as_deref()),
+                waiting_for_permission: running.is_some_and(|m| m.status == MessageStatus::WaitingForPermission),
+                elapsed: state.job_started.get(&conv.id).map(|started| now.duration_since(*started)),
+                tool_calls: metadata.and_then(|meta| meta.tools_used.as_ref()).map_or(0, |tools| tools.len()),
+                tokens: metadata.and_then(|meta| meta.tokens.as_ref()).map_or(0, |tokens| tokens.total),
+                quiet_for: state.last_progress.get(&conv.id).map(|last| now.duration_since(*last)),
+                stalled: state.stalled.contains(&conv.id),
+            }
+        })
+        .collect();
+
+    jobs.sort_by_key(|job| std::cmp::Reverse(job.elapsed));
+    jobs
+}
+
+/// Why a job should be stopped, if it went over any of the limits
+///
+/// Time spent waiting for the user to approve a tool doesn't count as running away.
+pub fn exceeded_limit(job: &Job, limits: &SafetyLimits) -> Option<String> {
+    if job.waiting_for_permission {
+        return None;
+    }
+
+    let elapsed = job.elapsed.unwrap_or_default();
+    if limits.max_wall_time_secs > 0 && elapsed.as_secs() >= limits.max_wall_time_secs {
+        return Some(format!("ran for {}", format_elapsed(elapsed)));
+    }
+    if limits.max_tool_calls > 0 && job.tool_calls >= limits.max_tool_calls {
+        return Some(format!("made {} tool calls", job.tool_calls));
+    }
+    if limits.max_tokens > 0 && job.tokens >= limits.max_tokens {
+        return Some(format!("used {} tokens", format::locale().number(job.tokens as u64)));
+    }
+    None
+}
+
+/// Whether the job went quiet for longer than the heartbeat window
+///
+/// Waiting on the user isn't a stall, the server has nothing to report until they answer.
+pub fn is_stalled(job: &Job, heartbeat: &HeartbeatSettings) -> bool {
+    heartbeat.stall_after_secs > 0
+        && !job.waiting_for_permission
+        && job.quiet_for.is_some_and(|quiet| quiet.as_secs() >= heartbeat.stall_after_secs)
+}
+
+/// `42s`, `3m 05s`, `1h 02m`
+pub fn format_elapsed(elapsed: Duration) -> String {
+    let secs = elapsed.as_secs();
+    if secs < 60 {
+        format!("{}s", secs)
+    } else if secs < 3600 {
+        format!("{}m {:02}s", secs / 60, secs % 60)
+    } else {
+        format!("{}h {:02}m", secs / 3600, (secs % 3600) / 60)
+    }
+}
+
+pub(crate) const PANEL_WIDTH: f32 = 280.0;
+
+impl BickyApp {
+    pub(crate) fn build_jobs_panel(&self) -> Element<'_, Message> {
+        let jobs = running_jobs(&self.state, self.state.clock.instant());
+
+        let mut list = column![
+            row![
+                text("Jobs").size(14).font(fonts::bold()),
+                Space::with_width(Length::Fill),
+                button(text("×").size(14).font(fonts::mono()))
+                    .on_press(Message::ToggleJobsPanel)
+                    .padding(0)
+                    .style(theme::icon_button),
+            ]
+            .align_y(iced::Alignment::Center)
+        ]
+        .spacing(10);
+
+        if jobs.is_empty() {
+            list = list.push(
+                text("Nothing running").size(12).font(fonts::mono()).color(theme::colors().text_dim)
+            );
+        }
+
+        for job in jobs {
+            let elapsed = job.elapsed.map(format_elapsed).unwrap_or_else(|| "—".to_string());
+            let (action, action_color) = if job.waiting_for_permission {
+                ("Waiting for permission".to_string(), theme::colors().spinner)
+            } else if job.stalled {
+                (stalled_label(job.quiet_for), theme::colors().error)
+            } else {
+                (job.current_action.unwrap_or("Thinking...").to_string(), theme::colors().text_dim)
+            };
+
+            let mut entry = column![
+                row![
+                    text(format!("#{}", job.title)).size(13).font(fonts::mono()),
+                    Space::with_width(Length::Fill),
+                    text(elapsed).size(12).font(fonts::mono()).color(theme::colors().text_dim),
+                ]
+                .align_y(iced::Alignment::Center),
+                text(action).size(12).font(fonts::mono()).color(action_color),
+            ]
+            .spacing(6);
+            // A down agent is the likely reason a job stopped moving
+            let conv = &self.state.conversations[job.conversation_idx];
+            for status in agent_health::down_agents(&self.state, conv) {
+                entry = entry.push(
+                    text(agent_health::down_label(&self.state, status)).size(12).font(fonts::mono()).color(theme::colors().error)
+                );
+            }
+            entry = entry.push(
+                row![
+                    button(text("Open").size(12).font(fonts::mono()))
+                        .on_press(Message::SelectConversation(job.conversation_idx))
+                        .padding(6)
+                        .style(theme::secondary_button),
+                    button(text("Refetch").size(12).font(fonts::mono()))
+                        .on_press(Message::RefetchInteraction(job.interaction_id.to_string()))
+                        .padding(6)
+                        .style(theme::secondary_button),
+                    button(text("Interrupt").size(12).font(fonts::mono()))
+                        .on_press(Message::InterruptInteraction(job.interaction_id.to_string()))
+                        .padding(6)
+                        .style(theme::secondary_button),
+                ]
+                .spacing(6),
+            );
+
+            list = list.push(
+                container(entry)
+                    .padding(10)
+                    .width(Length::Fill)
+                    .style(|_theme: &Theme| container::Style {
+                        background: Some(iced::Background::Color(theme::colors().surface_light)),
+                        border: Border {
+                            color: theme::colors().border,
+                            width: 1.0,
+                            radius: 6.0.into(),
+                        },
+                        ..Default::default()
+                    }),
+            );
+        }
+
+        container(
+            scrollable(list.padding(16))
+                .direction(scrolling::scrollbar())
+                .style(theme::scrollbar)
+                .height(Length::Fill),
+        )
+            .width(Length::Fixed(PANEL_WIDTH))
+            .height(Length::Fill)
+            .style(theme::sidebar_container)
+            .into()
+    }
+
+    // Flag jobs that went quiet, logging what we knew about them at the time
+    pub(crate) fn detect_stalled_jobs(&mut self) {
+        let newly_stalled: Vec<String> = running_jobs(&self.state, self.state.clock.instant())
+            .into_iter()
+            .filter(|job| !job.stalled && is_stalled(job, &self.state.settings.heartbeat))
+            .map(|job| {
+                eprintln!(
+                    "[GUI] Interaction {} (#{}) stalled: no progress for {}, running {}, last action {:?}, {} tool calls, {} tokens",
+                    job.interaction_id,
+                    job.title,
+                    job.quiet_for.map(format_elapsed).unwrap_or_default(),
+                    job.elapsed.map(format_elapsed).unwrap_or_default(),
+                    job.current_action,
+                    job.tool_calls,
+                    job.tokens,
+                );
+                job.interaction_id.to_string()
+            })
+            .collect();
+
+        for interaction_id in newly_stalled {
+            update(&mut self.state, Action::InteractionStalled(interaction_id));
+        }
+    }
+}
+
+/// `Stalled, no progress for 2m 10s`
+pub fn stalled_label(quiet_for: Option<Duration>) -> String {
+    match quiet_for {
+        Some(quiet) => format!("Stalled, no progress for {}", format_elapsed(quiet)),
+        None => "Stalled".to_string(),
+    }
+}
@@ -0,0 +1,208 @@
+use iced::keyboard::{key::Named, Key, Modifiers};
+use serde::Deserialize;
+use std::collections::HashMap;
+
+use crate::config;
+use crate::platform::Os;
+
+// Shared with the TUI, which dispatches the core action each command maps to
+pub use bicky_editor_core::keymap::KeyCommand;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum BindingKey {
+    Character(String),
+    Named(Named),
+}
+
+/// A key plus modifiers, e.g. `cmd+shift+n`
+///
+/// `cmd` is the platform command key: Command on macOS, Ctrl everywhere else.
+/// `ctrl` is the Control key on macOS and the same as `cmd` elsewhere.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct KeyBinding {
+    key: BindingKey,
+    command: bool,
+    control: bool,
+    shift: bool,
+    alt: bool,
+}
+
+impl KeyBinding {
+    pub fn parse(spec: &str) -> Option<Self> {
+        Self::parse_for(spec, Os::current())
+    }
+
+    pub fn parse_for(spec: &str, os: Os) -> Option<Self> {
+        let mut binding = Self {
+            key: BindingKey::Character(String::new()),
+            command: false,
+            control: false,
+            shift: false,
+            alt: false,
+        };
+
+        let parts: Vec<String> = spec.split('+').map(|p| p.trim().to_lowercase()).collect();
+        let (key, modifiers) = parts.split_last()?;
+
+        for modifier in modifiers {
+            match modifier.as_str() {
+                "cmd" | "command" => binding.command = true,
+                "ctrl" | "control" if os.has_command_key() => binding.control = true,
+                "ctrl" | "control" => binding.command = true,
+                "shift" => binding.shift = true,
+                "alt" | "option" => binding.alt = true,
+                _ => return None,
+            }
+        }
+
+        binding.key = match key.as_str() {
+            "enter" | "return" => BindingKey::Named(Named::Enter),
+            "escape" | "esc" => BindingKey::Named(Named::Escape),
+            "tab" => BindingKey::Named(Named::Tab),
+            "space" => BindingKey::Named(Named::Space),
+            "up" => BindingKey::Named(Named::ArrowUp),
+            "down" => BindingKey::Named(Named::ArrowDown),
+            "left" => BindingKey::Named(Named::ArrowLeft),
+            "right" => BindingKey::Named(Named::ArrowRight),
+            "pageup" => BindingKey::Named(Named::PageUp),
+            "pagedown" => BindingKey::Named(Named::PageDown),
+            "home" => BindingKey::Named(Named::Home),
+            "end" => BindingKey::Named(Named::End),
+            c if c.chars().count() == 1 => BindingKey::Character(c.to_string()),
+            _ => return None,
+        };
+
+        Some(binding)
+    }
+
+    pub fn matches(&self, key: &Key, modifiers: Modifiers, os: Os) -> bool {
+        let key_matches = match (&self.key, key.as_ref()) {
+            (BindingKey::Named(expected), Key::Named(named)) => *expected == named,
+            (BindingKey::Character(expected), Key::Character(c)) => expected.eq_ignore_ascii_case(c),
+            _ => false,
+        };
+        // Off macOS Ctrl is the command key, there's no separate Control to check
+        let (command, control) = if os.has_command_key() {
+            (modifiers.logo(), modifiers.control())
+        } else {
+            (modifiers.control(), false)
+        };
+
+        key_matches
+            && self.command == command
+            && self.control == control
+            && self.shift == modifiers.shift()
+            && self.alt == modifiers.alt()
+    }
+
+    fn has_modifier(&self) -> bool {
+        self.command || self.control || self.alt
+    }
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct KeymapFile {
+    #[serde(default)]
+    bindings: HashMap<String, String>,
+}
+
+/// Maps key presses to commands
+#[derive(Debug, Clone)]
+pub struct Keymap {
+    bindings: Vec<(KeyBinding, KeyCommand)>,
+}
+
+impl Default for Keymap {
+    fn default() -> Self {
+        let mut specs = vec![
+            ("cmd+n".to_string(), KeyCommand::NewConversation),
+            ("cmd+k".to_string(), KeyCommand::CommandPalette),
+            ("cmd+enter".to_string(), KeyCommand::Send),
+            ("escape".to_string(), KeyCommand::Interrupt),
+            ("cmd+shift+f".to_string(), KeyCommand::FocusMode),
+        ];
+        specs.extend((1..=9).map(|n| (format!("cmd+{}", n), KeyCommand::SwitchConversation(n))));
+
+        let bindings = specs
+            .iter()
+            .filter_map(|(spec, command)| Some((KeyBinding::parse(spec)?, *command)))
+            .collect();
+
+        Self { bindings }
+    }
+}
+
+impl Keymap {
+    /// Default bindings, overridden by Keymap.toml when present
+    ///
+    /// ```toml
+    /// [bindings]
+    /// "cmd+shift+n" = "new_conversation"
+    /// "cmd+n" = "none"
+    /// ```
+    pub fn load() -> Self {
+        let mut keymap = Self::default();
+
+        let Some(path) = config::find_config_file("Keymap.toml") else {
+            return keymap;
+        };
+
+        let file = match std::fs::read_to_string(&path).map(|content| toml::from_str::<KeymapFile>(&content)) {
+            Ok(Ok(file)) => file,
+            Ok(Err(e)) => {
+                eprintln!("[GUI] Failed to parse {}: {}", path.display(), e);
+                return keymap;
+            }
+            Err(e) => {
+                eprintln!("[GUI] Failed to read {}: {}", path.display(), e);
+                return keymap;
+            }
+        };
+
+        for (spec, name) in file.bindings {
+            let Some(binding) = KeyBinding::parse(&spec) else {
+                eprintln!("[GUI] Ignoring invalid key binding '{}'", spec);
+                continue;
+            };
+
+            keymap.bindings.retain(|(existing, _)| *existing != binding);
+
+            if name == "none" {
+                continue;
+            }
+
+            match KeyCommand::parse(&name) {
+                Some(command) => keymap.bindings.push((binding, command)),
+                None => eprintln!("[GUI] Unknown command '{}' for '{}'", name, spec),
+            }
+        }
+
+        println!("[GUI] Loaded keymap from {}", path.display());
+        keymap
+    }
+
+    /// Resolve a key press to a command
+    ///
+    /// `captured` is set when a widget (usually the message input) already handled the
+    /// key, in which case only modifier chords and Escape are allowed through so typing
+    /// never triggers commands.
+    pub fn resolve(&self, key: &Key, modifiers: Modifiers, captured: bool) -> Option<KeyCommand> {
+        let (binding, command) = self
+            .bindings
+            .iter()
+            .find(|(binding, _)| binding.matches(key, modifiers, Os::current()))?;
+
+        if captured {
+            let is_escape = binding.key == BindingKey::Named(Named::Escape);
+            if !binding.has_modifier() && !is_escape {
+                return None;
+            }
+            // The input already submits on Enter, with or without modifiers
+            if *command == KeyCommand::Send {
+                return None;
+            }
+        }
+
+        Some(*command)
+    }
+}
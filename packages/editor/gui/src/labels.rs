@@ -0,0 +1,154 @@
+use iced::widget::{button, container, row, text, Space, Stack};
+use iced::{Border, Color, Element, Length};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+
+use crate::state::{self, Action};
+use crate::{config, fonts, theme, BickyApp, Message};
+
+// Conversation ids to labels, next to Mind.toml
+pub(crate) const LABELS_FILE: &str = "Labels.toml";
+
+const STRIPE_WIDTH: f32 = 3.0;
+
+/// A color for grouping conversations at a glance
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ColorLabel {
+    Red,
+    Orange,
+    Yellow,
+    Green,
+    Blue,
+    Purple,
+}
+
+impl ColorLabel {
+    pub const ALL: [ColorLabel; 6] = [
+        ColorLabel::Red,
+        ColorLabel::Orange,
+        ColorLabel::Yellow,
+        ColorLabel::Green,
+        ColorLabel::Blue,
+        ColorLabel::Purple,
+    ];
+
+    // Picked to read on both the dark and light palettes
+    pub fn color(self) -> Color {
+        match self {
+            ColorLabel::Red => Color::from_rgb8(0xe5, 0x48, 0x4d),
+            ColorLabel::Orange => Color::from_rgb8(0xf0, 0x8c, 0x2e),
+            ColorLabel::Yellow => Color::from_rgb8(0xd9, 0xb4, 0x1e),
+            ColorLabel::Green => Color::from_rgb8(0x46, 0xa7, 0x58),
+            ColorLabel::Blue => Color::from_rgb8(0x3e, 0x8e, 0xd0),
+            ColorLabel::Purple => Color::from_rgb8(0x8e, 0x5c, 0xd9),
+        }
+    }
+
+    /// The label after `label` when clicking through them, no label after the last
+    pub fn next(label: Option<ColorLabel>) -> Option<ColorLabel> {
+        match label {
+            None => Some(Self::ALL[0]),
+            Some(label) => Self::ALL.iter().skip_while(|l| **l != label).nth(1).copied(),
+        }
+    }
+}
+
+pub fn load() -> BTreeMap<String, ColorLabel> {
+    let Some(path) = config::find_config_file(LABELS_FILE) else {
+        return BTreeMap::new();
+    };
+
+    match std::fs::read_to_string(&path).map(|content| toml::from_str(&content)) {
+        Ok(Ok(labels)) => labels,
+        Ok(Err(e)) => {
+            eprintln!("[GUI] Failed to parse {}: {}", path.display(), e);
+            BTreeMap::new()
+        }
+        Err(e) => {
+            eprintln!("[GUI] Failed to read {}: {}", path.display(), e);
+            BTreeMap::new()
+        }
+    }
+}
+
+/// Write the labels next to Mind.toml, or here when there isn't one
+pub fn save(labels: &BTreeMap<String, ColorLabel>) -> Result<PathBuf, String> {
+    let path = config::find_config_file(LABELS_FILE)
+        .or_else(|| config::find_config_file("Mind.toml").map(|mind| mind.with_file_name(LABELS_FILE)))
+        .unwrap_or_else(|| PathBuf::from(LABELS_FILE));
+
+    let contents = toml::to_string(labels).map_err(|e| e.to_string())?;
+    std::fs::write(&path, contents).map_err(|e| e.to_string())?;
+    Ok(path)
+}
+
+/// `content` with a labelled conversation's color down its left edge
+pub fn striped<'a>(content: impl Into<Element<'a, Message>>, label: Option<ColorLabel>) -> Element<'a, Message> {
+    let Some(color) = label.map(ColorLabel::color) else {
+        return content.into();
+    };
+    // Layered so the stripe takes the content's height rather than filling the sidebar
+    let stripe = container(Space::new(Length::Fixed(STRIPE_WIDTH), Length::Fill)).style(move |_| container::Style {
+        background: Some(color.into()),
+        ..Default::default()
+    });
+    Stack::new().push(content).push(stripe).into()
+}
+
+impl BickyApp {
+    /// Label a conversation, or take its label off, and save the change
+    pub(crate) fn set_label(&mut self, conversation_idx: usize, label: Option<ColorLabel>) {
+        state::update(&mut self.state, Action::SetLabel { conversation_idx, label });
+        self.save_labels();
+    }
+
+    // Failures only cost the labels surviving a restart
+    pub(crate) fn save_labels(&mut self) {
+        if let Err(e) = save(&self.state.labels) {
+            state::update(
+                &mut self.state,
+                Action::ShowNotification(state::Notification::Error(format!("Couldn't save labels: {}", e))),
+            );
+        }
+    }
+
+    /// One dot per label for showing only those conversations, hidden until something is labelled
+    pub(crate) fn build_label_filter(&self) -> Option<Element<'_, Message>> {
+        if self.state.labels.is_empty() && self.state.label_filter.is_none() {
+            return None;
+        }
+
+        let dots = ColorLabel::ALL.iter().fold(row![].spacing(6), |dots, &label| {
+            let selected = self.state.label_filter == Some(label);
+            let filter = (!selected).then_some(label);
+            dots.push(
+                button(Space::new(12, 12))
+                    .on_press(Message::FilterByLabel(filter))
+                    .padding(0)
+                    .style(move |_, _| button::Style {
+                        background: Some(label.color().into()),
+                        border: Border {
+                            color: if selected { theme::colors().text } else { label.color() },
+                            width: 2.0,
+                            radius: 6.0.into(),
+                        },
+                        ..Default::default()
+                    }),
+            )
+        });
+
+        let all = button(text("All").size(11).font(fonts::mono()))
+            .on_press_maybe(self.state.label_filter.map(|_| Message::FilterByLabel(None)))
+            .padding(0)
+            .style(theme::icon_button);
+
+        Some(
+            container(row![dots, Space::with_width(Length::Fill), all].align_y(iced::Alignment::Center))
+                .padding([4, 16])
+                .width(Length::Fill)
+                .into(),
+        )
+    }
+}
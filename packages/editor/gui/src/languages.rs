@@ -0,0 +1,56 @@
+use bicky_editor_core::stats::ConversationStats;
+use iced::widget::{container, text, Row};
+use iced::{Border, Element, Theme};
+
+use crate::state::{self, Conversation};
+use crate::{fonts, format, theme, BickyApp, Message};
+
+// More than this and the header gets crowded, the stats row lists the rest
+const MAX_CHIPS: usize = 4;
+
+impl BickyApp {
+    /// The languages the conversation is mostly about, busiest first
+    pub(crate) fn build_language_chips(&self, conv: &Conversation) -> Option<Element<'_, Message>> {
+        let languages = state::conversation_stats(conv).top_languages();
+        if languages.is_empty() {
+            return None;
+        }
+
+        let chips = languages.into_iter().take(MAX_CHIPS).map(|(language, _)| {
+            container(text(language).size(11).font(fonts::mono()).color(theme::colors().text_dim))
+                .padding([2, 8])
+                .style(|_theme: &Theme| container::Style {
+                    border: Border {
+                        color: theme::colors().border,
+                        width: 1.0,
+                        radius: 10.0.into(),
+                    },
+                    ..Default::default()
+                })
+                .into()
+        });
+        Some(Row::with_children(chips).spacing(6).into())
+    }
+
+    /// Lines and code blocks in each language, for the stats row
+    pub(crate) fn build_language_stats(&self, stats: &ConversationStats) -> Option<Element<'_, Message>> {
+        let languages = stats.top_languages();
+        if languages.is_empty() {
+            return None;
+        }
+
+        let locale = format::locale();
+        let entries = languages.into_iter().map(|(language, activity)| {
+            let mut summary = format!("{} {} lines", language, locale.number(activity.lines as u64));
+            if activity.blocks > 0 {
+                summary.push_str(&format!(
+                    " in {} {}",
+                    locale.number(activity.blocks as u64),
+                    if activity.blocks == 1 { "block" } else { "blocks" }
+                ));
+            }
+            text(summary).size(12).font(fonts::mono()).color(theme::colors().text_dim).into()
+        });
+        Some(Row::with_children(entries).spacing(12).into())
+    }
+}
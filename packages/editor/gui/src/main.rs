@@ -6,31 +6,113 @@ mod markdown;
 mod fonts;
 mod components;
 mod sse;
+mod events;
+mod config;
+mod desktop;
+mod keymap;
+mod widgets;
+mod palette;
+mod export;
+mod format;
+mod mentions;
+mod jobs;
+mod resume;
+mod worktrees;
+mod diff_view;
+mod merge;
+mod models;
+mod appearance;
+mod animation;
+mod scrolling;
+mod minimap;
+mod timeline;
+mod permission;
+mod permission_policy;
+mod permission_audit;
+mod message_errors;
+mod auth;
+mod tasks;
+mod servers;
+mod debug_overlay;
+mod sessions;
+mod platform;
+mod clipboard;
+mod history;
+mod labels;
+mod mutes;
+mod timings;
+mod type_tabs;
+mod backup;
+mod import;
+mod cli;
+mod plugins;
+mod hooks;
+mod budget;
+mod agents;
+mod agent_health;
+mod quick_actions;
+mod pins;
+mod languages;
+mod attachments;
+mod digest;
+mod file_drop;
+mod focus;
+mod images;
+mod presentation;
+mod artifacts;
+mod effects;
+mod action_log;
+mod snapshot;
+#[cfg(test)]
+mod tests;
 
-use iced::widget::{button, column, container, row, scrollable, text, text_input, Column, Space, Stack};
-use iced::{Element, Length, Subscription, Task as Command, Theme, Color, Border, Shadow, Vector};
+use iced::widget::{button, column, container, mouse_area, row, scrollable, text, text_editor, Column, Space, Stack};
+use iced::{event, keyboard, window, Element, Event, Length, Subscription, Task as Command, Theme, Color, Border, Shadow, Vector};
 use iced::time::{self, Duration};
 use state::{Action, AppState, update};
 use types::*;
-use std::sync::mpsc;
-use std::collections::HashMap;
+use bicky_editor_core::clock;
+use bicky_editor_core::Action as EditorAction;
+use bicky_editor_core::types::{InteractionType, ServerConversation, ServerMessage, PENDING_PERMISSION_KEY};
+use widgets::multiline_input::{self, ComposerMessage, MultilineInput};
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
 
 pub fn main() -> iced::Result {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    match cli::parse(&args) {
+        Ok(None) => {}
+        Ok(Some(invocation)) => std::process::exit(cli::run(invocation)),
+        Err(e) => {
+            eprintln!("{}\n\n{}", e, cli::USAGE);
+            std::process::exit(2);
+        }
+    }
+    
     let app = iced::application("Bicky", BickyApp::update, BickyApp::view)
         .subscription(BickyApp::subscription)
+        // Closing saves the core state first
+        .exit_on_close_request(false)
         .theme(|_| iced::Theme::custom(
             "Bicky".to_string(),
             iced::theme::Palette {
-                background: theme::Colors::BACKGROUND,
-                text: theme::Colors::TEXT,
-                primary: theme::Colors::PRIMARY,
-                success: theme::Colors::SUCCESS,
-                danger: theme::Colors::ERROR,
+                background: theme::colors().background,
+                text: theme::colors().text,
+                primary: theme::colors().primary,
+                success: theme::colors().success,
+                danger: theme::colors().error,
             }
         ))
-        .font(fonts::BERKELEY_MONO_BYTES)
-        .font(fonts::BERKELEY_MONO_SEMIBOLD_BYTES)
-        .default_font(fonts::DEFAULT_FONT);
+        .default_font(fonts::mono());
+    let app = match fonts::verify() {
+        Ok(()) => app
+            .font(fonts::BERKELEY_MONO_BYTES)
+            .font(fonts::BERKELEY_MONO_SEMIBOLD_BYTES),
+        Err(reason) => {
+            fonts::use_fallback(reason);
+            app.default_font(fonts::mono())
+        }
+    };
     
     // Run with initial command
     app.run_with(BickyApp::new)
@@ -38,64 +120,276 @@ pub fn main() -> iced::Result {
 
 struct BickyApp {
     state: AppState,
+    /// Client for the server being shown, one of `servers`
     api_client: api::ApiClient,
+    servers: Vec<servers::Server>,
     runtime: Option<tokio::runtime::Runtime>,
-    sse_receiver: Option<mpsc::Receiver<sse::SSEMessage>>,
-    interaction_cache: HashMap<String, serde_json::Value>,
+    /// Latest server copy of each interaction, keyed by server and interaction id
+    interaction_cache: HashMap<(String, String), ServerConversation>,
+    /// Background tasks publish here, the UI reads it through `inbox`
+    events: events::EventBus,
+    inbox: events::Inbox,
+    tasks: tasks::Tasks,
+    /// Running SSE stream per server, with the session it follows
+    streams: HashMap<String, (Option<String>, tokio_util::sync::CancellationToken)>,
+    keymap: keymap::Keymap,
+    composer: text_editor::Content,
+    composer_input: MultilineInput,
+    theme_watcher: theme::ThemeWatcher,
+    motion: animation::Motion,
+    transcript_scroll: scrolling::TranscriptScroll,
+    /// Where the timeline handle is while it's being dragged
+    timeline_scrub: Option<f32>,
 }
 
 #[derive(Debug, Clone)]
 enum Message {
-    InputChanged(String),
+    Composer(ComposerMessage),
     SendMessage,
-    MessageSent(Result<SendMessageResponse, String>),
+    /// The server and id the sending conversation had when it sent
+    MessageSent(String, String, Result<SendMessageResponse, api::ApiError>),
     NewConversation,
     SelectConversation(usize),
+    ToggleMute(usize),
+    ToggleBookmark(String),
+    TogglePin(usize, pins::Pin),
+    SetPinsExpanded(bool),
+    PinDraftChanged(String),
+    PinDraftedFile(usize),
+    TagDraftChanged(String),
+    AddDraftedTag(usize),
+    RemoveTag(usize, String),
+    /// The server and interaction whose tags were replaced
+    TagsUpdated(String, String, Result<Vec<String>, api::ApiError>),
+    ToggleTimings(String),
+    ToggleMinimap,
+    ToggleFocusMode,
+    StartPresentation,
+    MinimapJump(f32),
+    ShowArtifacts(bool),
+    SaveArtifact(types::Artifact),
+    SaveArtifactAs(types::Artifact),
+    SaveArtifactPathChanged(String),
+    ConfirmSaveArtifact,
+    CloseSaveArtifact,
+    ArtifactSaved(String, Result<std::path::PathBuf, String>),
+    TimelineScrubbed(f32),
+    TimelineReleased,
+    ArchiveConversation(usize),
+    UnarchiveConversation(usize),
+    ToggleArchived,
+    ConfirmDeleteConversation(usize),
+    CancelDeleteConversation,
+    DeleteConversation(usize),
+    CycleLabel(usize),
+    FilterByLabel(Option<labels::ColorLabel>),
+    FilterByType(Option<InteractionType>),
+    StartRename(usize),
+    RenameChanged(String),
+    CommitRename,
+    ConversationDeleted(Result<(), api::ApiError>),
+    ToggleJobsPanel,
+    ToggleWorktreePanel,
+    ConfirmDeleteWorktree(String),
+    CancelDeleteWorktree,
+    DeleteWorktree(String),
+    WorktreeDeleted(Result<String, api::ApiError>),
+    OpenDiff(String),
+    DiffLoaded(String, Result<WorktreeDiff, api::ApiError>),
+    SelectDiffFile(usize),
+    ToggleHunk(usize),
+    CloseDiff,
+    OpenMergeDialog(String, state::MergeMode),
+    MergeMessageChanged(String),
+    MergeBodyChanged(String),
+    SubmitMerge,
+    CloseMergeDialog,
+    SetExportFrom(usize),
+    SetExportTo(usize),
+    ToggleExportPrompts,
+    ToggleExportResponses,
+    /// Write the selection in the export dialog
+    ExportConversation,
+    CloseExportDialog,
+    TranscriptImported(String, std::path::PathBuf, Result<ImportConversationResponse, api::ApiError>),
+    ReviewReplyChanged(String),
+    SubmitReviewReply,
+    ReviewReplySubmitted(String, String, Result<(), api::ApiError>),
+    MergeDone(Result<String, api::ApiError>),
+    SwitchServer(String),
+    SessionsLoaded(String, Result<Vec<bicky_editor_core::types::Session>, api::ApiError>),
+    ConversationsListed(String, Result<Vec<serde_json::Value>, api::ApiError>),
+    SessionCountsLoaded(String, Result<bicky_editor_core::types::InteractionQueueStatus, api::ApiError>),
+    SwitchSession(Option<String>),
+    AgentsLoaded(String, Result<Vec<types::Agent>, api::ApiError>),
+    OpenAgentSelector(usize),
+    CloseAgentSelector,
+    /// Attach the agent to the selector's conversation, or detach it
+    ToggleAgent(String),
+    AgentsChanged(String, String, Result<Vec<String>, api::ApiError>),
+    /// Pick the agent for a conversation's next messages, `None` for automatic routing
+    RouteTo(usize, Option<String>),
+    OpenAgentConfig(String),
+    OpenFilePicker,
+    CloseFilePicker,
+    /// Show another directory in the file picker
+    BrowseFiles(std::path::PathBuf),
+    AttachFile(std::path::PathBuf),
+    AttachmentUploaded(std::path::PathBuf, Result<types::Attachment, api::ApiError>),
+    RemoveAttachment(usize),
+    ImageLoaded(String, Result<images::LoadedImage, String>),
+    /// Fetch a remote image, they aren't loaded until asked for
+    LoadImage(images::ImageSource),
+    /// Show an image large, `None` closes it
+    ExpandImage(Option<images::ImageSource>),
+    ToggleAgentHealth,
+    AgentStatusesLoaded(String, Result<Vec<types::AgentStatus>, api::ApiError>),
+    RestartAgent(String),
+    AgentRestarted(String, String, Result<types::AgentStatus, api::ApiError>),
+    /// Run the configured quick action at this index
+    QuickAction(usize),
+    CloseAgentConfig,
+    AgentConfigEdited(agents::ConfigField, String),
+    SaveAgentConfig,
+    AgentConfigSaved(String, String, Result<types::AgentConfig, api::ApiError>),
+    ApiKeyChanged(String),
+    SubmitApiKey,
+    CloseAuthPrompt,
+    InterruptInteraction(String),
+    InterruptSent(String, Result<(), api::ApiError>),
+    RefetchInteraction(String),
+    HoverSent(Option<String>),
+    TranscriptHovered(bool),
+    TranscriptScrolled(scrollable::Viewport),
+    WheelScrolled(iced::mouse::ScrollDelta),
+    EditSent(String),
+    ConfirmDeleteSent(String),
+    CancelDeleteSent,
+    DeleteSent(String),
+    SentDeleted(Result<bool, api::ApiError>),
+    ResendEdited,
+    ResumeInteraction(String),
+    RetryFailed(String),
+    CopyError(String),
     Tick,
+    /// What running one of the core's effects answered with
+    Editor(EditorAction),
+    /// The window's close button, saved before exiting
+    CloseRequested,
     ApproveToolUse,
+    AlwaysAllowTool { in_worktree: bool },
+    TogglePermissionRules,
+    TogglePermissionAudit,
+    ToggleActionLog,
+    StepActionLogBack,
+    StepActionLogForward,
+    RuleDraftChanged(permission_policy::RuleField, String),
+    RuleDraftDecision(permission_policy::Decision),
+    AddPermissionRule,
+    RemovePermissionRule(permission_policy::Decision, usize),
     DenyToolUse,
-    PermissionResponseSent(Result<(), String>),
-    SSEEvent(SSEEventData),
-    WorktreesLoaded(Result<Vec<Worktree>, String>),
+    PermissionResponseSent(String, Result<(), api::ApiError>),
+    RetryPermissionResponse(String),
+    Events(events::Batch),
+    InteractionFetched(String, String, Result<ServerConversation, api::ApiError>),
+    LoadOlderHistory,
+    HistoryLoaded(String, String, Result<ServerConversation, api::ApiError>),
+    WorktreesLoaded(Result<Vec<Worktree>, api::ApiError>),
+    WorktreeStatusesLoaded(Result<HashMap<String, WorktreeGitStatus>, api::ApiError>),
+    SystemAppearance(theme::Appearance),
+    WorktreeFilesLoaded(Result<Vec<String>, String>),
     OpenWorktreeDialog,
     CloseWorktreeDialog,
     WorktreeBranchNameChanged(String),
     WorktreeBaseBranchChanged(String),
     WorktreeCustomPathChanged(String),
     CreateWorktree,
-    WorktreeCreated(Result<Worktree, String>),
+    WorktreeCreated(Result<Worktree, api::ApiError>),
     SelectWorktree(String),
-}
-
-#[derive(Debug, Clone)]
-enum SSEEventData {
-    Connected,
-    InteractionUpdate { id: String },
-    Error(String),
+    WindowFocusChanged(bool),
+    /// Files are dragged over the window, or have left it
+    FilesHovered(bool),
+    FileDropped(std::path::PathBuf),
+    KeyPressed { key: keyboard::Key, modifiers: keyboard::Modifiers, captured: bool },
+    ToggleCommandPalette,
+    PaletteQueryChanged(String),
+    PaletteSubmit,
+    ExecutePaletteCommand(palette::PaletteCommand),
+    PluginLoaded(config::PluginSettings, Result<serde_json::Value, String>),
+    PluginRendered(String, Result<serde_json::Value, String>),
+    PluginPanelLoaded(String, Result<serde_json::Value, String>),
+    PluginCommandFinished(String, Result<serde_json::Value, String>),
+    HookFinished(hooks::HookRun, Result<Vec<hooks::HookAction>, String>),
+    /// A file a hook asked for, by script and the path it gave
+    HookWrote(String, String, Result<std::path::PathBuf, String>),
 }
 
 impl BickyApp {
+    /// Stream every server, restarting streams that follow the wrong session
     fn start_sse(&mut self) {
+        // Every server streams at once, so switching away doesn't miss updates.
+        // Without the stream nothing updates, so a panic in it restarts it.
         if let Some(rt) = &self.runtime {
-            let (tx, rx) = mpsc::channel();
-            self.sse_receiver = Some(rx);
-            
-            let base_url = self.api_client.base_url.clone();
-            rt.spawn(async move {
-                sse::connect_sse(base_url, None, tx).await;
-            });
+            for server in &self.servers {
+                // Only the server on screen follows a picked session
+                let session = (server.name == self.state.active_server)
+                    .then(|| state::active_session(&self.state).map(str::to_string))
+                    .flatten();
+                if let Some((following, token)) = self.streams.get(&server.name) {
+                    if *following == session {
+                        continue;
+                    }
+                    token.cancel();
+                }
+
+                let name = server.name.clone();
+                let api = server.api.clone();
+                let bus = self.events.clone();
+                let token = self.tasks.app();
+                let label = match &session {
+                    Some(id) => format!("SSE {} session {}", server.name, id),
+                    None => format!("SSE {}", server.name),
+                };
+                self.streams.insert(server.name.clone(), (session.clone(), token.clone()));
+                self.tasks.supervisor.spawn_critical(rt, label, token, move || {
+                    sse::connect_sse(name.clone(), api.clone(), session.clone(), bus.clone())
+                });
+            }
         }
     }
     
     fn new() -> (Self, Command<Message>) {
-        let app = Self::default();
+        let mut app = Self::default();
         let api = app.api_client.clone();
+
+        if let Some(reason) = fonts::fallback_reason() {
+            update(&mut app.state, Action::ShowNotification(state::Notification::Error(
+                format!("Using the system monospace font: {}", reason)
+            )));
+        }
         
-        // Load worktrees on startup
-        let cmd = Command::perform(
-            async move { api.get_worktrees().await },
-            Message::WorktreesLoaded
-        );
+        // Drafts and the session carry over from the last run
+        if let Some(snapshot) = snapshot::restore() {
+            update(&mut app.state, Action::Editor(EditorAction::LoadSnapshot { snapshot }));
+        }
+        
+        // The core reconnects the stream when the session changes
+        update(&mut app.state, Action::Editor(EditorAction::Connect { server_url: api.base_url.clone() }));
+        
+        // Load worktrees and every server's conversations on startup
+        let hydrate = Command::batch(app.servers.iter().map(|server| app.hydrate_conversations(server.name.clone())));
+        let cmd = Command::batch([
+            app.tasks.run(
+                "get worktrees",
+                async move { api.get_worktrees().await },
+                Message::WorktreesLoaded
+            ),
+            app.load_sessions(),
+            app.load_agents(),
+            hydrate,
+            app.load_plugins(),
+            app.run_effects(),
+        ]);
         
         (app, cmd)
     }
@@ -109,116 +403,171 @@ impl BickyApp {
         None
     }
     
-    fn find_conversation_by_interaction_id(&self, interaction_id: &str) -> Option<usize> {
+    /// Put the active conversation's draft in the composer, after switching to it
+    pub(crate) fn show_draft(&mut self) {
+        if multiline_input::text(&self.composer) != self.state.editor.draft.content {
+            multiline_input::set_text(&mut self.composer, &self.state.editor.draft.content);
+        }
+    }
+    
+    // Replies are matched back to the conversation by what it was called when it sent
+    pub(crate) fn send_message(&self, conversation_idx: usize, request: SendMessageRequest) -> Command<Message> {
+        let Some(conv) = self.state.conversations.get(conversation_idx) else {
+            return Command::none();
+        };
+        let (server, conversation_id) = (conv.server.clone(), conv.id.clone());
+        let api = self.api_client.clone();
+        self.tasks.run(
+            "send message",
+            async move { api.send_message(request).await },
+            move |result| Message::MessageSent(server.clone(), conversation_id.clone(), result)
+        )
+    }
+    
+    fn find_conversation_by_interaction_id(&self, server: &str, interaction_id: &str) -> Option<usize> {
         for (idx, conv) in self.state.conversations.iter().enumerate() {
-            if conv.id == interaction_id {
+            if conv.server == server && conv.id == interaction_id {
                 return Some(idx);
             }
         }
         None
     }
     
-    // Convert server's conversation format to GUI's format
-    fn convert_server_conversation(&self, conversation_data: &serde_json::Value) -> Option<(String, Vec<types::LegacyMessage>)> {
-        let interaction = conversation_data.get("interaction")?;
-        let messages = conversation_data.get("messages")?.as_array()?;
-        let interaction_id = interaction.get("id")?.as_str()?.to_string();
+    // The GUI's messages for the core's conversation, one per user message with its response
+    fn convert_server_conversation(&self, server: &str, conversation: &ServerConversation) -> Vec<types::LegacyMessage> {
+        let interaction = &conversation.interaction;
+        
+        // Server timestamps, corrected for that server's skew, decide the order
+        let skew = self.api_for(server).clock_skew();
+        let mut messages: Vec<&ServerMessage> = conversation.messages.iter().collect();
+        messages.sort_by_key(|msg| msg.timestamp);
+        let meta_of = |msg: &ServerMessage| msg.metadata.as_ref().and_then(|meta| serde_json::to_value(meta).ok());
         
         let mut gui_messages = Vec::new();
         let mut i = 0;
         
         while i < messages.len() {
-            let msg = &messages[i];
-            let role = msg.get("role")?.as_str()?;
+            let msg = messages[i];
             
-            if role == "user" {
-                let user_content = msg.get("content")?.as_str()?.to_string();
-                let msg_id = msg.get("id")?.as_str()?.to_string();
-                let status_str = msg.get("status")?.as_str()?;
+            if msg.role == "user" {
+                let user_meta = meta_of(msg);
                 
                 // Look for the next assistant message as the response
                 let mut response = None;
                 let mut metadata = None;
-                let mut status = match status_str {
+                let mut error = None;
+                let mut status = match msg.status.as_str() {
                     "pending" => types::MessageStatus::Pending,
                     "processing" => types::MessageStatus::Processing,
                     "completed" => types::MessageStatus::Completed,
                     "error" => types::MessageStatus::Error,
+                    // The server marks interrupted messages failed, keep them apart from real failures
+                    "failed" if user_meta.as_ref().and_then(|m| m.get("interrupted")).and_then(|i| i.as_bool()) == Some(true) => {
+                        types::MessageStatus::Cancelled
+                    }
+                    "failed" => types::MessageStatus::Failed,
                     _ => types::MessageStatus::Pending,
                 };
                 
                 // Check if there's an assistant message after this user message
-                if i + 1 < messages.len() {
-                    let next_msg = &messages[i + 1];
-                    if next_msg.get("role")?.as_str()? == "assistant" {
-                        response = next_msg.get("content").and_then(|c| c.as_str()).map(|s| s.to_string());
-                        
-                        // Extract metadata from assistant message
-                        if let Some(meta) = next_msg.get("metadata") {
-                            metadata = self.extract_metadata(meta);
-                        }
-                        
-                        // Update status based on assistant message
-                        if let Some(assistant_status) = next_msg.get("status").and_then(|s| s.as_str()) {
-                            if assistant_status == "completed" {
-                                status = types::MessageStatus::Completed;
-                            }
+                if let Some(next_msg) = messages.get(i + 1).filter(|next| next.role == "assistant") {
+                    response = Some(next_msg.content.clone());
+                    let next_meta = meta_of(next_msg);
+                    
+                    // Extract metadata from assistant message
+                    if let Some(meta) = &next_meta {
+                        metadata = self.extract_metadata(meta);
+                        // Server times, so they line up with when the message was accepted
+                        if let Some(metadata) = &mut metadata {
+                            metadata.timings = timings::from_server(meta, Some(skew.to_local(next_msg.timestamp)), |t| skew.to_local(t));
                         }
-                        
-                        i += 1; // Skip the assistant message since we've processed it
                     }
+                    
+                    // Update status based on assistant message, crashes still
+                    // complete the assistant message but flag it as an error
+                    let is_error_response = next_meta
+                        .as_ref()
+                        .and_then(|m| m.get("error"))
+                        .and_then(|e| e.as_bool())
+                        .unwrap_or(false);
+                    if is_error_response {
+                        // The server reports what went wrong as the response
+                        error = response.clone().map(types::MessageError::server);
+                    }
+                    if next_msg.status == "completed" {
+                        status = if is_error_response {
+                            types::MessageStatus::Error
+                        } else {
+                            types::MessageStatus::Completed
+                        };
+                    }
+                    
+                    i += 1; // Skip the assistant message since we've processed it
                 }
                 
                 // Check for current processing metadata in interaction
                 if status == types::MessageStatus::Processing {
-                    if let Some(interaction_meta) = interaction.get("metadata") {
-                        println!("[GUI] Interaction metadata: {:?}", interaction_meta);
-                        if let Some(current_action) = interaction_meta.get("currentAction").and_then(|a| a.as_str()) {
-                            println!("[GUI] Found currentAction: {}", current_action);
-                            if metadata.is_none() {
-                                metadata = Some(types::InteractionMetadata {
-                                    tokens: None,
-                                    model: None,
-                                    processing_time_ms: None,
-                                    tools_used: None,
-                                    current_action: Some(current_action.to_string()),
-                                    process_id: None,
-                                    status: None,
-                                    worktree_context: None,
-                                    tags: None,
-                                });
-                            } else if let Some(meta) = &mut metadata {
-                                meta.current_action = Some(current_action.to_string());
-                            }
+                    println!("[GUI] Interaction metadata: {:?}", interaction.metadata);
+                    if let Some(current_action) = interaction.metadata.get("currentAction").and_then(|a| a.as_str()) {
+                        println!("[GUI] Found currentAction: {}", current_action);
+                        if metadata.is_none() {
+                            metadata = Some(types::InteractionMetadata {
+                                tokens: None,
+                                model: None,
+                                processing_time_ms: None,
+                                tools_used: None,
+                                tool_calls: None,
+                                current_action: Some(current_action.to_string()),
+                                process_id: None,
+                                status: None,
+                                worktree_context: None,
+                                tags: None,
+                                timings: None,
+                                agent_id: None,
+                                artifacts: None,
+                            });
+                        } else if let Some(meta) = &mut metadata {
+                            meta.current_action = Some(current_action.to_string());
                         }
                     }
                 }
                 
                 // Check for pending tool permission in interaction metadata
                 let mut pending_tool_permission = None;
-                if let Some(interaction_meta) = interaction.get("metadata") {
-                    if let Some(permission_data) = interaction_meta.get("pendingToolPermission") {
-                        if let Ok(permission) = serde_json::from_value::<types::ToolPermissionRequest>(permission_data.clone()) {
-                            pending_tool_permission = Some(permission);
-                            status = types::MessageStatus::WaitingForPermission;
-                        }
+                if let Some(permission_data) = interaction.metadata.get(PENDING_PERMISSION_KEY) {
+                    if let Ok(permission) = serde_json::from_value::<types::ToolPermissionRequest>(permission_data.clone()) {
+                        pending_tool_permission = Some(permission);
+                        status = types::MessageStatus::WaitingForPermission;
                     }
                 }
                 
                 gui_messages.push(types::LegacyMessage {
-                    id: msg_id,
-                    content: user_content,
+                    id: msg.id.clone(),
+                    content: msg.content.clone(),
                     response,
                     status,
                     metadata,
                     pending_tool_permission,
+                    sent_at: Some(skew.to_local(msg.timestamp)),
+                    error,
+                    attachments: user_meta
+                        .as_ref()
+                        .and_then(|meta| meta.get("attachments"))
+                        .and_then(|attachments| serde_json::from_value(attachments.clone()).ok())
+                        .unwrap_or_default(),
                 });
             }
             
             i += 1;
         }
         
-        Some((interaction_id, gui_messages))
+        // Never show a time from the future or out of order
+        let times: Vec<_> = gui_messages.iter().map(|m| m.sent_at).collect();
+        for (msg, time) in gui_messages.iter_mut().zip(clock::normalize(&times, self.state.clock.utc())) {
+            msg.sent_at = Some(time);
+        }
+        
+        gui_messages
     }
     
     fn extract_metadata(&self, meta: &serde_json::Value) -> Option<types::InteractionMetadata> {
@@ -229,11 +578,15 @@ impl BickyApp {
             model: None,
             processing_time_ms: None,
             tools_used: None,
+            tool_calls: None,
             current_action: None,
             process_id: None,
             status: None,
             worktree_context: None,
             tags: None,
+            timings: None,
+            agent_id: None,
+            artifacts: None,
         };
         
         // Extract token usage
@@ -271,11 +624,36 @@ impl BickyApp {
             }
         }
         
+        // Extract tool calls, skipping ones we can't read
+        if let Some(calls) = meta_obj.get("toolCalls").and_then(|v| v.as_array()) {
+            let calls: Vec<types::ToolCall> = calls.iter()
+                .filter_map(|c| serde_json::from_value(c.clone()).ok())
+                .collect();
+            if !calls.is_empty() {
+                metadata.tool_calls = Some(calls);
+            }
+        }
+        
         // Extract current action
         if let Some(action) = meta_obj.get("currentAction").and_then(|v| v.as_str()) {
             metadata.current_action = Some(action.to_string());
         }
         
+        // Extract the agent that answered
+        if let Some(agent_id) = meta_obj.get("agentId").and_then(|v| v.as_str()) {
+            metadata.agent_id = Some(agent_id.to_string());
+        }
+        
+        // Extract artifacts, skipping ones we can't read
+        if let Some(artifacts) = meta_obj.get("artifacts").and_then(|v| v.as_array()) {
+            let artifacts: Vec<types::Artifact> = artifacts.iter()
+                .filter_map(|a| serde_json::from_value(a.clone()).ok())
+                .collect();
+            if !artifacts.is_empty() {
+                metadata.artifacts = Some(artifacts);
+            }
+        }
+        
         // Extract process ID
         if let Some(pid) = meta_obj.get("processId").and_then(|v| v.as_str()) {
             metadata.process_id = Some(pid.to_string());
@@ -313,106 +691,285 @@ impl BickyApp {
         Some(metadata)
     }
 
-    fn handle_sse_event(&mut self, event: SSEEventData) {
+    // Everything background tasks published, in the order they published it
+    fn handle_event(&mut self, event: events::AppEvent) -> Command<Message> {
         match event {
-            SSEEventData::Connected => {
-                println!("[GUI] SSE connected");
+            events::AppEvent::Sse { server, message: sse::SSEMessage::Connected } => {
+                println!("[GUI] SSE connected to {}", server);
+                // The core only follows the server on screen
+                if server == self.state.active_server {
+                    update(&mut self.state, Action::Editor(EditorAction::Connected));
+                }
+                Command::none()
             }
-            SSEEventData::InteractionUpdate { id } => {
-                let rt = self.runtime.get_or_insert_with(|| {
-                    tokio::runtime::Runtime::new().unwrap()
-                });
-                
-                let api = self.api_client.clone();
-                let id_clone = id.clone();
-                
+            events::AppEvent::Sse { server, message: sse::SSEMessage::InteractionUpdate { id, .. } } => {
+                // Counts of the followed session move with its interactions
+                let counts = match state::active_session(&self.state) {
+                    Some(session) if server == self.state.active_server => self.load_session_counts(session.to_string()),
+                    _ => Command::none(),
+                };
+                Command::batch([self.fetch_interaction(server, id), counts])
+            }
+            events::AppEvent::Sse { server, message: sse::SSEMessage::Error(msg) } => {
+                eprintln!("[GUI] SSE error from {}: {}", server, msg);
+                if server == self.state.active_server {
+                    update(&mut self.state, Action::Editor(EditorAction::Disconnected { reason: Some(msg) }));
+                }
+                Command::none()
+            }
+            events::AppEvent::Sse { server, message: sse::SSEMessage::Unhandled(event_type) } => {
+                if !self.state.unhandled_events.contains_key(&event_type) {
+                    eprintln!("[GUI] Dropping '{}' events from {}, the GUI doesn't handle them", event_type, server);
+                }
+                update(&mut self.state, Action::SseEventUnhandled(event_type));
+                Command::none()
+            }
+            events::AppEvent::Sse { server, message: sse::SSEMessage::Unauthorized } => {
+                eprintln!("[GUI] SSE connection to {} unauthorized", server);
+                // The prompt sets the key of the server on screen
+                if server == self.state.active_server {
+                    update(&mut self.state, Action::RequestCredentials);
+                }
+                Command::none()
+            }
+            events::AppEvent::Desktop(desktop::DesktopEvent::Clicked { server, conversation_id }) => {
+                let mut switched = Command::none();
+                if let Some(idx) = self.find_conversation_by_interaction_id(&server, &conversation_id) {
+                    self.tasks.switch_conversation();
+                    update(&mut self.state, Action::SelectConversation(idx));
+                    self.show_draft();
+                    switched = self.activate_server();
+                }
+                Command::batch([switched, window::get_latest().and_then(window::gain_focus)])
+            }
+        }
+    }
+    
+    fn fetch_interaction(&self, server: String, id: String) -> Command<Message> {
+        let api = self.api_for(&server);
+        self.tasks.perform(
+            "get interaction",
+            self.tasks.app(),
+            async move {
                 // Small delay to let server finish updating metadata
-                std::thread::sleep(std::time::Duration::from_millis(50));
-                
-                // Fetch the conversation
-                match rt.block_on(async move { api.get_interaction(&id_clone).await }) {
-                    Ok(conversation_data) => {
-                        // Update cache
-                        self.interaction_cache.insert(id.clone(), conversation_data.clone());
-                        
-                        // Convert to GUI format
-                        if let Some((interaction_id, new_messages)) = self.convert_server_conversation(&conversation_data) {
-                            // Find or create conversation
-                            if let Some(idx) = self.find_conversation_by_interaction_id(&interaction_id) {
-                                // Update existing conversation
-                                if let Some(conv) = self.state.conversations.get_mut(idx) {
-                                    // Update messages
-                                    conv.messages = new_messages;
-                                }
-                            } else {
-                                // Create new conversation
-                                let title = state::generate_ubuntu_style_name();
-                                let conversation = state::Conversation {
-                                    id: interaction_id.clone(),
-                                    title,
-                                    messages: new_messages,
-                                };
-                                self.state.conversations.push(conversation);
-                            }
-                        }
-                    }
-                    Err(e) => {
-                        eprintln!("[GUI] Failed to fetch interaction {}: {}", id, e);
+                tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+                let result = api.get_interaction(&id, state::HISTORY_PAGE_SIZE, None).await;
+                (server, id, result)
+            },
+            |(server, id, result)| Message::InteractionFetched(server, id, result)
+        )
+    }
+    
+    fn apply_interaction(&mut self, server: String, id: String, conversation: ServerConversation) -> Command<Message> {
+        // Update cache
+        self.interaction_cache.insert((server.clone(), id), conversation.clone());
+        
+        // Only the newest page comes back, older ones are loaded on scroll
+        let has_more = conversation.has_more;
+        
+        // Convert to GUI format
+        let new_messages = self.convert_server_conversation(&server, &conversation);
+        let interaction = conversation.interaction;
+        let interaction_id = interaction.id.clone();
+        if self.state.deleted_conversations.contains(&interaction_id) {
+            return Command::none();
+        }
+        // The core keeps the server's copy, tags are changed through it
+        update(&mut self.state, Action::Editor(EditorAction::InteractionPosted { interaction: interaction.clone() }));
+        let interaction_type = interaction.interaction_type.clone();
+        
+        // Find or create conversation
+        if let Some(idx) = self.find_conversation_by_interaction_id(&server, &interaction_id) {
+            let hooks = self.conversation_hooks(&self.state.conversations[idx], &new_messages);
+            // Update existing conversation
+            if let Some(conv) = self.state.conversations.get_mut(idx) {
+                // Tell the user about finished work if they're looking elsewhere
+                if !self.state.window_focused && !conv.muted {
+                    let alerts = desktop::alerts_for_update(
+                        &conv.title,
+                        &conv.messages,
+                        &new_messages,
+                        &self.state.settings.notifications,
+                    );
+                    for alert in alerts {
+                        desktop::notify(alert, server.clone(), conv.id.clone(), self.events.clone());
                     }
                 }
+                
+                // Update messages, minus the ones deleted locally
+                conv.interaction_type = interaction_type;
+                if let Some(attached) = agents::attached(&interaction) {
+                    conv.agents = attached;
+                    state::keep_route(conv);
+                }
+                conv.review_stack = interaction.review_stack.clone();
+                let new_messages = new_messages
+                    .into_iter()
+                    .filter(|m| !self.state.deleted_messages.contains(&m.id))
+                    .collect();
+                let changes = state::merge_newest_page(conv, new_messages, has_more);
+                // A refetch that changed nothing isn't progress
+                if changes.is_empty() {
+                    return hooks;
+                }
+                println!("[GUI] {} on {}: {}", interaction_id, server, changes.summary());
+                state::title_from_first_exchange(conv);
             }
-            SSEEventData::Error(msg) => {
-                eprintln!("[GUI] SSE error: {}", msg);
-            }
+            update(&mut self.state, Action::InteractionProgressed(interaction_id));
+            return hooks;
+        } else {
+            // Create new conversation, imported ones bring their own title
+            let imported_title = interaction.metadata.get("title").and_then(|title| title.as_str()).map(str::to_string);
+            let title_source = match imported_title {
+                Some(_) => state::TitleSource::Manual,
+                None => state::TitleSource::Generated,
+            };
+            let title = imported_title.unwrap_or_else(state::generate_ubuntu_style_name);
+            let history_cursor = new_messages.first().map(|m| m.id.clone()).filter(|_| has_more);
+            let mut conversation = state::Conversation {
+                id: interaction_id.clone(),
+                title,
+                title_source,
+                interaction_type,
+                muted: self.state.muted.contains(&interaction_id),
+                messages: new_messages,
+                archived: false,
+                worktree_id: interaction
+                    .metadata
+                    .get("worktreeContext")
+                    .and_then(|context| context.get("worktreeId")?.as_str())
+                    .map(str::to_string),
+                bookmarks: HashSet::new(),
+                server,
+                history_cursor,
+                loading_history: false,
+                budget: None,
+                agents: agents::attached(&interaction).unwrap_or_default(),
+                pins: Vec::new(),
+                route: None,
+                review_stack: interaction.review_stack.clone(),
+            };
+            state::title_from_first_exchange(&mut conversation);
+            self.state.conversations.push(conversation);
         }
+        Command::none()
     }
 
+    /// Handle a message, then carry out whatever the core reducer asked for meanwhile
     fn update(&mut self, message: Message) -> Command<Message> {
+        let command = self.handle(message);
+        Command::batch([command, self.run_effects()])
+    }
+
+    fn handle(&mut self, message: Message) -> Command<Message> {
+        // Any request can be turned away, ask for a key and still handle the failure below
+        if auth::unauthorized(&message, &self.state.active_server) {
+            update(&mut self.state, Action::RequestCredentials);
+        }
+        
         match message {
-            Message::InputChanged(text) => {
-                update(&mut self.state, Action::InputChanged(text));
-                Command::none()
+            Message::Composer(message) => {
+                match message {
+                    ComposerMessage::NextCompletion => {
+                        update(&mut self.state, Action::SelectNextMention);
+                        return Command::none();
+                    }
+                    ComposerMessage::PreviousCompletion => {
+                        update(&mut self.state, Action::SelectPreviousMention);
+                        return Command::none();
+                    }
+                    ComposerMessage::AcceptCompletion => {
+                        let path = self.state.mention.as_ref().and_then(|mention| {
+                            mentions::matches(&self.state.editor.worktree_files, &mention.query)
+                                .get(mention.selected)
+                                .map(|path| path.to_string())
+                        });
+                        if let Some(path) = path {
+                            multiline_input::complete_mention(&mut self.composer, &path);
+                            update(&mut self.state, Action::InputChanged(multiline_input::text(&self.composer)));
+                        }
+                        update(&mut self.state, Action::MentionChanged(None));
+                        return Command::none();
+                    }
+                    _ => {}
+                }
+                
+                let is_edit = matches!(&message, ComposerMessage::Edit(action) if action.is_edit());
+                let submit = multiline_input::update(&mut self.composer, message);
+                update(&mut self.state, Action::InputChanged(multiline_input::text(&self.composer)));
+                
+                // Typing a template prefix expands it, Esc puts the prefix back
+                if is_edit {
+                    if let Some(template) = self.state.settings.templates.0.get(&self.state.editor.draft.content).cloned() {
+                        let trigger = self.state.editor.draft.content.clone();
+                        multiline_input::expand_template(&mut self.composer, &template);
+                        update(&mut self.state, Action::InputChanged(multiline_input::text(&self.composer)));
+                        update(&mut self.state, Action::TemplateExpanded { trigger });
+                    }
+                }
+                
+                // Refresh the file list whenever a new `@` mention starts
+                let was_mentioning = self.state.mention.is_some();
+                update(&mut self.state, Action::MentionChanged(multiline_input::mention_at_cursor(&self.composer)));
+                
+                if submit && self.state.editing.is_some() {
+                    self.update(Message::ResendEdited)
+                } else if submit {
+                    self.update(Message::SendMessage)
+                } else if !was_mentioning && self.state.mention.is_some() {
+                    self.load_worktree_files()
+                } else {
+                    Command::none()
+                }
             }
             
             Message::SendMessage => {
-                if !self.state.input.is_empty() {
+                if !self.state.editor.draft.content.is_empty() {
                     if let Some(conv_idx) = self.state.active_conversation {
-                        let content = self.state.input.clone();
+                        if !self.within_budget(conv_idx) {
+                            return Command::none();
+                        }
+                        if attachments::uploading(&self.state.composer_attachments) {
+                            update(&mut self.state, Action::ShowNotification(state::Notification::Info(
+                                "Still uploading attachments, send once they're done".to_string()
+                            )));
+                            return Command::none();
+                        }
+                        let content = self.state.editor.draft.content.clone();
+                        let attachment_ids = attachments::uploaded(&self.state.composer_attachments)
+                            .into_iter()
+                            .map(|attachment| attachment.id)
+                            .collect();
                         update(&mut self.state, Action::SendMessage);
+                        self.composer = text_editor::Content::new();
                         
                         // Get the interaction ID from the active conversation if it exists
                         let interaction_id = self.state.conversations.get(conv_idx)
                             .and_then(|conv| {
-                                // Check if any message has been successfully sent (not pending)
-                                let has_sent_message = conv.messages.iter().any(|m| 
-                                    m.status != types::MessageStatus::Pending
-                                );
+                                // Messages that failed to send don't make it server-synced
+                                let interaction_id = message_errors::interaction_id(conv);
                                 
-                                if has_sent_message {
+                                if interaction_id.is_some() {
                                     // We have a server-synced conversation
                                     println!("[GUI] Sending message to existing interaction: {}", conv.id);
-                                    Some(conv.id.clone())
                                 } else {
                                     // First message or all messages still pending
                                     println!("[GUI] First message or pending, creating new interaction");
-                                    None
                                 }
+                                interaction_id
                             });
                         
-                        // Build request with optional worktree and interaction ID
+                        // Build request with the conversation's worktree and interaction ID
+                        let conv = self.state.conversations.get(conv_idx);
                         let request = SendMessageRequest {
                             content,
-                            metadata: None,
-                            worktree_id: self.state.current_worktree.as_ref().map(|w| w.id.clone()),
+                            metadata: conv.and_then(|conv| agents::with_route(conv, pins::with_pins(conv, None))),
+                            worktree_id: conv.and_then(|conv| conv.worktree_id.clone()),
                             interaction_id,
+                            attachment_ids,
                         };
                         
-                        // Send message asynchronously
-                        let api = self.api_client.clone();
-                        Command::perform(
-                            async move { api.send_message(request).await },
-                            Message::MessageSent
-                        )
+                        self.send_message(conv_idx, request)
                     } else {
                         Command::none()
                     }
@@ -421,27 +978,46 @@ impl BickyApp {
                 }
             }
             
-            Message::MessageSent(result) => {
+            Message::MessageSent(server, conversation_id, result) => {
+                // Whatever is on screen now, the reply belongs to the conversation that sent
+                let Some(conv_idx) = self.find_conversation_by_interaction_id(&server, &conversation_id) else {
+                    println!("[GUI] Conversation {} is gone, dropping its send result", conversation_id);
+                    return Command::none();
+                };
                 match result {
                     Ok(resp) => {
                         println!("[GUI] Message sent successfully, interaction ID: {}", resp.id);
                         // The resp.id is now the interaction ID, not a message ID
                         // Update the conversation's ID to match the server's interaction ID
-                        if let Some(conv_idx) = self.state.active_conversation {
-                            if let Some(conv) = self.state.conversations.get_mut(conv_idx) {
-                                let old_id = conv.id.clone();
-                                conv.id = resp.id.clone();
-                                println!("[GUI] Updated conversation ID from {} to {}", old_id, resp.id);
-                                // Update the last message's status to processing
-                                if let Some(last_msg) = conv.messages.last_mut() {
-                                    last_msg.status = types::MessageStatus::Processing;
-                                }
+                        if let Some(conv) = self.state.conversations.get_mut(conv_idx) {
+                            let old_id = conv.id.clone();
+                            conv.id = resp.id.clone();
+                            println!("[GUI] Updated conversation ID from {} to {}", old_id, resp.id);
+                            // A label given before the first send follows the new ID
+                            if let Some(label) = self.state.labels.remove(&old_id) {
+                                self.state.labels.insert(resp.id.clone(), label);
                             }
+                            if self.state.muted.remove(&old_id) {
+                                self.state.muted.insert(resp.id.clone());
+                            }
+                            // Update the last message's status to processing
+                            if let Some(last_msg) = conv.messages.last_mut() {
+                                last_msg.status = types::MessageStatus::Processing;
+                            }
+                            // So does whatever is being typed into it
+                            update(&mut self.state, Action::Editor(EditorAction::InteractionIdAssigned {
+                                temp_id: old_id,
+                                interaction_id: resp.id.clone(),
+                            }));
+                        }
+                        if let Some(message_id) = resp.message_id {
+                            update(&mut self.state, Action::MessageAccepted { conversation_idx: conv_idx, message_id });
                         }
                     }
                     Err(err) => {
                         eprintln!("[GUI] Failed to send message: {}", err);
-                        update(&mut self.state, Action::Error(err));
+                        // Keep the failure on the message itself, where it can be retried
+                        update(&mut self.state, Action::MessageSendFailed { conversation_idx: conv_idx, error: err.into() });
                     }
                 }
                 Command::none()
@@ -449,259 +1025,1390 @@ impl BickyApp {
             
             Message::NewConversation => {
                 // Just add a new conversation locally
+                self.tasks.switch_conversation();
                 update(&mut self.state, Action::NewConversation);
+                self.show_draft();
                 Command::none()
             }
             
             Message::SelectConversation(idx) => {
+                if self.state.active_conversation != Some(idx) {
+                    self.tasks.switch_conversation();
+                }
                 update(&mut self.state, Action::SelectConversation(idx));
+                self.show_draft();
+                self.load_images()
+            }
+            
+            Message::ToggleMute(idx) => {
+                self.toggle_mute(idx);
                 Command::none()
             }
             
-            Message::Tick => {
-                // Check for SSE messages
-                let mut events = Vec::new();
-                if let Some(rx) = &self.sse_receiver {
-                    // Collect all pending SSE messages
-                    while let Ok(msg) = rx.try_recv() {
-                        let event = match msg {
-                            sse::SSEMessage::Connected => SSEEventData::Connected,
-                            sse::SSEMessage::InteractionUpdate { id, .. } => SSEEventData::InteractionUpdate { id },
-                            sse::SSEMessage::Error(e) => SSEEventData::Error(e),
-                        };
-                        events.push(event);
-                    }
-                }
-                
-                // Process collected events
-                for event in events {
-                    self.handle_sse_event(event);
-                }
-                
-                // Auto-clear notifications after 5 seconds
-                if let Some((_, timestamp)) = &self.state.notification {
-                    if timestamp.elapsed().as_secs() >= 5 {
-                        update(&mut self.state, Action::ClearNotification);
-                    }
-                }
-                
-                // Still need to redraw for spinner animation
-                if let Some(conv_idx) = self.state.active_conversation {
-                    if let Some(conv) = self.state.conversations.get(conv_idx) {
-                        let has_processing = conv.messages.iter().any(|m| m.status == MessageStatus::Processing);
-                        if has_processing {
-                            // The subscription will handle the next tick
-                        }
-                    }
-                }
+            Message::ToggleTimings(message_id) => {
+                update(&mut self.state, Action::ToggleTimings(message_id));
                 Command::none()
             }
             
-            Message::ApproveToolUse => {
-                // Find the conversation with a message waiting for permission
-                if let Some(conv_idx) = self.state.active_conversation {
-                    if let Some(conv) = self.state.conversations.get(conv_idx) {
-                        if let Some(_msg) = conv.messages.iter().find(|m| m.status == MessageStatus::WaitingForPermission) {
-                            // Use the conversation ID which is the interaction ID
-                            let interaction_id = conv.id.clone();
-                            let api = self.api_client.clone();
-                            return Command::perform(
-                                async move { api.respond_to_permission(&interaction_id, true).await },
-                                Message::PermissionResponseSent
-                            );
-                        }
-                    }
+            Message::ToggleBookmark(message_id) => {
+                if let Some(conversation_idx) = self.state.active_conversation {
+                    update(&mut self.state, Action::ToggleBookmark { conversation_idx, message_id });
                 }
                 Command::none()
             }
             
-            Message::DenyToolUse => {
-                // Find the conversation with a message waiting for permission
-                if let Some(conv_idx) = self.state.active_conversation {
-                    if let Some(conv) = self.state.conversations.get(conv_idx) {
-                        if let Some(_msg) = conv.messages.iter().find(|m| m.status == MessageStatus::WaitingForPermission) {
-                            // Use the conversation ID which is the interaction ID
-                            let interaction_id = conv.id.clone();
-                            let api = self.api_client.clone();
-                            return Command::perform(
-                                async move { api.respond_to_permission(&interaction_id, false).await },
-                                Message::PermissionResponseSent
-                            );
-                        }
-                    }
-                }
+            Message::TogglePin(conversation_idx, pin) => {
+                update(&mut self.state, Action::TogglePin { conversation_idx, pin });
                 Command::none()
             }
             
-            Message::PermissionResponseSent(result) => {
-                if let Err(e) = result {
-                    eprintln!("Failed to send permission response: {}", e);
-                }
+            Message::SetPinsExpanded(expanded) => {
+                update(&mut self.state, Action::SetPinsExpanded(expanded));
                 Command::none()
             }
             
-            Message::SSEEvent(event) => {
-                self.handle_sse_event(event);
+            Message::PinDraftChanged(path) => {
+                update(&mut self.state, Action::PinDraftChanged(path));
                 Command::none()
             }
             
-            Message::WorktreesLoaded(result) => {
-                match result {
-                    Ok(worktrees) => {
-                        update(&mut self.state, Action::WorktreesLoaded(worktrees));
-                    }
-                    Err(err) => update(&mut self.state, Action::Error(err)),
-                }
+            Message::PinDraftedFile(conversation_idx) => {
+                update(&mut self.state, Action::PinDraftedFile(conversation_idx));
                 Command::none()
             }
             
-            Message::SelectWorktree(id) => {
-                update(&mut self.state, Action::SelectWorktree(id));
+            Message::TagDraftChanged(tag) => {
+                update(&mut self.state, Action::TagDraftChanged(tag));
                 Command::none()
             }
             
-            Message::OpenWorktreeDialog => {
-                update(&mut self.state, Action::OpenWorktreeDialog);
+            Message::AddDraftedTag(conversation_idx) => {
+                update(&mut self.state, Action::AddDraftedTag(conversation_idx));
                 Command::none()
             }
             
-            Message::CloseWorktreeDialog => {
-                update(&mut self.state, Action::CloseWorktreeDialog);
+            Message::RemoveTag(conversation_idx, tag) => {
+                update(&mut self.state, Action::RemoveTag { conversation_idx, tag });
                 Command::none()
             }
             
-            Message::WorktreeBranchNameChanged(name) => {
-                update(&mut self.state, Action::UpdateWorktreeBranchName(name));
+            Message::TagsUpdated(server, interaction_id, result) => match result {
+                Ok(_) => Command::none(),
+                Err(e) => {
+                    eprintln!("[GUI] Failed to update tags on {}: {}", interaction_id, e);
+                    update(&mut self.state, Action::ShowNotification(
+                        state::Notification::Error(format!("Couldn't save tags: {}", e))
+                    ));
+                    // Back to the tags the server has
+                    self.fetch_interaction(server, interaction_id)
+                }
+            },
+            
+            Message::ToggleMinimap => {
+                update(&mut self.state, Action::ToggleMinimap);
                 Command::none()
             }
             
-            Message::WorktreeBaseBranchChanged(branch) => {
-                update(&mut self.state, Action::UpdateWorktreeBaseBranch(branch));
+            Message::ToggleFocusMode => {
+                update(&mut self.state, Action::ToggleFocusMode);
                 Command::none()
             }
             
-            Message::WorktreeCustomPathChanged(path) => {
-                update(&mut self.state, Action::UpdateWorktreeCustomPath(path));
+            Message::StartPresentation => {
+                update(&mut self.state, Action::StartPresentation);
                 Command::none()
             }
             
-            Message::CreateWorktree => {
-                if let Some(dialog) = &self.state.worktree_dialog {
-                    if dialog.branch_name.is_empty() {
-                        update(&mut self.state, Action::WorktreeCreationFailed("Branch name is required".to_string()));
-                        return Command::none();
-                    }
-                    
-                    let api = self.api_client.clone();
-                    let request = CreateWorktreeRequest {
-                        branch: dialog.branch_name.clone(),
-                        base_branch: Some(dialog.base_branch.clone()),
-                        path: if dialog.custom_path.is_empty() { None } else { Some(dialog.custom_path.clone()) },
-                    };
-                    
-                    Command::perform(
-                        async move { api.create_worktree(request).await },
-                        Message::WorktreeCreated
-                    )
-                } else {
-                    Command::none()
-                }
+            Message::ShowArtifacts(show) => {
+                update(&mut self.state, Action::ShowArtifacts(show));
+                Command::none()
             }
             
-            Message::WorktreeCreated(result) => {
-                match result {
-                    Ok(worktree) => {
-                        update(&mut self.state, Action::WorktreeCreated(worktree));
-                    }
-                    Err(err) => {
-                        update(&mut self.state, Action::WorktreeCreationFailed(err));
-                    }
-                }
+            Message::SaveArtifact(artifact) => self.save_artifact_here(artifact),
+            
+            Message::SaveArtifactAs(artifact) => self.save_artifact_as(artifact),
+            
+            Message::SaveArtifactPathChanged(path) => {
+                update(&mut self.state, Action::SaveArtifactPathChanged(path));
                 Command::none()
             }
-        }
-    }
-
-    fn subscription(&self) -> Subscription<Message> {
-        // Poll every 16ms for 60fps updates
-        time::every(Duration::from_millis(16)).map(|_| Message::Tick)
-    }
-    
-    fn build_channel_list(&self) -> Element<Message> {
-        let mut channel_list = column![
-            // Header
-            container(
-                row![
-                    text("Interactions").size(14).font(fonts::BERKELEY_MONO_BOLD),
-                    button(text("+").size(14).font(fonts::BERKELEY_MONO))
-                        .on_press(Message::NewConversation)
-                        .padding(6)
-                        .style(theme::add_button)
-                ]
-                .spacing(10)
-                .align_y(iced::Alignment::Center)
-            )
-            .padding(16)
-            .width(Length::Fill),
-        ]
-        .spacing(4);
-        
-        // Channel entries
-        for (idx, conv) in self.state.conversations.iter().enumerate() {
-            let is_active = self.state.active_conversation == Some(idx);
             
-            let mut entry_content = column![
-                row![
-                    text("#").size(13).font(fonts::BERKELEY_MONO).color(theme::Colors::TEXT_DIM),
-                    text(&conv.title).size(14).font(fonts::BERKELEY_MONO),
-                ]
-                .spacing(6)
-                .align_y(iced::Alignment::Center)
-            ]
-            .spacing(2);
+            Message::ConfirmSaveArtifact => self.confirm_save_artifact(),
             
-            // Add current worktree info if conversation is active
-            if is_active {
-                if let Some(worktree) = &self.state.current_worktree {
-                    // Extract just the branch name from the path
-                    let branch_name = worktree.path
-                        .split('/')
-                        .last()
-                        .unwrap_or("main");
-                    
-                    entry_content = entry_content.push(
-                        row![
-                            Space::with_width(19), // Indent to align with title
-                            text(format!("🌿 {}", branch_name))
-                                .size(11)
-                                .font(fonts::BERKELEY_MONO)
-                                .color(theme::Colors::SUCCESS),
-                        ]
-                    );
-                }
+            Message::CloseSaveArtifact => {
+                update(&mut self.state, Action::CloseSaveArtifact);
+                Command::none()
             }
             
-            let channel_entry = button(
-                container(entry_content)
-                    .padding(12)
-                    .width(Length::Fill)
-            )
-            .on_press(Message::SelectConversation(idx))
-            .style(move |theme, _| {
-                if is_active {
-                    theme::channel_button_active(theme)
+            Message::ArtifactSaved(id, result) => {
+                self.artifact_saved(id, result);
+                Command::none()
+            }
+            
+            Message::MinimapJump(fraction) => self.jump_transcript(scrolling::Jump::Fraction(fraction)),
+            
+            Message::TimelineScrubbed(value) => {
+                self.timeline_scrub = Some(value);
+                self.scrub_timeline(value)
+            }
+            
+            Message::TimelineReleased => {
+                self.timeline_scrub = None;
+                Command::none()
+            }
+            
+            Message::ArchiveConversation(idx) => {
+                update(&mut self.state, Action::ArchiveConversation(idx));
+                self.show_draft();
+                Command::none()
+            }
+            
+            Message::UnarchiveConversation(idx) => {
+                update(&mut self.state, Action::UnarchiveConversation(idx));
+                Command::none()
+            }
+            
+            Message::ToggleArchived => {
+                update(&mut self.state, Action::ToggleArchivedSection);
+                Command::none()
+            }
+            
+            Message::ConfirmDeleteConversation(idx) => {
+                update(&mut self.state, Action::RequestDeleteConversation(idx));
+                Command::none()
+            }
+            
+            Message::CancelDeleteConversation => {
+                update(&mut self.state, Action::CancelDeleteConversation);
+                Command::none()
+            }
+            
+            Message::DeleteConversation(idx) => {
+                let Some((server, interaction_id)) = self.state.conversations.get(idx).map(|c| (c.server.clone(), c.id.clone())) else {
+                    return Command::none();
+                };
+                
+                let labelled = self.state.labels.contains_key(&interaction_id);
+                let muted = self.state.muted.contains(&interaction_id);
+                update(&mut self.state, Action::DeleteConversation(idx));
+                self.show_draft();
+                if labelled {
+                    self.save_labels();
+                }
+                if muted {
+                    self.save_mutes();
+                }
+                let api = self.api_for(&server);
+                self.interaction_cache.remove(&(server, interaction_id.clone()));
+                
+                self.tasks.run(
+                    "delete interaction",
+                    async move { api.delete_interaction(&interaction_id).await },
+                    Message::ConversationDeleted
+                )
+            }
+            
+            Message::ConversationDeleted(result) => {
+                if let Err(e) = result {
+                    eprintln!("[GUI] Failed to delete interaction on the server: {}", e);
+                    update(&mut self.state, Action::ShowNotification(
+                        state::Notification::Error(format!("Deleted locally, but the server said: {}", e))
+                    ));
+                }
+                Command::none()
+            }
+            
+            Message::ToggleJobsPanel => {
+                update(&mut self.state, Action::ToggleJobsPanel);
+                Command::none()
+            }
+            
+            Message::OpenDiff(worktree_id) => {
+                let title = self.state.editor.available_worktrees
+                    .iter()
+                    .find(|w| w.id == worktree_id)
+                    .map(|w| worktrees::branch_label(w).to_string())
+                    .unwrap_or_else(|| worktree_id.clone());
+                update(&mut self.state, Action::OpenDiff { worktree_id: worktree_id.clone(), title });
+                
+                let api = self.api_client.clone();
+                self.tasks.perform(
+                    "get worktree diff",
+                    self.tasks.conversation(),
+                    {
+                        let worktree_id = worktree_id.clone();
+                        async move { api.get_worktree_diff(&worktree_id).await }
+                    },
+                    move |result| Message::DiffLoaded(worktree_id.clone(), result)
+                )
+            }
+            
+            Message::DiffLoaded(worktree_id, result) => {
+                // Ignore diffs for a worktree we've since moved away from
+                if self.state.diff_view.as_ref().map(|view| &view.worktree_id) != Some(&worktree_id) {
+                    return Command::none();
+                }
+                match result {
+                    Ok(worktree_diff) => {
+                        let files = bicky_editor_core::diff::parse(&worktree_diff.diff);
+                        println!("[GUI] Loaded diff for {}: {} files against {}", worktree_id, files.len(), worktree_diff.base);
+                        update(&mut self.state, Action::DiffLoaded(files));
+                    }
+                    Err(e) => {
+                        eprintln!("[GUI] Failed to load diff for {}: {}", worktree_id, e);
+                        update(&mut self.state, Action::DiffFailed(e.to_string()));
+                    }
+                }
+                Command::none()
+            }
+            
+            Message::SelectDiffFile(idx) => {
+                update(&mut self.state, Action::SelectDiffFile(idx));
+                Command::none()
+            }
+            
+            Message::ToggleHunk(idx) => {
+                update(&mut self.state, Action::ToggleHunk(idx));
+                Command::none()
+            }
+            
+            Message::CloseDiff => {
+                update(&mut self.state, Action::CloseDiff);
+                Command::none()
+            }
+            
+            Message::OpenMergeDialog(worktree_id, mode) => {
+                update(&mut self.state, Action::OpenMergeDialog { worktree_id, mode });
+                Command::none()
+            }
+            
+            Message::MergeMessageChanged(message) => {
+                update(&mut self.state, Action::MergeMessageChanged(message));
+                Command::none()
+            }
+            
+            Message::MergeBodyChanged(body) => {
+                update(&mut self.state, Action::MergeBodyChanged(body));
+                Command::none()
+            }
+            
+            Message::SubmitMerge => {
+                let Some(dialog) = self.state.merge_dialog.clone() else {
+                    return Command::none();
+                };
+                if dialog.in_progress || dialog.message.trim().is_empty() {
+                    return Command::none();
+                }
+                
+                update(&mut self.state, Action::MergeStarted);
+                let api = self.api_client.clone();
+                match dialog.mode {
+                    state::MergeMode::Merge => {
+                        println!("[GUI] Merging {} into {}", dialog.branch, dialog.base);
+                        update(&mut self.state, Action::ShowNotification(
+                            state::Notification::Info(format!("Merging {} into {}...", dialog.branch, dialog.base))
+                        ));
+                        let request = MergeWorktreeRequest { message: dialog.message };
+                        self.tasks.run(
+                            "merge worktree",
+                            async move { api.merge_worktree(&dialog.worktree_id, request).await },
+                            move |result| Message::MergeDone(result.map(|merged| {
+                                format!("Merged {} into {}", dialog.branch, merged.base)
+                            }))
+                        )
+                    }
+                    state::MergeMode::PullRequest => {
+                        println!("[GUI] Opening a pull request from {} into {}", dialog.branch, dialog.base);
+                        update(&mut self.state, Action::ShowNotification(
+                            state::Notification::Info(format!("Pushing {} and opening a PR...", dialog.branch))
+                        ));
+                        let request = CreatePullRequestRequest { title: dialog.message, body: dialog.body };
+                        self.tasks.run(
+                            "create pull request",
+                            async move { api.create_pull_request(&dialog.worktree_id, request).await },
+                            |result| Message::MergeDone(result.map(|pr| format!("Opened {}", pr.url)))
+                        )
+                    }
+                }
+            }
+            
+            Message::CloseMergeDialog => {
+                update(&mut self.state, Action::CloseMergeDialog);
+                Command::none()
+            }
+            
+            Message::SetExportFrom(from) => {
+                update(&mut self.state, Action::SetExportFrom(from));
+                Command::none()
+            }
+            
+            Message::SetExportTo(to) => {
+                update(&mut self.state, Action::SetExportTo(to));
+                Command::none()
+            }
+            
+            Message::ToggleExportPrompts => {
+                update(&mut self.state, Action::ToggleExportPrompts);
+                Command::none()
+            }
+            
+            Message::ToggleExportResponses => {
+                update(&mut self.state, Action::ToggleExportResponses);
+                Command::none()
+            }
+            
+            Message::ExportConversation => {
+                let Some(dialog) = self.state.export_dialog.clone() else {
+                    return Command::none();
+                };
+                let Some(conv) = self.state.conversations.get(dialog.conversation_idx) else {
+                    return Command::none();
+                };
+                if dialog.selection.is_empty(conv) {
+                    return Command::none();
+                }
+                
+                let markdown = export::selection_to_markdown(conv, &dialog.selection);
+                let notification = match export::write_export(&conv.title, &markdown) {
+                    Ok(path) => {
+                        update(&mut self.state, Action::CloseExportDialog);
+                        state::Notification::Success(format!("Exported to {}", path.display()))
+                    }
+                    Err(e) => state::Notification::Error(format!("Export failed: {}", e)),
+                };
+                update(&mut self.state, Action::ShowNotification(notification));
+                Command::none()
+            }
+            
+            Message::CloseExportDialog => {
+                update(&mut self.state, Action::CloseExportDialog);
+                Command::none()
+            }
+            
+            Message::ReviewReplyChanged(reply) => {
+                update(&mut self.state, Action::ReviewReplyChanged(reply));
+                Command::none()
+            }
+            
+            Message::SubmitReviewReply => self.submit_review_reply(),
+            
+            Message::ReviewReplySubmitted(server, id, result) => self.review_reply_submitted(server, id, result),
+            
+            Message::ApiKeyChanged(api_key) => {
+                update(&mut self.state, Action::ApiKeyChanged(api_key));
+                Command::none()
+            }
+            
+            Message::SubmitApiKey => self.submit_api_key(),
+            
+            Message::SwitchServer(server) => self.switch_server(server),
+            Message::SessionsLoaded(server, result) => self.sessions_loaded(server, result),
+            Message::ConversationsListed(server, result) => self.conversations_listed(server, result),
+            Message::PluginLoaded(settings, answer) => {
+                self.plugin_loaded(settings, answer);
+                Command::none()
+            }
+            Message::PluginRendered(message_id, answer) => {
+                self.plugin_rendered(message_id, answer);
+                Command::none()
+            }
+            Message::PluginPanelLoaded(plugin, answer) => {
+                self.plugin_panel_loaded(plugin, answer);
+                Command::none()
+            }
+            Message::PluginCommandFinished(plugin, answer) => {
+                self.plugin_command_finished(plugin, answer);
+                Command::none()
+            }
+            Message::HookFinished(run, result) => self.hook_finished(run, result),
+            Message::HookWrote(script, path, result) => {
+                self.hook_wrote(script, path, result);
+                Command::none()
+            }
+            Message::TranscriptImported(server, path, result) => {
+                let name = path.file_name().map_or_else(|| path.display().to_string(), |n| n.to_string_lossy().into_owned());
+                match result {
+                    Ok(imported) => {
+                        println!("[GUI] Imported {} as {} on {}", path.display(), imported.id, server);
+                        // Left in the inbox it would be imported again next time
+                        if let Err(e) = import::archive(&path) {
+                            eprintln!("[GUI] Couldn't move {} out of the inbox: {}", path.display(), e);
+                        }
+                        update(&mut self.state, Action::ShowNotification(state::Notification::Success(
+                            format!("Imported {} ({} messages)", name, imported.message_count)
+                        )));
+                        self.fetch_interaction(server, imported.id)
+                    }
+                    Err(e) => {
+                        eprintln!("[GUI] Failed to import {}: {}", path.display(), e);
+                        update(&mut self.state, Action::ShowNotification(state::Notification::Error(
+                            format!("Couldn't import {}: {}", name, e)
+                        )));
+                        Command::none()
+                    }
+                }
+            }
+            Message::SessionCountsLoaded(session_id, Ok(status)) => {
+                update(&mut self.state, Action::Editor(EditorAction::SessionQueueLoaded { session_id, status }));
+                Command::none()
+            }
+            Message::SessionCountsLoaded(session_id, Err(e)) => {
+                eprintln!("[GUI] Failed to load counts for session {}: {}", session_id, e);
+                Command::none()
+            }
+            Message::SwitchSession(session_id) => self.switch_session(session_id),
+            
+            Message::AgentsLoaded(server, result) => {
+                self.agents_loaded(server, result);
+                Command::none()
+            }
+            Message::OpenAgentSelector(conversation_idx) => {
+                update(&mut self.state, Action::OpenAgentSelector(conversation_idx));
+                Command::none()
+            }
+            Message::CloseAgentSelector => {
+                update(&mut self.state, Action::CloseAgentSelector);
+                Command::none()
+            }
+            Message::ToggleAgent(agent_id) => self.toggle_agent(agent_id),
+            Message::AgentsChanged(server, conversation_id, result) => {
+                self.agents_changed(server, conversation_id, result);
+                Command::none()
+            }
+            Message::RouteTo(conversation_idx, agent_id) => {
+                update(&mut self.state, Action::RouteTo { conversation_idx, agent_id });
+                Command::none()
+            }
+            Message::OpenFilePicker => self.open_file_picker(),
+            Message::CloseFilePicker => {
+                update(&mut self.state, Action::CloseFilePicker);
+                Command::none()
+            }
+            Message::BrowseFiles(dir) => {
+                update(&mut self.state, Action::OpenFilePicker(attachments::FilePicker::open(dir)));
+                Command::none()
+            }
+            Message::AttachFile(path) => self.attach_file(path),
+            Message::AttachmentUploaded(path, result) => {
+                self.attachment_uploaded(path, result);
+                Command::none()
+            }
+            Message::RemoveAttachment(idx) => {
+                update(&mut self.state, Action::RemoveAttachment(idx));
+                Command::none()
+            }
+            Message::ImageLoaded(key, result) => {
+                if let Err(e) = &result {
+                    eprintln!("[GUI] Failed to load image {}: {}", key, e);
+                }
+                update(&mut self.state, Action::ImageLoaded { key, result });
+                Command::none()
+            }
+            Message::LoadImage(source) => self.load_image(source),
+            Message::ExpandImage(source) => {
+                update(&mut self.state, Action::ExpandImage(source));
+                Command::none()
+            }
+            Message::OpenAgentConfig(agent_id) => {
+                update(&mut self.state, Action::OpenAgentConfig(agent_id));
+                Command::none()
+            }
+            Message::CloseAgentConfig => {
+                update(&mut self.state, Action::CloseAgentConfig);
+                Command::none()
+            }
+            Message::AgentConfigEdited(field, value) => {
+                update(&mut self.state, Action::UpdateAgentConfig(field, value));
+                Command::none()
+            }
+            Message::SaveAgentConfig => self.save_agent_config(),
+            Message::QuickAction(idx) => self.run_quick_action(idx),
+            Message::ToggleAgentHealth => {
+                update(&mut self.state, Action::ToggleAgentHealth);
+                Command::none()
+            }
+            Message::AgentStatusesLoaded(server, result) => {
+                self.agent_statuses_loaded(server, result);
+                Command::none()
+            }
+            Message::RestartAgent(agent_id) => self.restart_agent(agent_id),
+            Message::AgentRestarted(server, agent_id, result) => self.agent_restarted(server, agent_id, result),
+            Message::AgentConfigSaved(server, agent_id, result) => {
+                self.agent_config_saved(server, agent_id, result);
+                Command::none()
+            }
+            
+            Message::CloseAuthPrompt => {
+                update(&mut self.state, Action::CloseAuthPrompt);
+                Command::none()
+            }
+            
+            Message::MergeDone(result) => {
+                match result {
+                    Ok(summary) => {
+                        println!("[GUI] {}", summary);
+                        update(&mut self.state, Action::CloseMergeDialog);
+                        update(&mut self.state, Action::ShowNotification(state::Notification::Success(summary)));
+                    }
+                    Err(e) => {
+                        eprintln!("[GUI] Merge failed: {}", e);
+                        update(&mut self.state, Action::MergeFailed(e.to_string()));
+                        update(&mut self.state, Action::ShowNotification(state::Notification::Error(e.to_string())));
+                    }
+                }
+                Command::none()
+            }
+            
+            Message::ToggleWorktreePanel => {
+                update(&mut self.state, Action::ToggleWorktreePanel);
+                Command::none()
+            }
+            
+            Message::ConfirmDeleteWorktree(id) => {
+                update(&mut self.state, Action::RequestDeleteWorktree(id));
+                Command::none()
+            }
+            
+            Message::CancelDeleteWorktree => {
+                update(&mut self.state, Action::CancelDeleteWorktree);
+                Command::none()
+            }
+            
+            Message::DeleteWorktree(id) => {
+                // Wait for the server, git refuses to remove checkouts with changes
+                println!("[GUI] Deleting worktree {}", id);
+                let api = self.api_client.clone();
+                self.tasks.run(
+                    "delete worktree",
+                    async move { api.delete_worktree(&id).await.map(|_| id) },
+                    Message::WorktreeDeleted
+                )
+            }
+            
+            Message::WorktreeDeleted(result) => {
+                match result {
+                    Ok(id) => {
+                        update(&mut self.state, Action::WorktreeDeleted(id));
+                        update(&mut self.state, Action::ShowNotification(
+                            state::Notification::Success("Deleted worktree".to_string())
+                        ));
+                    }
+                    Err(e) => {
+                        eprintln!("[GUI] Failed to delete worktree: {}", e);
+                        update(&mut self.state, Action::CancelDeleteWorktree);
+                        update(&mut self.state, Action::ShowNotification(
+                            state::Notification::Error(format!("Couldn't delete worktree: {}", e))
+                        ));
+                    }
+                }
+                Command::none()
+            }
+            
+            Message::InterruptInteraction(interaction_id) => {
+                if self.state.interrupting.contains(&interaction_id) {
+                    return Command::none();
+                }
+                println!("[GUI] Interrupting interaction {}", interaction_id);
+                update(&mut self.state, Action::InterruptRequested(interaction_id.clone()));
+                // Runaway jobs can be on a server that isn't on screen
+                let api = self.api_for(&self.server_of(&interaction_id));
+                self.tasks.run(
+                    "interrupt interaction",
+                    async move {
+                        let result = api.interrupt_interaction(&interaction_id).await;
+                        (interaction_id, result)
+                    },
+                    |(interaction_id, result)| Message::InterruptSent(interaction_id, result)
+                )
+            }
+            
+            Message::RefetchInteraction(interaction_id) => {
+                // Give it a fresh window, a refetch that changes nothing will stall it again
+                println!("[GUI] Refetching interaction {}", interaction_id);
+                update(&mut self.state, Action::InteractionProgressed(interaction_id.clone()));
+                let server = self.server_of(&interaction_id);
+                self.fetch_interaction(server, interaction_id)
+            }
+            
+            Message::InterruptSent(interaction_id, result) => {
+                match result {
+                    Ok(()) => update(&mut self.state, Action::InteractionInterrupted(interaction_id)),
+                    Err(e) => {
+                        eprintln!("[GUI] Failed to interrupt: {}", e);
+                        update(&mut self.state, Action::InterruptFailed(interaction_id));
+                        update(&mut self.state, Action::ShowNotification(
+                            state::Notification::Error(format!("Couldn't interrupt: {}", e))
+                        ));
+                    }
+                }
+                Command::none()
+            }
+            
+            Message::HoverSent(message_id) => {
+                update(&mut self.state, Action::HoverMessage(message_id));
+                Command::none()
+            }
+            
+            Message::TranscriptHovered(hovered) => {
+                self.transcript_scroll.hovered = hovered;
+                Command::none()
+            }
+            
+            Message::TranscriptScrolled(viewport) => {
+                self.transcript_scroll.scrolled(viewport);
+                self.transcript_near_top()
+            }
+            
+            Message::WheelScrolled(delta) => {
+                self.transcript_wheel(delta);
+                Command::none()
+            }
+            
+            Message::ConfirmDeleteSent(message_id) => {
+                update(&mut self.state, Action::RequestDeleteMessage(message_id));
+                Command::none()
+            }
+            
+            Message::CancelDeleteSent => {
+                update(&mut self.state, Action::CancelDeleteMessage);
+                Command::none()
+            }
+            
+            Message::DeleteSent(message_id) => {
+                let Some(conversation_idx) = self.state.active_conversation else {
+                    return Command::none();
+                };
+                
+                update(&mut self.state, Action::DeleteMessage { conversation_idx, message_id: message_id.clone() });
+                
+                // Local ids never made it to the server
+                if message_id.starts_with("temp-") {
+                    return Command::none();
+                }
+                
+                let api = self.api_client.clone();
+                self.tasks.run(
+                    "delete message",
+                    async move { api.delete_message(&message_id).await },
+                    Message::SentDeleted
+                )
+            }
+            
+            Message::SentDeleted(result) => {
+                match result {
+                    Ok(true) => {}
+                    Ok(false) => println!("[GUI] Server doesn't support deleting messages, removed locally only"),
+                    Err(e) => eprintln!("[GUI] Failed to delete message on the server: {}", e),
+                }
+                Command::none()
+            }
+            
+            Message::EditSent(message_id) => {
+                if let Some(conversation_idx) = self.state.active_conversation {
+                    update(&mut self.state, Action::EditMessage { conversation_idx, message_id });
+                    multiline_input::set_text(&mut self.composer, &self.state.editor.draft.content);
+                }
+                Command::none()
+            }
+            
+            Message::ResumeInteraction(message_id) => {
+                let Some(conversation_idx) = self.state.active_conversation else {
+                    return Command::none();
+                };
+                if !self.within_budget(conversation_idx) {
+                    return Command::none();
+                }
+                let Some(conv) = self.state.conversations.get(conversation_idx) else {
+                    return Command::none();
+                };
+                let Some(msg) = conv.messages.iter().find(|m| m.id == message_id) else {
+                    return Command::none();
+                };
+                
+                let content = resume::continuation_prompt(msg);
+                let request = SendMessageRequest {
+                    content: content.clone(),
+                    metadata: agents::with_route(conv, pins::with_pins(conv, Some(resume::continuation_metadata(msg)))),
+                    worktree_id: conv.worktree_id.clone(),
+                    interaction_id: Some(conv.id.clone()),
+                    attachment_ids: Vec::new(),
+                };
+                println!("[GUI] Resuming interaction {} from message {}", conv.id, message_id);
+                
+                update(&mut self.state, Action::ResumeMessage { conversation_idx, content });
+                self.send_message(conversation_idx, request)
+            }
+            
+            Message::RetryFailed(message_id) => self.retry_message(message_id),
+            
+            Message::CopyError(message_id) => self.copy_error(message_id),
+            
+            Message::ResendEdited => {
+                if self.state.editing.is_none() || self.state.editor.draft.content.is_empty() {
+                    return Command::none();
+                }
+                
+                let content = self.state.editor.draft.content.clone();
+                update(&mut self.state, Action::ResendMessage);
+                self.composer = text_editor::Content::new();
+                println!("[GUI] Resending edited message as a new interaction");
+                
+                let request = SendMessageRequest {
+                    content,
+                    metadata: None,
+                    worktree_id: self.state.conversations.last().and_then(|conv| conv.worktree_id.clone()),
+                    interaction_id: None,
+                    attachment_ids: Vec::new(),
+                };
+                // The edit went into a conversation of its own
+                self.send_message(self.state.conversations.len() - 1, request)
+            }
+            
+            Message::Tick => {
+                // Pick up edits to the theme file and OS appearance changes
+                self.reload_theme(true);
+                self.run_daily_backup();
+                let appearance = self.poll_system_appearance();
+                
+                // Start or stop the clock on running jobs
+                update(&mut self.state, Action::SyncJobs);
+                self.detect_stalled_jobs();
+                let interrupts = self.interrupt_runaway_jobs();
+                let worktree_status = self.poll_worktree_status();
+                let agent_status = self.poll_agent_status();
+                let approvals = self.apply_permission_policy();
+                self.update_motion();
+                let glide = self.step_transcript_scroll();
+                let renders = self.request_plugin_renders();
+                let panels = self.refresh_plugin_panels();
+                
+                if state::snapshot_due(&self.state) {
+                    update(&mut self.state, Action::SaveSnapshot);
+                }
+                
+                // Auto-clear notifications once they time out
+                if state::notification_expired(&self.state) {
+                    update(&mut self.state, Action::Editor(EditorAction::ClearNotification));
+                }
+                
+                // Still need to redraw for spinner animation
+                if let Some(conv_idx) = self.state.active_conversation {
+                    if let Some(conv) = self.state.conversations.get(conv_idx) {
+                        let has_processing = conv.messages.iter().any(|m| m.status == MessageStatus::Processing);
+                        if has_processing {
+                            // The subscription will handle the next tick
+                        }
+                    }
+                }
+                
+                Command::batch([interrupts, worktree_status, agent_status, appearance, approvals, glide, renders, panels])
+            }
+            
+            Message::Editor(action) => {
+                update(&mut self.state, Action::Editor(action));
+                Command::none()
+            }
+            
+            Message::CloseRequested => {
+                // The snapshot is written as the effects run, before the exit task does
+                update(&mut self.state, Action::SaveSnapshot);
+                iced::exit()
+            }
+            
+            Message::ApproveToolUse => {
+                self.answer_permission(true)
+            }
+            
+            Message::AlwaysAllowTool { in_worktree } => {
+                if let Some(rule) = self.pending_allow_rule(in_worktree) {
+                    update(&mut self.state, Action::AddPermissionRule(permission_policy::Decision::Allow, rule));
+                    self.save_permission_policy();
+                }
+                // Approving through the policy keeps the next tick from answering again
+                self.apply_permission_policy()
+            }
+            
+            Message::TogglePermissionRules => {
+                update(&mut self.state, Action::TogglePermissionRules);
+                Command::none()
+            }
+            
+            Message::TogglePermissionAudit => {
+                update(&mut self.state, Action::TogglePermissionAudit);
+                Command::none()
+            }
+            
+            Message::ToggleActionLog => {
+                update(&mut self.state, Action::ToggleActionLog);
+                Command::none()
+            }
+            
+            Message::StepActionLogBack => {
+                update(&mut self.state, Action::StepActionLogBack);
+                Command::none()
+            }
+            
+            Message::StepActionLogForward => {
+                update(&mut self.state, Action::StepActionLogForward);
+                Command::none()
+            }
+            
+            Message::RuleDraftChanged(field, value) => {
+                update(&mut self.state, Action::UpdateRuleDraft(field, value));
+                Command::none()
+            }
+            
+            Message::RuleDraftDecision(decision) => {
+                update(&mut self.state, Action::SetRuleDraftDecision(decision));
+                Command::none()
+            }
+            
+            Message::AddPermissionRule => {
+                let draft = &self.state.rule_draft;
+                match draft.build() {
+                    Ok(rule) => {
+                        let decision = draft.decision;
+                        update(&mut self.state, Action::AddPermissionRule(decision, rule));
+                        update(&mut self.state, Action::UpdateRuleDraft(permission_policy::RuleField::Tool, String::new()));
+                        update(&mut self.state, Action::UpdateRuleDraft(permission_policy::RuleField::Paths, String::new()));
+                        update(&mut self.state, Action::UpdateRuleDraft(permission_policy::RuleField::Command, String::new()));
+                        self.save_permission_policy();
+                        // A new rule may already answer something that's waiting
+                        self.apply_permission_policy()
+                    }
+                    Err(e) => {
+                        update(&mut self.state, Action::RuleDraftFailed(e));
+                        Command::none()
+                    }
+                }
+            }
+            
+            Message::RemovePermissionRule(decision, idx) => {
+                update(&mut self.state, Action::RemovePermissionRule(decision, idx));
+                self.save_permission_policy();
+                Command::none()
+            }
+            
+            Message::DenyToolUse => {
+                self.answer_permission(false)
+            }
+            
+            Message::PermissionResponseSent(interaction_id, result) => self.permission_response_sent(interaction_id, result),
+            
+            Message::RetryPermissionResponse(interaction_id) => self.retry_permission_response(interaction_id),
+            
+            Message::Events(batch) => {
+                update(&mut self.state, Action::EventsBacklogged(batch.remaining));
+                Command::batch(batch.events.into_iter().map(|event| self.handle_event(event)).collect::<Vec<_>>())
+            }
+            
+            Message::InteractionFetched(server, id, result) => {
+                match result {
+                    Ok(conversation) => {
+                        let applied = self.apply_interaction(server, id, conversation);
+                        Command::batch([applied, self.load_images()])
+                    }
+                    Err(e) => {
+                        eprintln!("[GUI] Failed to fetch interaction {} from {}: {}", id, server, e);
+                        Command::none()
+                    }
+                }
+            }
+            
+            Message::LoadOlderHistory => self.load_older_history(),
+            
+            Message::HistoryLoaded(server, id, result) => self.history_loaded(server, id, result),
+            
+            Message::WorktreesLoaded(result) => {
+                match result {
+                    Ok(worktrees) => {
+                        update(&mut self.state, Action::WorktreesLoaded(worktrees));
+                    }
+                    Err(err) => update(&mut self.state, Action::Error(err.to_string())),
+                }
+                Command::none()
+            }
+            
+            Message::SystemAppearance(appearance) => {
+                update(&mut self.state, Action::SystemAppearanceDetected(appearance));
+                self.reload_theme(true);
+                Command::none()
+            }
+            
+            Message::WorktreeStatusesLoaded(result) => {
+                match result {
+                    Ok(statuses) => update(&mut self.state, Action::Editor(EditorAction::WorktreeStatusesLoaded { statuses })),
+                    Err(err) => eprintln!("[GUI] Failed to load worktree statuses: {}", err),
+                }
+                Command::none()
+            }
+            
+            Message::WorktreeFilesLoaded(result) => {
+                match result {
+                    Ok(files) => update(&mut self.state, Action::Editor(EditorAction::WorktreeFilesLoaded { files })),
+                    Err(err) => eprintln!("[GUI] Failed to load worktree files: {}", err),
+                }
+                Command::none()
+            }
+            
+            Message::SelectWorktree(id) => {
+                self.tasks.switch_conversation();
+                update(&mut self.state, Action::SelectWorktree(id));
+                Command::none()
+            }
+            
+            Message::OpenWorktreeDialog => {
+                update(&mut self.state, Action::OpenWorktreeDialog);
+                Command::none()
+            }
+            
+            Message::CloseWorktreeDialog => {
+                update(&mut self.state, Action::CloseWorktreeDialog);
+                Command::none()
+            }
+            
+            Message::WorktreeBranchNameChanged(name) => {
+                update(&mut self.state, Action::UpdateWorktreeBranchName(name));
+                Command::none()
+            }
+            
+            Message::WorktreeBaseBranchChanged(branch) => {
+                update(&mut self.state, Action::UpdateWorktreeBaseBranch(branch));
+                Command::none()
+            }
+            
+            Message::WorktreeCustomPathChanged(path) => {
+                update(&mut self.state, Action::UpdateWorktreeCustomPath(path));
+                Command::none()
+            }
+            
+            Message::CreateWorktree => {
+                if let Some(dialog) = &self.state.worktree_dialog {
+                    if dialog.branch_name.is_empty() {
+                        update(&mut self.state, Action::WorktreeCreationFailed("Branch name is required".to_string()));
+                        return Command::none();
+                    }
+                    
+                    let api = self.api_client.clone();
+                    let request = CreateWorktreeRequest {
+                        branch: dialog.branch_name.clone(),
+                        base_branch: Some(dialog.base_branch.clone()),
+                        path: if dialog.custom_path.is_empty() { None } else { Some(dialog.custom_path.clone()) },
+                    };
+                    
+                    self.tasks.run(
+                        "create worktree",
+                        async move { api.create_worktree(request).await },
+                        Message::WorktreeCreated
+                    )
+                } else {
+                    Command::none()
+                }
+            }
+            
+            Message::WindowFocusChanged(focused) => {
+                update(&mut self.state, Action::WindowFocusChanged(focused));
+                Command::none()
+            }
+            
+            Message::FilesHovered(hovering) => {
+                update(&mut self.state, Action::SetDropHover(hovering));
+                Command::none()
+            }
+            
+            Message::FileDropped(path) => self.file_dropped(path),
+            
+            Message::KeyPressed { key, modifiers, captured } => {
+                // Arrow keys move through the palette while it's open
+                if self.state.command_palette.is_some() {
+                    match key {
+                        keyboard::Key::Named(keyboard::key::Named::ArrowDown) => {
+                            update(&mut self.state, Action::SelectNextCommand);
+                            return Command::none();
+                        }
+                        keyboard::Key::Named(keyboard::key::Named::ArrowUp) => {
+                            update(&mut self.state, Action::SelectPreviousCommand);
+                            return Command::none();
+                        }
+                        _ => {}
+                    }
+                }
+                
+                // Arrow keys step through the slides while presenting
+                if let Some(current) = self.state.presentation.filter(|_| self.state.command_palette.is_none()) {
+                    let count = self.state.active_conversation
+                        .and_then(|idx| self.state.conversations.get(idx))
+                        .map_or(0, |conv| presentation::slides(conv).len());
+                    if let Some(slide) = presentation::slide_for(&key, current, count).filter(|_| modifiers.is_empty()) {
+                        update(&mut self.state, Action::GoToSlide(slide));
+                        return Command::none();
+                    }
+                }
+                
+                match self.keymap.resolve(&key, modifiers, captured) {
+                    Some(command) => self.handle_key_command(command),
+                    // Unbound paging keys move the transcript unless something focused used them
+                    None => match scrolling::Jump::from_key(&key) {
+                        Some(jump) if !captured && modifiers.is_empty() => self.jump_transcript(jump),
+                        _ => Command::none(),
+                    },
+                }
+            }
+            
+            Message::CycleLabel(idx) => {
+                let Some(conv) = self.state.conversations.get(idx) else {
+                    return Command::none();
+                };
+                let label = labels::ColorLabel::next(state::label_of(&self.state, conv));
+                self.set_label(idx, label);
+                Command::none()
+            }
+            
+            Message::FilterByLabel(label) => {
+                update(&mut self.state, Action::FilterByLabel(label));
+                Command::none()
+            }
+            
+            Message::FilterByType(interaction_type) => {
+                update(&mut self.state, Action::FilterByType(interaction_type));
+                Command::none()
+            }
+            
+            Message::StartRename(idx) => {
+                update(&mut self.state, Action::StartRename(idx));
+                Command::batch([
+                    iced::widget::text_input::focus(rename_input_id()),
+                    iced::widget::text_input::select_all(rename_input_id()),
+                ])
+            }
+            
+            Message::RenameChanged(title) => {
+                update(&mut self.state, Action::RenameChanged(title));
+                Command::none()
+            }
+            
+            Message::CommitRename => {
+                update(&mut self.state, Action::CommitRename);
+                Command::none()
+            }
+            
+            Message::ToggleCommandPalette => {
+                if self.state.command_palette.is_some() {
+                    update(&mut self.state, Action::CloseCommandPalette);
+                    Command::none()
                 } else {
-                    theme::channel_button_inactive(theme)
+                    update(&mut self.state, Action::OpenCommandPalette);
+                    iced::widget::text_input::focus(palette_input_id())
+                }
+            }
+            
+            Message::PaletteQueryChanged(query) => {
+                update(&mut self.state, Action::UpdatePaletteQuery(query));
+                Command::none()
+            }
+            
+            Message::PaletteSubmit => {
+                let selected = self.state.command_palette.as_ref().and_then(|p| {
+                    palette::filter(&self.state, &p.query).into_iter().nth(p.selected)
+                });
+                match selected {
+                    Some(entry) => self.update(Message::ExecutePaletteCommand(entry.command)),
+                    None => Command::none(),
+                }
+            }
+            
+            Message::ExecutePaletteCommand(command) => {
+                update(&mut self.state, Action::CloseCommandPalette);
+                self.execute_palette_command(command)
+            }
+            
+            Message::WorktreeCreated(result) => {
+                match result {
+                    Ok(worktree) => {
+                        let payload = hooks::worktree_payload(&self.state.active_server, &worktree);
+                        update(&mut self.state, Action::WorktreeCreated(worktree));
+                        self.run_hooks(config::HookEvent::WorktreeCreated, payload, None)
+                    }
+                    Err(err) => {
+                        update(&mut self.state, Action::WorktreeCreationFailed(err.to_string()));
+                        Command::none()
+                    }
+                }
+            }
+        }
+    }
+
+    fn execute_palette_command(&mut self, command: palette::PaletteCommand) -> Command<Message> {
+        use palette::PaletteCommand;
+        
+        match command {
+            PaletteCommand::NewConversation => self.update(Message::NewConversation),
+            PaletteCommand::SwitchConversation(idx) => self.update(Message::SelectConversation(idx)),
+            PaletteCommand::SwitchServer(server) => self.update(Message::SwitchServer(server)),
+            PaletteCommand::SwitchWorktree(id) => self.update(Message::SelectWorktree(id)),
+            PaletteCommand::CreateWorktree => self.update(Message::OpenWorktreeDialog),
+            PaletteCommand::ExportConversation => {
+                update(&mut self.state, Action::OpenExportDialog);
+                Command::none()
+            }
+            PaletteCommand::ToggleMute(idx) => self.update(Message::ToggleMute(idx)),
+            PaletteCommand::ManageAgents(idx) => self.update(Message::OpenAgentSelector(idx)),
+            PaletteCommand::SetTokenBudget(conversation_idx, budget) => {
+                update(&mut self.state, Action::SetTokenBudget { conversation_idx, budget });
+                Command::none()
+            }
+            PaletteCommand::ToggleMinimap => self.update(Message::ToggleMinimap),
+            PaletteCommand::ToggleFocusMode => self.update(Message::ToggleFocusMode),
+            PaletteCommand::StartPresentation => self.update(Message::StartPresentation),
+            PaletteCommand::ShowArtifacts(show) => self.update(Message::ShowArtifacts(show)),
+            PaletteCommand::ToggleJobs => self.update(Message::ToggleJobsPanel),
+            PaletteCommand::ToggleAgentHealth => self.update(Message::ToggleAgentHealth),
+            PaletteCommand::ShowPins(expanded) => self.update(Message::SetPinsExpanded(expanded)),
+            PaletteCommand::AttachFile => self.open_file_picker(),
+            PaletteCommand::ToggleDebugOverlay => {
+                update(&mut self.state, Action::ToggleDebugOverlay);
+                Command::none()
+            }
+            PaletteCommand::ManageWorktrees => self.update(Message::ToggleWorktreePanel),
+            PaletteCommand::ShowDiff(worktree_id) => self.update(Message::OpenDiff(worktree_id)),
+            PaletteCommand::ArchiveConversation(idx) => self.update(Message::ArchiveConversation(idx)),
+            PaletteCommand::UnarchiveConversation(idx) => self.update(Message::UnarchiveConversation(idx)),
+            PaletteCommand::DeleteConversation(idx) => self.update(Message::ConfirmDeleteConversation(idx)),
+            PaletteCommand::SetAppearance(mode) => {
+                update(&mut self.state, Action::SetAppearance(mode));
+                self.reload_theme(false);
+                Command::none()
+            }
+            PaletteCommand::SetPaletteVariant(variant) => {
+                update(&mut self.state, Action::SetPaletteVariant(variant));
+                self.reload_theme(false);
+                Command::none()
+            }
+            PaletteCommand::ManagePermissionRules => self.update(Message::TogglePermissionRules),
+            PaletteCommand::ShowPermissionAudit => self.update(Message::TogglePermissionAudit),
+            PaletteCommand::ShowActionLog => self.update(Message::ToggleActionLog),
+            PaletteCommand::ForgetPermissionRules => {
+                update(&mut self.state, Action::ClearPermissionPolicy);
+                self.save_permission_policy();
+                Command::none()
+            }
+            PaletteCommand::OpenSettings => {
+                if let Err(e) = config::open_settings() {
+                    update(&mut self.state, Action::ShowNotification(
+                        state::Notification::Error(format!("Couldn't open settings: {}", e))
+                    ));
+                }
+                Command::none()
+            }
+            PaletteCommand::RestoreBackup(date) => {
+                self.restore_backup(date);
+                Command::none()
+            }
+            PaletteCommand::ImportTranscripts => self.import_transcripts(),
+            PaletteCommand::GenerateDigest { narrate } => self.generate_digest(narrate),
+            PaletteCommand::Plugin { plugin, command } => self.run_plugin_command(plugin, command),
+        }
+    }
+    
+    // The conversations here aren't the core's interactions, so commands go through the
+    // GUI's messages rather than `KeyCommand::action`, focus and draft still reach the core.
+    // Numbers count down the sidebar, whatever order `conversations` is in
+    fn handle_key_command(&mut self, command: keymap::KeyCommand) -> Command<Message> {
+        use keymap::KeyCommand;
+        
+        match command {
+            KeyCommand::NewConversation => self.update(Message::NewConversation),
+            KeyCommand::SwitchConversation(n) => {
+                match n.checked_sub(1).and_then(|i| state::sidebar_order(&self.state).get(i).copied()) {
+                    Some(idx) => self.update(Message::SelectConversation(idx)),
+                    None => Command::none(),
+                }
+            }
+            KeyCommand::Send => self.update(Message::SendMessage),
+            KeyCommand::CommandPalette => self.update(Message::ToggleCommandPalette),
+            KeyCommand::FocusMode => self.update(Message::ToggleFocusMode),
+            KeyCommand::Interrupt => {
+                // Escape backs out of open overlays first
+                if self.state.command_palette.is_some() {
+                    update(&mut self.state, Action::CloseCommandPalette);
+                } else if self.state.renaming.is_some() {
+                    update(&mut self.state, Action::CancelRename);
+                } else if self.state.mention.is_some() {
+                    update(&mut self.state, Action::MentionChanged(None));
+                } else if self.state.editing.is_some() {
+                    update(&mut self.state, Action::CancelEdit);
+                    self.composer = text_editor::Content::new();
+                } else if self.state.expanded_template.is_some() {
+                    update(&mut self.state, Action::DismissTemplate);
+                    multiline_input::set_text(&mut self.composer, &self.state.editor.draft.content);
+                } else if self.state.auth_prompt.is_some() {
+                    update(&mut self.state, Action::CloseAuthPrompt);
+                } else if self.state.export_dialog.is_some() {
+                    update(&mut self.state, Action::CloseExportDialog);
+                } else if self.state.artifact_save.as_ref().is_some_and(|dialog| !dialog.saving) {
+                    update(&mut self.state, Action::CloseSaveArtifact);
+                } else if self.state.expanded_image.is_some() {
+                    update(&mut self.state, Action::ExpandImage(None));
+                } else if self.state.file_picker.is_some() {
+                    update(&mut self.state, Action::CloseFilePicker);
+                } else if self.state.agent_config.as_ref().is_some_and(|draft| !draft.saving) {
+                    update(&mut self.state, Action::CloseAgentConfig);
+                } else if self.state.agent_selector.is_some() {
+                    update(&mut self.state, Action::CloseAgentSelector);
+                } else if self.state.merge_dialog.as_ref().is_some_and(|dialog| !dialog.in_progress) {
+                    update(&mut self.state, Action::CloseMergeDialog);
+                } else if self.state.worktree_dialog.is_some() {
+                    update(&mut self.state, Action::CloseWorktreeDialog);
+                } else if self.state.diff_view.is_some() {
+                    update(&mut self.state, Action::CloseDiff);
+                } else if self.state.presentation.is_some() {
+                    update(&mut self.state, Action::EndPresentation);
+                } else if self.state.focus_mode {
+                    update(&mut self.state, Action::ToggleFocusMode);
+                } else if let Some(conv) = self.state.active_conversation.and_then(|idx| self.state.conversations.get(idx)) {
+                    // Nothing left to back out of, so stop Wake if it's working here
+                    if jobs::is_running(conv) {
+                        return self.update(Message::InterruptInteraction(conv.id.clone()));
+                    }
                 }
+                Command::none()
+            }
+        }
+    }
+    
+    // Stop anything over the safety limits, once per job
+    fn interrupt_runaway_jobs(&mut self) -> Command<Message> {
+        let runaways: Vec<(String, String, String)> = jobs::running_jobs(&self.state, self.state.clock.instant())
+            .into_iter()
+            .filter(|job| !self.state.auto_interrupted.contains(job.interaction_id))
+            .filter_map(|job| {
+                let reason = jobs::exceeded_limit(&job, &self.state.settings.safety)?;
+                Some((job.interaction_id.to_string(), job.title.to_string(), reason))
             })
-            .width(Length::Fill);
+            .collect();
+        
+        let mut commands = Vec::new();
+        for (interaction_id, title, reason) in runaways {
+            println!("[GUI] Auto-interrupting {} ({})", interaction_id, reason);
+            update(&mut self.state, Action::AutoInterrupted(interaction_id.clone()));
+            update(&mut self.state, Action::ShowNotification(
+                state::Notification::Error(format!("Interrupted #{}: it {}", title, reason))
+            ));
+            commands.push(self.update(Message::InterruptInteraction(interaction_id)));
+        }
+        Command::batch(commands)
+    }
+    
+    fn load_worktree_files(&self) -> Command<Message> {
+        let root = self.state.editor.current_worktree
+            .as_ref()
+            .map(|w| std::path::PathBuf::from(&w.path))
+            .unwrap_or_else(|| std::path::PathBuf::from("."));
+        
+        // Candidates for one worktree are wrong for the next
+        self.tasks.perform(
+            "list worktree files",
+            self.tasks.conversation(),
+            async move {
+                tokio::task::spawn_blocking(move || mentions::list_files(&root))
+                    .await
+                    .map_err(|e| e.to_string())?
+            },
+            Message::WorktreeFilesLoaded
+        )
+    }
+    
+    fn subscription(&self) -> Subscription<Message> {
+        Subscription::batch([
+            // Poll every 16ms for 60fps updates
+            time::every(Duration::from_millis(16)).map(|_| Message::Tick),
+            // SSE updates and desktop notification clicks, in publish order
+            self.inbox.subscription().map(Message::Events),
+            // Track focus so we only raise desktop notifications when the user is away,
+            // and route key presses through the keymap
+            event::listen_with(|event, status, _window| match event {
+                Event::Window(window::Event::Focused) => Some(Message::WindowFocusChanged(true)),
+                Event::Window(window::Event::Unfocused) => Some(Message::WindowFocusChanged(false)),
+                Event::Window(window::Event::CloseRequested) => Some(Message::CloseRequested),
+                Event::Window(window::Event::FileHovered(_)) => Some(Message::FilesHovered(true)),
+                Event::Window(window::Event::FilesHoveredLeft) => Some(Message::FilesHovered(false)),
+                Event::Window(window::Event::FileDropped(path)) => Some(Message::FileDropped(path)),
+                Event::Keyboard(keyboard::Event::KeyPressed { key, modifiers, .. }) => Some(Message::KeyPressed {
+                    key,
+                    modifiers,
+                    captured: status == event::Status::Captured,
+                }),
+                Event::Mouse(iced::mouse::Event::WheelScrolled { delta }) => Some(Message::WheelScrolled(delta)),
+                _ => None,
+            }),
+        ])
+    }
+    
+    fn build_channel_list(&self) -> Element<Message> {
+        let mut channel_list = column![
+            // Header
+            container(
+                row![
+                    text("Interactions").size(14).font(fonts::bold()),
+                    button(text("+").size(14).font(fonts::mono()))
+                        .on_press(Message::NewConversation)
+                        .padding(6)
+                        .style(theme::add_button),
+                    Space::with_width(Length::Fill),
+                    button(text(format!("Jobs {}", self.state.job_started.len())).size(12).font(fonts::mono()))
+                        .on_press(Message::ToggleJobsPanel)
+                        .padding(6)
+                        .style(theme::add_button),
+                ]
+                .spacing(10)
+                .align_y(iced::Alignment::Center)
+            )
+            .padding(16)
+            .width(Length::Fill),
+        ]
+        .push_maybe(self.build_server_switcher())
+        .push_maybe(self.build_session_switcher())
+        .push_maybe(self.build_type_tabs())
+        .push_maybe(self.build_label_filter())
+        .spacing(4);
+        
+        // Channel entries, archived ones live in their own section at the bottom
+        let shown = |conv: &&state::Conversation| state::listed_in_sidebar(&self.state, conv);
+        for (idx, conv) in self.state.conversations.iter().enumerate().filter(|(_, conv)| shown(conv)) {
+            if !conv.archived {
+                channel_list = channel_list.push(self.build_channel_entry(idx, conv));
+            }
+        }
+        
+        let archived_count = self.state.conversations.iter().filter(shown).filter(|c| c.archived).count();
+        if archived_count > 0 {
+            let arrow = if self.state.show_archived { "▾" } else { "▸" };
+            channel_list = channel_list.push(
+                button(
+                    text(format!("{} Archived ({})", arrow, archived_count))
+                        .size(12)
+                        .font(fonts::mono())
+                )
+                .on_press(Message::ToggleArchived)
+                .padding([8, 16])
+                .style(theme::icon_button)
+            );
             
-            channel_list = channel_list.push(channel_entry);
+            if self.state.show_archived {
+                for (idx, conv) in self.state.conversations.iter().enumerate().filter(|(_, conv)| shown(conv)) {
+                    if conv.archived {
+                        channel_list = channel_list.push(self.build_channel_entry(idx, conv));
+                    }
+                }
+            }
         }
+        channel_list = channel_list.push_maybe(self.build_plugin_panels());
         
         // Sidebar container
         container(
             scrollable(channel_list)
+                .direction(scrolling::scrollbar())
+                .style(theme::scrollbar)
                 .width(Length::Fill)
                 .height(Length::Fill)
         )
@@ -711,6 +2418,182 @@ impl BickyApp {
         .into()
     }
 
+    fn build_channel_entry<'a>(&'a self, idx: usize, conv: &'a state::Conversation) -> Element<'a, Message> {
+        let is_active = self.state.active_conversation == Some(idx);
+        
+        // The dot marks which model answered last
+        let marker = match models::latest_model(conv) {
+            Some(model) => {
+                let badge = models::badge(model, &self.state.settings.models);
+                text(badge.icon).size(13).font(fonts::mono()).color(badge.color)
+            }
+            None => text("#").size(13).font(fonts::mono()).color(theme::colors().text_dim),
+        };
+        
+        let renaming = self.state.renaming.as_ref().filter(|r| r.conversation_idx == idx);
+        let title: Element<'a, Message> = match renaming {
+            Some(renaming) => iced::widget::text_input("Conversation title", &renaming.title)
+                .id(rename_input_id())
+                .on_input(Message::RenameChanged)
+                .on_submit(Message::CommitRename)
+                .padding(2)
+                .font(fonts::mono())
+                .size(14)
+                .style(theme::input_style)
+                .into(),
+            None => text(&conv.title).size(14).font(fonts::mono()).into(),
+        };
+        
+        let mut title_row = row![marker, title]
+            .push_maybe(renaming.is_none().then(|| Space::with_width(Length::Fill)))
+        .spacing(6)
+        .align_y(iced::Alignment::Center);
+        
+        // Muted conversations always show it, the active one offers to mute
+        if conv.muted || is_active {
+            let icon = if conv.muted { "🔕" } else { "🔔" };
+            title_row = title_row.push(
+                button(text(icon).size(12))
+                    .on_press(Message::ToggleMute(idx))
+                    .padding(0)
+                    .style(theme::icon_button)
+            );
+        }
+        
+        let label = state::label_of(&self.state, conv);
+        if is_active && renaming.is_none() {
+            let (icon, color) = match label {
+                Some(label) => ("●", label.color()),
+                None => ("○", theme::colors().text_dim),
+            };
+            title_row = title_row.push(
+                button(text(icon).size(12).color(color))
+                    .on_press(Message::CycleLabel(idx))
+                    .padding(0)
+                    .style(theme::icon_button)
+            );
+            title_row = title_row.push(
+                button(text("✎").size(12))
+                    .on_press(Message::StartRename(idx))
+                    .padding(0)
+                    .style(theme::icon_button)
+            );
+        }
+        
+        if is_active {
+            let (icon, archive) = if conv.archived {
+                ("📤", Message::UnarchiveConversation(idx))
+            } else {
+                ("📦", Message::ArchiveConversation(idx))
+            };
+            title_row = title_row.push(
+                button(text(icon).size(12))
+                    .on_press(archive)
+                    .padding(0)
+                    .style(theme::icon_button)
+            );
+            title_row = title_row.push(
+                button(text("🗑").size(12))
+                    .on_press(Message::ConfirmDeleteConversation(idx))
+                    .padding(0)
+                    .style(theme::icon_button)
+            );
+        }
+        
+        let mut entry_content = column![title_row]
+        .spacing(2);
+        
+        if self.state.confirm_delete_conversation.as_deref() == Some(conv.id.as_str()) {
+            entry_content = entry_content.push(
+                row![
+                    text("Delete for good?").size(11).font(fonts::mono()).color(theme::colors().error),
+                    button(text("Delete").size(11).font(fonts::mono()))
+                        .on_press(Message::DeleteConversation(idx))
+                        .padding(0)
+                        .style(theme::icon_button),
+                    button(text("Cancel").size(11).font(fonts::mono()))
+                        .on_press(Message::CancelDeleteConversation)
+                        .padding(0)
+                        .style(theme::icon_button),
+                ]
+                .spacing(8)
+            );
+        }
+        
+        // The active conversation's tags can be removed and added to
+        if let Some(tags) = state::tags_of(&self.state, conv).filter(|tags| is_active || !tags.is_empty()) {
+            let mut tag_row = row![Space::with_width(19)]
+                .spacing(6)
+                .align_y(iced::Alignment::Center);
+            for tag in tags {
+                let chip = text(format!("🏷️ {}", tag)).size(11).font(fonts::mono()).color(theme::colors().text_dim);
+                tag_row = if is_active {
+                    tag_row.push(
+                        button(row![chip, text("×").size(11).font(fonts::mono())].spacing(2))
+                            .on_press(Message::RemoveTag(idx, tag.to_string()))
+                            .padding(0)
+                            .style(theme::icon_button)
+                    )
+                } else {
+                    tag_row.push(chip)
+                };
+            }
+            if is_active {
+                tag_row = tag_row.push(
+                    iced::widget::text_input("+ tag", &self.state.tag_draft)
+                        .on_input(Message::TagDraftChanged)
+                        .on_submit(Message::AddDraftedTag(idx))
+                        .padding(2)
+                        .size(11)
+                        .font(fonts::mono())
+                        .style(theme::input_style)
+                        .width(Length::Fixed(72.0))
+                );
+            }
+            entry_content = entry_content.push(tag_row.wrap());
+        }
+        
+        // Show which worktree the conversation is bound to
+        if let Some(worktree_id) = &conv.worktree_id {
+            // Extract just the branch name from the path, the id will do until worktrees load
+            let branch_name = self.state.editor.available_worktrees
+                .iter()
+                .find(|w| &w.id == worktree_id)
+                .and_then(|w| w.path.split('/').last())
+                .unwrap_or(worktree_id);
+            
+            entry_content = entry_content.push(
+                row![
+                    Space::with_width(19), // Indent to align with title
+                    text(format!("🌿 {}", branch_name))
+                        .size(11)
+                        .font(fonts::mono())
+                        .color(if is_active { theme::colors().success } else { theme::colors().text_dim }),
+                    Space::with_width(8),
+                    self.worktree_badges(worktree_id, 11),
+                ]
+            );
+        }
+        
+        let channel_entry = button(labels::striped(
+            container(entry_content)
+                .padding(12)
+                .width(Length::Fill),
+            label,
+        ))
+        .on_press(Message::SelectConversation(idx))
+        .style(move |theme, _| {
+            if is_active {
+                theme::channel_button_active(theme)
+            } else {
+                theme::channel_button_inactive(theme)
+            }
+        })
+        .width(Length::Fill);
+        
+        channel_entry.into()
+    }
+    
     fn view(&self) -> Element<Message> {
         // Build channel list (sidebar)
         let channel_list = self.build_channel_list();
@@ -728,9 +2611,9 @@ impl BickyApp {
             .align_y(iced::Alignment::Center);
             
             // Add worktree display and quick switcher
-            if !self.state.available_worktrees.is_empty() {
+            if !self.state.editor.available_worktrees.is_empty() {
                 // Current worktree display
-                if let Some(worktree) = &self.state.current_worktree {
+                if let Some(worktree) = &self.state.editor.current_worktree {
                     let branch_name = worktree.branch
                         .as_ref()
                         .and_then(|b| b.strip_prefix("refs/heads/"))
@@ -739,12 +2622,13 @@ impl BickyApp {
                     header_content = header_content.push(
                         text(format!("Working in: {}", branch_name))
                             .size(13)
-                            .font(fonts::BERKELEY_MONO)
-                            .color(theme::Colors::TEXT)
+                            .font(fonts::mono())
+                            .color(theme::colors().text)
                     );
+                    header_content = header_content.push(self.worktree_badges(&worktree.id, 12));
                     
                     // Show other available worktrees as quick switch buttons
-                    let other_worktrees: Vec<_> = self.state.available_worktrees
+                    let other_worktrees: Vec<_> = self.state.editor.available_worktrees
                         .iter()
                         .filter(|w| w.id != worktree.id)
                         .collect();
@@ -753,8 +2637,8 @@ impl BickyApp {
                         header_content = header_content.push(
                             text("Switch to:")
                                 .size(12)
-                                .font(fonts::BERKELEY_MONO)
-                                .color(theme::Colors::TEXT_DIM)
+                                .font(fonts::mono())
+                                .color(theme::colors().text_dim)
                         );
                         
                         for other in other_worktrees.iter().take(3) {
@@ -764,7 +2648,7 @@ impl BickyApp {
                                 .unwrap_or("unknown");
                             
                             header_content = header_content.push(
-                                button(text(branch_name).size(12).font(fonts::BERKELEY_MONO))
+                                button(text(branch_name).size(12).font(fonts::mono()))
                                     .on_press(Message::SelectWorktree(other.id.clone()))
                                     .padding(6)
                                     .style(theme::secondary_button)
@@ -775,8 +2659,8 @@ impl BickyApp {
                             header_content = header_content.push(
                                 text(format!("(+{} more)", other_worktrees.len() - 3))
                                     .size(11)
-                                    .font(fonts::BERKELEY_MONO)
-                                    .color(theme::Colors::TEXT_DIM)
+                                    .font(fonts::mono())
+                                    .color(theme::colors().text_dim)
                             );
                         }
                     }
@@ -785,18 +2669,18 @@ impl BickyApp {
                     header_content = header_content.push(
                         text("Select worktree:")
                             .size(13)
-                            .font(fonts::BERKELEY_MONO)
-                            .color(theme::Colors::TEXT_DIM)
+                            .font(fonts::mono())
+                            .color(theme::colors().text_dim)
                     );
                     
-                    for worktree in self.state.available_worktrees.iter().take(3) {
+                    for worktree in self.state.editor.available_worktrees.iter().take(3) {
                         let branch_name = worktree.branch
                             .as_ref()
                             .and_then(|b| b.strip_prefix("refs/heads/"))
                             .unwrap_or("unknown");
                         
                         header_content = header_content.push(
-                            button(text(branch_name).size(12).font(fonts::BERKELEY_MONO))
+                            button(text(branch_name).size(12).font(fonts::mono()))
                                 .on_press(Message::SelectWorktree(worktree.id.clone()))
                                 .padding(6)
                                 .style(theme::primary_button)
@@ -807,29 +2691,58 @@ impl BickyApp {
                 header_content = header_content.push(
                     text("No worktrees available")
                         .size(13)
-                        .font(fonts::BERKELEY_MONO)
-                        .color(theme::Colors::TEXT_DIM)
+                        .font(fonts::mono())
+                        .color(theme::colors().text_dim)
+                );
+            }
+            
+            // Add diff, manage and create buttons
+            header_content = header_content.push(Space::with_width(Length::Fill));
+            if self.state.events_backlog > 0 {
+                header_content = header_content.push(
+                    text(format!("Catching up… {} events", self.state.events_backlog))
+                        .size(12)
+                        .font(fonts::mono())
+                        .color(theme::colors().text_dim)
+                );
+            }
+            if let Some(chips) = active_conversation.and_then(|conv| self.build_language_chips(conv)) {
+                header_content = header_content.push(chips);
+            }
+            if let Some(badges) = active_conversation.and_then(|conv| self.build_agent_badges(conv)) {
+                header_content = header_content.push(badges);
+            }
+            if let Some(worktree) = &self.state.editor.current_worktree {
+                header_content = header_content.push(
+                    button(text("Diff").size(12).font(fonts::mono()))
+                        .on_press(Message::OpenDiff(worktree.id.clone()))
+                        .padding(6)
+                        .style(theme::secondary_button)
                 );
             }
-            
-            // Add create button
-            header_content = header_content.push(Space::with_width(Length::Fill));
             header_content = header_content.push(
-                button(text("Create Worktree").size(12).font(fonts::BERKELEY_MONO))
+                button(text("Manage").size(12).font(fonts::mono()))
+                    .on_press(Message::ToggleWorktreePanel)
+                    .padding(6)
+                    .style(theme::secondary_button)
+            );
+            header_content = header_content.push(
+                button(text("Create Worktree").size(12).font(fonts::mono()))
                     .on_press(Message::OpenWorktreeDialog)
                     .padding(6)
                     .style(theme::secondary_button)
             );
             
+            // The active conversation's label runs down the left edge
+            let label = active_conversation.and_then(|conv| state::label_of(&self.state, conv));
             Some(
-                container(header_content)
-                    .padding(12)
+                container(labels::striped(container(header_content).padding(12).width(Length::Fill), label))
                     .width(Length::Fill)
                     .style(|_theme| {
                         container::Style {
-                            background: Some(theme::Colors::BACKGROUND_DIM.into()),
+                            background: Some(theme::colors().background_dim.into()),
                             border: iced::Border {
-                                color: theme::Colors::BORDER,
+                                color: theme::colors().border,
                                 width: 1.0,
                                 radius: 4.0.into(),
                             },
@@ -840,55 +2753,171 @@ impl BickyApp {
         };
         
         // Message list - clean and minimal with rich text
+        let now = self.state.clock.utc();
         let messages = if let Some(conv) = active_conversation {
             scrollable(
                 Column::with_children(
-                    conv.messages.iter().map(|msg| {
+                    self.build_history_loader(conv).into_iter().chain(conv.messages.iter().enumerate().map(|(msg_idx, msg)| {
                     let mut message_group = column![].spacing(8);
                     
-                    // User message
-                    let user_label = container(
-                        text("You").size(12).font(fonts::BERKELEY_MONO).color(theme::Colors::TEXT_DIM)
-                    )
-                    .padding(4);
+                    // User message, sent ones can be clicked to edit and resend
+                    let is_sent = msg.status != MessageStatus::Pending;
+                    let is_hovered = is_sent && self.state.hovered_message.as_deref() == Some(msg.id.as_str());
+                    
+                    let mut user_label_row = row![
+                        text("You").size(12).font(fonts::mono()).color(theme::colors().text_dim)
+                    ]
+                    .spacing(8);
+                    if let Some(sent_at) = msg.sent_at {
+                        user_label_row = user_label_row.push(
+                            text(format::locale().timestamp(sent_at, now)).size(11).font(fonts::mono()).color(theme::colors().text_dim)
+                        );
+                    }
+                    let is_editing = self.state.editing.as_ref().is_some_and(|e| {
+                        self.state.active_conversation == Some(e.conversation_idx) && e.message_id == msg.id
+                    });
+                    let is_confirming_delete = self.state.confirm_delete.as_deref() == Some(msg.id.as_str());
+                    let is_bookmarked = conv.bookmarks.contains(&msg.id);
+                    if is_bookmarked && !is_hovered {
+                        user_label_row = user_label_row.push(
+                            text("★").size(11).font(fonts::mono()).color(theme::colors().primary)
+                        );
+                    }
+                    if !is_hovered && conv.pins.contains(&pins::Pin::Message(msg.id.clone())) {
+                        user_label_row = user_label_row.push(
+                            text("📌").size(11).font(fonts::mono()).color(theme::colors().primary)
+                        );
+                    }
+                    if is_confirming_delete {
+                        user_label_row = user_label_row.push(
+                            text("Delete this message?").size(11).font(fonts::mono()).color(theme::colors().error)
+                        );
+                        user_label_row = user_label_row.push(
+                            button(text("Delete").size(11).font(fonts::mono()))
+                                .on_press(Message::DeleteSent(msg.id.clone()))
+                                .padding(0)
+                                .style(theme::icon_button)
+                        );
+                        user_label_row = user_label_row.push(
+                            button(text("Cancel").size(11).font(fonts::mono()))
+                                .on_press(Message::CancelDeleteSent)
+                                .padding(0)
+                                .style(theme::icon_button)
+                        );
+                    } else if is_editing {
+                        user_label_row = user_label_row.push(
+                            text("✎ editing").size(11).font(fonts::mono()).color(theme::colors().spinner)
+                        );
+                    } else if is_hovered {
+                        user_label_row = user_label_row.push(
+                            text("✎ click to edit").size(11).font(fonts::mono()).color(theme::colors().text_dim)
+                        );
+                        user_label_row = user_label_row.push(
+                            button(text(if is_bookmarked { "★ unbookmark" } else { "☆ bookmark" }).size(11).font(fonts::mono()))
+                                .on_press(Message::ToggleBookmark(msg.id.clone()))
+                                .padding(0)
+                                .style(theme::icon_button)
+                        );
+                        let pin = pins::Pin::Message(msg.id.clone());
+                        let is_pinned = conv.pins.contains(&pin);
+                        if let Some(conv_idx) = self.state.active_conversation {
+                            user_label_row = user_label_row.push(
+                                button(text(if is_pinned { "📌 unpin" } else { "📌 pin" }).size(11).font(fonts::mono()))
+                                    .on_press(Message::TogglePin(conv_idx, pin))
+                                    .padding(0)
+                                    .style(theme::icon_button)
+                            );
+                        }
+                        if state::can_delete(msg) {
+                            user_label_row = user_label_row.push(
+                                button(text("🗑 delete").size(11).font(fonts::mono()))
+                                    .on_press(Message::ConfirmDeleteSent(msg.id.clone()))
+                                    .padding(0)
+                                    .style(theme::icon_button)
+                            );
+                        }
+                    }
+                    let user_label = container(user_label_row).padding(4);
                     
+                    // Sends that never reached the server fail inside the user's own bubble
+                    let user_content = column![
+                        text(&msg.content)
+                            .size(14)
+                            .font(fonts::mono())
+                            .color(theme::colors().text)
+                    ]
+                    .spacing(10)
+                    .push_maybe(self.build_message_attachments(&msg.attachments))
+                    .push_maybe(msg.response.is_none().then(|| self.build_error_banner(msg)).flatten());
                     let user_msg = container(
-                        container(
-                            text(&msg.content)
-                                .size(14)
-                                .font(fonts::BERKELEY_MONO)
-                                .color(theme::Colors::TEXT)
-                        )
+                        container(user_content)
                         .padding(12)
                         .width(Length::Fill)
                         .style(|theme| theme::user_message_container(theme))
                     )
                     .width(Length::Fill);
                     
-                    message_group = message_group.push(user_label).push(user_msg);
+                    let user_msg: Element<Message> = if is_sent {
+                        mouse_area(user_msg)
+                            .on_press(Message::EditSent(msg.id.clone()))
+                            .interaction(iced::mouse::Interaction::Pointer)
+                            .into()
+                    } else {
+                        user_msg.into()
+                    };
+                    
+                    message_group = message_group
+                        .push(user_label)
+                        .push(self.slide_in(user_msg, self.entrance(&conv.id, msg_idx, false)));
                     
                     // Assistant response (if available)
                     if let Some(response) = &msg.response {
                         // Build assistant label with metadata
-                        let mut label_text = String::from("Wake");
-                        if let Some(metadata) = &msg.metadata {
-                            if let Some(model) = &metadata.model {
-                                label_text = format!("Wake ({})", model);
-                            }
+                        let mut label_row = row![
+                            text("Wake").size(12).font(fonts::mono()).color(theme::colors().text_dim)
+                        ]
+                        .spacing(6)
+                        .align_y(iced::Alignment::Center);
+                        if let Some(model) = msg.metadata.as_ref().and_then(|metadata| metadata.model.as_deref()) {
+                            let badge = models::badge(model, &self.state.settings.models);
+                            label_row = label_row.push(
+                                text(format!("{} {}", badge.icon, model)).size(12).font(fonts::mono()).color(badge.color)
+                            );
+                        }
+                        if let Some(agent_id) = msg.metadata.as_ref().and_then(|metadata| metadata.agent_id.as_deref()) {
+                            label_row = label_row.push(
+                                text(format!("via {}", agents::name(&self.state, agent_id))).size(12).font(fonts::mono()).color(theme::colors().text_dim)
+                            );
                         }
                         
-                        let assistant_label = container(
-                            text(label_text).size(12).font(fonts::BERKELEY_MONO).color(theme::Colors::TEXT_DIM)
-                        )
-                        .padding(4);
+                        let assistant_label = container(label_row).padding(4);
                         
-                        // Parse and render markdown
-                        let rendered_content = container(
-                            text(response)
-                                .size(14)
-                                .font(fonts::BERKELEY_MONO)
-                                .color(theme::Colors::TEXT)
-                        );
+                        // New responses fade and slide in rather than popping up
+                        let arrival = self.entrance(&conv.id, msg_idx, true);
+                        
+                        // Parse and render markdown, server errors show as the banner instead
+                        let rendered_content = match self.build_error_banner(msg) {
+                            Some(banner) if message_errors::error_detail(msg) == Some(response.as_str()) => container(banner),
+                            Some(banner) => container(
+                                column![
+                                    text(response)
+                                        .size(14)
+                                        .font(fonts::mono())
+                                        .color(theme::colors().text.scale_alpha(arrival)),
+                                    banner,
+                                ]
+                                .spacing(10)
+                            ),
+                            None => match self.state.plugin_renderings.get(&msg.id) {
+                                Some(plugins::Rendering::Blocks(blocks)) => container(plugins::blocks(blocks, arrival)),
+                                _ => container(
+                                    text(response)
+                                        .size(14)
+                                        .font(fonts::mono())
+                                        .color(theme::colors().text.scale_alpha(arrival))
+                                ),
+                            },
+                        };
                         
                         let assistant_msg = container(
                             container(rendered_content)
@@ -898,60 +2927,43 @@ impl BickyApp {
                         )
                         .width(Length::Fill);
                         
-                        message_group = message_group.push(assistant_label).push(assistant_msg);
+                        message_group = message_group.push(assistant_label).push(self.slide_in(assistant_msg, arrival));
+                        message_group = message_group.push_maybe(self.build_message_images(conv, msg));
                         
-                        // Add metadata info if available
+                        // Add metadata info if available, as configured
                         if let Some(metadata) = &msg.metadata {
-                            let mut info_parts = Vec::new();
-                            
-                            if let Some(tokens) = &metadata.tokens {
-                                let formatted_tokens = format_token_count(tokens.total);
-                                info_parts.push(formatted_tokens);
-                            }
-                            
-                            
-                            if let Some(time_ms) = metadata.processing_time_ms {
-                                let seconds = time_ms as f64 / 1000.0;
-                                info_parts.push(format!("{:.1}s", seconds));
-                            }
-                            
-                            // Add tools used
-                            if let Some(tools) = &metadata.tools_used {
-                                if !tools.is_empty() {
-                                    let tool_names = tools.join(", ");
-                                    info_parts.push(format!("🔧 {}", tool_names));
-                                }
-                            }
-                            
-                            // Add worktree context
-                            if let Some(context) = &metadata.worktree_context {
-                                if let Some(branch) = &context.branch {
-                                    let branch_name = branch.strip_prefix("refs/heads/").unwrap_or(branch);
-                                    info_parts.push(format!("🌿 {}", branch_name));
-                                }
-                            }
-                            
-                            // Add tags
-                            if let Some(tags) = &metadata.tags {
-                                if !tags.is_empty() {
-                                    let tag_list = tags.join(", ");
-                                    info_parts.push(format!("🏷️ {}", tag_list));
-                                }
-                            }
+                            let info_parts: Vec<String> = self.state.settings.messages.metadata
+                                .iter()
+                                .filter_map(|chip| metadata_chip(metadata, *chip))
+                                .collect();
                             
                             if !info_parts.is_empty() {
                                 let info_text = info_parts.join(" • ");
                                 let info_label = container(
-                                    text(info_text).size(11).font(fonts::BERKELEY_MONO).color(theme::Colors::TEXT_DIM)
+                                    text(info_text).size(11).font(fonts::mono()).color(theme::colors().text_dim)
                                 )
                                 .padding(4);
                                 message_group = message_group.push(info_label);
                             }
                         }
+                        message_group = message_group.push_maybe(self.build_timings(msg));
                     } else if msg.status == MessageStatus::Processing {
-                        // Show processing indicator
+                        // Show processing indicator, with a way to stop it
+                        let interrupt: Element<Message> = if self.state.interrupting.contains(&conv.id) {
+                            text("interrupting…").size(11).font(fonts::mono()).color(theme::colors().text_dim).into()
+                        } else {
+                            button(text("■ interrupt (esc)").size(11).font(fonts::mono()))
+                                .on_press(Message::InterruptInteraction(conv.id.clone()))
+                                .padding(0)
+                                .style(theme::icon_button)
+                                .into()
+                        };
                         let processing_label = container(
-                            text("Wake").size(12).font(fonts::BERKELEY_MONO).color(theme::Colors::TEXT_DIM)
+                            row![
+                                text("Wake").size(12).font(fonts::mono()).color(theme::colors().text_dim),
+                                interrupt,
+                            ]
+                            .spacing(8)
                         )
                         .padding(4);
                         
@@ -978,19 +2990,19 @@ impl BickyApp {
                                 text(spinner)
                                     .size(14)
                                     .font(fonts::UNICODE_FONT)
-                                    .color(theme::Colors::SPINNER),
+                                    .color(focus::indicator(self.state.focus_mode, theme::colors().spinner)),
                                 text(rest)
                                     .size(14)
-                                    .font(fonts::BERKELEY_MONO)
-                                    .color(theme::Colors::TEXT)
+                                    .font(fonts::mono())
+                                    .color(focus::indicator(self.state.focus_mode, theme::colors().text))
                             ]
                             .spacing(0)
                         } else {
                             row![
                                 text(processing_text.clone())
                                     .size(14)
-                                    .font(fonts::BERKELEY_MONO)
-                                    .color(theme::Colors::TEXT)
+                                    .font(fonts::mono())
+                                    .color(focus::indicator(self.state.focus_mode, theme::colors().text))
                             ]
                         };
                         
@@ -1003,10 +3015,26 @@ impl BickyApp {
                         .width(Length::Fill);
                         
                         message_group = message_group.push(processing_label).push(processing_msg);
+                        
+                        // Nothing heard for a while, say so rather than spin forever
+                        if self.state.stalled.contains(&conv.id) {
+                            let quiet_for = self.state.last_progress.get(&conv.id).map(|last| self.state.clock.since(*last));
+                            message_group = message_group.push(
+                                row![
+                                    text(jobs::stalled_label(quiet_for)).size(12).font(fonts::mono()).color(theme::colors().error),
+                                    button(text("Refetch").size(12).font(fonts::mono()))
+                                        .on_press(Message::RefetchInteraction(conv.id.clone()))
+                                        .padding(6)
+                                        .style(theme::secondary_button),
+                                ]
+                                .spacing(10)
+                                .align_y(iced::Alignment::Center)
+                            );
+                        }
                     } else if msg.status == MessageStatus::WaitingForPermission {
                         // Show tool permission request
                         let permission_label = container(
-                            text("Wake").size(12).font(fonts::BERKELEY_MONO).color(theme::Colors::TEXT_DIM)
+                            text("Wake").size(12).font(fonts::mono()).color(theme::colors().text_dim)
                         )
                         .padding(4);
                         
@@ -1017,25 +3045,48 @@ impl BickyApp {
                                 permission.description
                             );
                             
-                            let permission_content = column![
+                            let mut permission_content = column![
                                 text(permission_text)
                                     .size(14)
-                                    .font(fonts::BERKELEY_MONO)
-                                    .color(theme::Colors::TEXT),
-                                Space::with_height(12),
+                                    .font(fonts::mono())
+                                    .color(theme::colors().text),
+                            ];
+                            
+                            // What exactly the tool will run or change
+                            if let Some(input) = &permission.input {
+                                permission_content = permission_content
+                                    .push(Space::with_height(12))
+                                    .push(self.build_tool_input(input));
+                            }
+                            
+                            permission_content = permission_content.push(Space::with_height(12)).push(
                                 row![
-                                    button(text("Approve").size(13).font(fonts::BERKELEY_MONO))
+                                    button(text("Approve").size(13).font(fonts::mono()))
                                         .on_press(Message::ApproveToolUse)
                                         .padding(8)
                                         .style(theme::primary_button_style),
-                                    Space::with_width(8),
-                                    button(text("Deny").size(13).font(fonts::BERKELEY_MONO))
-                                        .on_press(Message::DenyToolUse)
+                                    button(text(format!("Always allow {}", permission.tool_name)).size(13).font(fonts::mono()))
+                                        .on_press(Message::AlwaysAllowTool { in_worktree: false })
                                         .padding(8)
                                         .style(theme::secondary_button),
                                 ]
+                                .push_maybe(self.worktree_path(conv).map(|_| {
+                                    button(text("Always allow in this worktree").size(13).font(fonts::mono()))
+                                        .on_press(Message::AlwaysAllowTool { in_worktree: true })
+                                        .padding(8)
+                                        .style(theme::secondary_button)
+                                }))
+                                .push(Space::with_width(8))
+                                .push(
+                                    button(text("Deny").size(13).font(fonts::mono()))
+                                        .on_press(Message::DenyToolUse)
+                                        .padding(8)
+                                        .style(theme::secondary_button)
+                                )
                                 .spacing(8)
-                            ];
+                            ).push_maybe(self.build_permission_delivery(conv).map(|delivery| {
+                                column![Space::with_height(12), delivery]
+                            }));
                             
                             let permission_msg = container(
                                 container(permission_content)
@@ -1047,24 +3098,87 @@ impl BickyApp {
                         }
                     }
                     
-                        container(message_group)
-                            .padding(8)
-                            .into()
-                    }).collect::<Vec<_>>()
+                    // Follow-up actions only make sense on the latest message
+                    let is_latest = conv.messages.last().is_some_and(|last| last.id == msg.id);
+                    
+                    // Once Wake is done in a worktree, offer to land the work
+                    if is_latest && msg.status == MessageStatus::Completed {
+                        if let Some(worktree) = conv.worktree_id.as_ref()
+                            .and_then(|id| self.state.editor.available_worktrees.iter().find(|w| &w.id == id))
+                        {
+                            let base = worktree.base_branch.as_deref().unwrap_or("main");
+                            message_group = message_group.push(
+                                row![
+                                    text(format!("Done in {}?", worktrees::branch_label(worktree)))
+                                        .size(12).font(fonts::mono()).color(theme::colors().text_dim),
+                                    button(text("Review diff").size(12).font(fonts::mono()))
+                                        .on_press(Message::OpenDiff(worktree.id.clone()))
+                                        .padding(6)
+                                        .style(theme::secondary_button),
+                                    button(text(format!("Merge into {}", base)).size(12).font(fonts::mono()))
+                                        .on_press(Message::OpenMergeDialog(worktree.id.clone(), state::MergeMode::Merge))
+                                        .padding(6)
+                                        .style(theme::secondary_button),
+                                    button(text("Create PR").size(12).font(fonts::mono()))
+                                        .on_press(Message::OpenMergeDialog(worktree.id.clone(), state::MergeMode::PullRequest))
+                                        .padding(6)
+                                        .style(theme::secondary_button),
+                                ]
+                                .spacing(10)
+                                .align_y(iced::Alignment::Center)
+                            );
+                        }
+                    }
+                    
+                    // Offer to pick up where Wake stopped
+                    if is_latest && resume::is_resumable(msg) {
+                        let reason = match msg.status {
+                            MessageStatus::Cancelled => "Interrupted",
+                            MessageStatus::Failed => "Stopped before finishing",
+                            _ => "Failed before finishing",
+                        };
+                        message_group = message_group.push(
+                            row![
+                                text(reason).size(12).font(fonts::mono()).color(theme::colors().error),
+                                button(text("Resume").size(12).font(fonts::mono()))
+                                    .on_press(Message::ResumeInteraction(msg.id.clone()))
+                                    .padding(6)
+                                    .style(theme::secondary_button),
+                            ]
+                            .spacing(10)
+                            .align_y(iced::Alignment::Center)
+                        );
+                    }
+                    
+                        // Hover covers the whole exchange so the label actions stay reachable
+                        let message_group = container(message_group).padding(8);
+                        if is_sent {
+                            mouse_area(message_group)
+                                .on_enter(Message::HoverSent(Some(msg.id.clone())))
+                                .on_exit(Message::HoverSent(None))
+                                .into()
+                        } else {
+                            message_group.into()
+                        }
+                    })).collect::<Vec<_>>()
                 ).spacing(16)
             )
+            .id(scrolling::transcript_id())
+            .on_scroll(Message::TranscriptScrolled)
+            .direction(scrolling::scrollbar())
+            .style(theme::scrollbar)
             .height(Length::Fill)
         } else {
             // No conversation selected
             scrollable(
                 container(
                     column![
-                        text("No interaction selected").size(16).font(fonts::BERKELEY_MONO).color(theme::Colors::TEXT_DIM),
+                        text("No interaction selected").size(16).font(fonts::mono()).color(theme::colors().text_dim),
                         Space::with_height(8),
                         text("Select an interaction from the sidebar or create a new one")
                             .size(14)
-                            .font(fonts::BERKELEY_MONO)
-                            .color(theme::Colors::TEXT_DIM)
+                            .font(fonts::mono())
+                            .color(theme::colors().text_dim)
                     ]
                     .align_x(iced::Alignment::Center)
                 )
@@ -1072,18 +3186,43 @@ impl BickyApp {
                 .height(Length::Fill)
                 .center_x(Length::Fill).center_y(Length::Fill)
             )
+            .direction(scrolling::scrollbar())
+            .style(theme::scrollbar)
             .height(Length::Fill)
         };
+        // Wheel momentum only applies while the pointer is over the transcript
+        let messages = mouse_area(messages)
+            .on_enter(Message::TranscriptHovered(true))
+            .on_exit(Message::TranscriptHovered(false));
+        let messages: Element<Message> = match active_conversation {
+            Some(conv) if state::minimap_enabled(&self.state) && !self.state.focus_mode && !conv.messages.is_empty() => {
+                row![messages, self.build_minimap(conv)].into()
+            }
+            _ => messages.into(),
+        };
+        // The artifacts tab takes the transcript's place
+        let messages: Element<Message> = match active_conversation {
+            Some(conv) if self.state.artifacts_tab && !self.state.focus_mode => self.build_artifact_list(conv),
+            _ => messages,
+        };
         
         // Input area - clean and focused
         let input_area = container(
-            text_input("Message Wake...", &self.state.input)
-                .on_input(Message::InputChanged)
-                .on_submit(Message::SendMessage)
-                .padding(12)
-                .size(14)
-                .font(fonts::BERKELEY_MONO)
-                .style(theme::input_style)
+            column![
+                self.build_edit_banner(),
+                self.build_route_selector(),
+                self.build_quick_actions(),
+                self.build_attachment_bar(),
+                self.build_mention_completions(),
+                multiline_input::multiline_input_view(
+                    &self.composer_input,
+                    &self.composer,
+                    self.has_mention_completions(),
+                    Message::Composer,
+                ),
+                self.build_token_estimate(),
+            ]
+            .spacing(6)
         )
         .padding(20);
         
@@ -1103,19 +3242,68 @@ impl BickyApp {
                 }
             }
             
-            if total_tokens > 0 || message_count > 0 {
-                let stats_text = format!(
-                    "{} messages • {}",
-                    message_count,
-                    format_token_count(total_tokens)
+            if total_tokens > 0 || message_count > 0 || conv.budget.is_some() {
+                let locale = format::locale();
+                let content = state::conversation_stats(conv);
+                let mut stats_text = format!(
+                    "{} messages • {} • {} words • {} min read",
+                    locale.number(message_count as u64),
+                    locale.token_count(total_tokens),
+                    locale.number(content.words as u64),
+                    locale.number(content.reading_minutes() as u64)
                 );
-                Some(
-                    container(
-                        text(stats_text)
+                if !content.files.is_empty() {
+                    stats_text.push_str(&format!(
+                        " • {} {}",
+                        locale.number(content.files.len() as u64),
+                        if content.files.len() == 1 { "file" } else { "files" }
+                    ));
+                }
+                let mut stats_row = row![
+                    text(stats_text)
+                        .size(12)
+                        .font(fonts::mono())
+                        .color(theme::colors().text_dim)
+                ]
+                .spacing(12);
+                
+                if content.lines_added > 0 || content.lines_removed > 0 {
+                    stats_row = stats_row.push(
+                        text(format!("+{}", locale.number(content.lines_added as u64)))
                             .size(12)
-                            .font(fonts::BERKELEY_MONO)
-                            .color(theme::Colors::TEXT_DIM)
-                    )
+                            .font(fonts::mono())
+                            .color(theme::colors().success)
+                    );
+                    stats_row = stats_row.push(
+                        text(format!("-{}", locale.number(content.lines_removed as u64)))
+                            .size(12)
+                            .font(fonts::mono())
+                            .color(theme::colors().error)
+                    );
+                }
+                
+                if let Some(meter) = self.build_budget_meter(conv) {
+                    stats_row = stats_row.push(meter);
+                }
+                
+                stats_row = stats_row.push_maybe(self.build_language_stats(&content));
+                
+                // Break responses down by model once more than one has answered
+                let by_model = models::responses_by_model(conv);
+                if by_model.len() > 1 {
+                    for (model, count) in by_model {
+                        let badge = models::badge(model, &self.state.settings.models);
+                        stats_row = stats_row.push(
+                            text(format!("{} {} {}", badge.icon, model, format::locale().number(count as u64)))
+                                .size(12)
+                                .font(fonts::mono())
+                                .color(badge.color)
+                        );
+                    }
+                }
+                
+                Some(
+                    container(stats_row)
                     .padding(10)
                     .width(Length::Fill)
                     .center_x(Length::Fill)
@@ -1127,39 +3315,70 @@ impl BickyApp {
             None
         };
         
-        // Main content area
+        // Main content area, focus mode leaves only the transcript and composer
+        let focus_mode = self.state.focus_mode;
         let mut main_content_items = vec![];
         
         // Add worktree header if available
-        if let Some(header) = worktree_header {
+        if let Some(header) = worktree_header.filter(|_| !focus_mode) {
             main_content_items.push(header.into());
         }
         
-        main_content_items.push(messages.into());
-        if let Some(stats) = session_stats {
+        if let Some(strip) = self.state.active_conversation.zip(active_conversation).filter(|_| !focus_mode).and_then(|(idx, conv)| self.build_pin_strip(idx, conv)) {
+            main_content_items.push(strip);
+        }
+        if let Some(timeline) = active_conversation.filter(|_| !focus_mode).and_then(|conv| self.build_timeline(conv)) {
+            main_content_items.push(timeline);
+        }
+        if let Some(tabs) = active_conversation.filter(|_| !focus_mode).and_then(|conv| self.build_conversation_tabs(conv)) {
+            main_content_items.push(tabs);
+        }
+        main_content_items.push(messages);
+        if let Some(stats) = session_stats.filter(|_| !focus_mode) {
             main_content_items.push(stats.into());
         }
         main_content_items.push(input_area.into());
         
         let main_content = Column::with_children(main_content_items);
-        
-        // IRC-style layout with sidebar
-        let layout = row![
-            channel_list,
-            container(main_content)
-                .width(Length::Fill)
-                .height(Length::Fill)
-                .padding(20)
-        ]
-        .width(Length::Fill)
-        .height(Length::Fill);
+        let presenting = self.state.presentation.zip(active_conversation);
+        let layout: Element<Message> = if let Some((slide, conv)) = presenting {
+            presentation::build_slide(conv, slide)
+        } else if focus_mode {
+            self.build_focus_layout(main_content)
+        } else {
+            // IRC-style layout with sidebar
+            let mut layout = row![
+                channel_list,
+                container(main_content)
+                    .width(Length::Fill)
+                    .height(Length::Fill)
+                    .padding(20)
+            ]
+            .width(Length::Fill)
+            .height(Length::Fill);
+            
+            // Panels stay in the layout while they slide closed
+            let panels = [
+                self.animated_panel(animation::Panel::Jobs, || self.build_jobs_panel()),
+                self.animated_panel(animation::Panel::AgentHealth, || self.build_agent_health_panel()),
+                self.animated_panel(animation::Panel::Worktrees, || self.build_worktree_panel()),
+                self.animated_panel(animation::Panel::Diff, || self.build_diff_panel()),
+                self.animated_panel(animation::Panel::PermissionRules, || self.build_rules_panel()),
+                self.animated_panel(animation::Panel::PermissionAudit, || self.build_audit_panel()),
+                self.animated_panel(animation::Panel::ActionLog, || self.build_action_log_panel()),
+            ];
+            for panel in panels.into_iter().flatten() {
+                layout = layout.push(panel);
+            }
+            layout.into()
+        };
         
         let main_view = container(layout)
             .width(Length::Fill)
             .height(Length::Fill);
             
         // Add notification if present
-        let view_with_notification = if let Some((notification, _)) = &self.state.notification {
+        let view_with_notification = if let Some((notification, _)) = &self.state.editor.notification {
             let notification_widget = self.build_notification(notification);
             container(
                 Stack::new()
@@ -1178,11 +3397,155 @@ impl BickyApp {
         };
             
         // Add dialog overlay if open
-        if let Some(dialog) = &self.state.worktree_dialog {
+        let view_with_dialog: Element<Message> = if let Some(dialog) = &self.state.worktree_dialog {
             self.build_worktree_dialog(dialog, view_with_notification)
         } else {
             view_with_notification.into()
+        };
+        let view_with_dialog = if let Some(dialog) = &self.state.merge_dialog {
+            self.build_merge_dialog(dialog, view_with_dialog)
+        } else {
+            view_with_dialog
+        };
+        let view_with_dialog = if let Some(dialog) = &self.state.export_dialog {
+            self.build_export_dialog(dialog, view_with_dialog)
+        } else {
+            view_with_dialog
+        };
+        let view_with_dialog = if let Some(selector) = &self.state.agent_selector {
+            self.build_agent_selector(selector, view_with_dialog)
+        } else {
+            view_with_dialog
+        };
+        let view_with_dialog = if let Some(draft) = &self.state.agent_config {
+            self.build_agent_config(draft, view_with_dialog)
+        } else {
+            view_with_dialog
+        };
+        let view_with_dialog = if self.state.drop_hover {
+            self.build_drop_target(view_with_dialog)
+        } else {
+            view_with_dialog
+        };
+        let view_with_dialog = if let Some(picker) = &self.state.file_picker {
+            self.build_file_picker(picker, view_with_dialog)
+        } else {
+            view_with_dialog
+        };
+        let view_with_dialog = if let Some(dialog) = &self.state.artifact_save {
+            self.build_save_artifact(dialog, view_with_dialog)
+        } else {
+            view_with_dialog
+        };
+        let view_with_dialog = if let Some(source) = &self.state.expanded_image {
+            self.build_expanded_image(source, view_with_dialog)
+        } else {
+            view_with_dialog
+        };
+        let view_with_dialog = if let Some(prompt) = &self.state.auth_prompt {
+            self.build_auth_prompt(prompt, view_with_dialog)
+        } else {
+            view_with_dialog
+        };
+        let view_with_dialog = if self.state.show_debug_overlay {
+            self.build_debug_overlay(view_with_dialog)
+        } else {
+            view_with_dialog
+        };
+        
+        // Command palette sits above everything else
+        if let Some(palette) = &self.state.command_palette {
+            self.build_command_palette(palette, view_with_dialog)
+        } else {
+            view_with_dialog
+        }
+    }
+    
+    fn build_command_palette<'a>(&'a self, palette_state: &'a state::CommandPaletteState, main_view: Element<'a, Message>) -> Element<'a, Message> {
+        let entries = palette::filter(&self.state, &palette_state.query);
+        
+        let mut results = column![].spacing(2);
+        if entries.is_empty() {
+            results = results.push(
+                container(
+                    text("No matching commands").size(13).font(fonts::mono()).color(theme::colors().text_dim)
+                )
+                .padding(10)
+            );
         }
+        
+        for (idx, entry) in entries.into_iter().enumerate() {
+            let is_selected = idx == palette_state.selected;
+            results = results.push(
+                button(text(entry.label).size(14).font(fonts::mono()))
+                    .on_press(Message::ExecutePaletteCommand(entry.command))
+                    .padding(10)
+                    .width(Length::Fill)
+                    .style(move |theme, _| {
+                        if is_selected {
+                            theme::channel_button_active(theme)
+                        } else {
+                            theme::channel_button_inactive(theme)
+                        }
+                    })
+            );
+        }
+        
+        let palette_box = container(
+            column![
+                iced::widget::text_input("Type a command...", &palette_state.query)
+                    .id(palette_input_id())
+                    .on_input(Message::PaletteQueryChanged)
+                    .on_submit(Message::PaletteSubmit)
+                    .padding(12)
+                    .size(14)
+                    .font(fonts::mono())
+                    .style(theme::input_style),
+                scrollable(results)
+                    .direction(scrolling::scrollbar())
+                    .style(theme::scrollbar)
+                    .height(Length::Shrink),
+            ]
+            .spacing(8)
+            .padding(12)
+            .width(560)
+        )
+        .max_height(420)
+        .style(|_theme: &Theme| {
+            container::Style {
+                background: Some(iced::Background::Color(theme::colors().background)),
+                border: Border {
+                    color: theme::colors().border,
+                    width: 1.0,
+                    radius: 8.0.into(),
+                },
+                shadow: Shadow {
+                    color: Color::from_rgba(0.0, 0.0, 0.0, 0.4),
+                    offset: Vector::new(0.0, 4.0),
+                    blur_radius: 16.0,
+                },
+                ..Default::default()
+            }
+        });
+        
+        Stack::new()
+            .push(main_view)
+            .push(
+                container(palette_box)
+                    .width(Length::Fill)
+                    .height(Length::Fill)
+                    .padding(iced::Padding { top: 80.0, ..iced::Padding::ZERO })
+                    .align_x(iced::alignment::Horizontal::Center)
+                    .style(|_: &Theme| {
+                        container::Style {
+                            background: Some(iced::Background::Color(
+                                Color::from_rgba(0.0, 0.0, 0.0, 0.5)
+                            )),
+                            ..Default::default()
+                        }
+                    })
+            )
+            .into()
     }
     
     fn build_worktree_dialog<'a>(&self, dialog: &'a state::WorktreeDialogState, main_view: container::Container<'a, Message>) -> Element<'a, Message> {
@@ -1191,17 +3554,18 @@ impl BickyApp {
         let dialog_content = container(
             column![
                 // Title
-                text("Create New Worktree").size(18).font(fonts::BERKELEY_MONO_BOLD),
+                text("Create New Worktree").size(18).font(fonts::bold()),
                 Space::with_height(20),
                 
                 // Branch name input
                 column![
-                    text("Branch Name").size(14).font(fonts::BERKELEY_MONO),
+                    text("Branch Name").size(14).font(fonts::mono()),
                     text_input("feature/my-new-feature", &dialog.branch_name)
                         .on_input(Message::WorktreeBranchNameChanged)
                         .padding(10)
-                        .font(fonts::BERKELEY_MONO)
-                        .size(14),
+                        .font(fonts::mono())
+                        .size(14)
+                        .style(theme::input_style),
                 ]
                 .spacing(8),
                 
@@ -1209,12 +3573,13 @@ impl BickyApp {
                 
                 // Base branch input
                 column![
-                    text("Base Branch").size(14).font(fonts::BERKELEY_MONO),
+                    text("Base Branch").size(14).font(fonts::mono()),
                     text_input("main", &dialog.base_branch)
                         .on_input(Message::WorktreeBaseBranchChanged)
                         .padding(10)
-                        .font(fonts::BERKELEY_MONO)
-                        .size(14),
+                        .font(fonts::mono())
+                        .size(14)
+                        .style(theme::input_style),
                 ]
                 .spacing(8),
                 
@@ -1222,12 +3587,13 @@ impl BickyApp {
                 
                 // Custom path input (optional)
                 column![
-                    text("Custom Path (optional)").size(14).font(fonts::BERKELEY_MONO),
+                    text("Custom Path (optional)").size(14).font(fonts::mono()),
                     text_input("Leave empty for default", &dialog.custom_path)
                         .on_input(Message::WorktreeCustomPathChanged)
                         .padding(10)
-                        .font(fonts::BERKELEY_MONO)
-                        .size(14),
+                        .font(fonts::mono())
+                        .size(14)
+                        .style(theme::input_style),
                 ]
                 .spacing(8),
                 
@@ -1238,8 +3604,8 @@ impl BickyApp {
                     let error_widget: Element<Message> = container(
                         text(error)
                             .size(14)
-                            .font(fonts::BERKELEY_MONO)
-                            .color(theme::Colors::ERROR)
+                            .font(fonts::mono())
+                            .color(theme::colors().error)
                     )
                     .padding(10)
                     .style(|_theme: &Theme| {
@@ -1248,7 +3614,7 @@ impl BickyApp {
                                 0.8, 0.2, 0.2
                             ).scale_alpha(0.1))),
                             border: Border {
-                                color: theme::Colors::ERROR,
+                                color: theme::colors().error,
                                 width: 1.0,
                                 radius: 4.0.into(),
                             },
@@ -1265,12 +3631,12 @@ impl BickyApp {
                 
                 // Buttons
                 row![
-                    button(text("Cancel").size(14).font(fonts::BERKELEY_MONO))
+                    button(text("Cancel").size(14).font(fonts::mono()))
                         .on_press(Message::CloseWorktreeDialog)
                         .padding(10)
                         .style(theme::secondary_button),
                     Space::with_width(10),
-                    button(text("Create").size(14).font(fonts::BERKELEY_MONO))
+                    button(text("Create").size(14).font(fonts::mono()))
                         .on_press(Message::CreateWorktree)
                         .padding(10)
                         .style(theme::primary_button),
@@ -1283,9 +3649,9 @@ impl BickyApp {
         )
         .style(|_theme: &Theme| {
             container::Style {
-                background: Some(iced::Background::Color(theme::Colors::BACKGROUND)),
+                background: Some(iced::Background::Color(theme::colors().background)),
                 border: Border {
-                    color: theme::Colors::BORDER,
+                    color: theme::colors().border,
                     width: 1.0,
                     radius: 8.0.into(),
                 },
@@ -1318,6 +3684,103 @@ impl BickyApp {
         .into()
     }
     
+    fn build_edit_banner(&self) -> Element<'_, Message> {
+        if self.state.editing.is_none() {
+            return Space::with_height(0).into();
+        }
+        
+        text("Editing a sent message • Enter resends it as a new conversation, Esc cancels")
+            .size(11)
+            .font(fonts::mono())
+            .color(theme::colors().spinner)
+            .into()
+    }
+    
+    fn has_mention_completions(&self) -> bool {
+        self.state.mention.as_ref().is_some_and(|mention| {
+            !mentions::matches(&self.state.editor.worktree_files, &mention.query).is_empty()
+        })
+    }
+    
+    fn build_mention_completions(&self) -> Element<'_, Message> {
+        let Some(mention) = &self.state.mention else {
+            return Space::with_height(0).into();
+        };
+        
+        let files = mentions::matches(&self.state.editor.worktree_files, &mention.query);
+        if files.is_empty() {
+            return Space::with_height(0).into();
+        }
+        
+        let mut list = column![].spacing(2);
+        for (idx, file) in files.into_iter().enumerate() {
+            let is_selected = idx == mention.selected;
+            list = list.push(
+                container(text(format!("@{}", file)).size(13).font(fonts::mono()))
+                    .padding([6, 10])
+                    .width(Length::Fill)
+                    .style(move |theme: &Theme| {
+                        let style = if is_selected {
+                            theme::channel_button_active(theme)
+                        } else {
+                            theme::channel_button_inactive(theme)
+                        };
+                        container::Style {
+                            background: style.background,
+                            text_color: Some(style.text_color),
+                            border: style.border,
+                            ..Default::default()
+                        }
+                    })
+            );
+        }
+        
+        container(list)
+            .padding(4)
+            .width(Length::Fill)
+            .style(|_theme: &Theme| {
+                container::Style {
+                    background: Some(iced::Background::Color(theme::colors().background)),
+                    border: Border {
+                        color: theme::colors().border,
+                        width: 1.0,
+                        radius: 8.0.into(),
+                    },
+                    ..Default::default()
+                }
+            })
+            .into()
+    }
+    
+    fn build_token_estimate(&self) -> Element<'_, Message> {
+        let tokens = self.state.input_tokens;
+        let limit = self.state.settings.composer.max_message_tokens;
+        
+        if tokens == 0 {
+            return Space::with_height(0).into();
+        }
+        
+        let (label, color) = if let Some(trigger) = &self.state.expanded_template {
+            (format!("{} template • Esc to dismiss", trigger), theme::colors().text_dim)
+        } else if tokens > limit {
+            (
+                format!("~{} • exceeds the {} limit", format::locale().token_count(tokens as u32), format::locale().token_count(limit as u32)),
+                theme::colors().error,
+            )
+        } else if tokens * 5 > limit * 4 {
+            // Warn once we're within 20% of the limit
+            (format!("~{} • close to limit", format::locale().token_count(tokens as u32)), theme::colors().spinner)
+        } else {
+            (format!("~{}", format::locale().token_count(tokens as u32)), theme::colors().text_dim)
+        };
+        
+        text(label)
+            .size(11)
+            .font(fonts::mono())
+            .color(color)
+            .into()
+    }
+    
     fn build_notification(&self, notification: &state::Notification) -> Element<Message> {
         use state::Notification;
         
@@ -1330,7 +3793,7 @@ impl BickyApp {
         container(
             text(text_content)
                 .size(14)
-                .font(fonts::BERKELEY_MONO)
+                .font(fonts::mono())
                 .color(text_color)
         )
         .padding([12, 20])
@@ -1354,27 +3817,84 @@ impl BickyApp {
     }
 }
 
-fn format_token_count(tokens: u32) -> String {
-    match tokens {
-        t if t >= 1_000_000 => format!("{:.1}M tokens", t as f64 / 1_000_000.0),
-        t if t >= 1_000 => format!("{:.1}K tokens", t as f64 / 1_000.0),
-        t => format!("{} tokens", t),
+fn palette_input_id() -> iced::widget::text_input::Id {
+    iced::widget::text_input::Id::new("command-palette")
+}
+
+fn rename_input_id() -> iced::widget::text_input::Id {
+    iced::widget::text_input::Id::new("rename-conversation")
+}
+
+// One stat for the line under a response, None when there's nothing to show
+fn metadata_chip(metadata: &types::InteractionMetadata, chip: config::MetadataChip) -> Option<String> {
+    match chip {
+        config::MetadataChip::Tokens => {
+            metadata.tokens.as_ref().map(|tokens| format::locale().token_count(tokens.total))
+        }
+        config::MetadataChip::Time => {
+            metadata.processing_time_ms.map(|time_ms| format::locale().seconds(time_ms))
+        }
+        config::MetadataChip::Model => metadata.model.clone(),
+        config::MetadataChip::Tools => {
+            metadata.tools_used.as_ref()
+                .filter(|tools| !tools.is_empty())
+                .map(|tools| format!("🔧 {}", tools.join(", ")))
+        }
+        config::MetadataChip::Branch => {
+            metadata.worktree_context.as_ref()
+                .and_then(|context| context.branch.as_deref())
+                .map(|branch| format!("🌿 {}", branch.strip_prefix("refs/heads/").unwrap_or(branch)))
+        }
+        config::MetadataChip::Tags => {
+            metadata.tags.as_ref()
+                .filter(|tags| !tags.is_empty())
+                .map(|tags| format!("🏷️ {}", tags.join(", ")))
+        }
     }
 }
 
 impl Default for BickyApp {
     fn default() -> Self {
-        let api_client = api::ApiClient::default();
+        let settings = config::Settings::load();
+        let clock: Arc<dyn state::Clock> = Arc::new(state::SystemClock);
+        let servers = servers::configured(&settings, clock.clone());
+        let api_client = servers[0].api.clone();
         let runtime = tokio::runtime::Runtime::new().ok();
+        let (events, inbox) = events::bus();
+        
+        let mut state = AppState {
+            settings,
+            permission_policy: permission_policy::load(),
+            permission_audit: permission_audit::load(),
+            labels: labels::load(),
+            muted: mutes::load(),
+            system_appearance: theme::Appearance::detect(),
+            ..AppState::new(clock, Arc::new(state::UuidIds::default()))
+        };
+        update(&mut state, Action::ServersConfigured(servers.iter().map(|s| s.name.clone()).collect()));
         
         let mut app = Self {
-            state: AppState::default(),
+            state,
             api_client,
+            servers,
             runtime,
-            sse_receiver: None,
             interaction_cache: HashMap::new(),
+            events,
+            inbox,
+            tasks: tasks::Tasks::default(),
+            streams: HashMap::new(),
+            keymap: keymap::Keymap::load(),
+            composer: text_editor::Content::new(),
+            composer_input: MultilineInput::new(),
+            theme_watcher: theme::ThemeWatcher::default(),
+            motion: animation::Motion::default(),
+            transcript_scroll: scrolling::TranscriptScroll::default(),
+            timeline_scrub: None,
         };
         
+        // Apply the user's theme before the first frame
+        app.reload_theme(false);
+        
         // Start SSE connection immediately
         app.start_sse();
         
@@ -1384,19 +3904,8 @@ impl Default for BickyApp {
 
 impl Default for api::ApiClient {
     fn default() -> Self {
-        // Try to read port from file
-        let port = std::fs::read_to_string("../../../.bicamrl-port")
-            .ok()
-            .and_then(|s| s.trim().parse::<u16>().ok())
-            .unwrap_or(3456);
+        let port = platform::local_server_port().unwrap_or(3456);
         
         api::ApiClient::new(format!("http://localhost:{}", port))
     }
 }
-
-// Implement Clone for ApiClient
-impl Clone for api::ApiClient {
-    fn clone(&self) -> Self {
-        api::ApiClient::new(self.base_url.clone())
-    }
-}
\ No newline at end of file
@@ -141,8 +141,8 @@ pub fn render_fragments<'a, Message: 'a>(fragments: &'a [Fragment]) -> Element<'
                         current_paragraph.push(
                             text(*line)
                                 .size(14)
-                                .font(fonts::BERKELEY_MONO)
-                                .color(theme::Colors::TEXT)
+                                .font(fonts::mono())
+                                .color(theme::colors().text)
                                 .into()
                         );
                     }
@@ -153,8 +153,8 @@ pub fn render_fragments<'a, Message: 'a>(fragments: &'a [Fragment]) -> Element<'
                     container(
                         text(content)
                             .size(13)
-                            .font(fonts::BERKELEY_MONO)
-                            .color(theme::Colors::PRIMARY)
+                            .font(fonts::mono())
+                            .color(theme::colors().primary)
                     )
                     .padding([2, 6])
                     .style(|theme| theme::code_container(theme))
@@ -183,8 +183,8 @@ pub fn render_fragments<'a, Message: 'a>(fragments: &'a [Fragment]) -> Element<'
                             container(
                                 text(lang)
                                     .size(11)
-                                    .color(theme::Colors::TEXT_DIM)
-                                    .font(fonts::BERKELEY_MONO_BOLD)
+                                    .color(theme::colors().text_dim)
+                                    .font(fonts::bold())
                             )
                             .padding(6)
                         );
@@ -195,8 +195,8 @@ pub fn render_fragments<'a, Message: 'a>(fragments: &'a [Fragment]) -> Element<'
                     container(
                         text(content.trim_end())
                             .size(13)
-                            .font(fonts::BERKELEY_MONO)
-                            .color(theme::Colors::TEXT)
+                            .font(fonts::mono())
+                            .color(theme::colors().text)
                     )
                     .padding(12)
                     .width(Length::Fill)
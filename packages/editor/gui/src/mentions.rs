@@ -0,0 +1,96 @@
+use crate::palette;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+/// How many completions the composer shows at once
+pub const MAX_COMPLETIONS: usize = 8;
+
+// Directories never worth suggesting when git isn't available
+const SKIPPED_DIRS: [&str; 5] = [".git", "node_modules", "target", "dist", ".bicamrl"];
+const MAX_WALKED_FILES: usize = 20_000;
+
+/// The `@` reference being typed at `column`, without the `@`
+///
+/// A mention starts at an `@` at the beginning of the line or after whitespace
+/// and runs up to the cursor, so `foo@bar` (an email, say) never completes.
+pub fn mention_query(line: &str, column: usize) -> Option<&str> {
+    let end = line.char_indices().nth(column).map(|(idx, _)| idx).unwrap_or(line.len());
+    let before = &line[..end];
+
+    let start = before.rfind('@')?;
+    let query = &before[start + 1..];
+    if query.chars().any(char::is_whitespace) {
+        return None;
+    }
+
+    let at_word_start = before[..start].chars().next_back().map_or(true, char::is_whitespace);
+    at_word_start.then_some(query)
+}
+
+/// Files matching the query, best match first
+pub fn matches<'a>(files: &'a [String], query: &str) -> Vec<&'a str> {
+    let mut scored: Vec<(i32, &str)> = files
+        .iter()
+        .filter_map(|file| Some((palette::fuzzy_score(query, file)?, file.as_str())))
+        .collect();
+
+    scored.sort_by_key(|(score, _)| std::cmp::Reverse(*score));
+    scored.into_iter().take(MAX_COMPLETIONS).map(|(_, file)| file).collect()
+}
+
+/// Paths of every file in the worktree, relative to its root
+///
+/// Uses git so ignored files stay out of the list, falling back to walking the
+/// directory when `root` isn't a repository.
+pub fn list_files(root: &Path) -> Result<Vec<String>, String> {
+    if let Some(files) = git_files(root) {
+        return Ok(files);
+    }
+
+    let mut files = Vec::new();
+    walk(root, root, &mut files).map_err(|e| format!("Failed to list {}: {}", root.display(), e))?;
+    files.sort();
+    Ok(files)
+}
+
+fn git_files(root: &Path) -> Option<Vec<String>> {
+    // Paths are relative to the repository root, wherever we were started from
+    let toplevel = Command::new("git")
+        .arg("-C")
+        .arg(root)
+        .args(["rev-parse", "--show-toplevel"])
+        .output()
+        .ok()
+        .filter(|output| output.status.success())?;
+    let toplevel = PathBuf::from(String::from_utf8_lossy(&toplevel.stdout).trim());
+
+    let output = Command::new("git")
+        .arg("-C")
+        .arg(&toplevel)
+        .args(["ls-files", "--cached", "--others", "--exclude-standard"])
+        .output()
+        .ok()
+        .filter(|output| output.status.success())?;
+
+    Some(String::from_utf8_lossy(&output.stdout).lines().map(str::to_string).collect())
+}
+
+fn walk(root: &Path, dir: &Path, files: &mut Vec<String>) -> std::io::Result<()> {
+    for entry in std::fs::read_dir(dir)? {
+        if files.len() >= MAX_WALKED_FILES {
+            return Ok(());
+        }
+
+        let path = entry?.path();
+        let name = path.file_name().and_then(|n| n.to_str()).unwrap_or("");
+
+        if path.is_dir() {
+            if !SKIPPED_DIRS.contains(&name) {
+                walk(root, &path, files)?;
+            }
+        } else if let Ok(relative) = path.strip_prefix(root) {
+            files.push(relative.to_string_lossy().replace('\\', "/"));
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,125 @@
+use iced::widget::{button, column, container, row, text, text_input, Space, Stack};
+use iced::{Border, Color, Element, Length, Theme};
+
+use crate::state::{MergeDialogState, MergeMode};
+use crate::{fonts, theme, BickyApp, Message};
+
+impl BickyApp {
+    pub(crate) fn build_merge_dialog<'a>(&self, dialog: &'a MergeDialogState, main_view: Element<'a, Message>) -> Element<'a, Message> {
+        let (title, message_label, action) = match dialog.mode {
+            MergeMode::Merge => (
+                format!("Merge {} into {}", dialog.branch, dialog.base),
+                "Commit Message",
+                "Merge",
+            ),
+            MergeMode::PullRequest => (
+                format!("Open a PR from {} into {}", dialog.branch, dialog.base),
+                "Title",
+                "Create PR",
+            ),
+        };
+
+        let mut content = column![
+            text(title).size(18).font(fonts::bold()),
+            Space::with_height(12),
+            text("Uncommitted changes in the worktree are committed first.")
+                .size(12)
+                .font(fonts::mono())
+                .color(theme::colors().text_dim),
+            Space::with_height(12),
+            column![
+                text(message_label).size(14).font(fonts::mono()),
+                text_input("Describe the change", &dialog.message)
+                    .on_input(Message::MergeMessageChanged)
+                    .on_submit(Message::SubmitMerge)
+                    .padding(10)
+                    .font(fonts::mono())
+                    .size(14)
+                    .style(theme::input_style),
+            ]
+            .spacing(8),
+        ]
+        .spacing(8);
+
+        if dialog.mode == MergeMode::PullRequest {
+            content = content.push(Space::with_height(8)).push(
+                column![
+                    text("Description (optional)").size(14).font(fonts::mono()),
+                    text_input("What reviewers should know", &dialog.body)
+                        .on_input(Message::MergeBodyChanged)
+                        .padding(10)
+                        .font(fonts::mono())
+                        .size(14)
+                        .style(theme::input_style),
+                ]
+                .spacing(8),
+            );
+        }
+
+        if let Some(error) = &dialog.error {
+            content = content.push(
+                container(text(error).size(14).font(fonts::mono()).color(theme::colors().error))
+                    .padding(10)
+                    .style(|_theme: &Theme| container::Style {
+                        background: Some(iced::Background::Color(Color::from_rgb(0.8, 0.2, 0.2).scale_alpha(0.1))),
+                        border: Border {
+                            color: theme::colors().error,
+                            width: 1.0,
+                            radius: 4.0.into(),
+                        },
+                        ..Default::default()
+                    }),
+            );
+        }
+
+        // No double submits while git is working
+        let submit = button(
+            text(if dialog.in_progress { "Working..." } else { action })
+                .size(14)
+                .font(fonts::mono()),
+        )
+        .on_press_maybe((!dialog.in_progress && !dialog.message.trim().is_empty()).then_some(Message::SubmitMerge))
+        .padding(10)
+        .style(theme::primary_button);
+
+        content = content.push(Space::with_height(12)).push(
+            row![
+                button(text("Cancel").size(14).font(fonts::mono()))
+                    .on_press(Message::CloseMergeDialog)
+                    .padding(10)
+                    .style(theme::secondary_button),
+                Space::with_width(10),
+                submit,
+            ]
+            .align_y(iced::Alignment::Center),
+        );
+
+        let dialog_content = container(content.padding(30).width(460))
+            .style(|_theme: &Theme| container::Style {
+                background: Some(iced::Background::Color(theme::colors().background)),
+                border: Border {
+                    color: theme::colors().border,
+                    width: 1.0,
+                    radius: 8.0.into(),
+                },
+                ..Default::default()
+            })
+            .center_x(Length::Fill)
+            .center_y(Length::Fill);
+
+        Stack::new()
+            .push(main_view)
+            .push(
+                container(dialog_content)
+                    .width(Length::Fill)
+                    .height(Length::Fill)
+                    .style(|_: &Theme| container::Style {
+                        background: Some(iced::Background::Color(Color::from_rgba(0.0, 0.0, 0.0, 0.7))),
+                        ..Default::default()
+                    }),
+            )
+            .width(Length::Fill)
+            .height(Length::Fill)
+            .into()
+    }
+}
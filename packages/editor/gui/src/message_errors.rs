@@ -0,0 +1,121 @@
+use iced::widget::{button, column, container, row, text};
+use iced::{Element, Length};
+use iced::Task as Command;
+
+use crate::state::{update, Action, Conversation, Notification};
+use crate::types::{LegacyMessage, MessageError, MessageStatus, SendMessageRequest};
+use crate::{agents, clipboard, fonts, pins, theme, BickyApp, Message};
+
+/// What went wrong with a message, once it has settled as failed
+pub fn failure(msg: &LegacyMessage) -> Option<&MessageError> {
+    match msg.status {
+        MessageStatus::Error | MessageStatus::Failed => msg.error.as_ref(),
+        _ => None,
+    }
+}
+
+pub fn error_detail(msg: &LegacyMessage) -> Option<&str> {
+    failure(msg).map(|e| e.detail.as_str())
+}
+
+/// Sending failed before the server ever saw the message
+pub fn never_sent(msg: &LegacyMessage) -> bool {
+    msg.status == MessageStatus::Error && msg.response.is_none() && msg.id.starts_with("temp-")
+}
+
+/// The interaction follow-ups go to, `None` until the server knows about the conversation
+pub fn interaction_id(conv: &Conversation) -> Option<String> {
+    conv.messages
+        .iter()
+        .any(|m| m.status != MessageStatus::Pending && !never_sent(m))
+        .then(|| conv.id.clone())
+}
+
+impl BickyApp {
+    /// Failure details with ways to act on them, shown inside the failed bubble
+    pub(crate) fn build_error_banner<'a>(&'a self, msg: &'a LegacyMessage) -> Option<Element<'a, Message>> {
+        let error = failure(msg)?;
+        let heading = if never_sent(msg) { "⚠ Couldn't send" } else { "⚠ Wake hit an error" };
+
+        // Sending again won't help with failures like a malformed response
+        let actions = row![]
+            .push_maybe(error.retryable.then(|| {
+                button(text("Retry").size(12).font(fonts::mono()))
+                    .on_press(Message::RetryFailed(msg.id.clone()))
+                    .padding(6)
+                    .style(theme::secondary_button)
+            }))
+            .push(
+                button(text("Copy error").size(12).font(fonts::mono()))
+                    .on_press(Message::CopyError(msg.id.clone()))
+                    .padding(6)
+                    .style(theme::secondary_button),
+            )
+            .spacing(8);
+
+        Some(
+            container(
+                column![
+                    text(heading).size(12).font(fonts::bold()).color(theme::colors().error),
+                    text(&error.detail).size(12).font(fonts::mono()).color(theme::colors().text),
+                ]
+                .push_maybe(error.guidance.as_ref().map(|guidance| {
+                    text(guidance).size(11).font(fonts::mono()).color(theme::colors().text_dim)
+                }))
+                .push(actions)
+                .spacing(6),
+            )
+            .padding(10)
+            .width(Length::Fill)
+            .style(theme::error_banner)
+            .into(),
+        )
+    }
+
+    /// Send a failed message again as it was first written
+    pub(crate) fn retry_message(&mut self, message_id: String) -> Command<Message> {
+        let Some(conversation_idx) = self.state.active_conversation else {
+            return Command::none();
+        };
+        let Some(conv) = self.state.conversations.get(conversation_idx) else {
+            return Command::none();
+        };
+        let Some(msg) = conv.messages.iter().find(|m| m.id == message_id) else {
+            return Command::none();
+        };
+        if !failure(msg).is_some_and(|e| e.retryable) {
+            return Command::none();
+        }
+
+        let request = SendMessageRequest {
+            content: msg.content.clone(),
+            metadata: agents::with_route(conv, pins::with_pins(conv, None)),
+            worktree_id: conv.worktree_id.clone(),
+            interaction_id: interaction_id(conv),
+            attachment_ids: msg.attachments.iter().map(|attachment| attachment.id.clone()).collect(),
+        };
+        println!("[GUI] Retrying message {} in {}", message_id, conv.id);
+        if !self.within_budget(conversation_idx) {
+            return Command::none();
+        }
+
+        update(&mut self.state, Action::RetryMessage { conversation_idx, message_id });
+        self.send_message(conversation_idx, request)
+    }
+
+    pub(crate) fn copy_error(&mut self, message_id: String) -> Command<Message> {
+        let detail = self
+            .state
+            .active_conversation
+            .and_then(|idx| self.state.conversations.get(idx))
+            .and_then(|conv| conv.messages.iter().find(|m| m.id == message_id))
+            .and_then(error_detail)
+            .map(str::to_string);
+        let Some(detail) = detail else {
+            return Command::none();
+        };
+
+        update(&mut self.state, Action::ShowNotification(Notification::Info("Copied error to the clipboard".to_string())));
+        clipboard::copy(detail)
+    }
+}
@@ -0,0 +1,152 @@
+use iced::widget::{container, mouse_area, Column, Space};
+use iced::{Background, Element, Length, Padding, Theme};
+
+use crate::state::Conversation;
+use crate::types::{LegacyMessage, MessageStatus};
+use crate::{theme, BickyApp, Message};
+
+const STRIP_WIDTH: f32 = 14.0;
+// Long transcripts are folded into this many rows
+const MAX_ROWS: usize = 160;
+// Labels and padding around each message, in estimated lines
+const MESSAGE_OVERHEAD: usize = 4;
+// Roughly how many characters fit on a transcript line
+const LINE_CHARS: usize = 90;
+
+/// Something worth finding in the transcript
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Marker {
+    Error,
+    Bookmark,
+}
+
+/// One row of the overview strip
+#[derive(Debug, Clone, PartialEq)]
+pub struct OverviewRow {
+    /// Where the row starts, as a fraction of the transcript height
+    pub start: f32,
+    /// How crowded the row is with messages, 0 to 1
+    pub density: f32,
+    pub marker: Option<Marker>,
+}
+
+// Wrapped lines a block of text takes up
+fn estimated_lines(text: &str) -> usize {
+    text.lines().map(|line| ((line.chars().count() + LINE_CHARS - 1) / LINE_CHARS).max(1)).sum()
+}
+
+fn estimated_height(msg: &LegacyMessage) -> usize {
+    MESSAGE_OVERHEAD + estimated_lines(&msg.content) + msg.response.as_deref().map_or(0, estimated_lines)
+}
+
+fn marker(conv: &Conversation, msg: &LegacyMessage) -> Option<Marker> {
+    // Bookmarks were placed on purpose, so they win over errors
+    if conv.bookmarks.contains(&msg.id) {
+        Some(Marker::Bookmark)
+    } else if matches!(msg.status, MessageStatus::Error | MessageStatus::Failed) {
+        Some(Marker::Error)
+    } else {
+        None
+    }
+}
+
+/// Where each message starts, as a fraction of the estimated transcript height
+pub fn message_starts(conv: &Conversation) -> Vec<f32> {
+    let heights: Vec<usize> = conv.messages.iter().map(estimated_height).collect();
+    let total = heights.iter().sum::<usize>().max(1) as f32;
+    heights
+        .iter()
+        .scan(0, |line, height| {
+            let start = *line as f32 / total;
+            *line += height;
+            Some(start)
+        })
+        .collect()
+}
+
+/// Fold a conversation into rows, spaced by how tall each message renders
+pub fn overview(conv: &Conversation) -> Vec<OverviewRow> {
+    let heights: Vec<usize> = conv.messages.iter().map(estimated_height).collect();
+    let total: usize = heights.iter().sum();
+    if total == 0 {
+        return Vec::new();
+    }
+
+    let rows = MAX_ROWS.min(total);
+    let mut counts = vec![0usize; rows];
+    let mut markers = vec![None; rows];
+    let row_of = |line: usize| (line * rows / total).min(rows - 1);
+
+    let mut line = 0;
+    for (msg, height) in conv.messages.iter().zip(&heights) {
+        let (first, last) = (row_of(line), row_of(line + height - 1));
+        for count in &mut counts[first..=last] {
+            *count += 1;
+        }
+        // Markers sit where the message starts so they stay a thin tick
+        if let Some(found) = marker(conv, msg) {
+            if markers[first] != Some(Marker::Bookmark) {
+                markers[first] = Some(found);
+            }
+        }
+        line += height;
+    }
+
+    let busiest = counts.iter().copied().max().unwrap_or(1).max(1) as f32;
+    counts
+        .into_iter()
+        .zip(markers)
+        .enumerate()
+        .map(|(idx, (count, marker))| OverviewRow {
+            start: idx as f32 / rows as f32,
+            density: count as f32 / busiest,
+            marker,
+        })
+        .collect()
+}
+
+impl BickyApp {
+    /// Overview strip beside the transcript, click a row to jump there
+    pub(crate) fn build_minimap(&self, conv: &Conversation) -> Element<'_, Message> {
+        let visible = self.transcript_scroll.visible_range();
+        let rows = overview(conv);
+        let row_height = 1.0 / rows.len().max(1) as f32;
+
+        let strip = Column::with_children(rows.into_iter().map(|row| -> Element<'_, Message> {
+            let in_view = visible.is_some_and(|(top, bottom)| row.start + row_height > top && row.start < bottom);
+            let color = match row.marker {
+                Some(Marker::Bookmark) => theme::colors().primary,
+                Some(Marker::Error) => theme::colors().error,
+                None => theme::colors().text_dim.scale_alpha(0.15 + 0.6 * row.density),
+            };
+
+            let bar = container(Space::new(Length::Fill, Length::Fill))
+                .width(Length::Fill)
+                .height(Length::Fill)
+                .style(move |_theme: &Theme| container::Style {
+                    background: Some(Background::Color(color)),
+                    ..Default::default()
+                });
+            let cell = container(bar)
+                .padding(Padding { left: 3.0, right: 3.0, ..Padding::ZERO })
+                .width(Length::Fill)
+                .height(Length::FillPortion(1))
+                .style(move |_theme: &Theme| container::Style {
+                    background: in_view.then(|| Background::Color(theme::colors().surface_light)),
+                    ..Default::default()
+                });
+
+            mouse_area(cell)
+                .on_press(Message::MinimapJump(row.start))
+                .interaction(iced::mouse::Interaction::Pointer)
+                .into()
+        }))
+        .height(Length::Fill);
+
+        container(strip)
+            .width(Length::Fixed(STRIP_WIDTH))
+            .height(Length::Fill)
+            .padding(Padding { top: 4.0, bottom: 4.0, ..Padding::ZERO })
+            .into()
+    }
+}
@@ -0,0 +1,76 @@
+use iced::Color;
+
+use crate::config::ModelStyles;
+use crate::state::Conversation;
+use crate::theme;
+
+// Picked for models nobody configured, all readable on the dark background
+const PALETTE: [Color; 8] = [
+    Color::from_rgb(0.38, 0.65, 1.0),  // #61a6ff
+    Color::from_rgb(0.75, 0.52, 0.99), // #c084fc
+    Color::from_rgb(0.37, 0.92, 0.83), // #5eead4
+    Color::from_rgb(1.0, 0.68, 0.38),  // #ffad61
+    Color::from_rgb(0.96, 0.45, 0.71), // #f472b6
+    Color::from_rgb(0.64, 0.90, 0.21), // #a3e635
+    Color::from_rgb(0.98, 0.80, 0.08), // #facc15
+    Color::from_rgb(0.58, 0.64, 0.72), // #94a3b8
+];
+const DEFAULT_ICON: &str = "●";
+
+/// How a model is marked wherever it shows up
+#[derive(Debug, Clone, PartialEq)]
+pub struct ModelBadge {
+    pub icon: String,
+    pub color: Color,
+}
+
+// FNV-1a, so a model keeps its color between runs
+fn palette_color(model: &str) -> Color {
+    let hash = model
+        .bytes()
+        .fold(0xcbf29ce484222325u64, |hash, byte| (hash ^ byte as u64).wrapping_mul(0x100000001b3));
+    PALETTE[(hash % PALETTE.len() as u64) as usize]
+}
+
+/// The configured badge for a model, falling back to a stable palette color
+pub fn badge(model: &str, styles: &ModelStyles) -> ModelBadge {
+    let name = model.to_lowercase();
+    let style = styles
+        .0
+        .iter()
+        .filter(|(key, _)| !key.is_empty() && name.contains(&key.to_lowercase()))
+        .max_by_key(|(key, _)| key.len())
+        .map(|(_, style)| style);
+
+    let color = style
+        .and_then(|style| style.color.as_deref())
+        .and_then(theme::parse_hex_color)
+        .unwrap_or_else(|| palette_color(&name));
+
+    let icon = style
+        .and_then(|style| style.icon.clone())
+        .unwrap_or_else(|| DEFAULT_ICON.to_string());
+
+    ModelBadge { icon, color }
+}
+
+/// The model behind the conversation's latest response
+pub fn latest_model(conv: &Conversation) -> Option<&str> {
+    conv.messages
+        .iter()
+        .rev()
+        .filter_map(|msg| msg.metadata.as_ref()?.model.as_deref())
+        .next()
+}
+
+/// Responses per model, in the order models first answered
+pub fn responses_by_model(conv: &Conversation) -> Vec<(&str, u32)> {
+    let mut counts: Vec<(&str, u32)> = Vec::new();
+    for model in conv.messages.iter().filter_map(|msg| msg.metadata.as_ref()?.model.as_deref()) {
+        match counts.iter_mut().find(|(name, _)| *name == model) {
+            Some((_, count)) => *count += 1,
+            None => counts.push((model, 1)),
+        }
+    }
+    counts
+}
@@ -0,0 +1,62 @@
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
+use std::path::PathBuf;
+
+use crate::state::{self, Action};
+use crate::{config, BickyApp};
+
+// Muted conversation ids, next to Mind.toml
+pub(crate) const MUTES_FILE: &str = "Muted.toml";
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct MutesFile {
+    #[serde(default)]
+    conversations: BTreeSet<String>,
+}
+
+pub fn load() -> BTreeSet<String> {
+    let Some(path) = config::find_config_file(MUTES_FILE) else {
+        return BTreeSet::new();
+    };
+
+    match std::fs::read_to_string(&path).map(|content| toml::from_str::<MutesFile>(&content)) {
+        Ok(Ok(file)) => file.conversations,
+        Ok(Err(e)) => {
+            eprintln!("[GUI] Failed to parse {}: {}", path.display(), e);
+            BTreeSet::new()
+        }
+        Err(e) => {
+            eprintln!("[GUI] Failed to read {}: {}", path.display(), e);
+            BTreeSet::new()
+        }
+    }
+}
+
+/// Write the muted conversations next to Mind.toml, or here when there isn't one
+pub fn save(muted: &BTreeSet<String>) -> Result<PathBuf, String> {
+    let path = config::find_config_file(MUTES_FILE)
+        .or_else(|| config::find_config_file("Mind.toml").map(|mind| mind.with_file_name(MUTES_FILE)))
+        .unwrap_or_else(|| PathBuf::from(MUTES_FILE));
+
+    let contents = toml::to_string(&MutesFile { conversations: muted.clone() }).map_err(|e| e.to_string())?;
+    std::fs::write(&path, contents).map_err(|e| e.to_string())?;
+    Ok(path)
+}
+
+impl BickyApp {
+    /// Mute a conversation, or unmute it, and save the change
+    pub(crate) fn toggle_mute(&mut self, conversation_idx: usize) {
+        state::update(&mut self.state, Action::ToggleMute(conversation_idx));
+        self.save_mutes();
+    }
+
+    // Failures only cost the mutes surviving a restart
+    pub(crate) fn save_mutes(&mut self) {
+        if let Err(e) = save(&self.state.muted) {
+            state::update(
+                &mut self.state,
+                Action::ShowNotification(state::Notification::Error(format!("Couldn't save muted conversations: {}", e))),
+            );
+        }
+    }
+}
@@ -0,0 +1,373 @@
+use crate::artifacts;
+use crate::budget::{self, TokenBudget};
+use crate::config::{AppearanceMode, PaletteVariant};
+use crate::format;
+use crate::presentation;
+use crate::state::{self, AppState};
+
+/// Everything the command palette can do
+#[derive(Debug, Clone, PartialEq)]
+pub enum PaletteCommand {
+    NewConversation,
+    SwitchConversation(usize),
+    SwitchServer(String),
+    SwitchWorktree(String),
+    CreateWorktree,
+    ManageWorktrees,
+    ShowDiff(String),
+    ExportConversation,
+    ToggleMute(usize),
+    /// `None` removes the conversation's budget
+    SetTokenBudget(usize, Option<TokenBudget>),
+    ManageAgents(usize),
+    ToggleMinimap,
+    ToggleFocusMode,
+    StartPresentation,
+    /// Show the conversation's artifacts, or its transcript again
+    ShowArtifacts(bool),
+    ArchiveConversation(usize),
+    UnarchiveConversation(usize),
+    DeleteConversation(usize),
+    ToggleJobs,
+    ToggleAgentHealth,
+    /// Expand or collapse the pinned context strip
+    ShowPins(bool),
+    AttachFile,
+    ToggleDebugOverlay,
+    SetAppearance(AppearanceMode),
+    SetPaletteVariant(PaletteVariant),
+    ManagePermissionRules,
+    ShowPermissionAudit,
+    ShowActionLog,
+    ForgetPermissionRules,
+    OpenSettings,
+    RestoreBackup(chrono::NaiveDate),
+    ImportTranscripts,
+    /// Write the past week's digest, `narrate` also has Wake tell it
+    GenerateDigest { narrate: bool },
+    /// A command a plugin offers, by its id
+    Plugin { plugin: String, command: String },
+}
+
+/// A command with the label shown in the palette
+#[derive(Debug, Clone, PartialEq)]
+pub struct PaletteEntry {
+    pub label: String,
+    pub command: PaletteCommand,
+}
+
+/// All commands available for the current state
+pub fn commands(state: &AppState) -> Vec<PaletteEntry> {
+    let mut entries = vec![PaletteEntry {
+        label: "New conversation".to_string(),
+        command: PaletteCommand::NewConversation,
+    }];
+
+    for (idx, conv) in state.conversations.iter().enumerate() {
+        if state.active_conversation != Some(idx) && state::on_active_server(state, conv) {
+            // Archived conversations are hidden from the sidebar but still searchable here
+            let label = if conv.archived {
+                format!("Switch to #{} (archived)", conv.title)
+            } else {
+                format!("Switch to #{}", conv.title)
+            };
+            entries.push(PaletteEntry {
+                label,
+                command: PaletteCommand::SwitchConversation(idx),
+            });
+        }
+    }
+
+    for server in state.servers.iter().filter(|s| **s != state.active_server) {
+        entries.push(PaletteEntry {
+            label: format!("Switch server: {}", server),
+            command: PaletteCommand::SwitchServer(server.clone()),
+        });
+    }
+
+    for worktree in &state.editor.available_worktrees {
+        if state.editor.current_worktree.as_ref().map(|w| &w.id) != Some(&worktree.id) {
+            let branch = worktree
+                .branch
+                .as_deref()
+                .map(|b| b.strip_prefix("refs/heads/").unwrap_or(b))
+                .unwrap_or(&worktree.path);
+            entries.push(PaletteEntry {
+                label: format!("Switch worktree: {}", branch),
+                command: PaletteCommand::SwitchWorktree(worktree.id.clone()),
+            });
+        }
+    }
+
+    entries.push(PaletteEntry {
+        label: "Create worktree".to_string(),
+        command: PaletteCommand::CreateWorktree,
+    });
+
+    if let Some(worktree) = &state.editor.current_worktree {
+        entries.push(PaletteEntry {
+            label: "Review worktree changes".to_string(),
+            command: PaletteCommand::ShowDiff(worktree.id.clone()),
+        });
+    }
+
+    entries.push(PaletteEntry {
+        label: if state.show_worktrees { "Hide worktrees" } else { "Manage worktrees" }.to_string(),
+        command: PaletteCommand::ManageWorktrees,
+    });
+
+    if let Some(idx) = state.active_conversation {
+        entries.push(PaletteEntry {
+            label: "Export conversation as Markdown…".to_string(),
+            command: PaletteCommand::ExportConversation,
+        });
+
+        entries.push(PaletteEntry {
+            label: if state.pins_expanded { "Hide pinned context" } else { "Pin a file…" }.to_string(),
+            command: PaletteCommand::ShowPins(!state.pins_expanded),
+        });
+
+        entries.push(PaletteEntry {
+            label: "Attach a file to the next message…".to_string(),
+            command: PaletteCommand::AttachFile,
+        });
+
+        if state.conversations.get(idx).is_some_and(|conv| !presentation::slides(conv).is_empty()) {
+            entries.push(PaletteEntry {
+                label: "Present conversation".to_string(),
+                command: PaletteCommand::StartPresentation,
+            });
+        }
+
+        if state.conversations.get(idx).is_some_and(|conv| !artifacts::list(conv).is_empty()) {
+            entries.push(PaletteEntry {
+                label: if state.artifacts_tab { "Show transcript" } else { "Show artifacts" }.to_string(),
+                command: PaletteCommand::ShowArtifacts(!state.artifacts_tab),
+            });
+        }
+
+        let muted = state.conversations.get(idx).is_some_and(|c| c.muted);
+        entries.push(PaletteEntry {
+            label: if muted { "Unmute conversation" } else { "Mute conversation" }.to_string(),
+            command: PaletteCommand::ToggleMute(idx),
+        });
+
+        if !state.agents.is_empty() {
+            entries.push(PaletteEntry {
+                label: "Attach or detach agents…".to_string(),
+                command: PaletteCommand::ManageAgents(idx),
+            });
+        }
+
+        // A new budget keeps whether it blocks, new ones only warn
+        let budget = state.conversations.get(idx).and_then(|c| c.budget);
+        let block = budget.is_some_and(|b| b.block);
+        for limit in budget::PRESETS.into_iter().filter(|limit| budget.map(|b| b.limit) != Some(*limit)) {
+            entries.push(PaletteEntry {
+                label: format!("Set token budget: {}", format::locale().token_count(limit)),
+                command: PaletteCommand::SetTokenBudget(idx, Some(TokenBudget { limit, block })),
+            });
+        }
+        if let Some(budget) = budget {
+            entries.push(PaletteEntry {
+                label: if budget.block { "Only warn over the token budget" } else { "Block sends over the token budget" }.to_string(),
+                command: PaletteCommand::SetTokenBudget(idx, Some(TokenBudget { block: !budget.block, ..budget })),
+            });
+            entries.push(PaletteEntry {
+                label: "Remove token budget".to_string(),
+                command: PaletteCommand::SetTokenBudget(idx, None),
+            });
+        }
+
+        let archived = state.conversations.get(idx).is_some_and(|c| c.archived);
+        entries.push(if archived {
+            PaletteEntry {
+                label: "Unarchive conversation".to_string(),
+                command: PaletteCommand::UnarchiveConversation(idx),
+            }
+        } else {
+            PaletteEntry {
+                label: "Archive conversation".to_string(),
+                command: PaletteCommand::ArchiveConversation(idx),
+            }
+        });
+
+        entries.push(PaletteEntry {
+            label: "Delete conversation".to_string(),
+            command: PaletteCommand::DeleteConversation(idx),
+        });
+    }
+
+    entries.push(PaletteEntry {
+        label: if state.show_jobs { "Hide jobs" } else { "Show jobs" }.to_string(),
+        command: PaletteCommand::ToggleJobs,
+    });
+
+    if !state.agents.is_empty() {
+        entries.push(PaletteEntry {
+            label: if state.show_agent_health { "Hide agent status" } else { "Show agent status" }.to_string(),
+            command: PaletteCommand::ToggleAgentHealth,
+        });
+    }
+
+    entries.push(PaletteEntry {
+        label: if state.show_debug_overlay { "Hide debug overlay" } else { "Show debug overlay" }.to_string(),
+        command: PaletteCommand::ToggleDebugOverlay,
+    });
+
+    entries.push(PaletteEntry {
+        label: if state.show_action_log { "Hide action log" } else { "Show action log" }.to_string(),
+        command: PaletteCommand::ShowActionLog,
+    });
+
+    entries.push(PaletteEntry {
+        label: if state::minimap_enabled(state) { "Hide minimap" } else { "Show minimap" }.to_string(),
+        command: PaletteCommand::ToggleMinimap,
+    });
+
+    entries.push(PaletteEntry {
+        label: if state.focus_mode { "Leave focus mode" } else { "Enter focus mode" }.to_string(),
+        command: PaletteCommand::ToggleFocusMode,
+    });
+
+    // Offer the appearances not already in use
+    let current = state::appearance_mode(state);
+    for (mode, label) in [
+        (AppearanceMode::System, "Follow system appearance"),
+        (AppearanceMode::Dark, "Use dark theme"),
+        (AppearanceMode::Light, "Use light theme"),
+    ] {
+        if mode != current {
+            entries.push(PaletteEntry {
+                label: label.to_string(),
+                command: PaletteCommand::SetAppearance(mode),
+            });
+        }
+    }
+
+    let current = state::palette_variant(state);
+    for (variant, label) in [
+        (PaletteVariant::Standard, "Use standard colors"),
+        (PaletteVariant::HighContrast, "Use high contrast colors"),
+        (PaletteVariant::Colorblind, "Use colorblind-safe colors"),
+    ] {
+        if variant != current {
+            entries.push(PaletteEntry {
+                label: label.to_string(),
+                command: PaletteCommand::SetPaletteVariant(variant),
+            });
+        }
+    }
+
+    entries.push(PaletteEntry {
+        label: if state.show_permission_rules { "Hide permission rules" } else { "Manage permission rules" }.to_string(),
+        command: PaletteCommand::ManagePermissionRules,
+    });
+
+    entries.push(PaletteEntry {
+        label: if state.show_permission_audit { "Hide permission log" } else { "Show permission log" }.to_string(),
+        command: PaletteCommand::ShowPermissionAudit,
+    });
+
+    let rules = state.permission_policy.len();
+    if rules > 0 {
+        entries.push(PaletteEntry {
+            label: format!("Forget permission rules ({})", rules),
+            command: PaletteCommand::ForgetPermissionRules,
+        });
+    }
+
+    entries.push(PaletteEntry {
+        label: "Import transcripts from Claude Code or Markdown".to_string(),
+        command: PaletteCommand::ImportTranscripts,
+    });
+
+    entries.push(PaletteEntry {
+        label: "Generate weekly digest".to_string(),
+        command: PaletteCommand::GenerateDigest { narrate: false },
+    });
+    entries.push(PaletteEntry {
+        label: "Generate weekly digest, narrated by Wake".to_string(),
+        command: PaletteCommand::GenerateDigest { narrate: true },
+    });
+
+    entries.push(PaletteEntry {
+        label: "Open settings".to_string(),
+        command: PaletteCommand::OpenSettings,
+    });
+
+    for backup in &state.backups {
+        entries.push(PaletteEntry {
+            label: format!("Restore labels and permissions from {}", backup.date),
+            command: PaletteCommand::RestoreBackup(backup.date),
+        });
+    }
+
+    for plugin in &state.plugins {
+        for command in &plugin.manifest.commands {
+            entries.push(PaletteEntry {
+                label: format!("{}: {}", plugin.manifest.name, command.label),
+                command: PaletteCommand::Plugin {
+                    plugin: plugin.manifest.name.clone(),
+                    command: command.id.clone(),
+                },
+            });
+        }
+    }
+
+    entries
+}
+
+/// Commands matching the query, best match first
+pub fn filter(state: &AppState, query: &str) -> Vec<PaletteEntry> {
+    let mut scored: Vec<(i32, PaletteEntry)> = commands(state)
+        .into_iter()
+        .filter_map(|entry| Some((fuzzy_score(query, &entry.label)?, entry)))
+        .collect();
+
+    // Stable sort keeps the natural order for equal scores
+    scored.sort_by_key(|(score, _)| std::cmp::Reverse(*score));
+    scored.into_iter().map(|(_, entry)| entry).collect()
+}
+
+/// Subsequence match with bonuses for consecutive characters and word starts
+///
+/// Returns `None` when the query doesn't match at all.
+pub fn fuzzy_score(query: &str, candidate: &str) -> Option<i32> {
+    let query: Vec<char> = query.to_lowercase().chars().filter(|c| !c.is_whitespace()).collect();
+    if query.is_empty() {
+        return Some(0);
+    }
+
+    let candidate: Vec<char> = candidate.to_lowercase().chars().collect();
+    let mut score = 0;
+    let mut query_idx = 0;
+    let mut previous_match: Option<usize> = None;
+
+    for (idx, ch) in candidate.iter().enumerate() {
+        if query_idx == query.len() {
+            break;
+        }
+        if *ch != query[query_idx] {
+            continue;
+        }
+
+        score += 1;
+        if previous_match == Some(idx.wrapping_sub(1)) {
+            score += 5;
+        }
+        if idx == 0 || !candidate[idx - 1].is_alphanumeric() {
+            score += 3;
+        }
+
+        previous_match = Some(idx);
+        query_idx += 1;
+    }
+
+    if query_idx == query.len() {
+        // Prefer shorter labels when everything else is equal
+        Some(score * 100 - candidate.len() as i32)
+    } else {
+        None
+    }
+}
@@ -0,0 +1,457 @@
+use bicky_editor_core::diff::{self, DiffLine, LineKind};
+use bicky_editor_core::Action as EditorAction;
+use iced::widget::{button, column, container, text, Column};
+use iced::{Element, Length, Theme};
+use serde_json::Value;
+
+use crate::api::ApiError;
+use crate::permission_audit::{self, AuditEntry, Resolver};
+use crate::permission_policy::{self, Decision, Request, Rule};
+use crate::state::{self, Action, Conversation};
+use crate::types::{LegacyMessage, MessageError, MessageStatus, ToolPermissionRequest};
+use crate::{diff_view, fonts, theme, BickyApp, Message};
+use iced::Task as Command;
+use std::collections::HashSet;
+use std::time::Duration;
+
+// Enough to judge a change without the card taking over the conversation
+const MAX_PREVIEW_LINES: usize = 200;
+const MAX_FIELD_CHARS: usize = 300;
+
+// The agent is blocked until an answer lands, so keep at it for a while
+pub const MAX_RESPONSE_ATTEMPTS: u32 = 5;
+
+// Arguments that get their own rendering rather than a plain field
+const RENDERED_KEYS: [&str; 8] = ["command", "old_string", "new_string", "edits", "content", "diff", "patch", "file_path"];
+
+/// What a tool is about to do, in the form that's easiest to judge
+#[derive(Debug, Clone, PartialEq)]
+pub enum ToolPreview {
+    /// A shell command
+    Command(String),
+    /// Changes to a file
+    Diff { path: Option<String>, lines: Vec<DiffLine> },
+    /// Arguments with no special rendering
+    Fields(Vec<(String, String)>),
+}
+
+fn line(kind: LineKind, text: &str) -> DiffLine {
+    DiffLine {
+        kind,
+        text: text.to_string(),
+    }
+}
+
+// Replacing one string with another, shown as every old line going and every new one arriving
+fn edit_lines(old: &str, new: &str) -> Vec<DiffLine> {
+    old.lines()
+        .map(|l| line(LineKind::Removed, l))
+        .chain(new.lines().map(|l| line(LineKind::Added, l)))
+        .collect()
+}
+
+fn field_text(value: &Value) -> String {
+    let text = match value {
+        Value::String(s) => s.clone(),
+        other => other.to_string(),
+    };
+    if text.chars().count() > MAX_FIELD_CHARS {
+        format!("{}…", text.chars().take(MAX_FIELD_CHARS).collect::<String>())
+    } else {
+        text
+    }
+}
+
+/// Break a tool's input into the parts worth showing before approving it
+pub fn preview(input: &Value) -> Vec<ToolPreview> {
+    let Some(args) = input.as_object() else {
+        return vec![ToolPreview::Fields(vec![("input".to_string(), field_text(input))])];
+    };
+    let get = |key: &str| args.get(key).and_then(Value::as_str);
+    let path = get("file_path").or_else(|| get("path")).map(str::to_string);
+
+    let mut parts = Vec::new();
+
+    if let Some(command) = get("command") {
+        parts.push(ToolPreview::Command(command.to_string()));
+    }
+
+    if let (Some(old), Some(new)) = (get("old_string"), get("new_string")) {
+        parts.push(ToolPreview::Diff { path: path.clone(), lines: edit_lines(old, new) });
+    } else if let Some(edits) = args.get("edits").and_then(Value::as_array) {
+        let mut lines = Vec::new();
+        for (idx, edit) in edits.iter().enumerate() {
+            if idx > 0 {
+                lines.push(line(LineKind::Context, "⋯"));
+            }
+            let field = |key: &str| edit.get(key).and_then(Value::as_str).unwrap_or("");
+            lines.extend(edit_lines(field("old_string"), field("new_string")));
+        }
+        parts.push(ToolPreview::Diff { path: path.clone(), lines });
+    } else if let Some(content) = get("content") {
+        // Writing a whole file, everything in it is new
+        parts.push(ToolPreview::Diff { path: path.clone(), lines: edit_lines("", content) });
+    }
+
+    if let Some(patch) = get("diff").or_else(|| get("patch")) {
+        for file in diff::parse(patch) {
+            let lines = file.hunks.into_iter().flat_map(|hunk| {
+                std::iter::once(line(LineKind::Context, &hunk.header)).chain(hunk.lines)
+            });
+            parts.push(ToolPreview::Diff { path: Some(file.path), lines: lines.collect() });
+        }
+    }
+
+    let shown_path = parts.iter().any(|part| matches!(part, ToolPreview::Diff { path: Some(_), .. }));
+    let fields: Vec<(String, String)> = args
+        .iter()
+        .filter(|(key, _)| !RENDERED_KEYS.contains(&key.as_str()) || (key.as_str() == "file_path" && !shown_path))
+        .filter(|(key, _)| !(key.as_str() == "path" && shown_path))
+        .map(|(key, value)| (key.clone(), field_text(value)))
+        .collect();
+    if !fields.is_empty() {
+        parts.push(ToolPreview::Fields(fields));
+    }
+
+    parts
+}
+
+/// How long to wait before sending an answer again, doubling from a second
+pub fn response_retry_delay(attempts: u32) -> Duration {
+    Duration::from_secs(1 << attempts.saturating_sub(1).min(4))
+}
+
+/// Identifies one permission request, so it's only answered once
+fn request_key(msg: &LegacyMessage, permission: &ToolPermissionRequest) -> String {
+    permission
+        .request_id
+        .clone()
+        .unwrap_or_else(|| format!("{}:{}", msg.id, permission.tool_name))
+}
+
+impl BickyApp {
+    /// Path of the worktree a conversation works in, rules are scoped by path since ids don't survive restarts
+    pub(crate) fn worktree_path(&self, conv: &Conversation) -> Option<String> {
+        let worktree_id = conv.worktree_id.as_deref()?;
+        self.state
+            .editor
+            .available_worktrees
+            .iter()
+            .find(|w| w.id == worktree_id)
+            .map(|w| w.path.clone())
+    }
+
+    /// The rule "always allow" would add for the active conversation's pending request
+    pub(crate) fn pending_allow_rule(&self, in_worktree: bool) -> Option<Rule> {
+        let conv = self.state.active_conversation.and_then(|idx| self.state.conversations.get(idx))?;
+        let permission = conv
+            .messages
+            .iter()
+            .find(|m| m.status == MessageStatus::WaitingForPermission)?
+            .pending_tool_permission
+            .as_ref()?;
+
+        Some(Rule {
+            worktree: if in_worktree { Some(self.worktree_path(conv)?) } else { None },
+            ..Rule::tool(permission.tool_name.clone())
+        })
+    }
+
+    /// Answer waiting requests the policy has a rule for, in any conversation
+    pub(crate) fn apply_permission_policy(&mut self) -> Command<Message> {
+        let mut waiting = HashSet::new();
+        let mut answers = Vec::new();
+
+        for conv in &self.state.conversations {
+            let worktree = self.worktree_path(conv);
+            for msg in conv.messages.iter().filter(|m| m.status == MessageStatus::WaitingForPermission) {
+                let Some(permission) = &msg.pending_tool_permission else {
+                    continue;
+                };
+                let key = request_key(msg, permission);
+                if !self.state.editor.auto_answered.contains(&key) {
+                    let request = Request::new(&permission.tool_name, permission.input.as_ref(), worktree.as_deref());
+                    if let Some((decision, rule)) = self.state.permission_policy.matching_rule(&request) {
+                        let approved = decision == Decision::Allow;
+                        let resolver = Resolver::Rule { rule: rule.summary() };
+                        if let Some(entry) = self.audit_entry(conv, msg, approved, resolver) {
+                            answers.push((key.clone(), conv.server.clone(), entry));
+                        }
+                    }
+                }
+                waiting.insert(key);
+            }
+        }
+
+        state::update(&mut self.state, Action::SyncPendingPermissions(waiting));
+
+        let commands: Vec<_> = answers
+            .into_iter()
+            .map(|(key, server, entry)| {
+                let (approved, interaction_id) = (entry.approved, entry.conversation_id.clone());
+                if approved {
+                    println!("[GUI] Auto-approving {} for {}", entry.tool, interaction_id);
+                } else {
+                    println!("[GUI] Auto-denying {} for {}", entry.tool, interaction_id);
+                    // Denials are surprising when they happen silently
+                    state::update(
+                        &mut self.state,
+                        Action::ShowNotification(state::Notification::Info(format!("Denied {} by permission rule", entry.tool))),
+                    );
+                }
+                state::update(&mut self.state, Action::Editor(EditorAction::PermissionAutoAnswered { key }));
+                self.record_permission(entry);
+                // Rules apply on every server, not just the one on screen
+                self.send_permission_response(server, interaction_id, approved)
+            })
+            .collect();
+        Command::batch(commands)
+    }
+
+    /// Answer the active conversation's waiting request from the permission card
+    pub(crate) fn answer_permission(&mut self, approved: bool) -> Command<Message> {
+        let Some(conv) = self.state.active_conversation.and_then(|idx| self.state.conversations.get(idx)) else {
+            return Command::none();
+        };
+        let Some(msg) = conv.messages.iter().find(|m| m.status == MessageStatus::WaitingForPermission) else {
+            return Command::none();
+        };
+
+        // Use the conversation ID which is the interaction ID
+        let interaction_id = conv.id.clone();
+        let server = conv.server.clone();
+        if let Some(entry) = self.audit_entry(conv, msg, approved, Resolver::User) {
+            self.record_permission(entry);
+        }
+        self.send_permission_response(server, interaction_id, approved)
+    }
+
+    /// Answer a conversation's waiting request for a hook, unless it's been answered already
+    pub(crate) fn answer_permission_from_hook(&mut self, server: &str, interaction_id: &str, approved: bool, script: &str) -> Command<Message> {
+        let Some(conv) = self.state.conversations.iter().find(|c| c.server == server && c.id == interaction_id) else {
+            return Command::none();
+        };
+        let Some(msg) = conv.messages.iter().find(|m| m.status == MessageStatus::WaitingForPermission) else {
+            return Command::none();
+        };
+        let Some(key) = msg.pending_tool_permission.as_ref().map(|permission| request_key(msg, permission)) else {
+            return Command::none();
+        };
+        if self.state.editor.auto_answered.contains(&key) || self.state.editor.permission_deliveries.contains_key(interaction_id) {
+            return Command::none();
+        }
+
+        let resolver = Resolver::Hook { command: script.to_string() };
+        let entry = self.audit_entry(conv, msg, approved, resolver);
+        println!("[GUI] Hook {} {} the request in {}", script, if approved { "approved" } else { "denied" }, interaction_id);
+        state::update(&mut self.state, Action::Editor(EditorAction::PermissionAutoAnswered { key }));
+        if let Some(entry) = entry {
+            self.record_permission(entry);
+        }
+        self.send_permission_response(server.to_string(), interaction_id.to_string(), approved)
+    }
+
+    fn send_permission_response(&mut self, server: String, interaction_id: String, approved: bool) -> Command<Message> {
+        state::update(
+            &mut self.state,
+            Action::Editor(EditorAction::PermissionResponseQueued { interaction_id: interaction_id.clone(), server, approved }),
+        );
+        self.deliver_permission_response(interaction_id, Duration::ZERO)
+    }
+
+    fn deliver_permission_response(&self, interaction_id: String, delay: Duration) -> Command<Message> {
+        let Some(delivery) = self.state.editor.permission_deliveries.get(&interaction_id) else {
+            return Command::none();
+        };
+        let api = self.api_for(&delivery.server);
+        let approved = delivery.approved;
+        let id = interaction_id.clone();
+        self.tasks.run(
+            "respond to permission",
+            async move {
+                tokio::time::sleep(delay).await;
+                api.respond_to_permission(&id, approved).await
+            },
+            move |result| Message::PermissionResponseSent(interaction_id.clone(), result),
+        )
+    }
+
+    /// Retry answers that didn't go through, then give up and ask the server where things stand
+    pub(crate) fn permission_response_sent(&mut self, interaction_id: String, result: Result<(), ApiError>) -> Command<Message> {
+        let Some(delivery) = self.state.editor.permission_deliveries.get(&interaction_id) else {
+            return Command::none();
+        };
+        let e = match result {
+            Ok(()) => {
+                state::update(&mut self.state, Action::Editor(EditorAction::PermissionResponseDelivered { interaction_id }));
+                return Command::none();
+            }
+            Err(e) => e,
+        };
+
+        let attempts = delivery.attempts;
+        if e.is_retryable() && attempts < MAX_RESPONSE_ATTEMPTS {
+            let delay = response_retry_delay(attempts);
+            eprintln!("[GUI] Failed to send permission response for {}: {}, retrying in {:?}", interaction_id, e, delay);
+            state::update(
+                &mut self.state,
+                Action::Editor(EditorAction::PermissionResponseRetrying { interaction_id: interaction_id.clone() }),
+            );
+            return self.deliver_permission_response(interaction_id, delay);
+        }
+
+        eprintln!("[GUI] Gave up sending permission response for {} after {} attempts: {}", interaction_id, attempts, e);
+        let server = delivery.server.clone();
+        state::update(
+            &mut self.state,
+            Action::Editor(EditorAction::PermissionResponseFailed { interaction_id: interaction_id.clone(), error: MessageError::from(e) }),
+        );
+        // The server may have taken the answer after all, or moved on without it
+        self.fetch_interaction(server, interaction_id)
+    }
+
+    /// Send a failed answer again, the card's retry button
+    pub(crate) fn retry_permission_response(&mut self, interaction_id: String) -> Command<Message> {
+        let Some(delivery) = self.state.editor.permission_deliveries.get(&interaction_id) else {
+            return Command::none();
+        };
+        println!("[GUI] Retrying permission response for {}", interaction_id);
+        let (server, approved) = (delivery.server.clone(), delivery.approved);
+        self.send_permission_response(server, interaction_id, approved)
+    }
+
+    /// How the answer to a conversation's request is getting on, once it has run into trouble
+    pub(crate) fn build_permission_delivery(&self, conv: &Conversation) -> Option<Element<'_, Message>> {
+        let delivery = self
+            .state
+            .editor
+            .permission_deliveries
+            .get(&conv.id)
+            .filter(|delivery| delivery.server == conv.server)?;
+
+        let Some(error) = &delivery.failed else {
+            return (delivery.attempts > 1).then(|| {
+                text(format!(
+                    "Couldn't reach the server, retrying ({} of {})…",
+                    delivery.attempts, MAX_RESPONSE_ATTEMPTS
+                ))
+                .size(12)
+                .font(fonts::mono())
+                .color(theme::colors().text_dim)
+                .into()
+            });
+        };
+
+        Some(
+            container(
+                column![
+                    text("⚠ Failed to deliver response").size(12).font(fonts::bold()).color(theme::colors().error),
+                    text(&error.detail).size(12).font(fonts::mono()).color(theme::colors().text),
+                ]
+                .push_maybe(error.guidance.as_ref().map(|guidance| {
+                    text(guidance).size(11).font(fonts::mono()).color(theme::colors().text_dim)
+                }))
+                .push(
+                    button(text("Retry").size(12).font(fonts::mono()))
+                        .on_press(Message::RetryPermissionResponse(conv.id.clone()))
+                        .padding(6)
+                        .style(theme::secondary_button),
+                )
+                .spacing(6),
+            )
+            .padding(10)
+            .width(Length::Fill)
+            .style(theme::error_banner)
+            .into(),
+        )
+    }
+
+    fn audit_entry(&self, conv: &Conversation, msg: &LegacyMessage, approved: bool, resolver: Resolver) -> Option<AuditEntry> {
+        let permission = msg.pending_tool_permission.as_ref()?;
+        let worktree = self.worktree_path(conv);
+        let request = Request::new(&permission.tool_name, permission.input.as_ref(), worktree.as_deref());
+        let now = self.state.clock.utc();
+
+        Some(AuditEntry {
+            conversation_id: conv.id.clone(),
+            tool: permission.tool_name.clone(),
+            target: request.command.or(request.path),
+            requested_at: self.state.editor.permission_requested.get(&request_key(msg, permission)).copied().unwrap_or(now),
+            resolved_at: now,
+            approved,
+            resolver,
+        })
+    }
+
+    // Keep the entry in memory even when the log file can't be written
+    fn record_permission(&mut self, entry: AuditEntry) {
+        if let Err(e) = permission_audit::append(&entry) {
+            eprintln!("[GUI] Failed to write permission audit log: {}", e);
+        }
+        state::update(&mut self.state, Action::PermissionResolved(entry));
+    }
+
+    // Persist the policy, failures only cost the rule surviving a restart
+    pub(crate) fn save_permission_policy(&mut self) {
+        match permission_policy::save(&self.state.permission_policy) {
+            Ok(path) => println!("[GUI] Saved permission policy to {}", path.display()),
+            Err(e) => state::update(
+                &mut self.state,
+                Action::ShowNotification(state::Notification::Error(format!("Couldn't save permissions: {}", e))),
+            ),
+        }
+    }
+
+    /// The tool's arguments on the permission card, diffs colorized and commands as code
+    pub(crate) fn build_tool_input(&self, input: &Value) -> Element<'_, Message> {
+        let tint = self.diff_tint();
+        let mut content = column![].spacing(8);
+
+        for part in preview(input) {
+            let element: Element<'_, Message> = match part {
+                ToolPreview::Command(command) => container(
+                    text(format!("$ {}", command)).size(13).font(fonts::mono()).color(theme::colors().text),
+                )
+                .padding(8)
+                .width(Length::Fill)
+                .style(theme::code_container)
+                .into(),
+                ToolPreview::Diff { path, lines } => {
+                    let mut block = column![].spacing(4);
+                    if let Some(path) = path {
+                        block = block.push(text(path).size(12).font(fonts::mono()).color(theme::colors().text_dim));
+                    }
+
+                    let hidden = lines.len().saturating_sub(MAX_PREVIEW_LINES);
+                    let mut rows = Column::with_children(lines.into_iter().take(MAX_PREVIEW_LINES).map(|line| {
+                        let (sign, color, background) = diff_view::line_colors(line.kind, tint);
+                        container(text(format!("{}{}", sign, line.text)).size(12).font(fonts::mono()).color(color))
+                            .width(Length::Fill)
+                            .style(move |_theme: &Theme| container::Style {
+                                background: background.map(iced::Background::Color),
+                                ..Default::default()
+                            })
+                            .into()
+                    }));
+                    if hidden > 0 {
+                        rows = rows.push(
+                            text(format!("… {} more lines", hidden)).size(12).font(fonts::mono()).color(theme::colors().text_dim),
+                        );
+                    }
+
+                    block
+                        .push(container(rows).padding(6).width(Length::Fill).style(theme::code_container))
+                        .into()
+                }
+                ToolPreview::Fields(fields) => Column::with_children(fields.into_iter().map(|(key, value)| {
+                    text(format!("{}: {}", key, value)).size(12).font(fonts::mono()).color(theme::colors().text_dim).into()
+                }))
+                .spacing(2)
+                .into(),
+            };
+            content = content.push(element);
+        }
+
+        content.into()
+    }
+}
@@ -0,0 +1,190 @@
+use chrono::{DateTime, Utc};
+use iced::widget::{button, column, container, row, scrollable, text, Column, Space};
+use iced::{Border, Element, Length, Theme};
+use serde::{Deserialize, Serialize};
+use std::io::Write;
+use std::path::PathBuf;
+
+use crate::{config, fonts, format, jobs, scrolling, theme, BickyApp, Message};
+
+// One JSON entry per line next to Mind.toml, appended as requests are answered
+pub(crate) const AUDIT_FILE: &str = "PermissionAudit.jsonl";
+// Older entries stay in the file but aren't loaded
+const MAX_LOADED_ENTRIES: usize = 5_000;
+
+pub(crate) const PANEL_WIDTH: f32 = 320.0;
+
+/// Who answered a permission request
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "by", rename_all = "snake_case")]
+pub enum Resolver {
+    User,
+    /// A permission rule, by its summary
+    Rule { rule: String },
+    /// A `permission_requested` hook, by its command
+    Hook { command: String },
+}
+
+/// One answered permission request
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AuditEntry {
+    pub conversation_id: String,
+    pub tool: String,
+    /// The command or path the tool asked for, when it named one
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub target: Option<String>,
+    pub requested_at: DateTime<Utc>,
+    pub resolved_at: DateTime<Utc>,
+    pub approved: bool,
+    #[serde(flatten)]
+    pub resolver: Resolver,
+}
+
+fn audit_path() -> PathBuf {
+    config::find_config_file(AUDIT_FILE)
+        .or_else(|| config::find_config_file("Mind.toml").map(|mind| mind.with_file_name(AUDIT_FILE)))
+        .unwrap_or_else(|| PathBuf::from(AUDIT_FILE))
+}
+
+/// The most recent entries, skipping lines that don't parse
+pub fn load() -> Vec<AuditEntry> {
+    let Some(path) = config::find_config_file(AUDIT_FILE) else {
+        return Vec::new();
+    };
+
+    let content = match std::fs::read_to_string(&path) {
+        Ok(content) => content,
+        Err(e) => {
+            eprintln!("[GUI] Failed to read {}: {}", path.display(), e);
+            return Vec::new();
+        }
+    };
+
+    let mut entries: Vec<AuditEntry> = content
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .filter_map(|line| match serde_json::from_str(line) {
+            Ok(entry) => Some(entry),
+            Err(e) => {
+                eprintln!("[GUI] Skipping bad audit entry in {}: {}", path.display(), e);
+                None
+            }
+        })
+        .collect();
+    let skip = entries.len().saturating_sub(MAX_LOADED_ENTRIES);
+    entries.drain(..skip);
+    entries
+}
+
+pub fn append(entry: &AuditEntry) -> Result<(), String> {
+    let line = serde_json::to_string(entry).map_err(|e| e.to_string())?;
+    let mut file = std::fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(audit_path())
+        .map_err(|e| e.to_string())?;
+    writeln!(file, "{}", line).map_err(|e| e.to_string())
+}
+
+impl BickyApp {
+    /// Answered permission requests for the active conversation, newest first
+    pub(crate) fn build_audit_panel(&self) -> Element<'_, Message> {
+        let conversation_id = self
+            .state
+            .active_conversation
+            .and_then(|idx| self.state.conversations.get(idx))
+            .map(|conv| conv.id.as_str());
+        let entries: Vec<&AuditEntry> = self
+            .state
+            .permission_audit
+            .iter()
+            .rev()
+            .filter(|entry| Some(entry.conversation_id.as_str()) == conversation_id)
+            .collect();
+
+        let mut list = column![
+            row![
+                text("Permission log").size(14).font(fonts::bold()),
+                Space::with_width(Length::Fill),
+                button(text("×").size(14).font(fonts::mono()))
+                    .on_press(Message::TogglePermissionAudit)
+                    .padding(0)
+                    .style(theme::icon_button),
+            ]
+            .align_y(iced::Alignment::Center)
+        ]
+        .spacing(10);
+
+        if entries.is_empty() {
+            list = list.push(
+                text("No permission requests answered in this conversation")
+                    .size(12)
+                    .font(fonts::mono())
+                    .color(theme::colors().text_dim),
+            );
+        }
+
+        let now = self.state.clock.utc();
+        list = list.push(Column::with_children(entries.into_iter().map(|entry| {
+            let (verdict, color) = if entry.approved {
+                ("✓ approved", theme::colors().success)
+            } else {
+                ("✗ denied", theme::colors().error)
+            };
+            let resolver = match &entry.resolver {
+                Resolver::User => "by you".to_string(),
+                Resolver::Rule { rule } => format!("by rule {}", rule),
+                Resolver::Hook { command } => format!("by hook {}", command),
+            };
+            let waited = (entry.resolved_at - entry.requested_at).to_std().unwrap_or_default();
+
+            let mut details = column![
+                row![
+                    text(&entry.tool).size(13).font(fonts::mono()),
+                    text(verdict).size(12).font(fonts::mono()).color(color),
+                ]
+                .spacing(8),
+            ]
+            .spacing(4);
+            if let Some(target) = &entry.target {
+                details = details.push(text(target).size(11).font(fonts::mono()).color(theme::colors().text));
+            }
+            details = details.push(
+                text(format!(
+                    "{} • {} • answered after {}",
+                    resolver,
+                    format::locale().timestamp(entry.resolved_at, now),
+                    jobs::format_elapsed(waited),
+                ))
+                .size(11)
+                .font(fonts::mono())
+                .color(theme::colors().text_dim),
+            );
+
+            container(details)
+                .padding(8)
+                .width(Length::Fill)
+                .style(|_theme: &Theme| container::Style {
+                    background: Some(iced::Background::Color(theme::colors().surface_light)),
+                    border: Border {
+                        color: theme::colors().border,
+                        width: 1.0,
+                        radius: 6.0.into(),
+                    },
+                    ..Default::default()
+                })
+                .into()
+        })).spacing(8));
+
+        container(
+            scrollable(list.padding(16))
+                .direction(scrolling::scrollbar())
+                .style(theme::scrollbar)
+                .height(Length::Fill),
+        )
+            .width(Length::Fixed(PANEL_WIDTH))
+            .height(Length::Fill)
+            .style(theme::sidebar_container)
+            .into()
+    }
+}
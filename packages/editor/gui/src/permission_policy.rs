@@ -0,0 +1,231 @@
+use iced::widget::{button, column, container, row, scrollable, text, text_input, Column, Space};
+use iced::{Border, Element, Length, Theme};
+use std::path::PathBuf;
+
+pub use bicky_editor_core::policy::{CommandPattern, Decision, Policy, Request, Rule};
+
+use crate::{config, fonts, scrolling, theme, BickyApp, Message};
+
+// Lives next to Mind.toml, and can be edited by hand
+pub(crate) const POLICY_FILE: &str = "Permissions.toml";
+
+pub(crate) const PANEL_WIDTH: f32 = 320.0;
+
+pub fn load() -> Policy {
+    let Some(path) = config::find_config_file(POLICY_FILE) else {
+        return Policy::default();
+    };
+
+    match std::fs::read_to_string(&path).map(|content| toml::from_str::<Policy>(&content)) {
+        Ok(Ok(policy)) => policy,
+        Ok(Err(e)) => {
+            eprintln!("[GUI] Failed to parse {}: {}", path.display(), e);
+            Policy::default()
+        }
+        Err(e) => {
+            eprintln!("[GUI] Failed to read {}: {}", path.display(), e);
+            Policy::default()
+        }
+    }
+}
+
+/// Write the policy next to Mind.toml, or here when there isn't one
+pub fn save(policy: &Policy) -> Result<PathBuf, String> {
+    let path = config::find_config_file(POLICY_FILE)
+        .or_else(|| config::find_config_file("Mind.toml").map(|mind| mind.with_file_name(POLICY_FILE)))
+        .unwrap_or_else(|| PathBuf::from(POLICY_FILE));
+
+    let contents = toml::to_string(policy).map_err(|e| e.to_string())?;
+    std::fs::write(&path, contents).map_err(|e| e.to_string())?;
+    Ok(path)
+}
+
+/// Fields of the add rule form
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RuleField {
+    Tool,
+    Paths,
+    Command,
+}
+
+/// A rule being written in the rules panel
+#[derive(Debug, Clone, PartialEq)]
+pub struct RuleDraft {
+    pub decision: Decision,
+    pub tool: String,
+    /// Comma separated globs
+    pub paths: String,
+    pub command: String,
+    pub error: Option<String>,
+}
+
+impl Default for RuleDraft {
+    fn default() -> Self {
+        Self {
+            decision: Decision::Allow,
+            tool: String::new(),
+            paths: String::new(),
+            command: String::new(),
+            error: None,
+        }
+    }
+}
+
+impl RuleDraft {
+    pub fn set(&mut self, field: RuleField, value: String) {
+        match field {
+            RuleField::Tool => self.tool = value,
+            RuleField::Paths => self.paths = value,
+            RuleField::Command => self.command = value,
+        }
+        self.error = None;
+    }
+
+    pub fn build(&self) -> Result<Rule, String> {
+        let tool = self.tool.trim();
+        if tool.is_empty() {
+            return Err("Tool name is required, use * for any tool".to_string());
+        }
+
+        let command = self.command.trim();
+        Ok(Rule {
+            paths: self
+                .paths
+                .split(',')
+                .map(str::trim)
+                .filter(|glob| !glob.is_empty())
+                .map(str::to_string)
+                .collect(),
+            command: if command.is_empty() {
+                None
+            } else {
+                Some(CommandPattern::new(command).map_err(|e| format!("Invalid command regex: {}", e))?)
+            },
+            ..Rule::tool(tool)
+        })
+    }
+}
+
+fn decision_label(decision: Decision) -> &'static str {
+    match decision {
+        Decision::Allow => "Allow",
+        Decision::Deny => "Deny",
+    }
+}
+
+impl BickyApp {
+    /// Active rules with remove buttons, and a form for adding more
+    pub(crate) fn build_rules_panel(&self) -> Element<'_, Message> {
+        let policy = &self.state.permission_policy;
+        let mut list = column![
+            row![
+                text("Permission rules").size(14).font(fonts::bold()),
+                Space::with_width(Length::Fill),
+                button(text("×").size(14).font(fonts::mono()))
+                    .on_press(Message::TogglePermissionRules)
+                    .padding(0)
+                    .style(theme::icon_button),
+            ]
+            .align_y(iced::Alignment::Center),
+            text("Deny rules win over allow rules. Anything unmatched asks first.")
+                .size(11)
+                .font(fonts::mono())
+                .color(theme::colors().text_dim),
+        ]
+        .spacing(10);
+
+        for decision in [Decision::Deny, Decision::Allow] {
+            let rules = policy.rules(decision);
+            list = list.push(
+                text(format!("{} ({})", decision_label(decision), rules.len()))
+                    .size(12)
+                    .font(fonts::bold())
+                    .color(match decision {
+                        Decision::Allow => theme::colors().success,
+                        Decision::Deny => theme::colors().error,
+                    }),
+            );
+            list = list.push(Column::with_children(rules.iter().enumerate().map(|(idx, rule)| {
+                row![
+                    text(rule.summary()).size(12).font(fonts::mono()).width(Length::Fill),
+                    button(text("Remove").size(11).font(fonts::mono()))
+                        .on_press(Message::RemovePermissionRule(decision, idx))
+                        .padding(4)
+                        .style(theme::secondary_button),
+                ]
+                .spacing(6)
+                .align_y(iced::Alignment::Center)
+                .into()
+            })).spacing(6));
+        }
+
+        list = list.push(Space::with_height(4)).push(self.build_rule_form());
+
+        container(
+            scrollable(list.padding(16))
+                .direction(scrolling::scrollbar())
+                .style(theme::scrollbar)
+                .height(Length::Fill),
+        )
+            .width(Length::Fixed(PANEL_WIDTH))
+            .height(Length::Fill)
+            .style(theme::sidebar_container)
+            .into()
+    }
+
+    fn build_rule_form(&self) -> Element<'_, Message> {
+        let draft = &self.state.rule_draft;
+        let field = |label: &'static str, placeholder: &'static str, value: &str, field: RuleField| {
+            column![
+                text(label).size(11).font(fonts::mono()).color(theme::colors().text_dim),
+                text_input(placeholder, value)
+                    .on_input(move |value| Message::RuleDraftChanged(field, value))
+                    .on_submit(Message::AddPermissionRule)
+                    .padding(6)
+                    .size(12)
+                    .font(fonts::mono())
+                    .style(theme::input_style),
+            ]
+            .spacing(4)
+        };
+        let toggle = |decision: Decision| {
+            button(text(decision_label(decision)).size(12).font(fonts::mono()))
+                .on_press(Message::RuleDraftDecision(decision))
+                .padding(6)
+                .style(if draft.decision == decision { theme::primary_button_style } else { theme::secondary_button })
+        };
+
+        let mut form = column![
+            text("New rule").size(12).font(fonts::bold()),
+            row![toggle(Decision::Allow), toggle(Decision::Deny)].spacing(6),
+            field("Tool", "Bash, mcp__*", &draft.tool, RuleField::Tool),
+            field("Paths (optional)", "src/**, *.md", &draft.paths, RuleField::Paths),
+            field("Command regex (optional)", "^cargo (build|test)", &draft.command, RuleField::Command),
+        ]
+        .spacing(8);
+
+        if let Some(error) = &draft.error {
+            form = form.push(text(error).size(11).font(fonts::mono()).color(theme::colors().error));
+        }
+        form = form.push(
+            button(text("Add rule").size(12).font(fonts::mono()))
+                .on_press(Message::AddPermissionRule)
+                .padding(6)
+                .style(theme::secondary_button),
+        );
+
+        container(form)
+            .padding(10)
+            .width(Length::Fill)
+            .style(|_theme: &Theme| container::Style {
+                background: Some(iced::Background::Color(theme::colors().surface_light)),
+                border: Border {
+                    color: theme::colors().border,
+                    width: 1.0,
+                    radius: 6.0.into(),
+                },
+                ..Default::default()
+            })
+            .into()
+    }
+}
@@ -0,0 +1,130 @@
+use iced::widget::{button, column, container, row, text, text_input, Row};
+use iced::{Border, Element, Length, Theme};
+use serde_json::{json, Value};
+
+use crate::state::Conversation;
+use crate::{fonts, theme, BickyApp, Message};
+
+// Pinned messages are named by the start of what was asked
+const LABEL_CHARS: usize = 32;
+
+/// Something sent along as context with every message in a conversation
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Pin {
+    /// A message and its response, by message id
+    Message(String),
+    /// A file path, relative to the conversation's worktree
+    File(String),
+}
+
+/// How the pin is shown in the strip
+pub fn label(conv: &Conversation, pin: &Pin) -> String {
+    match pin {
+        Pin::File(path) => path.clone(),
+        Pin::Message(id) => match conv.messages.iter().find(|m| &m.id == id) {
+            Some(msg) => {
+                let first = msg.content.lines().next().unwrap_or_default();
+                let ellipsis = if first.chars().count() > LABEL_CHARS { "…" } else { "" };
+                format!("“{}{}”", first.chars().take(LABEL_CHARS).collect::<String>(), ellipsis)
+            }
+            None => format!("Message {}", id),
+        },
+    }
+}
+
+/// The request's metadata with the conversation's pins added as `pinnedContext`
+///
+/// Pinned messages carry what we have of them, the server can look up any we
+/// no longer hold by id.
+pub fn with_pins(conv: &Conversation, metadata: Option<Value>) -> Option<Value> {
+    if conv.pins.is_empty() {
+        return metadata;
+    }
+    let pinned: Vec<Value> = conv
+        .pins
+        .iter()
+        .map(|pin| match pin {
+            Pin::Message(id) => {
+                let msg = conv.messages.iter().find(|m| &m.id == id);
+                json!({
+                    "type": "message",
+                    "messageId": id,
+                    "content": msg.map(|m| m.content.as_str()),
+                    "response": msg.and_then(|m| m.response.as_deref()),
+                })
+            }
+            Pin::File(path) => json!({ "type": "file", "path": path }),
+        })
+        .collect();
+
+    let mut metadata = metadata.unwrap_or_else(|| json!({}));
+    if let Some(object) = metadata.as_object_mut() {
+        object.insert("pinnedContext".to_string(), Value::Array(pinned));
+    }
+    Some(metadata)
+}
+
+impl BickyApp {
+    /// The conversation's pins under the header, only a count while collapsed
+    pub(crate) fn build_pin_strip(&self, conv_idx: usize, conv: &Conversation) -> Option<Element<'_, Message>> {
+        let expanded = self.state.pins_expanded;
+        if conv.pins.is_empty() && !expanded {
+            return None;
+        }
+
+        let toggle = button(
+            text(format!("{} Pinned context ({})", if expanded { "▾" } else { "▸" }, conv.pins.len()))
+                .size(12)
+                .font(fonts::mono()),
+        )
+        .on_press(Message::SetPinsExpanded(!expanded))
+        .padding(0)
+        .style(theme::icon_button);
+
+        let mut strip = column![toggle].spacing(8);
+        if expanded {
+            let pins = Row::with_children(conv.pins.iter().map(|pin| {
+                let icon = match pin {
+                    Pin::Message(_) => "💬",
+                    Pin::File(_) => "📄",
+                };
+                container(
+                    row![
+                        text(format!("{} {}", icon, label(conv, pin))).size(11).font(fonts::mono()),
+                        button(text("×").size(11).font(fonts::mono()))
+                            .on_press(Message::TogglePin(conv_idx, pin.clone()))
+                            .padding(0)
+                            .style(theme::icon_button),
+                    ]
+                    .spacing(6)
+                    .align_y(iced::Alignment::Center),
+                )
+                .padding([2, 8])
+                .style(|_theme: &Theme| container::Style {
+                    background: Some(iced::Background::Color(theme::colors().surface_light)),
+                    border: Border {
+                        color: theme::colors().border,
+                        width: 1.0,
+                        radius: 10.0.into(),
+                    },
+                    ..Default::default()
+                })
+                .into()
+            }))
+            .spacing(6)
+            .wrap();
+            strip = strip.push(pins).push(
+                text_input("Pin a file, e.g. src/main.rs", &self.state.pin_draft)
+                    .on_input(Message::PinDraftChanged)
+                    .on_submit(Message::PinDraftedFile(conv_idx))
+                    .padding(6)
+                    .size(12)
+                    .font(fonts::mono())
+                    .style(theme::input_style)
+                    .width(Length::Fixed(320.0)),
+            );
+        }
+
+        Some(container(strip).padding([8, 12]).width(Length::Fill).into())
+    }
+}
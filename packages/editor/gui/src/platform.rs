@@ -0,0 +1,98 @@
+use std::path::PathBuf;
+
+const APP_DIR: &str = "Bicamrl";
+const PORT_FILE: &str = ".bicamrl-port";
+
+/// Operating systems we behave differently on
+///
+/// Everything here takes the OS and environment as arguments, so each
+/// platform's behaviour can be checked from any of them.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Os {
+    Mac,
+    Windows,
+    /// Linux and the BSDs, anything following the XDG conventions
+    Unix,
+}
+
+impl Os {
+    pub fn current() -> Self {
+        if cfg!(target_os = "macos") {
+            Os::Mac
+        } else if cfg!(target_os = "windows") {
+            Os::Windows
+        } else {
+            Os::Unix
+        }
+    }
+
+    /// Whether `ctrl` in a key binding is the Control key rather than the command key
+    ///
+    /// Only macOS has a separate command key, elsewhere both mean Ctrl.
+    pub fn has_command_key(self) -> bool {
+        self == Os::Mac
+    }
+
+    /// Where user config goes, `None` without a home directory
+    pub fn config_dir(self, env: impl Fn(&str) -> Option<String>) -> Option<PathBuf> {
+        let home = || env(if self == Os::Windows { "USERPROFILE" } else { "HOME" }).map(PathBuf::from);
+        match self {
+            Os::Mac => Some(home()?.join("Library/Application Support").join(APP_DIR)),
+            Os::Windows => env("APPDATA")
+                .map(PathBuf::from)
+                .or_else(|| Some(home()?.join("AppData").join("Roaming")))
+                .map(|dir| dir.join(APP_DIR)),
+            Os::Unix => xdg_dir(&env, "XDG_CONFIG_HOME")
+                .or_else(|| Some(home()?.join(".config")))
+                .map(|dir| dir.join(APP_DIR.to_lowercase())),
+        }
+    }
+
+    /// Where files we write for ourselves go, like the server's port file
+    pub fn data_dir(self, env: impl Fn(&str) -> Option<String>) -> Option<PathBuf> {
+        let home = || env(if self == Os::Windows { "USERPROFILE" } else { "HOME" }).map(PathBuf::from);
+        match self {
+            Os::Mac => self.config_dir(env),
+            Os::Windows => env("LOCALAPPDATA")
+                .map(PathBuf::from)
+                .or_else(|| Some(home()?.join("AppData").join("Local")))
+                .map(|dir| dir.join(APP_DIR)),
+            Os::Unix => xdg_dir(&env, "XDG_DATA_HOME")
+                .or_else(|| Some(home()?.join(".local").join("share")))
+                .map(|dir| dir.join(APP_DIR.to_lowercase())),
+        }
+    }
+
+    /// Places the local server may have written its port to, most specific first
+    pub fn port_files(self, env: impl Fn(&str) -> Option<String>) -> Vec<PathBuf> {
+        // Next to the checkout when run from packages/editor/gui, then the user's data dir
+        let mut files: Vec<PathBuf> = [".", "../..", "../../.."]
+            .iter()
+            .map(|dir| PathBuf::from(dir).join(PORT_FILE))
+            .collect();
+        files.extend(self.data_dir(env).map(|dir| dir.join("port")));
+        files
+    }
+}
+
+// XDG says relative paths are invalid and should be ignored
+fn xdg_dir(env: &impl Fn(&str) -> Option<String>, var: &str) -> Option<PathBuf> {
+    env(var).filter(|path| path.starts_with('/')).map(PathBuf::from)
+}
+
+/// Read from the real environment
+pub fn env(var: &str) -> Option<String> {
+    std::env::var(var).ok().filter(|value| !value.is_empty())
+}
+
+pub fn config_dir() -> Option<PathBuf> {
+    Os::current().config_dir(env)
+}
+
+/// Port of the local server, from the first port file that has one
+pub fn local_server_port() -> Option<u16> {
+    Os::current()
+        .port_files(env)
+        .iter()
+        .find_map(|path| std::fs::read_to_string(path).ok()?.trim().parse::<u16>().ok())
+}
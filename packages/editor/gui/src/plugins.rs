@@ -0,0 +1,306 @@
+use bicky_editor_core::plugin::{Block, Context, Manifest, MessageContext, Outcome, Panel, Request, ToolCall};
+use iced::widget::{column, container, row, text, Column};
+use iced::{Element, Length};
+use iced::Task as Command;
+use serde::Serialize;
+use serde_json::Value;
+use std::io::Write;
+use std::process::Stdio;
+
+use crate::config::PluginSettings;
+use crate::state::{self, update, Action, AppState};
+use crate::types::{LegacyMessage, MessageStatus};
+use crate::{fonts, theme, BickyApp, Message};
+
+/// A configured plugin and what it said it does
+#[derive(Debug, Clone, PartialEq)]
+pub struct LoadedPlugin {
+    pub settings: PluginSettings,
+    pub manifest: Manifest,
+}
+
+/// Where a message stands with the plugins
+#[derive(Debug, Clone, PartialEq)]
+pub enum Rendering {
+    Pending,
+    /// Shown as text, no plugin took it or the one that did failed
+    Declined,
+    Blocks(Vec<Block>),
+}
+
+/// Ask a plugin something, its answer is JSON
+pub async fn ask(settings: PluginSettings, request: Request) -> Result<Value, String> {
+    let command = settings.command.clone();
+    let output = tokio::task::spawn_blocking(move || exchange(&settings.command, &settings.args, &request))
+        .await
+        .map_err(|e| e.to_string())??;
+    serde_json::from_slice(&output).map_err(|e| format!("{} answered with bad JSON: {}", command, e))
+}
+
+/// Run a program once with `input` as JSON on its stdin, gives what it printed
+///
+/// A program that fails gives what it printed on stderr instead.
+pub fn exchange(command: &str, args: &[String], input: &impl Serialize) -> Result<Vec<u8>, String> {
+    let mut child = std::process::Command::new(command)
+        .args(args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| format!("couldn't start {}: {}", command, e))?;
+
+    let input = serde_json::to_vec(input).map_err(|e| e.to_string())?;
+    if let Some(mut stdin) = child.stdin.take() {
+        // A program that answers without reading its input has closed the pipe
+        match stdin.write_all(&input) {
+            Err(e) if e.kind() != std::io::ErrorKind::BrokenPipe => return Err(e.to_string()),
+            _ => {}
+        }
+    }
+    let output = child.wait_with_output().map_err(|e| e.to_string())?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(match stderr.trim() {
+            "" => format!("{} exited with {}", command, output.status),
+            reason => reason.to_string(),
+        });
+    }
+    Ok(output.stdout)
+}
+
+/// Read a plugin's answer as what the request asked for
+pub fn parse<T: serde::de::DeserializeOwned>(answer: Result<Value, String>) -> Result<T, String> {
+    serde_json::from_value(answer?).map_err(|e| format!("unexpected answer: {}", e))
+}
+
+/// What the render hook is given, `None` until Wake has answered
+pub fn message_context(msg: &LegacyMessage) -> Option<MessageContext> {
+    let response = msg.response.as_ref().filter(|_| msg.status == MessageStatus::Completed)?;
+    let metadata = msg.metadata.as_ref();
+    let tool_calls = metadata
+        .and_then(|m| m.tool_calls.as_ref())
+        .into_iter()
+        .flatten()
+        .map(|call| ToolCall { name: call.name.clone(), arguments: call.arguments.clone() })
+        .collect();
+    Some(MessageContext {
+        prompt: msg.content.clone(),
+        response: response.clone(),
+        model: metadata.and_then(|m| m.model.clone()),
+        tool_calls,
+    })
+}
+
+/// Answered messages not yet offered to the plugins
+///
+/// Each comes with the first plugin that renders it, or `None` when none do.
+pub fn unrendered<'a>(state: &'a AppState, messages: &[LegacyMessage]) -> Vec<(String, Option<&'a LoadedPlugin>, MessageContext)> {
+    messages
+        .iter()
+        .filter(|msg| !state.plugin_renderings.contains_key(&msg.id))
+        .filter_map(|msg| {
+            let context = message_context(msg)?;
+            let plugin = state.plugins.iter().find(|plugin| plugin.manifest.renders(&context));
+            Some((msg.id.clone(), plugin, context))
+        })
+        .collect()
+}
+
+/// Where the user is, for panels and commands
+pub fn context(state: &AppState) -> Context {
+    let branch = state
+        .editor
+        .current_worktree
+        .as_ref()
+        .and_then(|worktree| worktree.branch.as_deref())
+        .map(|branch| branch.strip_prefix("refs/heads/").unwrap_or(branch).to_string());
+    let conversation_id = state
+        .active_conversation
+        .and_then(|idx| state.conversations.get(idx))
+        .map(|conv| conv.id.clone());
+    Context {
+        server: state.active_server.clone(),
+        conversation_id,
+        branch,
+    }
+}
+
+/// Lay out a plugin's blocks, `alpha` fades them in with the message
+pub fn blocks<'a>(blocks: &'a [Block], alpha: f32) -> Element<'a, Message> {
+    let colors = theme::colors();
+    let rows = blocks.iter().map(|block| -> Element<'a, Message> {
+        match block {
+            Block::Heading { text: heading } => text(heading).size(15).font(fonts::bold()).color(colors.text.scale_alpha(alpha)).into(),
+            Block::Text { text: body } => text(body).size(14).font(fonts::mono()).color(colors.text.scale_alpha(alpha)).into(),
+            Block::Code { text: code, .. } => container(text(code).size(13).font(fonts::mono()).color(colors.text.scale_alpha(alpha)))
+                .padding(8)
+                .width(Length::Fill)
+                .style(theme::code_container)
+                .into(),
+            Block::Field { label, value } => row![
+                text(label).size(13).font(fonts::mono()).color(colors.text_dim.scale_alpha(alpha)),
+                text(value).size(13).font(fonts::mono()).color(colors.text.scale_alpha(alpha)),
+            ]
+            .spacing(8)
+            .into(),
+        }
+    });
+    Column::with_children(rows).spacing(6).into()
+}
+
+impl BickyApp {
+    /// Ask every enabled plugin what it does
+    pub(crate) fn load_plugins(&self) -> Command<Message> {
+        let plugins = self.state.settings.plugins.iter().filter(|plugin| plugin.enabled && !plugin.command.trim().is_empty());
+        Command::batch(plugins.cloned().map(|settings| {
+            let command = settings.clone();
+            self.tasks.run(
+                "load plugin",
+                async move { ask(command, Request::Describe).await },
+                move |answer| Message::PluginLoaded(settings.clone(), answer),
+            )
+        }))
+    }
+
+    pub(crate) fn plugin_loaded(&mut self, settings: PluginSettings, answer: Result<Value, String>) {
+        match parse::<Manifest>(answer) {
+            Ok(manifest) => {
+                println!("[GUI] Loaded plugin {} from {}", manifest.name, settings.command);
+                update(&mut self.state, Action::PluginLoaded(LoadedPlugin { settings, manifest }));
+            }
+            Err(e) => {
+                eprintln!("[GUI] Plugin {} didn't load: {}", settings.command, e);
+                update(&mut self.state, Action::ShowNotification(state::Notification::Error(
+                    format!("Plugin {} didn't load: {}", settings.command, e)
+                )));
+            }
+        }
+    }
+
+    /// Offer newly answered messages of the conversation on screen to the plugins
+    pub(crate) fn request_plugin_renders(&mut self) -> Command<Message> {
+        if self.state.plugins.is_empty() {
+            return Command::none();
+        }
+        let Some(conv) = self.state.active_conversation.and_then(|idx| self.state.conversations.get(idx)) else {
+            return Command::none();
+        };
+
+        let mut requests = Vec::new();
+        let mut renderings = Vec::new();
+        for (message_id, plugin, message) in unrendered(&self.state, &conv.messages) {
+            let Some(plugin) = plugin else {
+                renderings.push((message_id, Rendering::Declined));
+                continue;
+            };
+            let settings = plugin.settings.clone();
+            let id = message_id.clone();
+            requests.push(self.tasks.perform(
+                "render message",
+                self.tasks.conversation(),
+                async move { ask(settings, Request::RenderMessage { message }).await },
+                move |answer| Message::PluginRendered(id.clone(), answer),
+            ));
+            renderings.push((message_id, Rendering::Pending));
+        }
+
+        for (message_id, rendering) in renderings {
+            update(&mut self.state, Action::PluginRenderRequested { message_id, rendering });
+        }
+        Command::batch(requests)
+    }
+
+    pub(crate) fn plugin_rendered(&mut self, message_id: String, answer: Result<Value, String>) {
+        let blocks = parse::<Option<Vec<Block>>>(answer).unwrap_or_else(|e| {
+            eprintln!("[GUI] Plugin couldn't render {}: {}", message_id, e);
+            None
+        });
+        update(&mut self.state, Action::PluginRendered { message_id, blocks });
+    }
+
+    /// Ask for the panels again whenever the user moves somewhere else
+    pub(crate) fn refresh_plugin_panels(&mut self) -> Command<Message> {
+        let context = context(&self.state);
+        if self.state.plugin_context.as_ref() == Some(&context) {
+            return Command::none();
+        }
+        update(&mut self.state, Action::PluginPanelsRequested(context.clone()));
+
+        let plugins = self.state.plugins.iter().filter(|plugin| plugin.manifest.panel);
+        Command::batch(plugins.map(|plugin| {
+            let settings = plugin.settings.clone();
+            let name = plugin.manifest.name.clone();
+            let context = context.clone();
+            self.tasks.run(
+                "plugin panel",
+                async move { ask(settings, Request::Panel { context }).await },
+                move |answer| Message::PluginPanelLoaded(name.clone(), answer),
+            )
+        }))
+    }
+
+    pub(crate) fn plugin_panel_loaded(&mut self, plugin: String, answer: Result<Value, String>) {
+        let panel = parse::<Option<Panel>>(answer).unwrap_or_else(|e| {
+            eprintln!("[GUI] Plugin {} has no panel: {}", plugin, e);
+            None
+        });
+        update(&mut self.state, Action::PluginPanelLoaded { plugin, panel });
+    }
+
+    pub(crate) fn run_plugin_command(&self, plugin: String, id: String) -> Command<Message> {
+        let Some(loaded) = self.state.plugins.iter().find(|p| p.manifest.name == plugin) else {
+            return Command::none();
+        };
+        println!("[GUI] Running {} from plugin {}", id, plugin);
+        let settings = loaded.settings.clone();
+        let context = context(&self.state);
+        self.tasks.run(
+            "plugin command",
+            async move { ask(settings, Request::RunCommand { id, context }).await },
+            move |answer| Message::PluginCommandFinished(plugin.clone(), answer),
+        )
+    }
+
+    pub(crate) fn plugin_command_finished(&mut self, plugin: String, answer: Result<Value, String>) {
+        match parse::<Outcome>(answer) {
+            Ok(Outcome::Done) => {}
+            Ok(Outcome::Notify { message }) => {
+                update(&mut self.state, Action::ShowNotification(state::Notification::Info(message)));
+            }
+            Ok(Outcome::Draft { text }) => {
+                update(&mut self.state, Action::InputChanged(text));
+                self.show_draft();
+            }
+            Err(e) => {
+                eprintln!("[GUI] Plugin {} command failed: {}", plugin, e);
+                update(&mut self.state, Action::ShowNotification(state::Notification::Error(
+                    format!("{}: {}", plugin, e)
+                )));
+            }
+        }
+    }
+
+    /// Panels in the order their plugins are configured
+    pub(crate) fn build_plugin_panels(&self) -> Option<Element<'_, Message>> {
+        let panels: Vec<Element<'_, Message>> = self
+            .state
+            .plugins
+            .iter()
+            .filter_map(|plugin| self.state.plugin_panels.get(&plugin.manifest.name))
+            .map(|panel| {
+                column![
+                    text(&panel.title).size(11).font(fonts::bold()).color(theme::colors().text_dim),
+                    blocks(&panel.blocks, 1.0),
+                ]
+                .spacing(6)
+                .into()
+            })
+            .collect();
+        if panels.is_empty() {
+            return None;
+        }
+
+        Some(container(Column::with_children(panels).spacing(12)).padding([8, 16]).width(Length::Fill).into())
+    }
+}
@@ -0,0 +1,99 @@
+use iced::keyboard::{key::Named, Key};
+use iced::widget::{column, container, row, scrollable, text, Column, Space};
+use iced::{Alignment, Element, Length};
+
+use crate::markdown::{self, Fragment};
+use crate::state::Conversation;
+use crate::types::LegacyMessage;
+use crate::{fonts, theme, Message};
+
+// Slides are read from across a room
+const PROMPT_SIZE: u16 = 30;
+const RESPONSE_SIZE: u16 = 22;
+const CODE_SIZE: u16 = 19;
+// Widest a slide's text gets, so lines stay readable at these sizes
+const MEASURE: f32 = 1100.0;
+
+/// The message pairs of a conversation, one per slide
+///
+/// Messages still waiting on a response are left out, there's nothing to
+/// show for them yet.
+pub fn slides(conv: &Conversation) -> Vec<&LegacyMessage> {
+    conv.messages.iter().filter(|msg| msg.response.is_some()).collect()
+}
+
+/// The slide a key moves to from `current`, `None` for keys that don't step
+pub fn slide_for(key: &Key, current: usize, count: usize) -> Option<usize> {
+    let last = count.checked_sub(1)?;
+    match key.as_ref() {
+        Key::Named(Named::ArrowRight | Named::ArrowDown | Named::PageDown | Named::Space) => Some((current + 1).min(last)),
+        Key::Named(Named::ArrowLeft | Named::ArrowUp | Named::PageUp) => Some(current.saturating_sub(1).min(last)),
+        Key::Named(Named::Home) => Some(0),
+        Key::Named(Named::End) => Some(last),
+        _ => None,
+    }
+}
+
+// Prose at slide size with code blocks set apart, inline code keeps its backticks
+fn body<'a>(response: &str) -> Column<'a, Message> {
+    let mut body = column![].spacing(16);
+    let mut prose = String::new();
+    for fragment in markdown::parse_markdown(response) {
+        match fragment {
+            Fragment::Text(content) => prose.push_str(&content),
+            Fragment::Code(content) => prose.push_str(&format!("`{}`", content)),
+            Fragment::CodeBlock { content, .. } => {
+                if !prose.trim().is_empty() {
+                    body = body.push(text(prose.trim().to_string()).size(RESPONSE_SIZE).color(theme::colors().text));
+                }
+                prose.clear();
+                body = body.push(
+                    container(text(content.trim_end().to_string()).size(CODE_SIZE).font(fonts::mono()).color(theme::colors().text))
+                        .padding(16)
+                        .width(Length::Fill)
+                        .style(theme::code_container),
+                );
+            }
+        }
+    }
+    if !prose.trim().is_empty() {
+        body = body.push(text(prose.trim().to_string()).size(RESPONSE_SIZE).color(theme::colors().text));
+    }
+    body
+}
+
+/// One message pair filling the window, with nothing but where it is in the deck
+pub fn build_slide<'a>(conv: &Conversation, slide: usize) -> Element<'a, Message> {
+    let slides = slides(conv);
+    let slide = slide.min(slides.len().saturating_sub(1));
+    let Some(msg) = slides.get(slide) else {
+        return container(text("Nothing to present yet").size(RESPONSE_SIZE).color(theme::colors().text_dim))
+            .center_x(Length::Fill)
+            .center_y(Length::Fill)
+            .into();
+    };
+
+    let content = column![
+        text(msg.content.clone()).size(PROMPT_SIZE).font(fonts::bold()).color(theme::colors().primary),
+        body(msg.response.as_deref().unwrap_or_default()),
+    ]
+    .spacing(32)
+    .max_width(MEASURE);
+
+    let footer = row![
+        text(conv.title.clone()).size(14).font(fonts::mono()).color(theme::colors().text_dim),
+        Space::with_width(Length::Fill),
+        text("← → to step, Esc to leave").size(14).font(fonts::mono()).color(theme::colors().text_dim),
+        Space::with_width(24),
+        text(format!("{} / {}", slide + 1, slides.len())).size(14).font(fonts::mono()).color(theme::colors().text_dim),
+    ]
+    .align_y(Alignment::Center);
+
+    column![
+        scrollable(container(content).center_x(Length::Fill).padding([48, 64])).height(Length::Fill),
+        container(footer).padding([12, 24]),
+    ]
+    .width(Length::Fill)
+    .height(Length::Fill)
+    .into()
+}
@@ -0,0 +1,77 @@
+use iced::widget::{button, row, text, Space};
+use iced::Element;
+use iced::Task as Command;
+
+use crate::config::QuickAction;
+use crate::state::{update, Action};
+use crate::widgets::multiline_input;
+use crate::{fonts, theme, BickyApp, Message};
+
+/// What a quick action does once its prompt is filled in
+#[derive(Debug, Clone, PartialEq)]
+pub enum Outcome {
+    Send(String),
+    /// Put in the composer for the user to finish
+    Fill(String),
+}
+
+/// The action's prompt with the composer's context filled in
+pub fn expand(action: &QuickAction, selection: &str, worktree: Option<&str>) -> Outcome {
+    let prompt = action
+        .prompt
+        .replace("{worktree}", worktree.unwrap_or_default())
+        .replace("{selection}", selection);
+    // Nothing selected or typed to act on, let the user say what they meant first
+    let missing = action.prompt.contains("{selection}") && selection.trim().is_empty();
+    if action.send && !missing {
+        Outcome::Send(prompt.replace("{cursor}", ""))
+    } else {
+        Outcome::Fill(prompt)
+    }
+}
+
+impl BickyApp {
+    pub(crate) fn run_quick_action(&mut self, idx: usize) -> Command<Message> {
+        let Some(action) = self.state.settings.quick_actions.0.get(idx).cloned() else {
+            return Command::none();
+        };
+        let selection = self
+            .composer
+            .selection()
+            .filter(|selected| !selected.is_empty())
+            .unwrap_or_else(|| self.state.editor.draft.content.clone());
+        let worktree = self.state.editor.current_worktree.as_ref().map(|worktree| worktree.path.as_str());
+        println!("[GUI] Quick action {}", action.label);
+
+        match expand(&action, &selection, worktree) {
+            Outcome::Send(prompt) => {
+                multiline_input::set_text(&mut self.composer, &prompt);
+                update(&mut self.state, Action::InputChanged(prompt));
+                self.update(Message::SendMessage)
+            }
+            Outcome::Fill(prompt) => {
+                multiline_input::expand_template(&mut self.composer, &prompt);
+                update(&mut self.state, Action::InputChanged(multiline_input::text(&self.composer)));
+                Command::none()
+            }
+        }
+    }
+
+    /// The configured quick actions, shown while a conversation is open
+    pub(crate) fn build_quick_actions(&self) -> Element<'_, Message> {
+        let actions = &self.state.settings.quick_actions.0;
+        if actions.is_empty() || self.state.active_conversation.is_none() {
+            return Space::with_height(0).into();
+        }
+
+        row(actions.iter().enumerate().map(|(idx, action)| {
+            button(text(&action.label).size(12).font(fonts::mono()))
+                .on_press(Message::QuickAction(idx))
+                .padding([4, 10])
+                .style(theme::secondary_button)
+                .into()
+        }))
+        .spacing(6)
+        .into()
+    }
+}
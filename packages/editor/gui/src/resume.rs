@@ -0,0 +1,50 @@
+use crate::types::{LegacyMessage, MessageStatus};
+
+/// Whether Wake stopped before finishing this message, by interrupt or by crash
+pub fn is_resumable(msg: &LegacyMessage) -> bool {
+    matches!(msg.status, MessageStatus::Failed | MessageStatus::Error | MessageStatus::Cancelled)
+}
+
+/// The follow-up message that picks an interrupted one back up
+///
+/// Wake only sees message content, so the original request and whatever it got
+/// through before stopping are restated here rather than left to the metadata.
+pub fn continuation_prompt(msg: &LegacyMessage) -> String {
+    let mut prompt = String::from(
+        "Your previous run on this request was interrupted before it finished. \
+         Continue from where you left off instead of starting over.\n\n",
+    );
+
+    prompt.push_str("Original request:\n");
+    prompt.push_str(&quote(&msg.content));
+
+    if let Some(action) = last_action(msg) {
+        prompt.push_str(&format!("\n\nLast thing you were doing: {}", action));
+    }
+
+    if let Some(partial) = msg.response.as_deref().filter(|r| !r.trim().is_empty()) {
+        prompt.push_str("\n\nOutput so far:\n");
+        prompt.push_str(&quote(partial));
+    }
+
+    prompt
+}
+
+/// Metadata flagging the message as a continuation, for the server's records
+pub fn continuation_metadata(msg: &LegacyMessage) -> serde_json::Value {
+    serde_json::json!({
+        "continuation": {
+            "resumeOf": msg.id,
+            "partialOutput": msg.response,
+            "lastAction": last_action(msg),
+        }
+    })
+}
+
+fn last_action(msg: &LegacyMessage) -> Option<&str> {
+    msg.metadata.as_ref().and_then(|meta| meta.current_action.as_deref())
+}
+
+fn quote(text: &str) -> String {
+    text.lines().map(|line| format!("> {}", line)).collect::<Vec<_>>().join("\n")
+}
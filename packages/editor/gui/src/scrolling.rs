@@ -0,0 +1,158 @@
+use iced::keyboard::{key::Named, Key};
+use iced::mouse::ScrollDelta;
+use iced::widget::scrollable::{self, AbsoluteOffset, Direction, RelativeOffset, Scrollbar, Viewport};
+use iced::Task as Command;
+use std::time::Instant;
+
+use crate::{BickyApp, Message};
+
+// Wheels that report lines are converted at roughly one line of text
+const LINE_HEIGHT: f32 = 20.0;
+// Share of each wheel step that keeps gliding after the scrollable has moved
+const MOMENTUM: f32 = 0.6;
+// Fraction of the remaining glide covered per 60fps frame
+const GLIDE_RATE: f32 = 0.18;
+// Paging keeps a little of the previous page in view
+const PAGE_OVERLAP: f32 = 48.0;
+
+pub fn transcript_id() -> scrollable::Id {
+    scrollable::Id::new("transcript")
+}
+
+/// Thin vertical scrollbar used by every scrollable
+pub fn scrollbar() -> Direction {
+    Direction::Vertical(Scrollbar::new().width(8).scroller_width(6).margin(1))
+}
+
+/// Jumps through the transcript, from the keyboard or the minimap
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Jump {
+    PageUp,
+    PageDown,
+    Top,
+    Bottom,
+    /// A point in the transcript, as a fraction of its height
+    Fraction(f32),
+}
+
+impl Jump {
+    pub fn from_key(key: &Key) -> Option<Self> {
+        match key {
+            Key::Named(Named::PageUp) => Some(Jump::PageUp),
+            Key::Named(Named::PageDown) => Some(Jump::PageDown),
+            Key::Named(Named::Home) => Some(Jump::Top),
+            Key::Named(Named::End) => Some(Jump::Bottom),
+            _ => None,
+        }
+    }
+}
+
+/// Scroll position and any glide still in progress for the transcript
+#[derive(Debug, Default)]
+pub struct TranscriptScroll {
+    viewport: Option<Viewport>,
+    pub hovered: bool,
+    // Pixels left to glide, positive is down
+    remaining: f32,
+    last_step: Option<Instant>,
+}
+
+impl TranscriptScroll {
+    pub fn scrolled(&mut self, viewport: Viewport) {
+        self.viewport = Some(viewport);
+    }
+
+    /// Top and bottom of what's on screen, as fractions of the transcript height
+    pub fn visible_range(&self) -> Option<(f32, f32)> {
+        let viewport = self.viewport.as_ref()?;
+        let content = viewport.content_bounds().height.max(1.0);
+        let top = viewport.absolute_offset().y / content;
+        Some((top, top + viewport.bounds().height / content))
+    }
+
+    /// Distance a jump needs to travel, `None` until the transcript has been laid out
+    fn distance(&self, jump: Jump) -> Option<f32> {
+        let viewport = self.viewport.as_ref()?;
+        let offset = viewport.absolute_offset().y;
+        let height = viewport.bounds().height;
+        let bottom = (viewport.content_bounds().height - height).max(0.0);
+
+        let page = (height - PAGE_OVERLAP).max(height / 2.0);
+        let target = match jump {
+            Jump::PageUp => offset - page,
+            Jump::PageDown => offset + page,
+            Jump::Top => 0.0,
+            Jump::Bottom => bottom,
+            // Land with a little context above the target
+            Jump::Fraction(fraction) => fraction * viewport.content_bounds().height - PAGE_OVERLAP,
+        };
+        Some(target.clamp(0.0, bottom) - offset)
+    }
+
+    fn at_edge(&self, direction: f32) -> bool {
+        self.viewport.as_ref().is_some_and(|viewport| {
+            let offset = viewport.relative_offset().y;
+            (direction < 0.0 && offset <= 0.0) || (direction > 0.0 && offset >= 1.0)
+        })
+    }
+}
+
+impl BickyApp {
+    /// Carry part of a wheel step on as momentum
+    pub(crate) fn transcript_wheel(&mut self, delta: ScrollDelta) {
+        let scroll = &mut self.transcript_scroll;
+        if !scroll.hovered || self.state.settings.appearance.reduced_motion {
+            return;
+        }
+
+        let pixels = match delta {
+            ScrollDelta::Lines { y, .. } => y * LINE_HEIGHT,
+            ScrollDelta::Pixels { y, .. } => y,
+        };
+        // Wheel up moves the content down, so the offset shrinks
+        let push = -pixels * MOMENTUM;
+        if push * scroll.remaining < 0.0 {
+            scroll.remaining = 0.0;
+        }
+        scroll.remaining += push;
+    }
+
+    pub(crate) fn jump_transcript(&mut self, jump: Jump) -> Command<Message> {
+        let Some(distance) = self.transcript_scroll.distance(jump) else {
+            return match jump {
+                Jump::Top => scrollable::snap_to(transcript_id(), RelativeOffset::START),
+                Jump::Bottom => scrollable::snap_to(transcript_id(), RelativeOffset::END),
+                Jump::Fraction(fraction) => scrollable::snap_to(transcript_id(), RelativeOffset { x: 0.0, y: fraction }),
+                Jump::PageUp | Jump::PageDown => Command::none(),
+            };
+        };
+
+        if self.state.settings.appearance.reduced_motion {
+            return scrollable::scroll_by(transcript_id(), AbsoluteOffset { x: 0.0, y: distance });
+        }
+        self.transcript_scroll.remaining = distance;
+        Command::none()
+    }
+
+    /// Move the transcript along whatever glide is left, called every tick
+    pub(crate) fn step_transcript_scroll(&mut self) -> Command<Message> {
+        let scroll = &mut self.transcript_scroll;
+        if scroll.remaining.abs() < 0.5 || scroll.at_edge(scroll.remaining) {
+            scroll.remaining = 0.0;
+            scroll.last_step = None;
+            return Command::none();
+        }
+
+        // Scale by real frame time so the glide feels the same when ticks run late
+        let now = self.state.clock.instant();
+        let frames = scroll
+            .last_step
+            .map_or(1.0, |last| (now - last).as_secs_f32() * 60.0)
+            .clamp(0.0, 6.0);
+        scroll.last_step = Some(now);
+
+        let step = scroll.remaining * (1.0 - (1.0 - GLIDE_RATE).powf(frames));
+        scroll.remaining -= step;
+        scrollable::scroll_by(transcript_id(), AbsoluteOffset { x: 0.0, y: step })
+    }
+}
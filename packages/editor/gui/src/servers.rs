@@ -0,0 +1,175 @@
+use iced::widget::{button, column, container, row, text};
+use iced::{Element, Length};
+use iced::Task as Command;
+
+use crate::api::{ApiClient, ApiError};
+use crate::config::Settings;
+use crate::state::{update, Action, AppState, Clock, DEFAULT_SERVER};
+use crate::{fonts, theme, BickyApp, Message};
+use bicky_editor_core::Action as EditorAction;
+use std::sync::Arc;
+
+/// One configured backend and the client talking to it
+#[derive(Clone)]
+pub struct Server {
+    pub name: String,
+    pub api: ApiClient,
+}
+
+/// Clients for every configured server, `[gui.server]` first, reading time from `clock`
+pub fn configured(settings: &Settings, clock: Arc<dyn Clock>) -> Vec<Server> {
+    let primary = Server {
+        name: settings.server.name.clone().unwrap_or_else(|| DEFAULT_SERVER.to_string()),
+        api: client(settings.server.url.as_deref()).with_clock(clock.clone()).with_limits(&settings.requests),
+    };
+    primary.api.credentials.set(settings.server.api_key());
+    let mut servers = vec![primary];
+
+    for extra in &settings.servers {
+        let Some(url) = extra.url.as_deref() else {
+            eprintln!("[GUI] Ignoring server {} without a url", extra.name.as_deref().unwrap_or("(unnamed)"));
+            continue;
+        };
+        let name = extra.name.clone().unwrap_or_else(|| url.to_string());
+        if servers.iter().any(|server| server.name == name) {
+            eprintln!("[GUI] Ignoring second server named {}", name);
+            continue;
+        }
+
+        // BICAMRL_API_KEY only stands in for the primary server's key
+        let api = client(Some(url)).with_clock(clock.clone()).with_limits(&settings.requests);
+        api.credentials.set(extra.api_key.clone());
+        servers.push(Server { name, api });
+    }
+
+    servers
+}
+
+// Without a url we fall back to the local server's port file
+fn client(url: Option<&str>) -> ApiClient {
+    url.map(|url| ApiClient::new(url.trim_end_matches('/').to_string()))
+        .unwrap_or_default()
+}
+
+/// Ids from a server's interaction list that aren't in the sidebar yet
+///
+/// Conversations we already have stay as they are, deleted ones stay gone.
+pub fn interactions_to_load(state: &AppState, server: &str, listed: &[serde_json::Value]) -> Vec<String> {
+    listed
+        .iter()
+        .filter_map(|interaction| interaction.get("id")?.as_str())
+        .filter(|id| {
+            !state.deleted_conversations.contains(*id)
+                && !state.conversations.iter().any(|conv| conv.server == server && conv.id == *id)
+        })
+        .map(str::to_string)
+        .collect()
+}
+
+impl BickyApp {
+    /// Client for the named server, the active one if it's unknown
+    pub(crate) fn api_for(&self, server: &str) -> ApiClient {
+        self.servers
+            .iter()
+            .find(|s| s.name == server)
+            .map(|s| s.api.clone())
+            .unwrap_or_else(|| self.api_client.clone())
+    }
+
+    /// Server an interaction lives on, the active one if we don't know it
+    pub(crate) fn server_of(&self, interaction_id: &str) -> String {
+        self.state
+            .conversations
+            .iter()
+            .find(|conv| conv.id == interaction_id)
+            .map_or_else(|| self.state.active_server.clone(), |conv| conv.server.clone())
+    }
+
+    pub(crate) fn switch_server(&mut self, server: String) -> Command<Message> {
+        update(&mut self.state, Action::SwitchServer(server));
+        self.show_draft();
+        self.activate_server()
+    }
+
+    /// Point requests at the server state says is active and load its worktrees
+    pub(crate) fn activate_server(&mut self) -> Command<Message> {
+        let Some(server) = self.servers.iter().find(|s| s.name == self.state.active_server) else {
+            return Command::none();
+        };
+        if server.api.base_url == self.api_client.base_url {
+            return Command::none();
+        }
+
+        println!("[GUI] Switched to server {} at {}", server.name, server.api.base_url);
+        self.api_client = server.api.clone();
+        self.tasks.switch_conversation();
+        // The old server's stream goes back to following every session
+        update(&mut self.state, Action::Editor(EditorAction::Connect { server_url: server.api.base_url.clone() }));
+
+        let api = self.api_client.clone();
+        Command::batch([
+            self.tasks.run(
+                "get worktrees",
+                async move { api.get_worktrees().await },
+                Message::WorktreesLoaded
+            ),
+            self.load_sessions(),
+            self.load_agents(),
+        ])
+    }
+
+    /// Build the sidebar from the interactions a server already has
+    pub(crate) fn hydrate_conversations(&self, server: String) -> Command<Message> {
+        let api = self.api_for(&server);
+        self.tasks.run(
+            "get interactions",
+            async move { api.get_interactions().await },
+            move |result| Message::ConversationsListed(server.clone(), result)
+        )
+    }
+
+    pub(crate) fn conversations_listed(&mut self, server: String, result: Result<Vec<serde_json::Value>, ApiError>) -> Command<Message> {
+        let interactions = match result {
+            Ok(interactions) => interactions,
+            Err(e) => {
+                eprintln!("[GUI] Failed to list interactions on {}: {}", server, e);
+                return Command::none();
+            }
+        };
+
+        let ids = interactions_to_load(&self.state, &server, &interactions);
+        println!("[GUI] Loading {} conversations from {}", ids.len(), server);
+        Command::batch(ids.into_iter().map(|id| self.fetch_interaction(server.clone(), id)))
+    }
+
+    /// One row per server at the top of the sidebar, hidden with only one
+    pub(crate) fn build_server_switcher(&self) -> Option<Element<'_, Message>> {
+        if self.state.servers.len() < 2 {
+            return None;
+        }
+
+        let entries = self.state.servers.iter().map(|name| {
+            let active = *name == self.state.active_server;
+            button(
+                row![
+                    text(if active { "●" } else { "○" }).size(12).font(fonts::mono()),
+                    text(name).size(13).font(fonts::mono()),
+                ]
+                .spacing(8),
+            )
+            .on_press(Message::SwitchServer(name.clone()))
+            .padding([6, 16])
+            .width(Length::Fill)
+            .style(move |theme, _| {
+                if active {
+                    theme::channel_button_active(theme)
+                } else {
+                    theme::channel_button_inactive(theme)
+                }
+            })
+            .into()
+        });
+
+        Some(container(column(entries).spacing(2)).width(Length::Fill).into())
+    }
+}
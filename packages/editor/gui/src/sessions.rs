@@ -0,0 +1,134 @@
+use bicky_editor_core::types::{InteractionQueueStatus, Session};
+use bicky_editor_core::Action as EditorAction;
+use iced::widget::{button, column, container, row, text, Space};
+use iced::{Element, Length};
+use iced::Task as Command;
+
+use crate::api::ApiError;
+use crate::state::{active_session, update, Action};
+use crate::{fonts, theme, BickyApp, Message};
+
+/// Short summary of a session's queue for the switcher
+pub fn counts_label(counts: &InteractionQueueStatus) -> String {
+    let mut parts = Vec::new();
+    if counts.processing > 0 {
+        parts.push(format!("{} working", counts.processing));
+    }
+    if counts.needs_review > 0 {
+        parts.push(format!("{} to review", counts.needs_review));
+    }
+    if counts.needs_work > 0 {
+        parts.push(format!("{} queued", counts.needs_work));
+    }
+    if parts.is_empty() {
+        format!("{} done", counts.completed)
+    } else {
+        parts.join(" · ")
+    }
+}
+
+fn session_name(session: &Session) -> &str {
+    session.name.as_deref().unwrap_or(&session.id)
+}
+
+impl BickyApp {
+    /// Fetch the active server's sessions, servers without them return none
+    pub(crate) fn load_sessions(&self) -> Command<Message> {
+        let server = self.state.active_server.clone();
+        let api = self.api_client.clone();
+        self.tasks.run(
+            "get sessions",
+            async move { api.get_sessions().await },
+            move |result| Message::SessionsLoaded(server.clone(), result)
+        )
+    }
+
+    pub(crate) fn sessions_loaded(&mut self, server: String, result: Result<Vec<Session>, ApiError>) -> Command<Message> {
+        // The user moved to another server while this was in flight
+        if server != self.state.active_server {
+            return Command::none();
+        }
+        let sessions = match result {
+            Ok(sessions) => sessions,
+            Err(e) => {
+                eprintln!("[GUI] Failed to load sessions from {}: {}", server, e);
+                return Command::none();
+            }
+        };
+
+        let ids: Vec<String> = sessions.iter().map(|s| s.id.clone()).collect();
+        update(&mut self.state, Action::Editor(EditorAction::SessionsLoaded(Ok(sessions))));
+        Command::batch(ids.into_iter().map(|id| self.load_session_counts(id)))
+    }
+
+    pub(crate) fn load_session_counts(&self, session_id: String) -> Command<Message> {
+        let api = self.api_client.clone();
+        let id = session_id.clone();
+        self.tasks.run(
+            "get session status",
+            async move { api.get_session_status(&id).await },
+            move |result| Message::SessionCountsLoaded(session_id.clone(), result)
+        )
+    }
+
+    pub(crate) fn switch_session(&mut self, session_id: Option<String>) -> Command<Message> {
+        update(&mut self.state, Action::SwitchSession(session_id));
+        match active_session(&self.state).map(str::to_string) {
+            Some(id) => {
+                println!("[GUI] Following session {} on {}", id, self.state.active_server);
+                self.load_session_counts(id)
+            }
+            None => Command::none(),
+        }
+    }
+
+    /// One row per session under the server switcher, hidden when the server has none
+    pub(crate) fn build_session_switcher(&self) -> Option<Element<'_, Message>> {
+        if self.state.editor.sessions.is_empty() {
+            return None;
+        }
+
+        let entry = |label: String, counts: String, session_id: Option<String>| {
+            let active = session_id.as_deref() == active_session(&self.state);
+            button(
+                row![
+                    text(if active { "●" } else { "○" }).size(12).font(fonts::mono()),
+                    text(label).size(13).font(fonts::mono()),
+                    Space::with_width(Length::Fill),
+                    text(counts).size(11).font(fonts::mono()).color(theme::colors().text_dim),
+                ]
+                .spacing(8),
+            )
+            .on_press(Message::SwitchSession(session_id))
+            .padding([6, 16])
+            .width(Length::Fill)
+            .style(move |theme, _| {
+                if active {
+                    theme::channel_button_active(theme)
+                } else {
+                    theme::channel_button_inactive(theme)
+                }
+            })
+            .into()
+        };
+
+        let mut entries: Vec<Element<'_, Message>> = vec![entry("All sessions".to_string(), String::new(), None)];
+        for session in &self.state.editor.sessions {
+            let counts = self.state.editor.session_queues.get(&session.id).map(counts_label).unwrap_or_default();
+            entries.push(entry(session_name(session).to_string(), counts, Some(session.id.clone())));
+        }
+
+        Some(
+            container(
+                column![
+                    container(text("Sessions").size(11).font(fonts::bold()).color(theme::colors().text_dim))
+                        .padding([4, 16]),
+                    column(entries).spacing(2),
+                ]
+                .spacing(2),
+            )
+            .width(Length::Fill)
+            .into(),
+        )
+    }
+}
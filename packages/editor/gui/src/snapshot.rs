@@ -0,0 +1,54 @@
+use std::path::PathBuf;
+
+use bicky_editor_core::types::Interaction;
+
+use crate::config;
+
+// The core state as of the last save, next to Mind.toml
+pub(crate) const SNAPSHOT_FILE: &str = "GuiSnapshot.json";
+// Interactions moved out of memory, one `<id>.json` each, next to the snapshot
+const ARCHIVE_DIR: &str = "GuiArchive";
+
+fn snapshot_path() -> PathBuf {
+    config::find_config_file(SNAPSHOT_FILE)
+        .or_else(|| config::find_config_file("Mind.toml").map(|mind| mind.with_file_name(SNAPSHOT_FILE)))
+        .unwrap_or_else(|| PathBuf::from(SNAPSHOT_FILE))
+}
+
+/// The last saved snapshot, if there is one to read
+pub fn restore() -> Option<String> {
+    let path = config::find_config_file(SNAPSHOT_FILE)?;
+    match std::fs::read_to_string(&path) {
+        Ok(snapshot) => Some(snapshot),
+        Err(e) => {
+            eprintln!("[GUI] Failed to read {}: {}", path.display(), e);
+            None
+        }
+    }
+}
+
+/// A torn write fails its checksum and is reported when it's restored
+pub fn persist(snapshot: &str) -> Result<(), String> {
+    std::fs::write(snapshot_path(), snapshot).map_err(|e| e.to_string())
+}
+
+/// Write interactions to cold storage, gives the ids written
+pub fn archive(interactions: &[Interaction]) -> Result<Vec<String>, String> {
+    let dir = snapshot_path().with_file_name(ARCHIVE_DIR);
+    std::fs::create_dir_all(&dir).map_err(|e| e.to_string())?;
+    interactions
+        .iter()
+        .map(|interaction| {
+            let json = serde_json::to_string(interaction).map_err(|e| e.to_string())?;
+            std::fs::write(dir.join(format!("{}.json", interaction.id)), json).map_err(|e| e.to_string())?;
+            Ok(interaction.id.clone())
+        })
+        .collect()
+}
+
+/// Read an archived interaction back
+pub fn unarchive(id: &str) -> Result<Interaction, String> {
+    let path = snapshot_path().with_file_name(ARCHIVE_DIR).join(format!("{}.json", id));
+    let json = std::fs::read_to_string(path).map_err(|e| e.to_string())?;
+    serde_json::from_str(&json).map_err(|e| e.to_string())
+}
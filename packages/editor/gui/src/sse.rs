@@ -1,23 +1,38 @@
 use futures::stream::StreamExt;
-use reqwest::Client;
 use serde_json::{json, Value};
-use std::sync::mpsc;
 
+use crate::api::ApiClient;
+use crate::events::{AppEvent, EventBus};
+
+#[derive(Debug, Clone)]
 pub enum SSEMessage {
     Connected,
     InteractionUpdate { id: String, data: Value },
     Error(String),
+    /// The server wants an API key, we'll keep retrying with whatever gets entered
+    Unauthorized,
+    /// An event of a type we don't handle, by its type
+    Unhandled(String),
 }
 
-pub async fn connect_sse(base_url: String, session_id: Option<String>, tx: mpsc::Sender<SSEMessage>) {
-    let url = match session_id {
-        Some(id) => format!("{}/sessions/{}/stream", base_url, id),
-        None => format!("{}/stream", base_url)
+pub async fn connect_sse(server: String, api: ApiClient, session_id: Option<String>, bus: EventBus) {
+    let publish = |message| bus.publish(AppEvent::Sse { server: server.clone(), message });
+    let mut path = match session_id {
+        Some(id) => format!("/sessions/{}/stream", id),
+        None => "/stream".to_string()
     };
-    let client = Client::new();
     
     loop {
-        match client.get(&url).send().await {
+        // The API's connection pool, reconnects reuse its sockets
+        match api.stream(&path).send().await {
+            Ok(response) if response.status() == reqwest::StatusCode::UNAUTHORIZED => {
+                publish(SSEMessage::Unauthorized);
+            }
+            // Servers without per-session streams send every event on the one stream
+            Ok(response) if response.status() == reqwest::StatusCode::NOT_FOUND && path != "/stream" => {
+                path = "/stream".to_string();
+                continue;
+            }
             Ok(response) => {
                 let mut stream = response.bytes_stream();
                 let mut buffer = String::new();
@@ -41,14 +56,14 @@ pub async fn connect_sse(base_url: String, session_id: Option<String>, tx: mpsc:
                                         let data_line = data_line.trim();
                                         if let Ok(json) = serde_json::from_str::<Value>(data_line) {
                                             if json.get("connected").is_some() {
-                                                let _ = tx.send(SSEMessage::Connected);
+                                                publish(SSEMessage::Connected);
                                             } else if let Some(event_type) = json.get("type").and_then(|v| v.as_str()) {
                                                 match event_type {
                                                     "interaction:created" | "interaction:updated" => {
                                                         if let Some(data) = json.get("data") {
                                                             if let Some(interaction) = data.get("interaction") {
                                                                 if let Some(id) = interaction.get("id").and_then(|v| v.as_str()) {
-                                                                    let _ = tx.send(SSEMessage::InteractionUpdate {
+                                                                    publish(SSEMessage::InteractionUpdate {
                                                                         id: id.to_string(),
                                                                         data: interaction.clone()
                                                                     });
@@ -61,14 +76,14 @@ pub async fn connect_sse(base_url: String, session_id: Option<String>, tx: mpsc:
                                                             if let Some(interaction_id) = data.get("interactionId").and_then(|v| v.as_str()) {
                                                                 // For message events, we need to fetch the full interaction
                                                                 // Send an update event with the interaction ID
-                                                                let _ = tx.send(SSEMessage::InteractionUpdate {
+                                                                publish(SSEMessage::InteractionUpdate {
                                                                     id: interaction_id.to_string(),
                                                                     data: json!({ "refetch": true })
                                                                 });
                                                             }
                                                         }
                                                     }
-                                                    _ => {}
+                                                    other => publish(SSEMessage::Unhandled(other.to_string())),
                                                 }
                                             }
                                         }
@@ -77,14 +92,14 @@ pub async fn connect_sse(base_url: String, session_id: Option<String>, tx: mpsc:
                             }
                         }
                         Err(e) => {
-                            let _ = tx.send(SSEMessage::Error(e.to_string()));
+                            publish(SSEMessage::Error(e.to_string()));
                             break;
                         }
                     }
                 }
             }
             Err(e) => {
-                let _ = tx.send(SSEMessage::Error(e.to_string()));
+                publish(SSEMessage::Error(e.to_string()));
             }
         }
         
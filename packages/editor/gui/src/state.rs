@@ -1,12 +1,87 @@
+use crate::config::{AppearanceMode, PaletteVariant, Settings};
+use crate::agents::{ConfigDraft, ConfigField};
+use crate::artifacts::SaveDialog;
+use crate::attachments::{self, FilePicker, Pending, Upload};
+use crate::images::{ImageLoad, ImageSource, LoadedImage};
+use crate::backup::Backup;
+use crate::budget::TokenBudget;
+use crate::export::ExportSelection;
+use crate::labels::ColorLabel;
+use crate::permission_audit::AuditEntry;
+use crate::pins::Pin;
+use crate::permission_policy::{Decision, Policy, Rule, RuleDraft, RuleField};
+use crate::plugins::{LoadedPlugin, Rendering};
+use crate::theme::Appearance;
+use crate::{jobs, mentions, message_errors, palette, presentation, worktrees};
 use crate::types::*;
+use bicky_editor_core::stats::ConversationStats;
+use bicky_editor_core::filter::InteractionFilter;
+use bicky_editor_core::plugin::{Block, Context as PluginContext, Panel};
+use bicky_editor_core::state::DEFAULT_SESSION_ID;
+use bicky_editor_core::types::InteractionType;
+use bicky_editor_core::action_log::ActionLog;
+use bicky_editor_core::middleware::Transition;
+use bicky_editor_core::{diff, tokenizer, Action as EditorAction, EditorState, Effect, Pipeline};
 use rand::seq::SliceRandom;
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
 
 /// A single conversation with a Wake instance
 #[derive(Debug, Clone)]
 pub struct Conversation {
     pub id: String,
     pub title: String,
+    pub title_source: TitleSource,
+    /// What kind of interaction the server keeps it as, conversations started here are queries
+    pub interaction_type: InteractionType,
     pub messages: Vec<LegacyMessage>,
+    /// Keep updating but never notify, for long-running background jobs
+    pub muted: bool,
+    /// Hidden from the sidebar, still reachable from the command palette
+    pub archived: bool,
+    /// Worktree Wake works in for this conversation, None for the main checkout
+    pub worktree_id: Option<String>,
+    /// Ids of messages the user marked to find again
+    pub bookmarks: HashSet<String>,
+    /// Name of the server the conversation lives on, ids are only unique per server
+    pub server: String,
+    /// Oldest message we have while the server holds older ones, `None` once all are loaded
+    pub history_cursor: Option<String>,
+    /// An older page is on its way
+    pub loading_history: bool,
+    /// Tokens it may use before sends are warned about or refused
+    pub budget: Option<TokenBudget>,
+    /// Ids of the agents working on it
+    pub agents: Vec<String>,
+    /// Messages and files sent along as context with every message
+    pub pins: Vec<Pin>,
+    /// Agent chosen for the next messages, `None` lets the server route
+    pub route: Option<String>,
+    /// Who reviews each step of the interaction, in order
+    pub review_stack: Vec<String>,
+}
+
+/// Where a conversation's title came from
+///
+/// Later sources win, a title from the first message never replaces one
+/// the user typed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum TitleSource {
+    /// An Ubuntu-style name, until there's something better
+    #[default]
+    Generated,
+    FirstMessage,
+    Manual,
+}
+
+/// A conversation's new title as it's being typed in the sidebar
+#[derive(Debug, Clone)]
+pub struct RenameState {
+    pub conversation_idx: usize,
+    pub title: String,
 }
 
 /// Dialog state for creating worktrees
@@ -18,12 +93,125 @@ pub struct WorktreeDialogState {
     pub error: Option<String>,
 }
 
-/// Notification types
+/// A sent message being edited in the composer
 #[derive(Debug, Clone)]
-pub enum Notification {
-    Success(String),
-    Error(String),
-    Info(String),
+pub struct EditingMessage {
+    pub conversation_idx: usize,
+    pub message_id: String,
+}
+
+/// `@` file completion while typing in the composer
+#[derive(Debug, Clone, Default)]
+pub struct MentionState {
+    pub query: String,
+    pub selected: usize,
+}
+
+/// Review panel for a worktree's changes
+#[derive(Debug, Clone, Default)]
+pub struct DiffView {
+    pub worktree_id: String,
+    pub title: String,
+    pub files: Vec<diff::FileDiff>,
+    pub loading: bool,
+    pub error: Option<String>,
+    pub selected_file: usize,
+    /// Hunks folded away in the selected file
+    pub collapsed: HashSet<usize>,
+    /// Reply to post as the reviewer of the interaction behind the changes
+    pub reply: String,
+    pub reply_sending: bool,
+    pub reply_error: Option<String>,
+}
+
+/// How finished work leaves a worktree
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum MergeMode {
+    Merge,
+    PullRequest,
+}
+
+/// Dialog for merging a worktree or opening a PR from it
+#[derive(Debug, Clone)]
+pub struct MergeDialogState {
+    pub worktree_id: String,
+    pub branch: String,
+    pub base: String,
+    pub mode: MergeMode,
+    /// Commit message when merging, PR title otherwise
+    pub message: String,
+    pub body: String,
+    pub in_progress: bool,
+    pub error: Option<String>,
+}
+
+/// Dialog for attaching agents to a conversation and detaching them
+#[derive(Debug, Clone, PartialEq)]
+pub struct AgentSelectorState {
+    pub conversation_idx: usize,
+    /// Agent being attached or detached, one at a time
+    pub pending: Option<String>,
+    pub error: Option<String>,
+}
+
+/// Dialog for picking which part of a conversation to export
+#[derive(Debug, Clone)]
+pub struct ExportDialogState {
+    pub conversation_idx: usize,
+    pub selection: ExportSelection,
+}
+
+/// Prompt for an API key after the server turned us away
+#[derive(Debug, Clone, Default)]
+pub struct AuthPromptState {
+    pub api_key: String,
+}
+
+/// Command palette overlay state
+#[derive(Debug, Clone, Default)]
+pub struct CommandPaletteState {
+    pub query: String,
+    pub selected: usize,
+}
+
+pub use bicky_editor_core::types::Notification;
+
+// Toasts clear themselves after this long
+const NOTIFICATION_TIMEOUT: Duration = Duration::from_secs(5);
+/// How often the core state is saved, so a crash loses at most this much
+pub const SAVE_INTERVAL: Duration = Duration::from_secs(30);
+
+/// Name of the server from `[gui.server]`, unless it's given one
+pub const DEFAULT_SERVER: &str = "local";
+
+/// Messages fetched per page of conversation history
+pub const HISTORY_PAGE_SIZE: usize = 50;
+
+pub use bicky_editor_core::clock::{Clock, SystemClock};
+
+/// Where new ids come from, so tests can predict them
+pub trait IdGenerator: std::fmt::Debug + Send + Sync {
+    /// A new conversation's id, until the server assigns one
+    fn conversation_id(&self) -> String;
+    /// A new message's id until the server assigns one, never handed out twice
+    /// so ids stay unique after deletions
+    fn temp_id(&self) -> String;
+}
+
+/// Random conversation ids and a running count for messages
+#[derive(Debug, Default)]
+pub struct UuidIds {
+    next_temp: AtomicUsize,
+}
+
+impl IdGenerator for UuidIds {
+    fn conversation_id(&self) -> String {
+        uuid::Uuid::new_v4().to_string()
+    }
+
+    fn temp_id(&self) -> String {
+        format!("temp-{}", self.next_temp.fetch_add(1, Ordering::Relaxed))
+    }
 }
 
 /// Pure application state - just data, no logic
@@ -31,43 +219,472 @@ pub enum Notification {
 pub struct AppState {
     pub conversations: Vec<Conversation>,
     pub active_conversation: Option<usize>,
-    pub input: String,
-    pub current_worktree: Option<Worktree>,
-    pub available_worktrees: Vec<Worktree>,
+    /// Configured servers by name, only the active one's conversations are shown
+    pub servers: Vec<String>,
+    pub active_server: String,
+    /// Agents the active server offers, empty when it doesn't have any
+    pub agents: Vec<Agent>,
+    pub agent_selector: Option<AgentSelectorState>,
+    /// Agent whose configuration is being edited
+    pub agent_config: Option<ConfigDraft>,
+    pub show_agent_health: bool,
+    /// The active conversation's pins are listed in full
+    pub pins_expanded: bool,
+    /// File path being typed into the pins strip
+    pub pin_draft: String,
+    /// Tag being typed on the active conversation's entry
+    pub tag_draft: String,
+    pub file_picker: Option<FilePicker>,
+    /// Files picked for the next message, sent with it once uploaded
+    pub composer_attachments: Vec<Pending>,
+    /// Images messages point at, by source, loaded once per session
+    pub images: HashMap<String, ImageLoad>,
+    /// Image shown large over everything
+    pub expanded_image: Option<ImageSource>,
+    /// The active conversation shows its artifacts instead of its transcript
+    pub artifacts_tab: bool,
+    /// Artifacts being downloaded, by id
+    pub saving_artifacts: HashSet<String>,
+    pub artifact_save: Option<SaveDialog>,
+    /// Latest status of each agent, keyed by agent id
+    pub agent_statuses: HashMap<String, AgentStatus>,
+    /// When agent statuses were last asked for
+    pub agent_status_checked: Option<Instant>,
+    /// Token estimate of the composer's text, which is `editor.draft`
+    pub input_tokens: usize,
+    /// When messages sent from here left, by the server's message id
+    pub sent_locally: HashMap<String, chrono::DateTime<chrono::Utc>>,
+    /// Messages with their timings waterfall showing
+    pub open_timings: HashSet<String>,
+    pub expanded_template: Option<String>,
+    pub mention: Option<MentionState>,
+    pub editing: Option<EditingMessage>,
+    pub hovered_message: Option<String>,
+    pub confirm_delete: Option<String>,
+    /// Server messages removed locally, kept out when conversations refresh
+    pub deleted_messages: HashSet<String>,
+    pub show_jobs: bool,
+    /// Supervised background tasks listed over the top right
+    pub show_debug_overlay: bool,
+    /// Only the transcript shows, for reading long output
+    pub focus_mode: bool,
+    /// The slide shown while presenting the active conversation
+    pub presentation: Option<usize>,
+    /// Events waiting behind the last batch, the UI is catching up while there are any
+    pub events_backlog: usize,
+    /// SSE event types the GUI has no handling for, with how many arrived
+    pub unhandled_events: BTreeMap<String, usize>,
+    pub show_worktrees: bool,
+    pub confirm_delete_worktree: Option<String>,
+    pub diff_view: Option<DiffView>,
+    pub merge_dialog: Option<MergeDialogState>,
+    pub export_dialog: Option<ExportDialogState>,
+    pub auth_prompt: Option<AuthPromptState>,
+    pub show_archived: bool,
+    pub confirm_delete_conversation: Option<String>,
+    pub renaming: Option<RenameState>,
+    /// Color labels by conversation id, saved to Labels.toml
+    pub labels: BTreeMap<String, ColorLabel>,
+    /// Muted conversation ids, saved to Muted.toml
+    pub muted: BTreeSet<String>,
+    /// Only conversations with this label are listed in the sidebar
+    pub label_filter: Option<ColorLabel>,
+    /// Which interaction types the sidebar lists, only its types are used
+    pub interaction_filter: InteractionFilter,
+    /// Local day the daily backup was last taken or found taken
+    pub backup_checked: Option<chrono::NaiveDate>,
+    /// When the core state was last saved
+    pub snapshot_saved: Option<Instant>,
+    /// Backups to restore from, newest first
+    pub backups: Vec<Backup>,
+    /// Interactions deleted here, so server updates don't bring them back
+    pub deleted_conversations: HashSet<String>,
+    /// When we first saw each running interaction, keyed by conversation id
+    pub job_started: HashMap<String, Instant>,
+    /// Jobs we already stopped for going over the safety limits
+    pub auto_interrupted: HashSet<String>,
+    /// Interactions with an interrupt sent but not yet confirmed
+    pub interrupting: HashSet<String>,
+    /// Rules that answer permission requests without asking
+    pub permission_policy: Policy,
+    /// Answered permission requests, oldest first
+    pub permission_audit: Vec<AuditEntry>,
+    pub show_permission_audit: bool,
+    pub show_permission_rules: bool,
+    pub rule_draft: RuleDraft,
+    /// Last time the server reported anything for each running interaction
+    pub last_progress: HashMap<String, Instant>,
+    /// Running interactions that went quiet for longer than the heartbeat window
+    pub stalled: HashSet<String>,
+    /// Plugins that loaded, in the order they're configured
+    pub plugins: Vec<LoadedPlugin>,
+    /// How plugins render messages, by message id, messages no plugin takes are `Declined`
+    pub plugin_renderings: HashMap<String, Rendering>,
+    /// Sidebar panels by plugin name
+    pub plugin_panels: HashMap<String, Panel>,
+    /// Where the user was when panels were last asked for
+    pub plugin_context: Option<PluginContext>,
+    /// When worktree statuses were last asked for
+    pub worktree_status_checked: Option<Instant>,
     pub worktree_dialog: Option<WorktreeDialogState>,
-    pub notification: Option<(Notification, std::time::Instant)>,
+    pub command_palette: Option<CommandPaletteState>,
+    /// Worktrees, the toast on screen and permission answers in flight, only changed through the core reducer
+    pub editor: EditorState,
+    /// What the core reducer asked for and the app hasn't carried out yet
+    pub effects: Vec<Effect>,
+    /// The core reducer with the middleware registered at startup, clones share it
+    pub pipeline: Arc<Mutex<Pipeline>>,
+    /// Every core action dispatched, recorded by the pipeline
+    pub action_log: Arc<Mutex<ActionLog>>,
+    pub show_action_log: bool,
+    /// The core state at the point stepped back to in the action log, `None` while following along live
+    pub inspected: Option<EditorState>,
+    pub settings: Settings,
+    pub window_focused: bool,
+    /// Files are being dragged over the window
+    pub drop_hover: bool,
+    /// What the OS was last seen to be set to
+    pub system_appearance: Appearance,
+    /// Picked from the palette, wins over the configured mode until restart
+    pub appearance_override: Option<AppearanceMode>,
+    /// Picked from the palette, wins over the configured variant until restart
+    pub palette_override: Option<PaletteVariant>,
+    /// Toggled from the palette, wins over the configured minimap setting until restart
+    pub minimap_override: Option<bool>,
+    /// When the OS appearance was last asked for
+    pub appearance_checked: Option<Instant>,
+    pub clock: Arc<dyn Clock>,
+    pub ids: Arc<dyn IdGenerator>,
 }
 
 impl Default for AppState {
     fn default() -> Self {
+        Self::new(Arc::new(SystemClock), Arc::new(UuidIds::default()))
+    }
+}
+
+impl AppState {
+    /// Fresh state reading time and ids from the given sources
+    pub fn new(clock: Arc<dyn Clock>, ids: Arc<dyn IdGenerator>) -> Self {
         // Start with one default conversation
         let default_conversation = Conversation {
-            id: uuid::Uuid::new_v4().to_string(),
+            id: ids.conversation_id(),
             title: generate_ubuntu_style_name(),
+            title_source: TitleSource::Generated,
+            interaction_type: InteractionType::Query,
             messages: Vec::new(),
+            muted: false,
+            archived: false,
+            worktree_id: None,
+            bookmarks: HashSet::new(),
+            server: DEFAULT_SERVER.to_string(),
+            history_cursor: None,
+            loading_history: false,
+            budget: None,
+            agents: Vec::new(),
+            pins: Vec::new(),
+            route: None,
+            review_stack: Vec::new(),
         };
+        // The composer starts out drafting for it
+        let focused_interaction = Some(default_conversation.id.clone());
+        let action_log = Arc::new(Mutex::new(ActionLog::default()));
         
         Self {
             conversations: vec![default_conversation],
             active_conversation: Some(0),
-            input: String::new(),
-            current_worktree: None,
-            available_worktrees: vec![],
+            servers: vec![DEFAULT_SERVER.to_string()],
+            active_server: DEFAULT_SERVER.to_string(),
+            agents: Vec::new(),
+            agent_selector: None,
+            agent_config: None,
+            show_agent_health: false,
+            pins_expanded: false,
+            pin_draft: String::new(),
+            tag_draft: String::new(),
+            file_picker: None,
+            composer_attachments: Vec::new(),
+            images: HashMap::new(),
+            expanded_image: None,
+            artifacts_tab: false,
+            saving_artifacts: HashSet::new(),
+            artifact_save: None,
+            agent_statuses: HashMap::new(),
+            agent_status_checked: None,
+            input_tokens: 0,
+            sent_locally: HashMap::new(),
+            open_timings: HashSet::new(),
+            expanded_template: None,
+            mention: None,
+            editing: None,
+            hovered_message: None,
+            confirm_delete: None,
+            deleted_messages: HashSet::new(),
+            show_jobs: false,
+            show_debug_overlay: false,
+            focus_mode: false,
+            presentation: None,
+            events_backlog: 0,
+            unhandled_events: BTreeMap::new(),
+            show_worktrees: false,
+            confirm_delete_worktree: None,
+            diff_view: None,
+            merge_dialog: None,
+            export_dialog: None,
+            auth_prompt: None,
+            show_archived: false,
+            confirm_delete_conversation: None,
+            renaming: None,
+            labels: BTreeMap::new(),
+            muted: BTreeSet::new(),
+            label_filter: None,
+            interaction_filter: InteractionFilter::default(),
+            backup_checked: None,
+            snapshot_saved: None,
+            backups: Vec::new(),
+            deleted_conversations: HashSet::new(),
+            job_started: HashMap::new(),
+            auto_interrupted: HashSet::new(),
+            interrupting: HashSet::new(),
+            permission_policy: Policy::default(),
+            permission_audit: Vec::new(),
+            show_permission_audit: false,
+            show_permission_rules: false,
+            rule_draft: RuleDraft::default(),
+            last_progress: HashMap::new(),
+            stalled: HashSet::new(),
+            plugins: Vec::new(),
+            plugin_renderings: HashMap::new(),
+            plugin_panels: HashMap::new(),
+            plugin_context: None,
+            worktree_status_checked: None,
             worktree_dialog: None,
-            notification: None,
+            command_palette: None,
+            editor: EditorState { focused_interaction, ..EditorState::default() },
+            effects: Vec::new(),
+            pipeline: Arc::new(Mutex::new(pipeline(action_log.clone(), clock.clone()))),
+            action_log,
+            show_action_log: false,
+            inspected: None,
+            settings: Settings::default(),
+            window_focused: true,
+            drop_hover: false,
+            system_appearance: Appearance::Dark,
+            appearance_override: None,
+            palette_override: None,
+            minimap_override: None,
+            appearance_checked: None,
+            clock,
+            ids,
         }
     }
 }
 
+/// Whether the toast on screen has been up long enough to clear
+pub fn notification_expired(state: &AppState) -> bool {
+    state.editor.notification.as_ref().is_some_and(|(_, shown)| {
+        (state.clock.utc() - *shown).to_std().is_ok_and(|up| up >= NOTIFICATION_TIMEOUT)
+    })
+}
+
+/// Whether it's been `SAVE_INTERVAL` since the core state was last saved
+pub fn snapshot_due(state: &AppState) -> bool {
+    state.snapshot_saved.map_or(true, |saved| state.clock.instant().duration_since(saved) >= SAVE_INTERVAL)
+}
+
+/// The configured mode, unless overridden from the palette
+pub fn appearance_mode(state: &AppState) -> AppearanceMode {
+    state.appearance_override.unwrap_or(state.settings.appearance.mode)
+}
+
+/// The configured palette variant, unless overridden from the palette
+pub fn palette_variant(state: &AppState) -> PaletteVariant {
+    state.palette_override.unwrap_or(state.settings.appearance.palette)
+}
+
+/// Whether the overview strip shows beside the transcript
+pub fn minimap_enabled(state: &AppState) -> bool {
+    state.minimap_override.unwrap_or(state.settings.messages.minimap)
+}
+
+/// Dark or light, after following the OS if that's the mode
+pub fn effective_appearance(state: &AppState) -> Appearance {
+    match appearance_mode(state) {
+        AppearanceMode::System => state.system_appearance,
+        AppearanceMode::Dark => Appearance::Dark,
+        AppearanceMode::Light => Appearance::Light,
+    }
+}
+
 /// All possible state transitions
 #[derive(Debug, Clone)]
 pub enum Action {
     // User actions
     InputChanged(String),
+    TemplateExpanded { trigger: String },
+    DismissTemplate,
+    MentionChanged(Option<String>),
+    SelectNextMention,
+    SelectPreviousMention,
     SendMessage,
+    HoverMessage(Option<String>),
+    /// The server took the newest message, under its own id
+    MessageAccepted { conversation_idx: usize, message_id: String },
+    ToggleTimings(String),
+    EditMessage { conversation_idx: usize, message_id: String },
+    CancelEdit,
+    RequestDeleteMessage(String),
+    CancelDeleteMessage,
+    DeleteMessage { conversation_idx: usize, message_id: String },
+    ResendMessage,
+    ResumeMessage { conversation_idx: usize, content: String },
     NewConversation,
     SelectConversation(usize),
+    /// Names of the configured servers, the first one takes over the default
+    ServersConfigured(Vec<String>),
+    SwitchServer(String),
+    /// Follows a session the server listed, `None` goes back to the default stream
+    SwitchSession(Option<String>),
+    AgentsLoaded(Vec<Agent>),
+    OpenAgentSelector(usize),
+    CloseAgentSelector,
+    /// Attaching or detaching an agent was asked for
+    AgentChangeRequested(String),
+    /// The agents the server says are attached now
+    AgentsAttached { server: String, conversation_id: String, agents: Vec<String> },
+    AgentChangeFailed(String),
+    /// Picks the agent for the next messages, `None` goes back to automatic routing
+    RouteTo { conversation_idx: usize, agent_id: Option<String> },
+    OpenAgentConfig(String),
+    CloseAgentConfig,
+    /// The picker lists its directory when opened, here it's only shown
+    OpenFilePicker(FilePicker),
+    CloseFilePicker,
+    /// A file was picked and is being uploaded
+    AttachmentPicked(Pending),
+    AttachmentUploaded { path: PathBuf, result: Result<Attachment, String> },
+    RemoveAttachment(usize),
+    ImageRequested(String),
+    ImageLoaded { key: String, result: Result<LoadedImage, String> },
+    ExpandImage(Option<ImageSource>),
+    ShowArtifacts(bool),
+    OpenSaveArtifact(SaveDialog),
+    SaveArtifactPathChanged(String),
+    CloseSaveArtifact,
+    ArtifactSaveStarted(String),
+    ArtifactSaved { id: String, result: Result<PathBuf, String> },
+    UpdateAgentConfig(ConfigField, String),
+    /// The configuration was sent to the server
+    AgentConfigRequested,
+    AgentConfigSaved { server: String, agent_id: String, config: AgentConfig },
+    AgentConfigFailed(String),
+    ToggleAgentHealth,
+    AgentStatusRequested,
+    AgentStatusesLoaded(Vec<AgentStatus>),
+    /// A restart was asked for, the agent shows as restarting until the server says otherwise
+    AgentRestartRequested(String),
+    AgentStatusChanged(AgentStatus),
+    ToggleMute(usize),
+    /// `None` removes the budget
+    SetTokenBudget { conversation_idx: usize, budget: Option<TokenBudget> },
+    ToggleBookmark { conversation_idx: usize, message_id: String },
+    /// Pin it to the conversation, or unpin it if it's pinned
+    TogglePin { conversation_idx: usize, pin: Pin },
+    SetPinsExpanded(bool),
+    PinDraftChanged(String),
+    /// Pin the typed file path to the conversation
+    PinDraftedFile(usize),
+    TagDraftChanged(String),
+    /// Tag the conversation with the typed tag
+    AddDraftedTag(usize),
+    RemoveTag { conversation_idx: usize, tag: String },
+    ToggleMinimap,
+    ToggleFocusMode,
+    /// Present the active conversation from its first slide
+    StartPresentation,
+    EndPresentation,
+    GoToSlide(usize),
+    ArchiveConversation(usize),
+    UnarchiveConversation(usize),
+    ToggleArchivedSection,
+    RequestDeleteConversation(usize),
+    CancelDeleteConversation,
+    DeleteConversation(usize),
+    SetLabel { conversation_idx: usize, label: Option<ColorLabel> },
+    FilterByLabel(Option<ColorLabel>),
+    /// List only conversations of this interaction type, `None` for all of them
+    FilterByType(Option<InteractionType>),
+    BackupsChecked { date: chrono::NaiveDate, backups: Vec<Backup> },
+    EventsBacklogged(usize),
+    /// The stream carried an event of a type we drop
+    SseEventUnhandled(String),
+    PluginLoaded(LoadedPlugin),
+    /// A plugin was asked to render the message, or none would
+    PluginRenderRequested { message_id: String, rendering: Rendering },
+    /// `None` keeps the message as text
+    PluginRendered { message_id: String, blocks: Option<Vec<Block>> },
+    PluginPanelsRequested(PluginContext),
+    PluginPanelLoaded { plugin: String, panel: Option<Panel> },
+    /// Labels and permission files were copied back from a backup, reloaded
+    LocalFilesRestored {
+        labels: BTreeMap<String, ColorLabel>,
+        muted: BTreeSet<String>,
+        permission_policy: Policy,
+        permission_audit: Vec<AuditEntry>,
+    },
+    StartRename(usize),
+    RenameChanged(String),
+    /// Keep the typed title, a blank one leaves the title as it was
+    CommitRename,
+    CancelRename,
+    ToggleJobsPanel,
+    ToggleDebugOverlay,
+    ToggleWorktreePanel,
+    RequestDeleteWorktree(String),
+    CancelDeleteWorktree,
+    WorktreeDeleted(String),
+    OpenDiff { worktree_id: String, title: String },
+    DiffLoaded(Vec<diff::FileDiff>),
+    DiffFailed(String),
+    SelectDiffFile(usize),
+    ReviewReplyChanged(String),
+    ReviewReplySending,
+    ReviewReplySent,
+    ReviewReplyFailed(String),
+    ToggleHunk(usize),
+    CloseDiff,
+    OpenMergeDialog { worktree_id: String, mode: MergeMode },
+    MergeMessageChanged(String),
+    MergeBodyChanged(String),
+    MergeStarted,
+    MergeFailed(String),
+    CloseMergeDialog,
+    /// Open the export dialog on the active conversation, everything selected
+    OpenExportDialog,
+    /// First message to export, the last moves along if it would end up before it
+    SetExportFrom(usize),
+    /// Last message to export, the first moves along if it would end up after it
+    SetExportTo(usize),
+    ToggleExportPrompts,
+    ToggleExportResponses,
+    CloseExportDialog,
+    /// The server answered 401, ask for a key unless we already are
+    RequestCredentials,
+    ApiKeyChanged(String),
+    CloseAuthPrompt,
+    SyncJobs,
+    AutoInterrupted(String),
+    InterruptRequested(String),
+    InterruptFailed(String),
+    /// The server stopped the interaction, anything in flight won't finish
+    InteractionInterrupted(String),
+    InteractionProgressed(String),
+    InteractionStalled(String),
     RespondToPermission { approved: bool },
+    /// Ask for the page before the oldest message we have, once at a time
+    HistoryRequested(usize),
+    HistoryLoaded { server: String, conversation_id: String, messages: Vec<LegacyMessage>, has_more: bool },
+    HistoryFailed { server: String, conversation_id: String },
     
     // Worktree dialog actions
     OpenWorktreeDialog,
@@ -77,20 +694,60 @@ pub enum Action {
     UpdateWorktreeCustomPath(String),
     CreateWorktree,
     
+    // Command palette actions
+    OpenCommandPalette,
+    CloseCommandPalette,
+    UpdatePaletteQuery(String),
+    SelectNextCommand,
+    SelectPreviousCommand,
+    
     // API responses
     WorktreesLoaded(Vec<Worktree>),
+    WorktreeStatusRequested,
+    AppearanceCheckRequested,
+    SystemAppearanceDetected(Appearance),
+    SetAppearance(AppearanceMode),
+    SetPaletteVariant(PaletteVariant),
     WorktreeCreated(Worktree),
     WorktreeCreationFailed(String),
     SelectWorktree(String),
     WorktreeChanged(Option<Worktree>),
     MessageSent { conversation_idx: usize, id: String },
     MessageProcessing { conversation_idx: usize, id: String },
-    MessageCompleted { conversation_idx: usize, id: String, response: Option<String>, error: Option<String>, metadata: Option<InteractionMetadata> },
+    MessageCompleted { conversation_idx: usize, id: String, response: Option<String>, error: Option<String>, metadata: Option<Box<InteractionMetadata>> },
     MessageWaitingForPermission { conversation_idx: usize, id: String, permission_request: crate::types::ToolPermissionRequest },
+    /// The server never got the newest pending message
+    MessageSendFailed { conversation_idx: usize, error: MessageError },
+    /// Send a failed message again, dropping it first if the server never saw it
+    RetryMessage { conversation_idx: usize, message_id: String },
+    AddPermissionRule(Decision, Rule),
+    RemovePermissionRule(Decision, usize),
+    ClearPermissionPolicy,
+    TogglePermissionRules,
+    UpdateRuleDraft(RuleField, String),
+    SetRuleDraftDecision(Decision),
+    RuleDraftFailed(String),
+    /// Note when new requests started waiting and forget ones that no longer are
+    SyncPendingPermissions(HashSet<String>),
+    PermissionResolved(AuditEntry),
+    TogglePermissionAudit,
+    /// Closing the log goes back to following along live
+    ToggleActionLog,
+    StepActionLogBack,
+    StepActionLogForward,
+    /// Hand the core state to be written out, periodically and on the way out
+    SaveSnapshot,
     
     // Notifications
+    /// Shown as of now on the clock
     ShowNotification(Notification),
-    ClearNotification,
+    
+    /// Worktree, notification and permission answer state kept by the core reducer
+    Editor(EditorAction),
+    
+    // Window
+    WindowFocusChanged(bool),
+    SetDropHover(bool),
     
     // Errors
     Error(String),
@@ -98,182 +755,1807 @@ pub enum Action {
 
 /// Pure state transitions - no side effects
 pub fn update(state: &mut AppState, action: Action) {
+    apply(state, action);
+    // Drafts belong to conversations, whichever action moves between them
+    if active_conversation_id(state) != state.editor.focused_interaction {
+        switch_draft(state);
+    }
+}
+
+fn apply(state: &mut AppState, action: Action) {
     match action {
         Action::InputChanged(text) => {
-            state.input = text;
+            set_input(state, text);
+            // Any further editing accepts the template
+            state.expanded_template = None;
+        }
+        
+        Action::TemplateExpanded { trigger } => {
+            state.expanded_template = Some(trigger);
+        }
+        
+        Action::DismissTemplate => {
+            if let Some(trigger) = state.expanded_template.take() {
+                set_input(state, trigger);
+            }
+        }
+        
+        Action::MentionChanged(query) => {
+            state.mention = match (query, state.mention.take()) {
+                // Keep the selection while the query stays the same
+                (Some(query), Some(mention)) if mention.query == query => Some(mention),
+                (Some(query), _) => Some(MentionState { query, selected: 0 }),
+                (None, _) => None,
+            };
+        }
+        
+        Action::SelectNextMention => {
+            let count = state.mention.as_ref()
+                .map(|m| mentions::matches(&state.editor.worktree_files, &m.query).len())
+                .unwrap_or(0);
+            if let Some(mention) = &mut state.mention {
+                if count > 0 {
+                    mention.selected = (mention.selected + 1) % count;
+                }
+            }
+        }
+        
+        Action::SelectPreviousMention => {
+            let count = state.mention.as_ref()
+                .map(|m| mentions::matches(&state.editor.worktree_files, &m.query).len())
+                .unwrap_or(0);
+            if let Some(mention) = &mut state.mention {
+                if count > 0 {
+                    mention.selected = (mention.selected + count - 1) % count;
+                }
+            }
         }
         
         Action::SendMessage => {
-            if !state.input.is_empty() {
+            if !state.editor.draft.content.is_empty() {
                 if let Some(conv_idx) = state.active_conversation {
                     if let Some(conv) = state.conversations.get_mut(conv_idx) {
                         let message = LegacyMessage {
-                            id: format!("temp-{}", conv.messages.len()),
-                            content: state.input.clone(),
+                            id: state.ids.temp_id(),
+                            content: state.editor.draft.content.clone(),
                             response: None,
                             status: MessageStatus::Pending,
                             metadata: None,
                             pending_tool_permission: None,
+                            sent_at: Some(state.clock.utc()),
+                            error: None,
+                            attachments: attachments::uploaded(&state.composer_attachments),
                         };
                         conv.messages.push(message);
-                        state.input.clear();
+                        state.composer_attachments.clear();
+                        clear_input(state);
+                        state.expanded_template = None;
+                        state.mention = None;
                     }
                 }
             }
         }
         
-        Action::NewConversation => {
-            let new_conversation = Conversation {
-                id: uuid::Uuid::new_v4().to_string(),
-                title: generate_ubuntu_style_name(),
-                messages: Vec::new(),
+        Action::HoverMessage(message_id) => {
+            state.hovered_message = message_id;
+        }
+        
+        Action::MessageAccepted { conversation_idx, message_id } => {
+            // The server's copy replaces ours, keep the one time only we know
+            let sent_at = state.conversations.get(conversation_idx)
+                .and_then(|conv| conv.messages.last())
+                .and_then(|msg| msg.sent_at);
+            if let Some(sent_at) = sent_at {
+                state.sent_locally.insert(message_id, sent_at);
+            }
+        }
+        
+        Action::ToggleTimings(message_id) => {
+            if !state.open_timings.remove(&message_id) {
+                state.open_timings.insert(message_id);
+            }
+        }
+        
+        Action::EditMessage { conversation_idx, message_id } => {
+            let content = state.conversations.get(conversation_idx)
+                .and_then(|conv| conv.messages.iter().find(|m| m.id == message_id))
+                .map(|msg| msg.content.clone());
+            
+            if let Some(content) = content {
+                set_input(state, content);
+                state.expanded_template = None;
+                state.mention = None;
+                state.editing = Some(EditingMessage { conversation_idx, message_id });
+            }
+        }
+        
+        Action::RequestDeleteMessage(message_id) => {
+            state.confirm_delete = Some(message_id);
+        }
+        
+        Action::CancelDeleteMessage => {
+            state.confirm_delete = None;
+        }
+        
+        Action::DeleteMessage { conversation_idx, message_id } => {
+            state.confirm_delete = None;
+            
+            let Some(conv) = state.conversations.get_mut(conversation_idx) else {
+                return;
+            };
+            let Some(position) = conv.messages.iter().position(|m| m.id == message_id) else {
+                return;
+            };
+            // In-flight messages are still being matched up with server responses
+            if !can_delete(&conv.messages[position]) {
+                return;
+            }
+            
+            conv.messages.remove(position);
+            conv.pins.retain(|pin| *pin != Pin::Message(message_id.clone()));
+            state.deleted_messages.insert(message_id.clone());
+            
+            if state.hovered_message.as_deref() == Some(message_id.as_str()) {
+                state.hovered_message = None;
+            }
+            if state.editing.as_ref().is_some_and(|e| e.conversation_idx == conversation_idx && e.message_id == message_id) {
+                state.editing = None;
+                clear_input(state);
+            }
+        }
+        
+        Action::CancelEdit => {
+            if state.editing.take().is_some() {
+                clear_input(state);
+            }
+        }
+        
+        // The server can't rewrite history, so the edited message starts a fresh
+        // interaction in its own conversation and the original stays untouched
+        Action::ResendMessage => {
+            let Some(editing) = state.editing.take() else {
+                return;
             };
-            state.conversations.push(new_conversation);
+            if state.editor.draft.content.is_empty() {
+                return;
+            }
+            
+            let original = state.conversations.get(editing.conversation_idx);
+            let title = original
+                .map(|conv| format!("{} (edited)", conv.title))
+                .unwrap_or_else(generate_ubuntu_style_name);
+            let worktree_id = original.and_then(|conv| conv.worktree_id.clone());
+            
+            state.conversations.push(Conversation {
+                id: state.ids.conversation_id(),
+                title,
+                title_source: TitleSource::Generated,
+                interaction_type: InteractionType::Query,
+                messages: vec![LegacyMessage {
+                    id: state.ids.temp_id(),
+                    content: state.editor.draft.content.clone(),
+                    response: None,
+                    status: MessageStatus::Pending,
+                    metadata: None,
+                    pending_tool_permission: None,
+                    sent_at: Some(state.clock.utc()),
+                    error: None,
+                    attachments: Vec::new(),
+                }],
+                muted: false,
+                archived: false,
+                worktree_id,
+                bookmarks: HashSet::new(),
+                server: state.active_server.clone(),
+                history_cursor: None,
+                loading_history: false,
+                budget: None,
+                agents: Vec::new(),
+                pins: Vec::new(),
+                route: None,
+                review_stack: Vec::new(),
+            });
+            clear_input(state);
             state.active_conversation = Some(state.conversations.len() - 1);
+            state.mention = None;
         }
         
+        // Queue the continuation like any other message, leaving the composer alone
+        Action::ResumeMessage { conversation_idx, content } => {
+            if let Some(conv) = state.conversations.get_mut(conversation_idx) {
+                conv.messages.push(LegacyMessage {
+                    id: state.ids.temp_id(),
+                    content,
+                    response: None,
+                    status: MessageStatus::Pending,
+                    metadata: None,
+                    pending_tool_permission: None,
+                    sent_at: Some(state.clock.utc()),
+                    error: None,
+                    attachments: Vec::new(),
+                });
+            }
+        }
+        
+        Action::NewConversation => new_conversation(state),
+        
         Action::SelectConversation(idx) => {
-            if idx < state.conversations.len() {
+            if let Some(conv) = state.conversations.get(idx) {
+                // Desktop notifications can point at another server's conversation
+                if conv.server != state.active_server {
+                    let server = conv.server.clone();
+                    enter_server(state, server);
+                }
                 state.active_conversation = Some(idx);
             }
+            state.renaming = state.renaming.take().filter(|r| r.conversation_idx == idx);
+            state.presentation = None;
+            state.artifacts_tab = false;
         }
         
-        Action::MessageSent { conversation_idx, id } => {
-            // Update the temp ID with real ID
-            if let Some(conv) = state.conversations.get_mut(conversation_idx) {
-                if let Some(msg) = conv.messages.last_mut() {
-                    if msg.id.starts_with("temp-") {
-                        msg.id = id;
-                    }
+        Action::ServersConfigured(servers) => {
+            let Some(primary) = servers.first().cloned() else {
+                return;
+            };
+            // Whatever was started before config loaded belongs to the primary server
+            for conv in &mut state.conversations {
+                if conv.server == state.active_server {
+                    conv.server = primary.clone();
                 }
             }
+            state.servers = servers;
+            state.active_server = primary;
         }
         
-        Action::MessageProcessing { conversation_idx, id } => {
-            if let Some(conv) = state.conversations.get_mut(conversation_idx) {
-                if let Some(msg) = conv.messages.iter_mut().find(|m| m.id == id) {
-                    msg.status = MessageStatus::Processing;
-                }
+        Action::SwitchServer(server) => {
+            if server == state.active_server || !state.servers.contains(&server) {
+                return;
+            }
+            enter_server(state, server);
+            
+            // Pick up where the user left off there, or start fresh
+            let first = (0..state.conversations.len()).find(|&i| {
+                let conv = &state.conversations[i];
+                on_active_server(state, conv) && !conv.archived
+            });
+            match first {
+                Some(idx) => state.active_conversation = Some(idx),
+                None => new_conversation(state),
             }
         }
         
-        Action::MessageCompleted { conversation_idx, id, response, error, metadata } => {
-            if let Some(conv) = state.conversations.get_mut(conversation_idx) {
-                if let Some(msg) = conv.messages.iter_mut().find(|m| m.id == id) {
-                    msg.response = response;
-                    msg.status = if error.is_some() {
-                        MessageStatus::Error
-                    } else {
-                        MessageStatus::Completed
-                    };
-                    msg.metadata = metadata;
-                    msg.pending_tool_permission = None; // Clear any pending permission
-                }
+        Action::SwitchSession(session_id) => {
+            // A click can land after the session was deleted elsewhere
+            let known = session_id.as_ref().map_or(true, |id| state.editor.sessions.iter().any(|s| &s.id == id));
+            if known {
+                let session_id = session_id.unwrap_or_else(|| DEFAULT_SESSION_ID.to_string());
+                dispatch(state, EditorAction::SwitchSession { session_id });
             }
         }
         
-        Action::MessageWaitingForPermission { conversation_idx, id, permission_request } => {
+        Action::AgentsLoaded(agents) => {
+            state.agents = agents;
+        }
+        
+        Action::OpenAgentSelector(conversation_idx) => {
+            if conversation_idx < state.conversations.len() && !state.agents.is_empty() {
+                state.agent_selector = Some(AgentSelectorState { conversation_idx, pending: None, error: None });
+            }
+        }
+        
+        Action::CloseAgentSelector => {
+            state.agent_selector = None;
+        }
+        
+        Action::AgentChangeRequested(agent_id) => {
+            if let Some(selector) = &mut state.agent_selector {
+                selector.pending = Some(agent_id);
+                selector.error = None;
+            }
+        }
+        
+        Action::AgentsAttached { server, conversation_id, agents } => {
+            if let Some(conv) = state.conversations.iter_mut().find(|c| c.server == server && c.id == conversation_id) {
+                conv.agents = agents;
+                keep_route(conv);
+            }
+            if let Some(selector) = &mut state.agent_selector {
+                selector.pending = None;
+            }
+        }
+        
+        Action::RouteTo { conversation_idx, agent_id } => {
             if let Some(conv) = state.conversations.get_mut(conversation_idx) {
-                if let Some(msg) = conv.messages.iter_mut().find(|m| m.id == id) {
-                    msg.status = MessageStatus::WaitingForPermission;
-                    msg.pending_tool_permission = Some(permission_request);
-                }
+                conv.route = agent_id.filter(|id| conv.agents.contains(id));
             }
         }
         
-        Action::RespondToPermission { approved } => {
-            if let Some(conv_idx) = state.active_conversation {
-                if let Some(conv) = state.conversations.get_mut(conv_idx) {
-                    // Find the message waiting for permission
-                    if let Some(_msg) = conv.messages.iter_mut().find(|m| m.status == MessageStatus::WaitingForPermission) {
-                        // Add the response as a new message
-                        let response_content = if approved { "Yes, go ahead" } else { "No, don't use that tool" };
-                        state.input = response_content.to_string();
-                        // The SendMessage action will handle sending this
+        Action::AgentChangeFailed(error) => {
+            if let Some(selector) = &mut state.agent_selector {
+                selector.pending = None;
+                selector.error = Some(error);
+            }
+        }
+        
+        Action::OpenAgentConfig(agent_id) => {
+            if let Some(agent) = state.agents.iter().find(|agent| agent.id == agent_id) {
+                state.agent_config = Some(ConfigDraft::for_agent(agent));
+            }
+        }
+        
+        Action::OpenFilePicker(picker) => {
+            state.file_picker = Some(picker);
+        }
+        
+        Action::CloseFilePicker => {
+            state.file_picker = None;
+        }
+        
+        Action::AttachmentPicked(pending) => {
+            state.file_picker = None;
+            // Picking a file again replaces it, say after a failed upload
+            state.composer_attachments.retain(|p| p.path != pending.path);
+            state.composer_attachments.push(pending);
+        }
+        
+        Action::AttachmentUploaded { path, result } => {
+            // Removed while uploading, nothing to update
+            if let Some(pending) = state.composer_attachments.iter_mut().find(|p| p.path == path && p.upload == Upload::Uploading) {
+                pending.upload = match result {
+                    Ok(attachment) => Upload::Done(attachment),
+                    Err(error) => Upload::Failed(error),
+                };
+            }
+        }
+        
+        Action::ShowArtifacts(show) => {
+            state.artifacts_tab = show;
+        }
+        
+        Action::OpenSaveArtifact(dialog) => {
+            state.artifact_save = Some(dialog);
+        }
+        
+        Action::SaveArtifactPathChanged(path) => {
+            if let Some(dialog) = &mut state.artifact_save {
+                dialog.path = path;
+                dialog.error = None;
+            }
+        }
+        
+        Action::CloseSaveArtifact => {
+            // The download can't be called back, the dialog waits for it
+            state.artifact_save = state.artifact_save.take().filter(|dialog| dialog.saving);
+        }
+        
+        Action::ArtifactSaveStarted(id) => {
+            if let Some(dialog) = state.artifact_save.as_mut().filter(|dialog| dialog.artifact.id == id) {
+                dialog.saving = true;
+                dialog.error = None;
+            }
+            state.saving_artifacts.insert(id);
+        }
+        
+        Action::ArtifactSaved { id, result } => {
+            state.saving_artifacts.remove(&id);
+            if state.artifact_save.as_ref().is_some_and(|dialog| dialog.artifact.id == id) {
+                match result {
+                    Ok(_) => state.artifact_save = None,
+                    Err(error) => {
+                        if let Some(dialog) = &mut state.artifact_save {
+                            dialog.saving = false;
+                            dialog.error = Some(error);
+                        }
                     }
                 }
             }
         }
         
-        Action::WorktreesLoaded(worktrees) => {
-            state.available_worktrees = worktrees;
+        Action::ImageRequested(key) => {
+            state.images.insert(key, ImageLoad::Loading);
         }
         
-        Action::SelectWorktree(id) => {
-            if let Some(worktree) = state.available_worktrees.iter().find(|w| w.id == id) {
-                state.current_worktree = Some(worktree.clone());
+        Action::ImageLoaded { key, result } => {
+            state.images.insert(key, match result {
+                Ok(image) => ImageLoad::Loaded(image),
+                Err(error) => ImageLoad::Failed(error),
+            });
+        }
+        
+        Action::ExpandImage(source) => {
+            state.expanded_image = source;
+        }
+        
+        Action::RemoveAttachment(idx) => {
+            if idx < state.composer_attachments.len() {
+                state.composer_attachments.remove(idx);
             }
         }
         
-        Action::WorktreeChanged(worktree) => {
-            state.current_worktree = worktree;
+        Action::CloseAgentConfig => {
+            state.agent_config = None;
         }
         
-        Action::OpenWorktreeDialog => {
-            state.worktree_dialog = Some(WorktreeDialogState {
-                base_branch: "main".to_string(),
-                ..Default::default()
-            });
+        Action::UpdateAgentConfig(field, value) => {
+            if let Some(draft) = &mut state.agent_config {
+                draft.set(field, value);
+            }
         }
         
-        Action::CloseWorktreeDialog => {
-            state.worktree_dialog = None;
+        Action::AgentConfigRequested => {
+            if let Some(draft) = &mut state.agent_config {
+                draft.saving = true;
+                draft.error = None;
+            }
         }
         
-        Action::UpdateWorktreeBranchName(name) => {
-            if let Some(dialog) = &mut state.worktree_dialog {
-                dialog.branch_name = name;
-                dialog.error = None; // Clear error when user types
+        Action::AgentConfigSaved { server, agent_id, config } => {
+            if server != state.active_server {
+                return;
+            }
+            if let Some(agent) = state.agents.iter_mut().find(|agent| agent.id == agent_id) {
+                agent.config = Some(config);
+            }
+            if state.agent_config.as_ref().is_some_and(|draft| draft.agent_id == agent_id) {
+                state.agent_config = None;
             }
         }
         
-        Action::UpdateWorktreeBaseBranch(branch) => {
-            if let Some(dialog) = &mut state.worktree_dialog {
-                dialog.base_branch = branch;
+        Action::AgentConfigFailed(error) => {
+            if let Some(draft) = &mut state.agent_config {
+                draft.saving = false;
+                draft.error = Some(error);
             }
         }
         
-        Action::UpdateWorktreeCustomPath(path) => {
-            if let Some(dialog) = &mut state.worktree_dialog {
-                dialog.custom_path = path;
+        Action::ToggleAgentHealth => {
+            state.show_agent_health = !state.show_agent_health;
+        }
+        
+        Action::AgentStatusRequested => {
+            state.agent_status_checked = Some(state.clock.instant());
+        }
+        
+        Action::AgentStatusesLoaded(statuses) => {
+            state.agent_statuses = statuses.into_iter().map(|status| (status.agent_id.clone(), status)).collect();
+        }
+        
+        Action::AgentRestartRequested(agent_id) => {
+            if let Some(status) = state.agent_statuses.get_mut(&agent_id) {
+                status.status = AgentHealth::Restarting;
+                status.error = None;
             }
         }
         
-        Action::CreateWorktree => {
-            // Just clear error, actual creation happens in update function
-            if let Some(dialog) = &mut state.worktree_dialog {
-                dialog.error = None;
+        Action::AgentStatusChanged(status) => {
+            state.agent_statuses.insert(status.agent_id.clone(), status);
+        }
+        
+        Action::ToggleMute(idx) => {
+            if let Some(conv) = state.conversations.get_mut(idx) {
+                conv.muted = !conv.muted;
+                if conv.muted {
+                    state.muted.insert(conv.id.clone());
+                } else {
+                    state.muted.remove(&conv.id);
+                }
             }
         }
         
-        Action::WorktreeCreated(worktree) => {
-            state.available_worktrees.push(worktree.clone());
-            state.current_worktree = Some(worktree.clone());
-            state.worktree_dialog = None;
-            state.notification = Some((
-                Notification::Success(format!("Created worktree '{}'", worktree.branch.as_deref().unwrap_or("unknown"))),
-                std::time::Instant::now()
-            ));
+        Action::SetTokenBudget { conversation_idx, budget } => {
+            if let Some(conv) = state.conversations.get_mut(conversation_idx) {
+                conv.budget = budget;
+            }
         }
         
-        Action::WorktreeCreationFailed(error) => {
-            if let Some(dialog) = &mut state.worktree_dialog {
-                dialog.error = Some(error);
+        Action::ToggleBookmark { conversation_idx, message_id } => {
+            if let Some(conv) = state.conversations.get_mut(conversation_idx) {
+                if !conv.bookmarks.remove(&message_id) {
+                    conv.bookmarks.insert(message_id);
+                }
             }
         }
         
-        Action::ShowNotification(notification) => {
-            state.notification = Some((notification, std::time::Instant::now()));
+        Action::TogglePin { conversation_idx, pin } => {
+            if let Some(conv) = state.conversations.get_mut(conversation_idx) {
+                match conv.pins.iter().position(|pinned| *pinned == pin) {
+                    Some(position) => {
+                        conv.pins.remove(position);
+                    }
+                    None => conv.pins.push(pin),
+                }
+            }
         }
         
-        Action::ClearNotification => {
-            state.notification = None;
+        Action::SetPinsExpanded(expanded) => {
+            state.pins_expanded = expanded;
         }
         
-        Action::Error(_) => {
-            // Errors are ignored for now
+        Action::PinDraftChanged(path) => {
+            state.pin_draft = path;
         }
-    }
+        
+        Action::PinDraftedFile(conversation_idx) => {
+            let path = state.pin_draft.trim().to_string();
+            if let (false, Some(conv)) = (path.is_empty(), state.conversations.get_mut(conversation_idx)) {
+                let pin = Pin::File(path);
+                if !conv.pins.contains(&pin) {
+                    conv.pins.push(pin);
+                }
+                state.pin_draft.clear();
+            }
+        }
+        
+        Action::TagDraftChanged(tag) => {
+            state.tag_draft = tag;
+        }
+        
+        Action::AddDraftedTag(conversation_idx) => {
+            if let Some(conv) = state.conversations.get(conversation_idx) {
+                let action = EditorAction::AddTag { interaction_id: conv.id.clone(), tag: std::mem::take(&mut state.tag_draft) };
+                dispatch(state, action);
+            }
+        }
+        
+        Action::RemoveTag { conversation_idx, tag } => {
+            if let Some(conv) = state.conversations.get(conversation_idx) {
+                let action = EditorAction::RemoveTag { interaction_id: conv.id.clone(), tag };
+                dispatch(state, action);
+            }
+        }
+        
+        Action::ToggleMinimap => {
+            state.minimap_override = Some(!minimap_enabled(state));
+        }
+        
+        Action::ArchiveConversation(idx) => {
+            if let Some(conv) = state.conversations.get_mut(idx) {
+                conv.archived = true;
+                if state.active_conversation == Some(idx) {
+                    state.active_conversation = nearest_visible(state, idx);
+                }
+            }
+        }
+        
+        Action::UnarchiveConversation(idx) => {
+            if let Some(conv) = state.conversations.get_mut(idx) {
+                conv.archived = false;
+            }
+        }
+        
+        Action::ToggleArchivedSection => {
+            state.show_archived = !state.show_archived;
+        }
+        
+        Action::RequestDeleteConversation(idx) => {
+            if let Some(conv) = state.conversations.get(idx) {
+                state.confirm_delete_conversation = Some(conv.id.clone());
+                // The confirmation lives in the sidebar, make sure it's on screen
+                if conv.archived {
+                    state.show_archived = true;
+                }
+            }
+        }
+        
+        Action::CancelDeleteConversation => {
+            state.confirm_delete_conversation = None;
+        }
+        
+        Action::SetLabel { conversation_idx, label } => {
+            let Some(id) = state.conversations.get(conversation_idx).map(|c| c.id.clone()) else {
+                return;
+            };
+            match label {
+                Some(label) => state.labels.insert(id, label),
+                None => state.labels.remove(&id),
+            };
+        }
+        
+        Action::FilterByLabel(label) => {
+            state.label_filter = label;
+        }
+        
+        Action::FilterByType(interaction_type) => {
+            state.interaction_filter.types = interaction_type.into_iter().collect();
+        }
+        
+        Action::BackupsChecked { date, backups } => {
+            state.backup_checked = Some(date);
+            state.backups = backups;
+        }
+        
+        Action::EventsBacklogged(remaining) => {
+            state.events_backlog = remaining;
+        }
+        
+        Action::SseEventUnhandled(event_type) => {
+            let count = state.unhandled_events.entry(event_type.clone()).or_insert(0);
+            *count += 1;
+            // Tell whoever's developing against the server about new event types,
+            // after that they're only counted in the debug overlay
+            if *count == 1 && cfg!(debug_assertions) {
+                notify(state, Notification::Info(format!("The server sent a '{}' event the GUI doesn't handle", event_type)));
+            }
+        }
+        
+        Action::PluginLoaded(plugin) => {
+            state.plugins.retain(|p| p.manifest.name != plugin.manifest.name);
+            state.plugins.push(plugin);
+            // The new plugin may take messages the others didn't
+            state.plugin_renderings.retain(|_, rendering| *rendering != Rendering::Declined);
+            state.plugin_context = None;
+        }
+        
+        Action::PluginRenderRequested { message_id, rendering } => {
+            state.plugin_renderings.insert(message_id, rendering);
+        }
+        
+        Action::PluginRendered { message_id, blocks } => {
+            let rendering = blocks.map_or(Rendering::Declined, Rendering::Blocks);
+            state.plugin_renderings.insert(message_id, rendering);
+        }
+        
+        Action::PluginPanelsRequested(context) => {
+            state.plugin_context = Some(context);
+        }
+        
+        Action::PluginPanelLoaded { plugin, panel } => match panel {
+            Some(panel) => {
+                state.plugin_panels.insert(plugin, panel);
+            }
+            None => {
+                state.plugin_panels.remove(&plugin);
+            }
+        },
+        
+        Action::LocalFilesRestored { labels, muted, permission_policy, permission_audit } => {
+            state.labels = labels;
+            for conv in &mut state.conversations {
+                conv.muted = muted.contains(&conv.id);
+            }
+            state.muted = muted;
+            state.permission_policy = permission_policy;
+            state.permission_audit = permission_audit;
+            // Answers already given stand, the restored rules only apply from here on
+        }
+        
+        Action::StartRename(idx) => {
+            if let Some(conv) = state.conversations.get(idx) {
+                state.renaming = Some(RenameState { conversation_idx: idx, title: conv.title.clone() });
+            }
+        }
+        
+        Action::RenameChanged(title) => {
+            if let Some(renaming) = &mut state.renaming {
+                renaming.title = title;
+            }
+        }
+        
+        Action::CommitRename => {
+            let Some(renaming) = state.renaming.take() else {
+                return;
+            };
+            let title = renaming.title.trim();
+            if let Some(conv) = state.conversations.get_mut(renaming.conversation_idx).filter(|_| !title.is_empty()) {
+                conv.title = title.to_string();
+                conv.title_source = TitleSource::Manual;
+            }
+        }
+        
+        Action::CancelRename => {
+            state.renaming = None;
+        }
+        
+        Action::DeleteConversation(idx) => {
+            state.confirm_delete_conversation = None;
+            state.renaming = None;
+            // It would be pointing at another conversation now
+            state.export_dialog = None;
+            if idx >= state.conversations.len() {
+                return;
+            }
+            
+            let removed = state.conversations.remove(idx);
+            state.labels.remove(&removed.id);
+            state.muted.remove(&removed.id);
+            dispatch(state, EditorAction::DiscardDraft { interaction_id: removed.id.clone() });
+            state.deleted_conversations.insert(removed.id);
+            
+            // Everything holding an index past the removed one shifts down
+            state.active_conversation = match state.active_conversation {
+                Some(active) if active == idx => nearest_visible(state, idx.min(state.conversations.len())),
+                Some(active) if active > idx => Some(active - 1),
+                active => active,
+            };
+            state.editing = match state.editing.take() {
+                Some(editing) if editing.conversation_idx == idx => {
+                    clear_input(state);
+                    None
+                }
+                Some(mut editing) => {
+                    if editing.conversation_idx > idx {
+                        editing.conversation_idx -= 1;
+                    }
+                    Some(editing)
+                }
+                None => None,
+            };
+        }
+        
+        Action::ToggleJobsPanel => {
+            state.show_jobs = !state.show_jobs;
+        }
+        
+        Action::ToggleDebugOverlay => {
+            state.show_debug_overlay = !state.show_debug_overlay;
+        }
+        
+        Action::ToggleFocusMode => {
+            state.focus_mode = !state.focus_mode;
+        }
+        
+        Action::StartPresentation => {
+            let has_slides = state.active_conversation.and_then(|idx| state.conversations.get(idx)).is_some_and(|conv| !presentation::slides(conv).is_empty());
+            if has_slides {
+                state.presentation = Some(0);
+            }
+        }
+        
+        Action::EndPresentation => {
+            state.presentation = None;
+        }
+        
+        Action::GoToSlide(slide) => {
+            let count = state.active_conversation.and_then(|idx| state.conversations.get(idx)).map_or(0, |conv| presentation::slides(conv).len());
+            if state.presentation.is_some() && count > 0 {
+                state.presentation = Some(slide.min(count - 1));
+            }
+        }
+        
+        Action::ToggleWorktreePanel => {
+            state.show_worktrees = !state.show_worktrees;
+            state.confirm_delete_worktree = None;
+        }
+        
+        Action::RequestDeleteWorktree(id) => {
+            state.confirm_delete_worktree = Some(id);
+        }
+        
+        Action::CancelDeleteWorktree => {
+            state.confirm_delete_worktree = None;
+        }
+        
+        Action::WorktreeDeleted(id) => {
+            dispatch(state, EditorAction::WorktreeDeleted { worktree_id: id.clone() });
+            if state.confirm_delete_worktree.as_deref() == Some(id.as_str()) {
+                state.confirm_delete_worktree = None;
+            }
+            // Conversations that worked there fall back to the main checkout
+            for conv in &mut state.conversations {
+                if conv.worktree_id.as_deref() == Some(id.as_str()) {
+                    conv.worktree_id = None;
+                }
+            }
+            if state.diff_view.as_ref().is_some_and(|view| view.worktree_id == id) {
+                state.diff_view = None;
+            }
+        }
+        
+        Action::OpenDiff { worktree_id, title } => {
+            // Refreshing keeps the file being looked at and any reply being written
+            let previous = state.diff_view.take().filter(|view| view.worktree_id == worktree_id);
+            let selected_file = previous.as_ref().map_or(0, |view| view.selected_file);
+            let reply = previous.map(|view| view.reply).unwrap_or_default();
+            state.diff_view = Some(DiffView {
+                worktree_id,
+                title,
+                loading: true,
+                selected_file,
+                reply,
+                ..Default::default()
+            });
+        }
+        
+        Action::DiffLoaded(files) => {
+            if let Some(view) = &mut state.diff_view {
+                view.selected_file = view.selected_file.min(files.len().saturating_sub(1));
+                view.files = files;
+                view.loading = false;
+                view.error = None;
+            }
+        }
+        
+        Action::DiffFailed(error) => {
+            if let Some(view) = &mut state.diff_view {
+                view.loading = false;
+                view.error = Some(error);
+            }
+        }
+        
+        Action::ReviewReplyChanged(reply) => {
+            if let Some(view) = &mut state.diff_view {
+                view.reply = reply;
+            }
+        }
+        
+        Action::ReviewReplySending => {
+            if let Some(view) = &mut state.diff_view {
+                view.reply_sending = true;
+                view.reply_error = None;
+            }
+        }
+        
+        Action::ReviewReplySent => {
+            if let Some(view) = &mut state.diff_view {
+                view.reply.clear();
+                view.reply_sending = false;
+            }
+        }
+        
+        Action::ReviewReplyFailed(error) => {
+            if let Some(view) = &mut state.diff_view {
+                view.reply_sending = false;
+                view.reply_error = Some(error);
+            }
+        }
+        
+        Action::SelectDiffFile(idx) => {
+            if let Some(view) = &mut state.diff_view {
+                if idx < view.files.len() {
+                    view.selected_file = idx;
+                    view.collapsed.clear();
+                }
+            }
+        }
+        
+        Action::ToggleHunk(idx) => {
+            if let Some(view) = &mut state.diff_view {
+                if !view.collapsed.remove(&idx) {
+                    view.collapsed.insert(idx);
+                }
+            }
+        }
+        
+        Action::CloseDiff => {
+            state.diff_view = None;
+        }
+        
+        Action::OpenMergeDialog { worktree_id, mode } => {
+            let Some(worktree) = state.editor.available_worktrees.iter().find(|w| w.id == worktree_id) else {
+                return;
+            };
+            let branch = worktrees::branch_label(worktree).to_string();
+            let base = worktree.base_branch.clone().unwrap_or_else(|| "main".to_string());
+            
+            // Describe the change by what was asked for in it
+            let request = state.conversations
+                .iter()
+                .find(|conv| conv.worktree_id.as_deref() == Some(worktree_id.as_str()))
+                .and_then(|conv| conv.messages.first())
+                .and_then(|msg| msg.content.lines().next())
+                .map(|line| line.chars().take(72).collect::<String>());
+            let message = match (mode, request) {
+                (_, Some(request)) => request,
+                (MergeMode::Merge, None) => format!("Merge {}", branch),
+                (MergeMode::PullRequest, None) => branch.clone(),
+            };
+            
+            state.merge_dialog = Some(MergeDialogState {
+                worktree_id,
+                branch,
+                base,
+                mode,
+                message,
+                body: String::new(),
+                in_progress: false,
+                error: None,
+            });
+        }
+        
+        Action::MergeMessageChanged(message) => {
+            if let Some(dialog) = &mut state.merge_dialog {
+                dialog.message = message;
+            }
+        }
+        
+        Action::MergeBodyChanged(body) => {
+            if let Some(dialog) = &mut state.merge_dialog {
+                dialog.body = body;
+            }
+        }
+        
+        Action::MergeStarted => {
+            if let Some(dialog) = &mut state.merge_dialog {
+                dialog.in_progress = true;
+                dialog.error = None;
+            }
+        }
+        
+        Action::MergeFailed(error) => {
+            if let Some(dialog) = &mut state.merge_dialog {
+                dialog.in_progress = false;
+                dialog.error = Some(error);
+            }
+        }
+        
+        Action::CloseMergeDialog => {
+            state.merge_dialog = None;
+        }
+        
+        Action::OpenExportDialog => {
+            let Some(conversation_idx) = state.active_conversation else {
+                return;
+            };
+            let Some(conv) = state.conversations.get(conversation_idx) else {
+                return;
+            };
+            if conv.messages.is_empty() {
+                return;
+            }
+            state.export_dialog = Some(ExportDialogState {
+                conversation_idx,
+                selection: ExportSelection::all(conv),
+            });
+        }
+        
+        Action::SetExportFrom(from) => {
+            if let Some(dialog) = &mut state.export_dialog {
+                let last = export_last_index(&state.conversations, dialog.conversation_idx);
+                dialog.selection.from = from.min(last);
+                dialog.selection.to = dialog.selection.to.max(dialog.selection.from);
+            }
+        }
+        
+        Action::SetExportTo(to) => {
+            if let Some(dialog) = &mut state.export_dialog {
+                let last = export_last_index(&state.conversations, dialog.conversation_idx);
+                dialog.selection.to = to.min(last);
+                dialog.selection.from = dialog.selection.from.min(dialog.selection.to);
+            }
+        }
+        
+        Action::ToggleExportPrompts => {
+            if let Some(dialog) = &mut state.export_dialog {
+                dialog.selection.prompts = !dialog.selection.prompts;
+            }
+        }
+        
+        Action::ToggleExportResponses => {
+            if let Some(dialog) = &mut state.export_dialog {
+                dialog.selection.responses = !dialog.selection.responses;
+            }
+        }
+        
+        Action::CloseExportDialog => {
+            state.export_dialog = None;
+        }
+        
+        Action::RequestCredentials => {
+            // Every failing request lands here, keep what's been typed so far
+            if state.auth_prompt.is_none() {
+                state.auth_prompt = Some(AuthPromptState::default());
+            }
+        }
+        
+        Action::ApiKeyChanged(api_key) => {
+            if let Some(prompt) = &mut state.auth_prompt {
+                prompt.api_key = api_key;
+            }
+        }
+        
+        Action::CloseAuthPrompt => {
+            state.auth_prompt = None;
+        }
+        
+        Action::SyncJobs => {
+            let running: Vec<String> = state.conversations.iter()
+                .filter(|conv| jobs::is_running(conv))
+                .map(|conv| conv.id.clone())
+                .collect();
+            
+            state.job_started.retain(|id, _| running.contains(id));
+            state.auto_interrupted.retain(|id| running.contains(id));
+            state.last_progress.retain(|id, _| running.contains(id));
+            state.stalled.retain(|id| running.contains(id));
+            let now = state.clock.instant();
+            for id in running {
+                state.job_started.entry(id.clone()).or_insert(now);
+                state.last_progress.entry(id).or_insert(now);
+            }
+        }
+        
+        Action::AutoInterrupted(interaction_id) => {
+            state.auto_interrupted.insert(interaction_id);
+        }
+        
+        Action::InterruptRequested(interaction_id) => {
+            state.interrupting.insert(interaction_id);
+        }
+        
+        Action::InterruptFailed(interaction_id) => {
+            state.interrupting.remove(&interaction_id);
+        }
+        
+        Action::InteractionInterrupted(interaction_id) => {
+            state.interrupting.remove(&interaction_id);
+            if let Some(conv) = state.conversations.iter_mut().find(|c| c.id == interaction_id) {
+                for msg in conv.messages.iter_mut().filter(|m| {
+                    matches!(m.status, MessageStatus::Pending | MessageStatus::Processing | MessageStatus::WaitingForPermission)
+                }) {
+                    msg.status = MessageStatus::Cancelled;
+                    msg.pending_tool_permission = None;
+                }
+            }
+        }
+        
+        Action::InteractionProgressed(interaction_id) => {
+            state.stalled.remove(&interaction_id);
+            state.last_progress.insert(interaction_id, state.clock.instant());
+        }
+        
+        Action::InteractionStalled(interaction_id) => {
+            state.stalled.insert(interaction_id);
+        }
+        
+        Action::MessageSent { conversation_idx, id } => {
+            // Update the temp ID with real ID
+            if let Some(conv) = state.conversations.get_mut(conversation_idx) {
+                if let Some(msg) = conv.messages.last_mut() {
+                    if msg.id.starts_with("temp-") {
+                        msg.id = id;
+                    }
+                }
+            }
+        }
+        
+        Action::MessageProcessing { conversation_idx, id } => {
+            if let Some(conv) = state.conversations.get_mut(conversation_idx) {
+                if let Some(msg) = conv.messages.iter_mut().find(|m| m.id == id) {
+                    msg.status = MessageStatus::Processing;
+                }
+            }
+        }
+        
+        Action::MessageCompleted { conversation_idx, id, response, error, metadata } => {
+            if let Some(conv) = state.conversations.get_mut(conversation_idx) {
+                if let Some(msg) = conv.messages.iter_mut().find(|m| m.id == id) {
+                    msg.response = response;
+                    msg.status = if error.is_some() {
+                        MessageStatus::Error
+                    } else {
+                        MessageStatus::Completed
+                    };
+                    msg.error = error.map(MessageError::server);
+                    msg.metadata = metadata.map(|metadata| *metadata);
+                    msg.pending_tool_permission = None; // Clear any pending permission
+                }
+                title_from_first_exchange(conv);
+            }
+        }
+        
+        Action::MessageWaitingForPermission { conversation_idx, id, permission_request } => {
+            if let Some(conv) = state.conversations.get_mut(conversation_idx) {
+                if let Some(msg) = conv.messages.iter_mut().find(|m| m.id == id) {
+                    msg.status = MessageStatus::WaitingForPermission;
+                    msg.pending_tool_permission = Some(permission_request);
+                }
+            }
+        }
+        
+        Action::MessageSendFailed { conversation_idx, error } => {
+            if let Some(conv) = state.conversations.get_mut(conversation_idx) {
+                if let Some(msg) = conv.messages.iter_mut().rev().find(|m| m.status == MessageStatus::Pending) {
+                    msg.status = MessageStatus::Error;
+                    msg.error = Some(error);
+                }
+            }
+        }
+        
+        Action::RetryMessage { conversation_idx, message_id } => {
+            let Some(conv) = state.conversations.get_mut(conversation_idx) else {
+                return;
+            };
+            let Some(position) = conv.messages.iter().position(|m| m.id == message_id) else {
+                return;
+            };
+            if !message_errors::failure(&conv.messages[position]).is_some_and(|e| e.retryable) {
+                return;
+            }
+            
+            let content = if message_errors::never_sent(&conv.messages[position]) {
+                conv.messages.remove(position).content
+            } else {
+                conv.messages[position].content.clone()
+            };
+            conv.messages.push(LegacyMessage {
+                id: state.ids.temp_id(),
+                content,
+                response: None,
+                status: MessageStatus::Pending,
+                metadata: None,
+                pending_tool_permission: None,
+                sent_at: Some(state.clock.utc()),
+                error: None,
+                attachments: Vec::new(),
+            });
+        }
+        
+        Action::AddPermissionRule(decision, rule) => {
+            state.permission_policy.add(decision, rule);
+        }
+        
+        Action::RemovePermissionRule(decision, idx) => {
+            let rules = state.permission_policy.rules_mut(decision);
+            if idx < rules.len() {
+                rules.remove(idx);
+            }
+        }
+        
+        Action::ClearPermissionPolicy => {
+            state.permission_policy = Policy::default();
+        }
+        
+        Action::TogglePermissionRules => {
+            state.show_permission_rules = !state.show_permission_rules;
+        }
+        
+        Action::UpdateRuleDraft(field, value) => {
+            state.rule_draft.set(field, value);
+        }
+        
+        Action::SetRuleDraftDecision(decision) => {
+            state.rule_draft.decision = decision;
+        }
+        
+        Action::RuleDraftFailed(error) => {
+            state.rule_draft.error = Some(error);
+        }
+        
+        Action::SyncPendingPermissions(waiting) => {
+            let interactions = state.conversations
+                .iter()
+                .filter(|c| c.messages.iter().any(|m| m.status == MessageStatus::WaitingForPermission))
+                .map(|c| (c.server.clone(), c.id.clone()))
+                .collect();
+            let at = state.clock.utc();
+            dispatch(state, EditorAction::SyncPendingPermissions { waiting, interactions, at });
+        }
+        
+        Action::PermissionResolved(entry) => {
+            state.permission_audit.push(entry);
+        }
+        
+        Action::TogglePermissionAudit => {
+            state.show_permission_audit = !state.show_permission_audit;
+        }
+        
+        Action::ToggleActionLog => {
+            state.show_action_log = !state.show_action_log;
+            if !state.show_action_log {
+                if let Ok(mut log) = state.action_log.lock() {
+                    log.resume();
+                }
+                state.inspected = None;
+            }
+        }
+        
+        Action::SaveSnapshot => {
+            state.snapshot_saved = Some(state.clock.instant());
+            dispatch(state, EditorAction::SaveSnapshot);
+        }
+        
+        Action::StepActionLogBack => {
+            let earlier = state.action_log.lock().ok().and_then(|mut log| log.step_back());
+            if earlier.is_some() {
+                state.inspected = earlier;
+            }
+        }
+        
+        Action::StepActionLogForward => {
+            let later = state.action_log.lock().ok().and_then(|mut log| log.step_forward().map(|editor| (editor, log.cursor())));
+            match later {
+                // Stepped past the newest, back to live
+                Some((_, None)) => state.inspected = None,
+                Some((editor, Some(_))) => state.inspected = Some(editor),
+                None => {}
+            }
+        }
+        
+        Action::RespondToPermission { approved } => {
+            // Find the message waiting for permission
+            let waiting = state.active_conversation
+                .and_then(|idx| state.conversations.get(idx))
+                .is_some_and(|conv| conv.messages.iter().any(|m| m.status == MessageStatus::WaitingForPermission));
+            if waiting {
+                // Add the response as a new message
+                let response_content = if approved { "Yes, go ahead" } else { "No, don't use that tool" };
+                set_input(state, response_content.to_string());
+                // The SendMessage action will handle sending this
+            }
+        }
+        
+        Action::HistoryRequested(conversation_idx) => {
+            if let Some(conv) = state.conversations.get_mut(conversation_idx) {
+                if conv.history_cursor.is_some() {
+                    conv.loading_history = true;
+                }
+            }
+        }
+        
+        Action::HistoryLoaded { server, conversation_id, messages, has_more } => {
+            let Some(conv) = state.conversations.iter_mut().find(|c| c.server == server && c.id == conversation_id) else {
+                return;
+            };
+            conv.loading_history = false;
+            let mut older: Vec<LegacyMessage> = messages
+                .into_iter()
+                .filter(|m| !conv.messages.iter().any(|existing| existing.id == m.id))
+                .collect();
+            conv.history_cursor = older.first().map(|m| m.id.clone()).filter(|_| has_more);
+            older.append(&mut conv.messages);
+            conv.messages = older;
+        }
+        
+        Action::HistoryFailed { server, conversation_id } => {
+            if let Some(conv) = state.conversations.iter_mut().find(|c| c.server == server && c.id == conversation_id) {
+                conv.loading_history = false;
+            }
+        }
+        
+        Action::WorktreesLoaded(worktrees) => {
+            dispatch(state, EditorAction::WorktreesLoaded { worktrees });
+            // Worktrees removed behind our back leave conversations pointing at nothing
+            let mut repaired = 0;
+            for conv in state.conversations.iter_mut().filter(|conv| conv.server == state.active_server) {
+                let dangling = conv.worktree_id.as_ref()
+                    .is_some_and(|id| !state.editor.available_worktrees.iter().any(|w| &w.id == id));
+                if dangling {
+                    conv.worktree_id = None;
+                    repaired += 1;
+                }
+            }
+            if repaired > 0 {
+                notify(state, Notification::Info(format!(
+                    "{} {} lost {} worktree, moved to the main checkout",
+                    repaired,
+                    if repaired == 1 { "conversation" } else { "conversations" },
+                    if repaired == 1 { "its" } else { "their" },
+                )));
+            }
+        }
+        
+        Action::AppearanceCheckRequested => {
+            state.appearance_checked = Some(state.clock.instant());
+        }
+        
+        Action::SystemAppearanceDetected(appearance) => {
+            state.system_appearance = appearance;
+        }
+        
+        Action::SetAppearance(mode) => {
+            state.appearance_override = Some(mode);
+        }
+        
+        Action::SetPaletteVariant(variant) => {
+            state.palette_override = Some(variant);
+        }
+        
+        Action::WorktreeStatusRequested => {
+            state.worktree_status_checked = Some(state.clock.instant());
+        }
+        
+        Action::SelectWorktree(id) => {
+            if state.editor.available_worktrees.iter().any(|w| w.id == id) {
+                bind_active_worktree(state, Some(id));
+            }
+        }
+        
+        Action::WorktreeChanged(worktree) => {
+            if let Some(worktree) = &worktree {
+                dispatch(state, EditorAction::WorktreeAdded { worktree: worktree.clone() });
+            }
+            bind_active_worktree(state, worktree.map(|w| w.id));
+        }
+        
+        Action::OpenWorktreeDialog => {
+            state.worktree_dialog = Some(WorktreeDialogState {
+                base_branch: "main".to_string(),
+                ..Default::default()
+            });
+        }
+        
+        Action::CloseWorktreeDialog => {
+            state.worktree_dialog = None;
+        }
+        
+        Action::UpdateWorktreeBranchName(name) => {
+            if let Some(dialog) = &mut state.worktree_dialog {
+                dialog.branch_name = name;
+                dialog.error = None; // Clear error when user types
+            }
+        }
+        
+        Action::UpdateWorktreeBaseBranch(branch) => {
+            if let Some(dialog) = &mut state.worktree_dialog {
+                dialog.base_branch = branch;
+            }
+        }
+        
+        Action::UpdateWorktreeCustomPath(path) => {
+            if let Some(dialog) = &mut state.worktree_dialog {
+                dialog.custom_path = path;
+            }
+        }
+        
+        Action::CreateWorktree => {
+            // Just clear error, actual creation happens in update function
+            if let Some(dialog) = &mut state.worktree_dialog {
+                dialog.error = None;
+            }
+        }
+        
+        Action::OpenCommandPalette => {
+            state.command_palette = Some(CommandPaletteState::default());
+        }
+        
+        Action::CloseCommandPalette => {
+            state.command_palette = None;
+        }
+        
+        Action::UpdatePaletteQuery(query) => {
+            if let Some(palette) = &mut state.command_palette {
+                palette.query = query;
+                palette.selected = 0; // Results changed, start from the top
+            }
+        }
+        
+        Action::SelectNextCommand => {
+            let count = state.command_palette.as_ref()
+                .map(|p| palette::filter(state, &p.query).len())
+                .unwrap_or(0);
+            if let Some(palette) = &mut state.command_palette {
+                if count > 0 {
+                    palette.selected = (palette.selected + 1) % count;
+                }
+            }
+        }
+        
+        Action::SelectPreviousCommand => {
+            let count = state.command_palette.as_ref()
+                .map(|p| palette::filter(state, &p.query).len())
+                .unwrap_or(0);
+            if let Some(palette) = &mut state.command_palette {
+                if count > 0 {
+                    palette.selected = (palette.selected + count - 1) % count;
+                }
+            }
+        }
+        
+        Action::WorktreeCreated(worktree) => {
+            let created = format!("Created worktree '{}'", worktree.branch.as_deref().unwrap_or("unknown"));
+            bind_active_worktree(state, Some(worktree.id.clone()));
+            dispatch(state, EditorAction::WorktreeAdded { worktree });
+            state.worktree_dialog = None;
+            notify(state, Notification::Success(created));
+        }
+        
+        Action::WorktreeCreationFailed(error) => {
+            if let Some(dialog) = &mut state.worktree_dialog {
+                dialog.error = Some(error);
+            }
+        }
+        
+        Action::ShowNotification(notification) => {
+            notify(state, notification);
+        }
+        
+        Action::Editor(action) => {
+            dispatch(state, action);
+        }
+        
+        Action::WindowFocusChanged(focused) => {
+            state.window_focused = focused;
+        }
+        
+        Action::SetDropHover(hovering) => {
+            // Nowhere to drop without a conversation
+            state.drop_hover = hovering && state.active_conversation.is_some();
+        }
+        
+        Action::Error(_) => {
+            // Errors are ignored for now
+        }
+    }
+    
+    sync_current_worktree(state);
+}
+
+fn bind_active_worktree(state: &mut AppState, worktree_id: Option<String>) {
+    if let Some(conv) = state.active_conversation.and_then(|idx| state.conversations.get_mut(idx)) {
+        conv.worktree_id = worktree_id;
+    }
+}
+
+// The core reducer keeps `state.editor`, nothing else changes it
+fn dispatch(state: &mut AppState, action: EditorAction) {
+    let (editor, effects) = state.pipeline
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+        .dispatch(&state.editor, &action);
+    state.editor = editor;
+    // Carried out by the app once it's done handling the message
+    state.effects.extend(effects);
+}
+
+// Middleware the GUI hangs off the core reducer
+fn pipeline(log: Arc<Mutex<ActionLog>>, clock: Arc<dyn Clock>) -> Pipeline {
+    let mut pipeline = Pipeline::new();
+    pipeline.register(ActionLog::recorder(log, clock));
+    // Nothing on screen shows the core's errors, so they're logged with the rest
+    pipeline.register(|transition: &Transition<'_>| {
+        if transition.after.error != transition.before.error {
+            if let Some(error) = &transition.after.error {
+                eprintln!("[GUI] {}", error);
+            }
+        }
+    });
+    pipeline
+}
+
+fn notify(state: &mut AppState, notification: Notification) {
+    let at = state.clock.utc();
+    dispatch(state, EditorAction::ShowNotification { notification, at });
+}
+
+// The current worktree always follows the active conversation
+fn sync_current_worktree(state: &mut AppState) {
+    let worktree_id = state.active_conversation
+        .and_then(|idx| state.conversations.get(idx))
+        .and_then(|conv| conv.worktree_id.clone())
+        .filter(|id| state.editor.available_worktrees.iter().any(|w| &w.id == id));
+    
+    // Runs after every action, the reducer is only bothered when the worktree moved
+    if state.editor.current_worktree.as_ref().map(|w| &w.id) != worktree_id.as_ref() {
+        dispatch(state, EditorAction::FocusWorktree { worktree_id });
+    }
+}
+
+fn active_conversation_id(state: &AppState) -> Option<String> {
+    state.active_conversation
+        .and_then(|idx| state.conversations.get(idx))
+        .map(|conv| conv.id.clone())
+}
+
+// Put the composer away with the conversation being left and take out the one
+// for the conversation on screen now. An edit in progress isn't a draft, it's
+// given up like leaving any other overlay.
+fn switch_draft(state: &mut AppState) {
+    state.expanded_template = None;
+    state.mention = None;
+    if state.editing.take().is_some() || state.editor.draft.content.trim().is_empty() {
+        dispatch(state, EditorAction::ClearDraft);
+    }
+
+    dispatch(state, EditorAction::FocusInteraction { interaction_id: active_conversation_id(state) });
+    state.input_tokens = tokenizer::estimate_tokens(&state.editor.draft.content);
+}
+
+fn set_input(state: &mut AppState, content: String) {
+    state.input_tokens = tokenizer::estimate_tokens(&content);
+    dispatch(state, EditorAction::UpdateDraftContent { content });
+}
+
+fn clear_input(state: &mut AppState) {
+    state.input_tokens = 0;
+    dispatch(state, EditorAction::ClearDraft);
+}
+
+fn new_conversation(state: &mut AppState) {
+    let new_conversation = Conversation {
+        id: state.ids.conversation_id(),
+        title: generate_ubuntu_style_name(),
+        title_source: TitleSource::Generated,
+        interaction_type: InteractionType::Query,
+        messages: Vec::new(),
+        muted: false,
+        archived: false,
+        // New conversations start out where the user is working now
+        worktree_id: state.editor.current_worktree.as_ref().map(|w| w.id.clone()),
+        bookmarks: HashSet::new(),
+        server: state.active_server.clone(),
+        history_cursor: None,
+        loading_history: false,
+        budget: None,
+        agents: Vec::new(),
+        pins: Vec::new(),
+        route: None,
+        review_stack: Vec::new(),
+    };
+    state.conversations.push(new_conversation);
+    state.active_conversation = Some(state.conversations.len() - 1);
+    state.presentation = None;
+    state.artifacts_tab = false;
+}
+
+// Worktrees and anything open on them belong to the server being left
+fn enter_server(state: &mut AppState, server: String) {
+    state.active_server = server;
+    state.active_conversation = None;
+    state.presentation = None;
+    state.artifacts_tab = false;
+    // No sessions listed sends the core back to the default one
+    dispatch(state, EditorAction::SessionsLoaded(Ok(Vec::new())));
+    state.agents.clear();
+    state.agent_selector = None;
+    state.agent_config = None;
+    state.agent_statuses.clear();
+    state.agent_status_checked = None;
+    state.editing = None;
+    state.mention = None;
+    state.editor.current_worktree = None;
+    state.editor.available_worktrees.clear();
+    state.editor.worktree_git_status.clear();
+    state.worktree_status_checked = None;
+    state.editor.worktree_files.clear();
+    state.worktree_dialog = None;
+    state.merge_dialog = None;
+    state.export_dialog = None;
+    state.diff_view = None;
+}
+
+fn export_last_index(conversations: &[Conversation], conversation_idx: usize) -> usize {
+    conversations
+        .get(conversation_idx)
+        .map_or(0, |conv| conv.messages.len().saturating_sub(1))
+}
+
+/// Words, reading time and the agent's edits across a conversation
+pub fn conversation_stats(conv: &Conversation) -> ConversationStats {
+    let mut stats = ConversationStats::default();
+    for msg in &conv.messages {
+        stats.add_text(&msg.content);
+        if let Some(response) = &msg.response {
+            stats.add_text(response);
+        }
+
+        let calls = msg.metadata.as_ref().and_then(|meta| meta.tool_calls.as_ref());
+        for call in calls.into_iter().flatten() {
+            stats.add_tool_call(&call.name, &call.arguments);
+        }
+    }
+    stats
+}
+
+// Long enough to tell conversations apart in the sidebar
+const MAX_TITLE_CHARS: usize = 40;
+
+/// A title from what the user first asked, `None` when there's nothing to go on
+pub fn title_from_message(content: &str) -> Option<String> {
+    let line = content.lines().map(str::trim).find(|line| !line.is_empty())?;
+    // Markdown headings, quotes and bullets aren't part of the request
+    let line = line.trim_start_matches(['#', '>', '-', '*', ' ']);
+    let words: Vec<&str> = line.split_whitespace().collect();
+    if words.is_empty() {
+        return None;
+    }
+
+    let mut title = String::new();
+    for word in words.iter() {
+        let len = title.chars().count() + usize::from(!title.is_empty()) + word.chars().count();
+        if len > MAX_TITLE_CHARS {
+            if title.is_empty() {
+                // One long word, cut it rather than leave nothing
+                title = word.chars().take(MAX_TITLE_CHARS).collect();
+            }
+            title.push('…');
+            return Some(title);
+        }
+        if !title.is_empty() {
+            title.push(' ');
+        }
+        title.push_str(word);
+    }
+    Some(title)
+}
+
+/// Drops the chosen route when that agent is no longer attached
+pub fn keep_route(conv: &mut Conversation) {
+    if conv.route.as_ref().is_some_and(|id| !conv.agents.contains(id)) {
+        conv.route = None;
+    }
+}
+
+/// Name a conversation after its first message once that has been answered
+///
+/// Only generated names are replaced, and only when the first message is
+/// really the first, not the oldest of a partly loaded history.
+pub fn title_from_first_exchange(conv: &mut Conversation) {
+    if conv.title_source != TitleSource::Generated || conv.history_cursor.is_some() {
+        return;
+    }
+    let title = conv
+        .messages
+        .first()
+        .filter(|msg| msg.status == MessageStatus::Completed)
+        .and_then(|msg| title_from_message(&msg.content));
+    if let Some(title) = title {
+        conv.title = title;
+        conv.title_source = TitleSource::FirstMessage;
+    }
+}
+
+/// What a refetch changed in a conversation's messages
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct MessageChanges {
+    pub added: Vec<String>,
+    /// Kept but different, e.g. a response came in or the status moved on
+    pub updated: Vec<String>,
+    pub removed: Vec<String>,
+    /// Messages whose status moved, with the status before and after
+    pub status_changes: Vec<(String, MessageStatus, MessageStatus)>,
+}
+
+impl MessageChanges {
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.updated.is_empty() && self.removed.is_empty()
+    }
+
+    /// One line for the log
+    pub fn summary(&self) -> String {
+        let mut summary = format!(
+            "{} added, {} updated, {} removed",
+            self.added.len(),
+            self.updated.len(),
+            self.removed.len()
+        );
+        for (id, before, after) in &self.status_changes {
+            summary.push_str(&format!("; {} {:?} -> {:?}", id, before, after));
+        }
+        summary
+    }
+}
+
+/// How `new` differs from `old`, matching messages by id
+pub fn message_changes(old: &[LegacyMessage], new: &[LegacyMessage]) -> MessageChanges {
+    let before: HashMap<&str, &LegacyMessage> = old.iter().map(|m| (m.id.as_str(), m)).collect();
+    let after: HashSet<&str> = new.iter().map(|m| m.id.as_str()).collect();
+    let mut changes = MessageChanges::default();
+
+    for msg in new {
+        match before.get(msg.id.as_str()) {
+            None => changes.added.push(msg.id.clone()),
+            Some(previous) if *previous != msg => {
+                changes.updated.push(msg.id.clone());
+                if previous.status != msg.status {
+                    changes.status_changes.push((msg.id.clone(), previous.status.clone(), msg.status.clone()));
+                }
+            }
+            Some(_) => {}
+        }
+    }
+    changes.removed = old
+        .iter()
+        .filter(|m| !after.contains(m.id.as_str()))
+        .map(|m| m.id.clone())
+        .collect();
+    changes
+}
+
+/// Fold the newest page from the server into what we have
+///
+/// Older pages already loaded are kept when the page overlaps them, otherwise
+/// the page replaces everything and history starts again from its first message.
+/// Only the messages that changed are touched, the returned changes say which.
+pub fn merge_newest_page(conv: &mut Conversation, page: Vec<LegacyMessage>, has_more: bool) -> MessageChanges {
+    let first = page.first().map(|m| m.id.clone());
+    let overlap = first
+        .as_ref()
+        .filter(|_| has_more)
+        .and_then(|id| conv.messages.iter().position(|m| &m.id == id));
+
+    let merged = match overlap {
+        Some(pos) => conv.messages[..pos].iter().cloned().chain(page).collect::<Vec<_>>(),
+        None => {
+            conv.history_cursor = first.filter(|_| has_more);
+            page
+        }
+    };
+
+    let changes = message_changes(&conv.messages, &merged);
+    let same_order = conv.messages.iter().map(|m| &m.id).eq(merged.iter().map(|m| &m.id));
+    if changes.is_empty() && same_order {
+        return changes;
+    }
+
+    // Each step leaves the front of the list matching `merged` up to `idx`
+    conv.messages.retain(|m| !changes.removed.contains(&m.id));
+    let len = merged.len();
+    for (idx, msg) in merged.into_iter().enumerate() {
+        match conv.messages.get_mut(idx) {
+            Some(existing) if existing.id == msg.id => {
+                if *existing != msg {
+                    *existing = msg;
+                }
+            }
+            _ => conv.messages.insert(idx, msg),
+        }
+    }
+    conv.messages.truncate(len);
+    changes
+}
+
+/// The conversation a review reply goes to, the active one if it works in the reviewed worktree
+pub fn review_target(state: &AppState) -> Option<usize> {
+    let view = state.diff_view.as_ref()?;
+    let works_there = |idx: &usize| {
+        state.conversations.get(*idx).is_some_and(|conv| {
+            conv.worktree_id.as_deref() == Some(view.worktree_id.as_str()) && on_active_server(state, conv)
+        })
+    };
+    state
+        .active_conversation
+        .filter(works_there)
+        .or_else(|| (0..state.conversations.len()).rev().find(works_there))
+}
+
+/// Session whose stream we follow, `None` for the server's default stream
+pub fn active_session(state: &AppState) -> Option<&str> {
+    Some(state.editor.session_id.as_str()).filter(|id| *id != DEFAULT_SESSION_ID)
+}
+
+/// Whether a conversation belongs to the server being shown
+pub fn on_active_server(state: &AppState, conv: &Conversation) -> bool {
+    conv.server == state.active_server
+}
+
+pub fn label_of(state: &AppState, conv: &Conversation) -> Option<ColorLabel> {
+    state.labels.get(&conv.id).copied()
+}
+
+/// The conversation's tags, `None` until the server's copy of it has been seen
+pub fn tags_of<'a>(state: &'a AppState, conv: &Conversation) -> Option<Vec<&'a str>> {
+    state.editor.interactions.get(&conv.id).map(bicky_editor_core::filter::tags)
+}
+
+/// Whether the sidebar lists the conversation with the current label filter
+pub fn matches_label_filter(state: &AppState, conv: &Conversation) -> bool {
+    state.label_filter.is_none() || label_of(state, conv) == state.label_filter
+}
+
+/// Whether the sidebar lists the conversation with the current type filter
+pub fn matches_type_filter(state: &AppState, conv: &Conversation) -> bool {
+    state.interaction_filter.matches_type(&conv.interaction_type)
+}
+
+/// Whether the sidebar lists the conversation, in its archived section if it's archived
+pub fn listed_in_sidebar(state: &AppState, conv: &Conversation) -> bool {
+    on_active_server(state, conv) && matches_type_filter(state, conv) && matches_label_filter(state, conv)
+}
+
+/// Conversations in the order the sidebar shows them, archived ones last while their section is open
+pub fn sidebar_order(state: &AppState) -> Vec<usize> {
+    let listed: Vec<(usize, &Conversation)> = state.conversations
+        .iter()
+        .enumerate()
+        .filter(|(_, conv)| listed_in_sidebar(state, conv))
+        .collect();
+    let open = listed.iter().filter(|(_, conv)| !conv.archived);
+    let archived = listed.iter().filter(|(_, conv)| state.show_archived && conv.archived);
+    open.chain(archived).map(|(idx, _)| *idx).collect()
+}
+
+// The closest conversation still shown in the sidebar, looking down the list first
+fn nearest_visible(state: &AppState, idx: usize) -> Option<usize> {
+    let visible = |i: &usize| state.conversations.get(*i).is_some_and(|c| !c.archived && on_active_server(state, c));
+    (idx..state.conversations.len())
+        .find(visible)
+        .or_else(|| (0..idx.min(state.conversations.len())).rev().find(visible))
+}
+
+/// Only settled messages can be deleted, pending and processing ones are
+/// still waiting on the server
+pub fn can_delete(msg: &LegacyMessage) -> bool {
+    matches!(msg.status, MessageStatus::Completed | MessageStatus::Error | MessageStatus::Failed | MessageStatus::Cancelled)
 }
 
 /// Generate Ubuntu-style release names (Adjective Animal)
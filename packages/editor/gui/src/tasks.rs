@@ -0,0 +1,301 @@
+use std::any::Any;
+use std::future::Future;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use iced::Task as Command;
+use tokio_util::sync::CancellationToken;
+
+use crate::state::{Clock, SystemClock};
+
+// Finished tasks kept around for the debug overlay
+const FINISHED_HISTORY: usize = 50;
+// Restart delays double up to this
+const MAX_RESTART_DELAY: Duration = Duration::from_secs(30);
+
+/// Cancellation for background work, scoped to what the work is for
+///
+/// Fetches that only matter while a conversation is on screen use the
+/// conversation scope and are aborted when the user moves on. Everything else
+/// lives until the app closes. Sends, deletes and other user actions aren't
+/// scoped at all, they should finish even if the user looks elsewhere.
+#[derive(Debug)]
+pub struct Tasks {
+    app: CancellationToken,
+    conversation: CancellationToken,
+    pub supervisor: Supervisor,
+}
+
+impl Default for Tasks {
+    fn default() -> Self {
+        let app = CancellationToken::new();
+        let conversation = app.child_token();
+        Self { app, conversation, supervisor: Supervisor::default() }
+    }
+}
+
+impl Tasks {
+    /// Work that lives as long as the app, e.g. the SSE stream
+    pub fn app(&self) -> CancellationToken {
+        self.app.child_token()
+    }
+
+    /// Work for the conversation on screen, e.g. its diff or mention candidates
+    pub fn conversation(&self) -> CancellationToken {
+        self.conversation.child_token()
+    }
+
+    /// Abort whatever was started for the conversation being left
+    pub fn switch_conversation(&mut self) {
+        self.conversation.cancel();
+        self.conversation = self.app.child_token();
+    }
+
+    pub fn shutdown(&self) {
+        self.app.cancel();
+    }
+}
+
+impl Drop for Tasks {
+    fn drop(&mut self) {
+        self.shutdown();
+    }
+}
+
+/// Run `future` until `token` is cancelled, `None` if it was cancelled first
+///
+/// The future is dropped on cancellation, so a request in flight is aborted.
+pub async fn cancellable<T>(token: CancellationToken, future: impl Future<Output = T>) -> Option<T> {
+    tokio::select! {
+        biased;
+        _ = token.cancelled() => None,
+        output = future => Some(output),
+    }
+}
+
+impl Tasks {
+    /// `Command::perform` under the supervisor, for work that should always finish
+    pub fn run<T, M>(
+        &self,
+        name: impl Into<String>,
+        future: impl Future<Output = T> + Send + 'static,
+        f: impl Fn(T) -> M + Send + 'static,
+    ) -> Command<M>
+    where
+        T: Send + 'static,
+        M: Send + 'static,
+    {
+        Command::perform(self.supervisor.track(name.into(), future), f)
+    }
+
+    /// Like `run`, but produces no message once `token` is cancelled
+    pub fn perform<T, M>(
+        &self,
+        name: impl Into<String>,
+        token: CancellationToken,
+        future: impl Future<Output = T> + Send + 'static,
+        f: impl Fn(T) -> M + Send + 'static,
+    ) -> Command<M>
+    where
+        T: Send + 'static,
+        M: Send + 'static,
+    {
+        // Cancelling drops the tracked future, which records it as cancelled
+        let future = cancellable(token, self.supervisor.track(name.into(), future));
+        Command::perform(future, move |output| output.map(&f)).and_then(Command::done)
+    }
+}
+
+/// Where a supervised task is at
+#[derive(Debug, Clone, PartialEq)]
+pub enum TaskStatus {
+    Running,
+    Finished,
+    Cancelled,
+    /// Gave up, with the panic message
+    Panicked(String),
+    /// Panicked and will be started again
+    Restarting(String),
+}
+
+/// One entry in the supervisor's registry
+#[derive(Debug, Clone)]
+pub struct TaskInfo {
+    pub id: u64,
+    pub name: String,
+    pub started: Instant,
+    pub status: TaskStatus,
+    pub restarts: u32,
+}
+
+/// Registry of every background task, so none of them fail silently
+///
+/// Clones share the registry.
+#[derive(Debug, Clone)]
+pub struct Supervisor {
+    registry: Arc<Mutex<Vec<TaskInfo>>>,
+    next_id: Arc<AtomicU64>,
+    clock: Arc<dyn Clock>,
+}
+
+impl Default for Supervisor {
+    fn default() -> Self {
+        Self::new(Arc::new(SystemClock))
+    }
+}
+
+impl Supervisor {
+    pub fn new(clock: Arc<dyn Clock>) -> Self {
+        Self {
+            registry: Arc::default(),
+            next_id: Arc::default(),
+            clock,
+        }
+    }
+
+    /// Every task still running plus the most recently finished, oldest first
+    pub fn snapshot(&self) -> Vec<TaskInfo> {
+        self.registry.lock().map(|tasks| tasks.clone()).unwrap_or_default()
+    }
+
+    /// Record `future` in the registry for as long as it runs
+    ///
+    /// Dropping it early counts as cancelled, unwinding out of it as a panic.
+    pub fn track<F: Future>(&self, name: String, future: F) -> impl Future<Output = F::Output> {
+        let guard = Tracked { supervisor: self.clone(), id: self.register(name.clone()), name, done: false };
+        async move {
+            let mut guard = guard;
+            let output = future.await;
+            guard.done = true;
+            guard.supervisor.set_status(guard.id, TaskStatus::Finished);
+            output
+        }
+    }
+
+    /// Run a task that has to stay up on `rt`, starting it again whenever it panics
+    pub fn spawn_critical<F, Fut>(
+        &self,
+        rt: &tokio::runtime::Runtime,
+        name: impl Into<String>,
+        token: CancellationToken,
+        start: F,
+    ) where
+        F: Fn() -> Fut + Send + 'static,
+        Fut: Future<Output = ()> + Send + 'static,
+    {
+        let name = name.into();
+        let supervisor = self.clone();
+        let id = supervisor.register(name.clone());
+        rt.spawn(async move {
+            let mut restarts = 0;
+            loop {
+                let status = match tokio::spawn(cancellable(token.clone(), start())).await {
+                    Ok(Some(())) => TaskStatus::Finished,
+                    Ok(None) => TaskStatus::Cancelled,
+                    Err(e) if e.is_panic() => {
+                        let reason = panic_message(e.into_panic());
+                        eprintln!("[GUI] {} panicked: {}", name, reason);
+                        TaskStatus::Restarting(reason)
+                    }
+                    Err(_) => TaskStatus::Cancelled,
+                };
+                let restart = matches!(status, TaskStatus::Restarting(_));
+                supervisor.set_status(id, status);
+                if !restart {
+                    return;
+                }
+
+                restarts += 1;
+                supervisor.count_restart(id);
+                if cancellable(token.clone(), tokio::time::sleep(restart_delay(restarts))).await.is_none() {
+                    supervisor.set_status(id, TaskStatus::Cancelled);
+                    return;
+                }
+                println!("[GUI] Restarting {} (attempt {})", name, restarts);
+                supervisor.set_status(id, TaskStatus::Running);
+            }
+        });
+    }
+
+    fn register(&self, name: String) -> u64 {
+        let id = self.next_id.fetch_add(1, Ordering::Relaxed);
+        if let Ok(mut tasks) = self.registry.lock() {
+            tasks.push(TaskInfo {
+                id,
+                name,
+                started: self.clock.instant(),
+                status: TaskStatus::Running,
+                restarts: 0,
+            });
+            prune(&mut tasks);
+        }
+        id
+    }
+
+    fn set_status(&self, id: u64, status: TaskStatus) {
+        if let Ok(mut tasks) = self.registry.lock() {
+            if let Some(task) = tasks.iter_mut().find(|task| task.id == id) {
+                task.status = status;
+            }
+            prune(&mut tasks);
+        }
+    }
+
+    fn count_restart(&self, id: u64) {
+        if let Ok(mut tasks) = self.registry.lock() {
+            if let Some(task) = tasks.iter_mut().find(|task| task.id == id) {
+                task.restarts += 1;
+            }
+        }
+    }
+}
+
+// Marks a tracked future cancelled or panicked if it goes away unfinished
+struct Tracked {
+    supervisor: Supervisor,
+    id: u64,
+    name: String,
+    done: bool,
+}
+
+impl Drop for Tracked {
+    fn drop(&mut self) {
+        if self.done {
+            return;
+        }
+        let status = if std::thread::panicking() {
+            eprintln!("[GUI] {} panicked", self.name);
+            TaskStatus::Panicked("panicked while running".to_string())
+        } else {
+            TaskStatus::Cancelled
+        };
+        self.supervisor.set_status(self.id, status);
+    }
+}
+
+// Drop the oldest finished tasks, running ones always stay
+fn prune(tasks: &mut Vec<TaskInfo>) {
+    let finished = tasks.iter().filter(|task| task.status != TaskStatus::Running).count();
+    let mut excess = finished.saturating_sub(FINISHED_HISTORY);
+    tasks.retain(|task| {
+        if excess > 0 && task.status != TaskStatus::Running {
+            excess -= 1;
+            false
+        } else {
+            true
+        }
+    });
+}
+
+fn restart_delay(restarts: u32) -> Duration {
+    Duration::from_secs(1 << restarts.min(5)).min(MAX_RESTART_DELAY)
+}
+
+fn panic_message(payload: Box<dyn Any + Send>) -> String {
+    payload
+        .downcast_ref::<&str>()
+        .map(|s| s.to_string())
+        .or_else(|| payload.downcast_ref::<String>().cloned())
+        .unwrap_or_else(|| "unknown panic".to_string())
+}
@@ -0,0 +1,228 @@
+use crate::api::{ApiError, Lane, LaneStats, Limiter};
+use crate::config::RequestLimits;
+use crate::debug_overlay::lane_summary;
+use std::time::Duration;
+use crate::types::MessageError;
+
+#[cfg(test)]
+mod api_error_tests {
+    use super::*;
+
+    #[test]
+    fn test_transient_failures_are_retryable() {
+        assert!(ApiError::Network("connection refused".to_string()).is_retryable());
+        assert!(ApiError::Timeout.is_retryable());
+        assert!(ApiError::Http { status: 503, body: String::new() }.is_retryable());
+        assert!(ApiError::Http { status: 429, body: String::new() }.is_retryable());
+        assert!(ApiError::Server { code: 500, message: "crashed".to_string() }.is_retryable());
+    }
+
+    #[test]
+    fn test_refusals_are_fatal() {
+        assert!(!ApiError::Decode("expected value".to_string()).is_retryable());
+        assert!(!ApiError::Http { status: 404, body: String::new() }.is_retryable());
+        assert!(!ApiError::Server { code: 409, message: "worktree is dirty".to_string() }.is_retryable());
+    }
+
+    #[test]
+    fn test_server_message_is_shown_as_is() {
+        let error = ApiError::Server { code: 409, message: "worktree is dirty".to_string() };
+        assert_eq!(error.to_string(), "worktree is dirty");
+    }
+
+    #[test]
+    fn test_http_error_includes_body() {
+        let empty = ApiError::Http { status: 502, body: "  ".to_string() };
+        assert_eq!(empty.to_string(), "Server returned 502");
+
+        let with_body = ApiError::Http { status: 502, body: "Bad Gateway\n".to_string() };
+        assert_eq!(with_body.to_string(), "Server returned 502: Bad Gateway");
+    }
+
+    #[test]
+    fn test_guidance_matches_failure() {
+        assert_eq!(ApiError::Network(String::new()).guidance(), "Check that the Bicamrl server is running");
+        assert_eq!(ApiError::Http { status: 404, body: String::new() }.guidance(), "It may have been deleted elsewhere");
+        assert_eq!(ApiError::Http { status: 500, body: String::new() }.guidance(), "The server had a problem, try again");
+    }
+
+    #[test]
+    fn test_unauthorized_asks_for_key() {
+        let error = ApiError::Unauthorized;
+
+        assert!(!error.is_retryable());
+        assert_eq!(error.guidance(), "Enter the server's API key to continue");
+    }
+
+    #[test]
+    fn test_message_error_keeps_classification() {
+        let error: MessageError = ApiError::Timeout.into();
+
+        assert!(error.retryable);
+        assert_eq!(error.detail, "The server took too long to respond");
+        assert!(error.guidance.is_some());
+    }
+}
+
+#[cfg(test)]
+mod credentials_tests {
+    use crate::api::Credentials;
+
+    #[test]
+    fn test_clones_share_the_key() {
+        let credentials = Credentials::default();
+        let task_copy = credentials.clone();
+        assert!(!task_copy.is_set());
+
+        credentials.set(Some("sk-test".to_string()));
+        assert!(task_copy.is_set());
+    }
+
+    #[test]
+    fn test_blank_key_counts_as_none() {
+        let credentials = Credentials::default();
+        credentials.set(Some("  ".to_string()));
+
+        assert!(!credentials.is_set());
+    }
+
+    #[test]
+    fn test_key_is_sent_as_bearer_token() {
+        let credentials = Credentials::default();
+        credentials.set(Some("sk-test\n".to_string()));
+
+        let request = credentials
+            .authorize(reqwest::Client::new().get("http://localhost/stream"))
+            .build()
+            .unwrap();
+        assert_eq!(request.headers()[reqwest::header::AUTHORIZATION], "Bearer sk-test");
+    }
+
+    #[test]
+    fn test_event_streams_are_authorized_and_never_time_out() {
+        let api = crate::api::ApiClient::new("http://localhost:3456".to_string());
+        api.credentials.set(Some("sk-test".to_string()));
+
+        let request = api.stream("/sessions/s1/stream").build().unwrap();
+        assert_eq!(request.url().as_str(), "http://localhost:3456/sessions/s1/stream");
+        assert_eq!(request.headers()[reqwest::header::AUTHORIZATION], "Bearer sk-test");
+        assert_eq!(request.timeout(), None);
+    }
+}
+
+#[cfg(test)]
+mod clock_skew_tests {
+    use crate::api::ApiClient;
+    use crate::state::Clock;
+    use chrono::{DateTime, Utc};
+    use std::sync::Arc;
+    use std::time::Instant;
+
+    // Always answers with the same moment
+    #[derive(Debug)]
+    struct FixedClock(DateTime<Utc>);
+
+    impl Clock for FixedClock {
+        fn instant(&self) -> Instant {
+            Instant::now()
+        }
+
+        fn utc(&self) -> DateTime<Utc> {
+            self.0
+        }
+    }
+
+    fn api_at(now: &str) -> ApiClient {
+        let clock = FixedClock(now.parse().unwrap());
+        ApiClient::new("http://localhost:3456".to_string()).with_clock(Arc::new(clock))
+    }
+
+    #[test]
+    fn test_skew_is_measured_against_the_injected_clock() {
+        let api = api_at("2024-01-01T12:00:00Z");
+        let sent: DateTime<Utc> = "2024-01-01T11:59:58Z".parse().unwrap();
+
+        // Answered at 12:00:00 by our clock, so stamped at 11:59:59 by ours and 12:00:04 by theirs
+        api.observe_date(sent, Some("Mon, 01 Jan 2024 12:00:04 GMT"));
+
+        assert_eq!(api.clock_skew().offset(), chrono::Duration::seconds(5));
+    }
+
+    #[test]
+    fn test_responses_without_a_date_leave_the_estimate_alone() {
+        let api = api_at("2024-01-01T12:00:00Z");
+
+        api.observe_date("2024-01-01T11:59:58Z".parse().unwrap(), None);
+
+        assert_eq!(api.clock_skew().offset(), chrono::Duration::zero());
+    }
+}
+
+#[cfg(test)]
+mod limiter_tests {
+    use super::*;
+
+    fn limiter(interactive: usize, background: usize) -> Limiter {
+        Limiter::new(&RequestLimits { interactive, background })
+    }
+
+    #[tokio::test]
+    async fn test_requests_past_the_limit_wait_for_a_slot() {
+        let limiter = limiter(2, 1);
+        let first = limiter.acquire(Lane::Background).await;
+
+        let queued = limiter.clone();
+        let second = tokio::spawn(async move { queued.acquire(Lane::Background).await });
+        tokio::time::sleep(Duration::from_millis(20)).await;
+        let stats = limiter.stats(Lane::Background);
+        assert_eq!((stats.in_flight, stats.queued, stats.peak_queued), (1, 1, 1));
+
+        drop(first);
+        let second = second.await.unwrap();
+        let stats = limiter.stats(Lane::Background);
+        assert_eq!((stats.in_flight, stats.queued, stats.waited), (1, 0, 1));
+        drop(second);
+        assert_eq!(limiter.stats(Lane::Background).in_flight, 0);
+    }
+
+    #[tokio::test]
+    async fn test_background_requests_do_not_hold_up_interactive_ones() {
+        let limiter = limiter(1, 1);
+        let _background = limiter.acquire(Lane::Background).await;
+
+        let interactive = tokio::time::timeout(Duration::from_millis(100), limiter.acquire(Lane::Interactive)).await;
+        assert!(interactive.is_ok());
+        assert_eq!(limiter.stats(Lane::Interactive).waited, 0);
+    }
+
+    #[tokio::test]
+    async fn test_giving_up_on_a_slot_leaves_the_queue() {
+        let limiter = limiter(1, 1);
+        let _held = limiter.acquire(Lane::Interactive).await;
+
+        let gave_up = tokio::time::timeout(Duration::from_millis(20), limiter.acquire(Lane::Interactive)).await;
+        assert!(gave_up.is_err());
+        assert_eq!(limiter.stats(Lane::Interactive).queued, 0);
+    }
+
+    #[test]
+    fn test_zero_limit_still_lets_one_through() {
+        assert_eq!(limiter(0, 0).stats(Lane::Interactive).limit, 1);
+    }
+
+    #[test]
+    fn test_lane_summary_mentions_waiting_only_once_there_was_some() {
+        let limiter = limiter(6, 4);
+        assert_eq!(lane_summary(&limiter.stats(Lane::Interactive)), "0/6 in flight");
+
+        let busy = LaneStats {
+            limit: 4,
+            in_flight: 4,
+            queued: 2,
+            peak_queued: 9,
+            waited: 40,
+            longest_wait: Duration::from_millis(1200),
+        };
+        assert_eq!(lane_summary(&busy), "4/4 in flight · 2 queued · 40 waited, peak 9, longest 1200ms");
+    }
+}
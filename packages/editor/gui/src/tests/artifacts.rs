@@ -0,0 +1,108 @@
+use crate::artifacts::{file_name, list, save_path, SaveDialog};
+use crate::state::{update, Action, AppState};
+use crate::tests::message;
+use crate::types::{Artifact, InteractionMetadata, LegacyMessage, MessageStatus};
+use serde_json::json;
+use std::path::{Path, PathBuf};
+
+#[cfg(test)]
+mod artifacts_tests {
+    use super::*;
+
+    fn artifact(id: &str, name: &str) -> Artifact {
+        Artifact { id: id.to_string(), name: name.to_string(), path: None, size: None, content_type: None, tool: None }
+    }
+
+    fn answered(id: &str, artifacts: Vec<Artifact>) -> LegacyMessage {
+        LegacyMessage {
+            response: Some("Done".to_string()),
+            metadata: Some(InteractionMetadata { artifacts: Some(artifacts), ..Default::default() }),
+            ..message(id, MessageStatus::Completed)
+        }
+    }
+
+    fn dialog(id: &str) -> SaveDialog {
+        SaveDialog { artifact: artifact(id, "report.md"), path: "/tmp/report.md".to_string(), saving: false, error: None }
+    }
+
+    #[test]
+    fn test_artifacts_are_read_from_metadata() {
+        let coverage: Artifact = serde_json::from_value(json!({
+            "id": "a1",
+            "name": "coverage.html",
+            "path": "target/coverage.html",
+            "size": 2048,
+            "contentType": "text/html",
+            "tool": "Bash",
+        }))
+        .unwrap();
+
+        assert_eq!(coverage.content_type.as_deref(), Some("text/html"));
+        assert_eq!(coverage.tool.as_deref(), Some("Bash"));
+        assert_eq!(serde_json::from_value::<Artifact>(json!({ "id": "a2", "name": "out.txt" })).unwrap(), artifact("a2", "out.txt"));
+    }
+
+    #[test]
+    fn test_a_conversation_lists_each_artifact_once_in_order() {
+        let mut state = AppState::default();
+        state.conversations[0].messages = vec![
+            answered("m1", vec![artifact("a1", "plot.png"), artifact("a2", "report.md")]),
+            answered("m2", vec![artifact("a2", "report.md"), artifact("a3", "data.csv")]),
+        ];
+
+        let ids: Vec<&str> = list(&state.conversations[0]).iter().map(|a| a.id.as_str()).collect();
+        assert_eq!(ids, ["a1", "a2", "a3"]);
+    }
+
+    #[test]
+    fn test_names_cannot_leave_the_chosen_folder() {
+        assert_eq!(file_name(&artifact("a1", "../../etc/passwd")), "passwd");
+        assert_eq!(file_name(&artifact("a1", "out/report.md")), "report.md");
+        assert_eq!(file_name(&artifact("a1", "..")), "artifact-a1");
+    }
+
+    #[test]
+    fn test_saves_never_overwrite_earlier_ones() {
+        let dir = Path::new("/downloads");
+        let taken = [PathBuf::from("/downloads/report.md"), PathBuf::from("/downloads/report (2).md")];
+        let exists = |path: &Path| taken.iter().any(|t| t == path);
+
+        assert_eq!(save_path(dir, &artifact("a1", "report.md"), exists), PathBuf::from("/downloads/report (3).md"));
+        assert_eq!(save_path(dir, &artifact("a2", "notes.md"), exists), PathBuf::from("/downloads/notes.md"));
+        assert_eq!(save_path(dir, &artifact("a3", ".env"), |p: &Path| p == Path::new("/downloads/.env")), PathBuf::from("/downloads/.env (2)"));
+    }
+
+    #[test]
+    fn test_the_artifacts_tab_is_left_with_the_conversation() {
+        let mut state = AppState::default();
+        update(&mut state, Action::ShowArtifacts(true));
+        assert!(state.artifacts_tab);
+
+        update(&mut state, Action::NewConversation);
+        assert!(!state.artifacts_tab);
+    }
+
+    #[test]
+    fn test_failed_saves_stay_in_the_dialog() {
+        let mut state = AppState::default();
+        update(&mut state, Action::OpenSaveArtifact(dialog("a1")));
+        update(&mut state, Action::ArtifactSaveStarted("a1".to_string()));
+        assert!(state.saving_artifacts.contains("a1"));
+
+        // Nothing closes a dialog that's still saving
+        update(&mut state, Action::CloseSaveArtifact);
+        assert!(state.artifact_save.as_ref().is_some_and(|d| d.saving));
+
+        update(&mut state, Action::ArtifactSaved { id: "a1".to_string(), result: Err("Permission denied".to_string()) });
+        let open = state.artifact_save.as_ref().unwrap();
+        assert!(!open.saving);
+        assert_eq!(open.error.as_deref(), Some("Permission denied"));
+        assert!(state.saving_artifacts.is_empty());
+
+        update(&mut state, Action::SaveArtifactPathChanged("/tmp/elsewhere.md".to_string()));
+        assert_eq!(state.artifact_save.as_ref().unwrap().error, None);
+        update(&mut state, Action::ArtifactSaveStarted("a1".to_string()));
+        update(&mut state, Action::ArtifactSaved { id: "a1".to_string(), result: Ok(PathBuf::from("/tmp/elsewhere.md")) });
+        assert!(state.artifact_save.is_none());
+    }
+}
@@ -0,0 +1,102 @@
+use crate::attachments::{content_type, list, size_label, uploaded, uploading, Entry, Pending, Upload};
+use crate::state::{update, Action, AppState};
+use crate::tests::Scratch;
+use crate::types::Attachment;
+use std::path::{Path, PathBuf};
+
+#[cfg(test)]
+mod attachments_tests {
+    use super::*;
+
+    fn picked(path: &str) -> Pending {
+        Pending {
+            path: PathBuf::from(path),
+            name: path.to_string(),
+            size: 2048,
+            upload: Upload::Uploading,
+        }
+    }
+
+    fn attachment(id: &str, name: &str) -> Attachment {
+        Attachment {
+            id: id.to_string(),
+            name: name.to_string(),
+            size: 2048,
+            content_type: Some("text/plain".to_string()),
+        }
+    }
+
+    #[test]
+    fn test_picker_lists_folders_first_without_hidden_files() {
+        let scratch = Scratch::new();
+        std::fs::write(scratch.0.join("notes.md"), "notes").unwrap();
+        std::fs::write(scratch.0.join("Build.log"), "ok").unwrap();
+        std::fs::write(scratch.0.join(".env"), "SECRET=1").unwrap();
+        std::fs::create_dir(scratch.0.join("src")).unwrap();
+
+        let entries = list(&scratch.0).unwrap();
+
+        assert_eq!(
+            entries,
+            [
+                Entry { name: "src".to_string(), is_dir: true },
+                Entry { name: "Build.log".to_string(), is_dir: false },
+                Entry { name: "notes.md".to_string(), is_dir: false },
+            ]
+        );
+        assert!(list(&scratch.0.join("missing")).is_err());
+    }
+
+    #[test]
+    fn test_content_type_and_size_labels() {
+        assert_eq!(content_type(Path::new("shot.PNG")), "image/png");
+        assert_eq!(content_type(Path::new("src/main.rs")), "text/plain");
+        assert_eq!(content_type(Path::new("Makefile")), "application/octet-stream");
+
+        assert_eq!(size_label(512), "512 B");
+        assert_eq!(size_label(1536), "1.5 KB");
+        assert_eq!(size_label(3 * 1024 * 1024), "3.0 MB");
+    }
+
+    #[test]
+    fn test_uploads_finish_on_their_own_chip() {
+        let mut state = AppState::default();
+        update(&mut state, Action::AttachmentPicked(picked("a.txt")));
+        update(&mut state, Action::AttachmentPicked(picked("b.txt")));
+        assert!(uploading(&state.composer_attachments));
+
+        update(&mut state, Action::AttachmentUploaded { path: PathBuf::from("b.txt"), result: Err("too large".to_string()) });
+        update(&mut state, Action::AttachmentUploaded { path: PathBuf::from("a.txt"), result: Ok(attachment("att-a", "a.txt")) });
+
+        assert!(!uploading(&state.composer_attachments));
+        assert_eq!(state.composer_attachments[1].upload, Upload::Failed("too large".to_string()));
+        assert_eq!(uploaded(&state.composer_attachments), [attachment("att-a", "a.txt")]);
+    }
+
+    #[test]
+    fn test_removed_uploads_stay_removed() {
+        let mut state = AppState::default();
+        update(&mut state, Action::AttachmentPicked(picked("a.txt")));
+        update(&mut state, Action::RemoveAttachment(0));
+        update(&mut state, Action::AttachmentUploaded { path: PathBuf::from("a.txt"), result: Ok(attachment("att-a", "a.txt")) });
+
+        assert!(state.composer_attachments.is_empty());
+    }
+
+    #[test]
+    fn test_sent_message_carries_its_attachments() {
+        let mut state = AppState::default();
+        let idx = state.active_conversation.unwrap();
+        update(&mut state, Action::AttachmentPicked(picked("a.txt")));
+        update(&mut state, Action::AttachmentPicked(picked("b.txt")));
+        update(&mut state, Action::AttachmentUploaded { path: PathBuf::from("a.txt"), result: Ok(attachment("att-a", "a.txt")) });
+        update(&mut state, Action::AttachmentUploaded { path: PathBuf::from("b.txt"), result: Err("refused".to_string()) });
+
+        update(&mut state, Action::InputChanged("What's in this log?".to_string()));
+        update(&mut state, Action::SendMessage);
+
+        let sent = state.conversations[idx].messages.last().unwrap();
+        assert_eq!(sent.attachments, [attachment("att-a", "a.txt")]);
+        assert!(state.composer_attachments.is_empty());
+    }
+}
@@ -0,0 +1,91 @@
+use crate::backup::{back_up, list, prune, restore};
+use chrono::NaiveDate;
+use crate::tests::Scratch;
+use std::path::{Path, PathBuf};
+
+#[cfg(test)]
+mod backup_tests {
+    use super::*;
+
+    fn day(d: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(2024, 3, d).unwrap()
+    }
+
+    fn files(dir: &Path) -> Vec<(&'static str, PathBuf)> {
+        vec![
+            ("Labels.toml", dir.join("Labels.toml")),
+            ("Permissions.toml", dir.join("Permissions.toml")),
+        ]
+    }
+
+    #[test]
+    fn test_backs_up_existing_files_once_a_day() {
+        let scratch = Scratch::new();
+        let root = scratch.0.join("backups");
+        std::fs::write(scratch.0.join("Labels.toml"), "c1 = \"red\"").unwrap();
+
+        let backup = back_up(&root, day(1), &files(&scratch.0)).unwrap().unwrap();
+        assert_eq!(backup.date, day(1));
+        assert_eq!(std::fs::read_to_string(backup.path.join("Labels.toml")).unwrap(), "c1 = \"red\"");
+        assert!(!backup.path.join("Permissions.toml").exists());
+
+        // Later the same day doesn't overwrite the morning's copy
+        std::fs::write(scratch.0.join("Labels.toml"), "c1 = \"blue\"").unwrap();
+        assert_eq!(back_up(&root, day(1), &files(&scratch.0)).unwrap(), None);
+        assert_eq!(std::fs::read_to_string(backup.path.join("Labels.toml")).unwrap(), "c1 = \"red\"");
+    }
+
+    #[test]
+    fn test_nothing_to_back_up() {
+        let scratch = Scratch::new();
+        let root = scratch.0.join("backups");
+
+        assert_eq!(back_up(&root, day(1), &files(&scratch.0)).unwrap(), None);
+        assert!(list(&root).is_empty());
+    }
+
+    #[test]
+    fn test_list_newest_first_and_prune() {
+        let scratch = Scratch::new();
+        let root = scratch.0.join("backups");
+        std::fs::write(scratch.0.join("Labels.toml"), "").unwrap();
+        for d in [3, 1, 4, 2] {
+            back_up(&root, day(d), &files(&scratch.0)).unwrap();
+        }
+        std::fs::create_dir_all(root.join("not-a-date")).unwrap();
+
+        let dates: Vec<NaiveDate> = list(&root).into_iter().map(|b| b.date).collect();
+        assert_eq!(dates, vec![day(4), day(3), day(2), day(1)]);
+
+        assert_eq!(prune(&root, 2).unwrap(), 2);
+        let dates: Vec<NaiveDate> = list(&root).into_iter().map(|b| b.date).collect();
+        assert_eq!(dates, vec![day(4), day(3)]);
+        assert!(root.join("not-a-date").exists());
+    }
+
+    #[test]
+    fn test_prune_keeps_at_least_one() {
+        let scratch = Scratch::new();
+        let root = scratch.0.join("backups");
+        std::fs::write(scratch.0.join("Labels.toml"), "").unwrap();
+        back_up(&root, day(1), &files(&scratch.0)).unwrap();
+
+        assert_eq!(prune(&root, 0).unwrap(), 0);
+        assert_eq!(list(&root).len(), 1);
+    }
+
+    #[test]
+    fn test_restore_leaves_files_the_backup_lacks() {
+        let scratch = Scratch::new();
+        let root = scratch.0.join("backups");
+        std::fs::write(scratch.0.join("Labels.toml"), "c1 = \"red\"").unwrap();
+        let backup = back_up(&root, day(1), &files(&scratch.0)).unwrap().unwrap();
+
+        std::fs::write(scratch.0.join("Labels.toml"), "c1 = \"blue\"").unwrap();
+        std::fs::write(scratch.0.join("Permissions.toml"), "allow = []").unwrap();
+
+        assert_eq!(restore(&backup, &files(&scratch.0)).unwrap(), 1);
+        assert_eq!(std::fs::read_to_string(scratch.0.join("Labels.toml")).unwrap(), "c1 = \"red\"");
+        assert_eq!(std::fs::read_to_string(scratch.0.join("Permissions.toml")).unwrap(), "allow = []");
+    }
+}
@@ -0,0 +1,82 @@
+use crate::budget::{check, tokens_used, TokenBudget, Verdict};
+use crate::palette::{commands, PaletteCommand};
+use crate::state::{update, Action, AppState, Conversation};
+use crate::tests::{conversation as conversation_of, message};
+use crate::types::{LegacyMessage, MessageStatus};
+use serde_json::json;
+
+#[cfg(test)]
+mod budget_tests {
+    use super::*;
+
+    fn answered(id: &str, tokens: u32) -> LegacyMessage {
+        LegacyMessage {
+            content: "Summarise the diff".to_string(),
+            response: Some("Done".to_string()),
+            metadata: Some(serde_json::from_value(json!({ "tokens": { "input": 0, "output": tokens, "total": tokens } })).unwrap()),
+            ..message(id, MessageStatus::Completed)
+        }
+    }
+
+    fn conversation(used: &[u32], budget: Option<TokenBudget>) -> Conversation {
+        let messages = used.iter().enumerate().map(|(n, tokens)| answered(&format!("m{}", n), *tokens)).collect();
+        Conversation { budget, ..conversation_of("Budgeted", messages) }
+    }
+
+    #[test]
+    fn test_tokens_used_adds_up_every_message() {
+        assert_eq!(tokens_used(&conversation(&[400, 600], None)), 1_000);
+    }
+
+    #[test]
+    fn test_no_budget_always_sends() {
+        assert_eq!(check(&conversation(&[u32::MAX, 1], None)), Verdict::Send);
+    }
+
+    #[test]
+    fn test_sends_warn_close_to_the_budget() {
+        let budget = Some(TokenBudget { limit: 1_000, block: true });
+
+        assert_eq!(check(&conversation(&[799], budget)), Verdict::Send);
+        assert!(matches!(check(&conversation(&[800], budget)), Verdict::Warn(warning) if warning.starts_with("Close to")));
+    }
+
+    #[test]
+    fn test_over_budget_blocks_only_when_asked_to() {
+        let used = [600, 600];
+
+        let refused = check(&conversation(&used, Some(TokenBudget { limit: 1_000, block: true })));
+        assert!(matches!(refused, Verdict::Refuse(reason) if reason.contains("1.2K tokens of 1.0K tokens")));
+
+        let warned = check(&conversation(&used, Some(TokenBudget { limit: 1_000, block: false })));
+        assert!(matches!(warned, Verdict::Warn(warning) if warning.starts_with("Over")));
+    }
+
+    #[test]
+    fn test_palette_sets_toggles_and_removes_the_budget() {
+        let mut state = AppState::default();
+        let idx = state.active_conversation.unwrap();
+        let budget_commands = |state: &AppState| -> Vec<PaletteCommand> {
+            commands(state)
+                .into_iter()
+                .map(|entry| entry.command)
+                .filter(|command| matches!(command, PaletteCommand::SetTokenBudget(..)))
+                .collect()
+        };
+
+        // New budgets only warn
+        let offered = budget_commands(&state);
+        assert_eq!(offered[0], PaletteCommand::SetTokenBudget(idx, Some(TokenBudget { limit: 100_000, block: false })));
+        assert!(!offered.contains(&PaletteCommand::SetTokenBudget(idx, None)));
+
+        let budget = TokenBudget { limit: 250_000, block: false };
+        update(&mut state, Action::SetTokenBudget { conversation_idx: idx, budget: Some(budget) });
+        let offered = budget_commands(&state);
+        assert!(!offered.contains(&PaletteCommand::SetTokenBudget(idx, Some(budget))));
+        assert!(offered.contains(&PaletteCommand::SetTokenBudget(idx, Some(TokenBudget { block: true, ..budget }))));
+        assert!(offered.contains(&PaletteCommand::SetTokenBudget(idx, None)));
+
+        update(&mut state, Action::SetTokenBudget { conversation_idx: idx, budget: None });
+        assert_eq!(state.conversations[idx].budget, None);
+    }
+}
@@ -0,0 +1,85 @@
+use crate::cli::{parse, take_messages, Command, Invocation};
+use serde_json::json;
+
+#[cfg(test)]
+mod cli_tests {
+    use super::*;
+
+    fn args(args: &[&str]) -> Vec<String> {
+        args.iter().map(|arg| arg.to_string()).collect()
+    }
+
+    fn command(line: &[&str]) -> Command {
+        parse(&args(line)).unwrap().unwrap().command
+    }
+
+    #[test]
+    fn test_no_arguments_starts_the_gui() {
+        assert_eq!(parse(&[]), Ok(None));
+    }
+
+    #[test]
+    fn test_send_with_options() {
+        assert_eq!(
+            command(&["send", "Run the tests", "--interaction", "i1", "--worktree", "w1"]),
+            Command::Send {
+                content: "Run the tests".to_string(),
+                interaction_id: Some("i1".to_string()),
+                worktree_id: Some("w1".to_string()),
+            }
+        );
+        assert!(parse(&args(&["send", "Run", "the", "tests"])).is_err());
+        assert!(parse(&args(&["send", "  "])).is_err());
+    }
+
+    #[test]
+    fn test_server_goes_anywhere() {
+        assert_eq!(
+            parse(&args(&["list", "--server", "staging"])),
+            Ok(Some(Invocation { server: Some("staging".to_string()), command: Command::List }))
+        );
+        assert_eq!(parse(&args(&["--server", "staging"])), Err("Missing command".to_string()));
+    }
+
+    #[test]
+    fn test_worktree_create() {
+        assert_eq!(
+            command(&["worktree", "create", "fix-parser", "--base", "main"]),
+            Command::CreateWorktree {
+                branch: "fix-parser".to_string(),
+                base_branch: Some("main".to_string()),
+                path: None,
+            }
+        );
+    }
+
+    #[test]
+    fn test_export_and_help() {
+        assert_eq!(command(&["export", "i1"]), Command::Export { interaction_id: "i1".to_string() });
+        assert_eq!(command(&["--help"]), Command::Help);
+        assert_eq!(command(&["list", "-h"]), Command::Help);
+    }
+
+    #[test]
+    fn test_mistakes_are_errors() {
+        assert_eq!(parse(&args(&["lst"])), Err("Unknown command: lst".to_string()));
+        assert_eq!(parse(&args(&["list", "--verbose"])), Err("Unknown option --verbose".to_string()));
+        assert_eq!(parse(&args(&["export", "i1", "--base", "main"])), Err("--base doesn't apply to this command".to_string()));
+        assert_eq!(parse(&args(&["send", "hi", "--worktree"])), Err("--worktree needs a value".to_string()));
+    }
+
+    #[test]
+    fn test_page_messages_come_oldest_first() {
+        let mut page = json!({
+            "messages": [
+                { "id": "m2", "timestamp": "2024-03-01T10:05:00.000Z" },
+                { "id": "m1", "timestamp": "2024-03-01T10:00:00.000Z" }
+            ]
+        });
+
+        let ids: Vec<_> = take_messages(&mut page).iter().map(|m| m["id"].clone()).collect();
+
+        assert_eq!(ids, vec![json!("m1"), json!("m2")]);
+        assert!(take_messages(&mut json!({})).is_empty());
+    }
+}
@@ -0,0 +1,39 @@
+use crate::clipboard::Backend;
+use crate::platform::Os;
+
+#[cfg(test)]
+mod backend_tests {
+    use super::*;
+
+    fn env(vars: &'static [&'static str]) -> impl Fn(&str) -> Option<String> {
+        move |var| vars.contains(&var).then(|| "set".to_string())
+    }
+
+    #[test]
+    fn test_wayland_wins_over_xwayland() {
+        assert_eq!(Backend::detect(Os::Unix, env(&["WAYLAND_DISPLAY", "DISPLAY"])), Backend::Wayland);
+    }
+
+    #[test]
+    fn test_plain_x11() {
+        assert_eq!(Backend::detect(Os::Unix, env(&["DISPLAY"])), Backend::X11);
+    }
+
+    #[test]
+    fn test_no_display_has_no_clipboard() {
+        assert_eq!(Backend::detect(Os::Unix, env(&[])), Backend::Unavailable);
+    }
+
+    #[test]
+    fn test_mac_and_windows_ignore_display_variables() {
+        assert_eq!(Backend::detect(Os::Mac, env(&["DISPLAY"])), Backend::Native);
+        assert_eq!(Backend::detect(Os::Windows, env(&[])), Backend::Native);
+    }
+
+    #[test]
+    fn test_only_linux_desktops_have_primary_selection() {
+        assert!(Backend::Wayland.has_primary_selection());
+        assert!(Backend::X11.has_primary_selection());
+        assert!(!Backend::Native.has_primary_selection());
+    }
+}
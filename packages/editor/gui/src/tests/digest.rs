@@ -0,0 +1,107 @@
+use crate::digest::{narration_prompt, to_markdown, weekly, Completion, ConversationWeek};
+use crate::state::{update, Action, AppState};
+use crate::tests;
+use crate::types::{InteractionMetadata, LegacyMessage, MessageStatus, TokenUsage, Worktree, WorktreeStatus};
+use chrono::{DateTime, Duration, TimeZone, Utc};
+
+#[cfg(test)]
+mod digest_tests {
+    use super::*;
+
+    fn now() -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2024, 1, 8, 12, 0, 0).unwrap()
+    }
+
+    fn message(content: &str, status: MessageStatus, days_ago: i64, total: u32) -> LegacyMessage {
+        LegacyMessage {
+            content: content.to_string(),
+            metadata: Some(InteractionMetadata {
+                tokens: Some(TokenUsage { input: total / 2, output: total - total / 2, total }),
+                ..Default::default()
+            }),
+            sent_at: Some(now() - Duration::days(days_ago)),
+            ..tests::message(&format!("m-{}", content), status)
+        }
+    }
+
+    fn week() -> AppState {
+        let mut state = AppState::default();
+        state.editor.available_worktrees.push(Worktree {
+            id: "wt-1".to_string(),
+            path: "/tmp/wt-1".to_string(),
+            branch: Some("refs/heads/feature/cache".to_string()),
+            base_branch: Some("main".to_string()),
+            base_commit: None,
+            status: WorktreeStatus::Active,
+            created_at: "2024-01-01T00:00:00Z".to_string(),
+        });
+
+        let conv = &mut state.conversations[0];
+        conv.title = "Cache".to_string();
+        conv.worktree_id = Some("wt-1".to_string());
+        conv.messages = vec![
+            message("Key the cache by path", MessageStatus::Completed, 2, 1200),
+            message("Add eviction", MessageStatus::Failed, 1, 300),
+            message("Old news", MessageStatus::Completed, 9, 5000),
+        ];
+
+        update(&mut state, Action::NewConversation);
+        let conv = state.conversations.last_mut().unwrap();
+        conv.title = "Parser".to_string();
+        conv.worktree_id = Some("wt-gone".to_string());
+        conv.messages = vec![message("Why does the parser panic?", MessageStatus::Completed, 3, 4000)];
+
+        update(&mut state, Action::NewConversation);
+        state.conversations.last_mut().unwrap().messages = vec![message("Last month", MessageStatus::Completed, 30, 100)];
+        state
+    }
+
+    #[test]
+    fn test_digest_covers_only_the_past_week() {
+        let digest = weekly(&week(), now());
+
+        assert_eq!(digest.since, now() - Duration::days(7));
+        assert_eq!(
+            digest.conversations,
+            [
+                ConversationWeek { title: "Cache".to_string(), messages: 2, tokens: 1500 },
+                ConversationWeek { title: "Parser".to_string(), messages: 1, tokens: 4000 },
+            ]
+        );
+        assert_eq!(digest.tokens, 5500);
+        assert_eq!(digest.worktrees, ["feature/cache", "wt-gone"]);
+    }
+
+    #[test]
+    fn test_notable_completions_took_the_most_tokens() {
+        let digest = weekly(&week(), now());
+
+        assert_eq!(
+            digest.completions,
+            [
+                Completion { conversation: "Parser".to_string(), prompt: "Why does the parser panic?".to_string(), tokens: 4000 },
+                Completion { conversation: "Cache".to_string(), prompt: "Key the cache by path".to_string(), tokens: 1200 },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_digest_markdown() {
+        let markdown = to_markdown(&weekly(&week(), now()));
+
+        assert!(markdown.starts_with("# Weekly digest\n\n2024-01-01 to 2024-01-08\n"));
+        assert!(markdown.contains("- 2 conversations, 3 messages\n- 5500 tokens spent\n- 2 worktrees touched\n"));
+        assert!(markdown.contains("| Cache | 2 | 1500 |\n| Parser | 1 | 4000 |\n"));
+        assert!(markdown.contains("## Worktrees touched\n\n- feature/cache\n- wt-gone\n"));
+        assert!(markdown.contains("- **Parser**: Why does the parser panic? (4000 tokens)\n"));
+        assert!(narration_prompt(&markdown).ends_with("(1200 tokens)"));
+    }
+
+    #[test]
+    fn test_quiet_week() {
+        let markdown = to_markdown(&weekly(&AppState::default(), now()));
+
+        assert!(markdown.contains("- 0 conversations, 0 messages\n"));
+        assert!(markdown.ends_with("_Nothing happened this week._\n"));
+    }
+}
@@ -0,0 +1,85 @@
+use crate::events::{bus, AppEvent, Backlog, BACKLOG_CAPACITY, BATCH_SIZE};
+use crate::sse::SSEMessage;
+use serde_json::json;
+
+#[cfg(test)]
+mod events_tests {
+    use super::*;
+
+    fn update(server: &str, id: &str, version: u64) -> AppEvent {
+        AppEvent::Sse {
+            server: server.to_string(),
+            message: SSEMessage::InteractionUpdate { id: id.to_string(), data: json!({ "version": version }) },
+        }
+    }
+
+    fn version(event: &AppEvent) -> Option<(&str, u64)> {
+        match event {
+            AppEvent::Sse { message: SSEMessage::InteractionUpdate { id, data }, .. } => Some((id.as_str(), data["version"].as_u64()?)),
+            _ => None,
+        }
+    }
+
+    #[test]
+    fn test_updates_to_one_interaction_coalesce_in_place() {
+        let mut backlog = Backlog::default();
+        backlog.push(update("local", "a", 1));
+        backlog.push(update("local", "b", 1));
+        backlog.push(update("local", "a", 2));
+        backlog.push(update("staging", "a", 1));
+
+        let events = backlog.take(10);
+        let versions: Vec<_> = events.iter().filter_map(version).collect();
+        assert_eq!(versions, vec![("a", 2), ("b", 1), ("a", 1)]);
+        assert!(backlog.is_empty());
+    }
+
+    #[test]
+    fn test_other_events_are_kept_in_order() {
+        let mut backlog = Backlog::default();
+        let connected = || AppEvent::Sse { server: "local".to_string(), message: SSEMessage::Connected };
+        backlog.push(connected());
+        backlog.push(update("local", "a", 1));
+        backlog.push(connected());
+
+        let events = backlog.take(10);
+        assert_eq!(events.len(), 3);
+        assert!(matches!(events[2], AppEvent::Sse { message: SSEMessage::Connected, .. }));
+    }
+
+    #[test]
+    fn test_full_backlog_drops_new_events_but_still_coalesces() {
+        let mut backlog = Backlog::default();
+        for n in 0..BACKLOG_CAPACITY + 5 {
+            backlog.push(update("local", &n.to_string(), 1));
+        }
+        assert_eq!(backlog.len(), BACKLOG_CAPACITY);
+        assert_eq!(backlog.dropped, 5);
+
+        backlog.push(update("local", "0", 2));
+        assert_eq!(backlog.dropped, 5);
+        assert_eq!(version(&backlog.take(1)[0]), Some(("0", 2)));
+
+        backlog.take(BACKLOG_CAPACITY);
+        assert_eq!(backlog.dropped, 0);
+    }
+
+    #[test]
+    fn test_burst_drains_a_batch_at_a_time() {
+        let (bus, inbox) = bus();
+        for n in 0..BATCH_SIZE * 2 + 1 {
+            bus.publish(update("local", &n.to_string(), 1));
+        }
+        // Coalesced away, the burst is still the same size
+        bus.publish(update("local", "0", 2));
+
+        let first = inbox.take_batch().unwrap();
+        assert_eq!(first.events.len(), BATCH_SIZE);
+        assert_eq!(first.remaining, BATCH_SIZE + 1);
+        assert_eq!(version(&first.events[0]), Some(("0", 2)));
+
+        assert_eq!(inbox.take_batch().unwrap().remaining, 1);
+        assert_eq!(inbox.take_batch().unwrap().remaining, 0);
+        assert!(inbox.take_batch().is_none());
+    }
+}
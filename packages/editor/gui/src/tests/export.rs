@@ -0,0 +1,90 @@
+use crate::export::{preview, selection_to_markdown, ExportSelection};
+use crate::state::Conversation;
+use crate::tests::{conversation as conversation_of, message};
+use crate::types::{LegacyMessage, MessageStatus};
+
+#[cfg(test)]
+mod export_tests {
+    use super::*;
+
+    fn exchange(n: usize, status: MessageStatus) -> LegacyMessage {
+        LegacyMessage {
+            content: format!("Question {}", n),
+            response: (status == MessageStatus::Completed).then(|| format!("Answer {}", n)),
+            ..message(&format!("m{}", n), status)
+        }
+    }
+
+    fn conversation() -> Conversation {
+        conversation_of(
+            "Notes",
+            vec![
+                exchange(1, MessageStatus::Completed),
+                exchange(2, MessageStatus::Failed),
+                exchange(3, MessageStatus::Completed),
+            ],
+        )
+    }
+
+    #[test]
+    fn test_everything() {
+        let conv = conversation();
+        let markdown = selection_to_markdown(&conv, &ExportSelection::all(&conv));
+
+        assert_eq!(markdown, "# Notes\n\n\
+            ## You\n\nQuestion 1\n\n## Wake\n\nAnswer 1\n\n\
+            ## You\n\nQuestion 2\n\n_No response: the interaction failed._\n\n\
+            ## You\n\nQuestion 3\n\n## Wake\n\nAnswer 3\n\n");
+    }
+
+    #[test]
+    fn test_range() {
+        let conv = conversation();
+        let selection = ExportSelection { from: 1, to: 2, ..ExportSelection::all(&conv) };
+        let markdown = selection_to_markdown(&conv, &selection);
+
+        assert!(!markdown.contains("Question 1"));
+        assert!(markdown.contains("Question 2"));
+        assert!(markdown.contains("Answer 3"));
+    }
+
+    #[test]
+    fn test_only_responses() {
+        let conv = conversation();
+        let selection = ExportSelection { prompts: false, ..ExportSelection::all(&conv) };
+        let markdown = selection_to_markdown(&conv, &selection);
+
+        assert!(!markdown.contains("## You"));
+        assert!(markdown.contains("Answer 1"));
+        assert!(markdown.contains("the interaction failed"));
+    }
+
+    #[test]
+    fn test_only_prompts() {
+        let conv = conversation();
+        let selection = ExportSelection { responses: false, ..ExportSelection::all(&conv) };
+        let markdown = selection_to_markdown(&conv, &selection);
+
+        assert!(!markdown.contains("## Wake"));
+        assert!(!markdown.contains("failed"));
+        assert!(markdown.contains("Question 3"));
+    }
+
+    #[test]
+    fn test_nothing_selected() {
+        let conv = conversation();
+        let selection = ExportSelection { prompts: false, responses: false, ..ExportSelection::all(&conv) };
+        assert!(selection.is_empty(&conv));
+        assert!(!ExportSelection::all(&conv).is_empty(&conv));
+    }
+
+    #[test]
+    fn test_preview_is_cut_short() {
+        let long = (0..100).map(|n| n.to_string()).collect::<Vec<_>>().join("\n");
+        let shown = preview(&long);
+
+        assert!(shown.starts_with("0\n1\n"));
+        assert!(shown.ends_with("… 60 more lines"));
+        assert_eq!(preview("short\n\n"), "short");
+    }
+}
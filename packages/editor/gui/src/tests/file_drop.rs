@@ -0,0 +1,40 @@
+use crate::attachments::MAX_BYTES;
+use crate::file_drop::{drop_action, reference, DropAction};
+use crate::state::{update, Action, AppState};
+use std::path::Path;
+
+#[cfg(test)]
+mod file_drop_tests {
+    use super::*;
+
+    #[test]
+    fn test_files_are_attached_and_the_rest_inserted() {
+        assert_eq!(drop_action(false, 2048), DropAction::Attach);
+        assert_eq!(drop_action(false, MAX_BYTES), DropAction::Attach);
+        assert_eq!(drop_action(false, MAX_BYTES + 1), DropAction::InsertPath);
+        assert_eq!(drop_action(true, 0), DropAction::InsertPath);
+    }
+
+    #[test]
+    fn test_worktree_paths_become_mentions() {
+        let worktree = Some(Path::new("/work/repo"));
+
+        assert_eq!(reference(Path::new("/work/repo/src/main.rs"), worktree), "@src/main.rs");
+        assert_eq!(reference(Path::new("/work/repo"), worktree), "/work/repo");
+        assert_eq!(reference(Path::new("/home/me/notes.md"), worktree), "/home/me/notes.md");
+        assert_eq!(reference(Path::new("/work/repo/src"), None), "/work/repo/src");
+    }
+
+    #[test]
+    fn test_drop_target_needs_a_conversation() {
+        let mut state = AppState::default();
+        update(&mut state, Action::SetDropHover(true));
+        assert!(state.drop_hover);
+        update(&mut state, Action::SetDropHover(false));
+        assert!(!state.drop_hover);
+
+        state.active_conversation = None;
+        update(&mut state, Action::SetDropHover(true));
+        assert!(!state.drop_hover);
+    }
+}
@@ -0,0 +1,18 @@
+use crate::fonts::{verify, BERKELEY_MONO_BYTES};
+
+#[cfg(test)]
+mod fonts_tests {
+    use super::*;
+
+    #[test]
+    fn test_embedded_fonts_load() {
+        assert_eq!(verify(), Ok(()));
+    }
+
+    #[test]
+    fn test_embedded_font_is_a_real_font() {
+        // A truncated or LFS-pointer checkout would still compile
+        assert!(BERKELEY_MONO_BYTES.len() > 1024);
+        assert_eq!(&BERKELEY_MONO_BYTES[..4], b"OTTO");
+    }
+}
@@ -0,0 +1,104 @@
+use crate::config::{HookEvent, HookSettings};
+use crate::hooks::{fired, message_payload, run_script, sandboxed, HookAction};
+use crate::state::Conversation;
+use crate::tests;
+use crate::types::{LegacyMessage, MessageStatus, ToolPermissionRequest};
+use serde_json::json;
+use std::path::Path;
+
+#[cfg(test)]
+mod hooks_tests {
+    use super::*;
+
+    fn message(id: &str, status: MessageStatus) -> LegacyMessage {
+        let waiting = status == MessageStatus::WaitingForPermission;
+        LegacyMessage {
+            content: "Run the tests".to_string(),
+            response: (status == MessageStatus::Completed).then(|| "All green".to_string()),
+            pending_tool_permission: waiting.then(|| ToolPermissionRequest {
+                tool_name: "Bash".to_string(),
+                description: "Run cargo test".to_string(),
+                request_id: Some("req-1".to_string()),
+                input: Some(json!({ "command": "cargo test" })),
+            }),
+            ..tests::message(id, status)
+        }
+    }
+
+    fn conversation(messages: Vec<LegacyMessage>) -> Conversation {
+        tests::conversation("Tests", messages)
+    }
+
+    #[test]
+    fn test_hooks_fire_when_a_message_changes_status() {
+        let old = vec![message("m1", MessageStatus::Processing), message("m2", MessageStatus::Completed)];
+        let new = vec![
+            message("m1", MessageStatus::WaitingForPermission),
+            message("m2", MessageStatus::Completed),
+            message("m3", MessageStatus::Completed),
+            message("m4", MessageStatus::Processing),
+        ];
+
+        let events: Vec<(HookEvent, &str)> = fired(&old, &new).into_iter().map(|(event, msg)| (event, msg.id.as_str())).collect();
+        assert_eq!(events, vec![(HookEvent::PermissionRequested, "m1"), (HookEvent::MessageCompleted, "m3")]);
+    }
+
+    #[test]
+    fn test_permission_payload_names_the_tool() {
+        let msg = message("m1", MessageStatus::WaitingForPermission);
+        let payload = message_payload(HookEvent::PermissionRequested, &conversation(vec![msg.clone()]), &msg);
+
+        assert_eq!(payload["event"], "permission_requested");
+        assert_eq!(payload["conversationId"], "c1");
+        assert_eq!(payload["message"]["prompt"], "Run the tests");
+        assert_eq!(payload["permission"]["tool"], "Bash");
+        assert_eq!(payload["permission"]["input"], json!({ "command": "cargo test" }));
+    }
+
+    #[test]
+    fn test_scripts_ask_for_actions() {
+        let msg = message("m1", MessageStatus::WaitingForPermission);
+        let payload = message_payload(HookEvent::PermissionRequested, &conversation(vec![msg.clone()]), &msg);
+        let script = r#"
+            notify("Tests passed");
+            respond(event.permission.tool == "Bash");
+            write_file("logs/run.txt", `ran ${event.message.prompt}`);
+        "#;
+        assert_eq!(run_script(script, &payload), Ok(vec![
+            HookAction::Notify { message: "Tests passed".to_string() },
+            HookAction::Respond { approved: true },
+            HookAction::WriteFile { path: "logs/run.txt".to_string(), contents: "ran Run the tests".to_string() },
+        ]));
+
+        assert_eq!(run_script("let x = 1;", &payload), Ok(Vec::new()));
+        assert!(run_script("delete_file(\"x\");", &payload).is_err());
+    }
+
+    #[test]
+    fn test_scripts_are_stopped() {
+        assert!(run_script("loop {}", &json!({})).is_err());
+        assert!(run_script("fn f() { f() } f();", &json!({})).is_err());
+        assert!(run_script("import \"secrets\" as s;", &json!({})).is_err());
+    }
+
+    #[test]
+    fn test_writes_stay_in_the_hooks_folder() {
+        let root = Path::new("/tmp/hooks");
+        assert_eq!(sandboxed(root, "logs/run.txt"), Ok(root.join("logs/run.txt")));
+        assert_eq!(sandboxed(root, "./run.txt"), Ok(root.join("./run.txt")));
+
+        assert!(sandboxed(root, "../Mind.toml").is_err());
+        assert!(sandboxed(root, "logs/../../Mind.toml").is_err());
+        assert!(sandboxed(root, "/etc/passwd").is_err());
+        assert!(sandboxed(root, "").is_err());
+    }
+
+    #[test]
+    fn test_hooks_are_configured_by_event() {
+        let hook: HookSettings = toml::from_str("on = \"worktree_created\"\nscript = \"setup.rhai\"").unwrap();
+        assert_eq!(hook, HookSettings { on: HookEvent::WorktreeCreated, script: "setup.rhai".to_string() });
+
+        assert!(toml::from_str::<HookSettings>("on = \"app_started\"\nscript = \"x.rhai\"").is_err());
+        assert!(toml::from_str::<HookSettings>("on = \"worktree_created\"\ncommand = \"./setup.sh\"").is_err());
+    }
+}
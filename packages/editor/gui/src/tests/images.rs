@@ -0,0 +1,127 @@
+use crate::images::{find_images, inspect, load, message_images, ImageInfo, ImageLoad, ImageSource, LoadedImage, Preview};
+use crate::state::{update, Action, AppState};
+use crate::tests::message;
+use crate::types::{InteractionMetadata, LegacyMessage, MessageStatus, ToolCall};
+use base64::Engine;
+use serde_json::json;
+use std::path::{Path, PathBuf};
+
+#[cfg(test)]
+mod images_tests {
+    use super::*;
+
+    // A 1x1 PNG
+    const PIXEL: &str = "iVBORw0KGgoAAAANSUhEUgAAAAEAAAABCAYAAAAfFcSJAAAADUlEQVR42mP8z8BQDwAEhQGAhKmMIQAAAABJRU5ErkJggg==";
+
+    fn file(path: &str) -> ImageSource {
+        ImageSource::File(PathBuf::from(path))
+    }
+
+    #[test]
+    fn test_markdown_images_urls_and_paths_are_found() {
+        let text = "Here's the chart: ![latency](charts/latency.png)\n\
+                    Compare with https://example.com/img/before.jpg?size=large and `/tmp/after.webp`.\n\
+                    The logo.png in the README is unchanged, see ./docs/notes.md";
+
+        assert_eq!(
+            find_images(text, Some(Path::new("/work/repo"))),
+            [
+                file("/work/repo/charts/latency.png"),
+                ImageSource::Url("https://example.com/img/before.jpg?size=large".to_string()),
+                file("/tmp/after.webp"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_relative_paths_need_a_root_and_repeats_are_dropped() {
+        let text = "![a](plots/a.svg) and again plots/a.svg";
+
+        assert_eq!(find_images(text, Some(Path::new("/w"))), [file("/w/plots/a.svg")]);
+        assert!(find_images(text, None).is_empty());
+    }
+
+    #[test]
+    fn test_data_uris_are_inline_images() {
+        let text = format!("Rendered: ![plot](data:image/png;base64,{})", PIXEL);
+
+        let images = find_images(&text, None);
+        assert_eq!(images, [ImageSource::Inline { mime: "image/png".to_string(), data: PIXEL.to_string() }]);
+        assert_eq!(images[0].label(), "inline image/png");
+        assert!(images[0].key().len() < 64);
+    }
+
+    #[test]
+    fn test_labels_name_the_file() {
+        assert_eq!(file("/tmp/shots/home.png").label(), "home.png");
+        assert_eq!(ImageSource::Url("https://example.com/a/b.gif?x=1".to_string()).label(), "b.gif");
+    }
+
+    #[test]
+    fn test_tool_arguments_are_searched_too() {
+        let msg = LegacyMessage {
+            response: Some("Done.".to_string()),
+            metadata: Some(InteractionMetadata {
+                tool_calls: Some(vec![ToolCall {
+                    name: "Screenshot".to_string(),
+                    arguments: json!({ "output": "/tmp/shot.png", "scale": 2 }),
+                }]),
+                ..Default::default()
+            }),
+            ..message("m1", MessageStatus::Completed)
+        };
+
+        assert_eq!(message_images(&msg, None), [file("/tmp/shot.png")]);
+    }
+
+    #[test]
+    fn test_headers_give_format_and_size() {
+        let mut gif = b"GIF89a".to_vec();
+        gif.extend_from_slice(&[0x20, 0x03, 0x58, 0x02]);
+        assert_eq!(inspect(&gif), Some(ImageInfo { format: "GIF", dimensions: Some((800, 600)), bytes: 10 }));
+
+        // Start of image, an APP0 segment, then a baseline frame of 640x480
+        let jpeg = [
+            0xff, 0xd8, 0xff, 0xe0, 0x00, 0x04, 0x00, 0x00, 0xff, 0xc0, 0x00, 0x11, 0x08, 0x01, 0xe0, 0x02, 0x80,
+        ];
+        assert_eq!(inspect(&jpeg).and_then(|info| info.dimensions), Some((640, 480)));
+
+        assert_eq!(inspect(b"<svg xmlns=\"http://www.w3.org/2000/svg\"/>").map(|info| info.format), Some("SVG"));
+        assert_eq!(inspect(b"not an image"), None);
+    }
+
+    #[tokio::test]
+    async fn test_inline_images_load_without_the_network() {
+        let info = load(ImageSource::Inline { mime: "image/png".to_string(), data: PIXEL.to_string() }).await;
+
+        assert_eq!(info.as_ref().map(|image| (image.info.format, image.info.dimensions)), Ok(("PNG", Some((1, 1)))));
+        assert!(matches!(info.map(|image| image.preview), Ok(Preview::Raster(_))));
+        assert!(load(file("/nonexistent/shot.png")).await.is_err());
+    }
+
+    #[test]
+    fn test_svgs_are_drawn_as_vectors_and_urls_wait_for_a_click() {
+        let svg = LoadedImage::from_bytes(b"<svg xmlns=\"http://www.w3.org/2000/svg\"/>".to_vec()).unwrap();
+        assert!(matches!(svg.preview, Preview::Vector(_)));
+        assert_eq!(LoadedImage::from_bytes(b"not an image".to_vec()), None);
+
+        assert!(!ImageSource::Url("https://example.com/a.png".to_string()).loads_automatically());
+        assert!(file("/tmp/a.png").loads_automatically());
+    }
+
+    #[test]
+    fn test_images_are_cached_by_key_and_expand() {
+        let mut state = AppState::default();
+        let info = LoadedImage::from_bytes(base64::engine::general_purpose::STANDARD.decode(PIXEL).unwrap()).unwrap();
+
+        update(&mut state, Action::ImageRequested("/tmp/a.png".to_string()));
+        assert_eq!(state.images["/tmp/a.png"], ImageLoad::Loading);
+        update(&mut state, Action::ImageLoaded { key: "/tmp/a.png".to_string(), result: Ok(info.clone()) });
+        assert_eq!(state.images["/tmp/a.png"], ImageLoad::Loaded(info));
+
+        update(&mut state, Action::ExpandImage(Some(file("/tmp/a.png"))));
+        assert_eq!(state.expanded_image, Some(file("/tmp/a.png")));
+        update(&mut state, Action::ExpandImage(None));
+        assert!(state.expanded_image.is_none());
+    }
+}
@@ -0,0 +1,113 @@
+use crate::import::{archive, parse, pending, Format};
+use crate::types::{ImportedMessage, ImportedRole};
+use crate::tests::Scratch;
+use std::path::Path;
+
+#[cfg(test)]
+mod import_tests {
+    use super::*;
+
+    fn said(messages: &[ImportedMessage]) -> Vec<(ImportedRole, &str)> {
+        messages.iter().map(|m| (m.role, m.content.as_str())).collect()
+    }
+
+    const SESSION: &str = r#"{"type":"summary","summary":"Fix the parser panic","leafUuid":"a"}
+{"type":"user","isMeta":true,"message":{"role":"user","content":"Caveat: generated by local commands"},"timestamp":"2024-03-01T09:59:00Z"}
+{"type":"user","message":{"role":"user","content":"<command-name>/clear</command-name>"},"timestamp":"2024-03-01T09:59:30Z"}
+{"type":"user","message":{"role":"user","content":"Why does the parser panic?"},"timestamp":"2024-03-01T10:00:00Z"}
+{"type":"assistant","message":{"role":"assistant","content":[{"type":"thinking","thinking":"hmm"},{"type":"text","text":"Let me look."},{"type":"tool_use","id":"t1","name":"Read","input":{}}]},"timestamp":"2024-03-01T10:00:02Z"}
+{"type":"user","message":{"role":"user","content":[{"type":"tool_result","tool_use_id":"t1","content":"fn parse() {}"}]},"timestamp":"2024-03-01T10:00:03Z"}
+{"type":"assistant","message":{"role":"assistant","content":[{"type":"text","text":"An unchecked index."}]},"timestamp":"2024-03-01T10:00:05Z"}
+{"type":"assistant","isSidechain":true,"message":{"role":"assistant","content":"sub-agent chatter"},"timestamp":"2024-03-01T10:00:06Z"}
+not json, the session was cut off here"#;
+
+    #[test]
+    fn test_reads_claude_code_session() {
+        let request = parse(Format::ClaudeCode, SESSION).unwrap();
+
+        assert_eq!(request.title.as_deref(), Some("Fix the parser panic"));
+        assert_eq!(request.from, "claude-code");
+        assert_eq!(said(&request.messages), vec![
+            (ImportedRole::User, "Why does the parser panic?"),
+            (ImportedRole::Assistant, "Let me look.\n\nAn unchecked index."),
+        ]);
+        assert_eq!(request.messages[0].timestamp.as_deref(), Some("2024-03-01T10:00:00Z"));
+    }
+
+    #[test]
+    fn test_reads_claude_code_session_as_array() {
+        let array = format!("[{}]", SESSION.lines().take(7).collect::<Vec<_>>().join(","));
+
+        let request = parse(Format::ClaudeCode, &array).unwrap();
+
+        assert_eq!(request.messages.len(), 2);
+    }
+
+    #[test]
+    fn test_reads_our_own_exports() {
+        let markdown = "# Parser work\n\n## You\n\nWhy does it panic?\n\n## Wake (sonnet)\n\nAn unchecked index.\n\n## You\n\nFix it\n\n_No response: the interaction failed._\n\n";
+
+        let request = parse(Format::Markdown, markdown).unwrap();
+
+        assert_eq!(request.title.as_deref(), Some("Parser work"));
+        assert_eq!(request.from, "markdown");
+        assert_eq!(said(&request.messages), vec![
+            (ImportedRole::User, "Why does it panic?"),
+            (ImportedRole::Assistant, "An unchecked index."),
+            (ImportedRole::User, "Fix it"),
+        ]);
+    }
+
+    #[test]
+    fn test_reads_bold_speakers() {
+        let markdown = "**User:** Hi there\nsecond line\n**Assistant**: Hello\n";
+
+        let request = parse(Format::Markdown, markdown).unwrap();
+
+        assert_eq!(request.title, None);
+        assert_eq!(said(&request.messages), vec![
+            (ImportedRole::User, "Hi there\nsecond line"),
+            (ImportedRole::Assistant, "Hello"),
+        ]);
+    }
+
+    #[test]
+    fn test_headings_in_code_blocks_are_content() {
+        let markdown = "## User\n\nWhat does this do?\n\n```md\n## Assistant\n```\n\n## Claude\n\nIt's a heading.\n";
+
+        let request = parse(Format::Markdown, markdown).unwrap();
+
+        assert_eq!(said(&request.messages), vec![
+            (ImportedRole::User, "What does this do?\n\n```md\n## Assistant\n```"),
+            (ImportedRole::Assistant, "It's a heading."),
+        ]);
+    }
+
+    #[test]
+    fn test_transcript_without_messages_is_an_error() {
+        assert!(parse(Format::Markdown, "# Notes\n\nJust some notes.\n").is_err());
+        assert!(parse(Format::ClaudeCode, "{\"type\":\"summary\",\"summary\":\"Empty\"}").is_err());
+    }
+
+    #[test]
+    fn test_format_follows_extension() {
+        assert_eq!(Format::of(Path::new("session.jsonl")), Some(Format::ClaudeCode));
+        assert_eq!(Format::of(Path::new("chat.MD")), Some(Format::Markdown));
+        assert_eq!(Format::of(Path::new("notes.pdf")), None);
+        assert_eq!(Format::of(Path::new("README")), None);
+    }
+
+    #[test]
+    fn test_imported_transcripts_leave_the_inbox() {
+        let scratch = Scratch::new();
+        let transcript = scratch.0.join("session.jsonl");
+        std::fs::write(&transcript, SESSION).unwrap();
+        std::fs::write(scratch.0.join("notes.pdf"), "").unwrap();
+        assert_eq!(pending(&scratch.0), vec![transcript.clone()]);
+
+        let moved = archive(&transcript).unwrap();
+
+        assert!(moved.is_file());
+        assert!(pending(&scratch.0).is_empty());
+    }
+}
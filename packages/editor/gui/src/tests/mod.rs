@@ -0,0 +1,116 @@
+use crate::state::{Conversation, TitleSource};
+use crate::types::{LegacyMessage, MessageStatus};
+use bicky_editor_core::types::InteractionType;
+use std::collections::HashSet;
+use std::path::PathBuf;
+
+// A fresh directory per test, removed when dropped
+#[cfg(test)]
+pub(crate) struct Scratch(pub PathBuf);
+
+#[cfg(test)]
+impl Scratch {
+    pub(crate) fn new() -> Self {
+        let dir = std::env::temp_dir().join(format!("bicky-test-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        Self(dir)
+    }
+}
+
+#[cfg(test)]
+impl Drop for Scratch {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_dir_all(&self.0);
+    }
+}
+
+// A message with nothing but its id and status, tests fill in the rest
+#[cfg(test)]
+pub(crate) fn message(id: &str, status: MessageStatus) -> LegacyMessage {
+    LegacyMessage {
+        id: id.to_string(),
+        content: format!("Message {}", id),
+        response: None,
+        status,
+        metadata: None,
+        pending_tool_permission: None,
+        sent_at: None,
+        error: None,
+        attachments: Vec::new(),
+    }
+}
+
+// A conversation on the local server holding `messages`
+#[cfg(test)]
+pub(crate) fn conversation(title: &str, messages: Vec<LegacyMessage>) -> Conversation {
+    Conversation {
+        id: "c1".to_string(),
+        title: title.to_string(),
+        title_source: TitleSource::Manual,
+        interaction_type: InteractionType::Query,
+        messages,
+        muted: false,
+        archived: false,
+        worktree_id: None,
+        bookmarks: HashSet::new(),
+        server: "local".to_string(),
+        history_cursor: None,
+        loading_history: false,
+        budget: None,
+        agents: Vec::new(),
+        pins: Vec::new(),
+        route: None,
+        review_stack: Vec::new(),
+    }
+}
+
+#[cfg(test)]
+mod api;
+#[cfg(test)]
+mod artifacts;
+#[cfg(test)]
+mod attachments;
+#[cfg(test)]
+mod backup;
+#[cfg(test)]
+mod budget;
+#[cfg(test)]
+mod cli;
+#[cfg(test)]
+mod clipboard;
+#[cfg(test)]
+mod digest;
+#[cfg(test)]
+mod events;
+#[cfg(test)]
+mod export;
+#[cfg(test)]
+mod file_drop;
+#[cfg(test)]
+mod fonts;
+#[cfg(test)]
+mod hooks;
+#[cfg(test)]
+mod images;
+#[cfg(test)]
+mod import;
+#[cfg(test)]
+mod multiline_input;
+#[cfg(test)]
+mod pins;
+#[cfg(test)]
+mod platform;
+#[cfg(test)]
+mod plugins;
+#[cfg(test)]
+mod quick_actions;
+#[cfg(test)]
+mod servers;
+#[cfg(test)]
+mod sessions;
+#[cfg(test)]
+mod state;
+#[cfg(test)]
+mod tasks;
+#[cfg(test)]
+mod timings;
\ No newline at end of file
@@ -0,0 +1,44 @@
+use crate::widgets::multiline_input::{committed_text, text, update, ComposerMessage};
+use iced::widget::text_editor::{Action, Binding, Content, Edit};
+use std::sync::Arc;
+
+#[cfg(test)]
+mod composer_tests {
+    use super::*;
+
+    fn paste(content: &mut Content, pasted: &str) {
+        update(content, ComposerMessage::Edit(Action::Edit(Edit::Paste(Arc::new(pasted.to_string())))));
+    }
+
+    #[test]
+    fn test_committed_ime_text_keeps_every_character() {
+        let binding = committed_text::<()>("日本語");
+
+        assert_eq!(
+            binding,
+            Some(Binding::Sequence(vec![Binding::Insert('日'), Binding::Insert('本'), Binding::Insert('語')]))
+        );
+    }
+
+    #[test]
+    fn test_single_characters_use_the_default_binding() {
+        assert_eq!(committed_text::<()>("a"), None);
+        assert_eq!(committed_text::<()>("\u{8}"), None);
+    }
+
+    #[test]
+    fn test_paste_drops_trailing_nul() {
+        let mut content = Content::new();
+        paste(&mut content, "cargo test\0");
+
+        assert_eq!(text(&content), "cargo test");
+    }
+
+    #[test]
+    fn test_paste_normalizes_line_endings() {
+        let mut content = Content::new();
+        paste(&mut content, "a\r\nb\rc");
+
+        assert_eq!(text(&content), "a\nb\nc");
+    }
+}
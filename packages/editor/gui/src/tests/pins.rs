@@ -0,0 +1,78 @@
+use crate::pins::{label, with_pins, Pin};
+use crate::state::{update, Action, AppState, Conversation};
+use crate::tests::{conversation as conversation_of, message};
+use crate::types::{LegacyMessage, MessageStatus};
+use serde_json::json;
+
+#[cfg(test)]
+mod pins_tests {
+    use super::*;
+
+    fn answered(id: &str, content: &str) -> LegacyMessage {
+        LegacyMessage {
+            content: content.to_string(),
+            response: Some("Use a HashMap".to_string()),
+            ..message(id, MessageStatus::Completed)
+        }
+    }
+
+    fn conversation(pins: Vec<Pin>) -> Conversation {
+        Conversation { pins, ..conversation_of("Pinned", vec![answered("m1", "How should the cache be keyed?")]) }
+    }
+
+    #[test]
+    fn test_nothing_pinned_leaves_metadata_alone() {
+        let conv = conversation(Vec::new());
+
+        assert_eq!(with_pins(&conv, None), None);
+        assert_eq!(with_pins(&conv, Some(json!({ "continuation": {} }))), Some(json!({ "continuation": {} })));
+    }
+
+    #[test]
+    fn test_pins_go_out_with_the_request_metadata() {
+        let conv = conversation(vec![
+            Pin::Message("m1".to_string()),
+            Pin::File("src/cache.rs".to_string()),
+            Pin::Message("gone".to_string()),
+        ]);
+
+        let metadata = with_pins(&conv, Some(json!({ "continuation": { "resumeOf": "m0" } }))).unwrap();
+
+        assert_eq!(metadata, json!({
+            "continuation": { "resumeOf": "m0" },
+            "pinnedContext": [
+                { "type": "message", "messageId": "m1", "content": "How should the cache be keyed?", "response": "Use a HashMap" },
+                { "type": "file", "path": "src/cache.rs" },
+                { "type": "message", "messageId": "gone", "content": null, "response": null },
+            ],
+        }));
+    }
+
+    #[test]
+    fn test_pinned_messages_are_labelled_by_what_was_asked() {
+        let mut conv = conversation(Vec::new());
+        conv.messages.push(answered("m2", "Explain every step the scheduler takes when a job times out"));
+
+        assert_eq!(label(&conv, &Pin::Message("m1".to_string())), "“How should the cache be keyed?”");
+        assert_eq!(label(&conv, &Pin::Message("m2".to_string())), "“Explain every step the scheduler…”");
+        assert_eq!(label(&conv, &Pin::File("src/cache.rs".to_string())), "src/cache.rs");
+    }
+
+    #[test]
+    fn test_pins_toggle_and_typed_files_are_pinned_once() {
+        let mut state = AppState::default();
+        let idx = state.active_conversation.unwrap();
+
+        update(&mut state, Action::TogglePin { conversation_idx: idx, pin: Pin::Message("m1".to_string()) });
+        update(&mut state, Action::PinDraftChanged(" src/cache.rs ".to_string()));
+        update(&mut state, Action::PinDraftedFile(idx));
+        update(&mut state, Action::PinDraftChanged("src/cache.rs".to_string()));
+        update(&mut state, Action::PinDraftedFile(idx));
+
+        assert_eq!(state.conversations[idx].pins, [Pin::Message("m1".to_string()), Pin::File("src/cache.rs".to_string())]);
+        assert!(state.pin_draft.is_empty());
+
+        update(&mut state, Action::TogglePin { conversation_idx: idx, pin: Pin::Message("m1".to_string()) });
+        assert_eq!(state.conversations[idx].pins, [Pin::File("src/cache.rs".to_string())]);
+    }
+}
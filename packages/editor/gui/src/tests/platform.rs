@@ -0,0 +1,113 @@
+use crate::keymap::{KeyBinding, KeyCommand, Keymap};
+use crate::platform::Os;
+use iced::keyboard::{Key, Modifiers};
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+#[cfg(test)]
+mod dirs_tests {
+    use super::*;
+
+    fn env(vars: &[(&str, &str)]) -> impl Fn(&str) -> Option<String> {
+        let vars: HashMap<String, String> = vars.iter().map(|(k, v)| (k.to_string(), v.to_string())).collect();
+        move |var| vars.get(var).cloned()
+    }
+
+    #[test]
+    fn test_mac_uses_application_support() {
+        let env = env(&[("HOME", "/Users/ada")]);
+
+        assert_eq!(Os::Mac.config_dir(&env), Some(PathBuf::from("/Users/ada/Library/Application Support/Bicamrl")));
+        assert_eq!(Os::Mac.data_dir(&env), Os::Mac.config_dir(&env));
+    }
+
+    #[test]
+    fn test_windows_uses_appdata() {
+        let env = env(&[("APPDATA", r"C:\Users\ada\AppData\Roaming"), ("LOCALAPPDATA", r"C:\Users\ada\AppData\Local")]);
+
+        assert_eq!(Os::Windows.config_dir(&env), Some(PathBuf::from(r"C:\Users\ada\AppData\Roaming").join("Bicamrl")));
+        assert_eq!(Os::Windows.data_dir(&env), Some(PathBuf::from(r"C:\Users\ada\AppData\Local").join("Bicamrl")));
+    }
+
+    #[test]
+    fn test_windows_falls_back_to_profile() {
+        let env = env(&[("USERPROFILE", r"C:\Users\ada")]);
+
+        assert_eq!(
+            Os::Windows.config_dir(&env),
+            Some(PathBuf::from(r"C:\Users\ada").join("AppData").join("Roaming").join("Bicamrl"))
+        );
+    }
+
+    #[test]
+    fn test_unix_follows_xdg() {
+        let env = env(&[("HOME", "/home/ada"), ("XDG_CONFIG_HOME", "/etc/ada")]);
+
+        assert_eq!(Os::Unix.config_dir(&env), Some(PathBuf::from("/etc/ada/bicamrl")));
+        assert_eq!(Os::Unix.data_dir(&env), Some(PathBuf::from("/home/ada/.local/share/bicamrl")));
+    }
+
+    #[test]
+    fn test_unix_ignores_relative_xdg() {
+        let env = env(&[("HOME", "/home/ada"), ("XDG_CONFIG_HOME", "config")]);
+
+        assert_eq!(Os::Unix.config_dir(&env), Some(PathBuf::from("/home/ada/.config/bicamrl")));
+    }
+
+    #[test]
+    fn test_no_home_means_no_dirs() {
+        let env = env(&[]);
+
+        assert_eq!(Os::Unix.config_dir(&env), None);
+        assert_eq!(Os::Mac.data_dir(&env), None);
+    }
+
+    #[test]
+    fn test_port_file_in_checkout_wins() {
+        let files = Os::Unix.port_files(env(&[("HOME", "/home/ada")]));
+
+        assert_eq!(files.first(), Some(&PathBuf::from("./.bicamrl-port")));
+        assert_eq!(files.last(), Some(&PathBuf::from("/home/ada/.local/share/bicamrl/port")));
+    }
+}
+
+#[cfg(test)]
+mod keymap_tests {
+    use super::*;
+
+    fn key(c: &str) -> Key {
+        Key::Character(c.into())
+    }
+
+    #[test]
+    fn test_cmd_is_command_on_mac_and_ctrl_elsewhere() {
+        let mac = KeyBinding::parse_for("cmd+k", Os::Mac).unwrap();
+        assert!(mac.matches(&key("k"), Modifiers::LOGO, Os::Mac));
+        assert!(!mac.matches(&key("k"), Modifiers::CTRL, Os::Mac));
+
+        let linux = KeyBinding::parse_for("cmd+k", Os::Unix).unwrap();
+        assert!(linux.matches(&key("k"), Modifiers::CTRL, Os::Unix));
+        assert!(!linux.matches(&key("k"), Modifiers::LOGO, Os::Unix));
+    }
+
+    #[test]
+    fn test_ctrl_is_control_on_mac() {
+        let binding = KeyBinding::parse_for("ctrl+k", Os::Mac).unwrap();
+
+        assert!(binding.matches(&key("k"), Modifiers::CTRL, Os::Mac));
+        assert!(!binding.matches(&key("k"), Modifiers::LOGO, Os::Mac));
+    }
+
+    #[test]
+    fn test_ctrl_is_cmd_elsewhere() {
+        assert_eq!(KeyBinding::parse_for("ctrl+k", Os::Windows), KeyBinding::parse_for("cmd+k", Os::Windows));
+    }
+
+    #[test]
+    fn test_focus_mode_has_a_default_key() {
+        let cmd = if Os::current().has_command_key() { Modifiers::LOGO } else { Modifiers::CTRL };
+
+        assert_eq!(Keymap::default().resolve(&key("f"), cmd | Modifiers::SHIFT, true), Some(KeyCommand::FocusMode));
+        assert_eq!(KeyCommand::parse("focus_mode"), Some(KeyCommand::FocusMode));
+    }
+}
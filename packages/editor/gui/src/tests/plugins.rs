@@ -0,0 +1,121 @@
+use crate::config::PluginSettings;
+use crate::plugins::{ask, context, message_context, parse, unrendered, LoadedPlugin, Rendering};
+use crate::state::AppState;
+use crate::tests::message;
+use crate::types::{InteractionMetadata, LegacyMessage, MessageStatus, ToolCall, Worktree, WorktreeStatus};
+use bicky_editor_core::plugin::{Manifest, Request};
+use serde_json::json;
+
+#[cfg(test)]
+mod plugins_tests {
+    use super::*;
+
+    fn answered(id: &str, tools: &[&str]) -> LegacyMessage {
+        let tool_calls = tools
+            .iter()
+            .map(|name| ToolCall { name: name.to_string(), arguments: json!({ "path": "src/lib.rs" }) })
+            .collect();
+        let metadata = InteractionMetadata { model: Some("wake-1".to_string()), tool_calls: Some(tool_calls), ..Default::default() };
+        LegacyMessage {
+            content: "Run the tests".to_string(),
+            response: Some("All green".to_string()),
+            metadata: Some(metadata),
+            ..message(id, MessageStatus::Completed)
+        }
+    }
+
+    fn plugin(name: &str, tools: &[&str]) -> LoadedPlugin {
+        LoadedPlugin {
+            settings: PluginSettings { command: name.to_string(), ..Default::default() },
+            manifest: Manifest {
+                name: name.to_string(),
+                tools: tools.iter().map(|tool| tool.to_string()).collect(),
+                ..Default::default()
+            },
+        }
+    }
+
+    fn script(script: &str) -> PluginSettings {
+        PluginSettings {
+            command: "sh".to_string(),
+            args: vec!["-c".to_string(), script.to_string()],
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_message_context_carries_the_tool_calls() {
+        let context = message_context(&answered("m1", &["run_tests"])).unwrap();
+
+        assert_eq!(context.prompt, "Run the tests");
+        assert_eq!(context.response, "All green");
+        assert_eq!(context.model.as_deref(), Some("wake-1"));
+        assert_eq!(context.tool_calls[0].name, "run_tests");
+        assert_eq!(context.tool_calls[0].arguments, json!({ "path": "src/lib.rs" }));
+    }
+
+    #[test]
+    fn test_unanswered_messages_are_not_offered() {
+        let mut msg = answered("m1", &[]);
+        msg.status = MessageStatus::Processing;
+        assert!(message_context(&msg).is_none());
+
+        msg.status = MessageStatus::Completed;
+        msg.response = None;
+        assert!(message_context(&msg).is_none());
+    }
+
+    #[test]
+    fn test_unrendered_pairs_messages_with_the_first_plugin_that_takes_them() {
+        let mut state = AppState {
+            plugins: vec![plugin("edits", &["edit_file"]), plugin("tests", &["run_tests"]), plugin("all", &["run_tests"])],
+            ..Default::default()
+        };
+        state.plugin_renderings.insert("m3".to_string(), Rendering::Declined);
+        let messages = vec![answered("m1", &["run_tests"]), answered("m2", &["read_file"]), answered("m3", &["edit_file"])];
+
+        let offered: Vec<(String, Option<String>)> = unrendered(&state, &messages)
+            .into_iter()
+            .map(|(id, plugin, _)| (id, plugin.map(|p| p.manifest.name.clone())))
+            .collect();
+        assert_eq!(offered, vec![("m1".to_string(), Some("tests".to_string())), ("m2".to_string(), None)]);
+    }
+
+    #[test]
+    fn test_context_names_the_branch_without_its_ref_prefix() {
+        let worktree = Worktree {
+            id: "w1".to_string(),
+            path: "/tmp/w1".to_string(),
+            branch: Some("refs/heads/feature/plugins".to_string()),
+            base_branch: None,
+            base_commit: None,
+            status: WorktreeStatus::Active,
+            created_at: "2024-01-01T00:00:00Z".to_string(),
+        };
+        let mut state = AppState::default();
+        state.editor.current_worktree = Some(worktree);
+
+        let context = context(&state);
+        assert_eq!(context.server, state.active_server);
+        assert_eq!(context.branch.as_deref(), Some("feature/plugins"));
+        let active = state.active_conversation.map(|idx| state.conversations[idx].id.clone());
+        assert_eq!(context.conversation_id, active);
+    }
+
+    #[tokio::test]
+    async fn test_plugin_answers_on_stdout() {
+        let answer = ask(script("cat > /dev/null; echo '{\"name\": \"echo\"}'"), Request::Describe).await;
+
+        let manifest: Manifest = parse(answer).unwrap();
+        assert_eq!(manifest.name, "echo");
+    }
+
+    #[tokio::test]
+    async fn test_plugin_failure_reports_stderr() {
+        let answer = ask(script("echo 'no such command' >&2; exit 3"), Request::Describe).await;
+        assert_eq!(answer, Err("no such command".to_string()));
+
+        let answer = ask(script("echo not json"), Request::Describe).await;
+        assert!(answer.unwrap_err().contains("bad JSON"));
+    }
+}
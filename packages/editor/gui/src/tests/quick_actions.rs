@@ -0,0 +1,50 @@
+use crate::config::{QuickAction, QuickActions, Settings};
+use crate::quick_actions::{expand, Outcome};
+
+#[cfg(test)]
+mod quick_actions_tests {
+    use super::*;
+
+    fn action(prompt: &str, send: bool) -> QuickAction {
+        QuickAction { label: "Go".to_string(), prompt: prompt.to_string(), send }
+    }
+
+    #[test]
+    fn test_sending_action_fills_in_the_context() {
+        let action = action("Explain {selection} in {worktree}{cursor}", true);
+
+        assert_eq!(
+            expand(&action, "fn main()", Some("/repo/wt")),
+            Outcome::Send("Explain fn main() in /repo/wt".to_string())
+        );
+    }
+
+    #[test]
+    fn test_sending_action_without_anything_selected_waits_for_the_user() {
+        let action = action("Explain this:\n\n{selection}", true);
+
+        assert_eq!(expand(&action, "  ", None), Outcome::Fill("Explain this:\n\n  ".to_string()));
+    }
+
+    #[test]
+    fn test_template_action_keeps_the_cursor_marker() {
+        let action = action("Write tests for {cursor}", false);
+
+        assert_eq!(expand(&action, "parse()", None), Outcome::Fill("Write tests for {cursor}".to_string()));
+    }
+
+    #[test]
+    fn test_configured_actions_replace_the_built_in_ones() {
+        assert_eq!(Settings::default().quick_actions.0.len(), 3);
+
+        let settings: Settings = toml::from_str(
+            "[[quick_actions]]\nlabel = \"Summarize\"\nprompt = \"Summarize {selection}\"\nsend = true",
+        )
+        .unwrap();
+        assert_eq!(settings.quick_actions, QuickActions(vec![QuickAction {
+            label: "Summarize".to_string(),
+            prompt: "Summarize {selection}".to_string(),
+            send: true,
+        }]));
+    }
+}
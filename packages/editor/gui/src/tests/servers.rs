@@ -0,0 +1,91 @@
+use crate::config::{ServerSettings, Settings};
+use crate::servers::{configured, interactions_to_load};
+use crate::state::{AppState, SystemClock, DEFAULT_SERVER};
+use std::sync::Arc;
+
+#[cfg(test)]
+mod configured_tests {
+    use super::*;
+
+    fn remote(name: Option<&str>, url: Option<&str>) -> ServerSettings {
+        ServerSettings {
+            name: name.map(str::to_string),
+            url: url.map(str::to_string),
+            api_key: Some("sk-remote".to_string()),
+        }
+    }
+
+    #[test]
+    fn test_primary_comes_first_as_local() {
+        let servers = configured(&Settings::default(), Arc::new(SystemClock));
+
+        assert_eq!(servers.len(), 1);
+        assert_eq!(servers[0].name, "local");
+    }
+
+    #[test]
+    fn test_extra_servers_are_named_by_url() {
+        let settings = Settings {
+            servers: vec![remote(None, Some("https://box.example.com/"))],
+            ..Settings::default()
+        };
+        let servers = configured(&settings, Arc::new(SystemClock));
+
+        assert_eq!(servers[1].name, "https://box.example.com/");
+        assert_eq!(servers[1].api.base_url, "https://box.example.com");
+        assert!(servers[1].api.credentials.is_set());
+    }
+
+    #[test]
+    fn test_unusable_servers_are_skipped() {
+        let settings = Settings {
+            servers: vec![
+                remote(Some("box"), None),
+                remote(Some("local"), Some("https://other.example.com")),
+                remote(Some("box"), Some("https://box.example.com")),
+            ],
+            ..Settings::default()
+        };
+        let names: Vec<_> = configured(&settings, Arc::new(SystemClock)).into_iter().map(|s| s.name).collect();
+
+        assert_eq!(names, ["local", "box"]);
+    }
+}
+
+#[cfg(test)]
+mod hydration_tests {
+    use super::*;
+    use serde_json::json;
+
+    fn listed(ids: &[&str]) -> Vec<serde_json::Value> {
+        ids.iter().map(|id| json!({ "id": id, "messageCount": 2 })).collect()
+    }
+
+    #[test]
+    fn test_loads_interactions_not_in_the_sidebar() {
+        let mut state = AppState::default();
+        state.conversations[0].id = "i1".to_string();
+
+        let ids = interactions_to_load(&state, DEFAULT_SERVER, &listed(&["i1", "i2", "i3"]));
+
+        assert_eq!(ids, ["i2", "i3"]);
+    }
+
+    #[test]
+    fn test_deleted_and_malformed_entries_are_skipped() {
+        let mut state = AppState::default();
+        state.deleted_conversations.insert("i2".to_string());
+        let mut entries = listed(&["i1", "i2"]);
+        entries.push(json!({ "messageCount": 0 }));
+
+        assert_eq!(interactions_to_load(&state, DEFAULT_SERVER, &entries), ["i1"]);
+    }
+
+    #[test]
+    fn test_same_id_on_another_server_still_loads() {
+        let mut state = AppState::default();
+        state.conversations[0].id = "i1".to_string();
+
+        assert_eq!(interactions_to_load(&state, "remote", &listed(&["i1"])), ["i1"]);
+    }
+}
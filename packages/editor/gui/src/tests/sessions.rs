@@ -0,0 +1,28 @@
+use crate::sessions::counts_label;
+use bicky_editor_core::types::InteractionQueueStatus;
+
+#[cfg(test)]
+mod counts_label_tests {
+    use super::*;
+
+    fn counts(needs_work: u32, needs_review: u32, processing: u32, completed: u32) -> InteractionQueueStatus {
+        InteractionQueueStatus {
+            queue_size: needs_work + needs_review + processing,
+            needs_work,
+            needs_review,
+            processing,
+            completed,
+            analyzing: 0,
+        }
+    }
+
+    #[test]
+    fn test_busy_session_lists_what_is_pending() {
+        assert_eq!(counts_label(&counts(1, 2, 3, 9)), "3 working · 2 to review · 1 queued");
+    }
+
+    #[test]
+    fn test_idle_session_shows_completed() {
+        assert_eq!(counts_label(&counts(0, 0, 0, 4)), "4 done");
+    }
+}
@@ -0,0 +1,2439 @@
+use crate::api::ApiError;
+use crate::permission_policy::{Decision, Rule};
+use crate::tests::message;
+use crate::state::*;
+use crate::types::*;
+use bicky_editor_core::tokenizer;
+use bicky_editor_core::Action as EditorAction;
+use chrono::{DateTime, Utc};
+use std::collections::HashSet;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+// A clock that only moves when the test says so
+#[derive(Debug)]
+struct ManualClock {
+    instant: Instant,
+    utc: DateTime<Utc>,
+}
+
+impl Clock for ManualClock {
+    fn instant(&self) -> Instant {
+        self.instant
+    }
+
+    fn utc(&self) -> DateTime<Utc> {
+        self.utc
+    }
+}
+
+// Ids counted up from zero, so tests know what to expect
+#[derive(Debug, Default)]
+struct SequentialIds {
+    next_conversation: AtomicUsize,
+    next_temp: AtomicUsize,
+}
+
+impl IdGenerator for SequentialIds {
+    fn conversation_id(&self) -> String {
+        format!("conversation-{}", self.next_conversation.fetch_add(1, Ordering::Relaxed))
+    }
+
+    fn temp_id(&self) -> String {
+        format!("temp-{}", self.next_temp.fetch_add(1, Ordering::Relaxed))
+    }
+}
+
+#[cfg(test)]
+mod state_tests {
+    use super::*;
+
+    fn fixed_state() -> AppState {
+        let clock = ManualClock {
+            instant: Instant::now(),
+            utc: "2024-01-01T12:00:00Z".parse().unwrap(),
+        };
+        AppState::new(Arc::new(clock), Arc::new(SequentialIds::default()))
+    }
+
+    fn advance(state: &mut AppState, by: Duration) {
+        state.clock = Arc::new(ManualClock {
+            instant: state.clock.instant() + by,
+            utc: state.clock.utc() + chrono::Duration::from_std(by).unwrap(),
+        });
+    }
+
+    fn worktree(id: &str) -> Worktree {
+        Worktree {
+            id: id.to_string(),
+            path: format!("/tmp/{}", id),
+            branch: Some(format!("feature/{}", id)),
+            base_branch: Some("main".to_string()),
+            base_commit: None,
+            status: WorktreeStatus::Active,
+            created_at: "2024-01-01T00:00:00Z".to_string(),
+        }
+    }
+
+    fn permission_request(tool: &str) -> ToolPermissionRequest {
+        ToolPermissionRequest {
+            tool_name: tool.to_string(),
+            description: format!("Use {}", tool),
+            request_id: Some("req-1".to_string()),
+            input: None,
+        }
+    }
+
+    mod composer {
+        use super::*;
+
+        #[test]
+        fn test_input_changed_counts_tokens() {
+            let mut state = fixed_state();
+            update(&mut state, Action::InputChanged("hello there".to_string()));
+
+            assert_eq!(state.editor.draft.content, "hello there");
+            assert_eq!(state.input_tokens, tokenizer::estimate_tokens("hello there"));
+        }
+
+        #[test]
+        fn test_send_message_stamps_with_clock() {
+            let mut state = fixed_state();
+            update(&mut state, Action::InputChanged("hello".to_string()));
+            update(&mut state, Action::SendMessage);
+
+            let msg = &state.conversations[0].messages[0];
+            assert_eq!(msg.id, "temp-0");
+            assert_eq!(msg.status, MessageStatus::Pending);
+            assert_eq!(msg.sent_at, Some(state.clock.utc()));
+            assert!(state.editor.draft.content.is_empty());
+            assert_eq!(state.input_tokens, 0);
+        }
+
+        #[test]
+        fn test_send_empty_message_is_ignored() {
+            let mut state = fixed_state();
+            update(&mut state, Action::SendMessage);
+
+            assert!(state.conversations[0].messages.is_empty());
+        }
+
+        #[test]
+        fn test_dismiss_template_restores_trigger() {
+            let mut state = fixed_state();
+            update(&mut state, Action::InputChanged("expanded body".to_string()));
+            update(&mut state, Action::TemplateExpanded { trigger: "/review".to_string() });
+            update(&mut state, Action::DismissTemplate);
+
+            assert_eq!(state.editor.draft.content, "/review");
+            assert!(state.expanded_template.is_none());
+        }
+
+        #[test]
+        fn test_resend_starts_new_conversation() {
+            let mut state = fixed_state();
+            state.conversations[0].messages.push(message("m1", MessageStatus::Completed));
+            update(&mut state, Action::EditMessage { conversation_idx: 0, message_id: "m1".to_string() });
+            assert_eq!(state.editor.draft.content, "Message m1");
+
+            update(&mut state, Action::InputChanged("Message m1, reworded".to_string()));
+            update(&mut state, Action::ResendMessage);
+
+            assert_eq!(state.conversations.len(), 2);
+            assert_eq!(state.active_conversation, Some(1));
+            assert!(state.conversations[1].title.ends_with("(edited)"));
+            assert_eq!(state.conversations[1].messages[0].content, "Message m1, reworded");
+            assert_eq!(state.conversations[0].messages[0].content, "Message m1");
+            assert!(state.editing.is_none());
+            assert!(state.editor.draft.content.is_empty());
+        }
+    }
+
+    mod messages {
+        use super::*;
+
+        #[test]
+        fn test_delete_settled_message() {
+            let mut state = fixed_state();
+            state.conversations[0].messages.push(message("m1", MessageStatus::Completed));
+            update(&mut state, Action::RequestDeleteMessage("m1".to_string()));
+            update(&mut state, Action::DeleteMessage { conversation_idx: 0, message_id: "m1".to_string() });
+
+            assert!(state.conversations[0].messages.is_empty());
+            assert!(state.deleted_messages.contains("m1"));
+            assert!(state.confirm_delete.is_none());
+        }
+
+        #[test]
+        fn test_delete_in_flight_message_is_refused() {
+            let mut state = fixed_state();
+            state.conversations[0].messages.push(message("m1", MessageStatus::Processing));
+            update(&mut state, Action::DeleteMessage { conversation_idx: 0, message_id: "m1".to_string() });
+
+            assert_eq!(state.conversations[0].messages.len(), 1);
+            assert!(state.deleted_messages.is_empty());
+        }
+
+        #[test]
+        fn test_delete_message_being_edited_clears_composer() {
+            let mut state = fixed_state();
+            state.conversations[0].messages.push(message("m1", MessageStatus::Error));
+            update(&mut state, Action::EditMessage { conversation_idx: 0, message_id: "m1".to_string() });
+            update(&mut state, Action::DeleteMessage { conversation_idx: 0, message_id: "m1".to_string() });
+
+            assert!(state.editing.is_none());
+            assert!(state.editor.draft.content.is_empty());
+        }
+
+        #[test]
+        fn test_message_lifecycle() {
+            let mut state = fixed_state();
+            update(&mut state, Action::InputChanged("hi".to_string()));
+            update(&mut state, Action::SendMessage);
+            update(&mut state, Action::MessageSent { conversation_idx: 0, id: "server-1".to_string() });
+            update(&mut state, Action::MessageProcessing { conversation_idx: 0, id: "server-1".to_string() });
+            assert_eq!(state.conversations[0].messages[0].status, MessageStatus::Processing);
+
+            update(&mut state, Action::MessageCompleted {
+                conversation_idx: 0,
+                id: "server-1".to_string(),
+                response: Some("hello".to_string()),
+                error: None,
+                metadata: None,
+            });
+            let msg = &state.conversations[0].messages[0];
+            assert_eq!(msg.status, MessageStatus::Completed);
+            assert_eq!(msg.response.as_deref(), Some("hello"));
+        }
+
+        #[test]
+        fn test_completed_with_error() {
+            let mut state = fixed_state();
+            state.conversations[0].messages.push(message("m1", MessageStatus::Processing));
+            update(&mut state, Action::MessageCompleted {
+                conversation_idx: 0,
+                id: "m1".to_string(),
+                response: None,
+                error: Some("boom".to_string()),
+                metadata: None,
+            });
+
+            assert_eq!(state.conversations[0].messages[0].status, MessageStatus::Error);
+        }
+
+        #[test]
+        fn test_toggle_bookmark() {
+            let mut state = fixed_state();
+            let toggle = || Action::ToggleBookmark { conversation_idx: 0, message_id: "m1".to_string() };
+            update(&mut state, toggle());
+            assert!(state.conversations[0].bookmarks.contains("m1"));
+
+            update(&mut state, toggle());
+            assert!(state.conversations[0].bookmarks.is_empty());
+        }
+    }
+
+    mod message_errors {
+        use super::*;
+        use crate::message_errors::{error_detail, interaction_id, never_sent};
+
+        #[test]
+        fn test_send_failure_marks_pending_message() {
+            let mut state = fixed_state();
+            update(&mut state, Action::InputChanged("hi".to_string()));
+            update(&mut state, Action::SendMessage);
+            update(&mut state, Action::MessageSendFailed { conversation_idx: 0, error: ApiError::Network("connection refused".to_string()).into() });
+
+            let msg = &state.conversations[0].messages[0];
+            assert_eq!(msg.status, MessageStatus::Error);
+            assert_eq!(error_detail(msg), Some("Couldn't reach the server: connection refused"));
+            assert!(never_sent(msg));
+            assert_eq!(interaction_id(&state.conversations[0]), None);
+        }
+
+        #[test]
+        fn test_completed_with_error_keeps_detail() {
+            let mut state = fixed_state();
+            state.conversations[0].messages.push(message("m1", MessageStatus::Processing));
+            update(&mut state, Action::MessageCompleted {
+                conversation_idx: 0,
+                id: "m1".to_string(),
+                response: None,
+                error: Some("rate limited".to_string()),
+                metadata: None,
+            });
+
+            assert_eq!(error_detail(&state.conversations[0].messages[0]), Some("rate limited"));
+        }
+
+        #[test]
+        fn test_retry_replaces_message_that_never_sent() {
+            let mut state = fixed_state();
+            update(&mut state, Action::InputChanged("hi".to_string()));
+            update(&mut state, Action::SendMessage);
+            update(&mut state, Action::MessageSendFailed { conversation_idx: 0, error: ApiError::Timeout.into() });
+            update(&mut state, Action::RetryMessage { conversation_idx: 0, message_id: "temp-0".to_string() });
+
+            let messages = &state.conversations[0].messages;
+            assert_eq!(messages.len(), 1);
+            assert_eq!(messages[0].status, MessageStatus::Pending);
+            assert_eq!(messages[0].content, "hi");
+            assert!(messages[0].error.is_none());
+        }
+
+        #[test]
+        fn test_retry_keeps_server_error_in_history() {
+            let mut state = fixed_state();
+            let mut failed = message("m1", MessageStatus::Error);
+            failed.response = Some("I encountered an error: boom".to_string());
+            failed.error = failed.response.clone().map(MessageError::server);
+            state.conversations[0].messages.push(failed);
+            assert!(interaction_id(&state.conversations[0]).is_some());
+
+            update(&mut state, Action::RetryMessage { conversation_idx: 0, message_id: "m1".to_string() });
+
+            let messages = &state.conversations[0].messages;
+            assert_eq!(messages.len(), 2);
+            assert_eq!(messages[1].content, "Message m1");
+            assert_eq!(messages[1].status, MessageStatus::Pending);
+        }
+
+        #[test]
+        fn test_retry_ignores_fatal_failures() {
+            let mut state = fixed_state();
+            update(&mut state, Action::InputChanged("hi".to_string()));
+            update(&mut state, Action::SendMessage);
+            update(&mut state, Action::MessageSendFailed {
+                conversation_idx: 0,
+                error: ApiError::Decode("missing field `id`".to_string()).into(),
+            });
+            update(&mut state, Action::RetryMessage { conversation_idx: 0, message_id: "temp-0".to_string() });
+
+            let messages = &state.conversations[0].messages;
+            assert_eq!(messages.len(), 1);
+            assert_eq!(messages[0].status, MessageStatus::Error);
+        }
+
+        #[test]
+        fn test_retry_ignores_healthy_messages() {
+            let mut state = fixed_state();
+            state.conversations[0].messages.push(message("m1", MessageStatus::Completed));
+            update(&mut state, Action::RetryMessage { conversation_idx: 0, message_id: "m1".to_string() });
+
+            assert_eq!(state.conversations[0].messages.len(), 1);
+        }
+    }
+
+    mod conversations {
+        use super::*;
+
+        #[test]
+        fn test_ids_come_from_generator() {
+            let mut state = fixed_state();
+            update(&mut state, Action::NewConversation);
+
+            assert_eq!(state.conversations[0].id, "conversation-0");
+            assert_eq!(state.conversations[1].id, "conversation-1");
+        }
+
+        #[test]
+        fn test_temp_ids_are_not_reused_after_delete() {
+            let mut state = fixed_state();
+            for (content, id) in [("first", "temp-0"), ("second", "temp-1")] {
+                update(&mut state, Action::InputChanged(content.to_string()));
+                update(&mut state, Action::SendMessage);
+                update(&mut state, Action::MessageCompleted {
+                    conversation_idx: 0,
+                    id: id.to_string(),
+                    response: Some("ok".to_string()),
+                    error: None,
+                    metadata: None,
+                });
+            }
+            update(&mut state, Action::DeleteMessage { conversation_idx: 0, message_id: "temp-0".to_string() });
+            update(&mut state, Action::InputChanged("third".to_string()));
+            update(&mut state, Action::SendMessage);
+
+            let ids: Vec<&str> = state.conversations[0].messages.iter().map(|m| m.id.as_str()).collect();
+            assert_eq!(ids, ["temp-1", "temp-2"]);
+        }
+
+        #[test]
+        fn test_new_conversation_becomes_active() {
+            let mut state = fixed_state();
+            update(&mut state, Action::NewConversation);
+
+            assert_eq!(state.conversations.len(), 2);
+            assert_eq!(state.active_conversation, Some(1));
+        }
+
+        #[test]
+        fn test_select_out_of_range_is_ignored() {
+            let mut state = fixed_state();
+            update(&mut state, Action::SelectConversation(5));
+
+            assert_eq!(state.active_conversation, Some(0));
+        }
+
+        #[test]
+        fn test_archive_active_moves_to_nearest_visible() {
+            let mut state = fixed_state();
+            update(&mut state, Action::NewConversation);
+            update(&mut state, Action::NewConversation);
+            update(&mut state, Action::SelectConversation(1));
+            update(&mut state, Action::ArchiveConversation(1));
+
+            assert!(state.conversations[1].archived);
+            assert_eq!(state.active_conversation, Some(2));
+        }
+
+        #[test]
+        fn test_archive_last_visible_leaves_nothing_active() {
+            let mut state = fixed_state();
+            update(&mut state, Action::ArchiveConversation(0));
+
+            assert_eq!(state.active_conversation, None);
+        }
+
+        #[test]
+        fn test_delete_conversation_shifts_indices() {
+            let mut state = fixed_state();
+            update(&mut state, Action::NewConversation);
+            update(&mut state, Action::NewConversation);
+            let removed = state.conversations[0].id.clone();
+            update(&mut state, Action::DeleteConversation(0));
+
+            assert_eq!(state.conversations.len(), 2);
+            assert_eq!(state.active_conversation, Some(1));
+            assert!(state.deleted_conversations.contains(&removed));
+        }
+
+        #[test]
+        fn test_delete_conversation_moves_edit_index() {
+            let mut state = fixed_state();
+            update(&mut state, Action::NewConversation);
+            state.conversations[1].messages.push(message("m1", MessageStatus::Completed));
+            update(&mut state, Action::EditMessage { conversation_idx: 1, message_id: "m1".to_string() });
+            update(&mut state, Action::DeleteConversation(0));
+
+            assert_eq!(state.editing.as_ref().map(|e| e.conversation_idx), Some(0));
+        }
+
+        #[test]
+        fn test_sidebar_order_skips_other_servers_and_closed_archives() {
+            let mut state = fixed_state();
+            update(&mut state, Action::NewConversation);
+            update(&mut state, Action::NewConversation);
+            update(&mut state, Action::NewConversation);
+            // Ahead of the ones listed: one on another server, one archived
+            state.conversations[0].server = "remote".to_string();
+            update(&mut state, Action::ArchiveConversation(1));
+
+            assert_eq!(sidebar_order(&state), vec![2, 3]);
+
+            update(&mut state, Action::ToggleArchivedSection);
+            assert_eq!(sidebar_order(&state), vec![2, 3, 1]);
+        }
+
+        #[test]
+        fn test_request_delete_of_archived_reveals_section() {
+            let mut state = fixed_state();
+            update(&mut state, Action::NewConversation);
+            update(&mut state, Action::ArchiveConversation(0));
+            update(&mut state, Action::RequestDeleteConversation(0));
+
+            assert!(state.show_archived);
+            assert_eq!(state.confirm_delete_conversation.as_ref(), Some(&state.conversations[0].id));
+        }
+    }
+
+    mod permissions {
+        use super::*;
+
+        #[test]
+        fn test_waiting_for_permission() {
+            let mut state = fixed_state();
+            state.conversations[0].messages.push(message("m1", MessageStatus::Processing));
+            update(&mut state, Action::MessageWaitingForPermission {
+                conversation_idx: 0,
+                id: "m1".to_string(),
+                permission_request: permission_request("Bash"),
+            });
+
+            let msg = &state.conversations[0].messages[0];
+            assert_eq!(msg.status, MessageStatus::WaitingForPermission);
+            assert_eq!(msg.pending_tool_permission.as_ref().map(|p| p.tool_name.as_str()), Some("Bash"));
+        }
+
+        #[test]
+        fn test_completion_clears_pending_permission() {
+            let mut state = fixed_state();
+            let mut waiting = message("m1", MessageStatus::WaitingForPermission);
+            waiting.pending_tool_permission = Some(permission_request("Bash"));
+            state.conversations[0].messages.push(waiting);
+            update(&mut state, Action::MessageCompleted {
+                conversation_idx: 0,
+                id: "m1".to_string(),
+                response: Some("done".to_string()),
+                error: None,
+                metadata: None,
+            });
+
+            assert!(state.conversations[0].messages[0].pending_tool_permission.is_none());
+        }
+
+        #[test]
+        fn test_sync_pending_keeps_first_seen_time() {
+            let mut state = fixed_state();
+            let first_seen = state.clock.utc();
+            update(&mut state, Action::SyncPendingPermissions(HashSet::from(["a".to_string()])));
+            advance(&mut state, Duration::from_secs(30));
+            update(&mut state, Action::SyncPendingPermissions(HashSet::from(["a".to_string(), "b".to_string()])));
+
+            assert_eq!(state.editor.permission_requested["a"], first_seen);
+            assert_eq!(state.editor.permission_requested["b"], first_seen + chrono::Duration::seconds(30));
+        }
+
+        #[test]
+        fn test_sync_pending_forgets_answered_requests() {
+            let mut state = fixed_state();
+            update(&mut state, Action::SyncPendingPermissions(HashSet::from(["a".to_string()])));
+            update(&mut state, Action::Editor(EditorAction::PermissionAutoAnswered { key: "a".to_string() }));
+            update(&mut state, Action::SyncPendingPermissions(HashSet::new()));
+
+            assert!(state.editor.permission_requested.is_empty());
+            assert!(state.editor.auto_answered.is_empty());
+        }
+
+        #[test]
+        fn test_respond_fills_composer_only_when_waiting() {
+            let mut state = fixed_state();
+            update(&mut state, Action::RespondToPermission { approved: true });
+            assert!(state.editor.draft.content.is_empty());
+
+            state.conversations[0].messages.push(message("m1", MessageStatus::WaitingForPermission));
+            update(&mut state, Action::RespondToPermission { approved: false });
+            assert_eq!(state.editor.draft.content, "No, don't use that tool");
+        }
+
+        #[test]
+        fn test_interrupt_cancels_in_flight_messages() {
+            let mut state = fixed_state();
+            let id = state.conversations[0].id.clone();
+            let mut waiting = message("m2", MessageStatus::WaitingForPermission);
+            waiting.pending_tool_permission = Some(permission_request("Edit"));
+            state.conversations[0].messages.extend([message("m1", MessageStatus::Completed), waiting]);
+            update(&mut state, Action::InterruptRequested(id.clone()));
+            update(&mut state, Action::InteractionInterrupted(id.clone()));
+
+            let messages = &state.conversations[0].messages;
+            assert_eq!(messages[0].status, MessageStatus::Completed);
+            assert_eq!(messages[1].status, MessageStatus::Cancelled);
+            assert!(messages[1].pending_tool_permission.is_none());
+            assert!(!state.interrupting.contains(&id));
+        }
+
+        #[test]
+        fn test_permission_delivery_counts_attempts_until_delivered() {
+            let mut state = fixed_state();
+            let id = state.conversations[0].id.clone();
+            let server = state.conversations[0].server.clone();
+            update(&mut state, Action::Editor(EditorAction::PermissionResponseQueued { interaction_id: id.clone(), server, approved: true }));
+            update(&mut state, Action::Editor(EditorAction::PermissionResponseRetrying { interaction_id: id.clone() }));
+
+            assert_eq!(state.editor.permission_deliveries[&id].attempts, 2);
+
+            update(&mut state, Action::Editor(EditorAction::PermissionResponseDelivered { interaction_id: id }));
+            assert!(state.editor.permission_deliveries.is_empty());
+        }
+
+        #[test]
+        fn test_failed_delivery_kept_while_request_waits() {
+            let mut state = fixed_state();
+            let id = state.conversations[0].id.clone();
+            let server = state.conversations[0].server.clone();
+            state.conversations[0].messages.push(message("m1", MessageStatus::WaitingForPermission));
+            update(&mut state, Action::Editor(EditorAction::PermissionResponseQueued { interaction_id: id.clone(), server, approved: false }));
+            update(&mut state, Action::Editor(EditorAction::PermissionResponseFailed {
+                interaction_id: id.clone(),
+                error: MessageError::from(ApiError::Timeout),
+            }));
+            update(&mut state, Action::SyncPendingPermissions(HashSet::new()));
+
+            assert!(state.editor.permission_deliveries[&id].failed.is_some());
+
+            // The server moved on, there's nothing left to retry
+            state.conversations[0].messages[0].status = MessageStatus::Completed;
+            update(&mut state, Action::SyncPendingPermissions(HashSet::new()));
+            assert!(state.editor.permission_deliveries.is_empty());
+        }
+
+        #[test]
+        fn test_in_flight_delivery_survives_sync() {
+            let mut state = fixed_state();
+            let id = state.conversations[0].id.clone();
+            let server = state.conversations[0].server.clone();
+            update(&mut state, Action::Editor(EditorAction::PermissionResponseQueued { interaction_id: id.clone(), server, approved: true }));
+            update(&mut state, Action::SyncPendingPermissions(HashSet::new()));
+
+            assert!(state.editor.permission_deliveries.contains_key(&id));
+        }
+
+        #[test]
+        fn test_remove_rule_out_of_range_is_ignored() {
+            let mut state = fixed_state();
+            state.permission_policy.add(Decision::Allow, Rule::tool("Read"));
+            update(&mut state, Action::RemovePermissionRule(Decision::Allow, 3));
+            assert_eq!(state.permission_policy.len(), 1);
+
+            update(&mut state, Action::RemovePermissionRule(Decision::Allow, 0));
+            assert!(state.permission_policy.is_empty());
+        }
+    }
+
+    mod jobs {
+        use super::*;
+
+        #[test]
+        fn test_sync_jobs_tracks_running_conversations() {
+            let mut state = fixed_state();
+            let id = state.conversations[0].id.clone();
+            state.conversations[0].messages.push(message("m1", MessageStatus::Processing));
+            update(&mut state, Action::SyncJobs);
+            let started = state.clock.instant();
+
+            advance(&mut state, Duration::from_secs(10));
+            update(&mut state, Action::SyncJobs);
+            assert_eq!(state.job_started[&id], started);
+            assert_eq!(state.last_progress[&id], started);
+
+            update(&mut state, Action::InteractionProgressed(id.clone()));
+            assert_eq!(state.last_progress[&id], started + Duration::from_secs(10));
+        }
+
+        #[test]
+        fn test_sync_jobs_forgets_finished_conversations() {
+            let mut state = fixed_state();
+            let id = state.conversations[0].id.clone();
+            state.conversations[0].messages.push(message("m1", MessageStatus::Processing));
+            update(&mut state, Action::SyncJobs);
+            update(&mut state, Action::InteractionStalled(id.clone()));
+            update(&mut state, Action::AutoInterrupted(id.clone()));
+
+            state.conversations[0].messages[0].status = MessageStatus::Completed;
+            update(&mut state, Action::SyncJobs);
+
+            assert!(state.job_started.is_empty());
+            assert!(state.last_progress.is_empty());
+            assert!(state.stalled.is_empty());
+            assert!(state.auto_interrupted.is_empty());
+        }
+        #[test]
+        fn test_running_jobs_measure_on_state_clock() {
+            let mut state = fixed_state();
+            state.conversations[0].messages.push(message("m1", MessageStatus::Processing));
+            update(&mut state, Action::SyncJobs);
+            advance(&mut state, Duration::from_secs(90));
+            let id = state.conversations[0].id.clone();
+            update(&mut state, Action::InteractionProgressed(id));
+            advance(&mut state, Duration::from_secs(15));
+
+            let jobs = crate::jobs::running_jobs(&state, state.clock.instant());
+            assert_eq!(jobs[0].elapsed, Some(Duration::from_secs(105)));
+            assert_eq!(jobs[0].quiet_for, Some(Duration::from_secs(15)));
+        }
+    }
+
+    mod worktree_dialog {
+        use super::*;
+
+        #[test]
+        fn test_open_dialog_defaults_to_main() {
+            let mut state = fixed_state();
+            update(&mut state, Action::OpenWorktreeDialog);
+
+            let dialog = state.worktree_dialog.as_ref().unwrap();
+            assert_eq!(dialog.base_branch, "main");
+            assert!(dialog.branch_name.is_empty());
+        }
+
+        #[test]
+        fn test_typing_clears_error() {
+            let mut state = fixed_state();
+            update(&mut state, Action::OpenWorktreeDialog);
+            update(&mut state, Action::WorktreeCreationFailed("branch exists".to_string()));
+            assert_eq!(state.worktree_dialog.as_ref().unwrap().error.as_deref(), Some("branch exists"));
+
+            update(&mut state, Action::UpdateWorktreeBranchName("feature/x".to_string()));
+            let dialog = state.worktree_dialog.as_ref().unwrap();
+            assert_eq!(dialog.branch_name, "feature/x");
+            assert!(dialog.error.is_none());
+        }
+
+        #[test]
+        fn test_updates_without_dialog_are_ignored() {
+            let mut state = fixed_state();
+            update(&mut state, Action::UpdateWorktreeBranchName("feature/x".to_string()));
+            update(&mut state, Action::WorktreeCreationFailed("nope".to_string()));
+
+            assert!(state.worktree_dialog.is_none());
+        }
+
+        #[test]
+        fn test_created_binds_conversation_and_notifies() {
+            let mut state = fixed_state();
+            update(&mut state, Action::OpenWorktreeDialog);
+            update(&mut state, Action::WorktreeCreated(worktree("wt-1")));
+
+            assert!(state.worktree_dialog.is_none());
+            assert_eq!(state.conversations[0].worktree_id.as_deref(), Some("wt-1"));
+            assert_eq!(state.editor.current_worktree.as_ref().map(|w| w.id.as_str()), Some("wt-1"));
+            assert!(matches!(
+                &state.editor.notification,
+                Some((Notification::Success(text), _)) if text.contains("feature/wt-1")
+            ));
+        }
+
+        #[test]
+        fn test_deleted_worktree_falls_back_to_main_checkout() {
+            let mut state = fixed_state();
+            update(&mut state, Action::WorktreeCreated(worktree("wt-1")));
+            update(&mut state, Action::WorktreeDeleted("wt-1".to_string()));
+
+            assert!(state.conversations[0].worktree_id.is_none());
+            assert!(state.editor.current_worktree.is_none());
+            assert!(state.editor.available_worktrees.is_empty());
+        }
+
+        #[test]
+        fn test_loading_worktrees_repairs_dangling_refs() {
+            let mut state = fixed_state();
+            update(&mut state, Action::WorktreeCreated(worktree("wt-1")));
+            update(&mut state, Action::WorktreesLoaded(vec![worktree("wt-2")]));
+
+            assert!(state.conversations[0].worktree_id.is_none());
+            assert!(state.editor.current_worktree.is_none());
+            assert!(matches!(
+                &state.editor.notification,
+                Some((Notification::Info(text), _)) if text.starts_with("1 conversation lost its worktree")
+            ));
+        }
+
+        #[test]
+        fn test_loading_worktrees_keeps_known_refs() {
+            let mut state = fixed_state();
+            update(&mut state, Action::WorktreeCreated(worktree("wt-1")));
+            update(&mut state, Action::Editor(EditorAction::ClearNotification));
+            update(&mut state, Action::WorktreesLoaded(vec![worktree("wt-1")]));
+
+            assert_eq!(state.conversations[0].worktree_id.as_deref(), Some("wt-1"));
+            assert!(state.editor.notification.is_none());
+        }
+
+        #[test]
+        fn test_current_worktree_follows_active_conversation() {
+            let mut state = fixed_state();
+            update(&mut state, Action::WorktreeCreated(worktree("wt-1")));
+            update(&mut state, Action::NewConversation);
+            assert_eq!(state.editor.current_worktree.as_ref().map(|w| w.id.as_str()), Some("wt-1"));
+
+            update(&mut state, Action::WorktreeChanged(None));
+            assert!(state.editor.current_worktree.is_none());
+            update(&mut state, Action::SelectConversation(0));
+            assert_eq!(state.editor.current_worktree.as_ref().map(|w| w.id.as_str()), Some("wt-1"));
+        }
+    }
+
+    mod review_reply {
+        use super::*;
+
+        fn reviewing(worktree_id: &str) -> AppState {
+            let mut state = fixed_state();
+            update(&mut state, Action::OpenDiff { worktree_id: worktree_id.to_string(), title: "Diff".to_string() });
+            state
+        }
+
+        #[test]
+        fn test_reply_goes_to_active_conversation_in_worktree() {
+            let mut state = reviewing("wt-1");
+            assert_eq!(review_target(&state), None);
+
+            update(&mut state, Action::WorktreeCreated(worktree("wt-1")));
+            update(&mut state, Action::NewConversation);
+            assert_eq!(review_target(&state), Some(1));
+
+            // Another conversation elsewhere falls back to the newest one in the worktree
+            state.conversations[1].worktree_id = None;
+            assert_eq!(review_target(&state), Some(0));
+        }
+
+        #[test]
+        fn test_sent_reply_is_cleared() {
+            let mut state = reviewing("wt-1");
+            update(&mut state, Action::ReviewReplyChanged("Looks good, ship it".to_string()));
+            update(&mut state, Action::ReviewReplySending);
+            assert!(state.diff_view.as_ref().unwrap().reply_sending);
+
+            update(&mut state, Action::ReviewReplySent);
+            let view = state.diff_view.as_ref().unwrap();
+            assert!(view.reply.is_empty());
+            assert!(!view.reply_sending);
+        }
+
+        #[test]
+        fn test_failed_reply_is_kept_to_retry() {
+            let mut state = reviewing("wt-1");
+            update(&mut state, Action::ReviewReplyChanged("Needs tests".to_string()));
+            update(&mut state, Action::ReviewReplySending);
+            update(&mut state, Action::ReviewReplyFailed("Interaction not found".to_string()));
+
+            let view = state.diff_view.as_ref().unwrap();
+            assert_eq!(view.reply, "Needs tests");
+            assert_eq!(view.reply_error.as_deref(), Some("Interaction not found"));
+            assert!(!view.reply_sending);
+        }
+
+        #[test]
+        fn test_refresh_keeps_reply() {
+            let mut state = reviewing("wt-1");
+            update(&mut state, Action::ReviewReplyChanged("Half written".to_string()));
+            update(&mut state, Action::OpenDiff { worktree_id: "wt-1".to_string(), title: "Diff".to_string() });
+            assert_eq!(state.diff_view.as_ref().unwrap().reply, "Half written");
+
+            update(&mut state, Action::OpenDiff { worktree_id: "wt-2".to_string(), title: "Diff".to_string() });
+            assert!(state.diff_view.as_ref().unwrap().reply.is_empty());
+        }
+    }
+
+    mod auth_prompt {
+        use super::*;
+
+        #[test]
+        fn test_repeated_401s_keep_typed_key() {
+            let mut state = fixed_state();
+            update(&mut state, Action::RequestCredentials);
+            update(&mut state, Action::ApiKeyChanged("sk-partial".to_string()));
+            update(&mut state, Action::RequestCredentials);
+
+            assert_eq!(state.auth_prompt.as_ref().map(|p| p.api_key.as_str()), Some("sk-partial"));
+
+            update(&mut state, Action::CloseAuthPrompt);
+            assert!(state.auth_prompt.is_none());
+        }
+
+        #[test]
+        fn test_typing_without_prompt_is_ignored() {
+            let mut state = fixed_state();
+            update(&mut state, Action::ApiKeyChanged("sk-test".to_string()));
+
+            assert!(state.auth_prompt.is_none());
+        }
+    }
+
+    mod servers {
+        use super::*;
+
+        fn two_servers() -> AppState {
+            let mut state = fixed_state();
+            update(&mut state, Action::ServersConfigured(vec!["laptop".to_string(), "remote".to_string()]));
+            state
+        }
+
+        #[test]
+        fn test_configured_primary_takes_over_default() {
+            let state = two_servers();
+
+            assert_eq!(state.active_server, "laptop");
+            assert_eq!(state.conversations[0].server, "laptop");
+        }
+
+        #[test]
+        fn test_switch_starts_fresh_on_new_server() {
+            let mut state = two_servers();
+            update(&mut state, Action::WorktreeCreated(worktree("wt-1")));
+            update(&mut state, Action::SwitchServer("remote".to_string()));
+
+            let active = state.active_conversation.unwrap();
+            assert_eq!(state.conversations.len(), 2);
+            assert_eq!(state.conversations[active].server, "remote");
+            assert!(state.conversations[active].worktree_id.is_none());
+            assert!(state.editor.available_worktrees.is_empty());
+            assert!(state.editor.current_worktree.is_none());
+        }
+
+        #[test]
+        fn test_switch_back_returns_to_existing_conversation() {
+            let mut state = two_servers();
+            update(&mut state, Action::SwitchServer("remote".to_string()));
+            update(&mut state, Action::SwitchServer("laptop".to_string()));
+
+            assert_eq!(state.active_conversation, Some(0));
+            assert_eq!(state.conversations.len(), 2);
+        }
+
+        #[test]
+        fn test_unknown_server_is_ignored() {
+            let mut state = two_servers();
+            update(&mut state, Action::SwitchServer("elsewhere".to_string()));
+
+            assert_eq!(state.active_server, "laptop");
+            assert_eq!(state.active_conversation, Some(0));
+        }
+
+        #[test]
+        fn test_selecting_other_servers_conversation_follows_it() {
+            let mut state = two_servers();
+            update(&mut state, Action::SwitchServer("remote".to_string()));
+            update(&mut state, Action::SelectConversation(0));
+
+            assert_eq!(state.active_server, "laptop");
+            assert_eq!(state.active_conversation, Some(0));
+        }
+
+        #[test]
+        fn test_archive_stays_on_active_server() {
+            let mut state = two_servers();
+            update(&mut state, Action::SwitchServer("remote".to_string()));
+            update(&mut state, Action::NewConversation);
+            update(&mut state, Action::ArchiveConversation(2));
+
+            // Conversation 0 is visible too, but on the other server
+            assert_eq!(state.active_conversation, Some(1));
+        }
+    }
+
+    mod sessions {
+        use super::*;
+        use bicky_editor_core::types::{InteractionQueueStatus, Session, SessionStatus};
+        use bicky_editor_core::Effect;
+
+        fn session(id: &str) -> Session {
+            Session {
+                id: id.to_string(),
+                name: None,
+                created_at: Utc::now(),
+                status: SessionStatus::Active,
+                metadata: None,
+            }
+        }
+
+        fn counts(processing: u32) -> InteractionQueueStatus {
+            InteractionQueueStatus {
+                queue_size: processing,
+                needs_work: 0,
+                needs_review: 0,
+                processing,
+                completed: 0,
+                analyzing: 0,
+            }
+        }
+
+        fn sessions_loaded(state: &mut AppState, sessions: Vec<Session>) {
+            update(state, Action::Editor(EditorAction::SessionsLoaded(Ok(sessions))));
+        }
+
+        #[test]
+        fn test_switch_to_known_session() {
+            let mut state = fixed_state();
+            sessions_loaded(&mut state, vec![session("s1"), session("s2")]);
+            update(&mut state, Action::SwitchSession(Some("s2".to_string())));
+            assert_eq!(active_session(&state), Some("s2"));
+
+            update(&mut state, Action::SwitchSession(Some("elsewhere".to_string())));
+            assert_eq!(active_session(&state), Some("s2"));
+
+            update(&mut state, Action::SwitchSession(None));
+            assert_eq!(active_session(&state), None);
+        }
+
+        #[test]
+        fn test_removed_session_falls_back_to_default_stream() {
+            let mut state = fixed_state();
+            sessions_loaded(&mut state, vec![session("s1"), session("s2")]);
+            update(&mut state, Action::SwitchSession(Some("s2".to_string())));
+            update(&mut state, Action::Editor(EditorAction::SessionQueueLoaded { session_id: "s2".to_string(), status: counts(1) }));
+
+            sessions_loaded(&mut state, vec![session("s1")]);
+
+            assert_eq!(active_session(&state), None);
+            assert!(state.editor.session_queues.is_empty());
+        }
+
+        #[test]
+        fn test_counts_for_unknown_session_are_dropped() {
+            let mut state = fixed_state();
+            update(&mut state, Action::Editor(EditorAction::SessionQueueLoaded { session_id: "s1".to_string(), status: counts(2) }));
+
+            assert!(state.editor.session_queues.is_empty());
+        }
+
+        #[test]
+        fn test_switching_server_forgets_sessions() {
+            let mut state = fixed_state();
+            update(&mut state, Action::ServersConfigured(vec!["laptop".to_string(), "remote".to_string()]));
+            sessions_loaded(&mut state, vec![session("s1")]);
+            update(&mut state, Action::SwitchSession(Some("s1".to_string())));
+
+            update(&mut state, Action::SwitchServer("remote".to_string()));
+
+            assert!(state.editor.sessions.is_empty());
+            assert_eq!(active_session(&state), None);
+        }
+
+        #[test]
+        fn test_switching_session_asks_for_its_stream() {
+            let mut state = fixed_state();
+            update(&mut state, Action::Editor(EditorAction::Connect { server_url: "http://localhost:3456".to_string() }));
+            sessions_loaded(&mut state, vec![session("s1")]);
+            state.effects.clear();
+
+            update(&mut state, Action::SwitchSession(Some("s1".to_string())));
+
+            assert_eq!(state.effects, vec![Effect::ConnectToStream {
+                server_url: "http://localhost:3456".to_string(),
+                session_id: "s1".to_string(),
+            }]);
+        }
+    }
+
+    mod agents {
+        use super::*;
+        use crate::agent_health;
+        use crate::agents::{self, ConfigField};
+
+        fn agent(id: &str) -> Agent {
+            Agent { id: id.to_string(), name: format!("Agent {}", id), description: None, config: None }
+        }
+
+        fn with_agents() -> AppState {
+            let mut state = fixed_state();
+            update(&mut state, Action::AgentsLoaded(vec![agent("reviewer"), agent("tester")]));
+            state
+        }
+
+        #[test]
+        fn test_selector_only_opens_when_the_server_has_agents() {
+            let mut state = fixed_state();
+            update(&mut state, Action::OpenAgentSelector(0));
+            assert!(state.agent_selector.is_none());
+
+            let mut state = with_agents();
+            update(&mut state, Action::OpenAgentSelector(0));
+            assert_eq!(state.agent_selector, Some(AgentSelectorState { conversation_idx: 0, pending: None, error: None }));
+        }
+
+        #[test]
+        fn test_attached_agents_come_from_the_server_answer() {
+            let mut state = with_agents();
+            update(&mut state, Action::OpenAgentSelector(0));
+            update(&mut state, Action::AgentChangeRequested("tester".to_string()));
+            assert_eq!(state.agent_selector.as_ref().unwrap().pending.as_deref(), Some("tester"));
+
+            let conv = &state.conversations[0];
+            let (server, conversation_id) = (conv.server.clone(), conv.id.clone());
+            update(&mut state, Action::AgentsAttached { server, conversation_id, agents: vec!["reviewer".to_string(), "tester".to_string()] });
+
+            assert_eq!(state.conversations[0].agents, vec!["reviewer", "tester"]);
+            assert!(state.agent_selector.as_ref().unwrap().pending.is_none());
+        }
+
+        #[test]
+        fn test_failed_change_is_shown_in_the_selector() {
+            let mut state = with_agents();
+            update(&mut state, Action::OpenAgentSelector(0));
+            update(&mut state, Action::AgentChangeRequested("tester".to_string()));
+
+            update(&mut state, Action::AgentChangeFailed("agent is busy".to_string()));
+
+            let selector = state.agent_selector.as_ref().unwrap();
+            assert!(selector.pending.is_none());
+            assert_eq!(selector.error.as_deref(), Some("agent is busy"));
+            assert!(state.conversations[0].agents.is_empty());
+        }
+
+        #[test]
+        fn test_switching_server_forgets_agents() {
+            let mut state = with_agents();
+            update(&mut state, Action::ServersConfigured(vec!["laptop".to_string(), "remote".to_string()]));
+            update(&mut state, Action::OpenAgentSelector(0));
+
+            update(&mut state, Action::SwitchServer("remote".to_string()));
+
+            assert!(state.agents.is_empty());
+            assert!(state.agent_selector.is_none());
+        }
+
+        #[test]
+        fn test_config_form_starts_from_the_agents_config() {
+            let mut state = with_agents();
+            state.agents[0].config = Some(AgentConfig {
+                system_prompt: Some("Review carefully".to_string()),
+                temperature: Some(0.5),
+                max_tokens: None,
+                allowed_tools: Some(vec!["read".to_string(), "grep".to_string()]),
+            });
+
+            update(&mut state, Action::OpenAgentConfig("reviewer".to_string()));
+
+            let draft = state.agent_config.as_ref().unwrap();
+            assert_eq!(draft.system_prompt, "Review carefully");
+            assert_eq!(draft.temperature, "0.5");
+            assert_eq!(draft.max_tokens, "");
+            assert_eq!(draft.allowed_tools, "read, grep");
+        }
+
+        #[test]
+        fn test_config_form_checks_what_was_typed() {
+            let mut state = with_agents();
+            update(&mut state, Action::OpenAgentConfig("tester".to_string()));
+            update(&mut state, Action::UpdateAgentConfig(ConfigField::Temperature, "hot".to_string()));
+            assert!(state.agent_config.as_ref().unwrap().build().is_err());
+
+            update(&mut state, Action::UpdateAgentConfig(ConfigField::Temperature, " 0.2 ".to_string()));
+            update(&mut state, Action::UpdateAgentConfig(ConfigField::MaxTokens, "2048".to_string()));
+            update(&mut state, Action::UpdateAgentConfig(ConfigField::AllowedTools, "bash, , edit".to_string()));
+
+            let config = state.agent_config.as_ref().unwrap().build().unwrap();
+            assert_eq!(config, AgentConfig {
+                system_prompt: None,
+                temperature: Some(0.2),
+                max_tokens: Some(2048),
+                allowed_tools: Some(vec!["bash".to_string(), "edit".to_string()]),
+            });
+        }
+
+        #[test]
+        fn test_saved_config_replaces_the_agents_and_closes_the_form() {
+            let mut state = with_agents();
+            update(&mut state, Action::OpenAgentConfig("tester".to_string()));
+            update(&mut state, Action::AgentConfigRequested);
+            assert!(state.agent_config.as_ref().unwrap().saving);
+
+            let config = AgentConfig { max_tokens: Some(1000), ..Default::default() };
+            let server = state.active_server.clone();
+            update(&mut state, Action::AgentConfigSaved { server, agent_id: "tester".to_string(), config: config.clone() });
+
+            assert!(state.agent_config.is_none());
+            assert_eq!(state.agents[1].config, Some(config));
+        }
+
+        #[test]
+        fn test_failed_save_keeps_the_form_open() {
+            let mut state = with_agents();
+            update(&mut state, Action::OpenAgentConfig("tester".to_string()));
+            update(&mut state, Action::AgentConfigRequested);
+
+            update(&mut state, Action::AgentConfigFailed("unknown tool".to_string()));
+
+            let draft = state.agent_config.as_ref().unwrap();
+            assert!(!draft.saving);
+            assert_eq!(draft.error.as_deref(), Some("unknown tool"));
+            assert!(state.agents[1].config.is_none());
+        }
+
+        fn status(agent_id: &str, health: AgentHealth) -> AgentStatus {
+            AgentStatus {
+                agent_id: agent_id.to_string(),
+                status: health,
+                // 90 minutes before the fixed clock
+                started_at: Some("2024-01-01T10:30:00Z".parse().unwrap()),
+                current_interaction: None,
+                current_task: None,
+                error: (health == AgentHealth::Crashed).then(|| "out of memory".to_string()),
+            }
+        }
+
+        #[test]
+        fn test_down_agents_explain_a_stuck_conversation() {
+            let mut state = with_agents();
+            state.conversations[0].agents = vec!["reviewer".to_string(), "tester".to_string()];
+            update(&mut state, Action::AgentStatusesLoaded(vec![
+                status("reviewer", AgentHealth::Busy),
+                status("tester", AgentHealth::Crashed),
+            ]));
+
+            let down = agent_health::down_agents(&state, &state.conversations[0]);
+            assert_eq!(down.len(), 1);
+            assert_eq!(agent_health::down_label(&state, down[0]), "Agent tester crashed: out of memory");
+
+            let now = state.clock.utc();
+            assert_eq!(agent_health::uptime(&state.agent_statuses["reviewer"], now), Some(Duration::from_secs(90 * 60)));
+            assert_eq!(agent_health::uptime(&state.agent_statuses["tester"], now), None);
+        }
+
+        #[test]
+        fn test_restart_shows_restarting_until_the_server_answers() {
+            let mut state = with_agents();
+            update(&mut state, Action::AgentStatusesLoaded(vec![status("tester", AgentHealth::Crashed)]));
+
+            update(&mut state, Action::AgentRestartRequested("tester".to_string()));
+            assert_eq!(state.agent_statuses["tester"].status, AgentHealth::Restarting);
+            assert!(state.agent_statuses["tester"].error.is_none());
+
+            update(&mut state, Action::AgentStatusChanged(status("tester", AgentHealth::Idle)));
+            assert_eq!(state.agent_statuses["tester"].status, AgentHealth::Idle);
+        }
+
+        #[test]
+        fn test_switching_server_forgets_agent_statuses() {
+            let mut state = with_agents();
+            update(&mut state, Action::ServersConfigured(vec!["laptop".to_string(), "remote".to_string()]));
+            update(&mut state, Action::AgentStatusRequested);
+            update(&mut state, Action::AgentStatusesLoaded(vec![status("tester", AgentHealth::Busy)]));
+
+            update(&mut state, Action::SwitchServer("remote".to_string()));
+
+            assert!(state.agent_statuses.is_empty());
+            assert!(state.agent_status_checked.is_none());
+        }
+        fn attach(state: &mut AppState, agents: &[&str]) {
+            let conv = &state.conversations[0];
+            let (server, conversation_id) = (conv.server.clone(), conv.id.clone());
+            let agents = agents.iter().map(|id| id.to_string()).collect();
+            update(state, Action::AgentsAttached { server, conversation_id, agents });
+        }
+
+        #[test]
+        fn test_route_only_picks_attached_agents() {
+            let mut state = with_agents();
+            attach(&mut state, &["reviewer", "tester"]);
+
+            update(&mut state, Action::RouteTo { conversation_idx: 0, agent_id: Some("tester".to_string()) });
+            assert_eq!(state.conversations[0].route.as_deref(), Some("tester"));
+
+            update(&mut state, Action::RouteTo { conversation_idx: 0, agent_id: Some("planner".to_string()) });
+            assert!(state.conversations[0].route.is_none());
+        }
+
+        #[test]
+        fn test_detaching_the_routed_agent_goes_back_to_auto() {
+            let mut state = with_agents();
+            attach(&mut state, &["reviewer", "tester"]);
+            update(&mut state, Action::RouteTo { conversation_idx: 0, agent_id: Some("tester".to_string()) });
+
+            attach(&mut state, &["reviewer", "tester"]);
+            assert_eq!(state.conversations[0].route.as_deref(), Some("tester"));
+
+            attach(&mut state, &["reviewer"]);
+            assert!(state.conversations[0].route.is_none());
+        }
+
+        #[test]
+        fn test_messages_carry_the_route() {
+            let mut state = with_agents();
+            assert_eq!(agents::with_route(&state.conversations[0], None), None);
+
+            attach(&mut state, &["reviewer", "tester"]);
+            let metadata = agents::with_route(&state.conversations[0], Some(serde_json::json!({ "continuation": true })));
+            assert_eq!(metadata, Some(serde_json::json!({ "continuation": true, "routing": "auto" })));
+
+            update(&mut state, Action::RouteTo { conversation_idx: 0, agent_id: Some("reviewer".to_string()) });
+            let metadata = agents::with_route(&state.conversations[0], None);
+            assert_eq!(metadata, Some(serde_json::json!({ "agentId": "reviewer" })));
+        }
+
+        #[test]
+        fn test_pipeline_names_each_reviewer() {
+            let mut state = with_agents();
+            assert_eq!(agents::pipeline(&state, &state.conversations[0]), None);
+
+            state.conversations[0].review_stack = vec!["reviewer".to_string(), "gone".to_string(), "user".to_string()];
+            assert_eq!(agents::pipeline(&state, &state.conversations[0]).as_deref(), Some("Agent reviewer → gone → you"));
+        }
+    }
+
+    mod conversation_stats {
+        use super::*;
+
+        fn with_tool_calls(id: &str, calls: Vec<ToolCall>) -> LegacyMessage {
+            let mut msg = message(id, MessageStatus::Completed);
+            msg.response = Some("Done, both files are updated.".to_string());
+            msg.metadata = Some(InteractionMetadata { tool_calls: Some(calls), ..Default::default() });
+            msg
+        }
+
+        fn call(name: &str, arguments: serde_json::Value) -> ToolCall {
+            ToolCall { name: name.to_string(), arguments }
+        }
+
+        #[test]
+        fn test_counts_words_and_edits_across_messages() {
+            let mut state = fixed_state();
+            let idx = state.active_conversation.unwrap();
+            state.conversations[idx].messages = vec![
+                with_tool_calls("m1", vec![
+                    call("Edit", serde_json::json!({ "file_path": "a.rs", "old_string": "x", "new_string": "y\nz" })),
+                    call("Read", serde_json::json!({ "file_path": "b.rs" })),
+                ]),
+                with_tool_calls("m2", vec![
+                    call("Write", serde_json::json!({ "file_path": "a.rs", "content": "1\n2\n" })),
+                ]),
+            ];
+
+            let stats = conversation_stats(&state.conversations[idx]);
+
+            // "Message m1" plus the five word response, twice
+            assert_eq!(stats.words, 14);
+            assert_eq!(stats.reading_minutes(), 1);
+            assert_eq!(stats.files.len(), 1);
+            assert_eq!((stats.lines_added, stats.lines_removed), (4, 1));
+        }
+
+        #[test]
+        fn test_empty_conversation_has_no_stats() {
+            let state = fixed_state();
+            let stats = conversation_stats(&state.conversations[0]);
+
+            assert_eq!(stats.words, 0);
+            assert_eq!(stats.reading_minutes(), 0);
+        }
+    }
+
+    mod history {
+        use super::*;
+
+        fn messages(ids: &[&str]) -> Vec<LegacyMessage> {
+            ids.iter().map(|id| message(id, MessageStatus::Completed)).collect()
+        }
+
+        fn ids(state: &AppState) -> Vec<String> {
+            state.conversations[0].messages.iter().map(|m| m.id.clone()).collect()
+        }
+
+        fn loaded(state: &AppState, older: &[&str], has_more: bool) -> Action {
+            let conv = &state.conversations[0];
+            Action::HistoryLoaded {
+                server: conv.server.clone(),
+                conversation_id: conv.id.clone(),
+                messages: messages(older),
+                has_more,
+            }
+        }
+
+        #[test]
+        fn test_first_page_sets_cursor_when_more_remain() {
+            let mut state = fixed_state();
+            merge_newest_page(&mut state.conversations[0], messages(&["m3", "m4"]), true);
+
+            assert_eq!(state.conversations[0].history_cursor.as_deref(), Some("m3"));
+
+            merge_newest_page(&mut state.conversations[0], messages(&["m1", "m2"]), false);
+            assert_eq!(state.conversations[0].history_cursor, None);
+        }
+
+        #[test]
+        fn test_older_pages_are_prepended_without_duplicates() {
+            let mut state = fixed_state();
+            merge_newest_page(&mut state.conversations[0], messages(&["m3", "m4"]), true);
+
+            update(&mut state, Action::HistoryRequested(0));
+            assert!(state.conversations[0].loading_history);
+
+            let action = loaded(&state, &["m1", "m2", "m3"], true);
+            update(&mut state, action);
+
+            assert_eq!(ids(&state), ["m1", "m2", "m3", "m4"]);
+            assert_eq!(state.conversations[0].history_cursor.as_deref(), Some("m1"));
+            assert!(!state.conversations[0].loading_history);
+
+            let action = loaded(&state, &["m0"], false);
+            update(&mut state, action);
+            assert_eq!(state.conversations[0].history_cursor, None);
+        }
+
+        #[test]
+        fn test_refresh_keeps_loaded_history() {
+            let mut state = fixed_state();
+            state.conversations[0].messages = messages(&["m1", "m2", "m3", "m4"]);
+            state.conversations[0].history_cursor = Some("m1".to_string());
+
+            // The newest page moved on by one message
+            merge_newest_page(&mut state.conversations[0], messages(&["m3", "m4", "m5"]), true);
+
+            assert_eq!(ids(&state), ["m1", "m2", "m3", "m4", "m5"]);
+            assert_eq!(state.conversations[0].history_cursor.as_deref(), Some("m1"));
+        }
+
+        #[test]
+        fn test_refresh_past_loaded_history_starts_over() {
+            let mut state = fixed_state();
+            state.conversations[0].messages = messages(&["m1", "m2"]);
+            state.conversations[0].history_cursor = Some("m1".to_string());
+
+            merge_newest_page(&mut state.conversations[0], messages(&["m8", "m9"]), true);
+
+            assert_eq!(ids(&state), ["m8", "m9"]);
+            assert_eq!(state.conversations[0].history_cursor.as_deref(), Some("m8"));
+        }
+
+        #[test]
+        fn test_refresh_reports_what_changed() {
+            let mut state = fixed_state();
+            state.conversations[0].messages = vec![
+                message("m1", MessageStatus::Completed),
+                message("m2", MessageStatus::Processing),
+            ];
+
+            let mut page = vec![message("m1", MessageStatus::Completed), message("m2", MessageStatus::Completed)];
+            page[1].response = Some("Done".to_string());
+            page.push(message("m3", MessageStatus::Pending));
+            let changes = merge_newest_page(&mut state.conversations[0], page, false);
+
+            assert_eq!(changes.added, ["m3"]);
+            assert_eq!(changes.updated, ["m2"]);
+            assert!(changes.removed.is_empty());
+            assert_eq!(
+                changes.status_changes,
+                [("m2".to_string(), MessageStatus::Processing, MessageStatus::Completed)]
+            );
+            assert_eq!(ids(&state), ["m1", "m2", "m3"]);
+            assert_eq!(state.conversations[0].messages[1].response.as_deref(), Some("Done"));
+        }
+
+        #[test]
+        fn test_unchanged_refresh_reports_nothing() {
+            let mut state = fixed_state();
+            state.conversations[0].messages = messages(&["m1", "m2"]);
+
+            let changes = merge_newest_page(&mut state.conversations[0], messages(&["m1", "m2"]), false);
+
+            assert!(changes.is_empty());
+            assert_eq!(changes.summary(), "0 added, 0 updated, 0 removed");
+        }
+
+        #[test]
+        fn test_messages_gone_from_the_server_are_removed() {
+            let mut state = fixed_state();
+            state.conversations[0].messages = messages(&["m1", "m2", "m3"]);
+
+            let changes = merge_newest_page(&mut state.conversations[0], messages(&["m1", "m3"]), false);
+
+            assert_eq!(changes.removed, ["m2"]);
+            assert_eq!(ids(&state), ["m1", "m3"]);
+        }
+
+        #[test]
+        fn test_nothing_to_request_without_cursor() {
+            let mut state = fixed_state();
+            update(&mut state, Action::HistoryRequested(0));
+
+            assert!(!state.conversations[0].loading_history);
+        }
+
+        #[test]
+        fn test_failed_page_can_be_retried() {
+            let mut state = fixed_state();
+            state.conversations[0].history_cursor = Some("m1".to_string());
+            update(&mut state, Action::HistoryRequested(0));
+
+            let conv = &state.conversations[0];
+            let action = Action::HistoryFailed { server: conv.server.clone(), conversation_id: conv.id.clone() };
+            update(&mut state, action);
+
+            assert!(!state.conversations[0].loading_history);
+            assert_eq!(state.conversations[0].history_cursor.as_deref(), Some("m1"));
+        }
+    }
+
+    mod titles {
+        use super::*;
+
+        fn ask(state: &mut AppState, content: &str) {
+            let mut msg = message("m1", MessageStatus::Pending);
+            msg.content = content.to_string();
+            state.conversations[0].messages.push(msg);
+        }
+
+        fn complete(state: &mut AppState) {
+            update(state, Action::MessageCompleted {
+                conversation_idx: 0,
+                id: "m1".to_string(),
+                response: Some("Done".to_string()),
+                error: None,
+                metadata: None,
+            });
+        }
+
+        #[test]
+        fn test_title_comes_from_first_line() {
+            assert_eq!(title_from_message("## Fix the login bug\n\nIt crashes").as_deref(), Some("Fix the login bug"));
+            assert_eq!(title_from_message("  \n> quoted   ask  ").as_deref(), Some("quoted ask"));
+            assert_eq!(title_from_message("\n  \n"), None);
+            assert_eq!(title_from_message("---"), None);
+        }
+
+        #[test]
+        fn test_long_titles_are_cut_at_a_word() {
+            let title = title_from_message("Refactor the websocket reconnect logic so that it backs off properly").unwrap();
+            assert_eq!(title, "Refactor the websocket reconnect logic…");
+
+            let word = "x".repeat(60);
+            assert_eq!(title_from_message(&word).unwrap().chars().count(), 41);
+        }
+
+        #[test]
+        fn test_titled_after_first_exchange() {
+            let mut state = fixed_state();
+            let generated = state.conversations[0].title.clone();
+            ask(&mut state, "Add dark mode to settings");
+            assert_eq!(state.conversations[0].title, generated);
+
+            complete(&mut state);
+            assert_eq!(state.conversations[0].title, "Add dark mode to settings");
+            assert_eq!(state.conversations[0].title_source, TitleSource::FirstMessage);
+        }
+
+        #[test]
+        fn test_generated_name_kept_without_usable_text() {
+            let mut state = fixed_state();
+            let generated = state.conversations[0].title.clone();
+            ask(&mut state, "***");
+            complete(&mut state);
+
+            assert_eq!(state.conversations[0].title, generated);
+            assert_eq!(state.conversations[0].title_source, TitleSource::Generated);
+        }
+
+        #[test]
+        fn test_manual_rename_wins() {
+            let mut state = fixed_state();
+            update(&mut state, Action::StartRename(0));
+            update(&mut state, Action::RenameChanged("  Dark mode  ".to_string()));
+            update(&mut state, Action::CommitRename);
+            assert!(state.renaming.is_none());
+            assert_eq!(state.conversations[0].title, "Dark mode");
+
+            ask(&mut state, "Add dark mode to settings");
+            complete(&mut state);
+            assert_eq!(state.conversations[0].title, "Dark mode");
+            assert_eq!(state.conversations[0].title_source, TitleSource::Manual);
+        }
+
+        #[test]
+        fn test_blank_rename_keeps_title() {
+            let mut state = fixed_state();
+            let title = state.conversations[0].title.clone();
+            update(&mut state, Action::StartRename(0));
+            update(&mut state, Action::RenameChanged("   ".to_string()));
+            update(&mut state, Action::CommitRename);
+
+            assert_eq!(state.conversations[0].title, title);
+            assert_eq!(state.conversations[0].title_source, TitleSource::Generated);
+        }
+    }
+
+    mod labels {
+        use super::*;
+        use crate::labels::ColorLabel;
+
+        #[test]
+        fn test_clicking_cycles_through_labels() {
+            assert_eq!(ColorLabel::next(None), Some(ColorLabel::Red));
+            assert_eq!(ColorLabel::next(Some(ColorLabel::Red)), Some(ColorLabel::Orange));
+            assert_eq!(ColorLabel::next(Some(ColorLabel::Purple)), None);
+        }
+
+        #[test]
+        fn test_labels_are_kept_by_conversation_id() {
+            let mut state = fixed_state();
+            let id = state.conversations[0].id.clone();
+            update(&mut state, Action::SetLabel { conversation_idx: 0, label: Some(ColorLabel::Green) });
+
+            assert_eq!(state.labels.get(&id), Some(&ColorLabel::Green));
+            assert_eq!(label_of(&state, &state.conversations[0]), Some(ColorLabel::Green));
+
+            update(&mut state, Action::SetLabel { conversation_idx: 0, label: None });
+            assert!(state.labels.is_empty());
+        }
+
+        #[test]
+        fn test_filter_shows_only_matching_conversations() {
+            let mut state = fixed_state();
+            update(&mut state, Action::NewConversation);
+            update(&mut state, Action::SetLabel { conversation_idx: 0, label: Some(ColorLabel::Blue) });
+
+            update(&mut state, Action::FilterByLabel(Some(ColorLabel::Blue)));
+            assert!(matches_label_filter(&state, &state.conversations[0]));
+            assert!(!matches_label_filter(&state, &state.conversations[1]));
+
+            update(&mut state, Action::FilterByLabel(None));
+            assert!(matches_label_filter(&state, &state.conversations[1]));
+        }
+
+        #[test]
+        fn test_deleting_drops_the_label() {
+            let mut state = fixed_state();
+            update(&mut state, Action::NewConversation);
+            update(&mut state, Action::SetLabel { conversation_idx: 0, label: Some(ColorLabel::Red) });
+            update(&mut state, Action::DeleteConversation(0));
+
+            assert!(state.labels.is_empty());
+        }
+
+        #[test]
+        fn test_labels_round_trip_through_toml() {
+            let mut state = fixed_state();
+            update(&mut state, Action::SetLabel { conversation_idx: 0, label: Some(ColorLabel::Yellow) });
+
+            let saved = toml::to_string(&state.labels).unwrap();
+            let loaded: std::collections::BTreeMap<String, ColorLabel> = toml::from_str(&saved).unwrap();
+            assert_eq!(loaded, state.labels);
+        }
+    }
+
+    mod drafts {
+        use super::*;
+
+        #[test]
+        fn test_switching_keeps_each_conversations_draft() {
+            let mut state = fixed_state();
+            update(&mut state, Action::InputChanged("first draft".to_string()));
+            update(&mut state, Action::NewConversation);
+            assert!(state.editor.draft.content.is_empty());
+
+            update(&mut state, Action::InputChanged("second draft".to_string()));
+            update(&mut state, Action::SelectConversation(0));
+            assert_eq!(state.editor.draft.content, "first draft");
+            assert!(state.input_tokens > 0);
+
+            update(&mut state, Action::SelectConversation(1));
+            assert_eq!(state.editor.draft.content, "second draft");
+        }
+
+        #[test]
+        fn test_reselecting_keeps_the_input() {
+            let mut state = fixed_state();
+            update(&mut state, Action::InputChanged("still typing".to_string()));
+            update(&mut state, Action::SelectConversation(0));
+
+            assert_eq!(state.editor.draft.content, "still typing");
+            assert!(state.editor.drafts.is_empty());
+        }
+
+        #[test]
+        fn test_blank_drafts_are_not_kept() {
+            let mut state = fixed_state();
+            update(&mut state, Action::InputChanged("  \n".to_string()));
+            update(&mut state, Action::NewConversation);
+
+            assert!(state.editor.drafts.is_empty());
+        }
+
+        #[test]
+        fn test_leaving_an_edit_gives_it_up() {
+            let mut state = fixed_state();
+            state.conversations[0].messages.push(message("m1", MessageStatus::Completed));
+            update(&mut state, Action::EditMessage { conversation_idx: 0, message_id: "m1".to_string() });
+            update(&mut state, Action::NewConversation);
+            update(&mut state, Action::SelectConversation(0));
+
+            assert!(state.editing.is_none());
+            assert!(state.editor.draft.content.is_empty());
+        }
+
+        #[test]
+        fn test_deleting_drops_the_draft() {
+            let mut state = fixed_state();
+            update(&mut state, Action::InputChanged("never sent".to_string()));
+            update(&mut state, Action::NewConversation);
+            update(&mut state, Action::DeleteConversation(0));
+
+            assert!(state.editor.drafts.is_empty());
+        }
+
+        #[test]
+        fn test_core_focuses_the_conversation_on_screen() {
+            let mut state = fixed_state();
+            update(&mut state, Action::NewConversation);
+
+            assert_eq!(state.editor.focused_interaction.as_deref(), Some(state.conversations[1].id.as_str()));
+        }
+    }
+
+    mod timings {
+        use super::*;
+
+        #[test]
+        fn test_accepted_message_keeps_local_send_time() {
+            let mut state = fixed_state();
+            update(&mut state, Action::InputChanged("Hello".to_string()));
+            update(&mut state, Action::SendMessage);
+            update(&mut state, Action::MessageAccepted { conversation_idx: 0, message_id: "server-1".to_string() });
+
+            assert_eq!(state.sent_locally.get("server-1"), Some(&state.clock.utc()));
+        }
+
+        #[test]
+        fn test_toggle_timings() {
+            let mut state = fixed_state();
+            update(&mut state, Action::ToggleTimings("m1".to_string()));
+            assert!(state.open_timings.contains("m1"));
+
+            update(&mut state, Action::ToggleTimings("m1".to_string()));
+            assert!(state.open_timings.is_empty());
+        }
+    }
+
+    mod export {
+        use super::*;
+
+        // The active conversation with four messages
+        fn exporting() -> AppState {
+            let mut state = fixed_state();
+            update(&mut state, Action::InputChanged("Hello".to_string()));
+            update(&mut state, Action::SendMessage);
+            for id in ["m2", "m3", "m4"] {
+                state.conversations[0].messages.push(message(id, MessageStatus::Completed));
+            }
+            update(&mut state, Action::OpenExportDialog);
+            state
+        }
+
+        fn range(state: &AppState) -> (usize, usize) {
+            let selection = state.export_dialog.as_ref().unwrap().selection;
+            (selection.from, selection.to)
+        }
+
+        #[test]
+        fn test_open_selects_everything() {
+            let state = exporting();
+            let selection = state.export_dialog.as_ref().unwrap().selection;
+
+            assert_eq!(range(&state), (0, 3));
+            assert!(selection.prompts && selection.responses);
+        }
+
+        #[test]
+        fn test_open_without_messages_does_nothing() {
+            let mut state = fixed_state();
+            update(&mut state, Action::OpenExportDialog);
+            assert!(state.export_dialog.is_none());
+        }
+
+        #[test]
+        fn test_range_ends_push_each_other() {
+            let mut state = exporting();
+            update(&mut state, Action::SetExportTo(1));
+            update(&mut state, Action::SetExportFrom(2));
+            assert_eq!(range(&state), (2, 2));
+
+            update(&mut state, Action::SetExportFrom(0));
+            update(&mut state, Action::SetExportTo(9));
+            assert_eq!(range(&state), (0, 3));
+        }
+
+        #[test]
+        fn test_toggle_roles() {
+            let mut state = exporting();
+            update(&mut state, Action::ToggleExportPrompts);
+            update(&mut state, Action::ToggleExportResponses);
+
+            let conv = &state.conversations[0];
+            assert!(state.export_dialog.as_ref().unwrap().selection.is_empty(conv));
+        }
+
+        #[test]
+        fn test_deleting_a_conversation_closes_the_dialog() {
+            let mut state = exporting();
+            update(&mut state, Action::DeleteConversation(0));
+            assert!(state.export_dialog.is_none());
+        }
+    }
+
+    mod interaction_types {
+        use super::*;
+        use crate::type_tabs;
+        use bicky_editor_core::types::InteractionType;
+
+        // A query, then an action and an observation from the server
+        fn typed() -> AppState {
+            let mut state = fixed_state();
+            update(&mut state, Action::InputChanged("Hello".to_string()));
+            update(&mut state, Action::SendMessage);
+            for interaction_type in [InteractionType::Action, InteractionType::Observation] {
+                let mut conv = state.conversations[0].clone();
+                conv.id = format!("{:?}", interaction_type);
+                conv.interaction_type = interaction_type;
+                state.conversations.push(conv);
+            }
+            state
+        }
+
+        #[test]
+        fn test_filter_by_type() {
+            let mut state = typed();
+            update(&mut state, Action::FilterByType(Some(InteractionType::Action)));
+
+            let shown: Vec<&str> = state.conversations
+                .iter()
+                .filter(|conv| matches_type_filter(&state, conv))
+                .map(|conv| conv.id.as_str())
+                .collect();
+            assert_eq!(shown, vec!["Action"]);
+
+            update(&mut state, Action::FilterByType(None));
+            assert!(state.conversations.iter().all(|conv| matches_type_filter(&state, conv)));
+        }
+
+        #[test]
+        fn test_counts_skip_empty_and_archived() {
+            let mut state = typed();
+            state.conversations[2].archived = true;
+
+            assert_eq!(type_tabs::counts(&state), vec![
+                (InteractionType::Query, 1),
+                (InteractionType::Action, 1),
+            ]);
+        }
+
+        #[test]
+        fn test_new_conversations_are_queries() {
+            let mut state = fixed_state();
+            update(&mut state, Action::InputChanged("Hello".to_string()));
+            update(&mut state, Action::SendMessage);
+
+            assert_eq!(state.conversations[0].interaction_type, InteractionType::Query);
+        }
+    }
+
+    mod focus_mode {
+        use super::*;
+        use crate::focus;
+        use crate::palette::{self, PaletteCommand};
+        use iced::Color;
+
+        fn focus_label(state: &AppState) -> Option<String> {
+            palette::commands(state)
+                .into_iter()
+                .find(|entry| entry.command == PaletteCommand::ToggleFocusMode)
+                .map(|entry| entry.label)
+        }
+
+        #[test]
+        fn test_focus_mode_toggles_from_the_palette() {
+            let mut state = fixed_state();
+            assert_eq!(focus_label(&state).as_deref(), Some("Enter focus mode"));
+
+            update(&mut state, Action::ToggleFocusMode);
+            assert!(state.focus_mode);
+            assert_eq!(focus_label(&state).as_deref(), Some("Leave focus mode"));
+
+            update(&mut state, Action::ToggleFocusMode);
+            assert!(!state.focus_mode);
+        }
+
+        #[test]
+        fn test_indicators_dim_only_in_focus_mode() {
+            let spinner = Color::from_rgb(1.0, 0.5, 0.0);
+
+            assert_eq!(focus::indicator(false, spinner), spinner);
+            let dimmed = focus::indicator(true, spinner);
+            assert!(dimmed.a < spinner.a);
+            assert_eq!((dimmed.r, dimmed.g, dimmed.b), (spinner.r, spinner.g, spinner.b));
+        }
+    }
+
+    mod presentation {
+        use super::*;
+        use crate::palette::{self, PaletteCommand};
+        use crate::presentation::{slide_for, slides};
+        use iced::keyboard::{key::Named, Key};
+
+        fn answered(id: &str) -> LegacyMessage {
+            LegacyMessage {
+                response: Some(format!("Answer {}", id)),
+                ..message(id, MessageStatus::Completed)
+            }
+        }
+
+        fn presentable() -> AppState {
+            let mut state = fixed_state();
+            state.active_conversation = Some(0);
+            state.conversations[0].messages =
+                vec![answered("a"), message("pending", MessageStatus::Processing), answered("b"), answered("c")];
+            state
+        }
+
+        fn can_present(state: &AppState) -> bool {
+            palette::commands(state).iter().any(|entry| entry.command == PaletteCommand::StartPresentation)
+        }
+
+        #[test]
+        fn test_one_slide_per_answered_message() {
+            let state = presentable();
+
+            let ids: Vec<&str> = slides(&state.conversations[0]).iter().map(|msg| msg.id.as_str()).collect();
+            assert_eq!(ids, ["a", "b", "c"]);
+        }
+
+        #[test]
+        fn test_arrow_keys_step_within_the_deck() {
+            let right = Key::Named(Named::ArrowRight);
+            let left = Key::Named(Named::ArrowLeft);
+
+            assert_eq!(slide_for(&right, 0, 3), Some(1));
+            assert_eq!(slide_for(&right, 2, 3), Some(2));
+            assert_eq!(slide_for(&left, 0, 3), Some(0));
+            assert_eq!(slide_for(&Key::Named(Named::End), 0, 3), Some(2));
+            assert_eq!(slide_for(&Key::Character("a".into()), 1, 3), None);
+            assert_eq!(slide_for(&right, 0, 0), None);
+        }
+
+        #[test]
+        fn test_presenting_starts_at_the_first_slide_and_ends() {
+            let mut state = presentable();
+            assert!(can_present(&state));
+
+            update(&mut state, Action::StartPresentation);
+            assert_eq!(state.presentation, Some(0));
+            update(&mut state, Action::GoToSlide(7));
+            assert_eq!(state.presentation, Some(2));
+
+            update(&mut state, Action::EndPresentation);
+            assert_eq!(state.presentation, None);
+            update(&mut state, Action::GoToSlide(1));
+            assert_eq!(state.presentation, None);
+        }
+
+        #[test]
+        fn test_nothing_to_present_without_answers() {
+            let mut state = fixed_state();
+            state.active_conversation = Some(0);
+            state.conversations[0].messages = vec![message("pending", MessageStatus::Processing)];
+
+            assert!(!can_present(&state));
+            update(&mut state, Action::StartPresentation);
+            assert_eq!(state.presentation, None);
+        }
+
+        #[test]
+        fn test_switching_conversations_ends_the_presentation() {
+            let mut state = presentable();
+            update(&mut state, Action::StartPresentation);
+
+            update(&mut state, Action::NewConversation);
+            assert_eq!(state.presentation, None);
+        }
+    }
+
+    mod mutes {
+        use super::*;
+
+        #[test]
+        fn test_muting_is_remembered_by_conversation_id() {
+            let mut state = fixed_state();
+            let id = state.conversations[0].id.clone();
+
+            update(&mut state, Action::ToggleMute(0));
+            assert!(state.conversations[0].muted);
+            assert!(state.muted.contains(&id));
+
+            update(&mut state, Action::ToggleMute(0));
+            assert!(!state.conversations[0].muted);
+            assert!(state.muted.is_empty());
+        }
+
+        #[test]
+        fn test_restored_mutes_apply_to_loaded_conversations() {
+            let mut state = fixed_state();
+            let id = state.conversations[0].id.clone();
+
+            update(&mut state, Action::LocalFilesRestored {
+                labels: Default::default(),
+                muted: [id.clone()].into_iter().collect(),
+                permission_policy: Default::default(),
+                permission_audit: Vec::new(),
+            });
+
+            assert!(state.conversations[0].muted);
+            assert!(state.muted.contains(&id));
+        }
+
+        #[test]
+        fn test_deleted_conversations_are_no_longer_muted() {
+            let mut state = fixed_state();
+            let id = state.conversations[0].id.clone();
+            update(&mut state, Action::ToggleMute(0));
+
+            update(&mut state, Action::DeleteConversation(0));
+
+            assert!(!state.muted.contains(&id));
+        }
+    }
+
+    mod backups {
+        use super::*;
+        use crate::backup::Backup;
+        use crate::labels::ColorLabel;
+        use crate::palette::{self, PaletteCommand};
+        use chrono::NaiveDate;
+
+        #[test]
+        fn test_checked_backups_can_be_restored_from_the_palette() {
+            let mut state = fixed_state();
+            let date = NaiveDate::from_ymd_opt(2024, 3, 1).unwrap();
+            let backup = Backup { date, path: "backups/2024-03-01".into() };
+            update(&mut state, Action::BackupsChecked { date, backups: vec![backup] });
+
+            assert_eq!(state.backup_checked, Some(date));
+            assert!(palette::commands(&state).iter().any(|entry| entry.command == PaletteCommand::RestoreBackup(date)));
+        }
+
+        #[test]
+        fn test_restored_files_replace_loaded_ones() {
+            let mut state = fixed_state();
+            state.labels.insert("old".to_string(), ColorLabel::Red);
+            let labels = [("c1".to_string(), ColorLabel::Blue)].into_iter().collect();
+
+            update(&mut state, Action::LocalFilesRestored {
+                labels,
+                muted: Default::default(),
+                permission_policy: Default::default(),
+                permission_audit: Vec::new(),
+            });
+
+            assert_eq!(state.labels.get("c1"), Some(&ColorLabel::Blue));
+            assert!(!state.labels.contains_key("old"));
+        }
+    }
+
+    mod unhandled_events {
+        use super::*;
+
+        #[test]
+        fn test_unhandled_event_types_are_counted() {
+            let mut state = fixed_state();
+            update(&mut state, Action::SseEventUnhandled("session:renamed".to_string()));
+            update(&mut state, Action::SseEventUnhandled("session:renamed".to_string()));
+            update(&mut state, Action::SseEventUnhandled("worktree:merged".to_string()));
+
+            assert_eq!(state.unhandled_events.get("session:renamed"), Some(&2));
+            assert_eq!(state.unhandled_events.get("worktree:merged"), Some(&1));
+        }
+
+        #[test]
+        fn test_only_the_first_of_a_type_notifies() {
+            let mut state = fixed_state();
+            update(&mut state, Action::SseEventUnhandled("session:renamed".to_string()));
+            assert!(matches!(&state.editor.notification, Some((Notification::Info(msg), _)) if msg.contains("session:renamed")));
+
+            update(&mut state, Action::Editor(EditorAction::ClearNotification));
+            update(&mut state, Action::SseEventUnhandled("session:renamed".to_string()));
+            assert!(state.editor.notification.is_none());
+        }
+    }
+
+    mod plugins {
+        use super::*;
+        use crate::config::PluginSettings;
+        use crate::palette::{self, PaletteCommand};
+        use crate::plugins::{LoadedPlugin, Rendering};
+        use bicky_editor_core::plugin::{Block, CommandInfo, Context, Manifest, Panel};
+
+        fn plugin(name: &str) -> LoadedPlugin {
+            LoadedPlugin {
+                settings: PluginSettings { command: format!("{}-plugin", name), ..Default::default() },
+                manifest: Manifest {
+                    name: name.to_string(),
+                    tools: vec!["run_tests".to_string()],
+                    panel: true,
+                    commands: vec![CommandInfo { id: "summarize".to_string(), label: "Summarize".to_string() }],
+                },
+            }
+        }
+
+        #[test]
+        fn test_reloaded_plugin_replaces_the_old_one() {
+            let mut state = fixed_state();
+            update(&mut state, Action::PluginLoaded(plugin("tests")));
+            let mut reloaded = plugin("tests");
+            reloaded.manifest.panel = false;
+            update(&mut state, Action::PluginLoaded(reloaded.clone()));
+
+            assert_eq!(state.plugins, vec![reloaded]);
+        }
+
+        #[test]
+        fn test_loading_a_plugin_offers_declined_messages_again() {
+            let mut state = fixed_state();
+            update(&mut state, Action::PluginRendered { message_id: "m1".to_string(), blocks: None });
+            update(&mut state, Action::PluginRenderRequested { message_id: "m2".to_string(), rendering: Rendering::Pending });
+            update(&mut state, Action::PluginPanelsRequested(Context::default()));
+
+            update(&mut state, Action::PluginLoaded(plugin("tests")));
+
+            assert!(!state.plugin_renderings.contains_key("m1"));
+            assert_eq!(state.plugin_renderings.get("m2"), Some(&Rendering::Pending));
+            assert_eq!(state.plugin_context, None);
+        }
+
+        #[test]
+        fn test_rendered_blocks_replace_pending() {
+            let mut state = fixed_state();
+            update(&mut state, Action::PluginRenderRequested { message_id: "m1".to_string(), rendering: Rendering::Pending });
+            let blocks = vec![Block::Heading { text: "3 passed".to_string() }];
+            update(&mut state, Action::PluginRendered { message_id: "m1".to_string(), blocks: Some(blocks.clone()) });
+
+            assert_eq!(state.plugin_renderings.get("m1"), Some(&Rendering::Blocks(blocks)));
+        }
+
+        #[test]
+        fn test_panel_without_content_is_hidden() {
+            let mut state = fixed_state();
+            let panel = Panel { title: "CI".to_string(), blocks: Vec::new() };
+            update(&mut state, Action::PluginPanelLoaded { plugin: "ci".to_string(), panel: Some(panel.clone()) });
+            assert_eq!(state.plugin_panels.get("ci"), Some(&panel));
+
+            update(&mut state, Action::PluginPanelLoaded { plugin: "ci".to_string(), panel: None });
+            assert!(state.plugin_panels.is_empty());
+        }
+
+        #[test]
+        fn test_plugin_commands_are_in_the_palette() {
+            let mut state = fixed_state();
+            update(&mut state, Action::PluginLoaded(plugin("tests")));
+
+            let entry = palette::commands(&state).into_iter().find(|entry| entry.label == "tests: Summarize").unwrap();
+            assert_eq!(entry.command, PaletteCommand::Plugin {
+                plugin: "tests".to_string(),
+                command: "summarize".to_string(),
+            });
+        }
+    }
+
+    mod notifications {
+        use super::*;
+
+        #[test]
+        fn test_notification_expires_after_timeout() {
+            let mut state = fixed_state();
+            update(&mut state, Action::ShowNotification(Notification::Info("hi".to_string())));
+            assert!(!notification_expired(&state));
+
+            advance(&mut state, Duration::from_secs(4));
+            assert!(!notification_expired(&state));
+
+            advance(&mut state, Duration::from_secs(1));
+            assert!(notification_expired(&state));
+        }
+
+        #[test]
+        fn test_new_notification_restarts_timeout() {
+            let mut state = fixed_state();
+            update(&mut state, Action::ShowNotification(Notification::Info("first".to_string())));
+            advance(&mut state, Duration::from_secs(4));
+            update(&mut state, Action::ShowNotification(Notification::Error("second".to_string())));
+            advance(&mut state, Duration::from_secs(4));
+
+            assert!(!notification_expired(&state));
+        }
+
+        #[test]
+        fn test_clear_notification() {
+            let mut state = fixed_state();
+            update(&mut state, Action::ShowNotification(Notification::Success("done".to_string())));
+            update(&mut state, Action::Editor(EditorAction::ClearNotification));
+
+            assert!(state.editor.notification.is_none());
+            assert!(!notification_expired(&state));
+        }
+    }
+
+    mod core_pipeline {
+        use super::*;
+
+        #[test]
+        fn test_middleware_is_registered_at_startup() {
+            let state = fixed_state();
+
+            assert!(!state.pipeline.lock().unwrap().is_empty());
+        }
+
+        #[test]
+        fn test_clones_share_the_pipeline() {
+            let state = fixed_state();
+            let copy = state.clone();
+
+            assert!(Arc::ptr_eq(&state.pipeline, &copy.pipeline));
+        }
+    }
+
+    mod action_log {
+        use super::*;
+
+        fn set_error(state: &mut AppState, message: &str) {
+            update(state, Action::Editor(EditorAction::SetError { message: message.to_string() }));
+        }
+
+        #[test]
+        fn test_core_actions_are_recorded() {
+            let mut state = fixed_state();
+            set_error(&mut state, "first");
+
+            let log = state.action_log.lock().unwrap();
+            let last = log.entries().back().unwrap();
+            assert_eq!(last.action, EditorAction::SetError { message: "first".to_string() });
+            assert_eq!(last.at, "2024-01-01T12:00:00Z".parse::<DateTime<Utc>>().unwrap());
+        }
+
+        #[test]
+        fn test_stepping_back_inspects_without_touching_the_live_state() {
+            let mut state = fixed_state();
+            set_error(&mut state, "first");
+            set_error(&mut state, "second");
+
+            update(&mut state, Action::StepActionLogBack);
+
+            assert_eq!(state.inspected.as_ref().unwrap().error, Some("first".to_string()));
+            assert_eq!(state.editor.error, Some("second".to_string()));
+        }
+
+        #[test]
+        fn test_stepping_past_the_newest_goes_back_to_live() {
+            let mut state = fixed_state();
+            set_error(&mut state, "first");
+            update(&mut state, Action::StepActionLogBack);
+
+            update(&mut state, Action::StepActionLogForward);
+
+            assert!(state.inspected.is_none());
+            assert!(state.action_log.lock().unwrap().cursor().is_none());
+        }
+
+        #[test]
+        fn test_closing_the_log_resumes() {
+            let mut state = fixed_state();
+            set_error(&mut state, "first");
+            update(&mut state, Action::ToggleActionLog);
+            update(&mut state, Action::StepActionLogBack);
+
+            update(&mut state, Action::ToggleActionLog);
+
+            assert!(!state.show_action_log);
+            assert!(state.inspected.is_none());
+            assert!(state.action_log.lock().unwrap().cursor().is_none());
+        }
+    }
+
+    mod tags {
+        use super::*;
+        use bicky_editor_core::types::{Interaction, InteractionType};
+        use bicky_editor_core::Effect;
+
+        // The server's copy of the active conversation, tagged `bug`
+        fn seen_by_core(state: &mut AppState) {
+            let interaction = Interaction {
+                id: state.conversations[0].id.clone(),
+                source: "user".to_string(),
+                interaction_type: InteractionType::Query,
+                content: Vec::new(),
+                needs_work: false,
+                review_stack: Vec::new(),
+                history: Vec::new(),
+                metadata: [("tags".to_string(), serde_json::json!(["bug"]))].into_iter().collect(),
+                timestamp: state.clock.utc(),
+            };
+            update(state, Action::Editor(EditorAction::InteractionPosted { interaction }));
+        }
+
+        #[test]
+        fn test_conversations_the_server_has_not_sent_have_no_tags() {
+            let state = fixed_state();
+
+            assert_eq!(tags_of(&state, &state.conversations[0]), None);
+        }
+
+        #[test]
+        fn test_adding_the_typed_tag_saves_every_tag() {
+            let mut state = fixed_state();
+            seen_by_core(&mut state);
+            update(&mut state, Action::TagDraftChanged(" review ".to_string()));
+
+            update(&mut state, Action::AddDraftedTag(0));
+
+            assert_eq!(tags_of(&state, &state.conversations[0]), Some(vec!["bug", "review"]));
+            assert!(state.tag_draft.is_empty());
+            assert_eq!(state.effects, vec![Effect::UpdateInteractionTags {
+                interaction_id: state.conversations[0].id.clone(),
+                tags: vec!["bug".to_string(), "review".to_string()],
+            }]);
+        }
+
+        #[test]
+        fn test_removing_a_tag_saves_the_rest() {
+            let mut state = fixed_state();
+            seen_by_core(&mut state);
+
+            update(&mut state, Action::RemoveTag { conversation_idx: 0, tag: "bug".to_string() });
+
+            assert_eq!(tags_of(&state, &state.conversations[0]), Some(vec![]));
+            assert_eq!(state.effects, vec![Effect::UpdateInteractionTags {
+                interaction_id: state.conversations[0].id.clone(),
+                tags: Vec::new(),
+            }]);
+        }
+    }
+
+    mod snapshots {
+        use super::*;
+        use bicky_editor_core::Effect;
+
+        #[test]
+        fn test_saving_hands_the_core_state_to_be_written() {
+            let mut state = fixed_state();
+            update(&mut state, Action::InputChanged("half a thought".to_string()));
+
+            update(&mut state, Action::SaveSnapshot);
+
+            let Some(Effect::PersistState { snapshot }) = state.effects.last() else {
+                panic!("expected a snapshot to write, got {:?}", state.effects);
+            };
+            let restored = bicky_editor_core::EditorState::from_snapshot(snapshot).unwrap();
+            assert_eq!(restored.draft.content, "half a thought");
+        }
+
+        #[test]
+        fn test_saves_again_after_the_interval() {
+            let mut state = fixed_state();
+            assert!(snapshot_due(&state));
+
+            update(&mut state, Action::SaveSnapshot);
+            assert!(!snapshot_due(&state));
+
+            advance(&mut state, SAVE_INTERVAL);
+            assert!(snapshot_due(&state));
+        }
+    }
+
+    mod core_effects {
+        use super::*;
+        use bicky_editor_core::Effect;
+
+        #[test]
+        fn test_effects_wait_for_the_app_to_run_them() {
+            let mut state = fixed_state();
+            update(&mut state, Action::Editor(EditorAction::FetchSessions));
+            update(&mut state, Action::Editor(EditorAction::ClearNotification));
+
+            assert_eq!(state.effects, vec![Effect::FetchSessions]);
+        }
+    }
+}
+
+#[cfg(test)]
+mod action_sequences {
+    use super::*;
+    use proptest::prelude::*;
+
+    // Small index and id ranges so actions keep hitting the same items
+    fn action() -> impl Strategy<Value = Action> {
+        let idx = 0usize..4;
+        let worktree_id = prop::sample::select(vec!["wt-1", "wt-2"]);
+        prop_oneof![
+            Just(Action::NewConversation),
+            idx.clone().prop_map(Action::SelectConversation),
+            idx.clone().prop_map(Action::ArchiveConversation),
+            idx.clone().prop_map(Action::UnarchiveConversation),
+            idx.clone().prop_map(Action::DeleteConversation),
+            idx.clone().prop_map(Action::ToggleMute),
+            "[a-z ]{0,12}".prop_map(Action::InputChanged),
+            Just(Action::SendMessage),
+            Just(Action::CancelEdit),
+            Just(Action::ResendMessage),
+            (idx.clone(), 0usize..8).prop_map(|(conversation_idx, n)| Action::EditMessage {
+                conversation_idx,
+                message_id: format!("temp-{}", n),
+            }),
+            (idx.clone(), 0usize..8).prop_map(|(conversation_idx, n)| Action::MessageCompleted {
+                conversation_idx,
+                id: format!("temp-{}", n),
+                response: Some("ok".to_string()),
+                error: None,
+                metadata: None,
+            }),
+            (idx.clone(), 0usize..8).prop_map(|(conversation_idx, n)| Action::DeleteMessage {
+                conversation_idx,
+                message_id: format!("temp-{}", n),
+            }),
+            (idx.clone(), 0usize..8).prop_map(|(conversation_idx, n)| Action::RetryMessage {
+                conversation_idx,
+                message_id: format!("temp-{}", n),
+            }),
+            idx.clone().prop_map(|conversation_idx| Action::MessageSendFailed {
+                conversation_idx,
+                error: ApiError::Network("offline".to_string()).into(),
+            }),
+            (idx, 0usize..8).prop_map(|(conversation_idx, n)| Action::ToggleBookmark {
+                conversation_idx,
+                message_id: format!("temp-{}", n),
+            }),
+            worktree_id.clone().prop_map(|id| Action::WorktreeCreated(worktree(id))),
+            worktree_id.clone().prop_map(|id| Action::SelectWorktree(id.to_string())),
+            worktree_id.prop_map(|id| Action::WorktreeDeleted(id.to_string())),
+            Just(Action::WorktreeChanged(None)),
+            Just(Action::OpenWorktreeDialog),
+            Just(Action::CloseWorktreeDialog),
+            prop::sample::select(vec!["local", "remote"]).prop_map(|server| Action::SwitchServer(server.to_string())),
+        ]
+    }
+
+    fn worktree(id: &str) -> Worktree {
+        Worktree {
+            id: id.to_string(),
+            path: format!("/tmp/{}", id),
+            branch: Some(id.to_string()),
+            base_branch: None,
+            base_commit: None,
+            status: WorktreeStatus::Active,
+            created_at: "2024-01-01T00:00:00Z".to_string(),
+        }
+    }
+
+    proptest! {
+        #[test]
+        fn test_state_stays_consistent(actions in prop::collection::vec(action(), 0..60)) {
+            let mut state = AppState::new(Arc::new(SystemClock), Arc::new(SequentialIds::default()));
+            update(&mut state, Action::ServersConfigured(vec!["local".to_string(), "remote".to_string()]));
+            for action in actions {
+                update(&mut state, action);
+
+                if let Some(active) = state.active_conversation {
+                    prop_assert!(active < state.conversations.len());
+                    prop_assert_eq!(&state.conversations[active].server, &state.active_server);
+                }
+                if let Some(editing) = &state.editing {
+                    prop_assert!(editing.conversation_idx < state.conversations.len());
+                }
+                prop_assert_eq!(state.input_tokens, tokenizer::estimate_tokens(&state.editor.draft.content));
+                for conv in &state.conversations {
+                    prop_assert!(!state.deleted_conversations.contains(&conv.id));
+                    let ids: HashSet<&str> = conv.messages.iter().map(|m| m.id.as_str()).collect();
+                    prop_assert_eq!(ids.len(), conv.messages.len());
+                }
+
+                // The current worktree is always the one the active conversation works in
+                let expected = state.active_conversation
+                    .and_then(|idx| state.conversations[idx].worktree_id.as_ref())
+                    .filter(|id| state.editor.available_worktrees.iter().any(|w| &&w.id == id));
+                prop_assert_eq!(state.editor.current_worktree.as_ref().map(|w| &w.id), expected);
+            }
+        }
+
+        #[test]
+        fn test_notification_expires_on_clock(shown_after in 0u64..20_000, waited in 0u64..20_000) {
+            let (start, start_utc) = (Instant::now(), Utc::now());
+            let mut state = AppState::default();
+            let at = |ms: u64| Arc::new(ManualClock {
+                instant: start + Duration::from_millis(ms),
+                utc: start_utc + chrono::Duration::milliseconds(ms as i64),
+            });
+
+            state.clock = at(shown_after);
+            update(&mut state, Action::ShowNotification(Notification::Info("hi".to_string())));
+            state.clock = at(shown_after + waited);
+
+            prop_assert_eq!(notification_expired(&state), waited >= 5_000);
+        }
+    }
+}
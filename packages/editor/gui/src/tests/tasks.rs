@@ -0,0 +1,144 @@
+use crate::tasks::{cancellable, Supervisor, TaskStatus, Tasks};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio_util::sync::CancellationToken;
+use tokio::sync::oneshot;
+
+#[cfg(test)]
+mod tasks_tests {
+    use super::*;
+
+    #[test]
+    fn test_switch_cancels_only_conversation_work() {
+        let mut tasks = Tasks::default();
+        let diff = tasks.conversation();
+        let sse = tasks.app();
+
+        tasks.switch_conversation();
+
+        assert!(diff.is_cancelled());
+        assert!(!sse.is_cancelled());
+        assert!(!tasks.conversation().is_cancelled());
+    }
+
+    #[test]
+    fn test_shutdown_cancels_everything() {
+        let tasks = Tasks::default();
+        let diff = tasks.conversation();
+        let sse = tasks.app();
+
+        drop(tasks);
+
+        assert!(diff.is_cancelled());
+        assert!(sse.is_cancelled());
+    }
+
+    #[tokio::test]
+    async fn test_finished_work_is_returned() {
+        let tasks = Tasks::default();
+
+        assert_eq!(cancellable(tasks.conversation(), async { 42 }).await, Some(42));
+    }
+
+    #[tokio::test]
+    async fn test_stale_result_never_applies() {
+        let mut tasks = Tasks::default();
+        let (respond, response) = oneshot::channel::<Vec<String>>();
+        let applied = Arc::new(AtomicBool::new(false));
+
+        // A fetch for the old conversation that would update state when it lands
+        let fetch = tokio::spawn(cancellable(tasks.conversation(), {
+            let applied = applied.clone();
+            async move {
+                let files = response.await.unwrap_or_default();
+                applied.store(true, Ordering::SeqCst);
+                files
+            }
+        }));
+
+        tasks.switch_conversation();
+        let _ = respond.send(vec!["src/main.rs".to_string()]);
+
+        assert_eq!(fetch.await.unwrap(), None);
+        assert!(!applied.load(Ordering::SeqCst));
+    }
+
+    #[tokio::test]
+    async fn test_supervisor_records_finished_work() {
+        let supervisor = Supervisor::default();
+
+        assert_eq!(supervisor.track("get worktrees".to_string(), async { 7 }).await, 7);
+
+        let tasks = supervisor.snapshot();
+        assert_eq!(tasks.len(), 1);
+        assert_eq!(tasks[0].name, "get worktrees");
+        assert_eq!(tasks[0].status, TaskStatus::Finished);
+    }
+
+    #[tokio::test]
+    async fn test_supervisor_records_cancelled_work() {
+        let supervisor = Supervisor::default();
+        let token = CancellationToken::new();
+        let (_respond, response) = oneshot::channel::<()>();
+
+        let fetch = tokio::spawn(cancellable(token.clone(), supervisor.track("get diff".to_string(), response)));
+        tokio::task::yield_now().await;
+        assert_eq!(supervisor.snapshot()[0].status, TaskStatus::Running);
+
+        token.cancel();
+        assert!(fetch.await.unwrap().is_none());
+        assert_eq!(supervisor.snapshot()[0].status, TaskStatus::Cancelled);
+    }
+
+    #[test]
+    fn test_critical_task_restarts_after_panic() {
+        let rt = tokio::runtime::Runtime::new().unwrap();
+        let supervisor = Supervisor::default();
+        let token = CancellationToken::new();
+        let starts = Arc::new(AtomicBool::new(false));
+
+        supervisor.spawn_critical(&rt, "SSE local", token.clone(), {
+            let starts = starts.clone();
+            move || {
+                let first = !starts.swap(true, Ordering::SeqCst);
+                async move {
+                    if first {
+                        panic!("stream broke");
+                    }
+                }
+            }
+        });
+
+        let mut task = None;
+        for _ in 0..200 {
+            task = supervisor.snapshot().into_iter().next().filter(|t| t.restarts > 0);
+            if task.is_some() {
+                break;
+            }
+            std::thread::sleep(Duration::from_millis(10));
+        }
+        let task = task.expect("the panic was never noticed");
+        assert_eq!(task.status, TaskStatus::Restarting("stream broke".to_string()));
+
+        token.cancel();
+    }
+
+    #[test]
+    fn test_history_keeps_running_tasks() {
+        let rt = tokio::runtime::Runtime::new().unwrap();
+        let supervisor = Supervisor::default();
+        let (_respond, response) = oneshot::channel::<()>();
+        let running = rt.spawn(supervisor.track("SSE local".to_string(), response));
+
+        for i in 0..80 {
+            rt.block_on(supervisor.track(format!("fetch {}", i), async {}));
+        }
+
+        let tasks = supervisor.snapshot();
+        assert!(tasks.iter().any(|t| t.name == "SSE local" && t.status == TaskStatus::Running));
+        assert!(tasks.len() <= 51);
+        assert!(!tasks.iter().any(|t| t.name == "fetch 0"));
+        running.abort();
+    }
+}
@@ -0,0 +1,110 @@
+use crate::tests::message;
+use crate::timings::{from_server, phases};
+use crate::types::{InteractionMetadata, LegacyMessage, MessageStatus, ResponseTimings};
+use chrono::{DateTime, Duration, TimeZone, Utc};
+use serde_json::json;
+
+#[cfg(test)]
+mod timings_tests {
+    use super::*;
+
+    fn at(millis: i64) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2024, 1, 1, 12, 0, 0).unwrap() + Duration::milliseconds(millis)
+    }
+
+    fn answered(accepted: i64, timings: ResponseTimings) -> LegacyMessage {
+        LegacyMessage {
+            content: "Hello".to_string(),
+            response: Some("Hi".to_string()),
+            metadata: Some(InteractionMetadata { timings: Some(timings), ..Default::default() }),
+            sent_at: Some(at(accepted)),
+            ..message("m1", MessageStatus::Completed)
+        }
+    }
+
+    fn summary(msg: &LegacyMessage, sent: Option<i64>) -> Vec<(String, u64)> {
+        phases(msg, sent.map(at)).into_iter().map(|p| (p.name.clone(), p.millis())).collect()
+    }
+
+    #[test]
+    fn test_every_stage_known() {
+        let msg = answered(80, ResponseTimings {
+            started_at: Some(at(300)),
+            model_started_at: Some(at(350)),
+            first_token_at: Some(at(1350)),
+            completed_at: Some(at(4350)),
+        });
+
+        assert_eq!(summary(&msg, Some(0)), vec![
+            ("Network".to_string(), 80),
+            ("Queue".to_string(), 220),
+            ("Setup".to_string(), 50),
+            ("First token".to_string(), 1000),
+            ("Generation".to_string(), 3000),
+        ]);
+    }
+
+    #[test]
+    fn test_unknown_stages_fold_into_the_next() {
+        let msg = answered(80, ResponseTimings {
+            started_at: Some(at(300)),
+            model_started_at: Some(at(350)),
+            first_token_at: None,
+            completed_at: Some(at(4350)),
+        });
+
+        // Not sent from here, so there's no network time either
+        assert_eq!(summary(&msg, None), vec![
+            ("Queue".to_string(), 220),
+            ("Setup".to_string(), 50),
+            ("First token + Generation".to_string(), 4000),
+        ]);
+    }
+
+    #[test]
+    fn test_disagreeing_clocks_never_go_backwards() {
+        let msg = answered(500, ResponseTimings {
+            started_at: Some(at(400)),
+            completed_at: Some(at(900)),
+            ..Default::default()
+        });
+
+        assert_eq!(summary(&msg, Some(0)), vec![
+            ("Network".to_string(), 500),
+            ("Queue".to_string(), 0),
+            ("Setup + First token + Generation".to_string(), 400),
+        ]);
+    }
+
+    #[test]
+    fn test_nothing_to_show_without_times() {
+        let mut msg = answered(0, ResponseTimings::default());
+        msg.sent_at = None;
+        assert!(phases(&msg, None).is_empty());
+    }
+
+    #[test]
+    fn test_server_times_are_moved_to_our_clock() {
+        let meta = json!({
+            "timings": {
+                "startedAt": "2024-01-01T12:00:01Z",
+                "firstTokenAt": "2024-01-01T12:00:02Z",
+                "completedAt": "2024-01-01T12:00:05Z"
+            }
+        });
+        let timings = from_server(&meta, None, |t| t - Duration::seconds(1)).unwrap();
+
+        assert_eq!(timings.started_at, Some(at(0)));
+        assert_eq!(timings.model_started_at, None);
+        assert_eq!(timings.first_token_at, Some(at(1000)));
+        assert_eq!(timings.completed_at, Some(at(4000)));
+    }
+
+    #[test]
+    fn test_response_time_stands_in_for_completion() {
+        let timings = from_server(&json!({ "model": "x" }), Some(at(2000)), |t| t).unwrap();
+        assert_eq!(timings.completed_at, Some(at(2000)));
+
+        assert_eq!(from_server(&json!({}), None, |t| t), None);
+    }
+}
@@ -1,29 +1,277 @@
 use iced::{Background, Border, Color, Theme};
-use iced::widget::{button, container, text_input};
+use iced::widget::{button, container, scrollable, slider, text_editor, text_input};
+
+use bicky_editor_core::theme::{parse_hex, parse_tokens};
+use std::path::PathBuf;
+use std::sync::RwLock;
+use std::time::{Duration, Instant, SystemTime};
+
+use crate::config::{self, PaletteVariant};
+
+const THEME_POLL_INTERVAL: Duration = Duration::from_secs(1);
 
 // Color palette
-pub struct Colors;
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Colors {
+    pub background: Color,
+    pub background_dim: Color,
+    pub surface: Color,
+    pub surface_light: Color,
+    pub primary: Color,
+    pub secondary: Color,
+    pub text: Color,
+    pub text_dim: Color,
+    pub border: Color,
+    pub success: Color,
+    pub error: Color,
+    pub spinner: Color,
+    /// Background of the user's messages
+    pub user: Color,
+    /// Outline of Wake's messages
+    pub assistant: Color,
+}
+
+/// Whether the dark or the light palette is showing
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Appearance {
+    Dark,
+    Light,
+}
+
+impl Appearance {
+    /// What the OS is set to, dark when it doesn't say
+    pub fn detect() -> Self {
+        match dark_light::detect() {
+            dark_light::Mode::Light => Appearance::Light,
+            dark_light::Mode::Dark | dark_light::Mode::Default => Appearance::Dark,
+        }
+    }
+}
 
 impl Colors {
-    pub const BACKGROUND: Color = Color::from_rgb(0.11, 0.11, 0.13); // #1c1c21
-    pub const BACKGROUND_DIM: Color = Color::from_rgb(0.09, 0.09, 0.11); // #171719
-    pub const SURFACE: Color = Color::from_rgb(0.13, 0.13, 0.16); // #212127
-    pub const SURFACE_LIGHT: Color = Color::from_rgb(0.16, 0.16, 0.20); // #292933
-    pub const PRIMARY: Color = Color::from_rgb(0.38, 0.65, 1.0); // #61a6ff
-    pub const SECONDARY: Color = Color::from_rgb(0.50, 0.50, 0.58); // #80809e
-    pub const TEXT: Color = Color::from_rgb(0.88, 0.88, 0.90); // #e0e0e6
-    pub const TEXT_DIM: Color = Color::from_rgb(0.50, 0.50, 0.58); // #80809e
-    pub const BORDER: Color = Color::from_rgb(0.20, 0.20, 0.24); // #33333d
-    pub const SUCCESS: Color = Color::from_rgb(0.40, 0.80, 0.40); // #66cc66
-    pub const ERROR: Color = Color::from_rgb(0.90, 0.40, 0.40); // #e66666
-    pub const SPINNER: Color = Color::from_rgb(1.0, 0.68, 0.38); // #FFAD61 - warm amber/orange
+    pub const DARK: Colors = Colors {
+        background: Color::from_rgb(0.11, 0.11, 0.13), // #1c1c21
+        background_dim: Color::from_rgb(0.09, 0.09, 0.11), // #171719
+        surface: Color::from_rgb(0.13, 0.13, 0.16), // #212127
+        surface_light: Color::from_rgb(0.16, 0.16, 0.20), // #292933
+        primary: Color::from_rgb(0.38, 0.65, 1.0), // #61a6ff
+        secondary: Color::from_rgb(0.50, 0.50, 0.58), // #80809e
+        text: Color::from_rgb(0.88, 0.88, 0.90), // #e0e0e6
+        text_dim: Color::from_rgb(0.50, 0.50, 0.58), // #80809e
+        border: Color::from_rgb(0.20, 0.20, 0.24), // #33333d
+        success: Color::from_rgb(0.40, 0.80, 0.40), // #66cc66
+        error: Color::from_rgb(0.90, 0.40, 0.40), // #e66666
+        spinner: Color::from_rgb(1.0, 0.68, 0.38), // #FFAD61 - warm amber/orange
+        user: Color::from_rgb(0.16, 0.16, 0.20), // #292933
+        assistant: Color::from_rgb(0.20, 0.20, 0.24), // #33333d
+    };
+
+    pub const LIGHT: Colors = Colors {
+        background: Color::from_rgb(0.98, 0.98, 0.98), // #fafafa
+        background_dim: Color::from_rgb(0.94, 0.94, 0.95), // #f0f0f2
+        surface: Color::from_rgb(0.94, 0.94, 0.95), // #f0f0f3
+        surface_light: Color::from_rgb(0.90, 0.90, 0.92), // #e6e6eb
+        primary: Color::from_rgb(0.12, 0.44, 0.92), // #1f6feb
+        secondary: Color::from_rgb(0.42, 0.42, 0.48), // #6b6b7b
+        text: Color::from_rgb(0.12, 0.12, 0.14), // #1f1f24
+        text_dim: Color::from_rgb(0.42, 0.42, 0.48), // #6b6b7b
+        border: Color::from_rgb(0.83, 0.83, 0.86), // #d4d4dc
+        success: Color::from_rgb(0.18, 0.62, 0.27), // #2e9e44
+        error: Color::from_rgb(0.81, 0.23, 0.23), // #cf3b3b
+        spinner: Color::from_rgb(0.85, 0.47, 0.02), // #d97706
+        user: Color::from_rgb(0.90, 0.90, 0.92), // #e6e6eb
+        assistant: Color::from_rgb(0.83, 0.83, 0.86), // #d4d4dc
+    };
+
+    /// Pure black and white with brighter status colors
+    pub const HIGH_CONTRAST_DARK: Colors = Colors {
+        background: Color::from_rgb(0.0, 0.0, 0.0), // #000000
+        background_dim: Color::from_rgb(0.0, 0.0, 0.0), // #000000
+        surface: Color::from_rgb(0.04, 0.04, 0.04), // #0a0a0a
+        surface_light: Color::from_rgb(0.1, 0.1, 0.1), // #1a1a1a
+        primary: Color::from_rgb(0.36, 0.7, 1.0), // #5cb3ff
+        secondary: Color::from_rgb(0.82, 0.82, 0.82), // #d0d0d0
+        text: Color::from_rgb(1.0, 1.0, 1.0), // #ffffff
+        text_dim: Color::from_rgb(0.82, 0.82, 0.82), // #d0d0d0
+        border: Color::from_rgb(0.6, 0.6, 0.6), // #9a9a9a
+        success: Color::from_rgb(0.24, 0.86, 0.52), // #3ddc84
+        error: Color::from_rgb(1.0, 0.42, 0.42), // #ff6b6b
+        spinner: Color::from_rgb(1.0, 0.76, 0.2), // #ffc233
+        user: Color::from_rgb(0.1, 0.1, 0.1), // #1a1a1a
+        assistant: Color::from_rgb(0.6, 0.6, 0.6), // #9a9a9a
+    };
+
+    pub const HIGH_CONTRAST_LIGHT: Colors = Colors {
+        background: Color::from_rgb(1.0, 1.0, 1.0), // #ffffff
+        background_dim: Color::from_rgb(1.0, 1.0, 1.0), // #ffffff
+        surface: Color::from_rgb(0.96, 0.96, 0.96), // #f5f5f5
+        surface_light: Color::from_rgb(0.88, 0.88, 0.88), // #e0e0e0
+        primary: Color::from_rgb(0.0, 0.28, 0.7), // #0047b3
+        secondary: Color::from_rgb(0.2, 0.2, 0.2), // #333333
+        text: Color::from_rgb(0.0, 0.0, 0.0), // #000000
+        text_dim: Color::from_rgb(0.2, 0.2, 0.2), // #333333
+        border: Color::from_rgb(0.33, 0.33, 0.33), // #555555
+        success: Color::from_rgb(0.0, 0.42, 0.12), // #006b1f
+        error: Color::from_rgb(0.69, 0.0, 0.13), // #b00020
+        spinner: Color::from_rgb(0.54, 0.29, 0.0), // #8a4b00
+        user: Color::from_rgb(0.88, 0.88, 0.88), // #e0e0e0
+        assistant: Color::from_rgb(0.33, 0.33, 0.33), // #555555
+    };
+
+    /// The built-in palette for an appearance and variant
+    pub fn builtin(appearance: Appearance, variant: PaletteVariant) -> Self {
+        match (variant, appearance) {
+            (PaletteVariant::Standard, Appearance::Dark) => Self::DARK,
+            (PaletteVariant::Standard, Appearance::Light) => Self::LIGHT,
+            (PaletteVariant::HighContrast, Appearance::Dark) => Self::HIGH_CONTRAST_DARK,
+            (PaletteVariant::HighContrast, Appearance::Light) => Self::HIGH_CONTRAST_LIGHT,
+            // Okabe-Ito blue, orange and yellow/purple stay apart for red-green colorblindness
+            (PaletteVariant::Colorblind, Appearance::Dark) => Self {
+                success: Color::from_rgb(0.34, 0.71, 0.91), // #56b4e9
+                error: Color::from_rgb(0.9, 0.62, 0.0), // #e69f00
+                spinner: Color::from_rgb(0.94, 0.89, 0.26), // #f0e442
+                ..Self::DARK
+            },
+            (PaletteVariant::Colorblind, Appearance::Light) => Self {
+                success: Color::from_rgb(0.0, 0.45, 0.7), // #0072b2
+                error: Color::from_rgb(0.84, 0.37, 0.0), // #d55e00
+                spinner: Color::from_rgb(0.8, 0.47, 0.65), // #cc79a7
+                ..Self::LIGHT
+            },
+        }
+    }
+
+    // Theme file names are the fields in kebab-case
+    fn token_mut(&mut self, name: &str) -> Option<&mut Color> {
+        Some(match name {
+            "background" => &mut self.background,
+            "background-dim" => &mut self.background_dim,
+            "surface" => &mut self.surface,
+            "surface-light" => &mut self.surface_light,
+            "primary" => &mut self.primary,
+            "secondary" => &mut self.secondary,
+            "text" => &mut self.text,
+            "text-dim" => &mut self.text_dim,
+            "border" => &mut self.border,
+            "success" => &mut self.success,
+            "error" => &mut self.error,
+            "spinner" => &mut self.spinner,
+            "user" => &mut self.user,
+            "assistant" => &mut self.assistant,
+            _ => return None,
+        })
+    }
+
+    /// A palette with a theme file's overrides applied, plus whatever was wrong with it
+    pub fn from_theme_file(base: Self, source: &str) -> (Self, Vec<String>) {
+        let (tokens, errors) = parse_tokens(source);
+        let mut colors = base;
+        let mut problems: Vec<String> = errors.iter().map(|e| e.to_string()).collect();
+
+        for token in tokens {
+            let [r, g, b, a] = token.color;
+            match colors.token_mut(&token.name) {
+                Some(color) => *color = Color::from_rgba8(r, g, b, a as f32 / 255.0),
+                None => problems.push(format!("line {}: unknown token {}", token.line, token.name)),
+            }
+        }
+
+        (colors, problems)
+    }
+}
+
+static COLORS: RwLock<Colors> = RwLock::new(Colors::DARK);
+
+/// The palette in use, with the user's theme applied
+pub fn colors() -> Colors {
+    COLORS.read().map(|colors| *colors).unwrap_or(Colors::DARK)
+}
+
+fn set_colors(colors: Colors) {
+    if let Ok(mut current) = COLORS.write() {
+        *current = colors;
+    }
+}
+
+/// `#rrggbb` or `#rgb`
+pub fn parse_hex_color(value: &str) -> Option<Color> {
+    let [r, g, b, a] = parse_hex(value)?;
+    Some(Color::from_rgba8(r, g, b, a as f32 / 255.0))
+}
+
+/// What changed after polling the theme file
+#[derive(Debug, Clone, PartialEq)]
+pub enum ThemeReload {
+    Applied(PathBuf),
+    Invalid(PathBuf, Vec<String>),
+    /// No theme file, the built-in palette is showing
+    Builtin,
+}
+
+/// Watches the active theme file and applies it whenever it changes on disk
+#[derive(Default)]
+pub struct ThemeWatcher {
+    applied: Option<(Appearance, PaletteVariant)>,
+    loaded: Option<(PathBuf, SystemTime)>,
+    last_check: Option<Instant>,
+}
+
+impl ThemeWatcher {
+    /// The appearance and palette last applied
+    pub fn applied(&self) -> Option<(Appearance, PaletteVariant)> {
+        self.applied
+    }
+
+    /// Reload the theme if the palette switched, or its file appeared, changed or went away, as of `now`
+    pub fn poll(&mut self, appearance: Appearance, variant: PaletteVariant, file: &str, now: Instant) -> Option<ThemeReload> {
+        let switched = self.applied != Some((appearance, variant));
+        if !switched && self.last_check.is_some_and(|last| now.saturating_duration_since(last) < THEME_POLL_INTERVAL) {
+            return None;
+        }
+        self.last_check = Some(now);
+
+        let current = config::find_config_file(file).and_then(|path| {
+            let modified = std::fs::metadata(&path).and_then(|meta| meta.modified()).ok()?;
+            Some((path, modified))
+        });
+
+        if !switched && current == self.loaded {
+            return None;
+        }
+        self.applied = Some((appearance, variant));
+        self.loaded = current.clone();
+
+        let base = Colors::builtin(appearance, variant);
+        let Some((path, _)) = current else {
+            set_colors(base);
+            return Some(ThemeReload::Builtin);
+        };
+
+        let source = match std::fs::read_to_string(&path) {
+            Ok(source) => source,
+            Err(e) => {
+                set_colors(base);
+                return Some(ThemeReload::Invalid(path, vec![e.to_string()]));
+            }
+        };
+
+        // Valid tokens still apply when others are broken
+        let (colors, problems) = Colors::from_theme_file(base, &source);
+        set_colors(colors);
+
+        if problems.is_empty() {
+            Some(ThemeReload::Applied(path))
+        } else {
+            Some(ThemeReload::Invalid(path, problems))
+        }
+    }
 }
 
 // Channel list styles
 pub fn channel_button_active(_theme: &Theme) -> button::Style {
     button::Style {
-        background: Some(Background::Color(Colors::SURFACE_LIGHT)),
-        text_color: Colors::PRIMARY,
+        background: Some(Background::Color(colors().surface_light)),
+        text_color: colors().primary,
         border: Border {
             width: 0.0,
             radius: 4.0.into(),
@@ -36,7 +284,7 @@ pub fn channel_button_active(_theme: &Theme) -> button::Style {
 pub fn channel_button_inactive(_theme: &Theme) -> button::Style {
     button::Style {
         background: Some(Background::Color(Color::TRANSPARENT)),
-        text_color: Colors::TEXT_DIM,
+        text_color: colors().text_dim,
         border: Border {
             width: 0.0,
             radius: 4.0.into(),
@@ -49,7 +297,7 @@ pub fn channel_button_inactive(_theme: &Theme) -> button::Style {
 // Sidebar style
 pub fn sidebar_container(_theme: &Theme) -> container::Style {
     container::Style {
-        background: Some(Background::Color(Colors::SURFACE)),
+        background: Some(Background::Color(colors().surface)),
         border: Border {
             width: 0.0,
             radius: 0.0.into(),
@@ -62,7 +310,7 @@ pub fn sidebar_container(_theme: &Theme) -> container::Style {
 // Message styles
 pub fn user_message_container(_theme: &Theme) -> container::Style {
     container::Style {
-        background: Some(Background::Color(Colors::SURFACE_LIGHT)),
+        background: Some(Background::Color(colors().user)),
         border: Border {
             width: 0.0,
             radius: 8.0.into(),
@@ -78,7 +326,20 @@ pub fn assistant_message_container(_theme: &Theme) -> container::Style {
         border: Border {
             width: 1.0,
             radius: 8.0.into(),
-            color: Colors::BORDER,
+            color: colors().assistant,
+        },
+        ..Default::default()
+    }
+}
+
+// Failure details inside a message bubble
+pub fn error_banner(_theme: &Theme) -> container::Style {
+    container::Style {
+        background: Some(Background::Color(colors().error.scale_alpha(0.12))),
+        border: Border {
+            width: 1.0,
+            radius: 6.0.into(),
+            color: colors().error,
         },
         ..Default::default()
     }
@@ -87,21 +348,43 @@ pub fn assistant_message_container(_theme: &Theme) -> container::Style {
 // Input style
 pub fn input_style(_theme: &Theme, _status: text_input::Status) -> text_input::Style {
     text_input::Style {
-        background: Background::Color(Colors::SURFACE),
+        background: Background::Color(colors().surface),
+        border: Border {
+            width: 1.0,
+            radius: 8.0.into(),
+            color: colors().border,
+        },
+        icon: Color::TRANSPARENT,
+        placeholder: Color {
+            a: 0.7,
+            ..colors().text_dim
+        },
+        value: colors().text,
+        selection: Color {
+            a: 0.3,
+            ..colors().primary
+        },
+    }
+}
+
+// Composer style, matches the single line inputs
+pub fn editor_style(_theme: &Theme, _status: text_editor::Status) -> text_editor::Style {
+    text_editor::Style {
+        background: Background::Color(colors().surface),
         border: Border {
             width: 1.0,
             radius: 8.0.into(),
-            color: Colors::BORDER,
+            color: colors().border,
         },
         icon: Color::TRANSPARENT,
         placeholder: Color {
             a: 0.7,
-            ..Colors::TEXT_DIM
+            ..colors().text_dim
         },
-        value: Colors::TEXT,
+        value: colors().text,
         selection: Color {
             a: 0.3,
-            ..Colors::PRIMARY
+            ..colors().primary
         },
     }
 }
@@ -109,11 +392,11 @@ pub fn input_style(_theme: &Theme, _status: text_input::Status) -> text_input::S
 // Code block style
 pub fn code_container(_theme: &Theme) -> container::Style {
     container::Style {
-        background: Some(Background::Color(Colors::BACKGROUND)),
+        background: Some(Background::Color(colors().background)),
         border: Border {
             width: 1.0,
             radius: 4.0.into(),
-            color: Colors::BORDER,
+            color: colors().border,
         },
         ..Default::default()
     }
@@ -122,7 +405,7 @@ pub fn code_container(_theme: &Theme) -> container::Style {
 // Button styles
 pub fn primary_button(_theme: &Theme, _status: button::Status) -> button::Style {
     button::Style {
-        background: Some(Background::Color(Colors::PRIMARY)),
+        background: Some(Background::Color(colors().primary)),
         text_color: Color::WHITE,
         border: Border {
             width: 0.0,
@@ -136,11 +419,22 @@ pub fn primary_button(_theme: &Theme, _status: button::Status) -> button::Style
 pub fn add_button(_theme: &Theme, _status: button::Status) -> button::Style {
     button::Style {
         background: Some(Background::Color(Color::TRANSPARENT)),
-        text_color: Colors::TEXT_DIM,
+        text_color: colors().text_dim,
         border: Border {
             width: 1.0,
             radius: 4.0.into(),
-            color: Colors::BORDER,
+            color: colors().border,
+        },
+        ..Default::default()
+    }
+}
+
+pub fn icon_button(_theme: &Theme, status: button::Status) -> button::Style {
+    button::Style {
+        background: Some(Background::Color(Color::TRANSPARENT)),
+        text_color: match status {
+            button::Status::Hovered | button::Status::Pressed => colors().text,
+            _ => colors().text_dim,
         },
         ..Default::default()
     }
@@ -148,7 +442,7 @@ pub fn add_button(_theme: &Theme, _status: button::Status) -> button::Style {
 
 pub fn primary_button_style(_theme: &Theme, _status: button::Status) -> button::Style {
     button::Style {
-        background: Some(Background::Color(Colors::PRIMARY)),
+        background: Some(Background::Color(colors().primary)),
         text_color: Color::WHITE,
         border: Border {
             width: 0.0,
@@ -161,13 +455,64 @@ pub fn primary_button_style(_theme: &Theme, _status: button::Status) -> button::
 
 pub fn secondary_button(_theme: &Theme, _status: button::Status) -> button::Style {
     button::Style {
-        background: Some(Background::Color(Colors::SURFACE_LIGHT)),
-        text_color: Colors::TEXT,
+        background: Some(Background::Color(colors().surface_light)),
+        text_color: colors().text,
         border: Border {
             width: 1.0,
             radius: 6.0.into(),
-            color: Colors::BORDER,
+            color: colors().border,
         },
         ..Default::default()
     }
-}
\ No newline at end of file
+}
+
+// Scrollbar style, a thin scroller that only stands out when touched
+pub fn scrollbar(_theme: &Theme, status: scrollable::Status) -> scrollable::Style {
+    let scroller_color = match status {
+        scrollable::Status::Dragged { .. } => colors().primary,
+        scrollable::Status::Hovered { is_vertical_scrollbar_hovered: true, .. } => colors().text_dim,
+        _ => colors().border,
+    };
+    let rail = scrollable::Rail {
+        background: None,
+        border: Border::default(),
+        scroller: scrollable::Scroller {
+            color: scroller_color,
+            border: Border {
+                radius: 3.0.into(),
+                ..Default::default()
+            },
+        },
+    };
+
+    scrollable::Style {
+        container: container::Style::default(),
+        vertical_rail: rail,
+        horizontal_rail: rail,
+        gap: None,
+    }
+}
+
+// Timeline scrubber, only the handle shows over the turn strip
+pub fn timeline_scrubber(_theme: &Theme, status: slider::Status) -> slider::Style {
+    let handle = match status {
+        slider::Status::Dragged => colors().primary,
+        _ => colors().text,
+    };
+    slider::Style {
+        rail: slider::Rail {
+            backgrounds: (Background::Color(Color::TRANSPARENT), Background::Color(Color::TRANSPARENT)),
+            width: 0.0,
+            border: Border::default(),
+        },
+        handle: slider::Handle {
+            shape: slider::HandleShape::Rectangle {
+                width: 3,
+                border_radius: 1.0.into(),
+            },
+            background: Background::Color(handle),
+            border_width: 0.0,
+            border_color: Color::TRANSPARENT,
+        },
+    }
+}
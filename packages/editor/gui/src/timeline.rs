@@ -0,0 +1,136 @@
+use chrono::{DateTime, Utc};
+use iced::widget::{container, row, slider, text, Row, Space, Stack};
+use iced::{Background, Color, Element, Length, Padding, Theme};
+use iced::Task as Command;
+
+use crate::state::Conversation;
+use crate::types::MessageStatus;
+use crate::{fonts, format, minimap, scrolling, theme, BickyApp, Message};
+
+// Turns closer together than this share a cell
+const CELLS: usize = 120;
+const STRIP_HEIGHT: f32 = 12.0;
+
+/// Where each turn falls in the session, 0 for the first and 1 for the last
+///
+/// Turns are spaced by when they were sent, or evenly when some have no timestamp.
+pub fn turn_positions(conv: &Conversation) -> Vec<f32> {
+    let count = conv.messages.len();
+    let evenly = || (0..count).map(|idx| idx as f32 / (count.max(2) - 1) as f32).collect();
+
+    let Some(times) = conv.messages.iter().map(|m| m.sent_at).collect::<Option<Vec<DateTime<Utc>>>>() else {
+        return evenly();
+    };
+    let (Some(first), Some(last)) = (times.iter().min(), times.iter().max()) else {
+        return evenly();
+    };
+    let span = (*last - *first).num_milliseconds();
+    if span <= 0 {
+        return evenly();
+    }
+
+    times
+        .iter()
+        .map(|time| (*time - *first).num_milliseconds() as f32 / span as f32)
+        .collect()
+}
+
+/// The turn closest to a point on the timeline
+pub fn turn_at(positions: &[f32], value: f32) -> Option<usize> {
+    positions
+        .iter()
+        .enumerate()
+        .min_by(|(_, a), (_, b)| (*a - value).abs().total_cmp(&(*b - value).abs()))
+        .map(|(idx, _)| idx)
+}
+
+// Answered turns take Wake's color, ones still waiting on it the user's
+fn turn_color(status: &MessageStatus) -> Color {
+    match status {
+        MessageStatus::Completed => theme::colors().assistant,
+        MessageStatus::Error | MessageStatus::Failed => theme::colors().error,
+        MessageStatus::Cancelled => theme::colors().text_dim,
+        MessageStatus::Processing | MessageStatus::WaitingForPermission => theme::colors().spinner,
+        MessageStatus::Pending => theme::colors().user,
+    }
+}
+
+impl BickyApp {
+    /// Timeline of turns above the transcript, `None` until there's a history to scrub
+    pub(crate) fn build_timeline<'a>(&'a self, conv: &'a Conversation) -> Option<Element<'a, Message>> {
+        if conv.messages.len() < 2 {
+            return None;
+        }
+
+        let positions = turn_positions(conv);
+        let mut cells: Vec<Option<Color>> = vec![None; CELLS];
+        for (msg, position) in conv.messages.iter().zip(&positions) {
+            let cell = (position * (CELLS - 1) as f32).round() as usize;
+            cells[cell.min(CELLS - 1)] = Some(turn_color(&msg.status));
+        }
+
+        let strip = Row::with_children(cells.into_iter().map(|color| {
+            container(Space::new(Length::Fill, Length::Fill))
+                .width(Length::FillPortion(1))
+                .height(Length::Fill)
+                .style(move |_theme: &Theme| container::Style {
+                    background: Some(Background::Color(color.unwrap_or(theme::colors().surface_light))),
+                    ..Default::default()
+                })
+                .into()
+        }))
+        .spacing(1)
+        .height(Length::Fixed(STRIP_HEIGHT));
+
+        // Follow the transcript unless the handle is being dragged
+        let value = self.timeline_scrub.unwrap_or_else(|| self.timeline_position(conv, &positions));
+        let scrubber = slider(0.0..=1.0, value, Message::TimelineScrubbed)
+            .on_release(Message::TimelineReleased)
+            .step(0.001f32)
+            .height(STRIP_HEIGHT)
+            .style(theme::timeline_scrubber);
+
+        let now = self.state.clock.utc();
+        let time_label = |time: Option<DateTime<Utc>>| {
+            text(time.map(|time| format::locale().timestamp(time, now)).unwrap_or_default())
+                .size(11)
+                .font(fonts::mono())
+                .color(theme::colors().text_dim)
+        };
+
+        Some(
+            container(
+                row![
+                    time_label(conv.messages.first().and_then(|m| m.sent_at)),
+                    Stack::new().push(strip).push(scrubber).width(Length::Fill).height(Length::Fixed(STRIP_HEIGHT)),
+                    time_label(conv.messages.last().and_then(|m| m.sent_at)),
+                ]
+                .spacing(8)
+                .align_y(iced::Alignment::Center),
+            )
+            .padding(Padding { top: 6.0, bottom: 6.0, left: 16.0, right: 16.0 })
+            .into(),
+        )
+    }
+
+    // Timeline position of the turn at the top of the transcript
+    fn timeline_position(&self, conv: &Conversation, positions: &[f32]) -> f32 {
+        let Some((top, _)) = self.transcript_scroll.visible_range() else {
+            return 1.0;
+        };
+        let turn = minimap::message_starts(conv).iter().rposition(|start| *start <= top).unwrap_or(0);
+        positions.get(turn).copied().unwrap_or(1.0)
+    }
+
+    /// Scroll to the turn nearest where the timeline was dragged to
+    pub(crate) fn scrub_timeline(&mut self, value: f32) -> Command<Message> {
+        let Some(conv) = self.state.active_conversation.and_then(|idx| self.state.conversations.get(idx)) else {
+            return Command::none();
+        };
+        let Some(turn) = turn_at(&turn_positions(conv), value) else {
+            return Command::none();
+        };
+        let start = minimap::message_starts(conv).get(turn).copied().unwrap_or(0.0);
+        self.jump_transcript(scrolling::Jump::Fraction(start))
+    }
+}
@@ -0,0 +1,138 @@
+use chrono::{DateTime, Utc};
+use iced::widget::{button, column, container, row, text, Space};
+use iced::{Element, Length};
+
+use crate::types::{LegacyMessage, ResponseTimings};
+use crate::{fonts, format, theme, BickyApp, Message};
+use bicky_editor_core::clock;
+
+// Bars are laid out in this many parts of the whole request
+const RESOLUTION: f64 = 1000.0;
+
+const LABEL_WIDTH: f32 = 170.0;
+
+// Each stage by the name of the time it ends at, starting with the client send
+const STAGES: [&str; 5] = ["Network", "Queue", "Setup", "First token", "Generation"];
+
+/// One bar of the waterfall
+#[derive(Debug, Clone, PartialEq)]
+pub struct Phase {
+    pub name: String,
+    pub start: DateTime<Utc>,
+    pub end: DateTime<Utc>,
+}
+
+impl Phase {
+    pub fn millis(&self) -> u64 {
+        (self.end - self.start).num_milliseconds().max(0) as u64
+    }
+}
+
+/// Timings from an assistant message's metadata, `completed` standing in when the server didn't say
+pub fn from_server(
+    meta: &serde_json::Value,
+    completed: Option<DateTime<Utc>>,
+    to_local: impl Fn(DateTime<Utc>) -> DateTime<Utc>,
+) -> Option<ResponseTimings> {
+    let at = |key: &str| {
+        meta.pointer(&format!("/timings/{}", key))
+            .and_then(|t| t.as_str())
+            .and_then(clock::parse_timestamp)
+            .map(&to_local)
+    };
+    let timings = ResponseTimings {
+        started_at: at("startedAt"),
+        model_started_at: at("modelStartedAt"),
+        first_token_at: at("firstTokenAt"),
+        completed_at: at("completedAt").or(completed),
+    };
+    (timings != ResponseTimings::default()).then_some(timings)
+}
+
+/// The stages between the times we know, a stage with no end time is folded into the next
+pub fn phases(msg: &LegacyMessage, sent_locally: Option<DateTime<Utc>>) -> Vec<Phase> {
+    let timings = msg.metadata.as_ref().and_then(|m| m.timings.clone()).unwrap_or_default();
+    let points = [
+        sent_locally,
+        msg.sent_at,
+        timings.started_at,
+        timings.model_started_at,
+        timings.first_token_at,
+        timings.completed_at,
+    ];
+
+    let mut phases = Vec::new();
+    let mut last: Option<(usize, DateTime<Utc>)> = None;
+    for (idx, point) in points.iter().enumerate() {
+        let Some(time) = *point else {
+            continue;
+        };
+        if let Some((from, start)) = last {
+            phases.push(Phase {
+                name: STAGES[from..idx].join(" + "),
+                start,
+                // Clocks that disagree shouldn't draw bars backwards
+                end: time.max(start),
+            });
+        }
+        last = Some((idx, last.map_or(time, |(_, start)| time.max(start))));
+    }
+    phases
+}
+
+impl BickyApp {
+    /// A "timings" toggle under the response, opening the waterfall of where the time went
+    pub(crate) fn build_timings<'a>(&'a self, msg: &'a LegacyMessage) -> Option<Element<'a, Message>> {
+        let phases = phases(msg, self.state.sent_locally.get(&msg.id).copied());
+        let first = phases.first()?;
+        let last = phases.last()?;
+        let total = (last.end - first.start).num_milliseconds().max(1) as f64;
+
+        let open = self.state.open_timings.contains(&msg.id);
+        let toggle = button(
+            text(format!("{} timings {}", if open { "▾" } else { "▸" }, format::locale().seconds(total as u64)))
+                .size(11)
+                .font(fonts::mono())
+                .color(theme::colors().text_dim),
+        )
+        .on_press(Message::ToggleTimings(msg.id.clone()))
+        .padding(4)
+        .style(theme::icon_button);
+
+        if !open {
+            return Some(toggle.into());
+        }
+
+        // Portions of the whole, so bars line up however wide the transcript is
+        let portion = |millis: i64| (millis.max(0) as f64 / total * RESOLUTION).round() as u16;
+        let bars = phases.iter().fold(column![].spacing(4), |bars, phase| {
+            let before = portion((phase.start - first.start).num_milliseconds());
+            let width = portion(phase.millis() as i64).max(1);
+            let after = (RESOLUTION as u16).saturating_sub(before + width);
+
+            let bar = container(Space::new(Length::Fill, 8)).style(|_| container::Style {
+                background: Some(theme::colors().primary.into()),
+                ..Default::default()
+            });
+            bars.push(
+                row![
+                    container(text(phase.name.clone()).size(11).font(fonts::mono())).width(LABEL_WIDTH),
+                    row![]
+                        .push_maybe((before > 0).then(|| Space::with_width(Length::FillPortion(before))))
+                        .push(container(bar).width(Length::FillPortion(width)))
+                        .push_maybe((after > 0).then(|| Space::with_width(Length::FillPortion(after))))
+                        .width(Length::Fill)
+                        .align_y(iced::Alignment::Center),
+                    text(format::locale().seconds(phase.millis()))
+                        .size(11)
+                        .font(fonts::mono())
+                        .color(theme::colors().text_dim),
+                ]
+                .spacing(8)
+                .align_y(iced::Alignment::Center),
+            )
+        });
+
+        Some(column![toggle, container(bars).padding([4, 8])].into())
+    }
+}
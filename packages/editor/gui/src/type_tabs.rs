@@ -0,0 +1,69 @@
+use iced::widget::{button, container, row, text};
+use iced::{Element, Length};
+
+use bicky_editor_core::types::InteractionType;
+
+use crate::state::{self, AppState};
+use crate::{fonts, theme, BickyApp, Message};
+
+/// Tab label for conversations of one type
+pub fn plural(interaction_type: &InteractionType) -> &'static str {
+    match interaction_type {
+        InteractionType::Query => "Queries",
+        InteractionType::Action => "Actions",
+        InteractionType::Observation => "Observations",
+        InteractionType::Feedback => "Feedback",
+        InteractionType::System => "System",
+        InteractionType::Reflection => "Reflections",
+    }
+}
+
+/// Conversations of each type in the sidebar right now, types without any left out
+pub fn counts(state: &AppState) -> Vec<(InteractionType, usize)> {
+    InteractionType::ALL
+        .into_iter()
+        .map(|interaction_type| {
+            let count = state
+                .conversations
+                .iter()
+                .filter(|conv| !conv.archived && state::on_active_server(state, conv))
+                .filter(|conv| conv.interaction_type == interaction_type)
+                .count();
+            (interaction_type, count)
+        })
+        .filter(|(_, count)| *count > 0)
+        .collect()
+}
+
+impl BickyApp {
+    /// "All" and a tab per interaction type, hidden while there's only one type to show
+    pub(crate) fn build_type_tabs(&self) -> Option<Element<'_, Message>> {
+        let filter = &self.state.interaction_filter;
+        let counts = counts(&self.state);
+        if counts.len() < 2 && filter.types.is_empty() {
+            return None;
+        }
+
+        let tab = |label: String, selected: bool, message: Message| {
+            button(text(label).size(11).font(fonts::mono()))
+                .on_press(message)
+                .padding([4, 8])
+                .style(move |theme, _| {
+                    if selected {
+                        theme::channel_button_active(theme)
+                    } else {
+                        theme::channel_button_inactive(theme)
+                    }
+                })
+        };
+
+        let all = tab("All".to_string(), filter.types.is_empty(), Message::FilterByType(None));
+        let tabs = counts.into_iter().fold(row![all].spacing(4), |tabs, (interaction_type, count)| {
+            let selected = filter.types.contains(&interaction_type);
+            let label = format!("{} {}", plural(&interaction_type), count);
+            tabs.push(tab(label, selected, Message::FilterByType(Some(interaction_type))))
+        });
+
+        Some(container(tabs.wrap()).padding([4, 16]).width(Length::Fill).into())
+    }
+}
@@ -1,19 +1,25 @@
+use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+pub use bicky_editor_core::types::{MessageError, Worktree, WorktreeGitStatus, WorktreeStatus};
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct TokenUsage {
     pub input: u32,
     pub output: u32,
     pub total: u32,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
 pub struct InteractionMetadata {
     // Result metadata
     pub tokens: Option<TokenUsage>,
     pub model: Option<String>,
     pub processing_time_ms: Option<u64>,
     pub tools_used: Option<Vec<String>>,
+    /// Tool calls with their arguments, when the server reports them
+    #[serde(default)]
+    pub tool_calls: Option<Vec<ToolCall>>,
     
     // Processing state
     pub current_action: Option<String>,
@@ -23,26 +29,56 @@ pub struct InteractionMetadata {
     // Context
     pub worktree_context: Option<WorktreeContext>,
     pub tags: Option<Vec<String>>,
+    
+    /// When each stage of answering happened, in local time
+    #[serde(default)]
+    pub timings: Option<ResponseTimings>,
+    /// The agent that answered, when more than one works on the interaction
+    #[serde(default)]
+    pub agent_id: Option<String>,
+    /// Files the tools produced while answering
+    #[serde(default)]
+    pub artifacts: Option<Vec<Artifact>>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+/// The server's `ResponseTimings`, any stage it didn't report is `None`
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ResponseTimings {
+    pub started_at: Option<DateTime<Utc>>,
+    pub model_started_at: Option<DateTime<Utc>>,
+    pub first_token_at: Option<DateTime<Utc>>,
+    pub completed_at: Option<DateTime<Utc>>,
+}
+
+/// One tool call the agent made, in the server's `ToolCall` shape
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ToolCall {
+    pub name: String,
+    #[serde(default)]
+    pub arguments: serde_json::Value,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct WorktreeContext {
     pub id: String,
     pub path: String,
     pub branch: Option<String>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct ToolPermissionRequest {
     #[serde(rename = "toolName")]
     pub tool_name: String,
     pub description: String,
     #[serde(rename = "requestId")]
     pub request_id: Option<String>,
+    /// The tool's arguments, e.g. the shell command or the file and edit
+    #[serde(default)]
+    pub input: Option<serde_json::Value>,
 }
 
 // Old Message type for backward compatibility
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct LegacyMessage {
     pub id: String,
     pub content: String,
@@ -50,47 +86,46 @@ pub struct LegacyMessage {
     pub status: MessageStatus,
     pub metadata: Option<InteractionMetadata>,
     pub pending_tool_permission: Option<ToolPermissionRequest>,
+    /// When it was sent, on our clock
+    #[serde(default)]
+    pub sent_at: Option<DateTime<Utc>>,
+    /// What went wrong, from the server or while sending
+    #[serde(default)]
+    pub error: Option<MessageError>,
+    /// Files sent along with it
+    #[serde(default)]
+    pub attachments: Vec<Attachment>,
 }
 
-// New message type matching server format
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct Message {
+/// A file uploaded to the server, sent with a message by id
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Attachment {
     pub id: String,
-    #[serde(rename = "interactionId")]
-    pub interaction_id: String,
-    pub role: MessageRole,
-    pub content: String,
-    pub timestamp: String,
-    pub status: MessageStatus,
-    pub metadata: Option<serde_json::Value>,
+    pub name: String,
+    /// In bytes
+    pub size: u64,
+    #[serde(default)]
+    pub content_type: Option<String>,
 }
 
+/// A file a tool produced, kept by the server and downloaded by id
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
-#[serde(rename_all = "lowercase")]
-pub enum MessageRole {
-    User,
-    Assistant,
-    System,
-    Tool,
-}
-
-// Conversation structure from server
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct Conversation {
-    pub interaction: Interaction,
-    pub messages: Vec<Message>,
-}
-
-// Interaction structure from server
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct Interaction {
+#[serde(rename_all = "camelCase")]
+pub struct Artifact {
     pub id: String,
-    pub source: String,
-    #[serde(rename = "type")]
-    pub interaction_type: String,
-    #[serde(rename = "createdAt")]
-    pub created_at: String,
-    pub metadata: Option<serde_json::Value>,
+    pub name: String,
+    /// Where the tool wrote it, relative to the worktree when it was inside one
+    #[serde(default)]
+    pub path: Option<String>,
+    /// In bytes
+    #[serde(default)]
+    pub size: Option<u64>,
+    #[serde(default)]
+    pub content_type: Option<String>,
+    /// The tool that produced it
+    #[serde(default)]
+    pub tool: Option<String>,
 }
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
@@ -102,6 +137,8 @@ pub enum MessageStatus {
     Error,
     Failed,
     WaitingForPermission,
+    /// Interrupted before it finished
+    Cancelled,
 }
 
 
@@ -110,35 +147,60 @@ pub struct SendMessageResponse {
     pub id: String,
     #[serde(rename = "type")]
     pub status: String,
+    /// The server's id for the message, older servers don't send it
+    #[serde(rename = "messageId", default)]
+    pub message_id: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct Worktree {
-    pub id: String,
-    pub path: String,
-    pub branch: Option<String>,
-    #[serde(rename = "baseCommit")]
-    pub base_commit: Option<String>,
-    pub status: WorktreeStatus,
-    #[serde(rename = "createdAt")]
-    pub created_at: String,
+pub struct SendMessageRequest {
+    pub content: String,
+    pub metadata: Option<serde_json::Value>,
+    #[serde(rename = "worktreeId")]
+    pub worktree_id: Option<String>,
+    #[serde(rename = "interactionId")]
+    pub interaction_id: Option<String>,
+    #[serde(rename = "attachmentIds", default, skip_serializing_if = "Vec::is_empty")]
+    pub attachment_ids: Vec<String>,
+}
+
+/// A finished conversation from another tool, the server stores it without answering
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ImportConversationRequest {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
+    /// Which tool's transcript it was, e.g. "claude-code"
+    pub from: String,
+    pub messages: Vec<ImportedMessage>,
 }
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ImportedMessage {
+    pub role: ImportedRole,
+    pub content: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub timestamp: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "lowercase")]
-pub enum WorktreeStatus {
-    Active,
-    Inactive,
+pub enum ImportedRole {
+    User,
+    Assistant,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct SendMessageRequest {
-    pub content: String,
+pub struct ImportConversationResponse {
+    pub id: String,
+    #[serde(rename = "messageCount")]
+    pub message_count: usize,
+}
+
+/// A result posted to an interaction on behalf of an agent
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SubmitResultRequest {
+    pub response: String,
     pub metadata: Option<serde_json::Value>,
-    #[serde(rename = "worktreeId")]
-    pub worktree_id: Option<String>,
-    #[serde(rename = "interactionId")]
-    pub interaction_id: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -147,4 +209,102 @@ pub struct CreateWorktreeRequest {
     #[serde(rename = "baseBranch")]
     pub base_branch: Option<String>,
     pub path: Option<String>,
-}
\ No newline at end of file
+}
+
+/// A worktree's changes since it was branched off
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WorktreeDiff {
+    pub base: String,
+    pub diff: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MergeWorktreeRequest {
+    pub message: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MergeResult {
+    pub commit: String,
+    pub base: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CreatePullRequestRequest {
+    pub title: String,
+    pub body: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PullRequestResult {
+    pub url: String,
+    pub base: String,
+}
+
+/// An agent the server can put to work on interactions
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Agent {
+    pub id: String,
+    pub name: String,
+    #[serde(default)]
+    pub description: Option<String>,
+    /// How it's set up, servers that don't say leave it `None`
+    #[serde(default)]
+    pub config: Option<AgentConfig>,
+}
+
+/// How an agent works, what `update_agent_config` sends
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AgentConfig {
+    pub system_prompt: Option<String>,
+    pub temperature: Option<f32>,
+    pub max_tokens: Option<u32>,
+    /// Tools it may use, every tool when `None`
+    pub allowed_tools: Option<Vec<String>>,
+}
+
+/// How an agent is doing, anything newer than we know is `Unknown`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum AgentHealth {
+    Idle,
+    Busy,
+    Crashed,
+    Restarting,
+    #[serde(other)]
+    Unknown,
+}
+
+/// One agent's entry in `GET /agents/status`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AgentStatus {
+    pub agent_id: String,
+    pub status: AgentHealth,
+    /// When it last started, for its uptime
+    #[serde(default)]
+    pub started_at: Option<DateTime<Utc>>,
+    /// Interaction it's working on
+    #[serde(default)]
+    pub current_interaction: Option<String>,
+    /// What it's doing there, e.g. the tool it's running
+    #[serde(default)]
+    pub current_task: Option<String>,
+    /// Why it crashed
+    #[serde(default)]
+    pub error: Option<String>,
+}
+
+/// An interaction's tags, what replacing them answers with
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct InteractionTags {
+    pub tags: Vec<String>,
+}
+
+/// Agents attached to an interaction, what attaching and detaching answer with
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AttachedAgents {
+    #[serde(rename = "attachedAgents")]
+    pub attached_agents: Vec<String>,
+}
@@ -0,0 +1 @@
+pub mod multiline_input;
\ No newline at end of file
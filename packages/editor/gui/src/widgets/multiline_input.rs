@@ -1,43 +1,301 @@
-use iced::widget::{container, text_input};
-use iced::{Element, Length, Border, Color};
+use iced::keyboard::{key::Named, Key};
+use iced::widget::{container, text_editor};
+use iced::widget::text_editor::{Action, Binding, Content, Edit, KeyPress, Motion, Status};
+use iced::{Element, Length};
+use std::sync::Arc;
 
+use crate::{fonts, mentions, theme};
+
+/// The message composer: Enter sends, Shift+Enter starts a new line
 pub struct MultilineInput {
     placeholder: String,
+    /// The editor grows with its content up to this height, then scrolls
+    max_height: f32,
 }
 
 impl MultilineInput {
     pub fn new() -> Self {
         Self {
-            placeholder: "Type a message... (Enter to send)".to_string(),
+            placeholder: "Message Wake... (Shift+Enter for a new line)".to_string(),
+            max_height: 240.0,
         }
     }
 }
 
+/// Messages produced by the composer on top of plain text editing
+#[derive(Debug, Clone)]
+pub enum ComposerMessage {
+    Edit(Action),
+    Submit,
+    /// Enter pressed inside a list or a code fence
+    Newline,
+    /// Wrap the selection in a markdown emphasis marker
+    Wrap(&'static str),
+    /// Keys redirected to the `@` completion list while it's open
+    NextCompletion,
+    PreviousCompletion,
+    AcceptCompletion,
+}
+
 pub fn multiline_input_view<'a, Message>(
     input: &'a MultilineInput,
-    value: &'a str,
-    on_change: impl Fn(String) -> Message + 'a,
-    on_submit: Message,
-) -> Element<'a, Message> 
+    content: &'a Content,
+    completing: bool,
+    on_message: fn(ComposerMessage) -> Message,
+) -> Element<'a, Message>
 where
     Message: Clone + 'a,
 {
-    let input_widget = text_input(&input.placeholder, value)
-        .on_input(on_change)
-        .on_submit(on_submit)
+    let editor = text_editor(content)
+        .placeholder(input.placeholder.as_str())
+        .on_action(move |action| on_message(ComposerMessage::Edit(action)))
+        .key_binding(move |key_press| key_binding(content, key_press, completing, on_message))
+        .height(Length::Shrink)
+        .padding(12)
         .size(14)
-        .padding(12);
-
-    container(input_widget)
-        .width(Length::Fill)
-        .style(|_theme| container::Style {
-            background: Some(iced::Background::Color(Color::from_rgb(0.15, 0.15, 0.15))),
-            border: Border {
-                color: Color::from_rgb(0.3, 0.3, 0.3),
-                width: 1.0,
-                radius: 8.0.into(),
-            },
-            ..Default::default()
-        })
-        .into()
-}
\ No newline at end of file
+        .font(fonts::mono())
+        .style(theme::editor_style);
+
+    container(editor).max_height(input.max_height).into()
+}
+
+fn key_binding<Message>(
+    content: &Content,
+    key_press: KeyPress,
+    completing: bool,
+    on_message: fn(ComposerMessage) -> Message,
+) -> Option<Binding<Message>> {
+    if key_press.status != Status::Focused {
+        return None;
+    }
+
+    let modifiers = key_press.modifiers;
+
+    if completing {
+        let completion = match key_press.key.as_ref() {
+            Key::Named(Named::ArrowDown) => Some(ComposerMessage::NextCompletion),
+            Key::Named(Named::ArrowUp) => Some(ComposerMessage::PreviousCompletion),
+            Key::Named(Named::Tab) => Some(ComposerMessage::AcceptCompletion),
+            Key::Named(Named::Enter) if !modifiers.shift() => Some(ComposerMessage::AcceptCompletion),
+            // Let Escape through to the app so it closes the list without unfocusing
+            Key::Named(Named::Escape) => return None,
+            _ => None,
+        };
+        if let Some(completion) = completion {
+            return Some(Binding::Custom(on_message(completion)));
+        }
+    }
+
+    match key_press.key.as_ref() {
+        Key::Named(Named::Enter) if !modifiers.shift() => {
+            if continues_block(content) {
+                Some(Binding::Custom(on_message(ComposerMessage::Newline)))
+            } else {
+                Some(Binding::Custom(on_message(ComposerMessage::Submit)))
+            }
+        }
+        Key::Character("b") if modifiers.command() => {
+            Some(Binding::Custom(on_message(ComposerMessage::Wrap("**"))))
+        }
+        Key::Character("i") if modifiers.command() => {
+            Some(Binding::Custom(on_message(ComposerMessage::Wrap("*"))))
+        }
+        // Leave other chords to the global keymap
+        Key::Character(c) if modifiers.command() && !matches!(c, "c" | "x" | "v" | "a") => None,
+        _ => key_press
+            .text
+            .as_deref()
+            .and_then(committed_text)
+            .or_else(|| Binding::from_key_press(key_press)),
+    }
+}
+
+/// Insert every character of text an input method committed in one key press
+///
+/// iced keeps only the first character of a key press's text, which drops
+/// all but the first of several characters composed through XIM or IBus.
+pub fn committed_text<Message>(text: &str) -> Option<Binding<Message>> {
+    let chars: Vec<char> = text.chars().filter(|c| !c.is_control()).collect();
+    if chars.len() < 2 {
+        return None;
+    }
+    Some(Binding::Sequence(chars.into_iter().map(Binding::Insert).collect()))
+}
+
+/// Apply a composer message to the content, returns true when the message should be sent
+pub fn update(content: &mut Content, message: ComposerMessage) -> bool {
+    match message {
+        ComposerMessage::Edit(action) => {
+            let typed_backtick = matches!(action, Action::Edit(Edit::Insert('`')));
+            content.perform(normalize_paste(action));
+
+            if typed_backtick {
+                close_fence(content);
+            }
+            false
+        }
+
+        ComposerMessage::Submit => true,
+
+        ComposerMessage::Newline => {
+            let (line_index, _) = content.cursor_position();
+            let line = content.line(line_index).map(|l| l.to_string()).unwrap_or_default();
+
+            match list_item(&line).filter(|_| !inside_fence(content, line_index)) {
+                // Enter on an empty bullet ends the list
+                Some((item, _)) if item.trim().is_empty() => {
+                    content.perform(Action::Move(Motion::Home));
+                    content.perform(Action::Select(Motion::End));
+                    content.perform(Action::Edit(Edit::Backspace));
+                }
+                Some((_, marker)) => {
+                    content.perform(Action::Edit(Edit::Enter));
+                    content.perform(Action::Edit(Edit::Paste(Arc::new(marker))));
+                }
+                None => content.perform(Action::Edit(Edit::Enter)),
+            }
+            false
+        }
+
+        ComposerMessage::Wrap(marker) => {
+            match content.selection() {
+                Some(selection) => {
+                    let wrapped = format!("{}{}{}", marker, selection, marker);
+                    content.perform(Action::Edit(Edit::Paste(Arc::new(wrapped))));
+                }
+                None => {
+                    content.perform(Action::Edit(Edit::Paste(Arc::new(marker.repeat(2)))));
+                    for _ in 0..marker.len() {
+                        content.perform(Action::Move(Motion::Left));
+                    }
+                }
+            }
+            false
+        }
+
+        // The completion list lives in app state, see `complete_mention`
+        ComposerMessage::NextCompletion
+        | ComposerMessage::PreviousCompletion
+        | ComposerMessage::AcceptCompletion => false,
+    }
+}
+
+// Windows and some terminals put \r\n or bare \r on the clipboard, which the
+// editor would show as extra blank lines in pasted code. Some X11 and Wayland
+// clients also end their selection with a NUL that would be inserted as-is.
+fn normalize_paste(action: Action) -> Action {
+    match action {
+        Action::Edit(Edit::Paste(text)) if text.contains(['\r', '\0']) => {
+            let text = text.replace("\r\n", "\n").replace('\r', "\n").replace('\0', "");
+            Action::Edit(Edit::Paste(Arc::new(text)))
+        }
+        action => action,
+    }
+}
+
+/// The `@` reference being typed at the cursor, without the `@`
+pub fn mention_at_cursor(content: &Content) -> Option<String> {
+    let (line_index, column) = content.cursor_position();
+    let line = content.line(line_index)?;
+    mentions::mention_query(&line, column).map(str::to_string)
+}
+
+/// Replace the `@` reference at the cursor with a full path
+pub fn complete_mention(content: &mut Content, path: &str) {
+    let Some(query) = mention_at_cursor(content) else {
+        return;
+    };
+
+    for _ in 0..query.chars().count() {
+        content.perform(Action::Select(Motion::Left));
+    }
+    // With an empty query there's no selection and the paste lands right after the `@`
+    content.perform(Action::Edit(Edit::Paste(Arc::new(format!("{} ", path)))));
+}
+
+/// Replace the content with a template, leaving the cursor at its `{cursor}` marker
+pub fn expand_template(content: &mut Content, template: &str) {
+    let (before, after) = template.split_once("{cursor}").unwrap_or((template, ""));
+    *content = Content::with_text(&format!("{}{}", before, after));
+
+    content.perform(Action::Move(Motion::DocumentStart));
+    for _ in 0..before.matches('\n').count() {
+        content.perform(Action::Move(Motion::Down));
+    }
+    content.perform(Action::Move(Motion::Home));
+    let column = before.rsplit('\n').next().unwrap_or("").chars().count();
+    for _ in 0..column {
+        content.perform(Action::Move(Motion::Right));
+    }
+}
+
+/// Replace the content with plain text, cursor at the end
+pub fn set_text(content: &mut Content, text: &str) {
+    *content = Content::with_text(text);
+    content.perform(Action::Move(Motion::DocumentEnd));
+}
+
+/// The composer's text without the trailing newline the editor always reports
+pub fn text(content: &Content) -> String {
+    let mut text = content.text();
+    if text.ends_with('\n') {
+        text.pop();
+    }
+    text
+}
+
+// Enter keeps editing rather than sending inside lists and code fences
+fn continues_block(content: &Content) -> bool {
+    let (line_index, _) = content.cursor_position();
+    if inside_fence(content, line_index) {
+        return true;
+    }
+    content
+        .line(line_index)
+        .is_some_and(|line| list_item(&line).is_some())
+}
+
+fn is_fence(line: &str) -> bool {
+    line.trim_start().starts_with("```")
+}
+
+// An odd number of fences up to and including the cursor line means we're in a block
+fn inside_fence(content: &Content, line_index: usize) -> bool {
+    content.lines().take(line_index + 1).filter(|line| is_fence(line)).count() % 2 == 1
+}
+
+// Typing ``` on an empty line opens a block: add the closing fence below
+fn close_fence(content: &mut Content) {
+    let (line_index, _) = content.cursor_position();
+    let is_bare_fence = content.line(line_index).is_some_and(|line| line.trim() == "```");
+    let opening = content.lines().take(line_index).filter(|line| is_fence(line)).count() % 2 == 0;
+
+    if is_bare_fence && opening {
+        content.perform(Action::Edit(Edit::Paste(Arc::new("\n```".to_string()))));
+        content.perform(Action::Move(Motion::Up));
+        content.perform(Action::Move(Motion::End));
+    }
+}
+
+/// Split a markdown list line into its item text and the prefix for the next item
+pub fn list_item(line: &str) -> Option<(&str, String)> {
+    let rest = line.trim_start();
+    let indent = &line[..line.len() - rest.len()];
+
+    for bullet in ["- [ ] ", "- [x] ", "- ", "* ", "+ "] {
+        if let Some(item) = rest.strip_prefix(bullet) {
+            let next = if bullet.starts_with("- [") { "- [ ] " } else { bullet };
+            return Some((item, format!("{}{}", indent, next)));
+        }
+    }
+
+    let digits = rest.len() - rest.trim_start_matches(|c: char| c.is_ascii_digit()).len();
+    if digits > 0 {
+        if let Some(item) = rest[digits..].strip_prefix(". ") {
+            let number: u64 = rest[..digits].parse().ok()?;
+            return Some((item, format!("{}{}. ", indent, number + 1)));
+        }
+    }
+
+    None
+}
@@ -0,0 +1,203 @@
+use iced::widget::{button, column, container, row, scrollable, text, Row, Space};
+use iced::{Border, Color, Element, Length, Task as Command, Theme};
+use std::time::Duration;
+
+use crate::state::{Action, AppState, MergeMode};
+use crate::types::{Worktree, WorktreeGitStatus, WorktreeStatus};
+use crate::{fonts, scrolling, state, theme, BickyApp, Message};
+
+pub(crate) const PANEL_WIDTH: f32 = 280.0;
+// Agents write to worktrees all the time, so statuses go stale quickly
+const STATUS_POLL_INTERVAL: Duration = Duration::from_secs(5);
+
+/// The branch a worktree has checked out, falling back to its directory name
+pub fn branch_label(worktree: &Worktree) -> &str {
+    worktree
+        .branch
+        .as_deref()
+        .map(|b| b.strip_prefix("refs/heads/").unwrap_or(b))
+        .or_else(|| worktree.path.rsplit('/').next())
+        .unwrap_or(&worktree.path)
+}
+
+/// How many conversations are bound to the worktree
+pub fn conversations_using(state: &AppState, worktree_id: &str) -> usize {
+    state
+        .conversations
+        .iter()
+        .filter(|conv| conv.worktree_id.as_deref() == Some(worktree_id))
+        .count()
+}
+
+/// `●3 ↑2 ↓1` for uncommitted files, commits ahead and commits behind, nothing when clean
+pub fn status_badges(status: &WorktreeGitStatus) -> Vec<(String, Color)> {
+    let colors = theme::colors();
+    let mut badges = Vec::new();
+    if status.dirty > 0 {
+        badges.push((format!("●{}", status.dirty), colors.spinner));
+    }
+    if status.ahead > 0 {
+        badges.push((format!("↑{}", status.ahead), colors.success));
+    }
+    if status.behind > 0 {
+        badges.push((format!("↓{}", status.behind), colors.error));
+    }
+    badges
+}
+
+impl BickyApp {
+    /// Ask the server for worktree statuses every few seconds
+    pub(crate) fn poll_worktree_status(&mut self) -> Command<Message> {
+        let due = self
+            .state
+            .worktree_status_checked
+            .map_or(true, |checked| self.state.clock.since(checked) >= STATUS_POLL_INTERVAL);
+        if !due || self.state.editor.available_worktrees.is_empty() {
+            return Command::none();
+        }
+
+        state::update(&mut self.state, Action::WorktreeStatusRequested);
+        let api = self.api_client.clone();
+        self.tasks.perform(
+            "get worktree statuses",
+            self.tasks.app(),
+            async move { api.get_worktree_statuses().await },
+            Message::WorktreeStatusesLoaded,
+        )
+    }
+
+    /// Status badges for a worktree, empty until its status is known
+    pub(crate) fn worktree_badges(&self, worktree_id: &str, size: u16) -> Row<'_, Message> {
+        let badges = self
+            .state
+            .editor
+            .worktree_git_status
+            .get(worktree_id)
+            .map(status_badges)
+            .unwrap_or_default();
+
+        Row::with_children(badges.into_iter().map(|(label, color)| {
+            text(label).size(size).font(fonts::mono()).color(color).into()
+        }))
+        .spacing(6)
+    }
+
+    pub(crate) fn build_worktree_panel(&self) -> Element<'_, Message> {
+        let mut list = column![
+            row![
+                text("Worktrees").size(14).font(fonts::bold()),
+                Space::with_width(Length::Fill),
+                button(text("×").size(14).font(fonts::mono()))
+                    .on_press(Message::ToggleWorktreePanel)
+                    .padding(0)
+                    .style(theme::icon_button),
+            ]
+            .align_y(iced::Alignment::Center)
+        ]
+        .spacing(10);
+
+        if self.state.editor.available_worktrees.is_empty() {
+            list = list.push(
+                text("No worktrees").size(12).font(fonts::mono()).color(theme::colors().text_dim)
+            );
+        }
+
+        for worktree in &self.state.editor.available_worktrees {
+            let used_by = conversations_using(&self.state, &worktree.id);
+            let mut details = worktree.path.clone();
+            if worktree.status == WorktreeStatus::Inactive {
+                details.push_str(" (missing)");
+            }
+
+            let mut entry = column![
+                row![
+                    text(branch_label(worktree)).size(13).font(fonts::mono()),
+                    self.worktree_badges(&worktree.id, 12),
+                ]
+                .spacing(8),
+                text(details).size(11).font(fonts::mono()).color(theme::colors().text_dim),
+            ]
+            .spacing(6);
+
+            if used_by > 0 {
+                entry = entry.push(
+                    text(format!("Used by {} conversation{}", used_by, if used_by == 1 { "" } else { "s" }))
+                        .size(11)
+                        .font(fonts::mono())
+                        .color(theme::colors().text_dim),
+                );
+            }
+
+            entry = if self.state.confirm_delete_worktree.as_deref() == Some(worktree.id.as_str()) {
+                let warning = if used_by > 0 {
+                    "Delete it? Its conversations move back to the main checkout."
+                } else {
+                    "Delete it for good?"
+                };
+                entry
+                    .push(text(warning).size(12).font(fonts::mono()).color(theme::colors().error))
+                    .push(
+                        row![
+                            button(text("Delete").size(12).font(fonts::mono()))
+                                .on_press(Message::DeleteWorktree(worktree.id.clone()))
+                                .padding(6)
+                                .style(theme::secondary_button),
+                            button(text("Cancel").size(12).font(fonts::mono()))
+                                .on_press(Message::CancelDeleteWorktree)
+                                .padding(6)
+                                .style(theme::secondary_button),
+                        ]
+                        .spacing(6),
+                    )
+            } else {
+                entry.push(
+                    row![
+                        button(text("Diff").size(12).font(fonts::mono()))
+                            .on_press(Message::OpenDiff(worktree.id.clone()))
+                            .padding(6)
+                            .style(theme::secondary_button),
+                        button(text("Merge").size(12).font(fonts::mono()))
+                            .on_press(Message::OpenMergeDialog(worktree.id.clone(), MergeMode::Merge))
+                            .padding(6)
+                            .style(theme::secondary_button),
+                        button(text("PR").size(12).font(fonts::mono()))
+                            .on_press(Message::OpenMergeDialog(worktree.id.clone(), MergeMode::PullRequest))
+                            .padding(6)
+                            .style(theme::secondary_button),
+                        button(text("Delete").size(12).font(fonts::mono()))
+                            .on_press(Message::ConfirmDeleteWorktree(worktree.id.clone()))
+                            .padding(6)
+                            .style(theme::secondary_button),
+                    ]
+                    .spacing(6),
+                )
+            };
+
+            list = list.push(
+                container(entry)
+                    .padding(10)
+                    .width(Length::Fill)
+                    .style(|_theme: &Theme| container::Style {
+                        background: Some(iced::Background::Color(theme::colors().surface_light)),
+                        border: Border {
+                            color: theme::colors().border,
+                            width: 1.0,
+                            radius: 6.0.into(),
+                        },
+                        ..Default::default()
+                    }),
+            );
+        }
+
+        container(
+            scrollable(list.padding(16))
+                .direction(scrolling::scrollbar())
+                .style(theme::scrollbar)
+                .height(Length::Fill),
+        )
+            .width(Length::Fixed(PANEL_WIDTH))
+            .height(Length::Fill)
+            .style(theme::sidebar_container)
+            .into()
+    }
+}
@@ -0,0 +1,139 @@
+use bicky_editor_core::types::{Interaction, ServerConversation, Session};
+use reqwest::{Client, Method, RequestBuilder, Response, StatusCode};
+use serde::de::DeserializeOwned;
+use serde::Deserialize;
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::time::Duration;
+
+// Messages fetched with an interaction, older ones stay on the server
+const PAGE_SIZE: usize = 50;
+
+/// What `POST /message` answers
+#[derive(Debug, Clone, Deserialize)]
+pub struct SentMessage {
+    /// The interaction the message went to, new or followed up on
+    pub id: String,
+}
+
+// Clones share the connection pool
+#[derive(Clone)]
+pub struct ApiClient {
+    client: Client,
+    pub base_url: String,
+    api_key: Option<String>,
+}
+
+impl ApiClient {
+    pub fn new(base_url: String, api_key: Option<String>) -> Self {
+        Self {
+            client: Client::builder()
+                .connect_timeout(Duration::from_secs(5))
+                .build()
+                .unwrap_or_default(),
+            base_url,
+            api_key,
+        }
+    }
+
+    /// The same client and key, pointed at another server
+    pub fn at(&self, base_url: &str) -> Self {
+        Self { base_url: base_url.to_string(), ..self.clone() }
+    }
+
+    /// A request with the API key attached, servers without auth ignore it
+    pub fn request(&self, method: Method, path: &str) -> RequestBuilder {
+        let request = self.client.request(method, format!("{}{}", self.base_url, path));
+        match &self.api_key {
+            Some(key) => request.bearer_auth(key.trim()),
+            None => request,
+        }
+    }
+
+    pub async fn send_message(
+        &self,
+        content: &str,
+        metadata: &HashMap<String, Value>,
+        interaction_id: Option<&str>,
+    ) -> Result<SentMessage, String> {
+        let body = json!({
+            "content": content,
+            "metadata": metadata,
+            "interactionId": interaction_id,
+        });
+        read(self.request(Method::POST, "/message").json(&body)).await
+    }
+
+    /// Interactions without their messages, `get_interaction` has those
+    pub async fn get_interactions(&self) -> Result<Vec<Interaction>, String> {
+        read(self.request(Method::GET, "/interactions")).await
+    }
+
+    /// An interaction with its newest messages as content
+    pub async fn get_interaction(&self, id: &str) -> Result<Interaction, String> {
+        let request = self
+            .request(Method::GET, &format!("/interactions/{}", id))
+            .query(&[("limit", PAGE_SIZE)]);
+        read::<ServerConversation>(request).await.map(ServerConversation::into_interaction)
+    }
+
+    pub async fn respond_to_permission(&self, interaction_id: &str, approved: bool) -> Result<(), String> {
+        let request = self
+            .request(Method::POST, &format!("/interactions/{}/permission/response", interaction_id))
+            .json(&json!({ "approved": approved }));
+        read::<Value>(request).await.map(|_| ())
+    }
+
+    /// The review goes back as a result, the feedback as its text
+    pub async fn submit_review(&self, interaction_id: &str, approved: bool, feedback: Option<&str>) -> Result<(), String> {
+        let response = feedback.unwrap_or(if approved { "Approved" } else { "Changes requested" });
+        let request = self
+            .request(Method::POST, &format!("/interactions/{}/result", interaction_id))
+            .json(&json!({ "response": response, "metadata": { "review": { "approved": approved } } }));
+        read::<Value>(request).await.map(|_| ())
+    }
+
+    /// Sessions on the server, none when it only has its default session
+    pub async fn get_sessions(&self) -> Result<Vec<Session>, String> {
+        let resp = self.request(Method::GET, "/sessions").send().await.map_err(|e| e.to_string())?;
+        if without_sessions(resp.status()) {
+            return Ok(Vec::new());
+        }
+        parse(resp).await
+    }
+
+    pub async fn create_session(&self, name: Option<&str>) -> Result<Session, String> {
+        let request = self.request(Method::POST, "/sessions").json(&json!({ "name": name }));
+        let resp = request.send().await.map_err(|e| e.to_string())?;
+        if without_sessions(resp.status()) {
+            return Err("This server only has its default session".to_string());
+        }
+        parse(resp).await
+    }
+}
+
+/// Servers without session routes answer these, everything goes to the default session
+pub fn without_sessions(status: StatusCode) -> bool {
+    matches!(status, StatusCode::NOT_FOUND | StatusCode::METHOD_NOT_ALLOWED)
+}
+
+async fn read<T: DeserializeOwned>(request: RequestBuilder) -> Result<T, String> {
+    parse(request.send().await.map_err(|e| e.to_string())?).await
+}
+
+async fn parse<T: DeserializeOwned>(resp: Response) -> Result<T, String> {
+    let status = resp.status();
+    if !status.is_success() {
+        // The server says what went wrong as `error` when it can
+        let reason = resp
+            .json::<Value>()
+            .await
+            .ok()
+            .and_then(|body| body.get("error")?.as_str().map(str::to_string));
+        return Err(match reason {
+            Some(reason) => format!("{}: {}", status, reason),
+            None => status.to_string(),
+        });
+    }
+    resp.json::<T>().await.map_err(|e| e.to_string())
+}
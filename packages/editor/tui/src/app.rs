@@ -0,0 +1,301 @@
+use bicky_editor_core::action_log::ActionLog;
+use bicky_editor_core::archive::InteractionStub;
+use bicky_editor_core::clock::{Clock, SystemClock};
+use bicky_editor_core::keymap::KeyCommand;
+use bicky_editor_core::types::Interaction;
+use bicky_editor_core::{Action, EditorState, Effect, Pipeline};
+use ratatui::crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use std::sync::{Arc, Mutex};
+
+// Lines moved by PageUp and PageDown
+const SCROLL_STEP: u16 = 10;
+
+/// Where keys go
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Mode {
+    /// Moving through conversations, answering permission prompts
+    #[default]
+    Browse,
+    /// Typing into the focused conversation's draft
+    Compose,
+    /// Stepping through the action log, showing the state after each action
+    Debug,
+}
+
+/// What a key asks the main loop to do
+#[derive(Debug, Clone, PartialEq)]
+pub enum Command {
+    Dispatch(Action),
+    /// Load an interaction's messages
+    Fetch(String),
+    Quit,
+}
+
+/// The core's state, plus what only the terminal needs
+#[derive(Debug)]
+pub struct App {
+    pub state: EditorState,
+    pub mode: Mode,
+    /// Lines scrolled up from the newest message
+    pub scroll: u16,
+    /// The reducer and the middleware watching it
+    pub pipeline: Pipeline,
+    /// Every action dispatched, recorded by the pipeline
+    pub log: Arc<Mutex<ActionLog>>,
+    /// Stamps the actions that need a time, so replaying them gives the same state
+    pub clock: Arc<dyn Clock>,
+    // Where things really are while `state` shows an earlier point in the log
+    live: Option<EditorState>,
+}
+
+impl Default for App {
+    fn default() -> Self {
+        let clock: Arc<dyn Clock> = Arc::new(SystemClock);
+        let log = Arc::new(Mutex::new(ActionLog::default()));
+        let mut pipeline = Pipeline::new();
+        pipeline.register(ActionLog::recorder(log.clone(), clock.clone()));
+        Self {
+            state: EditorState::default(),
+            mode: Mode::default(),
+            scroll: 0,
+            pipeline,
+            log,
+            clock,
+            live: None,
+        }
+    }
+}
+
+impl App {
+    /// Run an action through the core pipeline, returns the effects to carry out
+    pub fn dispatch(&mut self, action: &Action) -> Vec<Effect> {
+        // Stepping through the log doesn't hold up what the server sends
+        let live = self.live.as_ref().unwrap_or(&self.state);
+        let (state, effects) = self.pipeline.dispatch(live, action);
+        match &mut self.live {
+            Some(live) => *live = state,
+            None => self.state = state,
+        }
+        effects
+    }
+
+    /// The state as it is now, whatever point in the log is being shown
+    pub fn live(&self) -> &EditorState {
+        self.live.as_ref().unwrap_or(&self.state)
+    }
+
+    /// Whether `state` is an earlier point in the log
+    pub fn inspecting(&self) -> bool {
+        self.live.is_some()
+    }
+
+    /// Conversations in the order the list shows them
+    pub fn conversations(&self) -> Vec<&Interaction> {
+        self.state.visible_interactions()
+    }
+
+    /// Conversations moved to disk, listed after the others
+    pub fn archived(&self) -> Vec<&InteractionStub> {
+        self.state.archived_interactions()
+    }
+
+    /// Every id in the list, top to bottom
+    pub fn list_ids(&self) -> Vec<String> {
+        let live = self.conversations().into_iter().map(|i| i.id.clone());
+        live.chain(self.archived().into_iter().map(|stub| stub.id.clone())).collect()
+    }
+
+    /// The conversation being looked at, `None` while drafting a new one
+    pub fn focused(&self) -> Option<&Interaction> {
+        self.state.interactions.get(self.state.focused_interaction.as_ref()?)
+    }
+
+    pub fn on_key(&mut self, key: KeyEvent) -> Vec<Command> {
+        if key.modifiers.contains(KeyModifiers::CONTROL) && key.code == KeyCode::Char('c') {
+            return vec![Command::Quit];
+        }
+        match self.mode {
+            Mode::Browse => self.browse(key),
+            Mode::Compose => self.compose(key),
+            Mode::Debug => self.debug(key),
+        }
+    }
+
+    fn browse(&mut self, key: KeyEvent) -> Vec<Command> {
+        match key.code {
+            KeyCode::Char('q') => vec![Command::Quit],
+            KeyCode::Char('j') | KeyCode::Down => self.step(1),
+            KeyCode::Char('k') | KeyCode::Up => self.step(-1),
+            KeyCode::Enter | KeyCode::Tab | KeyCode::Char('i') => {
+                self.mode = Mode::Compose;
+                Vec::new()
+            }
+            KeyCode::Char('c') => {
+                self.mode = Mode::Compose;
+                self.scroll = 0;
+                self.command(KeyCommand::NewConversation)
+            }
+            KeyCode::Char(c @ '1'..='9') => {
+                let command = KeyCommand::SwitchConversation(c as usize - '0' as usize);
+                match command.action(&self.state, self.clock.utc()) {
+                    Some(Action::FocusInteraction { interaction_id: Some(id) })
+                        if self.state.focused_interaction.as_ref() != Some(&id) => self.focus(id),
+                    _ => Vec::new(),
+                }
+            }
+            KeyCode::Char('y') | KeyCode::Char('n') => self.answer_permission(key.code == KeyCode::Char('y')),
+            KeyCode::Char('a') | KeyCode::Char('x') => self.review(key.code == KeyCode::Char('a')),
+            KeyCode::Char('E') => vec![Command::Dispatch(Action::ExportReviewReport {
+                session_id: self.state.session_id.clone(),
+            })],
+            KeyCode::Char('r') => self.state.focused_interaction.clone().map(Command::Fetch).into_iter().collect(),
+            KeyCode::PageUp => {
+                self.scroll = self.scroll.saturating_add(SCROLL_STEP);
+                Vec::new()
+            }
+            KeyCode::PageDown => {
+                self.scroll = self.scroll.saturating_sub(SCROLL_STEP);
+                Vec::new()
+            }
+            KeyCode::Char('D') => {
+                self.mode = Mode::Debug;
+                Vec::new()
+            }
+            KeyCode::Esc => vec![Command::Dispatch(Action::ClearError)],
+            _ => Vec::new(),
+        }
+    }
+
+    // Nothing is dispatched from here, the log only shows what happened
+    fn debug(&mut self, key: KeyEvent) -> Vec<Command> {
+        match key.code {
+            KeyCode::Char('h') | KeyCode::Left => {
+                let earlier = self.log.lock().ok().and_then(|mut log| log.step_back());
+                if let Some(state) = earlier {
+                    let live = std::mem::replace(&mut self.state, state);
+                    self.live.get_or_insert(live);
+                }
+            }
+            KeyCode::Char('l') | KeyCode::Right => {
+                let later = self.log.lock().ok().and_then(|mut log| log.step_forward().map(|state| (state, log.cursor())));
+                match later {
+                    // Stepped past the newest, back to live
+                    Some((_, None)) => self.resume(),
+                    Some((state, Some(_))) => self.state = state,
+                    None => {}
+                }
+            }
+            KeyCode::Esc | KeyCode::Char('D') => {
+                self.resume();
+                self.mode = Mode::Browse;
+            }
+            _ => {}
+        }
+        Vec::new()
+    }
+
+    fn resume(&mut self) {
+        if let Ok(mut log) = self.log.lock() {
+            log.resume();
+        }
+        if let Some(live) = self.live.take() {
+            self.state = live;
+        }
+    }
+
+    fn compose(&mut self, key: KeyEvent) -> Vec<Command> {
+        let mut content = self.state.draft.content.clone();
+        let control = key.modifiers.contains(KeyModifiers::CONTROL);
+        let alt = key.modifiers.contains(KeyModifiers::ALT);
+
+        match key.code {
+            KeyCode::Esc => {
+                self.mode = Mode::Browse;
+                return Vec::new();
+            }
+            // Terminals can't tell Shift+Enter apart, Alt+Enter and Ctrl+J break the line
+            KeyCode::Enter if alt => content.push('\n'),
+            KeyCode::Char('j') if control => content.push('\n'),
+            KeyCode::Enter => {
+                let commands = self.command(KeyCommand::Send);
+                if !commands.is_empty() {
+                    self.scroll = 0;
+                }
+                return commands;
+            }
+            KeyCode::Char('u') if control => content.clear(),
+            KeyCode::Char(c) if !control => content.push(c),
+            KeyCode::Backspace => {
+                content.pop();
+            }
+            _ => return Vec::new(),
+        }
+        vec![Command::Dispatch(Action::UpdateDraftContent { content })]
+    }
+
+    // The same commands the GUI binds, as the core action they map to
+    fn command(&self, command: KeyCommand) -> Vec<Command> {
+        command.action(&self.state, self.clock.utc()).map(Command::Dispatch).into_iter().collect()
+    }
+
+    // Focus the next or previous conversation in the list
+    fn step(&mut self, by: isize) -> Vec<Command> {
+        let ids = self.list_ids();
+        if ids.is_empty() {
+            return Vec::new();
+        }
+        let current = self
+            .state
+            .focused_interaction
+            .as_ref()
+            .and_then(|focused| ids.iter().position(|id| id == focused));
+        let next = match current {
+            Some(idx) => (idx as isize + by).clamp(0, ids.len() as isize - 1) as usize,
+            None => 0,
+        };
+        if current == Some(next) {
+            return Vec::new();
+        }
+
+        self.focus(ids[next].clone())
+    }
+
+    fn focus(&mut self, id: String) -> Vec<Command> {
+        self.scroll = 0;
+        let mut commands = vec![Command::Dispatch(Action::FocusInteraction { interaction_id: Some(id.clone()) })];
+        // The list only has the interaction, its messages come separately,
+        // archived ones are reloaded by the reducer
+        if self.state.interactions.get(&id).is_some_and(|i| i.content.is_empty()) {
+            commands.push(Command::Fetch(id));
+        }
+        commands
+    }
+
+    // The draft, when there is one, goes along as the feedback
+    fn review(&self, approved: bool) -> Vec<Command> {
+        let Some(id) = self.state.focused_interaction.clone().filter(|id| self.state.pending_reviews.contains(id)) else {
+            return Vec::new();
+        };
+        let feedback = Some(self.state.draft.content.trim()).filter(|draft| !draft.is_empty()).map(str::to_string);
+        let mut commands = vec![Command::Dispatch(Action::SubmitReview {
+            interaction_id: id,
+            approved,
+            feedback: feedback.clone(),
+            reviewed_at: self.clock.utc(),
+        })];
+        if feedback.is_some() {
+            commands.push(Command::Dispatch(Action::UpdateDraftContent { content: String::new() }));
+        }
+        commands
+    }
+
+    fn answer_permission(&self, approved: bool) -> Vec<Command> {
+        match self.focused().filter(|i| i.pending_permission().is_some()) {
+            Some(interaction) => vec![Command::Dispatch(Action::RespondToPermission {
+                interaction_id: interaction.id.clone(),
+                approved,
+            })],
+            None => Vec::new(),
+        }
+    }
+}
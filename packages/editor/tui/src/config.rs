@@ -0,0 +1,61 @@
+use std::path::PathBuf;
+
+// Written by the local server, same places the GUI looks
+const PORT_FILE: &str = ".bicamrl-port";
+const DEFAULT_PORT: u16 = 3456;
+
+/// Where to connect, from `--server`, then `BICAMRL_SERVER_URL`, then the local port file
+#[derive(Debug, Clone, PartialEq)]
+pub struct Config {
+    pub server_url: String,
+    /// Sent as a bearer token, from `BICAMRL_API_KEY`
+    pub api_key: Option<String>,
+}
+
+impl Config {
+    pub fn load() -> Self {
+        let args: Vec<String> = std::env::args().skip(1).collect();
+        Self::from(&args, env)
+    }
+
+    pub fn from(args: &[String], env: impl Fn(&str) -> Option<String>) -> Self {
+        let flag = args
+            .iter()
+            .position(|arg| arg == "--server")
+            .and_then(|idx| args.get(idx + 1))
+            .cloned();
+        let server_url = flag
+            .or_else(|| env("BICAMRL_SERVER_URL"))
+            .unwrap_or_else(|| format!("http://localhost:{}", local_server_port().unwrap_or(DEFAULT_PORT)));
+
+        Self {
+            server_url: server_url.trim_end_matches('/').to_string(),
+            api_key: env("BICAMRL_API_KEY"),
+        }
+    }
+}
+
+/// Where the working set is kept between runs, so a dropped SSH session keeps its drafts
+pub fn snapshot_path() -> Option<PathBuf> {
+    let data = env("XDG_DATA_HOME")
+        .filter(|path| path.starts_with('/'))
+        .map(PathBuf::from)
+        .or_else(|| Some(PathBuf::from(env("HOME")?).join(".local").join("share")))?;
+    Some(data.join("bicamrl").join("tui-snapshot.json"))
+}
+
+/// Where archived interactions are written, one file each, next to the snapshot
+pub fn archive_dir() -> Option<PathBuf> {
+    Some(snapshot_path()?.with_file_name("tui-archive"))
+}
+
+fn env(var: &str) -> Option<String> {
+    std::env::var(var).ok().filter(|value| !value.trim().is_empty())
+}
+
+fn local_server_port() -> Option<u16> {
+    [".", "../..", "../../.."]
+        .iter()
+        .map(|dir| PathBuf::from(dir).join(PORT_FILE))
+        .find_map(|path| std::fs::read_to_string(path).ok()?.trim().parse().ok())
+}
@@ -0,0 +1,177 @@
+use bicky_editor_core::types::Interaction;
+use bicky_editor_core::{report, Action, Effect};
+use std::time::{SystemTime, UNIX_EPOCH};
+use tokio::sync::mpsc::UnboundedSender;
+use tokio::task::JoinHandle;
+
+use crate::api::ApiClient;
+use crate::{config, sse, Event};
+
+/// Carries out the reducer's effects, results come back as actions
+pub struct Effects {
+    api: ApiClient,
+    events: UnboundedSender<Event>,
+    // Only one stream at a time, a new connection replaces it
+    stream: Option<JoinHandle<()>>,
+}
+
+impl Effects {
+    pub fn new(api: ApiClient, events: UnboundedSender<Event>) -> Self {
+        Self { api, events, stream: None }
+    }
+
+    pub fn run(&mut self, effect: Effect) {
+        let api = self.api.clone();
+        let events = self.events.clone();
+        let send = move |action| {
+            let _ = events.send(Event::Action(action));
+        };
+
+        match effect {
+            Effect::SubmitInteraction { temp_id, content, metadata, interaction_id, .. } => {
+                tokio::spawn(async move {
+                    let result = match api.send_message(&content, &metadata, interaction_id.as_deref()).await {
+                        Ok(sent) => api.get_interaction(&sent.id).await,
+                        Err(e) => Err(e),
+                    };
+                    // A new conversation is followed from here on
+                    let started = result.as_ref().ok().filter(|_| interaction_id.is_none()).map(|i| i.id.clone());
+                    send(Action::InteractionSubmitted { temp_id, result });
+                    if let Some(id) = started {
+                        send(Action::FocusInteraction { interaction_id: Some(id) });
+                    }
+                });
+            }
+
+            Effect::SubmitReview { interaction_id, approved, feedback } => {
+                tokio::spawn(async move {
+                    let result = api.submit_review(&interaction_id, approved, feedback.as_deref()).await;
+                    send(Action::ReviewSubmitted(result));
+                });
+            }
+
+            Effect::RespondToPermission { interaction_id, approved } => {
+                tokio::spawn(async move {
+                    let result = api.respond_to_permission(&interaction_id, approved).await;
+                    send(Action::PermissionResponded(result));
+                });
+            }
+
+            Effect::ConnectToStream { server_url, session_id } => {
+                if let Some(stream) = self.stream.take() {
+                    stream.abort();
+                }
+                self.stream = Some(tokio::spawn(sse::follow(api.at(&server_url), session_id, self.events.clone())));
+            }
+
+            // The queue shows in the list itself, the server has no status to fetch
+            Effect::FetchQueueStatus => {}
+
+            Effect::FetchSessions => {
+                tokio::spawn(async move {
+                    send(Action::SessionsLoaded(api.get_sessions().await));
+                });
+            }
+
+            Effect::CreateSession { name } => {
+                tokio::spawn(async move {
+                    send(Action::SessionCreated(api.create_session(name.as_deref()).await));
+                });
+            }
+
+            // Tags aren't edited from the terminal
+            Effect::UpdateInteractionTags { .. } => {}
+
+            // Written before returning, it runs on the way out too
+            Effect::PersistState { snapshot } => {
+                if let Err(e) = persist(&snapshot) {
+                    send(Action::SetError { message: format!("Couldn't save drafts: {}", e) });
+                }
+            }
+
+            Effect::WriteExport { name, contents } => {
+                send(Action::ReviewReportExported(write_export(&name, &contents)));
+            }
+
+            Effect::ArchiveInteractions { interactions } => {
+                send(Action::InteractionsArchived(archive(&interactions)));
+            }
+
+            // An archive that's gone missing is fetched again from the server
+            Effect::LoadArchivedInteraction { interaction_id } => match unarchive(&interaction_id) {
+                Ok(interaction) => send(Action::ArchivedInteractionLoaded { interaction_id, result: Ok(interaction) }),
+                Err(_) => fetch(&self.api, &self.events, interaction_id),
+            },
+        }
+    }
+}
+
+/// Ask for an interaction's messages, they arrive as `InteractionPosted`
+pub fn fetch(api: &ApiClient, events: &UnboundedSender<Event>, id: String) {
+    let api = api.clone();
+    let events = events.clone();
+    tokio::spawn(async move {
+        let action = match api.get_interaction(&id).await {
+            Ok(interaction) => Action::InteractionPosted { interaction },
+            Err(e) => Action::SetError { message: format!("Couldn't load {}: {}", id, e) },
+        };
+        let _ = events.send(Event::Action(action));
+    });
+}
+
+/// Ask for the interactions the server has, they arrive as `Event::Listed`
+pub fn list(api: &ApiClient, events: &UnboundedSender<Event>) {
+    let api = api.clone();
+    let events = events.clone();
+    tokio::spawn(async move {
+        let event = match api.get_interactions().await {
+            Ok(interactions) => Event::Listed(interactions),
+            Err(e) => Event::Action(Action::SetError { message: format!("Couldn't list conversations: {}", e) }),
+        };
+        let _ = events.send(event);
+    });
+}
+
+fn persist(snapshot: &str) -> Result<(), String> {
+    let path = config::snapshot_path().ok_or("no HOME to save to")?;
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir).map_err(|e| e.to_string())?;
+    }
+    std::fs::write(&path, snapshot).map_err(|e| e.to_string())
+}
+
+// One `<id>.json` each, the ids written are what can be dropped from memory
+fn archive(interactions: &[Interaction]) -> Result<Vec<String>, String> {
+    let dir = config::archive_dir().ok_or("no HOME to archive to")?;
+    std::fs::create_dir_all(&dir).map_err(|e| e.to_string())?;
+    interactions
+        .iter()
+        .map(|interaction| {
+            let json = serde_json::to_string(interaction).map_err(|e| e.to_string())?;
+            std::fs::write(dir.join(format!("{}.json", interaction.id)), json).map_err(|e| e.to_string())?;
+            Ok(interaction.id.clone())
+        })
+        .collect()
+}
+
+fn unarchive(id: &str) -> Result<Interaction, String> {
+    let path = config::archive_dir().ok_or("no HOME to read from")?.join(format!("{}.json", id));
+    let json = std::fs::read_to_string(path).map_err(|e| e.to_string())?;
+    serde_json::from_str(&json).map_err(|e| e.to_string())
+}
+
+// Into `./exports`, named the way the GUI names its exports
+fn write_export(name: &str, contents: &str) -> Result<String, String> {
+    let dir = std::env::current_dir().map_err(|e| e.to_string())?.join("exports");
+    std::fs::create_dir_all(&dir).map_err(|e| e.to_string())?;
+
+    let timestamp = SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_secs()).unwrap_or_default();
+    let path = dir.join(report::export_file_name(name, timestamp));
+    std::fs::write(&path, contents).map_err(|e| e.to_string())?;
+    Ok(path.display().to_string())
+}
+
+/// The snapshot from last time, if there is one
+pub fn restore() -> Option<String> {
+    std::fs::read_to_string(config::snapshot_path()?).ok()
+}
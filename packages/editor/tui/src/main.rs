@@ -0,0 +1,134 @@
+mod api;
+mod app;
+mod config;
+mod effects;
+mod sse;
+mod ui;
+#[cfg(test)]
+mod tests;
+
+use bicky_editor_core::types::Interaction;
+use bicky_editor_core::Action;
+use ratatui::crossterm::event::{self, Event as TermEvent, KeyEvent, KeyEventKind};
+use std::time::Duration;
+use tokio::sync::mpsc::{self, UnboundedSender};
+
+use app::{App, Command};
+use effects::Effects;
+
+// Drafts are saved this often as well as on the way out
+const SAVE_INTERVAL: Duration = Duration::from_secs(30);
+// Interactions quiet this long are moved to disk when drafts are saved
+const ARCHIVE_AFTER_DAYS: i64 = 7;
+
+/// Everything the main loop waits on
+#[derive(Debug)]
+pub enum Event {
+    Key(KeyEvent),
+    Resize,
+    Action(Action),
+    /// The server's interactions, without their messages
+    Listed(Vec<Interaction>),
+    /// An interaction changed on the server
+    Changed(String),
+    Save,
+}
+
+#[tokio::main]
+async fn main() -> std::io::Result<()> {
+    let config = config::Config::load();
+    let api = api::ApiClient::new(config.server_url.clone(), config.api_key.clone());
+    let (events, mut incoming) = mpsc::unbounded_channel();
+    let mut effects = Effects::new(api.clone(), events.clone());
+    let mut app = App::default();
+
+    if let Some(snapshot) = effects::restore() {
+        run(&mut app, &mut effects, Action::LoadSnapshot { snapshot });
+    }
+    run(&mut app, &mut effects, Action::Connect { server_url: config.server_url.clone() });
+    effects::list(&api, &events);
+    read_terminal(events.clone());
+    save_periodically(events.clone());
+
+    let mut terminal = ratatui::init();
+    let result = loop {
+        if let Err(e) = terminal.draw(|frame| ui::draw(frame, &app)) {
+            break Err(e);
+        }
+        let Some(event) = incoming.recv().await else {
+            break Ok(());
+        };
+
+        match event {
+            Event::Key(key) => {
+                let commands = app.on_key(key);
+                if commands.contains(&Command::Quit) {
+                    break Ok(());
+                }
+                for command in commands {
+                    match command {
+                        Command::Dispatch(action) => run(&mut app, &mut effects, action),
+                        Command::Fetch(id) => effects::fetch(&api, &events, id),
+                        Command::Quit => {}
+                    }
+                }
+            }
+            Event::Resize => {}
+            Event::Action(action) => run(&mut app, &mut effects, action),
+            Event::Listed(interactions) => {
+                // Ones we have, from a snapshot or the stream, keep their messages, archived ones stay on disk
+                for interaction in interactions {
+                    let known = app.live().interactions.contains_key(&interaction.id) || app.live().archived.contains_key(&interaction.id);
+                    if !known {
+                        run(&mut app, &mut effects, Action::InteractionPosted { interaction });
+                    }
+                }
+            }
+            Event::Changed(id) => effects::fetch(&api, &events, id),
+            Event::Save => {
+                let cutoff = app.clock.utc() - chrono::Duration::days(ARCHIVE_AFTER_DAYS);
+                run(&mut app, &mut effects, Action::ArchiveOlderThan { cutoff });
+                run(&mut app, &mut effects, Action::SaveSnapshot);
+            }
+        }
+    };
+
+    // Keep the drafts for next time
+    run(&mut app, &mut effects, Action::SaveSnapshot);
+    ratatui::restore();
+    result
+}
+
+fn run(app: &mut App, effects: &mut Effects, action: Action) {
+    for effect in app.dispatch(&action) {
+        effects.run(effect);
+    }
+}
+
+// Crossterm reads block, so they get a thread of their own
+fn read_terminal(events: UnboundedSender<Event>) {
+    std::thread::spawn(move || loop {
+        let event = match event::read() {
+            Ok(TermEvent::Key(key)) if key.kind == KeyEventKind::Press => Event::Key(key),
+            Ok(TermEvent::Resize(..)) => Event::Resize,
+            Ok(_) => continue,
+            Err(_) => return,
+        };
+        if events.send(event).is_err() {
+            return;
+        }
+    });
+}
+
+fn save_periodically(events: UnboundedSender<Event>) {
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(SAVE_INTERVAL);
+        interval.tick().await;
+        loop {
+            interval.tick().await;
+            if events.send(Event::Save).is_err() {
+                return;
+            }
+        }
+    });
+}
@@ -0,0 +1,91 @@
+use bicky_editor_core::Action;
+use futures::StreamExt;
+use reqwest::Method;
+use serde_json::Value;
+use std::time::Duration;
+use tokio::sync::mpsc::UnboundedSender;
+
+use crate::api::ApiClient;
+use crate::Event;
+
+// Before trying a dropped stream again
+const RECONNECT_DELAY: Duration = Duration::from_secs(5);
+
+/// What one server-sent event means for us
+#[derive(Debug, Clone, PartialEq)]
+pub enum Update {
+    Connected,
+    /// Something about this interaction changed, fetch it again
+    Changed(String),
+}
+
+/// Parse one event's `data:` payload, keep-alives and unknown events give `None`
+pub fn parse(message: &str) -> Option<Update> {
+    let data = message.lines().find_map(|line| line.strip_prefix("data:"))?;
+    let json: Value = serde_json::from_str(data.trim()).ok()?;
+    if json.get("connected").is_some() {
+        return Some(Update::Connected);
+    }
+
+    let data = json.get("data")?;
+    let id = match json.get("type")?.as_str()? {
+        "interaction:created" | "interaction:updated" => data.get("interaction")?.get("id")?,
+        "message:added" | "message:updated" => data.get("interactionId")?,
+        _ => return None,
+    };
+    Some(Update::Changed(id.as_str()?.to_string()))
+}
+
+/// Where a session's events are streamed from
+pub fn stream_path(session_id: &str) -> String {
+    format!("/sessions/{}/stream", session_id)
+}
+
+/// Follow a session's stream until the task is aborted, reconnecting when it drops
+pub async fn follow(api: ApiClient, session_id: String, events: UnboundedSender<Event>) {
+    let mut path = stream_path(&session_id);
+    loop {
+        let reason = match api.request(Method::GET, &path).send().await {
+            // Servers without per-session streams send every event on the one stream
+            Ok(resp) if resp.status() == reqwest::StatusCode::NOT_FOUND && path != "/stream" => {
+                path = "/stream".to_string();
+                continue;
+            }
+            Ok(resp) if !resp.status().is_success() => resp.status().to_string(),
+            Ok(resp) => {
+                let mut stream = resp.bytes_stream();
+                let mut buffer = String::new();
+                let mut reason = "stream closed".to_string();
+                while let Some(chunk) = stream.next().await {
+                    let bytes = match chunk {
+                        Ok(bytes) => bytes,
+                        Err(e) => {
+                            reason = e.to_string();
+                            break;
+                        }
+                    };
+                    buffer.push_str(&String::from_utf8_lossy(&bytes));
+                    while let Some(end) = buffer.find("\n\n") {
+                        let message: String = buffer.drain(..end + 2).collect();
+                        let event = match parse(&message) {
+                            Some(Update::Connected) => Event::Action(Action::Connected),
+                            Some(Update::Changed(id)) => Event::Changed(id),
+                            None => continue,
+                        };
+                        if events.send(event).is_err() {
+                            return;
+                        }
+                    }
+                }
+                reason
+            }
+            Err(e) => e.to_string(),
+        };
+
+        let disconnected = Action::Disconnected { reason: Some(reason) };
+        if events.send(Event::Action(disconnected)).is_err() {
+            return;
+        }
+        tokio::time::sleep(RECONNECT_DELAY).await;
+    }
+}
@@ -0,0 +1,15 @@
+use crate::api::without_sessions;
+use reqwest::StatusCode;
+
+#[cfg(test)]
+mod api_tests {
+    use super::*;
+
+    #[test]
+    fn test_missing_session_routes_mean_the_default_session() {
+        assert!(without_sessions(StatusCode::NOT_FOUND));
+        assert!(without_sessions(StatusCode::METHOD_NOT_ALLOWED));
+        assert!(!without_sessions(StatusCode::INTERNAL_SERVER_ERROR));
+        assert!(!without_sessions(StatusCode::UNAUTHORIZED));
+    }
+}
@@ -0,0 +1,284 @@
+use crate::app::{App, Command, Mode};
+use bicky_editor_core::archive::InteractionStub;
+use bicky_editor_core::clock::Clock;
+use bicky_editor_core::types::{ConversationItem, Interaction, InteractionType, PENDING_PERMISSION_KEY};
+use bicky_editor_core::{Action, Effect};
+use chrono::{DateTime, TimeZone, Utc};
+use ratatui::crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use serde_json::json;
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Instant;
+
+// Always answers with the same moment
+#[derive(Debug)]
+struct FixedClock(DateTime<Utc>);
+
+impl Clock for FixedClock {
+    fn instant(&self) -> Instant {
+        Instant::now()
+    }
+
+    fn utc(&self) -> DateTime<Utc> {
+        self.0
+    }
+}
+
+#[cfg(test)]
+mod app_tests {
+    use super::*;
+
+    fn interaction(id: &str, minute: u32, content: &[(&str, &str)]) -> Interaction {
+        let timestamp = Utc.with_ymd_and_hms(2024, 3, 1, 10, minute, 0).unwrap();
+        Interaction {
+            id: id.to_string(),
+            source: "user".to_string(),
+            interaction_type: InteractionType::Query,
+            content: content
+                .iter()
+                .map(|(role, text)| ConversationItem {
+                    role: role.to_string(),
+                    content: text.to_string(),
+                    timestamp,
+                    metadata: None,
+                })
+                .collect(),
+            needs_work: false,
+            review_stack: vec![],
+            history: vec![],
+            metadata: HashMap::new(),
+            timestamp,
+        }
+    }
+
+    // Newest first, as the list shows them
+    fn app() -> App {
+        let mut app = App::default();
+        app.dispatch(&Action::InteractionPosted { interaction: interaction("older", 0, &[("user", "Hello")]) });
+        app.dispatch(&Action::InteractionPosted { interaction: interaction("newer", 5, &[]) });
+        app
+    }
+
+    fn key(code: KeyCode) -> KeyEvent {
+        KeyEvent::new(code, KeyModifiers::NONE)
+    }
+
+    fn apply(app: &mut App, commands: Vec<Command>) -> Vec<Command> {
+        let mut rest = Vec::new();
+        for command in commands {
+            match command {
+                Command::Dispatch(action) => {
+                    app.dispatch(&action);
+                }
+                other => rest.push(other),
+            }
+        }
+        rest
+    }
+
+    #[test]
+    fn test_actions_go_through_the_pipeline() {
+        let app = app();
+        assert_eq!(app.pipeline.len(), 1);
+        assert_eq!(app.log.lock().unwrap().len(), 2);
+    }
+
+    #[test]
+    fn test_debug_log_steps_back_and_returns_to_live() {
+        let mut app = app();
+        app.on_key(key(KeyCode::Char('D')));
+        assert_eq!(app.mode, Mode::Debug);
+
+        app.on_key(key(KeyCode::Char('h')));
+        assert!(app.inspecting());
+        assert_eq!(app.conversations().len(), 1);
+        assert_eq!(app.live().interactions.len(), 2);
+
+        app.on_key(key(KeyCode::Char('l')));
+        assert!(!app.inspecting());
+        assert_eq!(app.conversations().len(), 2);
+    }
+
+    #[test]
+    fn test_actions_arriving_while_inspecting_land_on_live() {
+        let mut app = app();
+        app.on_key(key(KeyCode::Char('D')));
+        app.on_key(key(KeyCode::Char('h')));
+
+        app.dispatch(&Action::InteractionPosted { interaction: interaction("newest", 9, &[]) });
+        assert_eq!(app.conversations().len(), 1);
+        assert_eq!(app.live().interactions.len(), 3);
+
+        app.on_key(key(KeyCode::Esc));
+        assert_eq!(app.mode, Mode::Browse);
+        assert_eq!(app.conversations().len(), 3);
+    }
+
+    #[test]
+    fn test_moving_down_focuses_and_loads_conversations() {
+        let mut app = app();
+
+        let commands = app.on_key(key(KeyCode::Char('j')));
+        let rest = apply(&mut app, commands);
+
+        assert_eq!(app.state.focused_interaction.as_deref(), Some("newer"));
+        assert_eq!(rest, vec![Command::Fetch("newer".to_string())]);
+
+        let commands = app.on_key(key(KeyCode::Down));
+        let rest = apply(&mut app, commands);
+
+        assert_eq!(app.state.focused_interaction.as_deref(), Some("older"));
+        // Its messages are already here
+        assert!(rest.is_empty());
+    }
+
+    #[test]
+    fn test_moving_stops_at_the_ends() {
+        let mut app = app();
+        let commands = app.on_key(key(KeyCode::Char('j')));
+        apply(&mut app, commands);
+
+        assert!(app.on_key(key(KeyCode::Char('k'))).is_empty());
+    }
+
+    #[test]
+    fn test_number_keys_focus_conversations_by_position() {
+        let mut app = app();
+
+        let commands = app.on_key(key(KeyCode::Char('2')));
+        assert_eq!(commands, vec![Command::Dispatch(Action::FocusInteraction { interaction_id: Some("older".to_string()) })]);
+        apply(&mut app, commands);
+
+        assert!(app.on_key(key(KeyCode::Char('2'))).is_empty());
+        assert!(app.on_key(key(KeyCode::Char('9'))).is_empty());
+    }
+
+    #[test]
+    fn test_archived_conversations_come_last_and_load_from_disk() {
+        let mut app = app();
+        let stub = InteractionStub::of(&interaction("archived", 0, &[]), &app.state.session_id);
+        app.state.archived.insert(stub.id.clone(), stub);
+        assert_eq!(app.list_ids(), vec!["newer", "older", "archived"]);
+
+        for _ in 0..2 {
+            let commands = app.on_key(key(KeyCode::Char('j')));
+            apply(&mut app, commands);
+        }
+        let commands = app.on_key(key(KeyCode::Char('j')));
+        assert_eq!(commands, vec![Command::Dispatch(Action::FocusInteraction { interaction_id: Some("archived".to_string()) })]);
+
+        // The reducer asks for it to be read back, not the server
+        let effects = app.dispatch(&Action::FocusInteraction { interaction_id: Some("archived".to_string()) });
+        assert_eq!(effects, vec![Effect::LoadArchivedInteraction { interaction_id: "archived".to_string() }]);
+    }
+
+    #[test]
+    fn test_typing_edits_the_draft_and_enter_sends() {
+        let sent_at = Utc.with_ymd_and_hms(2024, 3, 1, 11, 0, 0).unwrap();
+        let mut app = app();
+        app.clock = Arc::new(FixedClock(sent_at));
+        app.on_key(key(KeyCode::Enter));
+        assert_eq!(app.mode, Mode::Compose);
+
+        for c in "hi!".chars() {
+            let commands = app.on_key(key(KeyCode::Char(c)));
+            apply(&mut app, commands);
+        }
+        let commands = app.on_key(key(KeyCode::Backspace));
+        apply(&mut app, commands);
+        assert_eq!(app.state.draft.content, "hi");
+
+        assert_eq!(app.on_key(key(KeyCode::Enter)), vec![Command::Dispatch(Action::SubmitInteraction { at: sent_at })]);
+    }
+
+    #[test]
+    fn test_alt_enter_breaks_the_line() {
+        let mut app = app();
+        app.mode = Mode::Compose;
+
+        let commands = app.on_key(KeyEvent::new(KeyCode::Enter, KeyModifiers::ALT));
+        apply(&mut app, commands);
+
+        assert_eq!(app.state.draft.content, "\n");
+    }
+
+    #[test]
+    fn test_blank_draft_is_not_sent() {
+        let mut app = app();
+        app.mode = Mode::Compose;
+
+        assert!(app.on_key(key(KeyCode::Enter)).is_empty());
+    }
+
+    #[test]
+    fn test_permission_prompt_is_answered_from_browse() {
+        let mut app = App::default();
+        let mut waiting = interaction("i1", 0, &[]);
+        waiting.metadata.insert(PENDING_PERMISSION_KEY.to_string(), json!({
+            "toolName": "Bash",
+            "description": "Run cargo test"
+        }));
+        app.dispatch(&Action::InteractionPosted { interaction: waiting });
+        app.dispatch(&Action::FocusInteraction { interaction_id: Some("i1".to_string()) });
+
+        assert_eq!(app.on_key(key(KeyCode::Char('n'))), vec![Command::Dispatch(Action::RespondToPermission {
+            interaction_id: "i1".to_string(),
+            approved: false,
+        })]);
+    }
+
+    #[test]
+    fn test_answering_without_a_prompt_does_nothing() {
+        let mut app = app();
+        app.dispatch(&Action::FocusInteraction { interaction_id: Some("older".to_string()) });
+
+        assert!(app.on_key(key(KeyCode::Char('y'))).is_empty());
+    }
+
+    #[test]
+    fn test_ctrl_c_quits_while_typing() {
+        let mut app = app();
+        app.mode = Mode::Compose;
+
+        assert_eq!(app.on_key(KeyEvent::new(KeyCode::Char('c'), KeyModifiers::CONTROL)), vec![Command::Quit]);
+    }
+
+    #[test]
+    fn test_review_sends_the_draft_as_feedback() {
+        let reviewed_at = Utc.with_ymd_and_hms(2024, 3, 1, 11, 0, 0).unwrap();
+        let mut app = App::default();
+        app.clock = Arc::new(FixedClock(reviewed_at));
+        let mut reviewable = interaction("i1", 0, &[("user", "Refactor it")]);
+        reviewable.review_stack = vec!["user".to_string()];
+        app.dispatch(&Action::InteractionPosted { interaction: reviewable });
+        app.dispatch(&Action::FocusInteraction { interaction_id: Some("i1".to_string()) });
+        app.dispatch(&Action::UpdateDraftContent { content: "  Split the module  ".to_string() });
+
+        assert_eq!(app.on_key(key(KeyCode::Char('x'))), vec![
+            Command::Dispatch(Action::SubmitReview {
+                interaction_id: "i1".to_string(),
+                approved: false,
+                feedback: Some("Split the module".to_string()),
+                reviewed_at,
+            }),
+            Command::Dispatch(Action::UpdateDraftContent { content: String::new() }),
+        ]);
+    }
+
+    #[test]
+    fn test_review_needs_an_interaction_awaiting_one() {
+        let mut app = app();
+        app.dispatch(&Action::FocusInteraction { interaction_id: Some("older".to_string()) });
+
+        assert!(app.on_key(key(KeyCode::Char('a'))).is_empty());
+    }
+
+    #[test]
+    fn test_report_covers_the_current_session() {
+        let mut app = app();
+
+        assert_eq!(app.on_key(key(KeyCode::Char('E'))), vec![Command::Dispatch(Action::ExportReviewReport {
+            session_id: "default-session".to_string(),
+        })]);
+    }
+}
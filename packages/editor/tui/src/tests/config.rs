@@ -0,0 +1,33 @@
+use crate::config::Config;
+
+#[cfg(test)]
+mod config_tests {
+    use super::*;
+
+    fn args(args: &[&str]) -> Vec<String> {
+        args.iter().map(|arg| arg.to_string()).collect()
+    }
+
+    #[test]
+    fn test_flag_beats_environment() {
+        let env = |var: &str| (var == "BICAMRL_SERVER_URL").then(|| "http://env:1".to_string());
+
+        let config = Config::from(&args(&["--server", "https://bicamrl.example.com/"]), env);
+
+        assert_eq!(config.server_url, "https://bicamrl.example.com");
+    }
+
+    #[test]
+    fn test_environment_gives_url_and_key() {
+        let env = |var: &str| match var {
+            "BICAMRL_SERVER_URL" => Some("http://box:4000".to_string()),
+            "BICAMRL_API_KEY" => Some("secret".to_string()),
+            _ => None,
+        };
+
+        let config = Config::from(&[], env);
+
+        assert_eq!(config.server_url, "http://box:4000");
+        assert_eq!(config.api_key.as_deref(), Some("secret"));
+    }
+}
@@ -0,0 +1,8 @@
+#[cfg(test)]
+mod api;
+#[cfg(test)]
+mod app;
+#[cfg(test)]
+mod config;
+#[cfg(test)]
+mod sse;
\ No newline at end of file
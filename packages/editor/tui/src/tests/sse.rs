@@ -0,0 +1,31 @@
+use crate::sse::{parse, stream_path, Update};
+
+#[cfg(test)]
+mod sse_tests {
+    use super::*;
+
+    #[test]
+    fn test_reads_connection_and_changes() {
+        assert_eq!(parse("data: {\"connected\": true}\n\n"), Some(Update::Connected));
+        assert_eq!(
+            parse("data: {\"type\":\"interaction:updated\",\"data\":{\"interaction\":{\"id\":\"i1\"}}}\n\n"),
+            Some(Update::Changed("i1".to_string()))
+        );
+        assert_eq!(
+            parse("data: {\"type\":\"message:added\",\"data\":{\"interactionId\":\"i2\"}}\n\n"),
+            Some(Update::Changed("i2".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_streams_the_given_session() {
+        assert_eq!(stream_path("s1"), "/sessions/s1/stream");
+    }
+
+    #[test]
+    fn test_ignores_keep_alives_and_other_events() {
+        assert_eq!(parse(": keep-alive\n\n"), None);
+        assert_eq!(parse("data: {\"type\":\"process:spawned\",\"data\":{}}\n\n"), None);
+        assert_eq!(parse("data: not json\n\n"), None);
+    }
+}
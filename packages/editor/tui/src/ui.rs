@@ -0,0 +1,232 @@
+use bicky_editor_core::types::{Interaction, ToolPermissionRequest};
+use bicky_editor_core::Action;
+use ratatui::layout::{Constraint, Layout, Position, Rect};
+use ratatui::style::{Style, Stylize};
+use ratatui::text::{Line, Span, Text};
+use ratatui::widgets::{Block, List, ListItem, ListState, Paragraph, Wrap};
+use ratatui::Frame;
+
+use crate::app::{App, Mode};
+
+// Rows of the action log shown at once
+const LOG_ROWS: u16 = 10;
+
+pub fn draw(frame: &mut Frame, app: &App) {
+    let log_height = if app.mode == Mode::Debug { LOG_ROWS + 2 } else { 0 };
+    let [body, log, status] =
+        Layout::vertical([Constraint::Min(0), Constraint::Length(log_height), Constraint::Length(1)]).areas(frame.area());
+    let [list, main] = Layout::horizontal([Constraint::Percentage(30), Constraint::Min(0)]).areas(body);
+
+    draw_list(frame, app, list);
+    draw_conversation(frame, app, main);
+    if app.mode == Mode::Debug {
+        draw_log(frame, app, log);
+    }
+    frame.render_widget(status_line(app), status);
+}
+
+// The newest actions, the one the shown state comes right after highlighted
+fn draw_log(frame: &mut Frame, app: &App, area: Rect) {
+    let Ok(log) = app.log.lock() else {
+        return;
+    };
+    let applied = log.cursor().unwrap_or(log.len());
+    let items: Vec<ListItem> = log
+        .entries()
+        .iter()
+        .enumerate()
+        .map(|(idx, entry)| {
+            let line = Line::from(vec![
+                Span::raw(format!("{}  ", entry.at.format("%H:%M:%S"))).dim(),
+                Span::raw(action_name(&entry.action)),
+            ]);
+            // Not applied to what's shown
+            if idx >= applied {
+                ListItem::new(line).dim()
+            } else {
+                ListItem::new(line)
+            }
+        })
+        .collect();
+
+    let title = match log.cursor() {
+        Some(cursor) => format!(" Action log · {} of {} ", cursor, log.len()),
+        None => format!(" Action log · live, {} ", log.len()),
+    };
+    let list = List::new(items)
+        .block(Block::bordered().title(title).border_style(Style::new().magenta()))
+        .highlight_style(Style::new().reversed());
+    let selected = applied.checked_sub(1);
+    frame.render_stateful_widget(list, area, &mut ListState::default().with_selected(selected));
+}
+
+// Variant names are enough to follow along, the fields can be pages long
+fn action_name(action: &Action) -> String {
+    let debug = format!("{:?}", action);
+    debug.split(['(', '{', ' ']).next().unwrap_or_default().to_string()
+}
+
+fn draw_list(frame: &mut Frame, app: &App, area: Rect) {
+    let conversations = app.conversations();
+    let mut items: Vec<ListItem> = conversations
+        .iter()
+        .map(|interaction| {
+            // Waiting on the user beats working, working beats done
+            let marker = if interaction.pending_permission().is_some() {
+                Span::raw("! ").yellow().bold()
+            } else if interaction.submit_error().is_some() {
+                Span::raw("✗ ").red().bold()
+            } else if interaction.needs_work {
+                Span::raw("… ").dim()
+            } else {
+                Span::raw("  ")
+            };
+            ListItem::new(Line::from(vec![marker, Span::raw(interaction.title())]))
+        })
+        .collect();
+    // Archived ones stay listed, dimmed until they're opened
+    items.extend(app.archived().into_iter().map(|stub| ListItem::new(Line::from(format!("  {}", stub.title)).dim())));
+
+    let selected = app
+        .state
+        .focused_interaction
+        .as_ref()
+        .and_then(|focused| app.list_ids().iter().position(|id| id == focused));
+    let list = List::new(items)
+        .block(Block::bordered().title(" Conversations "))
+        .highlight_style(Style::new().reversed());
+    frame.render_stateful_widget(list, area, &mut ListState::default().with_selected(selected));
+}
+
+fn draw_conversation(frame: &mut Frame, app: &App, area: Rect) {
+    let focused = app.focused();
+    let permission = focused.and_then(Interaction::pending_permission);
+    let composer_height = app.state.draft.content.lines().count().clamp(1, 6) as u16 + 2;
+    let permission_height = if permission.is_some() { 5 } else { 0 };
+    let [messages, prompt, composer] = Layout::vertical([
+        Constraint::Min(0),
+        Constraint::Length(permission_height),
+        Constraint::Length(composer_height),
+    ])
+    .areas(area);
+
+    // An archived one shows its title while it's read back
+    let loading = app.state.focused_interaction.as_ref().filter(|_| focused.is_none()).and_then(|id| app.state.archived.get(id));
+    let title = match (focused, loading) {
+        (Some(interaction), _) => format!(" {} ", interaction.title()),
+        (None, Some(stub)) => format!(" {} ", stub.title),
+        (None, None) => " New conversation ".to_string(),
+    };
+    let body = match (focused, loading) {
+        (Some(interaction), _) => transcript(interaction),
+        (None, Some(_)) => Text::from(Line::raw("Loading…").dim().italic()),
+        (None, None) => Text::default(),
+    };
+    let transcript = Paragraph::new(body)
+        .block(Block::bordered().title(title))
+        .wrap(Wrap { trim: false });
+    // Newest at the bottom, PageUp moves back from there
+    let inner = messages.height.saturating_sub(2);
+    let total = transcript.line_count(messages.width.saturating_sub(2)) as u16;
+    let top = total.saturating_sub(inner).saturating_sub(app.scroll);
+    frame.render_widget(transcript.scroll((top, 0)), messages);
+
+    if let Some(permission) = &permission {
+        frame.render_widget(permission_prompt(permission), prompt);
+    }
+
+    let composing = app.mode == Mode::Compose;
+    let hint = if composing { " Enter send · Alt+Enter newline · Esc done " } else { " Enter to write " };
+    let border = if composing { Style::new().cyan() } else { Style::new().dim() };
+    let draft = Paragraph::new(app.state.draft.content.as_str())
+        .block(Block::bordered().title(hint).border_style(border))
+        .wrap(Wrap { trim: false });
+    frame.render_widget(draft, composer);
+
+    if composing {
+        // After the last character, the draft is short enough not to wrap in practice
+        let lines: Vec<&str> = app.state.draft.content.split('\n').collect();
+        let last = lines.last().copied().unwrap_or_default();
+        let x = composer.x + 1 + Line::raw(last).width() as u16;
+        let y = composer.y + lines.len().min(6) as u16;
+        frame.set_cursor_position(Position::new(x.min(composer.right().saturating_sub(2)), y));
+    }
+}
+
+fn transcript(interaction: &Interaction) -> Text<'static> {
+    let mut lines = Vec::new();
+    for item in &interaction.content {
+        let speaker = match item.role.as_str() {
+            "user" => Span::raw("You").cyan().bold(),
+            "assistant" => Span::raw("Wake").green().bold(),
+            role => Span::raw(role.to_string()).dim().bold(),
+        };
+        lines.push(Line::from(vec![speaker, Span::raw(format!("  {}", item.timestamp.format("%H:%M"))).dim()]));
+        lines.extend(item.content.lines().map(|line| Line::raw(line.to_string())));
+        lines.push(Line::raw(""));
+    }
+    if interaction.needs_work {
+        lines.push(Line::raw("Wake is working…").dim().italic());
+    }
+    if let Some(error) = interaction.submit_error() {
+        lines.push(Line::raw(format!("Couldn't send: {}", error)).red());
+    }
+    Text::from(lines)
+}
+
+fn permission_prompt(permission: &ToolPermissionRequest) -> Paragraph<'static> {
+    let input = permission
+        .input
+        .as_ref()
+        .and_then(|input| input.get("command").or_else(|| input.get("file_path")))
+        .and_then(|value| value.as_str())
+        .map(str::to_string);
+    let mut lines = vec![Line::raw(permission.description.clone())];
+    lines.extend(input.map(|input| Line::raw(input).dim()));
+    lines.push(Line::from(vec![
+        Span::raw("y").bold(),
+        Span::raw(" approve  "),
+        Span::raw("n").bold(),
+        Span::raw(" deny"),
+    ]));
+
+    Paragraph::new(lines)
+        .block(
+            Block::bordered()
+                .title(format!(" {} wants permission ", permission.tool_name))
+                .border_style(Style::new().yellow()),
+        )
+        .wrap(Wrap { trim: true })
+}
+
+fn status_line(app: &App) -> Line<'static> {
+    let connection = if app.state.connected {
+        Span::raw(" ● connected ").green()
+    } else {
+        Span::raw(" ○ offline ").red()
+    };
+    let waiting = app.state.get_pending_permissions().len();
+    let reviews = app.state.pending_reviews.len();
+    let mut spans = vec![connection];
+    if waiting > 0 {
+        spans.push(Span::raw(format!(" {} waiting on you ", waiting)).yellow());
+    }
+    if reviews > 0 {
+        spans.push(Span::raw(format!(" {} to review ", reviews)).cyan());
+    }
+    if let Some(path) = &app.state.last_export {
+        spans.push(Span::raw(format!(" report saved to {} ", path)).green());
+    }
+    match &app.state.error {
+        Some(error) => spans.push(Span::raw(format!(" {}  (Esc to dismiss)", error)).red()),
+        None if app.mode == Mode::Browse => {
+            spans.push(Span::raw(" j/k move · Enter write · c new · y/n answer · a/x review · E report · D log · q quit").dim())
+        }
+        None if app.mode == Mode::Debug => {
+            let shown = if app.inspecting() { " showing an earlier state ·" } else { "" };
+            spans.push(Span::raw(format!("{} h/l step · Esc back to live", shown)).magenta())
+        }
+        None => {}
+    }
+    Line::from(spans)
+}
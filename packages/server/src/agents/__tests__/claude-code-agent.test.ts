@@ -109,6 +109,31 @@ describe('ClaudeCodeAgent', () => {
       }
     });
 
+    test('reports when the first token arrived', async () => {
+      const interaction = Interaction.create({
+        source: 'user',
+        type: InteractionType.QUERY
+      });
+
+      const originalComplete = ClaudeCodeLLMProvider.prototype.completeWithTools;
+      ClaudeCodeLLMProvider.prototype.completeWithTools = async function(_messages, _tools, options) {
+        await options?.onTokenUpdate?.(5);
+        await options?.onTokenUpdate?.(12);
+        return { content: 'Done', model: 'claude-3-opus' };
+      };
+
+      try {
+        const before = Date.now();
+        const response = await agent.process(interaction, []);
+
+        const firstTokenAt = Date.parse(response.metadata!.firstTokenAt!);
+        expect(firstTokenAt).toBeGreaterThanOrEqual(before);
+        expect(firstTokenAt).toBeLessThanOrEqual(Date.now());
+      } finally {
+        ClaudeCodeLLMProvider.prototype.completeWithTools = originalComplete;
+      }
+    });
+
     test('filters out system messages except permission requests', async () => {
       const interaction = Interaction.create({
         source: 'user',
@@ -63,6 +63,7 @@ export class ClaudeCodeAgent implements Agent {
 
       // Track processing time
       const startTime = Date.now();
+      let firstTokenAt: number | undefined;
       
       // Let Claude Code handle everything
       const response = await this.provider.completeWithTools(
@@ -70,7 +71,7 @@ export class ClaudeCodeAgent implements Agent {
         [], // Claude Code has its own tools
         {
           onTokenUpdate: async (tokens) => {
-            // Could report progress here if needed
+            firstTokenAt ??= Date.now();
             console.log(`[ClaudeCodeAgent] Tokens generated: ${tokens}`);
           },
           interactionId: interaction.id,
@@ -88,7 +89,8 @@ export class ClaudeCodeAgent implements Agent {
           model: response.model,
           usage: response.usage,
           processingTimeMs: processingTime,
-          toolsUsed: response.toolCalls?.map(tc => tc.name)
+          toolsUsed: response.toolCalls?.map(tc => tc.name),
+          firstTokenAt: firstTokenAt ? new Date(firstTokenAt).toISOString() : undefined
         }
       };
 
@@ -25,7 +25,8 @@ export class DirectPermissionStrategy implements PermissionStrategy {
       // Request permission - the API client handles the waiting
       const approved = await this.apiClient.requestToolPermission({
         toolName: request.toolCall.name,
-        description: request.description || `Execute ${request.toolCall.name} tool`
+        description: request.description || `Execute ${request.toolCall.name} tool`,
+        input: request.toolCall.arguments
       });
 
       return approved;
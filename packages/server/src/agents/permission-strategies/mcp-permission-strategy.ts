@@ -42,7 +42,8 @@ export class MCPPermissionStrategy implements PermissionStrategy {
       // Request permission via our API
       const approved = await this.apiClient.requestToolPermission({
         toolName: request.toolCall.name,
-        description: request.description || this.getToolDescription(request.toolCall.name)
+        description: request.description || this.getToolDescription(request.toolCall.name),
+        input: request.toolCall.arguments
       });
 
       console.log(`[MCPPermissionStrategy] Permission ${approved ? 'granted' : 'denied'} for ${request.toolCall.name}`);
@@ -24,6 +24,8 @@ export interface AgentResponse {
     };
     processingTimeMs?: number;
     toolsUsed?: string[];
+    // ISO timestamp of the first generated token, when the provider reports progress
+    firstTokenAt?: string;
   };
 }
 
@@ -86,6 +86,10 @@ export class WakeProcessor {
     this.unsubscribeInteraction = this.interactionStore.subscribe(async (event: InteractionEvent) => {
       console.log(`[WakeProcessor] Received event: ${event.type}`);
       if (event.type === 'interaction:created') {
+        // Imported conversations are already answered, Wake joins when the user follows up
+        if (event.data.interaction.isImported) {
+          return;
+        }
         console.log(`[WakeProcessor] Spawning for new interaction: ${event.data.interaction.id}`);
         await this.spawnWakeProcess(event.data.interaction.id);
       }
@@ -93,7 +97,7 @@ export class WakeProcessor {
 
     // Listen to new messages to notify existing processes
     this.unsubscribeMessage = this.messageStore.subscribe(async (event: MessageEvent) => {
-      if (event.type === 'message:added' && event.data.message.role === 'user') {
+      if (event.type === 'message:added' && event.data.message.role === 'user' && event.data.message.status === 'pending') {
         console.log(`[WakeProcessor] User message added for interaction: ${event.data.interactionId}`);
         // Check if process exists for this interaction
         const process = this.processManager.getProcess(event.data.interactionId);
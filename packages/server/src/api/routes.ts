@@ -12,7 +12,10 @@ import { InMemoryWorktreeStore } from '../worktree/memory-store';
 import { createMonitoringRoutes } from './monitoring';
 import { ConversationService } from '../services/conversation-service';
 import { WorktreeService } from '../services/worktree-service';
+import { AgentService } from '../services/agent-service';
 import { createSSEStream } from '../utils/sse';
+import { AttachmentStore, MAX_ATTACHMENT_BYTES } from '../attachment/store';
+import { ArtifactStore } from '../artifact/store';
 
 /**
  * Clean API routes with proper separation of concerns
@@ -42,6 +45,8 @@ const initializeServices = async () => {
   const interactionStore = new InteractionStore();
   const messageStore = new MessageStore();
   const worktreeStore = new InMemoryWorktreeStore();
+  const attachmentStore = new AttachmentStore();
+  const artifactStore = new ArtifactStore();
   
   // Initialize worktree manager
   const repoRoot = process.env.BICAMRL_REPO_ROOT || process.cwd();
@@ -49,7 +54,7 @@ const initializeServices = async () => {
   await worktreeManager.initialize();
   
   // Initialize services
-  const conversationService = new ConversationService(interactionStore, messageStore, worktreeManager);
+  const conversationService = new ConversationService(interactionStore, messageStore, worktreeManager, attachmentStore, artifactStore);
   const worktreeService = new WorktreeService(worktreeManager);
   
   // Initialize wake processor
@@ -57,11 +62,16 @@ const initializeServices = async () => {
   const wakeProcessor = new WakeProcessor(interactionStore, messageStore, llmService, enableTools);
   await wakeProcessor.start();
   
+  const agentService = new AgentService(interactionStore, mindConfig, wakeProcessor);
+  
   return {
     interactionStore,
     messageStore,
+    attachmentStore,
+    artifactStore,
     conversationService,
     worktreeService,
+    agentService,
     wakeProcessor,
     mindConfig
   };
@@ -76,7 +86,7 @@ export const createApp = async (options?: { port?: number }) => {
   // Initialize services with optional port override
   const port = options?.port || process.env.PORT || 3456;
   const services = await initializeServices();
-  const { interactionStore, messageStore, conversationService, worktreeService, wakeProcessor } = services;
+  const { interactionStore, messageStore, attachmentStore, artifactStore, conversationService, worktreeService, agentService, wakeProcessor } = services;
   
   // Store port for Wake processes to use
   (wakeProcessor as any).serverPort = port;
@@ -98,7 +108,11 @@ export const createApp = async (options?: { port?: number }) => {
   
   app.get('/interactions/:id', async (c) => {
     const id = c.req.param('id');
-    const conversation = await conversationService.getConversation(id);
+    // ?limit=N pages from the newest messages back, ?before=<messageId> continues a page
+    const limit = Number(c.req.query('limit'));
+    const conversation = limit > 0
+      ? await conversationService.getConversationPage(id, limit, c.req.query('before'))
+      : await conversationService.getConversation(id);
     
     if (!conversation) {
       return c.json({ error: 'Interaction not found' }, 404);
@@ -107,6 +121,25 @@ export const createApp = async (options?: { port?: number }) => {
     return c.json(conversation);
   });
   
+  app.delete('/interactions/:id', async (c) => {
+    try {
+      const id = c.req.param('id');
+      if (!interactionStore.get(id)) {
+        return c.json({ error: 'Interaction not found' }, 404);
+      }
+      
+      await wakeProcessor.stopProcess(id);
+      await messageStore.deleteInteractionMessages(id);
+      await interactionStore.delete(id);
+      
+      return c.json({ success: true });
+      
+    } catch (error: any) {
+      console.error('[API] Error deleting interaction:', error);
+      return c.json({ error: 'Failed to delete interaction' }, 500);
+    }
+  });
+  
   app.post('/interactions/:id/result', async (c) => {
     try {
       const id = c.req.param('id');
@@ -140,7 +173,7 @@ export const createApp = async (options?: { port?: number }) => {
       });
       
     } catch (error: any) {
-      if (error.message === 'Content is required') {
+      if (error.message === 'Content is required' || error.message === 'Attachment not found') {
         return c.json({ error: error.message }, 400);
       }
       console.error('[API] Error creating message:', error);
@@ -148,6 +181,78 @@ export const createApp = async (options?: { port?: number }) => {
     }
   });
   
+  // Files to send with a message, uploaded first as the multipart field `file`
+  app.post('/attachments', async (c) => {
+    try {
+      const body = await c.req.parseBody();
+      const file = body['file'];
+      if (!(file instanceof File)) {
+        return c.json({ error: 'A file is required' }, 400);
+      }
+      if (file.size > MAX_ATTACHMENT_BYTES) {
+        return c.json({ error: 'File is larger than 10 MB' }, 413);
+      }
+      
+      const attachment = await attachmentStore.save(file.name, file.type, new Uint8Array(await file.arrayBuffer()));
+      return c.json({
+        id: attachment.id,
+        name: attachment.name,
+        size: attachment.size,
+        contentType: attachment.contentType
+      });
+      
+    } catch (error: any) {
+      console.error('[API] Error uploading attachment:', error);
+      return c.json({ error: 'Failed to upload attachment' }, 500);
+    }
+  });
+  
+  app.get('/attachments/:id', (c) => {
+    const id = c.req.param('id');
+    const attachment = attachmentStore.get(id);
+    const bytes = attachmentStore.read(id);
+    if (!attachment || !bytes) {
+      return c.json({ error: 'Attachment not found' }, 404);
+    }
+    
+    return new Response(bytes, {
+      headers: { 'Content-Type': attachment.contentType }
+    });
+  });
+  
+  // Files tool calls wrote, as they are on disk now
+  app.get('/artifacts/:id', (c) => {
+    const id = c.req.param('id');
+    const artifact = artifactStore.get(id);
+    const bytes = artifactStore.read(id);
+    if (!artifact || !bytes) {
+      return c.json({ error: 'Artifact not found' }, 404);
+    }
+    
+    return new Response(bytes, {
+      headers: { 'Content-Type': artifact.contentType }
+    });
+  });
+  
+  // Conversations from other tools' transcripts
+  app.post('/interactions/import', async (c) => {
+    try {
+      const request = await c.req.json();
+      const result = await conversationService.importConversation(request);
+      return c.json({
+        id: result.interactionId,
+        messageCount: result.messageCount
+      });
+      
+    } catch (error: any) {
+      if (error.message === 'Nothing to import') {
+        return c.json({ error: error.message }, 400);
+      }
+      console.error('[API] Error importing conversation:', error);
+      return c.json({ error: 'Failed to import conversation' }, 500);
+    }
+  });
+  
   // Worktree routes
   app.get('/worktrees', async (c) => {
     try {
@@ -172,6 +277,189 @@ export const createApp = async (options?: { port?: number }) => {
     }
   });
   
+  app.get('/worktrees/status', async (c) => {
+    try {
+      const statuses = await worktreeService.getStatuses();
+      return c.json(statuses);
+      
+    } catch (error: any) {
+      console.error('[API] Error getting worktree statuses:', error);
+      return c.json({ error: error.message || 'Failed to get worktree statuses' }, 500);
+    }
+  });
+  
+  app.get('/worktrees/:id/diff', async (c) => {
+    try {
+      const id = c.req.param('id');
+      if (!(await worktreeService.getWorktree(id))) {
+        return c.json({ error: 'Worktree not found' }, 404);
+      }
+      
+      const diff = await worktreeService.getDiff(id);
+      return c.json(diff);
+      
+    } catch (error: any) {
+      console.error('[API] Error diffing worktree:', error);
+      return c.json({ error: error.message || 'Failed to diff worktree' }, 500);
+    }
+  });
+  
+  app.post('/worktrees/:id/merge', async (c) => {
+    try {
+      const id = c.req.param('id');
+      if (!(await worktreeService.getWorktree(id))) {
+        return c.json({ error: 'Worktree not found' }, 404);
+      }
+      
+      const { message } = await c.req.json();
+      const result = await worktreeService.mergeWorktree(id, message);
+      return c.json(result);
+      
+    } catch (error: any) {
+      if (error.message === 'Commit message is required') {
+        return c.json({ error: error.message }, 400);
+      }
+      return c.json({ error: error.message || 'Failed to merge worktree' }, 500);
+    }
+  });
+  
+  app.post('/worktrees/:id/pr', async (c) => {
+    try {
+      const id = c.req.param('id');
+      if (!(await worktreeService.getWorktree(id))) {
+        return c.json({ error: 'Worktree not found' }, 404);
+      }
+      
+      const { title, body } = await c.req.json();
+      const result = await worktreeService.createPullRequest(id, title, body);
+      return c.json(result);
+      
+    } catch (error: any) {
+      if (error.message === 'Pull request title is required') {
+        return c.json({ error: error.message }, 400);
+      }
+      return c.json({ error: error.message || 'Failed to create pull request' }, 500);
+    }
+  });
+  
+  app.delete('/worktrees/:id', async (c) => {
+    try {
+      const id = c.req.param('id');
+      if (!(await worktreeService.getWorktree(id))) {
+        return c.json({ error: 'Worktree not found' }, 404);
+      }
+      
+      // Don't pull the checkout out from under a running Wake
+      const busy = interactionStore.getAll().some(interaction =>
+        interaction.metadata.worktreeContext?.worktreeId === id && interaction.hasActiveWake
+      );
+      if (busy) {
+        return c.json({ error: 'Worktree is in use by a running interaction' }, 409);
+      }
+      
+      await worktreeService.deleteWorktree(id);
+      return c.json({ success: true });
+      
+    } catch (error: any) {
+      console.error('[API] Error deleting worktree:', error);
+      return c.json({ error: error.message || 'Failed to delete worktree' }, 500);
+    }
+  });
+  
+  // Agent routes
+  app.get('/agents', (c) => {
+    return c.json(agentService.listAgents());
+  });
+  
+  app.get('/agents/status', (c) => {
+    return c.json(agentService.getStatuses());
+  });
+  
+  app.post('/agents/:id/restart', async (c) => {
+    try {
+      return c.json(await agentService.restartAgent(c.req.param('id')));
+      
+    } catch (error: any) {
+      if (error.message === 'Agent not found') {
+        return c.json({ error: error.message }, 404);
+      }
+      console.error('[API] Error restarting agent:', error);
+      return c.json({ error: 'Failed to restart agent' }, 500);
+    }
+  });
+  
+  app.put('/agents/:id/config', async (c) => {
+    try {
+      const config = await c.req.json();
+      const problem = AgentService.checkConfig(config);
+      if (problem) {
+        return c.json({ error: problem }, 400);
+      }
+      
+      return c.json(agentService.updateConfig(c.req.param('id'), config));
+      
+    } catch (error: any) {
+      if (error.message === 'Agent not found') {
+        return c.json({ error: error.message }, 404);
+      }
+      console.error('[API] Error updating agent config:', error);
+      return c.json({ error: 'Failed to update agent config' }, 500);
+    }
+  });
+  
+  app.post('/interactions/:id/agents', async (c) => {
+    try {
+      const id = c.req.param('id');
+      const { agentId } = await c.req.json();
+      if (!agentId) {
+        return c.json({ error: 'agentId is required' }, 400);
+      }
+      
+      const attachedAgents = await agentService.attachAgent(id, agentId);
+      return c.json({ attachedAgents });
+      
+    } catch (error: any) {
+      if (error.message === 'Interaction not found' || error.message === 'Agent not found') {
+        return c.json({ error: error.message }, 404);
+      }
+      console.error('[API] Error attaching agent:', error);
+      return c.json({ error: 'Failed to attach agent' }, 500);
+    }
+  });
+  
+  app.delete('/interactions/:id/agents/:agentId', async (c) => {
+    try {
+      const attachedAgents = await agentService.detachAgent(c.req.param('id'), c.req.param('agentId'));
+      return c.json({ attachedAgents });
+      
+    } catch (error: any) {
+      if (error.message === 'Interaction not found') {
+        return c.json({ error: error.message }, 404);
+      }
+      console.error('[API] Error detaching agent:', error);
+      return c.json({ error: 'Failed to detach agent' }, 500);
+    }
+  });
+  
+  // Replaces the tags, the GUI sends the full list after each add or remove
+  app.put('/interactions/:id/tags', async (c) => {
+    try {
+      const { tags } = await c.req.json();
+      if (!Array.isArray(tags) || !tags.every(tag => typeof tag === 'string')) {
+        return c.json({ error: 'tags must be a list of strings' }, 400);
+      }
+      
+      return c.json({ tags: await conversationService.setTags(c.req.param('id'), tags) });
+      
+    } catch (error: any) {
+      if (error.message === 'Interaction not found') {
+        return c.json({ error: error.message }, 404);
+      }
+      console.error('[API] Error updating interaction tags:', error);
+      return c.json({ error: 'Failed to update tags' }, 500);
+    }
+  });
+  
   // Interaction status update (for progress reporting)
   app.put('/interactions/:id/status', async (c) => {
     try {
@@ -187,6 +475,37 @@ export const createApp = async (options?: { port?: number }) => {
     }
   });
   
+  // Interrupt a running interaction
+  app.post('/interactions/:id/interrupt', async (c) => {
+    try {
+      const interactionId = c.req.param('id');
+      if (!interactionStore.get(interactionId)) {
+        return c.json({ error: 'Interaction not found' }, 404);
+      }
+      
+      await wakeProcessor.stopProcess(interactionId);
+      
+      // Nothing in flight will finish now that the process is gone
+      const inFlight = messageStore.getMessages(interactionId)
+        .filter(m => m.status === 'pending' || m.status === 'processing');
+      for (const message of inFlight) {
+        await messageStore.updateMessageMetadata(message.id, { interrupted: true });
+        await messageStore.updateMessageStatus(message.id, 'failed');
+      }
+      
+      await interactionStore.updateMetadata(interactionId, {
+        currentAction: undefined,
+        pendingToolPermission: undefined
+      });
+      
+      return c.json({ success: true, interrupted: inFlight.length });
+      
+    } catch (error: any) {
+      console.error('[API] Error interrupting interaction:', error);
+      return c.json({ error: 'Failed to interrupt interaction' }, 500);
+    }
+  });
+  
   // Message status update
   app.put('/messages/:id/status', async (c) => {
     try {
@@ -206,13 +525,14 @@ export const createApp = async (options?: { port?: number }) => {
   app.post('/interactions/:id/permission', async (c) => {
     try {
       const interactionId = c.req.param('id');
-      const { toolName, description, requestId } = await c.req.json();
+      const { toolName, description, requestId, input } = await c.req.json();
       
       const messageId = await conversationService.createPermissionRequest(
         interactionId,
         toolName,
         description,
-        requestId
+        requestId,
+        input
       );
       
       return c.json({ messageId });
@@ -367,9 +687,12 @@ export const createApp = async (options?: { port?: number }) => {
   (app as any).services = {
     interactionStore,
     messageStore,
+    attachmentStore,
+    artifactStore,
     wakeProcessor,
     conversationService,
-    worktreeService
+    worktreeService,
+    agentService
   };
   
   return app;
@@ -0,0 +1,53 @@
+import { describe, test, expect, beforeEach, afterEach } from 'bun:test';
+import { ArtifactStore } from '../store';
+import { existsSync, mkdirSync, rmSync, writeFileSync } from 'fs';
+import { join } from 'path';
+
+describe('ArtifactStore', () => {
+  let store: ArtifactStore;
+  let worktree: string;
+
+  beforeEach(() => {
+    worktree = `/tmp/test-artifacts-${Date.now()}`;
+    mkdirSync(join(worktree, 'src'), { recursive: true });
+    writeFileSync(join(worktree, 'src', 'main.rs'), 'fn main() {}');
+    store = new ArtifactStore();
+  });
+
+  afterEach(() => {
+    if (existsSync(worktree)) {
+      rmSync(worktree, { recursive: true, force: true });
+    }
+  });
+
+  test('registers the files writing tools named', () => {
+    const artifacts = store.registerToolCalls([
+      { id: '1', name: 'Read', arguments: { file_path: 'README.md' } },
+      { id: '2', name: 'Write', arguments: { file_path: 'src/main.rs', content: 'fn main() {}' } }
+    ], worktree);
+
+    expect(artifacts).toHaveLength(1);
+    expect(artifacts[0].name).toBe('main.rs');
+    expect(artifacts[0].path).toBe('src/main.rs');
+    expect(artifacts[0].size).toBe(12);
+    expect(artifacts[0].tool).toBe('Write');
+    expect(new TextDecoder().decode(store.read(artifacts[0].id)!)).toBe('fn main() {}');
+  });
+
+  test('a file written again keeps its id', () => {
+    const [first] = store.registerToolCalls([{ id: '1', name: 'Write', arguments: { file_path: 'src/main.rs' } }], worktree);
+    const [second] = store.registerToolCalls([{ id: '2', name: 'Edit', arguments: { file_path: join(worktree, 'src/main.rs') } }], worktree);
+
+    expect(second.id).toBe(first.id);
+    expect(store.get(first.id)?.tool).toBe('Edit');
+  });
+
+  test('deleted files are no longer served', () => {
+    const [artifact] = store.registerToolCalls([{ id: '1', name: 'write_file', arguments: { path: 'src/main.rs' } }], worktree);
+    rmSync(join(worktree, 'src', 'main.rs'));
+
+    expect(store.get(artifact.id)).not.toBeNull();
+    expect(store.read(artifact.id)).toBeNull();
+    expect(store.read('missing')).toBeNull();
+  });
+});
@@ -0,0 +1,100 @@
+import { existsSync, readFileSync, statSync } from 'fs';
+import { basename, extname, isAbsolute, relative, resolve } from 'path';
+import { v4 as uuidv4 } from 'uuid';
+import type { ToolCall } from '../llm/service';
+
+// Tools that leave a file behind at their `file_path` or `path` argument
+const FILE_WRITING_TOOLS = new Set(['Write', 'Edit', 'MultiEdit', 'write_file', 'edit_file']);
+
+const CONTENT_TYPES: Record<string, string> = {
+  '.txt': 'text/plain',
+  '.md': 'text/markdown',
+  '.json': 'application/json',
+  '.html': 'text/html',
+  '.css': 'text/css',
+  '.js': 'text/javascript',
+  '.ts': 'text/plain',
+  '.rs': 'text/plain',
+  '.py': 'text/plain',
+  '.csv': 'text/csv',
+  '.svg': 'image/svg+xml',
+  '.png': 'image/png',
+  '.jpg': 'image/jpeg',
+  '.jpeg': 'image/jpeg',
+  '.pdf': 'application/pdf'
+};
+
+/**
+ * A file a tool produced, as clients see it
+ */
+export interface Artifact {
+  id: string;
+  name: string;
+  // Relative to the worktree when the file is inside it
+  path: string;
+  size?: number;
+  contentType: string;
+  tool: string;
+}
+
+/**
+ * ArtifactStore - Files written by tool calls, looked up by id
+ *
+ * The files stay where the tools wrote them, a file written twice keeps its id.
+ */
+export class ArtifactStore {
+  private artifacts: Map<string, { artifact: Artifact; absolutePath: string }> = new Map();
+  private idsByPath: Map<string, string> = new Map();
+
+  /**
+   * The files a response's tool calls wrote, resolved against the directory they ran in
+   */
+  registerToolCalls(toolCalls: ToolCall[] | undefined, cwd: string): Artifact[] {
+    const artifacts: Artifact[] = [];
+    for (const call of toolCalls || []) {
+      if (!FILE_WRITING_TOOLS.has(call.name)) {
+        continue;
+      }
+      const target = call.arguments?.file_path ?? call.arguments?.path;
+      if (typeof target !== 'string' || target === '') {
+        continue;
+      }
+      const artifact = this.register(resolve(cwd, target), cwd, call.name);
+      if (!artifacts.some(a => a.id === artifact.id)) {
+        artifacts.push(artifact);
+      }
+    }
+    return artifacts;
+  }
+
+  register(absolutePath: string, cwd: string, tool: string): Artifact {
+    const id = this.idsByPath.get(absolutePath) || uuidv4();
+    const inside = relative(cwd, absolutePath);
+    const artifact: Artifact = {
+      id,
+      name: basename(absolutePath),
+      path: inside.startsWith('..') || isAbsolute(inside) ? absolutePath : inside,
+      size: existsSync(absolutePath) ? statSync(absolutePath).size : undefined,
+      contentType: CONTENT_TYPES[extname(absolutePath).toLowerCase()] || 'application/octet-stream',
+      tool
+    };
+    this.idsByPath.set(absolutePath, id);
+    this.artifacts.set(id, { artifact, absolutePath });
+    return artifact;
+  }
+
+  get(id: string): Artifact | null {
+    return this.artifacts.get(id)?.artifact || null;
+  }
+
+  /**
+   * The file as it is now, null when it's unknown or was since deleted
+   */
+  read(id: string): Uint8Array | null {
+    const entry = this.artifacts.get(id);
+    if (!entry || !existsSync(entry.absolutePath)) {
+      return null;
+    }
+    return readFileSync(entry.absolutePath);
+  }
+}
@@ -0,0 +1,47 @@
+import { describe, test, expect, beforeEach, afterEach } from 'bun:test';
+import { AttachmentStore } from '../store';
+import { existsSync, rmSync } from 'fs';
+import { dirname } from 'path';
+
+describe('AttachmentStore', () => {
+  let store: AttachmentStore;
+  let dir: string;
+
+  beforeEach(() => {
+    dir = `/tmp/test-attachments-${Date.now()}`;
+    store = new AttachmentStore(dir);
+  });
+
+  afterEach(() => {
+    if (existsSync(dir)) {
+      rmSync(dir, { recursive: true, force: true });
+    }
+  });
+
+  test('keeps the bytes and what the client called the file', async () => {
+    const bytes = new TextEncoder().encode('hello');
+    const attachment = await store.save('notes.txt', 'text/plain', bytes);
+
+    expect(attachment.name).toBe('notes.txt');
+    expect(attachment.size).toBe(5);
+    expect(attachment.contentType).toBe('text/plain');
+    expect(new TextDecoder().decode(store.read(attachment.id)!)).toBe('hello');
+  });
+
+  test('never lets the name choose where the file is written', async () => {
+    const attachment = await store.save('../../etc/passwd', '', new Uint8Array([1]));
+
+    expect(attachment.name).toBe('passwd');
+    expect(dirname(attachment.path)).toBe(dir);
+    expect(attachment.contentType).toBe('application/octet-stream');
+  });
+
+  test('resolves ids in order and refuses unknown ones', async () => {
+    const first = await store.save('a.txt', 'text/plain', new Uint8Array([1]));
+    const second = await store.save('b.txt', 'text/plain', new Uint8Array([2]));
+
+    expect(store.resolve([second.id, first.id]).map(a => a.name)).toEqual(['b.txt', 'a.txt']);
+    expect(() => store.resolve([first.id, 'missing'])).toThrow('Attachment not found');
+    expect(store.read('missing')).toBeNull();
+  });
+});
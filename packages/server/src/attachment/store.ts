@@ -0,0 +1,79 @@
+import { mkdirSync, readFileSync, writeFileSync } from 'fs';
+import { tmpdir } from 'os';
+import { basename, join } from 'path';
+import { v4 as uuidv4 } from 'uuid';
+
+// The GUI refuses bigger files before uploading, this catches other clients
+export const MAX_ATTACHMENT_BYTES = 10 * 1024 * 1024;
+
+/**
+ * A file uploaded to be sent with a message
+ */
+export interface Attachment {
+  id: string;
+  name: string;
+  size: number;
+  contentType: string;
+  // Where the bytes are on disk, for agents that read the file
+  path: string;
+}
+
+/**
+ * AttachmentStore - Uploaded files, written to disk and looked up by id
+ *
+ * Messages only carry the ids, the files stay here until the server stops.
+ */
+export class AttachmentStore {
+  private attachments: Map<string, Attachment> = new Map();
+
+  constructor(
+    private dir: string = process.env.BICAMRL_ATTACHMENTS_DIR || join(tmpdir(), 'bicamrl-attachments')
+  ) {}
+
+  /**
+   * Keep an uploaded file, named as the client named it
+   */
+  async save(name: string, contentType: string, bytes: Uint8Array): Promise<Attachment> {
+    mkdirSync(this.dir, { recursive: true });
+
+    const id = uuidv4();
+    const path = join(this.dir, id);
+    writeFileSync(path, bytes);
+
+    // The name is only shown, it never decides where the file goes
+    const attachment: Attachment = {
+      id,
+      name: basename(name) || id,
+      size: bytes.byteLength,
+      contentType: contentType || 'application/octet-stream',
+      path
+    };
+    this.attachments.set(id, attachment);
+    return attachment;
+  }
+
+  get(id: string): Attachment | null {
+    return this.attachments.get(id) || null;
+  }
+
+  /**
+   * The file's contents, null when there's no such attachment
+   */
+  read(id: string): Uint8Array | null {
+    const attachment = this.attachments.get(id);
+    return attachment ? readFileSync(attachment.path) : null;
+  }
+
+  /**
+   * The attachments a message names, in its order
+   */
+  resolve(ids: string[]): Attachment[] {
+    return ids.map(id => {
+      const attachment = this.attachments.get(id);
+      if (!attachment) {
+        throw new Error('Attachment not found');
+      }
+      return attachment;
+    });
+  }
+}
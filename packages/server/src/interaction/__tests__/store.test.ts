@@ -84,6 +84,26 @@ describe('InteractionStore', () => {
     });
   });
 
+  describe('delete', () => {
+    test('removes the interaction and emits interaction:deleted', async () => {
+      const interaction = Interaction.create({
+        source: 'user',
+        type: InteractionType.QUERY
+      });
+      await store.create(interaction);
+
+      const deleted = await store.delete(interaction.id);
+
+      expect(deleted).toBe(true);
+      expect(store.get(interaction.id)).toBeNull();
+      expect(emittedEvents.at(-1)?.type).toBe('interaction:deleted');
+    });
+
+    test('returns false for non-existent interaction', async () => {
+      expect(await store.delete('non-existent-id')).toBe(false);
+    });
+  });
+
   describe('getAll', () => {
     test('returns all interactions', () => {
       const interaction1 = store.create({
@@ -3,7 +3,7 @@
  * Provides clear separation between permanent context, temporary processing state, and results
  */
 
-import type { TokenUsage } from '../llm/service';
+import type { TokenUsage, ToolCall } from '../llm/service';
 
 /**
  * Permanent context that travels with the interaction
@@ -36,6 +36,7 @@ export interface ResultMetadata {
   usage?: TokenUsage;
   processingTimeMs?: number;
   toolsUsed?: string[];
+  toolCalls?: ToolCall[];
   completedAt?: Date;
 }
 
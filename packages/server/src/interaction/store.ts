@@ -2,7 +2,7 @@ import { EventEmitter } from 'events';
 import { Interaction } from './types';
 
 export interface InteractionEvent {
-  type: 'interaction:created' | 'interaction:updated';
+  type: 'interaction:created' | 'interaction:updated' | 'interaction:deleted';
   timestamp: Date;
   data: {
     interaction: Interaction;
@@ -68,6 +68,26 @@ export class InteractionStore extends EventEmitter {
     });
   }
 
+  /**
+   * Delete an interaction, returns false if it didn't exist
+   */
+  async delete(id: string): Promise<boolean> {
+    const interaction = this.interactions.get(id);
+    if (!interaction) {
+      return false;
+    }
+
+    this.interactions.delete(id);
+
+    this.emit('event', {
+      type: 'interaction:deleted',
+      timestamp: new Date(),
+      data: { interaction }
+    });
+
+    return true;
+  }
+
   /**
    * Update interaction metadata
    */
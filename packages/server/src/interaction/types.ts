@@ -28,6 +28,8 @@ export interface InteractionMetadata {
   // Process management
   wakeProcessId?: string;
   sleepProcessId?: string;
+  // What Wake is doing right now, cleared when it answers
+  currentAction?: string | null;
   
   // User preferences
   title?: string;
@@ -35,6 +37,15 @@ export interface InteractionMetadata {
   
   // System metadata
   lastActivityAt?: Date;
+  
+  // Agents put to work on it, by id
+  attachedAgents?: string[];
+  
+  // Set on conversations brought in from another tool's transcript
+  imported?: {
+    from: string;
+    importedAt: Date;
+  };
 }
 
 export interface InteractionData {
@@ -54,12 +65,13 @@ export class Interaction {
     source: string;
     type: InteractionType;
     metadata?: InteractionMetadata;
+    createdAt?: Date;
   }): Interaction {
     const data: InteractionData = {
       id: uuidv4(),
       source: params.source,
       type: params.type,
-      createdAt: new Date(),
+      createdAt: params.createdAt || new Date(),
       metadata: params.metadata || {}
     };
     
@@ -93,6 +105,10 @@ export class Interaction {
     return this.data.metadata.wakeProcessId;
   }
 
+  get isImported(): boolean {
+    return !!this.data.metadata.imported;
+  }
+
   get hasActiveWake(): boolean {
     return !!this.data.metadata.wakeProcessId;
   }
@@ -1,6 +1,6 @@
 import { describe, test, expect, beforeEach } from 'bun:test';
 import { MessageStore } from '../store';
-import { MessageStatus } from '../types';
+import { Message, MessageStatus } from '../types';
 import type { MessageEvent } from '../store';
 
 describe('MessageStore', () => {
@@ -392,4 +392,43 @@ describe('MessageStore', () => {
       expect(messages[1].timestamp.getTime()).toBeLessThanOrEqual(messages[2].timestamp.getTime());
     });
   });
-});
\ No newline at end of file
+
+  describe('getMessagePage', () => {
+    const addTurns = async (turns: number) => {
+      const ids: string[] = [];
+      for (let i = 0; i < turns * 2; i++) {
+        const message = Message.create({
+          interactionId: 'interaction-1',
+          role: i % 2 === 0 ? 'user' : 'assistant',
+          content: `Message ${i}`
+        });
+        await store.addMessage(message);
+        ids.push(message.id);
+      }
+      return ids;
+    };
+
+    test('returns the newest messages first', async () => {
+      const ids = await addTurns(3);
+
+      const page = store.getMessagePage('interaction-1', 2);
+      expect(page.messages.map(m => m.id)).toEqual(ids.slice(4));
+      expect(page.hasMore).toBe(true);
+    });
+
+    test('continues before the given message', async () => {
+      const ids = await addTurns(3);
+
+      const page = store.getMessagePage('interaction-1', 4, ids[4]);
+      expect(page.messages.map(m => m.id)).toEqual(ids.slice(0, 4));
+      expect(page.hasMore).toBe(false);
+    });
+
+    test('never starts on an assistant message', async () => {
+      const ids = await addTurns(3);
+
+      const page = store.getMessagePage('interaction-1', 3);
+      expect(page.messages.map(m => m.id)).toEqual(ids.slice(2));
+    });
+  });
+});
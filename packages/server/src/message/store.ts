@@ -124,6 +124,17 @@ export class MessageStore extends EventEmitter {
     });
   }
 
+  /**
+   * Remove every message belonging to an interaction
+   */
+  async deleteInteractionMessages(interactionId: string): Promise<void> {
+    const messages = this.messagesByInteraction.get(interactionId) || [];
+    for (const message of messages) {
+      this.messagesById.delete(message.id);
+    }
+    this.messagesByInteraction.delete(interactionId);
+  }
+
   /**
    * Get the last message in an interaction
    */
@@ -165,4 +176,29 @@ export class MessageStore extends EventEmitter {
     const messages = this.getMessages(interactionId);
     return messages.map(m => m.toJSON());
   }
+
+  /**
+   * Get the newest `limit` messages before `before` (or the end), oldest first
+   * A page never starts on an assistant message, its user message comes along
+   */
+  getMessagePage(interactionId: string, limit: number, before?: string): { messages: any[]; hasMore: boolean } {
+    const messages = this.getMessages(interactionId);
+    let end = messages.length;
+    if (before) {
+      const index = messages.findIndex(m => m.id === before);
+      if (index >= 0) {
+        end = index;
+      }
+    }
+
+    let start = Math.max(0, end - limit);
+    while (start > 0 && messages[start]!.role === 'assistant') {
+      start--;
+    }
+
+    return {
+      messages: messages.slice(start, end).map(m => m.toJSON()),
+      hasMore: start > 0
+    };
+  }
 }
\ No newline at end of file
@@ -14,11 +14,25 @@ export interface TokenUsage {
   totalTokens: number;
 }
 
+/**
+ * When each stage of answering a message happened, as ISO timestamps
+ */
+export interface ResponseTimings {
+  // Wake picked the message up
+  startedAt?: string;
+  // The conversation was loaded and handed to the agent
+  modelStartedAt?: string;
+  // Only agents that report progress know this
+  firstTokenAt?: string;
+  completedAt?: string;
+}
+
 export interface MessageMetadata {
   // LLM-related metadata
   model?: string;
   usage?: TokenUsage;
   processingTimeMs?: number;
+  timings?: ResponseTimings;
   
   // Tool-related metadata
   toolsUsed?: string[];
@@ -40,6 +54,35 @@ export interface MessageMetadata {
     requestId: string;
   };
   permissionResponse?: boolean;
+  
+  // Files sent with a user message, uploaded beforehand
+  attachments?: {
+    id: string;
+    name: string;
+    size: number;
+    contentType: string;
+    path: string;
+  }[];
+  
+  // Files the response's tool calls wrote, served from /artifacts/:id
+  artifacts?: {
+    id: string;
+    name: string;
+    path: string;
+    size?: number;
+    contentType: string;
+    tool: string;
+  }[];
+  
+  // Set when the user stopped processing before it finished
+  interrupted?: boolean;
+  
+  // Set on a user message that picks up an interrupted or failed one
+  continuation?: {
+    resumeOf: string;
+    partialOutput?: string;
+    lastAction?: string;
+  };
 }
 
 export interface MessageData {
@@ -62,14 +105,17 @@ export class Message {
     role: MessageRole;
     content: string;
     metadata?: MessageMetadata;
+    // Only for messages that already happened elsewhere, e.g. imported ones
+    timestamp?: Date;
+    status?: MessageStatus;
   }): Message {
     const data: MessageData = {
       id: uuidv4(),
       interactionId: params.interactionId,
       role: params.role,
       content: params.content,
-      timestamp: new Date(),
-      status: params.role === 'user' ? 'pending' : 'completed',
+      timestamp: params.timestamp || new Date(),
+      status: params.status || (params.role === 'user' ? 'pending' : 'completed'),
       metadata: params.metadata
     };
     
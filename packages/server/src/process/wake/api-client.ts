@@ -61,6 +61,7 @@ export class WakeApiClient {
   async requestToolPermission(request: {
     toolName: string;
     description: string;
+    input?: Record<string, any>;
   }): Promise<boolean> {
     const requestId = Math.random().toString(36).substring(7);
     
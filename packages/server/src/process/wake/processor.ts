@@ -137,6 +137,7 @@ export class WakeProcessor {
       const interaction = conversation; // In new architecture, conversation IS the interaction
       
       // Process with agent
+      const modelStartedAt = new Date().toISOString();
       const agentResponse = await this.agent!.process(interaction, conversation.messages);
       
       // Track tools used
@@ -154,7 +155,14 @@ export class WakeProcessor {
         processingTimeMs: processingTime,
         usage: agentResponse.metadata?.usage,
         toolsUsed: this.toolsUsed,
-        model: agentResponse.metadata?.model
+        toolCalls: agentResponse.toolCalls,
+        model: agentResponse.metadata?.model,
+        timings: {
+          startedAt: new Date(this.processingStartTime).toISOString(),
+          modelStartedAt,
+          firstTokenAt: agentResponse.metadata?.firstTokenAt,
+          completedAt: new Date().toISOString()
+        }
       });
       
       // Update message status
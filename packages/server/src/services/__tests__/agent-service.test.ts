@@ -0,0 +1,132 @@
+import { describe, test, expect, beforeEach } from 'bun:test';
+import { AgentService, type AgentProcesses } from '../agent-service';
+import { InteractionStore } from '../../interaction/store';
+import { Interaction, InteractionType } from '../../interaction/types';
+import type { MindConfig } from '../../config/mind';
+
+describe('AgentService', () => {
+  let service: AgentService;
+  let interactionStore: InteractionStore;
+  let interactionId: string;
+
+  const mindConfig: MindConfig = {
+    default_provider: 'mock',
+    llm_providers: {
+      mock: { type: 'mock', enabled: true },
+      lmstudio: { type: 'lmstudio', enabled: true, model: 'qwen' },
+      claude_code: { type: 'claude_code', enabled: false }
+    }
+  };
+
+  beforeEach(async () => {
+    interactionStore = new InteractionStore();
+    const interaction = Interaction.create({ source: 'user', type: InteractionType.QUERY });
+    await interactionStore.create(interaction);
+    interactionId = interaction.id;
+
+    service = new AgentService(interactionStore, mindConfig);
+  });
+
+  test('offers the enabled providers as agents', () => {
+    const agents = service.listAgents();
+
+    expect(agents.map(a => a.id)).toEqual(['mock', 'lmstudio']);
+    expect(agents[1].description).toBe('lmstudio, qwen');
+  });
+
+  test('attaches each agent once and keeps the order', async () => {
+    await service.attachAgent(interactionId, 'lmstudio');
+    await service.attachAgent(interactionId, 'mock');
+    const attached = await service.attachAgent(interactionId, 'lmstudio');
+
+    expect(attached).toEqual(['lmstudio', 'mock']);
+    expect(interactionStore.get(interactionId)?.metadata.attachedAgents).toEqual(['lmstudio', 'mock']);
+  });
+
+  test('detaching leaves the other agents attached', async () => {
+    await service.attachAgent(interactionId, 'lmstudio');
+    await service.attachAgent(interactionId, 'mock');
+
+    expect(await service.detachAgent(interactionId, 'lmstudio')).toEqual(['mock']);
+  });
+
+  test('keeps the configuration it was given', () => {
+    const kept = service.updateConfig('mock', { systemPrompt: 'Be brief', maxTokens: 512, allowedTools: ['Read'] });
+
+    expect(kept).toEqual({ systemPrompt: 'Be brief', maxTokens: 512, allowedTools: ['Read'] });
+    expect(service.getAgent('mock')?.config).toEqual(kept);
+  });
+
+  test('refuses configurations providers cannot use', () => {
+    expect(() => service.updateConfig('mock', { temperature: 3 })).toThrow('temperature must be between 0 and 2');
+    expect(() => service.updateConfig('mock', { maxTokens: 0 })).toThrow('maxTokens must be a positive whole number');
+    expect(() => service.updateConfig('claude_code', {})).toThrow('Agent not found');
+    expect(service.getAgent('mock')?.config).toEqual({});
+  });
+
+  test('refuses unknown agents and interactions', async () => {
+    await expect(service.attachAgent(interactionId, 'claude_code')).rejects.toThrow('Agent not found');
+    await expect(service.attachAgent('missing', 'mock')).rejects.toThrow('Interaction not found');
+  });
+
+  describe('statuses', () => {
+    let restarted: string[];
+    let failRestart: boolean;
+
+    // Wake processes for the interactions in `ids`, started at noon
+    const processes = (ids: string[]): AgentProcesses => ({
+      getActiveProcesses: () => ids.map(id => ({ id, startedAt: new Date('2024-01-01T12:00:00Z') })) as any,
+      restartProcess: async (id: string) => {
+        if (failRestart) {
+          throw new Error('Process exited with code 1');
+        }
+        restarted.push(id);
+      }
+    });
+
+    beforeEach(() => {
+      restarted = [];
+      failRestart = false;
+    });
+
+    test('the default agent works on interactions with none attached', async () => {
+      service = new AgentService(interactionStore, mindConfig, processes([interactionId]));
+      await interactionStore.updateMetadata(interactionId, { currentAction: 'Running Bash' });
+
+      const [mock, lmstudio] = service.getStatuses();
+      expect(mock).toEqual({
+        agentId: 'mock',
+        status: 'busy',
+        startedAt: '2024-01-01T12:00:00.000Z',
+        currentInteraction: interactionId,
+        currentTask: 'Running Bash'
+      });
+      expect(lmstudio).toEqual({ agentId: 'lmstudio', status: 'idle', startedAt: undefined });
+    });
+
+    test('restarting goes through the attached agent\'s processes', async () => {
+      service = new AgentService(interactionStore, mindConfig, processes([interactionId]));
+      await service.attachAgent(interactionId, 'lmstudio');
+
+      expect((await service.restartAgent('mock')).status).toBe('idle');
+      expect(restarted).toEqual([]);
+      expect((await service.restartAgent('lmstudio')).status).toBe('idle');
+      expect(restarted).toEqual([interactionId]);
+    });
+
+    test('a failed restart reports the agent crashed until one succeeds', async () => {
+      service = new AgentService(interactionStore, mindConfig, processes([interactionId]));
+      failRestart = true;
+
+      expect(await service.restartAgent('mock')).toEqual({
+        agentId: 'mock',
+        status: 'crashed',
+        error: 'Process exited with code 1'
+      });
+
+      failRestart = false;
+      expect((await service.restartAgent('mock')).status).toBe('idle');
+      await expect(service.restartAgent('claude_code')).rejects.toThrow('Agent not found');
+    });
+  });
+});
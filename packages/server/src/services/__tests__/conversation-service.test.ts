@@ -6,6 +6,8 @@ import { WorktreeManager } from '../../worktree/manager';
 import { InMemoryWorktreeStore } from '../../worktree/memory-store';
 import { InteractionType } from '../../interaction/types';
 import { MessageStatus } from '../../message/types';
+import { AttachmentStore } from '../../attachment/store';
+import { ArtifactStore } from '../../artifact/store';
 
 describe('ConversationService', () => {
   let service: ConversationService;
@@ -82,6 +84,24 @@ describe('ConversationService', () => {
       expect(interaction?.metadata.worktreeContext?.worktreeId).toBe('wt-123');
     });
 
+    test('puts uploaded attachments on the message', async () => {
+      const attachmentStore = new AttachmentStore(`/tmp/test-attachments-${Date.now()}`);
+      const attachment = await attachmentStore.save('notes.txt', 'text/plain', new TextEncoder().encode('hi'));
+      service = new ConversationService(interactionStore, messageStore, worktreeManager, attachmentStore);
+
+      const result = await service.handleSendMessage({
+        content: 'See attached',
+        attachmentIds: [attachment.id]
+      });
+
+      const message = messageStore.getMessage(result.messageId);
+      expect(message?.metadata?.attachments?.map(a => a.name)).toEqual(['notes.txt']);
+      await expect(service.handleSendMessage({
+        content: 'Missing file',
+        attachmentIds: ['missing']
+      })).rejects.toThrow('Attachment not found');
+    });
+
     test('throws error when content is empty', async () => {
       await expect(service.handleSendMessage({
         content: ''
@@ -111,6 +131,52 @@ describe('ConversationService', () => {
     });
   });
 
+  describe('importConversation', () => {
+    test('stores every message as already finished', async () => {
+      const result = await service.importConversation({
+        title: 'Fix the parser',
+        from: 'claude-code',
+        messages: [
+          { role: 'user', content: 'Why does it panic?', timestamp: '2024-03-01T10:00:00Z' },
+          { role: 'assistant', content: 'An unchecked index.', timestamp: '2024-03-01T10:00:05Z' }
+        ]
+      });
+
+      expect(result.messageCount).toBe(2);
+      const interaction = interactionStore.get(result.interactionId);
+      expect(interaction?.metadata.title).toBe('Fix the parser');
+      expect(interaction?.metadata.imported?.from).toBe('claude-code');
+      expect(interaction?.createdAt.toISOString()).toBe('2024-03-01T10:00:00.000Z');
+
+      const messages = messageStore.getMessages(result.interactionId);
+      expect(messages.map(m => m.status)).toEqual(['completed', 'completed']);
+      expect(messageStore.getPendingMessages(result.interactionId)).toHaveLength(0);
+    });
+
+    test('keeps the order of untimed messages', async () => {
+      const result = await service.importConversation({
+        from: 'markdown',
+        messages: [
+          { role: 'user', content: 'First' },
+          { role: 'assistant', content: 'Second' },
+          { role: 'user', content: 'Third' }
+        ]
+      });
+
+      const messages = messageStore.getMessages(result.interactionId);
+      const times = messages.map(m => m.timestamp.getTime());
+      expect(times[0]).toBeLessThan(times[1]);
+      expect(times[1]).toBeLessThan(times[2]);
+    });
+
+    test('rejects a transcript without messages', async () => {
+      await expect(service.importConversation({
+        from: 'markdown',
+        messages: [{ role: 'user', content: '   ' }]
+      })).rejects.toThrow('Nothing to import');
+    });
+  });
+
   describe('getConversation', () => {
     test('assembles full conversation with messages', async () => {
       // Create conversation with multiple messages
@@ -212,6 +278,23 @@ describe('ConversationService', () => {
       expect(assistant.metadata?.usage?.totalTokens).toBe(30);
     });
 
+    test('lists the files tool calls wrote as artifacts', async () => {
+      service = new ConversationService(interactionStore, messageStore, worktreeManager, undefined, new ArtifactStore());
+      const { interactionId } = await service.handleSendMessage({
+        content: 'Write a script'
+      });
+
+      await service.submitAssistantResponse(interactionId, 'Wrote it', {
+        toolCalls: [
+          { id: 'call-1', name: 'Write', arguments: { file_path: 'script.sh', content: 'echo hi' } },
+          { id: 'call-2', name: 'Bash', arguments: { command: 'ls' } }
+        ]
+      });
+
+      const assistant = messageStore.getMessages(interactionId)[1];
+      expect(assistant.metadata?.artifacts?.map(a => a.name)).toEqual(['script.sh']);
+    });
+
     test('clears currentAction from interaction metadata', async () => {
       const { interactionId } = await service.handleSendMessage({
         content: 'Test'
@@ -280,6 +363,24 @@ describe('ConversationService', () => {
       expect(interaction?.metadata.pendingPermission?.messageId).toBe(messageId);
       expect(interaction?.metadata.pendingPermission?.toolName).toBe('read_file');
     });
+
+    test('carries the tool input to the pending tool permission', async () => {
+      const { interactionId } = await service.handleSendMessage({
+        content: 'Test'
+      });
+
+      await service.createPermissionRequest(
+        interactionId,
+        'Bash',
+        'Execute a shell command',
+        'req-456',
+        { command: 'rm -rf build' }
+      );
+
+      const interaction = interactionStore.get(interactionId);
+      expect(interaction?.metadata.pendingToolPermission?.toolName).toBe('Bash');
+      expect(interaction?.metadata.pendingToolPermission?.input).toEqual({ command: 'rm -rf build' });
+    });
   });
 
   describe('handlePermissionResponse', () => {
@@ -346,6 +447,22 @@ describe('ConversationService', () => {
     });
   });
 
+  describe('setTags', () => {
+    test('replaces the tags, dropping blank and repeated ones', async () => {
+      const { interactionId } = await service.handleSendMessage({ content: 'Tag me' });
+
+      await service.setTags(interactionId, ['bug']);
+      const tags = await service.setTags(interactionId, [' review ', 'ui', '', 'review']);
+
+      expect(tags).toEqual(['review', 'ui']);
+      expect(interactionStore.get(interactionId)?.metadata.tags).toEqual(['review', 'ui']);
+    });
+
+    test('refuses unknown interactions', async () => {
+      await expect(service.setTags('missing', ['bug'])).rejects.toThrow('Interaction not found');
+    });
+  });
+
   describe('message status management', () => {
     test('updates user message status during processing', async () => {
       const { interactionId, messageId } = await service.handleSendMessage({
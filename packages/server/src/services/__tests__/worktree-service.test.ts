@@ -201,6 +201,78 @@ describe('WorktreeService', () => {
     });
   });
 
+  describe('getDiff', () => {
+    test('includes committed and uncommitted changes since the base', async () => {
+      await worktreeManager.initialize();
+      
+      const worktree = await service.createWorktree({
+        branch: 'feature/diffed'
+      });
+
+      execSync('echo "committed" > committed.txt', { cwd: worktree.path });
+      execSync('git add . && git commit -m "Add file"', { cwd: worktree.path });
+      execSync('echo "changed" >> README.md', { cwd: worktree.path });
+
+      const { base, diff } = await service.getDiff(worktree.id);
+      expect(base).toBe(worktree.baseCommit!);
+      expect(diff).toContain('+++ b/committed.txt');
+      expect(diff).toContain('+changed');
+    });
+
+    test('throws for non-existent worktree', async () => {
+      await worktreeManager.initialize();
+
+      await expect(service.getDiff('non-existent')).rejects.toThrow('not found');
+    });
+  });
+
+  describe('getStatuses', () => {
+    test('counts uncommitted files and commits ahead of the base', async () => {
+      await worktreeManager.initialize();
+      
+      const worktree = await service.createWorktree({
+        branch: 'feature/status'
+      });
+
+      execSync('echo "committed" > committed.txt', { cwd: worktree.path });
+      execSync('git add . && git commit -m "Add file"', { cwd: worktree.path });
+      execSync('echo "draft" > draft.txt', { cwd: worktree.path });
+      execSync('echo "changed" >> README.md', { cwd: worktree.path });
+
+      const statuses = await service.getStatuses();
+      expect(statuses[worktree.id]).toEqual({ dirty: 2, ahead: 1, behind: 0 });
+    });
+  });
+
+  describe('mergeWorktree', () => {
+    test('commits outstanding changes and merges into the base branch', async () => {
+      await worktreeManager.initialize();
+      
+      const worktree = await service.createWorktree({
+        branch: 'feature/merged'
+      });
+      execSync('echo "merged" > merged.txt', { cwd: worktree.path });
+
+      const result = await service.mergeWorktree(worktree.id, 'Add merged file');
+      
+      expect(result.commit).toBeDefined();
+      expect(existsSync(join(testRepoPath, 'merged.txt'))).toBe(true);
+      const log = execSync('git log --format=%s', { cwd: testRepoPath, encoding: 'utf-8' });
+      expect(log).toContain('Add merged file');
+    });
+
+    test('requires a commit message', async () => {
+      await worktreeManager.initialize();
+      const worktree = await service.createWorktree({
+        branch: 'feature/unmerged'
+      });
+
+      await expect(
+        service.mergeWorktree(worktree.id, '  ')
+      ).rejects.toThrow('Commit message is required');
+    });
+  });
+
   describe('getWorktree', () => {
     test('retrieves worktree by id', async () => {
       await worktreeManager.initialize();
@@ -0,0 +1,213 @@
+import type { InteractionStore } from '../interaction/store';
+import type { MindConfig } from '../config/mind';
+import type { WakeProcessor } from '../agents/wake-processor';
+
+/**
+ * An agent clients can put to work on interactions, one per enabled LLM provider
+ */
+export interface Agent {
+  id: string;
+  name: string;
+  description?: string;
+  config?: AgentConfig;
+}
+
+/**
+ * How an agent works, any field left out uses the provider's default
+ */
+export interface AgentConfig {
+  systemPrompt?: string;
+  temperature?: number;
+  maxTokens?: number;
+  // Every tool when left out
+  allowedTools?: string[];
+}
+
+/**
+ * How an agent is doing, as `GET /agents/status` reports it
+ */
+export interface AgentStatus {
+  agentId: string;
+  status: 'idle' | 'busy' | 'crashed' | 'restarting';
+  // When its oldest running Wake process started
+  startedAt?: string;
+  currentInteraction?: string;
+  currentTask?: string;
+  error?: string;
+}
+
+// The part of the wake processor statuses and restarts need
+export type AgentProcesses = Pick<WakeProcessor, 'getActiveProcesses' | 'restartProcess'>;
+
+export class AgentService {
+  private configs: Map<string, AgentConfig> = new Map();
+  private restarting: Set<string> = new Set();
+  // Why the last restart failed, until one succeeds
+  private crashes: Map<string, string> = new Map();
+
+  constructor(
+    private interactionStore: InteractionStore,
+    private mindConfig: MindConfig,
+    private processes?: AgentProcesses
+  ) {}
+
+  listAgents(): Agent[] {
+    return Object.entries(this.mindConfig.llm_providers || {})
+      .filter(([, provider]) => provider.enabled !== false)
+      .map(([id, provider]) => ({
+        id,
+        name: id,
+        description: provider.model ? `${provider.type}, ${provider.model}` : provider.type,
+        config: this.getConfig(id, provider.temperature)
+      }));
+  }
+
+  getAgent(id: string): Agent | null {
+    return this.listAgents().find(agent => agent.id === id) || null;
+  }
+
+  /**
+   * Replace an agent's configuration, gives the one now kept
+   */
+  updateConfig(agentId: string, config: AgentConfig): AgentConfig {
+    if (!this.getAgent(agentId)) {
+      throw new Error('Agent not found');
+    }
+    const problem = AgentService.checkConfig(config);
+    if (problem) {
+      throw new Error(problem);
+    }
+    
+    const { systemPrompt, temperature, maxTokens, allowedTools } = config;
+    // Only the known fields are kept, null clears one back to the default
+    const kept: AgentConfig = {};
+    if (systemPrompt) kept.systemPrompt = systemPrompt;
+    if (typeof temperature === 'number') kept.temperature = temperature;
+    if (typeof maxTokens === 'number') kept.maxTokens = maxTokens;
+    if (Array.isArray(allowedTools)) kept.allowedTools = allowedTools;
+    this.configs.set(agentId, kept);
+    return kept;
+  }
+
+  /**
+   * Why a configuration can't be used, null when it can
+   */
+  static checkConfig(config: AgentConfig): string | null {
+    const { systemPrompt, temperature, maxTokens, allowedTools } = config;
+    if (systemPrompt != null && typeof systemPrompt !== 'string') {
+      return 'systemPrompt must be a string';
+    }
+    if (temperature != null && (typeof temperature !== 'number' || temperature < 0 || temperature > 2)) {
+      return 'temperature must be between 0 and 2';
+    }
+    if (maxTokens != null && (!Number.isInteger(maxTokens) || maxTokens <= 0)) {
+      return 'maxTokens must be a positive whole number';
+    }
+    if (allowedTools != null && (!Array.isArray(allowedTools) || allowedTools.some(t => typeof t !== 'string'))) {
+      return 'allowedTools must be a list of tool names';
+    }
+    return null;
+  }
+
+  private getConfig(agentId: string, defaultTemperature?: number): AgentConfig {
+    const config = this.configs.get(agentId);
+    if (config) {
+      return config;
+    }
+    return defaultTemperature !== undefined ? { temperature: defaultTemperature } : {};
+  }
+
+  getStatuses(): AgentStatus[] {
+    return this.listAgents().map(agent => this.getStatus(agent.id));
+  }
+
+  getStatus(agentId: string): AgentStatus {
+    if (this.restarting.has(agentId)) {
+      return { agentId, status: 'restarting' };
+    }
+    const error = this.crashes.get(agentId);
+    if (error) {
+      return { agentId, status: 'crashed', error };
+    }
+    
+    const running = this.runningProcesses(agentId);
+    const startedAt = running
+      .map(p => p.startedAt)
+      .sort((a, b) => a.getTime() - b.getTime())[0]
+      ?.toISOString();
+    const busy = running
+      .map(p => this.interactionStore.get(p.id))
+      .find(interaction => interaction?.metadata.currentAction);
+    if (busy) {
+      return {
+        agentId,
+        status: 'busy',
+        startedAt,
+        currentInteraction: busy.id,
+        currentTask: busy.metadata.currentAction || undefined
+      };
+    }
+    return { agentId, status: 'idle', startedAt };
+  }
+
+  /**
+   * Restart the Wake processes working as this agent, gives its status after
+   */
+  async restartAgent(agentId: string): Promise<AgentStatus> {
+    if (!this.getAgent(agentId)) {
+      throw new Error('Agent not found');
+    }
+    
+    this.restarting.add(agentId);
+    try {
+      for (const wake of this.runningProcesses(agentId)) {
+        await this.processes!.restartProcess(wake.id);
+      }
+      this.crashes.delete(agentId);
+    } catch (error: any) {
+      this.crashes.set(agentId, error.message || 'Restart failed');
+    } finally {
+      this.restarting.delete(agentId);
+    }
+    return this.getStatus(agentId);
+  }
+
+  // Wake processes for interactions this agent works on, the default provider's when none is attached
+  private runningProcesses(agentId: string) {
+    return (this.processes?.getActiveProcesses() || []).filter(wake => {
+      const attached = this.interactionStore.get(wake.id)?.metadata.attachedAgents || [];
+      return attached.length > 0 ? attached.includes(agentId) : agentId === this.mindConfig.default_provider;
+    });
+  }
+
+  /**
+   * The agents attached to an interaction, in the order they were attached
+   */
+  getAttachedAgents(interactionId: string): string[] {
+    const interaction = this.interactionStore.get(interactionId);
+    if (!interaction) {
+      throw new Error('Interaction not found');
+    }
+    return interaction.metadata.attachedAgents || [];
+  }
+
+  async attachAgent(interactionId: string, agentId: string): Promise<string[]> {
+    const attached = this.getAttachedAgents(interactionId);
+    if (!this.getAgent(agentId)) {
+      throw new Error('Agent not found');
+    }
+    if (attached.includes(agentId)) {
+      return attached;
+    }
+    
+    const attachedAgents = [...attached, agentId];
+    await this.interactionStore.updateMetadata(interactionId, { attachedAgents });
+    return attachedAgents;
+  }
+
+  async detachAgent(interactionId: string, agentId: string): Promise<string[]> {
+    const attachedAgents = this.getAttachedAgents(interactionId).filter(id => id !== agentId);
+    await this.interactionStore.updateMetadata(interactionId, { attachedAgents });
+    return attachedAgents;
+  }
+}
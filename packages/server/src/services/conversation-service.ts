@@ -2,8 +2,11 @@ import { InteractionStore } from '../interaction/store';
 import { MessageStore } from '../message/store';
 import { Interaction, InteractionType } from '../interaction/types';
 import { Message } from '../message/types';
-import type { SendMessageRequest } from '@bicamrl/shared';
+import type { MessageMetadata } from '../message/types';
+import type { ImportConversationRequest, SendMessageRequest } from '@bicamrl/shared';
 import type { WorktreeManager } from '../worktree/manager';
+import type { AttachmentStore } from '../attachment/store';
+import type { ArtifactStore } from '../artifact/store';
 
 /**
  * ConversationService - Coordinates interactions and messages
@@ -16,7 +19,9 @@ export class ConversationService {
   constructor(
     private interactionStore: InteractionStore,
     private messageStore: MessageStore,
-    private worktreeManager: WorktreeManager
+    private worktreeManager: WorktreeManager,
+    private attachmentStore?: AttachmentStore,
+    private artifactStore?: ArtifactStore
   ) {}
 
   /**
@@ -26,7 +31,7 @@ export class ConversationService {
     interactionId: string;
     messageId: string;
   }> {
-    const { content, worktreeId } = request;
+    const { content, worktreeId, metadata } = request;
     
     if (!content || content.trim() === '') {
       throw new Error('Content is required');
@@ -58,7 +63,8 @@ export class ConversationService {
     const message = Message.create({
       interactionId: interaction.id,
       role: 'user',
-      content
+      content,
+      metadata
     });
     
     await this.messageStore.addMessage(message);
@@ -72,7 +78,12 @@ export class ConversationService {
   /**
    * Add a message to an existing conversation
    */
-  async addMessage(interactionId: string, content: string, role: 'user' | 'assistant' = 'user'): Promise<{
+  async addMessage(
+    interactionId: string,
+    content: string,
+    role: 'user' | 'assistant' = 'user',
+    metadata?: MessageMetadata
+  ): Promise<{
     messageId: string;
   }> {
     // Verify interaction exists
@@ -88,7 +99,8 @@ export class ConversationService {
     const message = Message.create({
       interactionId,
       role,
-      content
+      content,
+      metadata
     });
     
     await this.messageStore.addMessage(message);
@@ -108,9 +120,18 @@ export class ConversationService {
   }> {
     const { interactionId } = request;
     
+    // Uploaded files travel in the message's metadata, so agents can read them
+    if (request.attachmentIds && request.attachmentIds.length > 0) {
+      if (!this.attachmentStore) {
+        throw new Error('Attachment not found');
+      }
+      const attachments = this.attachmentStore.resolve(request.attachmentIds);
+      request = { ...request, metadata: { ...request.metadata, attachments } };
+    }
+    
     if (interactionId) {
       // Add to existing conversation
-      const result = await this.addMessage(interactionId, request.content);
+      const result = await this.addMessage(interactionId, request.content, 'user', request.metadata);
       return {
         interactionId,
         messageId: result.messageId,
@@ -126,6 +147,58 @@ export class ConversationService {
     }
   }
 
+  /**
+   * Store a conversation held somewhere else, every message already finished
+   */
+  async importConversation(request: ImportConversationRequest): Promise<{
+    interactionId: string;
+    messageCount: number;
+  }> {
+    const messages = (request.messages || []).filter(m =>
+      (m.role === 'user' || m.role === 'assistant') && m.content && m.content.trim() !== ''
+    );
+    if (messages.length === 0) {
+      throw new Error('Nothing to import');
+    }
+    
+    // Untimed messages follow the one before by a millisecond, so the order survives sorting
+    let previous: Date | undefined;
+    const timestamps = messages.map(m => {
+      const parsed = m.timestamp ? new Date(m.timestamp) : undefined;
+      const timestamp = parsed && !isNaN(parsed.getTime())
+        ? parsed
+        : new Date((previous || new Date()).getTime() + (previous ? 1 : 0));
+      previous = timestamp;
+      return timestamp;
+    });
+    
+    const interaction = Interaction.create({
+      source: 'user',
+      type: InteractionType.QUERY,
+      createdAt: timestamps[0],
+      metadata: {
+        title: request.title?.trim() || undefined,
+        imported: { from: request.from, importedAt: new Date() }
+      }
+    });
+    await this.interactionStore.create(interaction);
+    
+    for (const [i, m] of messages.entries()) {
+      await this.messageStore.addMessage(Message.create({
+        interactionId: interaction.id,
+        role: m.role,
+        content: m.content,
+        timestamp: timestamps[i],
+        status: 'completed'
+      }));
+    }
+    
+    return {
+      interactionId: interaction.id,
+      messageCount: messages.length
+    };
+  }
+
   /**
    * Get conversation (interaction + messages)
    */
@@ -146,6 +219,38 @@ export class ConversationService {
     };
   }
 
+  /**
+   * Get an interaction with one page of its messages, newest first by page
+   */
+  async getConversationPage(interactionId: string, limit: number, before?: string): Promise<{
+    interaction: any;
+    messages: any[];
+    hasMore: boolean;
+  } | null> {
+    const interaction = this.interactionStore.get(interactionId);
+    if (!interaction) {
+      return null;
+    }
+
+    return {
+      interaction: interaction.toJSON(),
+      ...this.messageStore.getMessagePage(interactionId, limit, before)
+    };
+  }
+
+  /**
+   * Replace an interaction's tags, blank and repeated ones are dropped
+   */
+  async setTags(interactionId: string, tags: string[]): Promise<string[]> {
+    if (!this.interactionStore.get(interactionId)) {
+      throw new Error('Interaction not found');
+    }
+    
+    const kept = [...new Set(tags.map(tag => tag.trim()).filter(tag => tag.length > 0))];
+    await this.interactionStore.updateMetadata(interactionId, { tags: kept });
+    return kept;
+  }
+
   /**
    * Get all conversations with message counts
    */
@@ -173,6 +278,16 @@ export class ConversationService {
     content: string,
     metadata?: any
   ): Promise<void> {
+    // Files the tools wrote can be downloaded from the message
+    if (this.artifactStore && metadata?.toolCalls?.length) {
+      const interaction = this.interactionStore.get(interactionId);
+      const cwd = interaction?.metadata.worktreeContext?.worktreePath || process.cwd();
+      const artifacts = this.artifactStore.registerToolCalls(metadata.toolCalls, cwd);
+      if (artifacts.length > 0) {
+        metadata = { ...metadata, artifacts };
+      }
+    }
+    
     const message = Message.create({
       interactionId,
       role: 'assistant',
@@ -198,22 +313,22 @@ export class ConversationService {
     interactionId: string,
     toolName: string,
     description: string,
-    requestId: string
+    requestId: string,
+    input?: Record<string, any>
   ): Promise<string> {
+    const permissionRequest = { toolName, description, requestId, input };
     const message = Message.create({
       interactionId,
       role: 'system',
       content: description,
-      metadata: {
-        permissionRequest: {
-          toolName,
-          description,
-          requestId
-        }
-      }
+      metadata: { permissionRequest }
     });
     
     await this.messageStore.addMessage(message);
+    // Clients show the pending request, arguments included, from the interaction
+    await this.interactionStore.updateMetadata(interactionId, {
+      pendingToolPermission: permissionRequest
+    });
     return message.id;
   }
 
@@ -245,5 +360,8 @@ export class ConversationService {
     });
     
     await this.messageStore.addMessage(responseMessage);
+    await this.interactionStore.updateMetadata(interactionId, {
+      pendingToolPermission: undefined
+    });
   }
 }
\ No newline at end of file
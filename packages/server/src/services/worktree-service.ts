@@ -1,5 +1,5 @@
 import type { WorktreeManager } from '../worktree/manager';
-import type { Worktree } from '../worktree/types';
+import type { Worktree, WorktreeGitStatus } from '../worktree/types';
 
 export interface CreateWorktreeRequest {
   branch: string;
@@ -46,9 +46,53 @@ export class WorktreeService {
   async deleteWorktree(id: string): Promise<void> {
     try {
       await this.worktreeManager.deleteWorktree(id);
-    } catch (error) {
+    } catch (error: any) {
       console.error('[WorktreeService] Error deleting worktree:', error);
-      throw new Error('Failed to delete worktree');
+      throw new Error(error.message || 'Failed to delete worktree');
+    }
+  }
+
+  async getDiff(id: string): Promise<{ base: string; diff: string }> {
+    try {
+      return await this.worktreeManager.getDiff(id);
+    } catch (error: any) {
+      console.error('[WorktreeService] Error diffing worktree:', error);
+      throw new Error(error.message || 'Failed to diff worktree');
+    }
+  }
+
+  async getStatuses(): Promise<Record<string, WorktreeGitStatus>> {
+    try {
+      return await this.worktreeManager.getStatuses();
+    } catch (error: any) {
+      console.error('[WorktreeService] Error getting worktree statuses:', error);
+      throw new Error(error.message || 'Failed to get worktree statuses');
+    }
+  }
+
+  async mergeWorktree(id: string, message: string): Promise<{ commit: string; base: string }> {
+    if (!message?.trim()) {
+      throw new Error('Commit message is required');
+    }
+
+    try {
+      return await this.worktreeManager.mergeWorktree(id, message);
+    } catch (error: any) {
+      console.error('[WorktreeService] Error merging worktree:', error);
+      throw new Error(error.message || 'Failed to merge worktree');
+    }
+  }
+
+  async createPullRequest(id: string, title: string, body = ''): Promise<{ url: string; base: string }> {
+    if (!title?.trim()) {
+      throw new Error('Pull request title is required');
+    }
+
+    try {
+      return await this.worktreeManager.createPullRequest(id, title, body);
+    } catch (error: any) {
+      console.error('[WorktreeService] Error creating pull request:', error);
+      throw new Error(error.message || 'Failed to create pull request');
     }
   }
 
@@ -6,6 +6,8 @@ export interface ToolPermissionRequest {
   toolName: string;
   description: string;
   requestId?: string;
+  // The tool's arguments, e.g. the shell command or the file and edit
+  input?: Record<string, any>;
 }
 
 export interface ToolPermissionResponse {
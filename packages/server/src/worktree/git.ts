@@ -1,10 +1,12 @@
-import { exec } from 'child_process';
+import { exec, execFile } from 'child_process';
 import { promisify } from 'util';
 import { join, isAbsolute, normalize } from 'path';
 import { existsSync } from 'fs';
-import type { GitOperations, GitWorktreeInfo } from './types';
+import type { GitOperations, GitWorktreeInfo, WorktreeGitStatus } from './types';
 
 const execAsync = promisify(exec);
+// For commands taking user input, so nothing goes through a shell
+const execFileAsync = promisify(execFile);
 
 export class GitWorktreeOperations implements GitOperations {
   constructor(private readonly repoRoot: string) {}
@@ -109,6 +111,91 @@ export class GitWorktreeOperations implements GitOperations {
     }
   }
 
+  async diff(path: string, base: string): Promise<string> {
+    try {
+      // Against the working tree, so uncommitted changes show up too
+      const { stdout } = await execAsync(`git diff --no-color --no-ext-diff ${base}`, {
+        cwd: path,
+        maxBuffer: 32 * 1024 * 1024
+      });
+      return stdout;
+    } catch (error: any) {
+      console.error('[Git] Failed to diff worktree:', error);
+      throw new Error(`Failed to diff worktree: ${error.message}`);
+    }
+  }
+
+  async status(path: string, base: string): Promise<WorktreeGitStatus> {
+    try {
+      const { stdout } = await execAsync('git status --porcelain', { cwd: path });
+      const dirty = stdout.split('\n').filter(line => line.trim()).length;
+
+      // A base that no longer exists shouldn't hide the dirty count
+      const counts = await execFileAsync('git', ['rev-list', '--left-right', '--count', `${base}...HEAD`], { cwd: path })
+        .then(({ stdout }) => stdout.trim().split(/\s+/).map(Number))
+        .catch(() => [0, 0]);
+
+      return { dirty, behind: counts[0] || 0, ahead: counts[1] || 0 };
+    } catch (error: any) {
+      console.error('[Git] Failed to get worktree status:', error);
+      throw new Error(`Failed to get worktree status: ${error.message}`);
+    }
+  }
+
+  async currentBranch(path: string): Promise<string> {
+    const { stdout } = await execAsync('git rev-parse --abbrev-ref HEAD', { cwd: path });
+    return stdout.trim();
+  }
+
+  async commitAll(path: string, message: string): Promise<boolean> {
+    try {
+      const { stdout } = await execAsync('git status --porcelain', { cwd: path });
+      if (!stdout.trim()) {
+        return false;
+      }
+
+      await execAsync('git add -A', { cwd: path });
+      await execFileAsync('git', ['commit', '-m', message], { cwd: path });
+      console.log(`[Git] Committed changes in ${path}`);
+      return true;
+    } catch (error: any) {
+      console.error('[Git] Failed to commit:', error);
+      throw new Error(`Failed to commit: ${error.message}`);
+    }
+  }
+
+  async merge(branch: string, message: string): Promise<string> {
+    try {
+      await execFileAsync('git', ['merge', '--no-ff', '-m', message, branch], { cwd: this.repoRoot });
+      const { stdout } = await execAsync('git rev-parse HEAD', { cwd: this.repoRoot });
+      console.log(`[Git] Merged ${branch}`);
+      return stdout.trim();
+    } catch (error: any) {
+      console.error('[Git] Failed to merge:', error);
+      // Leave the main checkout as it was rather than mid-merge
+      await execAsync('git merge --abort', { cwd: this.repoRoot }).catch(() => {});
+      throw new Error(`Failed to merge ${branch}: ${error.message}`);
+    }
+  }
+
+  async createPullRequest(path: string, branch: string, base: string, title: string, body: string): Promise<string> {
+    try {
+      await execFileAsync('git', ['push', '-u', 'origin', branch], { cwd: path });
+      const { stdout } = await execFileAsync(
+        'gh',
+        ['pr', 'create', '--base', base, '--head', branch, '--title', title, '--body', body],
+        { cwd: path }
+      );
+      // gh prints the new PR's URL last
+      const url = stdout.trim().split('\n').pop() || '';
+      console.log(`[Git] Opened pull request ${url}`);
+      return url;
+    } catch (error: any) {
+      console.error('[Git] Failed to create pull request:', error);
+      throw new Error(`Failed to create pull request: ${error.stderr || error.message}`);
+    }
+  }
+
   async getWorktreeInfo(path: string): Promise<GitWorktreeInfo | null> {
     const worktrees = await this.listWorktrees();
     const normalizedPath = normalize(path);
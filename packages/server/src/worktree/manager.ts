@@ -1,4 +1,4 @@
-import type { Worktree, WorktreeContext } from '@bicamrl/shared';
+import type { Worktree, WorktreeContext, WorktreeGitStatus } from '@bicamrl/shared';
 import { v4 as uuidv4 } from 'uuid';
 import { GitWorktreeOperations } from './git';
 import type { WorktreeStore } from './types';
@@ -38,6 +38,9 @@ export class WorktreeManager {
       throw new Error(`Invalid worktree path: ${path}`);
     }
 
+    // Remember what it branched off, merges and PRs go back there
+    const base = baseBranch || await this.gitOps.currentBranch(this.repoRoot);
+
     // Create Git worktree
     await this.gitOps.createWorktree(path, branch, baseBranch);
 
@@ -52,6 +55,7 @@ export class WorktreeManager {
       id: uuidv4(),
       path: gitInfo.path,
       branch: gitInfo.branch,
+      baseBranch: base,
       baseCommit: gitInfo.commit,
       status: 'active',
       createdAt: new Date()
@@ -90,8 +94,10 @@ export class WorktreeManager {
       throw new Error(`Worktree ${id} not found`);
     }
 
-    // Remove Git worktree
-    await this.gitOps.removeWorktree(worktree.path);
+    // Inactive worktrees are already gone from Git, only the record is left
+    if (worktree.status === 'active') {
+      await this.gitOps.removeWorktree(worktree.path);
+    }
 
     // Remove from memory and store
     this.worktrees.delete(id);
@@ -102,6 +108,76 @@ export class WorktreeManager {
     console.log(`[WorktreeManager] Deleted worktree ${id}`);
   }
 
+  async getDiff(id: string): Promise<{ base: string; diff: string }> {
+    const worktree = this.worktrees.get(id);
+    if (!worktree) {
+      throw new Error(`Worktree ${id} not found`);
+    }
+
+    // Everything since the worktree was branched off
+    const base = worktree.baseCommit || 'HEAD';
+    const diff = await this.gitOps.diff(worktree.path, base);
+    return { base, diff };
+  }
+
+  async getStatuses(): Promise<Record<string, WorktreeGitStatus>> {
+    const statuses: Record<string, WorktreeGitStatus> = {};
+    const active = Array.from(this.worktrees.values()).filter(w => w.status === 'active' && w.branch);
+
+    await Promise.all(active.map(async worktree => {
+      try {
+        const { base } = await this.branchesFor(worktree.id);
+        statuses[worktree.id] = await this.gitOps.status(worktree.path, base);
+      } catch (error) {
+        // One broken checkout shouldn't blank out the rest
+        console.error(`[WorktreeManager] Failed to get status for ${worktree.id}:`, error);
+      }
+    }));
+
+    return statuses;
+  }
+
+  async mergeWorktree(id: string, message: string): Promise<{ commit: string; base: string }> {
+    const { worktree, branch, base } = await this.branchesFor(id);
+
+    // Merging happens in the main checkout, which has to be on the base branch
+    const current = await this.gitOps.currentBranch(this.repoRoot);
+    if (current !== base) {
+      throw new Error(`The main checkout is on ${current}, check out ${base} to merge into it`);
+    }
+
+    await this.gitOps.commitAll(worktree.path, message);
+    const commit = await this.gitOps.merge(branch, message);
+
+    console.log(`[WorktreeManager] Merged worktree ${id} into ${base}`);
+    return { commit, base };
+  }
+
+  async createPullRequest(id: string, title: string, body: string): Promise<{ url: string; base: string }> {
+    const { worktree, branch, base } = await this.branchesFor(id);
+
+    await this.gitOps.commitAll(worktree.path, title);
+    const url = await this.gitOps.createPullRequest(worktree.path, branch, base, title, body);
+
+    console.log(`[WorktreeManager] Opened pull request for worktree ${id}`);
+    return { url, base };
+  }
+
+  private async branchesFor(id: string): Promise<{ worktree: Worktree; branch: string; base: string }> {
+    const worktree = this.worktrees.get(id);
+    if (!worktree) {
+      throw new Error(`Worktree ${id} not found`);
+    }
+    if (!worktree.branch) {
+      throw new Error(`Worktree ${id} has no branch checked out`);
+    }
+
+    const branch = worktree.branch.replace(/^refs\/heads\//, '');
+    // Worktrees discovered on startup don't know their base, assume the main checkout's
+    const base = worktree.baseBranch || await this.gitOps.currentBranch(this.repoRoot);
+    return { worktree, branch, base };
+  }
+
   async syncWithGit(): Promise<void> {
     const gitWorktrees = await this.gitOps.listWorktrees();
     
@@ -1,6 +1,6 @@
-import type { Worktree } from '@bicamrl/shared';
+import type { Worktree, WorktreeGitStatus } from '@bicamrl/shared';
 
-export type { Worktree };
+export type { Worktree, WorktreeGitStatus };
 
 export interface WorktreeStore {
   create(worktree: Omit<Worktree, 'id' | 'createdAt'>): Promise<Worktree>;
@@ -22,6 +22,12 @@ export interface GitOperations {
   listWorktrees(): Promise<GitWorktreeInfo[]>;
   createWorktree(path: string, branch: string, baseBranch?: string): Promise<void>;
   removeWorktree(path: string): Promise<void>;
+  diff(path: string, base: string): Promise<string>;
+  status(path: string, base: string): Promise<WorktreeGitStatus>;
+  currentBranch(path: string): Promise<string>;
+  commitAll(path: string, message: string): Promise<boolean>;
+  merge(branch: string, message: string): Promise<string>;
+  createPullRequest(path: string, branch: string, base: string, title: string, body: string): Promise<string>;
   getWorktreeInfo(path: string): Promise<GitWorktreeInfo | null>;
   validateWorktreePath(path: string): Promise<boolean>;
 }
\ No newline at end of file
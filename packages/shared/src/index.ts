@@ -42,6 +42,24 @@ export interface SendMessageRequest {
   metadata?: Record<string, any>;
   worktreeId?: string;
   interactionId?: string; // For continuing existing conversations
+  attachmentIds?: string[]; // Files uploaded to /attachments first
+}
+
+// A finished conversation from another tool, stored without being answered again
+export interface ImportConversationRequest {
+  title?: string;
+  // Where the transcript came from, e.g. 'claude-code' or 'markdown'
+  from: string;
+  messages: {
+    role: 'user' | 'assistant';
+    content: string;
+    timestamp?: string;
+  }[];
+}
+
+export interface ImportConversationResponse {
+  id: string;
+  messageCount: number;
 }
 
 export interface GetMessagesParams {
@@ -69,17 +87,34 @@ export interface Worktree {
   id: string;
   path: string;
   branch?: string;
+  baseBranch?: string;
   baseCommit?: string;
   status: 'active' | 'inactive';
   createdAt: Date;
 }
 
+// Uncommitted files, and commits ahead of and behind the base branch
+export interface WorktreeGitStatus {
+  dirty: number;
+  ahead: number;
+  behind: number;
+}
+
 export interface CreateWorktreeRequest {
   branch?: string;
   baseBranch?: string;
   path?: string;
 }
 
+export interface MergeWorktreeRequest {
+  message: string;
+}
+
+export interface CreatePullRequestRequest {
+  title: string;
+  body?: string;
+}
+
 export interface WorktreeContext {
   worktreeId: string;
   worktreePath: string;